@@ -9,6 +9,9 @@ use gpui_component::{ThemeMode, dock::DockPlacement, scroll::ScrollbarShow};
 use serde::Deserialize;
 use std::path::PathBuf;
 
+use crate::core::services::SessionExportFormat;
+use crate::utils::{clipboard::PastedText, context_item::ContextItem};
+
 // ============================================================================
 // Workspace Actions - 工作区相关操作
 // ============================================================================
@@ -127,10 +130,35 @@ pub struct AddToolCallDetailPanel {
     pub placement: DockPlacement,
 }
 
+/// 移动会话面板
+///
+/// 将正在运行的会话面板移动到另一个停靠位置，保留流式输出、滚动位置和输入内容
+#[derive(Action, Clone, PartialEq, Eq, Deserialize)]
+#[action(namespace = agent_studio, no_json)]
+pub struct MoveSessionPanel {
+    /// 会话唯一标识符
+    pub session_id: String,
+    /// 目标停靠位置，默认为 Center
+    #[serde(skip, default = "default_dock_placement")]
+    pub placement: DockPlacement,
+}
+
 fn default_dock_placement() -> DockPlacement {
     DockPlacement::Center
 }
 
+/// 导出会话记录
+///
+/// 将会话的完整记录（消息、思考、工具调用和计划）保存为 Markdown 或 JSON 文件
+#[derive(Action, Clone, PartialEq, Eq, Deserialize)]
+#[action(namespace = agent_studio, no_json)]
+pub struct ExportSession {
+    /// 会话唯一标识符
+    pub session_id: String,
+    /// 导出格式
+    pub format: SessionExportFormat,
+}
+
 // 切换 Dock 切换按钮的显示状态
 actions!(agent_studio, [ToggleDockToggleButton]);
 
@@ -171,6 +199,13 @@ pub struct SelectScrollbarShow(pub ScrollbarShow);
 #[action(namespace = agent_studio, no_json)]
 pub struct SelectLocale(pub SharedString);
 
+/// 切换配置档案
+///
+/// 参数为档案名称，保存为启动档案后重启应用
+#[derive(Action, Clone, PartialEq, Eq, Deserialize)]
+#[action(namespace = agent_studio, no_json)]
+pub struct SwitchProfile(pub SharedString);
+
 /// 选择字体
 ///
 /// 参数为字体索引，用于切换编辑器和界面字体
@@ -205,6 +240,62 @@ pub struct CreateTaskFromWelcome {
     pub images: Vec<(ImageContent, String)>,
     /// 目标工作区 ID（可选，如果未指定则使用 active workspace）
     pub workspace_id: Option<String>,
+    /// 实验标签（可选），用于按标签对比不同配置的效果
+    pub label: Option<String>,
+}
+
+/// 将同一任务同时发送给多个 Agent 并排对比
+///
+/// 每个 Agent 创建各自的会话和任务，会话面板在中央区域并排显示
+#[derive(Action, Clone, Debug, PartialEq, Deserialize)]
+#[action(namespace = agent_studio, no_json)]
+pub struct CompareAgents {
+    /// 任务描述,由用户输入
+    pub task_input: String,
+    /// 参与对比的 Agent 名称，按显示顺序排列
+    pub agent_names: Vec<String>,
+    /// 任务模式
+    pub mode: String,
+    /// 附加的图片列表 (ImageContent, filename)
+    pub images: Vec<(ImageContent, String)>,
+    /// 目标工作区 ID（可选，如果未指定则使用 active workspace）
+    pub workspace_id: Option<String>,
+    /// 实验标签（可选），未指定时生成一个，使对比任务共享同一标签
+    pub label: Option<String>,
+}
+
+/// 打开新建任务对话框
+///
+/// 在对话框中选择 Agent、工作区与工作目录、模式、模型、附件和提示词模板
+#[derive(Action, Clone, Debug, Default, PartialEq, Deserialize)]
+#[action(namespace = agent_studio, no_json)]
+pub struct NewTask {
+    /// 预先选中的工作区 ID（可选，如果未指定则使用 active workspace）
+    pub workspace_id: Option<String>,
+}
+
+/// 一步创建任务：创建会话、应用模式和模型、创建任务、打开会话面板并发送提示词
+///
+/// 由新建任务对话框触发，实际逻辑在 workspace/new_task.rs 中实现
+#[derive(Action, Clone, Debug, PartialEq, Deserialize)]
+#[action(namespace = agent_studio, no_json)]
+pub struct CreateTask {
+    /// 任务描述,由用户输入
+    pub task_input: String,
+    /// 使用的 Agent 名称
+    pub agent_name: String,
+    /// 目标工作区 ID
+    pub workspace_id: String,
+    /// 会话工作目录（可选，默认为工作区根目录）
+    pub cwd: Option<PathBuf>,
+    /// 会话模式 ID（可选，默认为 Agent 的默认模式）
+    pub mode_id: Option<String>,
+    /// 会话模型 ID（可选，默认为 Agent 的默认模型）
+    pub model_id: Option<String>,
+    /// 随第一条消息发送的附件
+    pub attachments: Vec<ContextItem>,
+    /// 实验标签（可选）
+    pub label: Option<String>,
 }
 
 /// 发送消息到指定会话
@@ -220,6 +311,18 @@ pub struct SendMessageToSession {
     pub message: String,
     /// 附带的图片列表 (ImageContent, filename)
     pub images: Vec<(ImageContent, String)>,
+    /// 以附件形式发送的粘贴文本
+    pub resources: Vec<PastedText>,
+    /// 固定在会话上的上下文（每次发送都会附带）
+    pub context: Vec<ContextItem>,
+    /// 附带工作区的仓库地图（目录结构与公开符号）
+    pub repo_map: bool,
+    /// 语义搜索查询，附带最相关的工作区代码片段
+    pub search_query: Option<String>,
+    /// 在消息前附加工作区记忆
+    pub memory: bool,
+    /// 附带工作区根目录的指令文件（AGENTS.md、CLAUDE.md、.cursorrules）
+    pub instructions: bool,
 }
 
 /// 取消会话
@@ -232,6 +335,17 @@ pub struct CancelSession {
     /// 会话唯一标识符
     pub session_id: String,
 }
+
+/// 固定上下文到当前会话
+///
+/// 由 MCP 浏览面板触发，固定到中央区域显示的会话面板
+/// 实际的查找逻辑在 workspace/actions.rs 中实现
+#[derive(Action, Clone, Debug, PartialEq, Deserialize)]
+#[action(namespace = agentx, no_json)]
+pub struct PinContextItem {
+    /// 要固定的上下文
+    pub item: ContextItem,
+}
 /// 显示会话对话面板
 ///
 #[derive(Action, Clone, PartialEq, Deserialize)]
@@ -277,7 +391,16 @@ actions!(
         TestAction,    // 测试用操作
         Tab,           // 切换到下一个标签页
         TabPrev,       // 切换到上一个标签页
-        ShowPanelInfo  // 显示面板信息
+        ShowPanelInfo, // 显示面板信息
+        ExportStats,   // 导出会话统计（JSON/CSV）
+        FindDuplicateTasks, // 查找并合并重复任务
+        ImportChatLogs,     // 导入其他工具导出的对话记录
+        GoBack,             // 返回上一个打开的面板
+        GoForward,          // 前进到下一个打开的面板
+        QuickSwitch,        // 快速切换到会话
+        ToggleFocusMode,    // 开启/关闭专注模式（免打扰）
+        NewProfile,         // 新建配置档案并切换过去
+        SyncNow             // 立即同步设置、提示词模板和记忆
     ]
 );
 
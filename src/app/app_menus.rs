@@ -3,7 +3,9 @@ use gpui_component::{ThemeMode, ThemeRegistry};
 use rust_i18n::t;
 
 use crate::{
-    About, CloseWindow, Open, Quit, SelectLocale, ToggleSearch,
+    About, AppState, CloseWindow, ExportStats, FindDuplicateTasks, GoBack, GoForward,
+    ImportChatLogs, NewTask, Open, QuickSwitch, Quit, SelectLocale, SwitchProfile, SyncNow,
+    ToggleFocusMode, ToggleSearch,
     app::actions::{SwitchTheme, SwitchThemeMode},
 };
 
@@ -14,7 +16,15 @@ pub fn init(title: impl Into<SharedString>, cx: &mut App) {
             items: vec![
                 MenuItem::action(t!("menu.app.about").to_string(), About),
                 MenuItem::Separator,
+                MenuItem::action(t!("menu.app.new_task").to_string(), NewTask::default()),
                 MenuItem::action(t!("menu.app.open").to_string(), Open),
+                MenuItem::action(t!("menu.app.export_stats").to_string(), ExportStats),
+                MenuItem::action(
+                    t!("menu.app.find_duplicates").to_string(),
+                    FindDuplicateTasks,
+                ),
+                MenuItem::action(t!("menu.app.import_chat_logs").to_string(), ImportChatLogs),
+                MenuItem::action(t!("menu.app.sync_now").to_string(), SyncNow),
                 MenuItem::Separator,
                 MenuItem::Submenu(Menu {
                     name: t!("menu.app.appearance").to_string().into(),
@@ -31,6 +41,7 @@ pub fn init(title: impl Into<SharedString>, cx: &mut App) {
                 }),
                 theme_menu(cx),
                 language_menu(cx),
+                profile_menu(cx),
                 MenuItem::Separator,
                 MenuItem::action(t!("menu.app.quit").to_string(), Quit),
             ],
@@ -86,6 +97,11 @@ pub fn init(title: impl Into<SharedString>, cx: &mut App) {
             items: vec![
                 MenuItem::action(t!("menu.window.close").to_string(), CloseWindow),
                 MenuItem::separator(),
+                MenuItem::action(t!("menu.window.back").to_string(), GoBack),
+                MenuItem::action(t!("menu.window.forward").to_string(), GoForward),
+                MenuItem::action(t!("menu.window.quick_switch").to_string(), QuickSwitch),
+                MenuItem::action(t!("menu.window.focus_mode").to_string(), ToggleFocusMode),
+                MenuItem::separator(),
                 MenuItem::action(t!("menu.window.toggle_search").to_string(), ToggleSearch),
             ],
         },
@@ -115,6 +131,36 @@ fn language_menu(_cx: &App) -> MenuItem {
     })
 }
 
+fn profile_menu(cx: &App) -> MenuItem {
+    let mut items: Vec<MenuItem> = match AppState::global(cx).profile_service() {
+        Some(service) => {
+            let current = service.current();
+            service
+                .list()
+                .into_iter()
+                .map(|profile| {
+                    let label = if profile.name == current {
+                        t!("menu.app.profiles.current", name = profile.name).to_string()
+                    } else {
+                        profile.name.clone()
+                    };
+                    MenuItem::action(label, SwitchProfile(profile.name.into()))
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+    items.push(MenuItem::Separator);
+    items.push(MenuItem::action(
+        t!("menu.app.profiles.new").to_string(),
+        NewProfile,
+    ));
+    MenuItem::Submenu(Menu {
+        name: t!("menu.app.profiles").to_string().into(),
+        items,
+    })
+}
+
 fn theme_menu(cx: &App) -> MenuItem {
     let themes = ThemeRegistry::global(cx).sorted_themes();
     MenuItem::Submenu(Menu {
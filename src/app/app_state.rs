@@ -1,18 +1,27 @@
 use gpui::{App, AppContext, Entity, Global, SharedString};
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
 use crate::{
     core::agent::{AgentManager, PermissionStore},
-    core::config::DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES,
+    core::config::{
+        AgentDisplay, DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES, EmbeddingConfig, IndexingConfig,
+        Snippet, launch_configs,
+    },
     core::event_bus::{
-        AgentConfigBusContainer, CodeSelectionBusContainer, PermissionBusContainer,
-        SessionUpdateBusContainer, WorkspaceUpdateBusContainer,
+        AgentConfigBusContainer, AgentConfigEvent, CodeSelectionBusContainer,
+        PermissionBusContainer, SessionUpdateBusContainer, WorkspaceUpdateBusContainer,
+        WorkspaceUpdateEvent,
     },
     core::services::{
-        AgentConfigService, AgentService, AiService, MessageService, PersistenceService,
-        WorkspaceService,
+        AgentConfigService, AgentService, AiService, AuditService, ChatBridgeService,
+        DirectChatService, EditorBridgeService, ImportService, MaintenanceService, McpService,
+        MessageService, MetricsService, PersistenceService, ProfileService, SearchService,
+        SemanticIndex, ShareService, SymbolIndex, SyncBackend, SyncService, WebhookService,
+        WorkspaceService, pick_warm_agents,
     },
+    utils::{context_item::ContextItem, path_filter::PathFilter},
 };
 
 /// Welcome session info - stores the session created when user selects an agent
@@ -40,14 +49,46 @@ pub struct AppState {
     workspace_service: Option<Arc<WorkspaceService>>,
     agent_config_service: Option<Arc<AgentConfigService>>,
     ai_service: Option<Arc<AiService>>,
+    metrics_service: Option<Arc<MetricsService>>,
+    audit_service: Option<Arc<AuditService>>,
+    maintenance_service: Option<Arc<MaintenanceService>>,
+    import_service: Option<Arc<ImportService>>,
+    search_service: Option<Arc<SearchService>>,
+    /// MCP servers run by the app for the MCP console
+    mcp_service: Option<Arc<McpService>>,
+    /// Named configurations; set by the binary, which picks the profile at startup
+    profile_service: Option<Arc<ProfileService>>,
+    /// Set when a sync backend is configured
+    sync_service: Option<Arc<SyncService>>,
+    share_service: Option<Arc<ShareService>>,
+    /// Code selections and focus requests from editor plugins
+    editor_bridge_service: Option<Arc<EditorBridgeService>>,
+    /// Sends session events to the webhooks in config.json
+    webhook_service: Option<Arc<WebhookService>>,
+    /// Prompts sessions from the Slack and Discord channels in config.json
+    chat_bridge_service: Option<Arc<ChatBridgeService>>,
+    /// Tree-sitter symbol indexes, one per workspace root
+    symbol_indexes: HashMap<PathBuf, Arc<SymbolIndex>>,
+    /// Embeddings indexes for `/search`, one per workspace root
+    semantic_indexes: HashMap<PathBuf, Arc<SemanticIndex>>,
     /// Config file path for AgentConfigService
     config_path: Option<PathBuf>,
     /// Current working directory for the code editor
     current_working_dir: PathBuf,
     /// Max lines to show in tool call previews (0 disables truncation)
     tool_call_preview_max_lines: usize,
+    /// Paths skipped when scanning workspaces
+    indexing: IndexingConfig,
+    /// Semantic search settings
+    embeddings: EmbeddingConfig,
+    /// Snippets of the prompt input, kept in line with config.json
+    snippets: Arc<RwLock<Vec<Snippet>>>,
+    /// Display name, color and avatar of each agent, kept in line with config.json
+    agent_displays: Arc<RwLock<HashMap<String, AgentDisplay>>>,
     /// Selected tool call for detail view
     pub selected_tool_call: Entity<Option<agent_client_protocol::ToolCall>>,
+    /// File or selection currently shown in the code editor
+    pub editor_context: Entity<Option<ContextItem>>,
     /// Cached title for rebuilding app menus after locale changes
     app_title: SharedString,
 }
@@ -61,17 +102,49 @@ impl AppState {
         let workspace_bus = WorkspaceUpdateBusContainer::new();
 
         // Create workspace service and set its bus
+        let sessions_dir = crate::core::config_manager::get_sessions_dir();
+        let persistence_service = Arc::new(PersistenceService::new(sessions_dir));
+        crate::core::crash_report::watch_persistence_service(&persistence_service);
         let mut workspace_service = WorkspaceService::new(config_path);
         workspace_service.set_workspace_bus(workspace_bus.clone());
+        workspace_service.set_persistence_service(persistence_service.clone());
         let workspace_service = Arc::new(workspace_service);
-        let sessions_dir = crate::core::config_manager::get_sessions_dir();
-        let persistence_service = Arc::new(PersistenceService::new(sessions_dir));
+        workspace_service.start_usage_tracking();
+        let metrics_service = Arc::new(MetricsService::new(
+            workspace_service.clone(),
+            persistence_service.clone(),
+        ));
+        let maintenance_service = Arc::new(MaintenanceService::new(
+            workspace_service.clone(),
+            persistence_service.clone(),
+        ));
+        let import_service = Arc::new(ImportService::new(
+            workspace_service.clone(),
+            persistence_service.clone(),
+        ));
+
+        let session_bus = SessionUpdateBusContainer::new();
+        let audit_service = Arc::new(AuditService::new(
+            crate::core::config_manager::get_audit_log_path(),
+        ));
+        audit_service.start(&session_bus);
+        let search_service = Arc::new(SearchService::new(
+            persistence_service.clone(),
+            workspace_service.clone(),
+        ));
+        search_service.start(&session_bus);
+        let share_service = Arc::new(ShareService::new(
+            persistence_service.clone(),
+            session_bus.clone(),
+            workspace_bus.clone(),
+        ));
+        let editor_bridge_service = Arc::new(EditorBridgeService::new(workspace_service.clone()));
 
         let state = Self {
             invisible_panels: cx.new(|_| Vec::new()),
             agent_manager: None,
             permission_store: None,
-            session_bus: SessionUpdateBusContainer::new(),
+            session_bus,
             permission_bus: PermissionBusContainer::new(),
             workspace_bus,
             code_selection_bus: Arc::new(std::sync::Mutex::new(
@@ -85,10 +158,29 @@ impl AppState {
             workspace_service: Some(workspace_service),
             agent_config_service: None,
             ai_service: None,
+            metrics_service: Some(metrics_service),
+            audit_service: Some(audit_service),
+            maintenance_service: Some(maintenance_service),
+            import_service: Some(import_service),
+            search_service: Some(search_service),
+            mcp_service: None,
+            profile_service: None,
+            sync_service: None,
+            share_service: Some(share_service),
+            editor_bridge_service: Some(editor_bridge_service),
+            webhook_service: None,
+            chat_bridge_service: None,
+            symbol_indexes: HashMap::new(),
+            semantic_indexes: HashMap::new(),
             config_path: None,
             current_working_dir: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
             tool_call_preview_max_lines: DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES,
+            indexing: IndexingConfig::default(),
+            embeddings: EmbeddingConfig::default(),
+            snippets: Arc::new(RwLock::new(Vec::new())),
+            agent_displays: Arc::new(RwLock::new(HashMap::new())),
             selected_tool_call: cx.new(|_| None),
+            editor_context: cx.new(|_| None),
             app_title: SharedString::from(""),
         };
         cx.set_global::<AppState>(state);
@@ -117,10 +209,43 @@ impl AppState {
             .get_or_insert_with(|| Arc::new(PersistenceService::new(sessions_dir)))
             .clone();
 
+        // Initialize AI Service from config
+        let ai_service = if !initial_config.models.is_empty() {
+            log::info!(
+                "Initializing AI Service with {} models",
+                initial_config.models.len()
+            );
+            let ai_service = AiService::new(
+                initial_config.models.clone(),
+                initial_config.system_prompts.clone(),
+            );
+            ai_service.set_utility_model(initial_config.utility_model.clone());
+            Some(Arc::new(ai_service))
+        } else {
+            log::warn!("No AI models configured in config.json");
+            None
+        };
+
         // Initialize services when agent_manager is set
         let mut agent_service = AgentService::new(manager.clone());
         agent_service.set_workspace_bus(self.workspace_bus.clone());
+        if let Some(ai_service) = ai_service.as_ref() {
+            agent_service.set_direct_chat(Arc::new(DirectChatService::new(
+                ai_service.clone(),
+                self.session_bus.clone(),
+            )));
+        }
+        if initial_config.agent_hibernate_after_secs > 0 {
+            agent_service.set_hibernate_after(std::time::Duration::from_secs(
+                initial_config.agent_hibernate_after_secs,
+            ));
+        }
+        agent_service.set_retry_policy((&initial_config.prompt_retry).into());
+        agent_service.set_output_limit(initial_config.output_guard.max_output_chars);
         let agent_service = Arc::new(agent_service);
+        agent_service.start_hibernation_monitor();
+        crate::core::crash_report::watch_agent_service(&agent_service);
+        self.start_warm_pool(&agent_service, &initial_config);
 
         let message_service = Arc::new(MessageService::new(
             self.session_bus.clone(),
@@ -144,33 +269,196 @@ impl AppState {
             None
         };
 
-        // Initialize AI Service from config
-        let ai_service = if !initial_config.models.is_empty() {
-            log::info!(
-                "Initializing AI Service with {} models",
-                initial_config.models.len()
-            );
-            Some(Arc::new(AiService::new(
-                initial_config.models.clone(),
-                initial_config.system_prompts.clone(),
-            )))
-        } else {
-            log::warn!("No AI models configured in config.json");
-            None
-        };
-
+        self.sync_service = self.start_sync(agent_config_service.as_ref(), &initial_config);
+        self.mcp_service = Some(self.start_mcp_service(&initial_config));
+        self.webhook_service = self.start_webhooks(&initial_config);
+        self.chat_bridge_service =
+            self.start_chat_bridges(&agent_service, &message_service, &initial_config);
+        if initial_config.editor_bridge
+            && let Some(service) = &self.editor_bridge_service
+            && let Err(e) = service.start(&crate::core::config_manager::get_editor_bridge_path())
+        {
+            log::warn!("Editor bridge disabled: {:#}", e);
+        }
         self.agent_manager = Some(manager);
         self.agent_service = Some(agent_service);
         self.message_service = Some(message_service);
         self.agent_config_service = agent_config_service;
         self.ai_service = ai_service;
         self.tool_call_preview_max_lines = initial_config.tool_call_preview_max_lines;
+        self.indexing = initial_config.indexing.clone();
+        self.embeddings = initial_config.embeddings.clone();
+        *self.snippets.write().unwrap() = initial_config.snippets.clone();
+        let snippets = self.snippets.clone();
+        self.agent_config_bus.subscribe_config_reloads(move |config| {
+            *snippets.write().unwrap() = config.snippets.clone();
+        });
+        self.track_agent_displays(&initial_config);
 
         log::info!(
             "Initialized service layer (AgentService, MessageService, PersistenceService, AgentConfigService, AiService)"
         );
     }
 
+    /// Start syncing settings, prompt templates and memories if configured
+    fn start_sync(
+        &self,
+        agent_config_service: Option<&Arc<AgentConfigService>>,
+        config: &crate::core::config::Config,
+    ) -> Option<Arc<SyncService>> {
+        let backend = match SyncBackend::from_config(&config.sync) {
+            Ok(backend) => backend?,
+            Err(e) => {
+                log::warn!("Sync disabled: {:#}", e);
+                return None;
+            }
+        };
+        let service = Arc::new(SyncService::new(
+            backend,
+            crate::core::config_manager::get_sync_state_path(),
+            agent_config_service?.clone(),
+            self.workspace_service.clone()?,
+        ));
+        if config.sync.interval_minutes > 0 {
+            service.start(std::time::Duration::from_secs(
+                config.sync.interval_minutes * 60,
+            ));
+        }
+        log::info!("Syncing with {}", service.backend().describe());
+        Some(service)
+    }
+
+    /// Send session events to the webhooks in config.json, also those added later
+    fn start_webhooks(&self, config: &crate::core::config::Config) -> Option<Arc<WebhookService>> {
+        let service = Arc::new(WebhookService::new(
+            config.webhooks.clone(),
+            self.workspace_service.clone()?,
+        ));
+        service.start(&self.workspace_bus);
+        let reloaded = service.clone();
+        self.agent_config_bus.subscribe_config_reloads(move |config| {
+            reloaded.set_hooks(config.webhooks.clone());
+        });
+        Some(service)
+    }
+
+    /// Poll the chat bridges in config.json, also those added later
+    fn start_chat_bridges(
+        &self,
+        agent_service: &Arc<AgentService>,
+        message_service: &Arc<MessageService>,
+        config: &crate::core::config::Config,
+    ) -> Option<Arc<ChatBridgeService>> {
+        let service = Arc::new(ChatBridgeService::new(
+            config.chat_bridges.clone(),
+            agent_service.clone(),
+            message_service.clone(),
+            self.workspace_service.clone()?,
+            self.persistence_service.clone()?,
+        ));
+        service.start();
+        let reloaded = service.clone();
+        self.agent_config_bus.subscribe_config_reloads(move |config| {
+            reloaded.set_bridges(config.chat_bridges.clone());
+        });
+        Some(service)
+    }
+
+    /// Keep the display of each agent in line with config.json
+    fn track_agent_displays(&self, config: &crate::core::config::Config) {
+        let displays_of = |config: &crate::core::config::Config| {
+            launch_configs(&config.agent_servers)
+                .into_iter()
+                .map(|(name, agent)| (name, agent.display))
+                .collect::<HashMap<_, _>>()
+        };
+        *self.agent_displays.write().unwrap() = displays_of(config);
+        let reloaded = self.agent_displays.clone();
+        self.agent_config_bus
+            .subscribe_config_reloads(move |config| {
+                *reloaded.write().unwrap() = displays_of(config);
+            });
+        let changed = self.agent_displays.clone();
+        self.agent_config_bus
+            .subscribe_agent_events(move |event| match event {
+                AgentConfigEvent::AgentAdded { name, config }
+                | AgentConfigEvent::AgentUpdated { name, config } => {
+                    let mut displays = changed.write().unwrap();
+                    for (name, agent) in config.launch_configs(name) {
+                        displays.insert(name, agent.display);
+                    }
+                }
+                AgentConfigEvent::AgentRemoved { name } => {
+                    changed.write().unwrap().remove(name);
+                }
+                _ => {}
+            });
+    }
+
+    /// Keep the MCP servers run by the app in line with config.json
+    fn start_mcp_service(&self, config: &crate::core::config::Config) -> Arc<McpService> {
+        let service = Arc::new(McpService::new(config.mcp_servers.clone()));
+        let reloaded = service.clone();
+        self.agent_config_bus.subscribe_config_reloads(move |config| {
+            reloaded.set_servers(config.mcp_servers.clone());
+        });
+        let changed = service.clone();
+        self.agent_config_bus.subscribe_mcp_events(move |event| match event {
+            AgentConfigEvent::McpServerAdded { name, config }
+            | AgentConfigEvent::McpServerUpdated { name, config } => {
+                changed.set_server(name, config.clone());
+            }
+            AgentConfigEvent::McpServerRemoved { name } => changed.remove_server(name),
+            _ => {}
+        });
+        service
+    }
+
+    /// Pre-create sessions for the most used agents in the active workspace
+    fn start_warm_pool(
+        &self,
+        agent_service: &Arc<AgentService>,
+        config: &crate::core::config::Config,
+    ) {
+        let pool_config = config.warm_pool.clone();
+        if pool_config.size == 0 || crate::core::safe_mode::is_active() {
+            return;
+        }
+        let Some(workspace_service) = self.workspace_service.clone() else {
+            return;
+        };
+
+        let available: Vec<String> = launch_configs(&config.agent_servers).into_keys().collect();
+        let mcp_servers: Vec<_> = config
+            .mcp_servers
+            .iter()
+            .filter(|(_, server)| server.enabled)
+            .map(|(name, server)| server.to_acp_mcp_server(name.clone()))
+            .collect();
+        let agent_service = agent_service.clone();
+
+        smol::spawn(async move {
+            let tasks = workspace_service.get_all_tasks().await;
+            let agents = pick_warm_agents(
+                &pool_config.agents,
+                tasks.iter().map(|task| task.agent_name.as_str()),
+                &available,
+                pool_config.size,
+            );
+            let cwd = match workspace_service.get_active_workspace().await {
+                Some(workspace) => workspace.path,
+                None => std::env::current_dir().unwrap_or_default(),
+            };
+            agent_service.start_warm_pool(
+                agents,
+                cwd,
+                mcp_servers,
+                std::time::Duration::from_secs(120),
+            );
+        })
+        .detach();
+    }
+
     /// Set the config path for AgentConfigService
     pub fn set_config_path(&mut self, path: PathBuf) {
         self.config_path = Some(path);
@@ -179,6 +467,35 @@ impl AppState {
     /// Set the PermissionStore
     pub fn set_permission_store(&mut self, store: Arc<PermissionStore>) {
         log::info!("Setting PermissionStore");
+        if let Some(audit_service) = self.audit_service.clone() {
+            store.set_audit_service(audit_service);
+        }
+        // Refuse the writes of locked tasks' agents, including those locked earlier
+        if let Some(workspace_service) = self.workspace_service.clone() {
+            let lock_store = store.clone();
+            smol::spawn(async move {
+                let session_ids = workspace_service.locked_session_ids().await;
+                lock_store.set_sessions_locked(&session_ids, true);
+            })
+            .detach();
+        }
+        let lock_store = store.clone();
+        self.workspace_bus.subscribe(move |event| {
+            if let WorkspaceUpdateEvent::TaskLockChanged {
+                session_ids,
+                locked,
+                ..
+            } = event
+            {
+                lock_store.set_sessions_locked(session_ids, *locked);
+            }
+        });
+        // Keep confirmation policies in line with config.json
+        let policy_store = store.clone();
+        self.agent_config_bus.subscribe_config_reloads(move |config| {
+            policy_store.set_policies(config.permission_policies.clone());
+            policy_store.set_command_policy(&config.command_policy);
+        });
         self.permission_store = Some(store);
     }
 
@@ -251,6 +568,105 @@ impl AppState {
         self.ai_service.as_ref()
     }
 
+    /// Get the MetricsService
+    pub fn metrics_service(&self) -> Option<&Arc<MetricsService>> {
+        self.metrics_service.as_ref()
+    }
+
+    /// Get the AuditService
+    pub fn audit_service(&self) -> Option<&Arc<AuditService>> {
+        self.audit_service.as_ref()
+    }
+
+    /// Get the MaintenanceService
+    pub fn maintenance_service(&self) -> Option<&Arc<MaintenanceService>> {
+        self.maintenance_service.as_ref()
+    }
+
+    /// Get the ImportService
+    pub fn import_service(&self) -> Option<&Arc<ImportService>> {
+        self.import_service.as_ref()
+    }
+
+    /// Get the SearchService
+    pub fn search_service(&self) -> Option<&Arc<SearchService>> {
+        self.search_service.as_ref()
+    }
+
+    /// Get the McpService
+    pub fn mcp_service(&self) -> Option<&Arc<McpService>> {
+        self.mcp_service.as_ref()
+    }
+
+    /// Get the SyncService, if a sync backend is configured
+    pub fn sync_service(&self) -> Option<&Arc<SyncService>> {
+        self.sync_service.as_ref()
+    }
+
+    pub fn share_service(&self) -> Option<&Arc<ShareService>> {
+        self.share_service.as_ref()
+    }
+
+    /// Get the WebhookService
+    pub fn webhook_service(&self) -> Option<&Arc<WebhookService>> {
+        self.webhook_service.as_ref()
+    }
+
+    /// Get the EditorBridgeService
+    pub fn editor_bridge_service(&self) -> Option<&Arc<EditorBridgeService>> {
+        self.editor_bridge_service.as_ref()
+    }
+
+    /// Get the ChatBridgeService
+    pub fn chat_bridge_service(&self) -> Option<&Arc<ChatBridgeService>> {
+        self.chat_bridge_service.as_ref()
+    }
+
+    /// Set the ProfileService
+    pub fn set_profile_service(&mut self, service: Arc<ProfileService>) {
+        self.profile_service = Some(service);
+    }
+
+    /// Get the ProfileService
+    pub fn profile_service(&self) -> Option<&Arc<ProfileService>> {
+        self.profile_service.as_ref()
+    }
+
+    /// Filter for scanning the workspace at `root` (.gitignore plus exclude globs)
+    pub fn path_filter(&self, root: &Path) -> PathFilter {
+        PathFilter::new(root, self.indexing.exclude_globs_for(root))
+    }
+
+    /// Symbol index for a workspace root, created on first use
+    pub fn symbol_index(&mut self, root: &Path) -> Arc<SymbolIndex> {
+        let filter = self.path_filter(root);
+        self.symbol_indexes
+            .entry(root.to_path_buf())
+            .or_insert_with(|| SymbolIndex::new(filter))
+            .clone()
+    }
+
+    /// Whether `/search` can run: embeddings are enabled and an AI model is configured
+    pub fn semantic_search_enabled(&self) -> bool {
+        self.embeddings.enabled && self.ai_service.is_some()
+    }
+
+    /// Embeddings index for a workspace root, if semantic search is enabled
+    pub fn semantic_index(&mut self, root: &Path) -> Option<Arc<SemanticIndex>> {
+        if !self.embeddings.enabled {
+            return None;
+        }
+        let ai_service = self.ai_service.clone()?;
+        let filter = self.path_filter(root);
+        let embeddings = self.embeddings.clone();
+        Some(
+            self.semantic_indexes
+                .entry(root.to_path_buf())
+                .or_insert_with(|| SemanticIndex::new(filter, ai_service, embeddings))
+                .clone(),
+        )
+    }
+
     /// Get the current working directory
     pub fn current_working_dir(&self) -> &PathBuf {
         &self.current_working_dir
@@ -259,6 +675,7 @@ impl AppState {
     /// Set the current working directory
     pub fn set_current_working_dir(&mut self, path: PathBuf) {
         log::info!("Setting current working directory: {:?}", path);
+        crate::core::config_manager::set_workspace_dir(path.clone());
         self.current_working_dir = path;
     }
 
@@ -266,5 +683,25 @@ impl AppState {
     pub fn tool_call_preview_max_lines(&self) -> usize {
         self.tool_call_preview_max_lines
     }
+
+    /// Snippets offered in the prompt input
+    pub fn snippets(&self) -> Vec<Snippet> {
+        self.snippets.read().unwrap().clone()
+    }
+
+    /// How `agent_name` appears in avatars and headers
+    pub fn agent_display(&self, agent_name: &str) -> AgentDisplay {
+        self.agent_displays
+            .read()
+            .unwrap()
+            .get(agent_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Name shown for `agent_name`: its configured display name, if any
+    pub fn agent_label(&self, agent_name: &str) -> String {
+        self.agent_display(agent_name).label(agent_name)
+    }
 }
 impl Global for AppState {}
@@ -0,0 +1,127 @@
+//! Focus mode (do not disturb)
+//!
+//! While focus mode is on, notifications are held back instead of popping up
+//! and dock tabs show no activity badges. Nothing is dropped: the held
+//! notifications are delivered, in order, as soon as focus mode ends, either
+//! when its duration runs out or when the user turns it off.
+
+use std::time::{Duration, Instant};
+
+use gpui::{App, Global, Window};
+use gpui_component::{WindowExt as _, notification::Notification};
+use smol::Timer;
+
+use crate::panels::AppSettings;
+
+/// Default focus mode duration, in minutes
+pub const DEFAULT_FOCUS_MINUTES: f64 = 60.0;
+
+#[derive(Default)]
+pub struct FocusMode {
+    until: Option<Instant>,
+    held: Vec<Notification>,
+    /// Bumped on every start, so a stale timer does not end a newer focus period
+    generation: u64,
+}
+
+impl Global for FocusMode {}
+
+impl FocusMode {
+    /// Whether focus mode is on
+    pub fn is_active(cx: &App) -> bool {
+        Self::remaining(cx).is_some()
+    }
+
+    /// Time left until focus mode ends on its own
+    pub fn remaining(cx: &App) -> Option<Duration> {
+        let until = cx.try_global::<Self>()?.until?;
+        until
+            .checked_duration_since(Instant::now())
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Number of notifications waiting for focus mode to end
+    pub fn held_count(cx: &App) -> usize {
+        cx.try_global::<Self>().map_or(0, |focus| focus.held.len())
+    }
+
+    /// Turn focus mode on for the duration configured in the settings
+    pub fn start(window: &mut Window, cx: &mut App) {
+        let minutes = AppSettings::global(cx).focus_mode_minutes.max(1.0);
+        let duration = Duration::from_secs_f64(minutes * 60.0);
+
+        let focus = cx.default_global::<Self>();
+        focus.until = Some(Instant::now() + duration);
+        focus.generation += 1;
+        let generation = focus.generation;
+
+        window
+            .spawn(cx, async move |cx| {
+                Timer::after(duration).await;
+                _ = cx.update(|window, cx| {
+                    if cx.global::<Self>().generation == generation {
+                        Self::end(window, cx);
+                    }
+                });
+            })
+            .detach();
+        window.refresh();
+    }
+
+    /// Turn focus mode off and deliver the held notifications
+    pub fn end(window: &mut Window, cx: &mut App) {
+        let focus = cx.default_global::<Self>();
+        focus.until = None;
+        let held = std::mem::take(&mut focus.held);
+        for note in held {
+            window.push_notification(note, cx);
+        }
+        window.refresh();
+    }
+
+    pub fn toggle(window: &mut Window, cx: &mut App) {
+        if Self::is_active(cx) {
+            Self::end(window, cx);
+        } else {
+            Self::start(window, cx);
+        }
+    }
+}
+
+/// Showing notifications with respect to focus mode
+pub trait NotificationExt {
+    /// Push `note`, or hold it until focus mode ends
+    fn show_notification(&mut self, note: impl Into<Notification>, cx: &mut App);
+}
+
+impl NotificationExt for Window {
+    fn show_notification(&mut self, note: impl Into<Notification>, cx: &mut App) {
+        if FocusMode::is_active(cx) {
+            cx.default_global::<FocusMode>().held.push(note.into());
+            return;
+        }
+        self.push_notification(note, cx);
+    }
+}
+
+/// Remaining time in a compact form, e.g. `1h 05m` or `12m`
+pub fn format_remaining(remaining: Duration) -> String {
+    let minutes = remaining.as_secs().div_ceil(60);
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, minutes) => format!("{}h {:02}m", hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(Duration::from_secs(30)), "1m");
+        assert_eq!(format_remaining(Duration::from_secs(12 * 60)), "12m");
+        assert_eq!(format_remaining(Duration::from_secs(65 * 60 - 10)), "1h 05m");
+        assert_eq!(format_remaining(Duration::from_secs(3 * 3600)), "3h 00m");
+    }
+}
@@ -1,6 +1,8 @@
 use gpui::{App, KeyBinding};
 
-use crate::app::actions::{Open, Paste, Quit, ToggleSearch};
+use crate::app::actions::{
+    GoBack, GoForward, NewTask, Open, Paste, QuickSwitch, Quit, ToggleSearch,
+};
 use gpui_term::{Clear, Copy, SelectAll};
 
 // 导出KeyBinding设置函数,供主应用使用
@@ -19,6 +21,22 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("cmd-v", Paste, None),
         #[cfg(not(target_os = "macos"))]
         KeyBinding::new("ctrl-v", Paste, None),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-[", GoBack, None),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("alt-left", GoBack, None),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-]", GoForward, None),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("alt-right", GoForward, None),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-p", QuickSwitch, None),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-p", QuickSwitch, None),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-n", NewTask::default(), None),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-n", NewTask::default(), None),
         // Terminal keybindings
         #[cfg(target_os = "macos")]
         KeyBinding::new("cmd-c", Copy, Some("Terminal")),
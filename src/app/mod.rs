@@ -1,8 +1,10 @@
 pub mod actions;
 pub mod app_menus;
 pub mod app_state;
+pub mod focus_mode;
 pub mod key_binding;
 pub mod menu;
+pub mod status_bar;
 pub mod system_tray;
 pub mod themes;
 pub mod title_bar;
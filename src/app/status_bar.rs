@@ -0,0 +1,235 @@
+//! Status bar at the bottom of the window
+//!
+//! Shows how many agents are working, whether a session is streaming, how
+//! many sessions still have updates waiting to be written, focus mode, the
+//! active workspace and the UI language. Each item leads to where it can be
+//! acted on.
+
+use std::{sync::Arc, time::Duration};
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    menu::DropdownMenu as _,
+};
+use rust_i18n::t;
+use smol::Timer;
+
+use crate::{
+    AppState, PanelAction, QuickSwitch, SelectLocale, ToggleFocusMode,
+    app::focus_mode::{FocusMode, format_remaining},
+    core::services::{SessionStatus, WorkspaceService},
+};
+
+/// How often the status is refreshed
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Snapshot of the global app state shown in the status bar
+#[derive(Clone, Debug, Default, PartialEq)]
+struct AppStatus {
+    /// Agents with at least one live session
+    active_agents: usize,
+    /// Sessions whose agent is responding right now
+    streaming_sessions: Vec<String>,
+    /// Sessions with updates not yet written to disk
+    pending_writes: usize,
+    /// Time left in focus mode and the notifications it holds back
+    focus: Option<(String, usize)>,
+    workspace: Option<(String, String)>,
+}
+
+pub struct StatusBar {
+    status: AppStatus,
+    _refresh_task: Task<()>,
+}
+
+impl StatusBar {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let refresh_task = cx.spawn(async move |this, cx| {
+            loop {
+                let (mut status, workspace_service) = cx.update(|cx| Self::snapshot(cx));
+                if let Some(service) = workspace_service {
+                    status.workspace = service
+                        .get_active_workspace()
+                        .await
+                        .map(|workspace| (workspace.id, workspace.name));
+                }
+                let updated = this.update(cx, |this, cx| {
+                    if this.status != status {
+                        this.status = status;
+                        cx.notify();
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
+                Timer::after(REFRESH_INTERVAL).await;
+            }
+        });
+
+        Self {
+            status: AppStatus::default(),
+            _refresh_task: refresh_task,
+        }
+    }
+
+    /// Current state of the services, apart from the active workspace which
+    /// has to be looked up asynchronously
+    fn snapshot(cx: &App) -> (AppStatus, Option<Arc<WorkspaceService>>) {
+        let state = AppState::global(cx);
+        let sessions = state
+            .agent_service()
+            .map(|service| service.list_sessions())
+            .unwrap_or_default();
+        let pending_writes = state
+            .persistence_service()
+            .map(|service| service.pending_sessions())
+            .unwrap_or_default();
+
+        let mut agents: Vec<&str> = sessions
+            .iter()
+            .filter(|s| !s.hibernated && s.status != SessionStatus::Closed)
+            .map(|s| s.agent_name.as_str())
+            .collect();
+        agents.sort_unstable();
+        agents.dedup();
+
+        let status = AppStatus {
+            active_agents: agents.len(),
+            streaming_sessions: sessions
+                .iter()
+                .filter(|s| s.status == SessionStatus::InProgress)
+                .map(|s| s.session_id.clone())
+                .collect(),
+            pending_writes,
+            focus: FocusMode::remaining(cx)
+                .map(|remaining| (format_remaining(remaining), FocusMode::held_count(cx))),
+            workspace: None,
+        };
+        (status, state.workspace_service().cloned())
+    }
+}
+
+impl Render for StatusBar {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let status = &self.status;
+        let locale = rust_i18n::locale().to_string();
+        let locale_label = match locale.as_str() {
+            "zh-CN" => t!("menu.app.language.zh_cn").to_string(),
+            _ => t!("menu.app.language.english").to_string(),
+        };
+        let streaming = status.streaming_sessions.clone();
+
+        h_flex()
+            .id("status-bar")
+            .w_full()
+            .h(px(24.))
+            .px_2()
+            .gap_1()
+            .flex_shrink_0()
+            .border_t_1()
+            .border_color(theme.border)
+            .bg(theme.title_bar)
+            .text_xs()
+            .text_color(theme.muted_foreground)
+            .child(
+                Button::new("status-agents")
+                    .xsmall()
+                    .ghost()
+                    .icon(IconName::Bot)
+                    .label(t!("status_bar.agents", count = status.active_agents).to_string())
+                    .tooltip(t!("status_bar.agents.tooltip").to_string())
+                    .on_click(|_, window, cx| window.dispatch_action(Box::new(QuickSwitch), cx)),
+            )
+            .when(!streaming.is_empty(), |this| {
+                this.child(
+                    Button::new("status-streaming")
+                        .xsmall()
+                        .ghost()
+                        .icon(Icon::new(IconName::LoaderCircle).text_color(theme.primary))
+                        .label(
+                            t!("status_bar.streaming", count = streaming.len()).to_string(),
+                        )
+                        .on_click(move |_, window, cx| {
+                            // A single streaming session is opened directly
+                            let action: Box<dyn Action> = match streaming.as_slice() {
+                                [session_id] => Box::new(PanelAction::show_conversation(Some(
+                                    session_id.clone(),
+                                ))),
+                                _ => Box::new(QuickSwitch),
+                            };
+                            window.dispatch_action(action, cx);
+                        }),
+                )
+            })
+            .when(status.pending_writes > 0, |this| {
+                this.child(
+                    div()
+                        .px_1()
+                        .child(t!("status_bar.pending", count = status.pending_writes).to_string()),
+                )
+            })
+            .child(div().flex_1())
+            .child({
+                let label = match &status.focus {
+                    Some((remaining, 0)) => {
+                        t!("status_bar.focus.on", remaining = remaining).to_string()
+                    }
+                    Some((remaining, held)) => {
+                        t!("status_bar.focus.held", remaining = remaining, count = held)
+                            .to_string()
+                    }
+                    None => t!("status_bar.focus.off").to_string(),
+                };
+                Button::new("status-focus")
+                    .xsmall()
+                    .ghost()
+                    .icon(Icon::new(IconName::Moon).when(status.focus.is_some(), |icon| {
+                        icon.text_color(theme.primary)
+                    }))
+                    .label(label)
+                    .tooltip(t!("status_bar.focus.tooltip").to_string())
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(Box::new(ToggleFocusMode), cx)
+                    })
+            })
+            .when_some(status.workspace.clone(), |this, (workspace_id, name)| {
+                this.child(
+                    Button::new("status-workspace")
+                        .xsmall()
+                        .ghost()
+                        .icon(IconName::Folder)
+                        .label(name)
+                        .tooltip(t!("status_bar.workspace.tooltip").to_string())
+                        .on_click(move |_, window, cx| {
+                            let action = PanelAction::show_welcome(Some(workspace_id.clone()));
+                            window.dispatch_action(Box::new(action), cx);
+                        }),
+                )
+            })
+            .child(
+                Button::new("status-locale")
+                    .xsmall()
+                    .ghost()
+                    .icon(IconName::Globe)
+                    .label(locale_label)
+                    .dropdown_menu(move |menu, _, _| {
+                        menu.menu_with_check(
+                            t!("menu.app.language.english").to_string(),
+                            locale == "en",
+                            Box::new(SelectLocale("en".into())),
+                        )
+                        .menu_with_check(
+                            t!("menu.app.language.zh_cn").to_string(),
+                            locale == "zh-CN",
+                            Box::new(SelectLocale("zh-CN".into())),
+                        )
+                    })
+                    .anchor(Corner::BottomRight),
+            )
+    }
+}
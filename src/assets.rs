@@ -62,6 +62,11 @@ pub enum Icon {
     MoveRight,
     TextWrap,
     ArrowRightToLine,
+    Lock,
+    LockOpen,
+    Smile,
+    TextQuote,
+    RefreshCw,
 }
 
 impl IconNamed for Icon {
@@ -93,6 +98,11 @@ impl IconNamed for Icon {
             Icon::MoveRight => "icons2/move-right.svg",
             Icon::TextWrap => "icons2/text-wrap.svg",
             Icon::ArrowRightToLine => "icons2/arrow-right-to-line.svg",
+            Icon::Lock => "icons2/lock.svg",
+            Icon::LockOpen => "icons2/lock-open.svg",
+            Icon::Smile => "icons2/smile.svg",
+            Icon::TextQuote => "icons2/text-quote.svg",
+            Icon::RefreshCw => "icons2/refresh-cw.svg",
         }
         .into()
     }
@@ -100,9 +110,15 @@ impl IconNamed for Icon {
 
 /// Get icon based on agent name
 pub fn get_agent_icon(name: &str) -> Icon {
+    // Default to Claude icon if no match
+    find_agent_icon(name).unwrap_or(crate::assets::Icon::Claude)
+}
+
+/// Icon of the agent's vendor, if the name tells which it is
+pub fn find_agent_icon(name: &str) -> Option<Icon> {
     let name_lower = name.to_lowercase();
     // TODO Check for specific agent names
-    if name_lower.contains("claude") {
+    let icon = if name_lower.contains("claude") {
         crate::assets::Icon::Claude
     } else if name_lower.contains("cursor") {
         crate::assets::Icon::Cursor
@@ -125,9 +141,9 @@ pub fn get_agent_icon(name: &str) -> Icon {
     } else if name_lower.contains("zai") {
         crate::assets::Icon::Zai
     } else {
-        // Default to Claude icon if no match
-        crate::assets::Icon::Claude
-    }
+        return None;
+    };
+    Some(icon)
 }
 
 /// Get default config.json content embedded in the binary
@@ -0,0 +1,74 @@
+use gpui::{
+    App, FontWeight, IntoElement, ObjectFit, ParentElement, RenderOnce, Styled, StyledImage,
+    Window, div, img, px, rgb,
+};
+use gpui_component::{ActiveTheme, Icon};
+
+use crate::AppState;
+use crate::assets::find_agent_icon;
+use crate::core::config::AgentDisplay;
+use crate::utils::avatar::{avatar_color, initial};
+
+/// An agent's avatar: its configured image, else its initial on its
+/// configured color, else its vendor's icon, else its initial on a color
+/// derived from its name
+#[derive(IntoElement)]
+pub struct AgentAvatar {
+    agent_name: String,
+    display: AgentDisplay,
+    size: f32,
+}
+
+impl AgentAvatar {
+    /// Avatar of the agent configured as `agent_name`
+    pub fn new(agent_name: impl Into<String>, cx: &App) -> Self {
+        let agent_name = agent_name.into();
+        let display = AppState::global(cx).agent_display(&agent_name);
+        Self {
+            agent_name,
+            display,
+            size: 16.0,
+        }
+    }
+
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+}
+
+impl RenderOnce for AgentAvatar {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let size = px(self.size);
+        if let Some(path) = self.display.avatar {
+            return img(path)
+                .size(size)
+                .flex_none()
+                .rounded_full()
+                .object_fit(ObjectFit::Cover)
+                .into_any_element();
+        }
+        let icon = find_agent_icon(&self.agent_name).filter(|_| self.display.color.is_none());
+        if let Some(icon) = icon {
+            return Icon::new(icon)
+                .size(size)
+                .text_color(cx.theme().foreground)
+                .into_any_element();
+        }
+
+        let color = avatar_color(&self.agent_name, self.display.color.as_deref());
+        div()
+            .size(size)
+            .flex_none()
+            .flex()
+            .items_center()
+            .justify_center()
+            .rounded_full()
+            .bg(rgb(color))
+            .text_color(gpui::white())
+            .text_size(px(self.size * 0.6))
+            .font_weight(FontWeight::SEMIBOLD)
+            .child(initial(&self.display.label(&self.agent_name)))
+            .into_any_element()
+    }
+}
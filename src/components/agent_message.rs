@@ -1,12 +1,15 @@
 use agent_client_protocol::{ContentBlock, ContentChunk, SessionId};
 use gpui::{
-    App, AppContext, Context, ElementId, Entity, IntoElement, ParentElement, Render, RenderOnce,
-    SharedString, Styled, Window, div, prelude::FluentBuilder as _, px,
+    AnyView, App, AppContext, Context, ElementId, Entity, FontWeight, InteractiveElement as _,
+    IntoElement, ParentElement, Render, RenderOnce, SharedString, StatefulInteractiveElement as _,
+    Styled, Window, div, prelude::FluentBuilder as _, px,
 };
-use gpui_component::{ActiveTheme, Icon, IconName, h_flex, text::TextView, v_flex};
+use gpui_component::{ActiveTheme, IconName, h_flex, text::TextView, v_flex};
 use serde::{Deserialize, Serialize};
 
-use crate::assets::get_agent_icon;
+use crate::AppState;
+use crate::components::AgentAvatar;
+use crate::core::services::AgentIdentity;
 
 /// Extended metadata for agent messages.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -151,9 +154,8 @@ impl RenderOnce for AgentMessage {
         let show_thinking = !self.data.is_complete();
         let full_text = self.data.full_text();
         let markdown_id = SharedString::from(format!("{}-markdown", self.id));
-
-        // Get icon based on agent name
-        let icon = Icon::new(get_agent_icon(agent_name));
+        let card_agent = self.data.agent_name().map(str::to_string);
+        let card_session = self.data.session_id.to_string();
 
         v_flex()
             .gap_3()
@@ -163,7 +165,15 @@ impl RenderOnce for AgentMessage {
                 h_flex()
                     .items_start()
                     .gap_2()
-                    .child(icon.size(px(16.)).mt_1().text_color(cx.theme().foreground))
+                    .child(
+                        div()
+                            .id(SharedString::from(format!("{}-avatar", self.id)))
+                            .mt_1()
+                            .child(AgentAvatar::new(agent_name, cx))
+                            .tooltip(move |_, cx| {
+                                AgentHoverCard::view(card_agent.clone(), &card_session, cx)
+                            }),
+                    )
                     // Message content with markdown rendering
                     .child(
                         div()
@@ -182,6 +192,101 @@ impl RenderOnce for AgentMessage {
     }
 }
 
+/// Card shown when hovering an agent's avatar: the agent's version and
+/// capabilities, and the model, mode and status of the session
+pub struct AgentHoverCard {
+    identity: AgentIdentity,
+    /// Configured display name of the agent
+    label: String,
+}
+
+impl AgentHoverCard {
+    /// Card for the session's agent, read from the AgentService when shown
+    pub fn view(agent_name: Option<String>, session_id: &str, cx: &mut App) -> AnyView {
+        let identity = AppState::global(cx)
+            .agent_service()
+            .and_then(|service| {
+                let agent_name = agent_name
+                    .clone()
+                    .or_else(|| service.get_agent_for_session(session_id))?;
+                Some(service.identity(&agent_name, session_id))
+            })
+            .unwrap_or_else(|| AgentIdentity {
+                agent_name: agent_name.unwrap_or_else(|| "Agent".to_string()),
+                session_id: session_id.to_string(),
+                ..Default::default()
+            });
+        let label = AppState::global(cx).agent_label(&identity.agent_name);
+        cx.new(|_| Self { identity, label }).into()
+    }
+}
+
+impl Render for AgentHoverCard {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let identity = &self.identity;
+        let muted_foreground = cx.theme().muted_foreground;
+        let row = |label: &'static str, value: Option<String>| {
+            h_flex()
+                .gap_2()
+                .items_start()
+                .child(
+                    div()
+                        .w(px(80.))
+                        .flex_none()
+                        .text_color(muted_foreground)
+                        .child(label),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .min_w_0()
+                        .child(value.unwrap_or_else(|| "Unknown".to_string())),
+                )
+        };
+        let capabilities = if identity.capabilities.is_empty() {
+            None
+        } else {
+            Some(identity.capabilities.join(", "))
+        };
+
+        v_flex()
+            .w(px(300.))
+            .p_3()
+            .gap_1()
+            .text_xs()
+            .text_color(cx.theme().foreground)
+            .bg(cx.theme().popover)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded(cx.theme().radius)
+            .shadow_md()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .pb_1()
+                    .child(AgentAvatar::new(identity.agent_name.clone(), cx))
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child(self.label.clone()),
+                    ),
+            )
+            .child(row("Version", identity.agent_info.clone()))
+            .child(row("Model", identity.model.clone()))
+            .child(row("Mode", identity.mode.clone()))
+            .child(row(
+                "Status",
+                identity
+                    .status
+                    .as_ref()
+                    .map(|status| format!("{:?}", status)),
+            ))
+            .child(row("Session", Some(identity.session_id.clone())))
+            .child(row("Capabilities", capabilities))
+    }
+}
+
 /// A stateful wrapper for AgentMessage that can be used as a GPUI view
 pub struct AgentMessageView {
     data: Entity<AgentMessageData>,
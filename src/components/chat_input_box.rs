@@ -1,28 +1,40 @@
 use gpui::{
-    App, ElementId, Entity, InteractiveElement, IntoElement, ParentElement, RenderOnce,
-    SharedString, Styled, Window, div, prelude::FluentBuilder, px,
+    AnyElement, App, Corner, ElementId, Entity, InteractiveElement, IntoElement, ParentElement,
+    RenderOnce, SharedString, StatefulInteractiveElement, Styled, Window, div,
+    prelude::FluentBuilder, px,
+};
+use std::{
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+    sync::Arc,
 };
-use std::{rc::Rc, sync::Arc};
 
 use gpui_component::{
-    ActiveTheme, Disableable, Icon, IconName, Sizable,
-    button::{Button, ButtonCustomVariant, ButtonVariants},
+    ActiveTheme, Disableable, Icon, IconName, Selectable, Sizable,
+    button::{Button, ButtonCustomVariant, ButtonGroup, ButtonVariants},
+    checkbox::Checkbox,
     h_flex,
     input::{Input, InputState},
+    menu::{DropdownMenu, PopupMenuItem},
     popover::Popover,
     select::{Select, SelectState},
+    text::TextView,
     v_flex,
 };
 
 use agent_client_protocol::{AvailableCommand, ImageContent};
 
 use crate::app::actions::AddCodeSelection;
+use crate::app::title_bar::OpenSettings;
 use crate::components::{
     AgentItem, FileItem, InputSuggestion, InputSuggestionItem, InputSuggestionState,
     ModeSelectItem, ModelSelectItem,
 };
 use crate::core::config::McpServerConfig;
+use crate::core::mcp_proxy::DisabledTools;
 use crate::core::services::SessionStatus;
+use crate::utils::clipboard::{PastedText, is_large_paste};
+use crate::utils::slash_command;
 
 impl InputSuggestionItem for AvailableCommand {
     fn label(&self) -> SharedString {
@@ -67,6 +79,9 @@ impl InputSuggestionItem for ChatSuggestion {
 /// Features:
 /// - @ trigger for file suggestions
 /// - Multi-line textarea with auto-grow (2-8 rows)
+/// - Enter to send, Shift+Enter for a newline (when `on_submit` is set;
+///   `send_with_modifier` switches to Ctrl/Cmd+Enter)
+/// - Optional Write/Preview tabs rendering the draft as markdown
 /// - Action buttons (attach, mode/model select, sources)
 /// - Send button with icon
 /// - Optional title displayed above the input box
@@ -77,6 +92,10 @@ pub struct ChatInputBox {
     input_state: Entity<InputState>,
     title: Option<String>,
     on_send: Option<Box<dyn Fn(&gpui::ClickEvent, &mut Window, &mut App) + 'static>>,
+    /// Called when Enter is pressed in the input
+    on_submit: Option<Rc<dyn Fn(&mut Window, &mut App) + 'static>>,
+    /// Send with Ctrl/Cmd+Enter instead of Enter
+    send_with_modifier: bool,
     on_cancel: Option<Box<dyn Fn(&gpui::ClickEvent, &mut Window, &mut App) + 'static>>,
     mode_select: Option<Entity<SelectState<Vec<ModeSelectItem>>>>,
     model_select: Option<Entity<SelectState<Vec<ModelSelectItem>>>>,
@@ -85,9 +104,17 @@ pub struct ChatInputBox {
     pasted_images: Vec<(ImageContent, String)>, // (ImageContent, filename for display)
     code_selections: Vec<AddCodeSelection>,     // Code selections from editor
     selected_files: Vec<String>,                // Selected file paths from file picker
+    pasted_texts: Vec<PastedText>,              // Large pastes attached as resources
+    /// Line count of a large paste waiting for attach/inline choice
+    pending_paste_lines: Option<usize>,
     on_remove_image: Option<Rc<dyn Fn(&usize, &mut Window, &mut App) + 'static>>,
     on_remove_code_selection: Option<Rc<dyn Fn(&usize, &mut Window, &mut App) + 'static>>,
     on_remove_file: Option<Rc<dyn Fn(&usize, &mut Window, &mut App) + 'static>>,
+    on_remove_pasted_text: Option<Rc<dyn Fn(&usize, &mut Window, &mut App) + 'static>>,
+    /// Called instead of inserting when pasted text exceeds LARGE_PASTE_LINES
+    on_large_paste: Option<Rc<dyn Fn(&String, &mut Window, &mut App) + 'static>>,
+    /// Called with `true` to attach the pending paste, `false` to insert it inline
+    on_resolve_paste: Option<Rc<dyn Fn(&bool, &mut Window, &mut App) + 'static>>,
     on_paste: Option<Rc<dyn Fn(&mut Window, &mut App) + 'static>>,
     session_status: Option<SessionStatus>, // Session status for button state
     file_suggestions: Vec<FileItem>,
@@ -96,6 +123,8 @@ pub struct ChatInputBox {
     command_suggestions: Vec<AvailableCommand>,
     /// Whether to show command suggestions
     show_command_suggestions: bool,
+    /// Argument hint for a completed command (e.g. `/review <path>`)
+    command_hint: Option<String>,
     /// Optional click/confirm handler for command selection
     on_command_select: Option<Box<dyn Fn(&AvailableCommand, &mut Window, &mut App) + 'static>>,
     /// Available MCP servers (name, config)
@@ -104,6 +133,19 @@ pub struct ChatInputBox {
     selected_mcps: Vec<String>,
     /// Callback when MCP checkbox is clicked (passes (name, checked) tuple)
     on_mcp_toggle: Option<Rc<dyn Fn(&(String, bool), &mut Window, &mut App) + 'static>>,
+    /// Tool names of the MCP servers whose tools were listed
+    mcp_tools: HashMap<String, Vec<String>>,
+    /// Tools left out of the session, by MCP server
+    disabled_mcp_tools: DisabledTools,
+    /// Callback when an MCP tool checkbox is clicked (passes (server, tool, enabled) tuple)
+    on_mcp_tool_toggle:
+        Option<Rc<dyn Fn(&(String, String, bool), &mut Window, &mut App) + 'static>>,
+    /// Callback to list the tools of an MCP server (passes the server name)
+    on_mcp_list_tools: Option<Rc<dyn Fn(&String, &mut Window, &mut App) + 'static>>,
+    /// Whether the markdown preview tab is active
+    preview: bool,
+    /// Callback when the Write/Preview tab is switched (passes the new preview state)
+    on_toggle_preview: Option<Rc<dyn Fn(&bool, &mut Window, &mut App) + 'static>>,
 }
 
 impl ChatInputBox {
@@ -114,6 +156,8 @@ impl ChatInputBox {
             input_state,
             title: None,
             on_send: None,
+            on_submit: None,
+            send_with_modifier: false,
             on_cancel: None,
             mode_select: None,
             model_select: None,
@@ -122,19 +166,31 @@ impl ChatInputBox {
             pasted_images: Vec::new(),
             code_selections: Vec::new(),
             selected_files: Vec::new(),
+            pasted_texts: Vec::new(),
+            pending_paste_lines: None,
             on_remove_image: None,
             on_remove_code_selection: None,
             on_remove_file: None,
+            on_remove_pasted_text: None,
+            on_large_paste: None,
+            on_resolve_paste: None,
             on_paste: None,
             session_status: None,
             file_suggestions: Vec::new(),
             on_file_select: None,
             command_suggestions: Vec::new(),
             show_command_suggestions: false,
+            command_hint: None,
             on_command_select: None,
             available_mcps: Vec::new(),
             selected_mcps: Vec::new(),
             on_mcp_toggle: None,
+            mcp_tools: HashMap::new(),
+            disabled_mcp_tools: DisabledTools::new(),
+            on_mcp_tool_toggle: None,
+            on_mcp_list_tools: None,
+            preview: false,
+            on_toggle_preview: None,
         }
     }
 
@@ -201,6 +257,38 @@ impl ChatInputBox {
         self
     }
 
+    /// Set a callback for Enter in the input
+    ///
+    /// Not called while the session is in progress or the input is empty.
+    pub fn on_submit<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut Window, &mut App) + 'static,
+    {
+        self.on_submit = Some(Rc::new(callback));
+        self
+    }
+
+    /// Send with Ctrl/Cmd+Enter and let Enter insert a newline
+    pub fn send_with_modifier(mut self, send_with_modifier: bool) -> Self {
+        self.send_with_modifier = send_with_modifier;
+        self
+    }
+
+    /// Show the markdown preview instead of the editor
+    pub fn preview(mut self, preview: bool) -> Self {
+        self.preview = preview;
+        self
+    }
+
+    /// Set a callback for switching between the Write and Preview tabs
+    pub fn on_toggle_preview<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&bool, &mut Window, &mut App) + 'static,
+    {
+        self.on_toggle_preview = Some(Rc::new(callback));
+        self
+    }
+
     /// Set a callback for when paste event occurs
     pub fn on_paste<F>(mut self, callback: F) -> Self
     where
@@ -210,6 +298,45 @@ impl ChatInputBox {
         self
     }
 
+    /// Set the pasted texts attached as resources
+    pub fn pasted_texts(mut self, texts: Vec<PastedText>) -> Self {
+        self.pasted_texts = texts;
+        self
+    }
+
+    /// Set a callback for removing an attached pasted text
+    pub fn on_remove_pasted_text<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&usize, &mut Window, &mut App) + 'static,
+    {
+        self.on_remove_pasted_text = Some(Rc::new(callback));
+        self
+    }
+
+    /// Intercept large text pastes instead of inserting them into the input
+    pub fn on_large_paste<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&String, &mut Window, &mut App) + 'static,
+    {
+        self.on_large_paste = Some(Rc::new(callback));
+        self
+    }
+
+    /// Show the attach/inline choice for a large paste of `lines` lines
+    pub fn pending_paste_lines(mut self, lines: Option<usize>) -> Self {
+        self.pending_paste_lines = lines;
+        self
+    }
+
+    /// Set a callback for the attach (`true`) / paste inline (`false`) choice
+    pub fn on_resolve_paste<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&bool, &mut Window, &mut App) + 'static,
+    {
+        self.on_resolve_paste = Some(Rc::new(callback));
+        self
+    }
+
     /// Set the list of code selections
     pub fn code_selections(mut self, selections: Vec<AddCodeSelection>) -> Self {
         self.code_selections = selections;
@@ -273,6 +400,12 @@ impl ChatInputBox {
         self
     }
 
+    /// Set the argument hint shown under the input
+    pub fn command_hint(mut self, hint: Option<String>) -> Self {
+        self.command_hint = hint;
+        self
+    }
+
     /// Set a callback for when a command suggestion is selected
     pub fn on_command_select<F>(mut self, callback: F) -> Self
     where
@@ -302,6 +435,35 @@ impl ChatInputBox {
         self.on_mcp_toggle = Some(Rc::new(callback));
         self
     }
+
+    /// Set the known tools of MCP servers and the ones disabled for the session
+    pub fn mcp_tools(
+        mut self,
+        tools: HashMap<String, Vec<String>>,
+        disabled: DisabledTools,
+    ) -> Self {
+        self.mcp_tools = tools;
+        self.disabled_mcp_tools = disabled;
+        self
+    }
+
+    /// Set a callback for when an MCP tool checkbox is toggled
+    pub fn on_mcp_tool_toggle<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&(String, String, bool), &mut Window, &mut App) + 'static,
+    {
+        self.on_mcp_tool_toggle = Some(Rc::new(callback));
+        self
+    }
+
+    /// Set a callback for listing the tools of an MCP server
+    pub fn on_mcp_list_tools<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&String, &mut Window, &mut App) + 'static,
+    {
+        self.on_mcp_list_tools = Some(Rc::new(callback));
+        self
+    }
 }
 
 impl RenderOnce for ChatInputBox {
@@ -317,10 +479,29 @@ impl RenderOnce for ChatInputBox {
             InputSuggestionState::with_input(input_state.clone(), window, cx)
         });
         let input_value = self.input_state.read(cx).value();
-        let is_empty = input_value.trim().is_empty();
+        // Pasted images and text can be sent without a message
+        let has_pastes = !self.pasted_images.is_empty()
+            || !self.pasted_texts.is_empty()
+            || self.pending_paste_lines.is_some();
+        let is_empty = input_value.trim().is_empty() && !has_pastes;
         let has_attachments = !self.pasted_images.is_empty()
             || !self.code_selections.is_empty()
-            || !self.selected_files.is_empty();
+            || !self.selected_files.is_empty()
+            || !self.pasted_texts.is_empty();
+        let on_large_paste = self.on_large_paste.clone();
+        let in_progress = matches!(self.session_status, Some(SessionStatus::InProgress));
+        let on_submit = self.on_submit.clone().map(|callback| {
+            let input_state = self.input_state.clone();
+            // Same rules as the send button: nothing to send, or still busy
+            Rc::new(move |window: &mut Window, cx: &mut App| {
+                let no_text = input_state.read(cx).value().trim().is_empty();
+                if in_progress || (no_text && !has_pastes) {
+                    return;
+                }
+                callback(window, cx);
+            }) as Rc<dyn Fn(&mut Window, &mut App)>
+        });
+        let preview = self.preview;
 
         // Get theme after use_keyed_state to avoid borrow conflicts
         let theme = cx.theme();
@@ -394,6 +575,13 @@ impl RenderOnce for ChatInputBox {
                                 // If no images, try to paste text to input
                                 if !has_images {
                                     if let Some(text) = clipboard_item.text() {
+                                        // Large pastes are offered as an attachment instead
+                                        if let Some(on_large_paste) = on_large_paste.as_ref()
+                                            && is_large_paste(&text)
+                                        {
+                                            on_large_paste(&text, window, cx);
+                                            return;
+                                        }
                                         let input = input_state.clone();
                                         input.update(cx, |state, cx| {
                                             // Insert text at cursor position
@@ -404,6 +592,51 @@ impl RenderOnce for ChatInputBox {
                             }
                         })
                     })
+                    .when_some(
+                        self.pending_paste_lines.zip(self.on_resolve_paste.clone()),
+                        |this, (lines, on_resolve)| {
+                            let on_inline = on_resolve.clone();
+                            this.child(
+                                h_flex()
+                                    .w_full()
+                                    .gap_2()
+                                    .items_center()
+                                    .px_2()
+                                    .py_1()
+                                    .rounded(px(6.))
+                                    .bg(theme.muted.opacity(0.5))
+                                    .child(
+                                        div()
+                                            .flex_1()
+                                            .text_xs()
+                                            .text_color(theme.muted_foreground)
+                                            .child(format!(
+                                                "Pasted {} lines. Attach as a file to keep the \
+                                                 prompt readable?",
+                                                lines
+                                            )),
+                                    )
+                                    .child(
+                                        Button::new("paste-attach")
+                                            .label("Attach")
+                                            .primary()
+                                            .xsmall()
+                                            .on_click(move |_, window, cx| {
+                                                on_resolve(&true, window, cx)
+                                            }),
+                                    )
+                                    .child(
+                                        Button::new("paste-inline")
+                                            .label("Paste inline")
+                                            .ghost()
+                                            .xsmall()
+                                            .on_click(move |_, window, cx| {
+                                                on_inline(&false, window, cx)
+                                            }),
+                                    ),
+                            )
+                        },
+                    )
                     .when(has_attachments, |this| {
                         this.child({
                             // Attachments row: Images, code selections, and files
@@ -468,6 +701,26 @@ impl RenderOnce for ChatInputBox {
                                 },
                             ));
 
+                            attachment_chips.extend(self.pasted_texts.iter().enumerate().map(
+                                |(idx, pasted)| {
+                                    render_chip(
+                                        "remove-pasted-text",
+                                        idx,
+                                        IconName::File,
+                                        format!(
+                                            "{} · {} lines · {}",
+                                            pasted.name,
+                                            pasted.line_count(),
+                                            pasted.preview(24)
+                                        ),
+                                        theme.muted.opacity(0.6),
+                                        theme.border,
+                                        theme.foreground.opacity(0.7),
+                                        self.on_remove_pasted_text.clone(),
+                                    )
+                                },
+                            ));
+
                             attachment_chips.extend(self.code_selections.iter().enumerate().map(
                                 |(idx, selection)| {
                                     let filename = std::path::Path::new(&selection.file_path)
@@ -529,6 +782,33 @@ impl RenderOnce for ChatInputBox {
                                 .children(attachment_chips)
                         })
                     })
+                    .when_some(self.on_toggle_preview.clone(), |this, on_toggle| {
+                        let on_write = on_toggle.clone();
+                        this.child(
+                            ButtonGroup::new("input-preview-toggle")
+                                .small()
+                                .child(
+                                    Button::new("input-write")
+                                        .label("Write")
+                                        .ghost()
+                                        .xsmall()
+                                        .selected(!preview)
+                                        .on_click(move |_, window, cx| {
+                                            on_write(&false, window, cx)
+                                        }),
+                                )
+                                .child(
+                                    Button::new("input-preview")
+                                        .label("Preview")
+                                        .ghost()
+                                        .xsmall()
+                                        .selected(preview)
+                                        .on_click(move |_, window, cx| {
+                                            on_toggle(&true, window, cx)
+                                        }),
+                                ),
+                        )
+                    })
                     .child(
                         // Textarea (multi-line input)
                         {
@@ -542,6 +822,8 @@ impl RenderOnce for ChatInputBox {
                                 .when_some(suggestion_header, |input, header| input.header(header))
                                 .max_height(px(200.))
                                 .apply_on_confirm(apply_on_confirm)
+                                .when_some(on_submit, |input, on_submit| input.on_submit(on_submit))
+                                .submit_with_modifier(self.send_with_modifier)
                                 .input(|state| Input::new(state).appearance(false))
                                 .render_item(|item, _selected, _window, cx| {
                                     let theme = cx.theme();
@@ -558,6 +840,20 @@ impl RenderOnce for ChatInputBox {
                                                     .text_color(theme.popover_foreground)
                                                     .child(format!("/{}", command.name)),
                                             )
+                                            .when_some(
+                                                slash_command::input_hint(command),
+                                                |row, hint| {
+                                                    row.child(
+                                                        div()
+                                                            .text_xs()
+                                                            .font_family(
+                                                                "Monaco, 'Courier New', monospace",
+                                                            )
+                                                            .text_color(theme.muted_foreground)
+                                                            .child(hint),
+                                                    )
+                                                },
+                                            )
                                             .child(
                                                 div()
                                                     .flex_1()
@@ -628,9 +924,22 @@ impl RenderOnce for ChatInputBox {
                                 });
                             }
 
-                            div().w_full().child(input)
+                            if preview {
+                                render_preview(&self.id, &input_value, cx).into_any_element()
+                            } else {
+                                div().w_full().child(input).into_any_element()
+                            }
                         },
                     )
+                    .when_some(self.command_hint.filter(|_| !preview), |this, hint| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .font_family("Monaco, 'Courier New', monospace")
+                                .text_color(theme.muted_foreground)
+                                .child(hint),
+                        )
+                    })
                     .child(
                         // Bottom row: Action buttons
                         h_flex()
@@ -674,6 +983,10 @@ impl RenderOnce for ChatInputBox {
                                         let available_mcps = self.available_mcps.clone();
                                         let selected_mcps = self.selected_mcps.clone();
                                         let on_mcp_toggle = self.on_mcp_toggle.clone();
+                                        let mcp_tools = self.mcp_tools.clone();
+                                        let disabled_mcp_tools = self.disabled_mcp_tools.clone();
+                                        let on_mcp_tool_toggle = self.on_mcp_tool_toggle.clone();
+                                        let on_mcp_list_tools = self.on_mcp_list_tools.clone();
 
                                         let label_text = if selected_count > 0 {
                                             format!("MCP ({})", selected_count)
@@ -691,13 +1004,13 @@ impl RenderOnce for ChatInputBox {
                                                     .disabled(!has_mcps),
                                             )
                                             .content(move |_state, _window, cx| {
-                                                use gpui_component::checkbox::Checkbox;
-
                                                 let theme = cx.theme();
 
                                                 let mut content = v_flex()
+                                                    .id("mcp-list")
                                                     .w(px(280.))
                                                     .max_h(px(350.))
+                                                    .overflow_y_scroll()
                                                     .gap_2()
                                                     .p_3();
 
@@ -751,12 +1064,25 @@ impl RenderOnce for ChatInputBox {
                                                                     },
                                                                 ),
                                                         );
+                                                        if is_selected {
+                                                            content =
+                                                                content.children(render_mcp_tools(
+                                                                    idx,
+                                                                    name,
+                                                                    mcp_tools.get(name),
+                                                                    disabled_mcp_tools.get(name),
+                                                                    on_mcp_tool_toggle.clone(),
+                                                                    on_mcp_list_tools.clone(),
+                                                                ));
+                                                        }
                                                     }
                                                 }
 
                                                 content
                                             })
-                                    }),
+                                    })
+                                    .child(render_emoji_picker(&self.input_state))
+                                    .child(render_snippet_menu(&self.input_state, cx)),
                             )
                             .child({
                                 // Determine button icon and behavior based on session status
@@ -818,3 +1144,166 @@ impl RenderOnce for ChatInputBox {
             )
     }
 }
+
+/// Markdown rendering of the current draft, shown in place of the editor
+fn render_preview(id: &ElementId, text: &str, cx: &App) -> impl IntoElement {
+    let theme = cx.theme();
+    let content = if text.trim().is_empty() {
+        div()
+            .text_sm()
+            .text_color(theme.muted_foreground)
+            .child("Nothing to preview")
+            .into_any_element()
+    } else {
+        TextView::markdown(
+            ElementId::NamedChild(Arc::new(id.clone()), "input-preview".into()),
+            text.to_string(),
+        )
+        .text_sm()
+        .text_color(theme.foreground)
+        .selectable(true)
+        .into_any_element()
+    };
+
+    div()
+        .id(ElementId::NamedChild(
+            Arc::new(id.clone()),
+            "input-preview-scroll".into(),
+        ))
+        .w_full()
+        .min_h(px(24.))
+        .max_h(px(240.))
+        .overflow_y_scroll()
+        .child(content)
+}
+
+/// Emoji offered by the input toolbar's picker
+const EMOJIS: &[&str] = &[
+    "👍", "👎", "👀", "🙏", "🎉", "✅", "❌", "⚠️", "🐛", "🔥", "🚀", "💡", "🤔", "😀", "😅", "😢",
+    "❤️", "⭐", "📝", "📌", "🔧", "🧪", "📦", "🔒",
+];
+
+/// Insert `text` at the cursor of the prompt input
+fn insert_at_cursor(
+    input_state: &Entity<InputState>,
+    text: &str,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    input_state.update(cx, |state, cx| {
+        state.insert(text.to_string(), window, cx);
+        state.focus(window, cx);
+    });
+}
+
+/// Toolbar button opening a grid of emoji to insert
+/// Checkboxes for the tools of a selected MCP server, or a button listing
+/// them when they are not known yet
+fn render_mcp_tools(
+    idx: usize,
+    server: &str,
+    tools: Option<&Vec<String>>,
+    disabled: Option<&BTreeSet<String>>,
+    on_tool_toggle: Option<Rc<dyn Fn(&(String, String, bool), &mut Window, &mut App) + 'static>>,
+    on_list_tools: Option<Rc<dyn Fn(&String, &mut Window, &mut App) + 'static>>,
+) -> Option<AnyElement> {
+    let Some(tools) = tools else {
+        let callback = on_list_tools?;
+        let server = server.to_string();
+        return Some(
+            div()
+                .pl_6()
+                .child(
+                    Button::new(("mcp-list-tools", idx))
+                        .label("Choose tools…")
+                        .ghost()
+                        .xsmall()
+                        .on_click(move |_, window, cx| callback(&server, window, cx)),
+                )
+                .into_any_element(),
+        );
+    };
+
+    Some(
+        v_flex()
+            .pl_6()
+            .gap_1()
+            .children(tools.iter().map(|tool| {
+                let enabled = disabled.is_none_or(|disabled| !disabled.contains(tool));
+                let key = (server.to_string(), tool.clone());
+                let callback = on_tool_toggle.clone();
+                Checkbox::new(ElementId::Name(
+                    format!("mcp-tool-{}-{}", server, tool).into(),
+                ))
+                .label(tool.clone())
+                .checked(enabled)
+                .on_click(move |checked, window, cx| {
+                    if let Some(cb) = &callback {
+                        cb(&(key.0.clone(), key.1.clone(), *checked), window, cx);
+                    }
+                })
+            }))
+            .into_any_element(),
+    )
+}
+
+fn render_emoji_picker(input_state: &Entity<InputState>) -> impl IntoElement {
+    let input_state = input_state.clone();
+    Popover::new("emoji-popover")
+        .trigger(
+            Button::new("emoji")
+                .icon(Icon::new(crate::assets::Icon::Smile))
+                .ghost()
+                .small()
+                .tooltip("Emoji"),
+        )
+        .content(move |_state, _window, _cx| {
+            h_flex()
+                .w(px(248.))
+                .flex_wrap()
+                .gap_1()
+                .p_2()
+                .children(EMOJIS.iter().enumerate().map(|(ix, emoji)| {
+                    let input_state = input_state.clone();
+                    Button::new(("emoji", ix))
+                        .label(*emoji)
+                        .ghost()
+                        .small()
+                        .on_click(move |_, window, cx| {
+                            insert_at_cursor(&input_state, emoji, window, cx);
+                        })
+                }))
+        })
+}
+
+/// Toolbar menu of the user's snippets (see `Config::snippets`)
+///
+/// Unlike slash commands, a snippet is only inserted at the cursor, so it
+/// can be edited or combined with other text before sending.
+fn render_snippet_menu(input_state: &Entity<InputState>, cx: &App) -> impl IntoElement {
+    let snippets = crate::AppState::global(cx).snippets();
+    let input_state = input_state.clone();
+    Button::new("snippets")
+        .icon(Icon::new(crate::assets::Icon::TextQuote))
+        .ghost()
+        .small()
+        .tooltip("Insert Snippet")
+        .dropdown_menu(move |mut menu, _, _| {
+            if snippets.is_empty() {
+                menu = menu.item(PopupMenuItem::new("No snippets yet").disabled(true));
+            }
+            for snippet in &snippets {
+                let input_state = input_state.clone();
+                let text = snippet.text.clone();
+                menu = menu.item(PopupMenuItem::new(snippet.name.clone()).on_click(
+                    move |_, window, cx| insert_at_cursor(&input_state, &text, window, cx),
+                ));
+            }
+            menu.separator().menu_with_icon(
+                "Manage Snippets...",
+                IconName::Settings,
+                Box::new(OpenSettings),
+            )
+        })
+        .anchor(Corner::BottomLeft)
+}
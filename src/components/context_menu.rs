@@ -0,0 +1,106 @@
+//! Right-click menus built from a list of actions
+//!
+//! Views describe what can be done with an item (a message, a task, a
+//! session) as [`ContextActions`] and attach them with
+//! `.context_menu(actions.into_builder())`, instead of rendering a button
+//! for every action.
+
+use std::rc::Rc;
+
+use gpui::{App, Context, SharedString, Window};
+use gpui_component::{
+    Icon,
+    menu::{PopupMenu, PopupMenuItem},
+};
+
+type ActionHandler = Rc<dyn Fn(&mut Window, &mut App)>;
+
+#[derive(Clone)]
+enum ContextEntry {
+    Action {
+        label: SharedString,
+        icon: Icon,
+        handler: ActionHandler,
+    },
+    Separator,
+}
+
+/// Actions of a right-click menu, in display order
+#[derive(Clone, Default)]
+pub struct ContextActions {
+    entries: Vec<ContextEntry>,
+}
+
+impl ContextActions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an action
+    pub fn action(
+        mut self,
+        label: impl Into<SharedString>,
+        icon: impl Into<Icon>,
+        handler: impl Fn(&mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.entries.push(ContextEntry::Action {
+            label: label.into(),
+            icon: icon.into(),
+            handler: Rc::new(handler),
+        });
+        self
+    }
+
+    /// Add a separator, unless it would start the menu or follow another one
+    pub fn separator(mut self) -> Self {
+        if matches!(self.entries.last(), Some(ContextEntry::Action { .. })) {
+            self.entries.push(ContextEntry::Separator);
+        }
+        self
+    }
+
+    /// Add actions only when `condition` holds
+    pub fn when(self, condition: bool, f: impl FnOnce(Self) -> Self) -> Self {
+        if condition { f(self) } else { self }
+    }
+
+    /// Add actions for `value` when it is set
+    pub fn when_some<T>(self, value: Option<T>, f: impl FnOnce(Self, T) -> Self) -> Self {
+        match value {
+            Some(value) => f(self, value),
+            None => self,
+        }
+    }
+
+    /// Append the actions to `menu`
+    pub fn build(&self, mut menu: PopupMenu) -> PopupMenu {
+        let count = self.entries.len();
+        for (ix, entry) in self.entries.iter().enumerate() {
+            match entry {
+                ContextEntry::Action {
+                    label,
+                    icon,
+                    handler,
+                } => {
+                    let handler = handler.clone();
+                    menu = menu.item(
+                        PopupMenuItem::new(label.clone())
+                            .icon(icon.clone())
+                            .on_click(move |_, window, cx| handler(window, cx)),
+                    );
+                }
+                // A trailing separator would end the menu with a line
+                ContextEntry::Separator if ix + 1 < count => menu = menu.separator(),
+                ContextEntry::Separator => {}
+            }
+        }
+        menu
+    }
+
+    /// Menu builder for `ContextMenuExt::context_menu`
+    pub fn into_builder(
+        self,
+    ) -> impl Fn(PopupMenu, &mut Window, &mut Context<PopupMenu>) -> PopupMenu + 'static {
+        move |menu, _, _| self.build(menu)
+    }
+}
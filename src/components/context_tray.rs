@@ -0,0 +1,254 @@
+use gpui::{
+    App, ElementId, InteractiveElement, IntoElement, ParentElement, RenderOnce, Styled, Window,
+    div, prelude::FluentBuilder, px,
+};
+use std::rc::Rc;
+
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Selectable, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+};
+
+use crate::utils::context_item::ContextItem;
+
+/// "Context" tray listing the items pinned to a conversation
+///
+/// Sits above the chat input. Each pinned item is shown as a chip that can be
+/// removed; the trailing buttons pin a file, a URL, or the code selections
+/// currently attached to the input. The file or selection open in the code
+/// editor is shown first as a toggle, since it is only attached while enabled.
+/// PDF chips switch between sending the extracted text and the PDF itself.
+#[derive(IntoElement)]
+pub struct ContextTray {
+    id: ElementId,
+    items: Vec<ContextItem>,
+    /// File or selection open in the code editor, and whether it is attached
+    editor_context: Option<(ContextItem, bool)>,
+    /// Number of code selections in the input that could be pinned
+    pinnable_selections: usize,
+    on_remove: Option<Rc<dyn Fn(&usize, &mut Window, &mut App) + 'static>>,
+    on_toggle_pdf: Option<Rc<dyn Fn(&usize, &mut Window, &mut App) + 'static>>,
+    on_add_file: Option<Rc<dyn Fn(&mut Window, &mut App) + 'static>>,
+    on_add_url: Option<Rc<dyn Fn(&mut Window, &mut App) + 'static>>,
+    on_pin_selections: Option<Rc<dyn Fn(&mut Window, &mut App) + 'static>>,
+    on_toggle_editor_context: Option<Rc<dyn Fn(&mut Window, &mut App) + 'static>>,
+}
+
+impl ContextTray {
+    pub fn new(id: impl Into<ElementId>, items: Vec<ContextItem>) -> Self {
+        Self {
+            id: id.into(),
+            items,
+            editor_context: None,
+            pinnable_selections: 0,
+            on_remove: None,
+            on_toggle_pdf: None,
+            on_add_file: None,
+            on_add_url: None,
+            on_pin_selections: None,
+            on_toggle_editor_context: None,
+        }
+    }
+
+    pub fn editor_context(mut self, item: Option<ContextItem>, enabled: bool) -> Self {
+        self.editor_context = item.map(|item| (item, enabled));
+        self
+    }
+
+    pub fn pinnable_selections(mut self, count: usize) -> Self {
+        self.pinnable_selections = count;
+        self
+    }
+
+    pub fn on_remove<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&usize, &mut Window, &mut App) + 'static,
+    {
+        self.on_remove = Some(Rc::new(callback));
+        self
+    }
+
+    /// Called with the index of a PDF to switch between its text and the PDF
+    pub fn on_toggle_pdf<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&usize, &mut Window, &mut App) + 'static,
+    {
+        self.on_toggle_pdf = Some(Rc::new(callback));
+        self
+    }
+
+    pub fn on_add_file<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut Window, &mut App) + 'static,
+    {
+        self.on_add_file = Some(Rc::new(callback));
+        self
+    }
+
+    pub fn on_add_url<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut Window, &mut App) + 'static,
+    {
+        self.on_add_url = Some(Rc::new(callback));
+        self
+    }
+
+    pub fn on_pin_selections<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut Window, &mut App) + 'static,
+    {
+        self.on_pin_selections = Some(Rc::new(callback));
+        self
+    }
+
+    pub fn on_toggle_editor_context<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut Window, &mut App) + 'static,
+    {
+        self.on_toggle_editor_context = Some(Rc::new(callback));
+        self
+    }
+}
+
+impl RenderOnce for ContextTray {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let theme = cx.theme();
+
+        let chips = self.items.iter().enumerate().map(|(idx, item)| {
+            let icon = match item {
+                ContextItem::File { .. } => IconName::File,
+                ContextItem::Selection { .. } => IconName::Frame,
+                ContextItem::Url { .. } => IconName::Globe,
+                ContextItem::McpResource { .. } => IconName::Inbox,
+                ContextItem::Pdf { .. } => IconName::BookOpen,
+                ContextItem::TableSample { .. } => IconName::LayoutDashboard,
+            };
+            let on_remove = self.on_remove.clone();
+            let on_toggle_pdf = self.on_toggle_pdf.clone();
+            let pdf_mode = match item {
+                ContextItem::Pdf { send_blob, .. } => Some(*send_blob),
+                _ => None,
+            };
+
+            h_flex()
+                .gap_1()
+                .items_center()
+                .py_0p5()
+                .px_1p5()
+                .rounded(px(6.))
+                .bg(theme.secondary)
+                .border_1()
+                .border_color(theme.border)
+                .child(
+                    Icon::new(icon)
+                        .size(px(13.))
+                        .text_color(theme.muted_foreground),
+                )
+                .child(
+                    div()
+                        .max_w(px(220.))
+                        .overflow_hidden()
+                        .text_ellipsis()
+                        .whitespace_nowrap()
+                        .text_size(px(11.5))
+                        .text_color(theme.foreground.opacity(0.85))
+                        .child(item.label()),
+                )
+                .when_some(pdf_mode, |this, send_blob| {
+                    this.child(
+                        Button::new(("context-pdf-mode", idx))
+                            .label(if send_blob { "PDF" } else { "Text" })
+                            .ghost()
+                            .xsmall()
+                            .tooltip(if send_blob {
+                                "Sending the PDF itself. Click to send its extracted text."
+                            } else {
+                                "Sending the extracted text. Click to send the PDF itself."
+                            })
+                            .when_some(on_toggle_pdf, |btn, callback| {
+                                btn.on_click(move |_, window, cx| callback(&idx, window, cx))
+                            }),
+                    )
+                })
+                .child(
+                    Button::new(("context-remove", idx))
+                        .icon(Icon::new(IconName::Close))
+                        .ghost()
+                        .xsmall()
+                        .when_some(on_remove, |btn, callback| {
+                            btn.on_click(move |_, window, cx| callback(&idx, window, cx))
+                        }),
+                )
+        });
+
+        let action_button = |name: &'static str,
+                             icon: IconName,
+                             label: String,
+                             callback: Option<Rc<dyn Fn(&mut Window, &mut App)>>| {
+            Button::new(name)
+                .icon(Icon::new(icon))
+                .label(label)
+                .ghost()
+                .xsmall()
+                .when_some(callback, |btn, callback| {
+                    btn.on_click(move |_, window, cx| callback(window, cx))
+                })
+        };
+
+        h_flex()
+            .id(self.id)
+            .w_full()
+            .flex_wrap()
+            .gap_1p5()
+            .items_center()
+            .px_1()
+            .child(
+                div()
+                    .text_xs()
+                    .font_semibold()
+                    .text_color(theme.muted_foreground)
+                    .child("Context"),
+            )
+            .when_some(self.editor_context, |this, (item, enabled)| {
+                let on_toggle = self.on_toggle_editor_context;
+                this.child(
+                    Button::new("context-editor")
+                        .icon(Icon::new(IconName::Eye))
+                        .label(item.label())
+                        .ghost()
+                        .xsmall()
+                        .selected(enabled)
+                        .tooltip(if enabled {
+                            "Attached from the editor. Click to stop attaching."
+                        } else {
+                            "Open in the editor. Click to attach it to prompts."
+                        })
+                        .when_some(on_toggle, |btn, callback| {
+                            btn.on_click(move |_, window, cx| callback(window, cx))
+                        }),
+                )
+            })
+            .children(chips)
+            .child(action_button(
+                "context-add-file",
+                IconName::Plus,
+                "File".to_string(),
+                self.on_add_file,
+            ))
+            .child(action_button(
+                "context-add-url",
+                IconName::Plus,
+                "URL".to_string(),
+                self.on_add_url,
+            ))
+            .when(self.pinnable_selections > 0, |this| {
+                this.child(action_button(
+                    "context-pin-selections",
+                    IconName::Frame,
+                    format!("Pin selection ({})", self.pinnable_selections),
+                    self.on_pin_selections,
+                ))
+            })
+    }
+}
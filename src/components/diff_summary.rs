@@ -4,12 +4,13 @@ use gpui_component::{
     button::{Button, ButtonVariants},
     h_flex, v_flex,
 };
-use similar::{ChangeTag, TextDiff};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
 use agent_client_protocol::{Diff, ToolCall, ToolCallContent, ToolCallId, ToolCallStatus};
 
+use crate::core::diff::diff_stats;
+
 /// Statistics for a single file's changes
 #[derive(Debug, Clone, Default)]
 pub struct FileChangeStats {
@@ -22,27 +23,12 @@ pub struct FileChangeStats {
 impl FileChangeStats {
     /// Calculate statistics from old and new text
     pub fn from_diff(path: PathBuf, old_text: Option<&str>, new_text: &str) -> Self {
-        let (additions, deletions, is_new_file) = match old_text {
-            Some(old) => {
-                let diff = TextDiff::from_lines(old, new_text);
-                let (mut adds, mut dels) = (0, 0);
-                for change in diff.iter_all_changes() {
-                    match change.tag() {
-                        ChangeTag::Insert => adds += 1,
-                        ChangeTag::Delete => dels += 1,
-                        ChangeTag::Equal => {}
-                    }
-                }
-                (adds, dels, false)
-            }
-            None => (new_text.lines().count(), 0, true),
-        };
-
+        let stats = diff_stats(old_text, new_text);
         Self {
             path,
-            additions,
-            deletions,
-            is_new_file,
+            additions: stats.additions,
+            deletions: stats.deletions,
+            is_new_file: old_text.is_none(),
         }
     }
 
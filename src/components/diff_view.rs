@@ -1,38 +1,11 @@
 use gpui::prelude::FluentBuilder;
 use gpui::{AnyElement, App, IntoElement, ParentElement, RenderOnce, Styled, Window, div, px};
 use gpui_component::{ActiveTheme, Icon, IconName, h_flex, v_flex};
-use similar::{ChangeTag, TextDiff};
 use std::path::PathBuf;
 
 use agent_client_protocol::Diff;
 
-/// Represents a single line in a diff view
-#[derive(Debug, Clone)]
-pub enum DiffLine {
-    /// Unchanged line (context)
-    Context {
-        line: String,
-        old_num: usize,
-        new_num: usize,
-    },
-    /// Line added in new version
-    Insert { line: String, new_num: usize },
-    /// Line deleted from old version
-    Delete { line: String, old_num: usize },
-}
-
-/// Represents a display item in the diff view (can be a line or a collapsed section)
-#[derive(Debug, Clone)]
-pub enum DiffDisplayItem {
-    /// A regular diff line
-    Line(DiffLine),
-    /// A collapsed section of unchanged lines
-    Collapsed {
-        start_old: usize,
-        start_new: usize,
-        count: usize,
-    },
-}
+use crate::core::diff::{DiffDisplayItem, DiffLine, collapse_context, diff_lines};
 
 /// Configuration for DiffView rendering
 #[derive(Debug, Clone)]
@@ -111,173 +84,6 @@ impl DiffView {
         self
     }
 
-    /// Compute line-by-line diff using similar crate
-    fn compute_diff(&self, old_text: &str, new_text: &str) -> Vec<DiffLine> {
-        let diff = TextDiff::from_lines(old_text, new_text);
-        let mut result = Vec::new();
-        let mut old_line_num = 1;
-        let mut new_line_num = 1;
-
-        for change in diff.iter_all_changes() {
-            // Remove trailing newlines
-            let line = change.value().trim_end_matches('\n').to_string();
-
-            match change.tag() {
-                ChangeTag::Equal => {
-                    result.push(DiffLine::Context {
-                        line,
-                        old_num: old_line_num,
-                        new_num: new_line_num,
-                    });
-                    old_line_num += 1;
-                    new_line_num += 1;
-                }
-                ChangeTag::Delete => {
-                    result.push(DiffLine::Delete {
-                        line,
-                        old_num: old_line_num,
-                    });
-                    old_line_num += 1;
-                }
-                ChangeTag::Insert => {
-                    result.push(DiffLine::Insert {
-                        line,
-                        new_num: new_line_num,
-                    });
-                    new_line_num += 1;
-                }
-            }
-        }
-
-        result
-    }
-
-    /// Apply context collapsing to diff lines
-    /// Only show changed lines with N lines of context before/after
-    fn apply_context_collapsing(&self, diff_lines: Vec<DiffLine>) -> Vec<DiffDisplayItem> {
-        let context_lines = self.config.context_lines;
-        let min_collapse_size = context_lines * 2 + 1; // Minimum lines to collapse
-
-        let mut display_items: Vec<DiffDisplayItem> = Vec::new();
-        let mut context_buffer: Vec<DiffLine> = Vec::new();
-        let mut last_change_index: Option<usize> = None;
-
-        for (i, line) in diff_lines.iter().enumerate() {
-            match line {
-                DiffLine::Context { .. } => {
-                    // Accumulate context lines
-                    context_buffer.push(line.clone());
-                }
-                DiffLine::Insert { .. } | DiffLine::Delete { .. } => {
-                    // Found a change - process buffered context
-                    if !context_buffer.is_empty() {
-                        if let Some(last_idx) = last_change_index {
-                            // There was a previous change
-                            let distance = i - last_idx - 1;
-
-                            if distance >= min_collapse_size {
-                                // Show context_lines after previous change
-                                for ctx in context_buffer.iter().take(context_lines) {
-                                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                                }
-
-                                // Collapse the middle
-                                let collapsed_count = distance - context_lines * 2;
-                                if collapsed_count > 0 {
-                                    if let DiffLine::Context {
-                                        old_num, new_num, ..
-                                    } = &context_buffer[context_lines]
-                                    {
-                                        display_items.push(DiffDisplayItem::Collapsed {
-                                            start_old: *old_num,
-                                            start_new: *new_num,
-                                            count: collapsed_count,
-                                        });
-                                    }
-                                }
-
-                                // Show context_lines before current change
-                                let start = context_buffer.len().saturating_sub(context_lines);
-                                for ctx in context_buffer.iter().skip(start) {
-                                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                                }
-                            } else {
-                                // Distance is small, show all context
-                                for ctx in &context_buffer {
-                                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                                }
-                            }
-                        } else {
-                            // This is the first change
-                            if context_buffer.len() > context_lines {
-                                // Collapse leading context, only show last context_lines
-                                if self.config.show_edge_collapsed {
-                                    let collapsed_count = context_buffer.len() - context_lines;
-                                    if let DiffLine::Context {
-                                        old_num, new_num, ..
-                                    } = &context_buffer[0]
-                                    {
-                                        display_items.push(DiffDisplayItem::Collapsed {
-                                            start_old: *old_num,
-                                            start_new: *new_num,
-                                            count: collapsed_count,
-                                        });
-                                    }
-                                }
-
-                                let start = context_buffer.len() - context_lines;
-                                for ctx in context_buffer.iter().skip(start) {
-                                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                                }
-                            } else {
-                                // Show all leading context
-                                for ctx in &context_buffer {
-                                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                                }
-                            }
-                        }
-
-                        context_buffer.clear();
-                    }
-
-                    // Add the change line
-                    display_items.push(DiffDisplayItem::Line(line.clone()));
-                    last_change_index = Some(i);
-                }
-            }
-        }
-
-        // Handle trailing context
-        if !context_buffer.is_empty() {
-            if context_buffer.len() > context_lines {
-                // Show first context_lines, collapse the rest
-                for ctx in context_buffer.iter().take(context_lines) {
-                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                }
-                if self.config.show_edge_collapsed {
-                    let collapsed_count = context_buffer.len() - context_lines;
-                    if let DiffLine::Context {
-                        old_num, new_num, ..
-                    } = &context_buffer[context_lines]
-                    {
-                        display_items.push(DiffDisplayItem::Collapsed {
-                            start_old: *old_num,
-                            start_new: *new_num,
-                            count: collapsed_count,
-                        });
-                    }
-                }
-            } else {
-                // Show all trailing context
-                for ctx in &context_buffer {
-                    display_items.push(DiffDisplayItem::Line(ctx.clone()));
-                }
-            }
-        }
-
-        display_items
-    }
-
     /// Render a single diff line
     fn render_diff_line<'a>(
         &self,
@@ -478,31 +284,13 @@ impl DiffView {
 
 impl RenderOnce for DiffView {
     fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
-        // Compute diff
-        let diff_lines = match &self.diff.old_text {
-            Some(old_text) => {
-                if old_text == &self.diff.new_text {
-                    Vec::new() // No changes
-                } else {
-                    self.compute_diff(old_text, &self.diff.new_text)
-                }
-            }
-            None => {
-                // New file - all lines are insertions
-                self.diff
-                    .new_text
-                    .lines()
-                    .enumerate()
-                    .map(|(i, line)| DiffLine::Insert {
-                        line: line.to_string(),
-                        new_num: i + 1,
-                    })
-                    .collect()
-            }
-        };
-
-        // Apply context collapsing to show only changed parts + context
-        let display_items = self.apply_context_collapsing(diff_lines);
+        // Compute diff, then only show changed parts + context
+        let lines = diff_lines(self.diff.old_text.as_deref(), &self.diff.new_text);
+        let display_items = collapse_context(
+            &lines,
+            self.config.context_lines,
+            self.config.show_edge_collapsed,
+        );
 
         let total_lines = display_items.len();
         let truncated = total_lines > self.config.max_lines;
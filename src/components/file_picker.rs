@@ -6,6 +6,8 @@ use gpui_component::{
 use std::path::{Path, PathBuf};
 use tokio::sync::mpsc;
 
+use crate::utils::path_filter::PathFilter;
+
 /// File item in the file picker
 #[derive(Clone, Debug)]
 pub struct FileItem {
@@ -44,8 +46,8 @@ pub struct FilePickerDelegate {
 }
 
 impl FilePickerDelegate {
-    pub fn new(root_path: &Path) -> Self {
-        let all_items = Self::scan_directory(root_path, root_path);
+    pub fn new(filter: &PathFilter) -> Self {
+        let all_items = Self::scan_directory(filter, filter.root());
 
         Self {
             filtered_items: all_items.clone(),
@@ -75,21 +77,9 @@ impl FilePickerDelegate {
     }
 
     /// Scan directory recursively and return all files and folders
-    fn scan_directory(path: &Path, base_path: &Path) -> Vec<FileItem> {
+    fn scan_directory(filter: &PathFilter, path: &Path) -> Vec<FileItem> {
         let mut items = Vec::new();
-
-        // Skip common ignore patterns
-        let ignore_patterns = [
-            "node_modules",
-            ".git",
-            "target",
-            ".next",
-            ".cache",
-            "dist",
-            "build",
-            ".vscode",
-            ".idea",
-        ];
+        let base_path = filter.root();
 
         if let Ok(entries) = std::fs::read_dir(path) {
             for entry in entries.flatten() {
@@ -100,8 +90,8 @@ impl FilePickerDelegate {
                     .unwrap_or("")
                     .to_string();
 
-                // Skip ignored directories
-                if ignore_patterns.contains(&file_name.as_str()) {
+                // Skip .gitignore'd and excluded paths
+                if filter.is_excluded(&entry_path) {
                     continue;
                 }
 
@@ -124,7 +114,7 @@ impl FilePickerDelegate {
                         .unwrap_or(0);
 
                     if depth < 3 {
-                        items.extend(Self::scan_directory(&entry_path, base_path));
+                        items.extend(Self::scan_directory(filter, &entry_path));
                     }
                 }
             }
@@ -70,6 +70,10 @@ pub struct InputSuggestionState<T: InputSuggestionItem + 'static> {
     on_confirm: Option<Rc<dyn Fn(&T, &mut Window, &mut App)>>,
     on_open_change: Option<Rc<dyn Fn(&bool, &mut Window, &mut App)>>,
     on_select_change: Option<Rc<dyn Fn(Option<usize>, &mut Window, &mut App)>>,
+    /// Called on plain Enter while the list is closed (Shift+Enter still inserts a newline)
+    on_submit: Option<Rc<dyn Fn(&mut Window, &mut App)>>,
+    /// Submit on Ctrl/Cmd+Enter instead, leaving plain Enter to the input
+    submit_with_modifier: bool,
     _subscriptions: Vec<Subscription>,
     _keystroke_subscription: Option<Subscription>,
 }
@@ -119,6 +123,8 @@ impl<T: InputSuggestionItem + 'static> InputSuggestionState<T> {
             on_confirm: None,
             on_open_change: None,
             on_select_change: None,
+            on_submit: None,
+            submit_with_modifier: false,
             _subscriptions,
             _keystroke_subscription,
         }
@@ -185,6 +191,14 @@ impl<T: InputSuggestionItem + 'static> InputSuggestionState<T> {
         self.on_select_change = on_select_change;
     }
 
+    pub fn set_on_submit(&mut self, on_submit: Option<Rc<dyn Fn(&mut Window, &mut App)>>) {
+        self.on_submit = on_submit;
+    }
+
+    pub fn set_submit_with_modifier(&mut self, submit_with_modifier: bool) {
+        self.submit_with_modifier = submit_with_modifier;
+    }
+
     pub fn set_selected_index(
         &mut self,
         index: Option<usize>,
@@ -231,7 +245,7 @@ impl<T: InputSuggestionItem + 'static> InputSuggestionState<T> {
         cx: &mut Context<Self>,
     ) -> bool {
         if !self.open || !self.enabled {
-            return false;
+            return self.handle_submit(event, window, cx);
         }
         if event.keystroke.modifiers.modified() {
             return false;
@@ -245,11 +259,39 @@ impl<T: InputSuggestionItem + 'static> InputSuggestionState<T> {
                 self.move_selection(1, window, cx);
                 true
             }
-            "enter" => self.confirm_selected(window, cx),
+            "enter" | "tab" => self.confirm_selected(window, cx),
             _ => false,
         }
     }
 
+    /// Enter (or Ctrl/Cmd+Enter) submits; other combinations fall through so the
+    /// input inserts a newline
+    fn handle_submit(
+        &mut self,
+        event: &KeystrokeEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if event.keystroke.key != "enter" {
+            return false;
+        }
+        let modifiers = &event.keystroke.modifiers;
+        let submits = if self.submit_with_modifier {
+            modifiers.secondary() && !modifiers.shift && !modifiers.alt
+        } else {
+            !modifiers.modified()
+        };
+        if !submits {
+            return false;
+        }
+        let Some(on_submit) = self.on_submit.clone() else {
+            return false;
+        };
+        // Run after the current keystroke dispatch so the callback can update the input
+        window.defer(cx, move |window, cx| on_submit(window, cx));
+        true
+    }
+
     fn on_input_event(
         &mut self,
         _: &Entity<InputState>,
@@ -390,6 +432,8 @@ pub struct InputSuggestion<T: InputSuggestionItem + 'static> {
     on_confirm: Option<Rc<dyn Fn(&T, &mut Window, &mut App)>>,
     on_open_change: Option<Rc<dyn Fn(&bool, &mut Window, &mut App)>>,
     on_select_change: Option<Rc<dyn Fn(Option<usize>, &mut Window, &mut App)>>,
+    on_submit: Option<Rc<dyn Fn(&mut Window, &mut App)>>,
+    submit_with_modifier: bool,
     input_builder: Option<Rc<dyn Fn(&Entity<InputState>) -> Input>>,
     render_item: Option<Rc<dyn Fn(&T, bool, &mut Window, &mut App) -> gpui::AnyElement>>,
     style: StyleRefinement,
@@ -413,6 +457,8 @@ impl<T: InputSuggestionItem + 'static> InputSuggestion<T> {
             on_confirm: None,
             on_open_change: None,
             on_select_change: None,
+            on_submit: None,
+            submit_with_modifier: false,
             input_builder: None,
             render_item: None,
             style: StyleRefinement::default(),
@@ -501,6 +547,18 @@ impl<T: InputSuggestionItem + 'static> InputSuggestion<T> {
         self
     }
 
+    /// Submit on Enter when no suggestion is open
+    pub fn on_submit(mut self, callback: Rc<dyn Fn(&mut Window, &mut App)>) -> Self {
+        self.on_submit = Some(callback);
+        self
+    }
+
+    /// Require Ctrl/Cmd+Enter to submit, so plain Enter inserts a newline
+    pub fn submit_with_modifier(mut self, submit_with_modifier: bool) -> Self {
+        self.submit_with_modifier = submit_with_modifier;
+        self
+    }
+
     pub fn input<F>(mut self, builder: F) -> Self
     where
         F: Fn(&Entity<InputState>) -> Input + 'static,
@@ -550,6 +608,8 @@ impl<T: InputSuggestionItem + 'static> RenderOnce for InputSuggestion<T> {
         let on_confirm = self.on_confirm.clone();
         let on_open_change = self.on_open_change.clone();
         let on_select_change = self.on_select_change.clone();
+        let on_submit = self.on_submit.clone();
+        let submit_with_modifier = self.submit_with_modifier;
         let clear_on_confirm = self.clear_on_confirm;
         let apply_on_confirm = self.apply_on_confirm;
 
@@ -566,6 +626,8 @@ impl<T: InputSuggestionItem + 'static> RenderOnce for InputSuggestion<T> {
                 on_open_change,
                 on_select_change,
             );
+            state.set_on_submit(on_submit);
+            state.set_submit_with_modifier(submit_with_modifier);
             state.set_clear_on_confirm(clear_on_confirm);
             state.set_apply_on_confirm(apply_on_confirm);
         });
@@ -0,0 +1,213 @@
+//! JSON tree viewer with expand/collapse, key search and copy-path
+//!
+//! Replaces pretty-printed JSON strings for structured values such as tool
+//! call inputs and outputs.
+
+use std::collections::HashSet;
+
+use gpui::{
+    ClipboardItem, Context, Entity, InteractiveElement as _, IntoElement, MouseButton,
+    ParentElement, Render, SharedString, StatefulInteractiveElement as _, Styled, Subscription,
+    Window, div, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    notification::Notification,
+    v_flex,
+};
+use rust_i18n::t;
+use serde_json::Value;
+
+use crate::app::focus_mode::NotificationExt as _;
+use crate::utils::json_tree::{JsonKey, JsonKind, JsonRow, visible_rows};
+
+/// Indentation per nesting level
+const INDENT: f32 = 14.;
+
+pub struct JsonTree {
+    id: SharedString,
+    value: Value,
+    /// Paths expanded or collapsed by the user, see [`visible_rows`]
+    toggled: HashSet<String>,
+    search_state: Entity<InputState>,
+    query: String,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl JsonTree {
+    pub fn new(
+        id: impl Into<SharedString>,
+        value: Value,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let search_state = cx
+            .new(|cx| InputState::new(window, cx).placeholder(t!("json_tree.search").to_string()));
+        let search_subscription =
+            cx.subscribe(&search_state, |this, input, event: &InputEvent, cx| {
+                if let InputEvent::Change = event {
+                    this.query = input.read(cx).value().to_string();
+                    // Toggles made for one search do not fit another
+                    this.toggled.clear();
+                    cx.notify();
+                }
+            });
+
+        Self {
+            id: id.into(),
+            value,
+            toggled: HashSet::new(),
+            search_state,
+            query: String::new(),
+            _subscriptions: vec![search_subscription],
+        }
+    }
+
+    /// Show another value, keeping the nodes the user expanded or collapsed
+    pub fn set_value(&mut self, value: Value, cx: &mut Context<Self>) {
+        if self.value != value {
+            self.value = value;
+            cx.notify();
+        }
+    }
+
+    fn toggle(&mut self, path: &str, cx: &mut Context<Self>) {
+        if !self.toggled.remove(path) {
+            self.toggled.insert(path.to_string());
+        }
+        cx.notify();
+    }
+
+    fn copy_path(path: &str, window: &mut Window, cx: &mut gpui::App) {
+        cx.write_to_clipboard(ClipboardItem::new_string(path.to_string()));
+        struct JsonPathCopied;
+        let note = Notification::success(t!("json_tree.path_copied", path = path).to_string());
+        window.show_notification(note.id::<JsonPathCopied>(), cx);
+    }
+
+    fn render_row(&self, ix: usize, row: JsonRow, cx: &mut Context<Self>) -> impl IntoElement {
+        let expandable = matches!(row.kind, JsonKind::Object(n) | JsonKind::Array(n) if n > 0);
+        let key = match &row.key {
+            Some(JsonKey::Name(name)) => Some(format!("{}:", name)),
+            Some(JsonKey::Index(index)) => Some(format!("{}:", index)),
+            None => None,
+        };
+        let (value, color) = match row.kind {
+            JsonKind::Object(count) => (
+                t!("json_tree.keys", count = count).to_string(),
+                cx.theme().muted_foreground,
+            ),
+            JsonKind::Array(count) => (
+                t!("json_tree.items", count = count).to_string(),
+                cx.theme().muted_foreground,
+            ),
+            JsonKind::String => (row.preview, cx.theme().green),
+            JsonKind::Number | JsonKind::Bool => (row.preview, cx.theme().yellow),
+            JsonKind::Null => (row.preview, cx.theme().muted_foreground),
+        };
+        let chevron = if row.expanded {
+            IconName::ChevronDown
+        } else {
+            IconName::ChevronRight
+        };
+        let path = row.path;
+
+        h_flex()
+            .id(("json-row", ix))
+            .group("json-row")
+            .w_full()
+            .gap_1()
+            .pl(px(INDENT * row.depth as f32))
+            .rounded(px(3.))
+            .hover(|this| this.bg(cx.theme().muted.opacity(0.3)))
+            .child(div().w(px(14.)).flex_shrink_0().when(expandable, |this| {
+                this.child(
+                    Icon::new(chevron)
+                        .size(px(12.))
+                        .text_color(cx.theme().muted_foreground),
+                )
+            }))
+            .when_some(key, |this, key| {
+                this.child(
+                    div()
+                        .flex_shrink_0()
+                        .text_color(cx.theme().accent)
+                        .when(row.matched, |this| this.bg(cx.theme().yellow.opacity(0.3)))
+                        .child(key),
+                )
+            })
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .text_color(color)
+                    .child(value),
+            )
+            .child(
+                div()
+                    .invisible()
+                    .group_hover("json-row", |this| this.visible())
+                    // Copying does not toggle the row
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .child(
+                        Button::new(("copy-json-path", ix))
+                            .icon(IconName::Copy)
+                            .ghost()
+                            .xsmall()
+                            .tooltip(t!("json_tree.copy_path", path = path).to_string())
+                            .on_click({
+                                let path = path.clone();
+                                move |_, window, cx| Self::copy_path(&path, window, cx)
+                            }),
+                    ),
+            )
+            .when(expandable, |this| {
+                this.cursor_pointer()
+                    .on_click(cx.listener(move |this, _, _window, cx| this.toggle(&path, cx)))
+            })
+    }
+}
+
+impl Render for JsonTree {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let rows = visible_rows(&self.value, &self.toggled, &self.query);
+        let empty = rows.is_empty();
+        let rows: Vec<_> = rows
+            .into_iter()
+            .enumerate()
+            .map(|(ix, row)| self.render_row(ix, row, cx))
+            .collect();
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(Input::new(&self.search_state).small().cleanable(true))
+            .child(
+                v_flex()
+                    .id(self.id.clone())
+                    .w_full()
+                    .p_2()
+                    .rounded(cx.theme().radius)
+                    .bg(cx.theme().secondary)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .text_size(px(12.))
+                    .font_family(cx.theme().mono_font_family.clone())
+                    .line_height(px(20.))
+                    .children(rows)
+                    .when(empty, |this| {
+                        this.child(
+                            div()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(t!("json_tree.no_match").to_string()),
+                        )
+                    }),
+            )
+    }
+}
@@ -1,19 +1,26 @@
+mod agent_avatar;
 mod agent_message;
 mod agent_select;
 mod agent_todo_list;
 mod chat_input_box;
 mod command_suggestions_popover;
+mod context_menu;
+mod context_tray;
 mod diff_summary;
 mod diff_view;
 mod file_picker;
 mod input_suggestion;
+mod json_tree;
 mod permission_request;
 mod select_items;
+mod split_diff_view;
 mod status_indicator;
 // mod task_list_item;
 mod tool_call_item;
 mod user_message;
 
+pub use agent_avatar::AgentAvatar;
+
 pub use agent_message::{AgentMessage, AgentMessageData, AgentMessageMeta, AgentMessageView};
 
 pub use agent_select::AgentItem;
@@ -22,6 +29,10 @@ pub use agent_todo_list::{AgentTodoList, AgentTodoListView, PlanMeta};
 
 pub use chat_input_box::ChatInputBox;
 
+pub use context_menu::ContextActions;
+
+pub use context_tray::ContextTray;
+
 pub use diff_summary::{DiffSummary, DiffSummaryData, FileChangeStats};
 
 pub use diff_view::DiffView;
@@ -30,6 +41,10 @@ pub use input_suggestion::{InputSuggestion, InputSuggestionItem, InputSuggestion
 
 pub use file_picker::{FileItem, FilePickerDelegate};
 
+pub use json_tree::JsonTree;
+
+pub use split_diff_view::SplitDiffView;
+
 pub use tool_call_item::{ToolCallItem, ToolCallItemView};
 
 pub use user_message::{UserMessage, UserMessageData, UserMessageView};
@@ -10,7 +10,7 @@ use gpui_component::{
     h_flex, v_flex,
 };
 
-use crate::AppState;
+use crate::{AppState, core::agent::command_policy::CommandRefusal};
 
 pub fn permission_option_kind_to_icon(kind: PermissionOptionKind) -> IconName {
     match kind {
@@ -43,6 +43,8 @@ pub struct PermissionRequest {
     options: Vec<PermissionOption>,
     /// Whether the request has been responded to
     responded: bool,
+    /// Set when the command policy refused the tool call instead of asking
+    refusal: Option<CommandRefusal>,
 }
 
 impl PermissionRequest {
@@ -66,9 +68,17 @@ impl PermissionRequest {
             tool_kind,
             options: options.into_iter().collect(),
             responded: false,
+            refusal: None,
         }
     }
 
+    /// Show the request as refused by the command policy, with nothing to answer
+    pub fn refused(mut self, refusal: Option<CommandRefusal>) -> Self {
+        self.responded |= refusal.is_some();
+        self.refusal = refusal;
+        self
+    }
+
     /// Handle user selection of a permission option
     fn on_option_selected(
         &mut self,
@@ -114,10 +124,36 @@ impl PermissionRequest {
             log::error!("PermissionStore not available in AppState");
         }
     }
+
+    /// Approve the request and later ones of the same tool kind in the session
+    fn on_allow_for_session(&mut self, cx: &mut Context<Self>) {
+        if self.responded {
+            return;
+        }
+        let Some(agent_service) = AppState::global(cx).agent_service().cloned() else {
+            log::error!("AgentService not available in AppState");
+            return;
+        };
+        let permission_id = self.permission_id.clone();
+        cx.spawn(async move |_entity, _cx| {
+            if let Err(e) = agent_service
+                .answer_permission(&permission_id, true, true)
+                .await
+            {
+                log::error!("Failed to send permission response: {}", e);
+            }
+        })
+        .detach();
+        self.responded = true;
+        cx.notify();
+    }
 }
 
 impl Render for PermissionRequest {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if let Some(refusal) = &self.refusal {
+            return render_refusal(refusal, cx).into_any_element();
+        }
         let responded = self.responded;
 
         v_flex()
@@ -207,12 +243,81 @@ impl Render for PermissionRequest {
                                     this.on_option_selected(option_id.to_string(), window, cx);
                                 },
                             ))
-                        })),
+                        }))
+                        .when(
+                            self.options
+                                .iter()
+                                .any(|option| permission_is_allow(option.kind)),
+                            |this| {
+                                this.child(
+                                    Button::new(SharedString::from(format!(
+                                        "permission-{}-session",
+                                        self.permission_id
+                                    )))
+                                    .label("Always Allow in This Session")
+                                    .icon(IconName::CircleCheck)
+                                    .ghost()
+                                    .small()
+                                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                                        this.on_allow_for_session(cx);
+                                    })),
+                                )
+                            },
+                        ),
                 )
             })
+            .into_any_element()
     }
 }
 
+/// Warning card for a command the command policy refused
+fn render_refusal(refusal: &CommandRefusal, cx: &App) -> impl IntoElement {
+    let theme = cx.theme();
+    v_flex()
+        .w_full()
+        .gap_2()
+        .p_3()
+        .rounded(theme.radius)
+        .border_1()
+        .border_color(theme.danger)
+        .bg(theme.danger.opacity(0.08))
+        .child(
+            h_flex()
+                .items_center()
+                .gap_2()
+                .child(
+                    Icon::new(IconName::TriangleAlert)
+                        .size(px(16.))
+                        .text_color(theme.danger),
+                )
+                .child(
+                    div()
+                        .text_size(px(13.))
+                        .font_weight(gpui::FontWeight::SEMIBOLD)
+                        .text_color(theme.foreground)
+                        .child("Command Blocked"),
+                ),
+        )
+        .child(
+            v_flex()
+                .gap_1()
+                .pl_6()
+                .child(
+                    div()
+                        .text_size(px(12.))
+                        .font_family("Monaco, 'Courier New', monospace")
+                        .text_color(theme.foreground)
+                        .child(refusal.command.clone()),
+                )
+                .child(
+                    div()
+                        .text_size(px(12.))
+                        .text_color(theme.muted_foreground)
+                        .child(refusal.message()),
+                ),
+        )
+}
+
 /// View wrapper for PermissionRequest
 pub struct PermissionRequestView {
     pub(crate) item: Entity<PermissionRequest>,
@@ -0,0 +1,155 @@
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, App, Hsla, IntoElement, ParentElement, RenderOnce, Styled, Window, div, px,
+};
+use gpui_component::{ActiveTheme, h_flex, v_flex};
+
+use agent_client_protocol::Diff;
+
+use crate::core::diff::{SplitCell, SplitRow, collapse_context, diff_lines, split_rows};
+
+/// A side-by-side diff: the old text on the left, the new one on the right,
+/// with removed and added lines facing each other
+#[derive(IntoElement)]
+pub struct SplitDiffView {
+    diff: Diff,
+    /// Maximum number of rows to display
+    max_lines: usize,
+    /// Number of unchanged lines shown around changes
+    context_lines: usize,
+}
+
+impl SplitDiffView {
+    pub fn new(diff: Diff) -> Self {
+        Self {
+            diff,
+            max_lines: 5000,
+            context_lines: 5,
+        }
+    }
+
+    /// Set maximum number of rows to display
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines;
+        self
+    }
+
+    /// Set number of context lines to show before/after changes
+    pub fn context_lines(mut self, context_lines: usize) -> Self {
+        self.context_lines = context_lines;
+        self
+    }
+
+    /// One side of a row; `color` marks a removed or added line
+    fn render_cell(cell: Option<&SplitCell>, color: Option<Hsla>, cx: &App) -> AnyElement {
+        let theme = cx.theme();
+        let Some(cell) = cell else {
+            return div()
+                .flex_1()
+                .min_w(px(0.))
+                .bg(theme.muted.opacity(0.3))
+                .into_any_element();
+        };
+        h_flex()
+            .flex_1()
+            .min_w(px(0.))
+            .overflow_hidden()
+            .when_some(color, |this, color| {
+                this.bg(color.opacity(0.1)).border_l_2().border_color(color)
+            })
+            .child(
+                div()
+                    .min_w(px(40.))
+                    .px_2()
+                    .text_color(color.unwrap_or(theme.muted_foreground))
+                    .child(format!("{:>4}", cell.num)),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .px_2()
+                    .text_color(color.unwrap_or(theme.foreground))
+                    .child(cell.line.clone()),
+            )
+            .into_any_element()
+    }
+
+    fn render_row(row: &SplitRow, cx: &App) -> AnyElement {
+        let theme = cx.theme();
+        match row {
+            SplitRow::Line { old, new, changed } => {
+                let (old_color, new_color) = if *changed {
+                    (Some(theme.red), Some(theme.green))
+                } else {
+                    (None, None)
+                };
+                h_flex()
+                    .w_full()
+                    .font_family("Monaco, 'Courier New', monospace")
+                    .text_size(px(12.))
+                    .line_height(px(18.))
+                    .child(Self::render_cell(old.as_ref(), old_color, cx))
+                    .child(div().w(px(1.)).h_full().bg(theme.border))
+                    .child(Self::render_cell(new.as_ref(), new_color, cx))
+                    .into_any_element()
+            }
+            SplitRow::Collapsed {
+                start_old,
+                start_new,
+                count,
+            } => h_flex()
+                .w_full()
+                .justify_center()
+                .bg(theme.muted.opacity(0.3))
+                .border_y_1()
+                .border_color(theme.border)
+                .child(
+                    div()
+                        .text_size(px(11.))
+                        .text_color(theme.muted_foreground)
+                        .child(format!(
+                            "⋯ {} unchanged lines hidden ({}..{}, {}..{}) ⋯",
+                            count,
+                            start_old,
+                            start_old + count - 1,
+                            start_new,
+                            start_new + count - 1
+                        )),
+                )
+                .into_any_element(),
+        }
+    }
+}
+
+impl RenderOnce for SplitDiffView {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let lines = diff_lines(self.diff.old_text.as_deref(), &self.diff.new_text);
+        let rows = split_rows(&collapse_context(&lines, self.context_lines, false));
+
+        div()
+            .w_full()
+            .rounded(cx.theme().radius)
+            .bg(cx.theme().secondary)
+            .overflow_hidden()
+            .child(
+                v_flex()
+                    .w_full()
+                    .when(rows.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .p_4()
+                                .flex()
+                                .justify_center()
+                                .text_color(cx.theme().muted_foreground)
+                                .text_size(px(12.))
+                                .child("No changes"),
+                        )
+                    })
+                    .children(
+                        rows.iter()
+                            .take(self.max_lines)
+                            .map(|row| Self::render_row(row, cx)),
+                    ),
+            )
+    }
+}
@@ -1,6 +1,7 @@
 use gpui::{
-    AnyElement, App, AppContext, Context, Entity, IntoElement, ParentElement, Render, RenderOnce,
-    SharedString, Styled, Window, div, prelude::FluentBuilder as _, px,
+    AnyElement, App, AppContext, Context, Entity, InteractiveElement as _, IntoElement,
+    ParentElement, Render, RenderOnce, SharedString, StatefulInteractiveElement as _, Styled,
+    Window, div, prelude::FluentBuilder as _, px, relative,
 };
 
 use agent_client_protocol::{
@@ -8,81 +9,86 @@ use agent_client_protocol::{
     ToolKind,
 };
 use gpui_component::{
-    ActiveTheme, IconName, Sizable,
+    ActiveTheme, IconName, Selectable as _, Sizable,
     button::{Button, ButtonVariants},
     collapsible::Collapsible,
     h_flex, v_flex,
 };
-use similar::{ChangeTag, TextDiff};
 
+use crate::AppState;
 use crate::PanelAction;
-use crate::components::DiffView;
+use crate::components::{DiffView, SplitDiffView};
+use crate::core::agent::permission_policy;
+use crate::core::diff::{DiffStats, diff_stats, edit_diffs};
 use crate::panels::conversation::types::{ToolCallStatusExt, ToolKindExt};
+use crate::utils::tabular::Table;
 use crate::utils::tool_call::{extract_terminal_output, extract_xml_content, truncate_lines};
 
-/// Diff statistics
-#[derive(Debug, Clone, Default)]
-struct DiffStats {
-    additions: usize,
-    deletions: usize,
-}
+/// Rows shown by the table and chart views
+const MAX_TABLE_ROWS: usize = 200;
 
-/// Calculate diff statistics from old and new text
-fn calculate_diff_stats(old_text: &str, new_text: &str) -> DiffStats {
-    let diff = TextDiff::from_lines(old_text, new_text);
-    let mut additions = 0;
-    let mut deletions = 0;
-
-    for change in diff.iter_all_changes() {
-        match change.tag() {
-            ChangeTag::Insert => additions += 1,
-            ChangeTag::Delete => deletions += 1,
-            ChangeTag::Equal => {}
-        }
+/// Diff statistics summed over all diffs of a tool call
+fn extract_diff_stats(diffs: &[acp::Diff]) -> Option<DiffStats> {
+    if diffs.is_empty() {
+        return None;
     }
+    Some(diffs.iter().fold(DiffStats::default(), |total, diff| {
+        let stats = diff_stats(diff.old_text.as_deref(), &diff.new_text);
+        DiffStats {
+            additions: total.additions + stats.additions,
+            deletions: total.deletions + stats.deletions,
+        }
+    }))
+}
 
-    DiffStats {
-        additions,
-        deletions,
-    }
+/// How a tabular tool output is shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum OutputView {
+    #[default]
+    Text,
+    Table,
+    Chart,
 }
 
-/// Extract diff statistics from tool call content
-fn extract_diff_stats_from_tool_call(tool_call: &ToolCall) -> Option<DiffStats> {
-    // Find the first Diff content in the tool call
-    for content in &tool_call.content {
-        if let ToolCallContent::Diff(diff) = content {
-            return Some(match &diff.old_text {
-                Some(old_text) => calculate_diff_stats(old_text, &diff.new_text),
-                None => {
-                    // New file - all lines are additions
-                    DiffStats {
-                        additions: diff.new_text.lines().count(),
-                        deletions: 0,
-                    }
-                }
-            });
-        }
-    }
-    None
+/// How file changes are shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum DiffLayout {
+    #[default]
+    Unified,
+    Split,
 }
 
 /// Tool call item component based on ACP's ToolCall - stateful version
 pub struct ToolCallItem {
     tool_call: ToolCall,
     open: bool,
+    /// Index of the text output that reads as a table, and the table
+    table: Option<(usize, Table)>,
+    output_view: OutputView,
+    diff_layout: DiffLayout,
+    /// Column the table is sorted by, and whether ascending
+    sort: Option<(usize, bool)>,
+    /// Permission request the agent is waiting on before running the tool
+    permission_id: Option<String>,
 }
 
 impl ToolCallItem {
     pub fn new(tool_call: ToolCall) -> Self {
-        Self {
-            tool_call,
-            open: false,
-        }
+        Self::new_with_open(tool_call, false)
     }
 
     pub fn new_with_open(tool_call: ToolCall, open: bool) -> Self {
-        Self { tool_call, open }
+        let mut item = Self {
+            tool_call,
+            open,
+            table: None,
+            output_view: OutputView::Text,
+            diff_layout: DiffLayout::default(),
+            sort: None,
+            permission_id: None,
+        };
+        item.refresh_table();
+        item
     }
 
     pub fn tool_call(&self) -> &ToolCall {
@@ -109,6 +115,7 @@ impl ToolCallItem {
     pub fn update_tool_call(&mut self, tool_call: ToolCall, cx: &mut Context<Self>) {
         log::debug!("tool_call: {:?}", &tool_call);
         self.tool_call = tool_call;
+        self.refresh_table();
         if self.has_content() {
             self.open = true;
         }
@@ -119,6 +126,7 @@ impl ToolCallItem {
     pub fn apply_update(&mut self, update_fields: ToolCallUpdateFields, cx: &mut Context<Self>) {
         log::debug!("Applying update to tool call: {:?}", update_fields);
         self.tool_call.update(update_fields);
+        self.refresh_table();
 
         // Auto-open when tool call completes or fails (so user can see result)
         match self.tool_call.status {
@@ -142,11 +150,394 @@ impl ToolCallItem {
     /// Add content to the tool call
     pub fn add_content(&mut self, content: ToolCallContent, cx: &mut Context<Self>) {
         self.tool_call.content.push(content);
+        self.refresh_table();
         cx.notify();
     }
 
+    /// Ask the user to approve or deny this tool call before the agent runs it
+    pub fn request_permission(&mut self, permission_id: String, cx: &mut Context<Self>) {
+        self.permission_id = Some(permission_id);
+        cx.notify();
+    }
+
+    /// Send the user's answer to the agent; the request stays up if that fails
+    fn answer_permission(&mut self, approve: bool, for_session: bool, cx: &mut Context<Self>) {
+        let Some(agent_service) = AppState::global(cx).agent_service().cloned() else {
+            log::error!("AgentService not available to answer the permission request");
+            return;
+        };
+        let Some(permission_id) = self.permission_id.take() else {
+            return;
+        };
+        cx.spawn(async move |this, cx| {
+            if let Err(e) = agent_service
+                .answer_permission(&permission_id, approve, for_session)
+                .await
+            {
+                log::error!(
+                    "Failed to answer permission request {}: {}",
+                    permission_id,
+                    e
+                );
+                _ = this.update(cx, |this, cx| {
+                    this.permission_id = Some(permission_id);
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+        cx.notify();
+    }
+
+    /// Approve / always allow / deny buttons of a pending permission request
+    fn render_permission(&self, permission_id: &str, cx: &mut Context<Self>) -> AnyElement {
+        let destructive = permission_policy::is_destructive(Some(&self.tool_call.kind));
+        let button_id =
+            |name: &str| SharedString::from(format!("permission-{}-{}", permission_id, name));
+
+        h_flex()
+            .gap_2()
+            .pl_8()
+            .items_center()
+            .flex_wrap()
+            .child(
+                div()
+                    .flex_1()
+                    .min_w(px(0.))
+                    .text_size(px(12.))
+                    .text_color(if destructive {
+                        cx.theme().warning
+                    } else {
+                        cx.theme().muted_foreground
+                    })
+                    .child(if destructive {
+                        "The agent wants to change files or run a command"
+                    } else {
+                        "The agent is waiting for permission"
+                    }),
+            )
+            .child(
+                Button::new(button_id("approve"))
+                    .label("Approve")
+                    .icon(IconName::Check)
+                    .primary()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                        this.answer_permission(true, false, cx);
+                    })),
+            )
+            .child(
+                Button::new(button_id("session"))
+                    .label("Always Allow in This Session")
+                    .icon(IconName::CircleCheck)
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                        this.answer_permission(true, true, cx);
+                    })),
+            )
+            .child(
+                Button::new(button_id("deny"))
+                    .label("Deny")
+                    .icon(IconName::CircleX)
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                        this.answer_permission(false, false, cx);
+                    })),
+            )
+            .into_any_element()
+    }
+
     pub fn has_content(&self) -> bool {
-        !self.tool_call.content.is_empty()
+        !self.tool_call.content.is_empty() || !self.input_diffs().is_empty()
+    }
+
+    /// The edits described by the raw input of an edit tool call that sent
+    /// no diff content
+    fn input_diffs(&self) -> Vec<acp::Diff> {
+        let has_diff = self
+            .tool_call
+            .content
+            .iter()
+            .any(|content| matches!(content, ToolCallContent::Diff(_)));
+        if self.tool_call.kind != ToolKind::Edit || has_diff {
+            return Vec::new();
+        }
+        self.tool_call
+            .raw_input
+            .as_ref()
+            .map(edit_diffs)
+            .unwrap_or_default()
+    }
+
+    /// All diffs of the tool call: its diff content, else its raw input edits
+    fn diffs(&self) -> Vec<acp::Diff> {
+        let diffs: Vec<_> = self
+            .tool_call
+            .content
+            .iter()
+            .filter_map(|content| match content {
+                ToolCallContent::Diff(diff) => Some(diff.clone()),
+                _ => None,
+            })
+            .collect();
+        if diffs.is_empty() {
+            self.input_diffs()
+        } else {
+            diffs
+        }
+    }
+
+    /// Re-read the first text output that is a JSON array or CSV as a table
+    fn refresh_table(&mut self) {
+        let kind = &self.tool_call.kind;
+        self.table = self
+            .tool_call
+            .content
+            .iter()
+            .enumerate()
+            .find_map(|(ix, content)| match content {
+                ToolCallContent::Content(c) => match &c.content {
+                    acp::ContentBlock::Text(text) => {
+                        Table::parse(&extract_xml_content(&text.text, kind)).map(|t| (ix, t))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            });
+        if self.table.is_none() {
+            self.output_view = OutputView::Text;
+            self.sort = None;
+        }
+    }
+
+    fn set_output_view(&mut self, view: OutputView, cx: &mut Context<Self>) {
+        self.output_view = view;
+        cx.notify();
+    }
+
+    fn set_diff_layout(&mut self, layout: DiffLayout, cx: &mut Context<Self>) {
+        self.diff_layout = layout;
+        cx.notify();
+    }
+
+    /// Sort by `column` ascending, then descending, then unsorted
+    fn sort_by(&mut self, column: usize, cx: &mut Context<Self>) {
+        self.sort = match self.sort {
+            Some((current, true)) if current == column => Some((column, false)),
+            Some((current, false)) if current == column => None,
+            _ => Some((column, true)),
+        };
+        cx.notify();
+    }
+
+    /// Text / Table / Chart toggle for a tabular output
+    fn render_view_switcher(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let (_, table) = self.table.as_ref()?;
+        let mut views = vec![("Text", OutputView::Text), ("Table", OutputView::Table)];
+        if !table.numeric_columns().is_empty() {
+            views.push(("Chart", OutputView::Chart));
+        }
+        let tool_call_id = self.tool_call.tool_call_id.to_string();
+        Some(
+            h_flex()
+                .gap_1()
+                .children(views.into_iter().map(|(label, view)| {
+                    Button::new(SharedString::from(format!(
+                        "tool-call-{}-view-{}",
+                        tool_call_id, label
+                    )))
+                    .label(label)
+                    .ghost()
+                    .xsmall()
+                    .selected(self.output_view == view)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.set_output_view(view, cx);
+                    }))
+                }))
+                .into_any_element(),
+        )
+    }
+
+    /// Unified / Split toggle for file changes
+    fn render_layout_switcher(
+        &self,
+        has_diffs: bool,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        if !has_diffs {
+            return None;
+        }
+        let tool_call_id = self.tool_call.tool_call_id.to_string();
+        let layouts = [
+            ("Unified", DiffLayout::Unified),
+            ("Split", DiffLayout::Split),
+        ];
+        Some(
+            h_flex()
+                .gap_1()
+                .children(layouts.into_iter().map(|(label, layout)| {
+                    Button::new(SharedString::from(format!(
+                        "tool-call-{}-layout-{}",
+                        tool_call_id, label
+                    )))
+                    .label(label)
+                    .ghost()
+                    .xsmall()
+                    .selected(self.diff_layout == layout)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.set_diff_layout(layout, cx);
+                    }))
+                }))
+                .into_any_element(),
+        )
+    }
+
+    /// Render a diff in the chosen layout, limited to 8 lines
+    fn render_diff(&self, diff: &acp::Diff, window: &mut Window, cx: &mut App) -> AnyElement {
+        match self.diff_layout {
+            DiffLayout::Unified => DiffView::new(diff.clone())
+                .max_lines(8)
+                .context_lines(1)
+                .show_file_header(false) // Hide file header in compact view
+                .render(window, cx)
+                .into_any_element(),
+            DiffLayout::Split => SplitDiffView::new(diff.clone())
+                .max_lines(8)
+                .context_lines(1)
+                .render(window, cx)
+                .into_any_element(),
+        }
+    }
+
+    /// Row order of the table view and chart
+    fn table_order(&self, table: &Table) -> Vec<usize> {
+        match self.sort {
+            Some((column, ascending)) => table.sorted_rows(column, ascending),
+            None => (0..table.rows.len()).collect(),
+        }
+    }
+
+    /// The output as a table whose headers sort it
+    fn render_table(&self, table: &Table, cx: &mut Context<Self>) -> AnyElement {
+        let border = cx.theme().border;
+        let headers = table.columns.iter().enumerate().map(|(ix, column)| {
+            let arrow = match self.sort {
+                Some((sorted, true)) if sorted == ix => " ↑",
+                Some((sorted, false)) if sorted == ix => " ↓",
+                _ => "",
+            };
+            div()
+                .id(("tool-table-column", ix))
+                .flex_1()
+                .min_w(px(60.))
+                .px_2()
+                .py_1()
+                .font_weight(gpui::FontWeight::SEMIBOLD)
+                .cursor_pointer()
+                .child(format!("{}{}", column, arrow))
+                .on_click(cx.listener(move |this, _ev, _window, cx| this.sort_by(ix, cx)))
+        });
+        let rows = self
+            .table_order(table)
+            .into_iter()
+            .take(MAX_TABLE_ROWS)
+            .map(|row| {
+                h_flex()
+                    .border_t_1()
+                    .border_color(border)
+                    .children(table.rows[row].iter().map(|cell| {
+                        div()
+                            .flex_1()
+                            .min_w(px(60.))
+                            .px_2()
+                            .py_0p5()
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .child(cell.clone())
+                    }))
+            });
+
+        v_flex()
+            .text_size(px(12.))
+            .border_1()
+            .border_color(border)
+            .rounded(cx.theme().radius)
+            .overflow_hidden()
+            .child(h_flex().bg(cx.theme().secondary).children(headers))
+            .children(rows)
+            .into_any_element()
+    }
+
+    /// The output as a bar chart of the sorted column, or of the first
+    /// numeric column when the table is not sorted by one
+    fn render_chart(&self, table: &Table, cx: &mut Context<Self>) -> AnyElement {
+        let numeric = table.numeric_columns();
+        let column = self
+            .sort
+            .map(|(column, _)| column)
+            .filter(|column| numeric.contains(column))
+            .or(numeric.first().copied());
+        let Some(column) = column else {
+            return self.render_table(table, cx);
+        };
+        let bars = table.bars(column);
+        let max = bars
+            .iter()
+            .map(|(_, value)| value.abs())
+            .fold(0.0, f64::max);
+
+        v_flex()
+            .gap_1()
+            .text_size(px(12.))
+            .child(
+                div()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(table.columns[column].clone()),
+            )
+            .children(
+                self.table_order(table)
+                    .into_iter()
+                    .take(MAX_TABLE_ROWS)
+                    .map(|row| {
+                        let (label, value) = &bars[row];
+                        let fraction = if max > 0.0 {
+                            (value.max(0.0) / max) as f32
+                        } else {
+                            0.0
+                        };
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(
+                                div()
+                                    .w(px(120.))
+                                    .flex_none()
+                                    .overflow_hidden()
+                                    .text_ellipsis()
+                                    .child(label.clone()),
+                            )
+                            .child(
+                                div().flex_1().h(px(10.)).child(
+                                    div()
+                                        .h_full()
+                                        .w(relative(fraction))
+                                        .rounded(px(2.))
+                                        .bg(cx.theme().primary),
+                                ),
+                            )
+                            .child(
+                                div()
+                                    .w(px(72.))
+                                    .flex_none()
+                                    .text_right()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(value.to_string()),
+                            )
+                    }),
+            )
+            .into_any_element()
     }
 
     /// Get formatted display title for the tool call
@@ -183,20 +574,21 @@ impl ToolCallItem {
     /// Render content based on type
     fn render_content(
         &self,
+        ix: usize,
         content: &ToolCallContent,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> AnyElement {
-        match content {
-            ToolCallContent::Diff(diff) => {
-                // Use DiffView component for diff content, limited to 10 lines
-                let diff_view = DiffView::new(diff.clone())
-                    .max_lines(8)
-                    .context_lines(1)
-                    .show_file_header(false); // Hide file header in compact view
-
-                diff_view.render(window, cx).into_any_element()
+        let table = self.table.as_ref().filter(|(table_ix, _)| *table_ix == ix);
+        if let Some((_, table)) = table {
+            match self.output_view {
+                OutputView::Table => return self.render_table(table, cx),
+                OutputView::Chart => return self.render_chart(table, cx),
+                OutputView::Text => {}
             }
+        }
+        match content {
+            ToolCallContent::Diff(diff) => self.render_diff(diff, window, cx),
             ToolCallContent::Content(c) => match &c.content {
                 acp::ContentBlock::Text(text) => {
                     let cleaned_text = extract_xml_content(&text.text, &self.tool_call.kind);
@@ -268,7 +660,11 @@ impl Render for ToolCallItem {
         let status_icon = self.tool_call.status.icon();
 
         // Extract diff stats if this is a diff tool call
-        let diff_stats = extract_diff_stats_from_tool_call(&self.tool_call);
+        let diffs = self.diffs();
+        let diff_stats = extract_diff_stats(&diffs);
+        let input_diffs = self.input_diffs();
+        // The text output of an edit shown as a diff only repeats it, unless it reports a failure
+        let hide_text = !input_diffs.is_empty() && self.tool_call.status != ToolCallStatus::Failed;
 
         Collapsible::new()
             .open(open)
@@ -362,15 +758,34 @@ impl Render for ToolCallItem {
                         )
                     }),
             )
+            .children(
+                self.permission_id
+                    .as_deref()
+                    .map(|permission_id| self.render_permission(permission_id, cx)),
+            )
             // Content - only visible when open and has content
             .when(has_content, |this| {
                 this.content(
-                    v_flex().gap_2().pl_8().children(
-                        self.tool_call
-                            .content
-                            .iter()
-                            .map(|content| self.render_content(content, window, cx)),
-                    ),
+                    v_flex()
+                        .gap_2()
+                        .pl_8()
+                        .children(self.render_view_switcher(cx))
+                        .children(self.render_layout_switcher(!diffs.is_empty(), cx))
+                        .children(
+                            input_diffs
+                                .iter()
+                                .map(|diff| self.render_diff(diff, window, cx)),
+                        )
+                        .children(
+                            self.tool_call
+                                .content
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, content)| {
+                                    !(hide_text && matches!(content, ToolCallContent::Content(_)))
+                                })
+                                .map(|(ix, content)| self.render_content(ix, content, window, cx)),
+                        ),
                 )
                 .max_h(px(300.))
                 .overflow_hidden()
@@ -427,6 +842,7 @@ impl ToolCallItemView {
     pub fn set_content(&mut self, content: Vec<ToolCallContent>, cx: &mut Context<Self>) {
         self.item.update(cx, |item, cx| {
             item.tool_call.content = content;
+            item.refresh_table();
             cx.notify();
         });
         cx.notify();
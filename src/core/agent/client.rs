@@ -4,7 +4,8 @@
 //! which agent binaries to spawn, and provides a REPL to interact with them.
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    path::PathBuf,
     rc::Rc,
     sync::{
         Arc,
@@ -23,11 +24,18 @@ use tokio::{
     task::LocalSet,
 };
 
-use crate::core::config::{AgentProcessConfig, ProxyConfig};
+use super::command_policy::{self, CommandPolicy, CommandRefusal};
+use super::{network_policy, permission_policy};
+use crate::core::config::{
+    AgentProcessConfig, CommandPolicyConfig, NetworkPolicy, PermissionDecision, PermissionRule,
+    ProxyConfig,
+};
+use crate::core::config_manager;
 use crate::core::event_bus::{
     permission_bus::{PermissionBusContainer, PermissionRequestEvent},
     session_bus::{SessionUpdateBusContainer, SessionUpdateEvent},
 };
+use crate::core::services::{AuditAction, AuditEntry, AuditService, DecisionSource};
 
 use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 
@@ -38,6 +46,10 @@ pub struct AgentManager {
     session_bus: SessionUpdateBusContainer,
     permission_bus: PermissionBusContainer,
     proxy_config: Arc<RwLock<ProxyConfig>>,
+    /// Process configs of spawned agents, kept so hibernated agents can be respawned
+    configs: Arc<RwLock<HashMap<String, AgentProcessConfig>>>,
+    /// Agents whose process was stopped by hibernation
+    hibernated: Arc<RwLock<HashSet<String>>>,
 }
 
 impl AgentManager {
@@ -58,6 +70,8 @@ impl AgentManager {
             session_bus,
             permission_bus,
             proxy_config,
+            configs: Arc::new(RwLock::new(HashMap::new())),
+            hibernated: Arc::new(RwLock::new(HashSet::new())),
         });
         let remaining = Arc::new(AtomicUsize::new(configs.len()));
 
@@ -81,10 +95,40 @@ impl AgentManager {
         Ok(manager)
     }
 
+    /// Register the agents of `configs` as hibernated instead of spawning
+    /// them; each one starts when a session first needs it (safe mode)
+    pub async fn initialize_hibernated(
+        configs: HashMap<String, AgentProcessConfig>,
+        permission_store: Arc<PermissionStore>,
+        session_bus: SessionUpdateBusContainer,
+        permission_bus: PermissionBusContainer,
+        proxy_config: ProxyConfig,
+    ) -> Result<Arc<Self>> {
+        if configs.is_empty() {
+            return Err(anyhow!("no agents defined in config"));
+        }
+        let hibernated = configs.keys().cloned().collect();
+        Ok(Arc::new(Self {
+            agents: Arc::new(RwLock::new(HashMap::new())),
+            permission_store,
+            session_bus,
+            permission_bus,
+            proxy_config: Arc::new(RwLock::new(proxy_config)),
+            configs: Arc::new(RwLock::new(configs)),
+            hibernated: Arc::new(RwLock::new(hibernated)),
+        }))
+    }
+
     pub async fn list_agents(&self) -> Vec<String> {
         let agents = self.agents.read().await;
-        let mut list = agents.keys().cloned().collect::<Vec<_>>();
+        let hibernated = self.hibernated.read().await;
+        let mut list = agents
+            .keys()
+            .chain(hibernated.iter())
+            .cloned()
+            .collect::<Vec<_>>();
         list.sort();
+        list.dedup();
         list
     }
 
@@ -96,6 +140,12 @@ impl AgentManager {
             .and_then(|handle| handle.get_init_response())
     }
 
+    /// The initialize response of an agent, or `None` when another task is
+    /// changing the agents
+    pub fn try_get_agent_init_response(&self, name: &str) -> Option<acp::InitializeResponse> {
+        self.agents.try_read().ok()?.get(name)?.get_init_response()
+    }
+
     /// Get all agents with their initialize responses
     pub async fn list_agents_with_info(&self) -> Vec<(String, Option<acp::InitializeResponse>)> {
         let agents = self.agents.read().await;
@@ -107,6 +157,10 @@ impl AgentManager {
         list
     }
 
+    pub fn permission_store(&self) -> &Arc<PermissionStore> {
+        &self.permission_store
+    }
+
     pub async fn get(&self, name: &str) -> Option<Arc<AgentHandle>> {
         let agents = self.agents.read().await;
         agents.get(name).cloned()
@@ -125,7 +179,7 @@ impl AgentManager {
         // Spawn new agent
         let handle = AgentHandle::spawn(
             name.clone(),
-            config,
+            config.clone(),
             self.permission_store.clone(),
             self.session_bus.clone(),
             self.permission_bus.clone(),
@@ -134,6 +188,7 @@ impl AgentManager {
         .await?;
 
         // Add to agents map
+        self.configs.write().await.insert(name.clone(), config);
         let mut agents = self.agents.write().await;
         agents.insert(name.clone(), Arc::new(handle));
         log::info!("Successfully added agent '{}'", name);
@@ -142,6 +197,12 @@ impl AgentManager {
 
     /// Remove an agent from the manager
     pub async fn remove_agent(&self, name: &str) -> Result<()> {
+        self.configs.write().await.remove(name);
+        if self.hibernated.write().await.remove(name) {
+            log::info!("Removed hibernated agent '{}'", name);
+            return Ok(());
+        }
+
         let handle = {
             let mut agents = self.agents.write().await;
             agents
@@ -157,20 +218,31 @@ impl AgentManager {
 
     /// Restart an agent with new configuration
     pub async fn restart_agent(&self, name: &str, config: AgentProcessConfig) -> Result<()> {
+        // A hibernated agent has no process to stop
+        let was_hibernated = self.hibernated.write().await.remove(name);
+
         // Remove old agent
         let old_handle = {
             let mut agents = self.agents.write().await;
-            agents
-                .remove(name)
-                .ok_or_else(|| anyhow!("Agent '{}' not found", name))?
+            match agents.remove(name) {
+                Some(handle) => Some(handle),
+                None if was_hibernated => None,
+                None => return Err(anyhow!("Agent '{}' not found", name)),
+            }
         };
 
         // Shutdown old agent
-        if let Err(e) = old_handle.shutdown().await {
-            warn!("Failed to shutdown old agent '{}': {}", name, e);
+        if let Some(old_handle) = old_handle {
+            if let Err(e) = old_handle.shutdown().await {
+                warn!("Failed to shutdown old agent '{}': {}", name, e);
+            }
         }
 
         // Spawn new agent
+        self.configs
+            .write()
+            .await
+            .insert(name.to_string(), config.clone());
         let new_handle = AgentHandle::spawn(
             name.to_string(),
             config,
@@ -216,6 +288,73 @@ impl AgentManager {
     pub async fn get_proxy_config(&self) -> ProxyConfig {
         self.proxy_config.read().await.clone()
     }
+
+    /// Get the process config an agent was spawned with
+    pub async fn get_config(&self, name: &str) -> Option<AgentProcessConfig> {
+        self.configs.read().await.get(name).cloned()
+    }
+
+    // ========== Hibernation ==========
+
+    /// Whether the agent's process is currently hibernated
+    pub async fn is_hibernated(&self, name: &str) -> bool {
+        self.hibernated.read().await.contains(name)
+    }
+
+    /// Stop the agent's process while keeping it registered, so it can be woken later
+    pub async fn hibernate_agent(&self, name: &str) -> Result<()> {
+        let handle = {
+            let mut agents = self.agents.write().await;
+            agents
+                .remove(name)
+                .ok_or_else(|| anyhow!("Agent '{}' not found", name))?
+        };
+        self.hibernated.write().await.insert(name.to_string());
+
+        if let Err(e) = handle.shutdown().await {
+            warn!("Failed to shutdown hibernating agent '{}': {}", name, e);
+        }
+        log::info!("Hibernated agent '{}'", name);
+        Ok(())
+    }
+
+    /// Respawn a hibernated agent from its last known config
+    pub async fn wake_agent(&self, name: &str) -> Result<Arc<AgentHandle>> {
+        if !self.is_hibernated(name).await {
+            return self
+                .get(name)
+                .await
+                .ok_or_else(|| anyhow!("Agent '{}' not found", name));
+        }
+
+        let config = self
+            .configs
+            .read()
+            .await
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("No config stored for hibernated agent '{}'", name))?;
+
+        let handle = Arc::new(
+            AgentHandle::spawn(
+                name.to_string(),
+                config,
+                self.permission_store.clone(),
+                self.session_bus.clone(),
+                self.permission_bus.clone(),
+                self.proxy_config.read().await.clone(),
+            )
+            .await?,
+        );
+
+        self.agents
+            .write()
+            .await
+            .insert(name.to_string(), handle.clone());
+        self.hibernated.write().await.remove(name);
+        log::info!("Woke hibernated agent '{}'", name);
+        Ok(handle)
+    }
 }
 
 pub struct AgentHandle {
@@ -226,7 +365,7 @@ pub struct AgentHandle {
 }
 
 impl AgentHandle {
-    async fn spawn(
+    pub(super) async fn spawn(
         name: String,
         config: AgentProcessConfig,
         permission_store: Arc<PermissionStore>,
@@ -322,6 +461,28 @@ impl AgentHandle {
         result
     }
 
+    /// Load an existing session (ACP `session/load`)
+    ///
+    /// The agent replays the conversation while loading; those replayed updates are
+    /// not published to the session bus since the UI already has them persisted.
+    pub async fn load_session(
+        &self,
+        request: acp::LoadSessionRequest,
+    ) -> Result<acp::LoadSessionResponse> {
+        let (tx, rx) = oneshot::channel();
+        self.sender
+            .send(AgentCommand::LoadSession {
+                request,
+                respond: tx,
+            })
+            .await
+            .map_err(|_| anyhow!("agent {} is not running", self.name))?;
+        let result = rx
+            .await
+            .map_err(|_| anyhow!("agent {} stopped", self.name))?;
+        result
+    }
+
     /// Cancel an ongoing session operation
     pub async fn cancel(&self, session_id: String) -> Result<()> {
         let (tx, rx) = oneshot::channel();
@@ -477,6 +638,9 @@ async fn agent_event_loop(
     init_response: Arc<std::sync::RwLock<Option<acp::InitializeResponse>>>,
     proxy_config: ProxyConfig,
 ) -> Result<()> {
+    // `${workspace}` and the like are expanded for this launch only
+    let config = config.resolve_templates(&config_manager::template_vars());
+
     // Node.js environment validation
     let requires_nodejs = config.command.ends_with(".js")
         || config.command.ends_with(".ts")
@@ -490,7 +654,6 @@ async fn agent_event_loop(
         );
 
         use crate::core::nodejs::NodeJsChecker;
-        use std::path::PathBuf;
 
         let custom_path = config.nodejs_path.as_ref().map(PathBuf::from);
         let nodejs_checker = NodeJsChecker::new(custom_path);
@@ -523,15 +686,31 @@ async fn agent_event_loop(
         }
     }
 
+    let launch = match network_policy::prepare_launch(
+        config.network,
+        &config.command,
+        &config.args,
+        &proxy_config,
+        std::env::consts::OS,
+    ) {
+        Ok(launch) => launch,
+        Err(e) => {
+            let error_msg = format!("Cannot start agent '{}': {}", agent_name, e);
+            log::error!("{}", error_msg);
+            let _ = ready_tx.send(Err(anyhow!(error_msg.clone())));
+            return Err(anyhow!(error_msg));
+        }
+    };
+
     let mut command = if cfg!(target_os = "windows") {
         let mut shell_cmd = tokio::process::Command::new("cmd");
-        let mut full_args = vec!["/C".to_string(), config.command.clone()];
-        full_args.extend(config.args.iter().cloned());
+        let mut full_args = vec!["/C".to_string(), launch.program.clone()];
+        full_args.extend(launch.args.iter().cloned());
         shell_cmd.args(&full_args);
         shell_cmd
     } else {
-        let mut cmd = tokio::process::Command::new(&config.command);
-        cmd.args(&config.args);
+        let mut cmd = tokio::process::Command::new(&launch.program);
+        cmd.args(&launch.args);
         cmd
     };
 
@@ -564,6 +743,15 @@ async fn agent_event_loop(
         }
     }
 
+    // Network policy restrictions override the settings above
+    if config.network != NetworkPolicy::Unrestricted {
+        log::info!("Agent '{}' runs with network policy {:?}", agent_name, config.network);
+    }
+    for var in &launch.env_remove {
+        command.env_remove(var);
+    }
+    command.envs(launch.env.iter().map(|(key, value)| (key, value)));
+
     // Set stdio for all platforms
     command.stdin(std::process::Stdio::piped());
     command.stdout(std::process::Stdio::piped());
@@ -583,11 +771,13 @@ async fn agent_event_loop(
         .ok_or_else(|| anyhow!("agent {agent_name} missing stdout"))?
         .compat();
 
+    let replaying_sessions = Arc::new(std::sync::RwLock::new(HashSet::new()));
     let client = GuiClient::new(
         agent_name.clone(),
-        permission_store,
+        permission_store.clone(),
         session_bus,
         permission_bus,
+        replaying_sessions.clone(),
     );
     let (conn, io_task) = acp::ClientSideConnection::new(client, outgoing, incoming, |fut| {
         tokio::task::spawn_local(fut);
@@ -654,18 +844,25 @@ async fn agent_event_loop(
                     }
                 }
 
+                let cwd = request.cwd.clone();
                 let result = conn.new_session(request).await.map_err(|err| {
                     log::error!("Agent {} new_session failed: {:?}", agent_name, err);
                     anyhow!(err)
                 });
 
-                if let Err(ref e) = result {
-                    log::error!("Agent {} new_session error details: {}", agent_name, e);
+                match &result {
+                    Ok(response) => permission_store
+                        .set_session_workspace(response.session_id.to_string(), cwd),
+                    Err(e) => {
+                        log::error!("Agent {} new_session error details: {}", agent_name, e)
+                    }
                 }
 
                 let _ = respond.send(result);
             }
             AgentCommand::ResumeSession { request, respond } => {
+                permission_store
+                    .set_session_workspace(request.session_id.to_string(), request.cwd.clone());
                 let result = conn
                     .resume_session(*request)
                     .await
@@ -687,7 +884,17 @@ async fn agent_event_loop(
                 let _ = respond.send(result);
             }
             AgentCommand::LoadSession { request, respond } => {
+                log::info!("Agent {} received load session command", agent_name);
+                let session_id = request.session_id.to_string();
+                permission_store.set_session_workspace(session_id.clone(), request.cwd.clone());
+                replaying_sessions
+                    .write()
+                    .unwrap()
+                    .insert(session_id.clone());
                 let result = conn.load_session(request).await.map_err(|err| anyhow!(err));
+                // Let notification handlers spawned during the replay run before unmuting
+                tokio::task::yield_now().await;
+                replaying_sessions.write().unwrap().remove(&session_id);
                 let _ = respond.send(result);
             }
             AgentCommand::SetSessionMode { request, respond } => {
@@ -745,20 +952,47 @@ struct GuiClient {
     permission_store: Arc<PermissionStore>,
     session_bus: SessionUpdateBusContainer,
     permission_bus: PermissionBusContainer,
+    /// Sessions currently being replayed by `session/load`
+    replaying_sessions: Arc<std::sync::RwLock<HashSet<String>>>,
 }
 
 impl GuiClient {
+    /// Show the user a command refused by the command policy
+    fn publish_refusal(
+        &self,
+        session_id: String,
+        tool_call: acp::ToolCallUpdate,
+        refusal: CommandRefusal,
+    ) {
+        log::warn!(
+            "[GuiClient] Refused command of agent '{}' in session '{}': {}",
+            self.agent_name,
+            session_id,
+            refusal.command
+        );
+        self.permission_bus.publish(PermissionRequestEvent {
+            permission_id: self.permission_store.next_id(),
+            session_id,
+            agent_name: self.agent_name.clone(),
+            tool_call,
+            options: Vec::new(),
+            refusal: Some(refusal),
+        });
+    }
+
     pub fn new(
         agent_name: String,
         permission_store: Arc<PermissionStore>,
         session_bus: SessionUpdateBusContainer,
         permission_bus: PermissionBusContainer,
+        replaying_sessions: Arc<std::sync::RwLock<HashSet<String>>>,
     ) -> Self {
         Self {
             agent_name,
             permission_store,
             session_bus,
             permission_bus,
+            replaying_sessions,
         }
     }
 }
@@ -769,19 +1003,107 @@ impl acp::Client for GuiClient {
         &self,
         args: acp::RequestPermissionRequest,
     ) -> acp::Result<acp::RequestPermissionResponse> {
+        let session_id = args.session_id.to_string();
+        let tool = tool_label(&args.tool_call);
+        // Locked sessions may still read, but everything else is refused
+        if self.permission_store.is_session_locked(&session_id)
+            && !permission_policy::is_read_only(args.tool_call.fields.kind.as_ref())
+            && let Some(response) =
+                permission_policy::automatic_response(PermissionDecision::AutoDeny, &args.options)
+        {
+            log::info!(
+                "[GuiClient] Refused permission request of locked session '{}'",
+                session_id
+            );
+            self.permission_store.audit_decision(
+                &session_id,
+                &self.agent_name,
+                tool,
+                decision_name(&response, &args.options),
+                DecisionSource::SessionLock,
+            );
+            return Ok(response);
+        }
+        if let Some(command) = command_policy::shell_command(&args.tool_call) {
+            if let Err(refusal) = self.permission_store.check_command(&command) {
+                let response = command_policy::refusal_response(&refusal, &args.options);
+                self.permission_store.audit_decision(
+                    &session_id,
+                    &self.agent_name,
+                    tool,
+                    decision_name(&response, &args.options),
+                    DecisionSource::CommandPolicy,
+                );
+                self.publish_refusal(session_id, args.tool_call, refusal);
+                return Ok(response);
+            }
+        }
+
+        let decision = self.permission_store.decide(
+            &self.agent_name,
+            &session_id,
+            args.tool_call.fields.kind.as_ref(),
+        );
+        if let Some(response) = permission_policy::automatic_response(decision, &args.options) {
+            log::info!(
+                "[GuiClient] Answered permission request of session '{}' by policy: {:?}",
+                session_id,
+                decision
+            );
+            self.permission_store.audit_decision(
+                &session_id,
+                &self.agent_name,
+                tool,
+                decision_name(&response, &args.options),
+                DecisionSource::Policy,
+            );
+            return Ok(response);
+        }
+        let kind = permission_policy::tool_kind_name(args.tool_call.fields.kind.as_ref());
+        if self
+            .permission_store
+            .is_approved_for_session(&session_id, &kind)
+            && let Some(response) = permission_policy::automatic_response(
+                PermissionDecision::AutoApprove,
+                &args.options,
+            )
+        {
+            log::info!(
+                "[GuiClient] Allowed {} tool call of session '{}' for the session",
+                kind,
+                session_id
+            );
+            self.permission_store.audit_decision(
+                &session_id,
+                &self.agent_name,
+                tool,
+                decision_name(&response, &args.options),
+                DecisionSource::SessionApproval,
+            );
+            return Ok(response);
+        }
+
         let (tx, rx) = oneshot::channel();
         let permission_id = self
             .permission_store
-            .add(self.agent_name.clone(), args.session_id.to_string(), tx)
+            .add(
+                self.agent_name.clone(),
+                session_id.clone(),
+                tool,
+                kind,
+                args.options.clone(),
+                tx,
+            )
             .await;
 
         // Publish permission request event to the permission bus
         let event = PermissionRequestEvent {
             permission_id: permission_id.clone(),
-            session_id: args.session_id.to_string(),
+            session_id,
             agent_name: self.agent_name.clone(),
             tool_call: args.tool_call,
             options: args.options,
+            refusal: None,
         };
 
         log::debug!(
@@ -811,8 +1133,45 @@ impl acp::Client for GuiClient {
 
     async fn create_terminal(
         &self,
-        _args: acp::CreateTerminalRequest,
+        args: acp::CreateTerminalRequest,
     ) -> Result<acp::CreateTerminalResponse, acp::Error> {
+        let command = std::iter::once(args.command.as_str())
+            .chain(args.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let session_id = args.session_id.to_string();
+        if self.permission_store.is_session_locked(&session_id) {
+            self.permission_store.audit_decision(
+                &session_id,
+                &self.agent_name,
+                command,
+                "refused".to_string(),
+                DecisionSource::SessionLock,
+            );
+            return Err(acp::Error::invalid_params().data(serde_json::json!({
+                "refused": true,
+                "message": "The session is locked (read-only)",
+            })));
+        }
+        if let Err(refusal) = self.permission_store.check_command(&command) {
+            let error = acp::Error::invalid_params().data(refusal.to_json());
+            self.permission_store.audit_decision(
+                &session_id,
+                &self.agent_name,
+                command.clone(),
+                "refused".to_string(),
+                DecisionSource::CommandPolicy,
+            );
+            let mut fields = acp::ToolCallUpdateFields::default();
+            fields.kind = Some(acp::ToolKind::Execute);
+            fields.title = Some(command);
+            let tool_call = acp::ToolCallUpdate::new(
+                acp::ToolCallId::from(format!("terminal-{}", args.session_id)),
+                fields,
+            );
+            self.publish_refusal(session_id, tool_call, refusal);
+            return Err(error);
+        }
         Err(acp::Error::method_not_found())
     }
 
@@ -855,6 +1214,19 @@ impl acp::Client for GuiClient {
             args.update
         );
 
+        if self
+            .replaying_sessions
+            .read()
+            .unwrap()
+            .contains(&args.session_id.to_string())
+        {
+            log::debug!(
+                "[GuiClient] Skipping replayed update for session '{}'",
+                args.session_id
+            );
+            return Ok(());
+        }
+
         // Publish event to the session bus
         let event = SessionUpdateEvent {
             session_id: args.session_id.to_string(),
@@ -881,28 +1253,172 @@ impl acp::Client for GuiClient {
 pub struct PendingPermission {
     agent: String,
     session_id: String,
+    /// Tool call title or command, for the audit log
+    tool: String,
+    /// Tool kind as named in config.json, for session approvals
+    kind: String,
+    options: Vec<acp::PermissionOption>,
     responder: oneshot::Sender<acp::RequestPermissionResponse>,
 }
 
+/// What a permission request is about, as shown in the audit log
+fn tool_label(tool_call: &acp::ToolCallUpdate) -> String {
+    command_policy::shell_command(tool_call)
+        .or_else(|| tool_call.fields.title.clone())
+        .unwrap_or_else(|| tool_call.tool_call_id.to_string())
+}
+
+/// The decision in `response`: the kind of the selected option, or `cancelled`
+fn decision_name(
+    response: &acp::RequestPermissionResponse,
+    options: &[acp::PermissionOption],
+) -> String {
+    match &response.outcome {
+        acp::RequestPermissionOutcome::Selected(selected) => options
+            .iter()
+            .find(|option| option.option_id == selected.option_id)
+            .and_then(|option| serde_json::to_value(option.kind).ok())
+            .and_then(|kind| kind.as_str().map(str::to_string))
+            .unwrap_or_else(|| selected.option_id.to_string()),
+        _ => "cancelled".to_string(),
+    }
+}
+
 #[derive(Default)]
 pub struct PermissionStore {
     pending: RwLock<HashMap<String, PendingPermission>>,
     next_id: AtomicU64,
+    /// Rules answering requests without asking (see `permission_policy`)
+    policies: std::sync::RwLock<Vec<PermissionRule>>,
+    /// Working directory of each session, for workspace-specific rules
+    session_workspaces: std::sync::RwLock<HashMap<String, PathBuf>>,
+    /// Allow/deny lists for shell commands (see `command_policy`)
+    command_policy: std::sync::RwLock<CommandPolicy>,
+    /// Where permission decisions are recorded
+    audit_service: std::sync::RwLock<Option<Arc<AuditService>>>,
+    /// Sessions of locked tasks, whose agents may not write
+    locked_sessions: std::sync::RwLock<HashSet<String>>,
+    /// Tool kinds the user allowed for the rest of a session, by session
+    session_approvals: std::sync::RwLock<HashMap<String, HashSet<String>>>,
 }
 
 impl PermissionStore {
+    /// Replace the confirmation policies, e.g. after config.json changed
+    pub fn set_policies(&self, policies: Vec<PermissionRule>) {
+        *self.policies.write().unwrap() = policies;
+    }
+
+    pub fn set_audit_service(&self, audit_service: Arc<AuditService>) {
+        *self.audit_service.write().unwrap() = Some(audit_service);
+    }
+
+    /// Record a permission decision in the audit log
+    pub fn audit_decision(
+        &self,
+        session_id: &str,
+        agent: &str,
+        tool: String,
+        decision: String,
+        decided_by: DecisionSource,
+    ) {
+        if let Some(audit_service) = self.audit_service.read().unwrap().as_ref() {
+            audit_service.record(AuditEntry::new(
+                session_id,
+                agent,
+                AuditAction::PermissionDecision {
+                    tool,
+                    decision,
+                    decided_by,
+                },
+            ));
+        }
+    }
+
+    pub fn set_command_policy(&self, config: &CommandPolicyConfig) {
+        *self.command_policy.write().unwrap() = CommandPolicy::new(config);
+    }
+
+    /// Check a shell command an agent wants to run against the command policy
+    pub fn check_command(&self, command: &str) -> Result<(), CommandRefusal> {
+        self.command_policy.read().unwrap().check(command)
+    }
+
+    /// Lock or unlock `session_ids`
+    pub fn set_sessions_locked(&self, session_ids: &[String], locked: bool) {
+        let mut locked_sessions = self.locked_sessions.write().unwrap();
+        for session_id in session_ids {
+            if locked {
+                locked_sessions.insert(session_id.clone());
+            } else {
+                locked_sessions.remove(session_id);
+            }
+        }
+    }
+
+    pub fn is_session_locked(&self, session_id: &str) -> bool {
+        self.locked_sessions.read().unwrap().contains(session_id)
+    }
+
+    /// Id for a new request shown to the user
+    fn next_id(&self) -> String {
+        self.next_id.fetch_add(1, Ordering::SeqCst).to_string()
+    }
+
+    pub fn set_session_workspace(&self, session_id: String, cwd: PathBuf) {
+        self.session_workspaces
+            .write()
+            .unwrap()
+            .insert(session_id, cwd);
+    }
+
+    /// How a `kind` tool call requested by `agent` in `session_id` is answered
+    pub fn decide(
+        &self,
+        agent: &str,
+        session_id: &str,
+        kind: Option<&acp::ToolKind>,
+    ) -> PermissionDecision {
+        let workspaces = self.session_workspaces.read().unwrap();
+        permission_policy::decide(
+            &self.policies.read().unwrap(),
+            &permission_policy::tool_kind_name(kind),
+            agent,
+            workspaces.get(session_id).map(PathBuf::as_path),
+        )
+    }
+
+    /// Whether the user allowed `kind` tool calls for the rest of `session_id`
+    pub fn is_approved_for_session(&self, session_id: &str, kind: &str) -> bool {
+        self.session_approvals
+            .read()
+            .unwrap()
+            .get(session_id)
+            .is_some_and(|kinds| kinds.contains(kind))
+    }
+
+    /// Forget what the user allowed for `session_id`, e.g. when it closes
+    pub fn clear_session_approvals(&self, session_id: &str) {
+        self.session_approvals.write().unwrap().remove(session_id);
+    }
+
     pub async fn add(
         &self,
         agent: String,
         session_id: String,
+        tool: String,
+        kind: String,
+        options: Vec<acp::PermissionOption>,
         responder: oneshot::Sender<acp::RequestPermissionResponse>,
     ) -> String {
-        let id = self.next_id.fetch_add(1, Ordering::SeqCst).to_string();
+        let id = self.next_id();
         self.pending.write().await.insert(
             id.clone(),
             PendingPermission {
                 agent,
                 session_id,
+                tool,
+                kind,
+                options,
                 responder,
             },
         );
@@ -917,6 +1433,13 @@ impl PermissionStore {
     ) -> anyhow::Result<()> {
         let pending = self.remove(id).await;
         if let Some(pending) = pending {
+            self.audit_decision(
+                &pending.session_id,
+                &pending.agent,
+                pending.tool,
+                decision_name(&response, &pending.options),
+                DecisionSource::User,
+            );
             pending
                 .responder
                 .send(response)
@@ -926,6 +1449,47 @@ impl PermissionStore {
         }
     }
 
+    /// Approve or deny a request with one of its own options
+    ///
+    /// With `for_session`, later requests of the same tool kind in the
+    /// session are approved without asking.
+    pub async fn answer(&self, id: &str, approve: bool, for_session: bool) -> anyhow::Result<()> {
+        let decision = if approve {
+            PermissionDecision::AutoApprove
+        } else {
+            PermissionDecision::AutoDeny
+        };
+        let (response, session_id, kind) = {
+            let pending = self.pending.read().await;
+            let pending = pending
+                .get(id)
+                .ok_or_else(|| anyhow!("Permission request ID not found: {}", id))?;
+            let response = match permission_policy::automatic_response(decision, &pending.options) {
+                Some(response) => response,
+                // Without an option to reject, a denial cancels the request
+                None if !approve => {
+                    acp::RequestPermissionResponse::new(acp::RequestPermissionOutcome::Cancelled)
+                }
+                None => {
+                    return Err(anyhow!(
+                        "Permission request {} offers no option to allow",
+                        id
+                    ));
+                }
+            };
+            (response, pending.session_id.clone(), pending.kind.clone())
+        };
+        if approve && for_session {
+            self.session_approvals
+                .write()
+                .unwrap()
+                .entry(session_id)
+                .or_default()
+                .insert(kind);
+        }
+        self.respond(id, response).await
+    }
+
     async fn remove(&self, id: &str) -> Option<PendingPermission> {
         self.pending.write().await.remove(id)
     }
@@ -0,0 +1,309 @@
+//! Agent Doctor - Health checks for configured agent servers
+//!
+//! For every `agent_servers` entry the doctor resolves the command, spawns the
+//! process, performs the ACP `initialize` handshake and reports what the agent
+//! advertised together with how long startup took. Failures are mapped to a
+//! short remediation hint so broken configs can be fixed without digging
+//! through logs. The probe process is shut down again right after the check.
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use agent_client_protocol as acp;
+
+use super::client::{AgentHandle, PermissionStore};
+use crate::core::config::{AgentProcessConfig, ProxyConfig};
+use crate::core::config_manager;
+use crate::core::event_bus::{
+    permission_bus::PermissionBusContainer, session_bus::SessionUpdateBusContainer,
+};
+
+/// How long an agent may take to spawn and answer `initialize`
+pub const DEFAULT_DOCTOR_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Result of checking a single agent
+#[derive(Clone, Debug)]
+pub struct AgentDiagnosis {
+    pub name: String,
+    /// Resolved executable, if it could be found on PATH
+    pub command_path: Option<PathBuf>,
+    /// `name version` reported by the agent
+    pub agent_info: Option<String>,
+    pub protocol_version: Option<String>,
+    /// Capabilities the agent advertised during initialize
+    pub capabilities: Vec<String>,
+    /// Time from spawn until initialize completed
+    pub latency: Option<Duration>,
+    /// What went wrong (None = healthy)
+    pub problem: Option<String>,
+    /// Suggested fix for `problem`
+    pub hint: Option<String>,
+}
+
+impl AgentDiagnosis {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            command_path: None,
+            agent_info: None,
+            protocol_version: None,
+            capabilities: Vec::new(),
+            latency: None,
+            problem: None,
+            hint: None,
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.problem.is_none()
+    }
+
+    fn fail(mut self, problem: String, hint: Option<String>) -> Self {
+        self.problem = Some(problem);
+        self.hint = hint;
+        self
+    }
+
+    /// Plain-text report used by the `--doctor` CLI flag
+    pub fn to_report(&self) -> String {
+        let mut lines = vec![format!(
+            "[{}] {}",
+            if self.is_healthy() { "ok" } else { "FAIL" },
+            self.name
+        )];
+        if let Some(path) = &self.command_path {
+            lines.push(format!("  command:      {}", path.display()));
+        }
+        if let Some(info) = &self.agent_info {
+            lines.push(format!("  agent:        {}", info));
+        }
+        if let Some(version) = &self.protocol_version {
+            lines.push(format!("  protocol:     {}", version));
+        }
+        if !self.capabilities.is_empty() {
+            lines.push(format!("  capabilities: {}", self.capabilities.join(", ")));
+        }
+        if let Some(latency) = self.latency {
+            lines.push(format!("  startup:      {} ms", latency.as_millis()));
+        }
+        if let Some(problem) = &self.problem {
+            lines.push(format!("  problem:      {}", problem));
+        }
+        if let Some(hint) = &self.hint {
+            lines.push(format!("  fix:          {}", hint));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Check every configured agent, one after another
+///
+/// Agents are probed sequentially so their startup latencies don't skew each other.
+pub async fn diagnose_agents(
+    configs: &HashMap<String, AgentProcessConfig>,
+    proxy_config: &ProxyConfig,
+    timeout: Duration,
+) -> Vec<AgentDiagnosis> {
+    let mut names: Vec<_> = configs.keys().cloned().collect();
+    names.sort();
+
+    let mut results = Vec::with_capacity(names.len());
+    for name in names {
+        let config = configs[&name].clone();
+        results.push(diagnose_agent(&name, config, proxy_config.clone(), timeout).await);
+    }
+    results
+}
+
+/// Spawn the agent, run ACP initialize and report the outcome
+pub async fn diagnose_agent(
+    name: &str,
+    config: AgentProcessConfig,
+    proxy_config: ProxyConfig,
+    timeout: Duration,
+) -> AgentDiagnosis {
+    let mut diagnosis = AgentDiagnosis::new(name);
+
+    if config.command.trim().is_empty() {
+        return diagnosis.fail(
+            "No command configured".to_string(),
+            Some("Set `command` to the agent executable (e.g. `npx`).".to_string()),
+        );
+    }
+
+    let command = config
+        .resolve_templates(&config_manager::template_vars())
+        .command;
+    match resolve_command(&command) {
+        Some(path) => diagnosis.command_path = Some(path),
+        None => {
+            let problem = format!("Command `{}` not found", command);
+            let hint = remediation_hint(&command, &problem);
+            return diagnosis.fail(problem, hint);
+        }
+    }
+
+    log::info!("[AgentDoctor] Probing agent '{}'", name);
+    let started = Instant::now();
+    let spawn = AgentHandle::spawn(
+        format!("{name}-doctor"),
+        config.clone(),
+        Arc::new(PermissionStore::default()),
+        SessionUpdateBusContainer::new(),
+        PermissionBusContainer::new(),
+        proxy_config,
+    );
+    let result = smol::future::or(async { Some(spawn.await) }, async {
+        smol::Timer::after(timeout).await;
+        None
+    })
+    .await;
+
+    let handle = match result {
+        Some(Ok(handle)) => handle,
+        Some(Err(e)) => {
+            let problem = format!("{:#}", e);
+            let hint = remediation_hint(&command, &problem);
+            return diagnosis.fail(problem, hint);
+        }
+        None => {
+            let problem = format!("No initialize response within {}s", timeout.as_secs());
+            let hint = remediation_hint(&command, &problem);
+            return diagnosis.fail(problem, hint);
+        }
+    };
+    diagnosis.latency = Some(started.elapsed());
+
+    if let Some(response) = handle.get_init_response() {
+        diagnosis.protocol_version = Some(format!("{:?}", response.protocol_version));
+        diagnosis.agent_info = response
+            .agent_info
+            .as_ref()
+            .map(|info| format!("{} {}", info.name, info.version));
+        diagnosis.capabilities = capability_names(&response.agent_capabilities);
+    }
+
+    if let Err(e) = handle.shutdown().await {
+        log::warn!("[AgentDoctor] Failed to shut down probe for '{}': {}", name, e);
+    }
+
+    diagnosis
+}
+
+/// Find the executable for a configured command
+fn resolve_command(command: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(command);
+    if path.components().count() > 1 {
+        return path.exists().then_some(path);
+    }
+    which::which(command).ok()
+}
+
+/// Names of the optional capabilities an agent reported on initialize
+pub fn capability_names(capabilities: &acp::AgentCapabilities) -> Vec<String> {
+    let mut names = Vec::new();
+    if capabilities.load_session {
+        names.push("load_session");
+    }
+    if capabilities.prompt_capabilities.image {
+        names.push("image");
+    }
+    if capabilities.prompt_capabilities.audio {
+        names.push("audio");
+    }
+    if capabilities.prompt_capabilities.embedded_context {
+        names.push("embedded_context");
+    }
+    if capabilities.mcp_capabilities.http {
+        names.push("mcp_http");
+    }
+    if capabilities.mcp_capabilities.sse {
+        names.push("mcp_sse");
+    }
+    names.into_iter().map(String::from).collect()
+}
+
+/// Map a failure to a concrete next step for the user
+pub fn remediation_hint(command: &str, problem: &str) -> Option<String> {
+    let problem = problem.to_lowercase();
+    let uses_node = command.contains("npx") || command.contains("node");
+
+    if problem.contains("node.js") {
+        return Some(
+            "Install Node.js 18+ or set its path under Settings > General > Node.js path."
+                .to_string(),
+        );
+    }
+    if problem.contains("not found") && uses_node {
+        return Some(format!(
+            "`{}` is not on PATH. Install Node.js (which ships npx) or use an absolute path.",
+            command
+        ));
+    }
+    if problem.contains("not found") || problem.contains("no such file") {
+        return Some(format!(
+            "Install the agent or point `command` at the absolute path of `{}`.",
+            command
+        ));
+    }
+    if problem.contains("permission denied") {
+        return Some(format!("Make `{}` executable (chmod +x).", command));
+    }
+    if problem.contains("failed to initialize") || problem.contains("no initialize response") {
+        return Some(
+            "The process started but did not speak ACP on stdio. Check `args` enable ACP mode \
+             (e.g. `--experimental-acp`) and that required API keys are set in `env`."
+                .to_string(),
+        );
+    }
+    if problem.contains("failed to spawn") {
+        return Some("Check that `command` and `args` run from a terminal.".to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remediation_hint_by_failure() {
+        let hint = remediation_hint("npx", "Command `npx` not found").unwrap();
+        assert!(hint.contains("Node.js"));
+
+        let hint = remediation_hint("my-agent", "Command `my-agent` not found").unwrap();
+        assert!(hint.contains("absolute path"));
+
+        let hint = remediation_hint("gemini", "No initialize response within 30s").unwrap();
+        assert!(hint.contains("ACP"));
+
+        assert!(remediation_hint("gemini", "something odd").is_none());
+    }
+
+    #[test]
+    fn test_missing_command_is_reported_without_spawning() {
+        let config = AgentProcessConfig {
+            command: "definitely-not-an-agent-binary".to_string(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            max_concurrent_sessions: None,
+            network: Default::default(),
+            display: Default::default(),
+            variants: Default::default(),
+            nodejs_path: None,
+        };
+        let diagnosis = smol::block_on(diagnose_agent(
+            "missing",
+            config,
+            ProxyConfig::default(),
+            Duration::from_secs(1),
+        ));
+        assert!(!diagnosis.is_healthy());
+        assert!(diagnosis.latency.is_none());
+        assert!(diagnosis.hint.is_some());
+    }
+}
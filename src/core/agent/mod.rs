@@ -1,5 +1,13 @@
 // Agent client modules
 mod client;
+pub mod command_policy;
+mod doctor;
+pub mod network_policy;
+pub mod permission_policy;
 
 // Re-export agent types
 pub use client::{AgentHandle, AgentManager, PermissionStore};
+pub use doctor::{
+    AgentDiagnosis, DEFAULT_DOCTOR_TIMEOUT, capability_names, diagnose_agent, diagnose_agents,
+    remediation_hint,
+};
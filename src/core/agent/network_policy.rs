@@ -0,0 +1,161 @@
+//! Network egress restrictions for agent processes
+//!
+//! The `network` option of an agent limits where its process can connect:
+//!
+//! - `unrestricted` (default): nothing changes.
+//! - `proxy-only`: the agent only gets the configured proxy and `NO_PROXY` is
+//!   cleared, so well-behaved HTTP clients send everything through it. This
+//!   is enforced through the environment only.
+//! - `offline`: the process runs without network access, in its own network
+//!   namespace on Linux (`unshare`) or a `sandbox-exec` profile on macOS.
+//!   Other platforms cannot isolate the process, so the agent is not started.
+
+use anyhow::{Result, bail};
+
+use crate::core::config::{NetworkPolicy, ProxyConfig};
+
+/// Proxy variables set for the agent, upper and lower case
+const PROXY_VARS: [&str; 6] = [
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+];
+
+/// Variables that would let requests bypass the proxy
+const NO_PROXY_VARS: [&str; 2] = ["NO_PROXY", "no_proxy"];
+
+/// Address nothing listens on, so offline agents fail fast instead of hanging
+const DEAD_PROXY: &str = "http://127.0.0.1:9";
+
+/// Sandbox profile denying every network operation but local sockets
+const MACOS_OFFLINE_PROFILE: &str =
+    "(version 1)(allow default)(deny network*)(allow network* (local unix))";
+
+/// How to start an agent process under its network policy
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentLaunch {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Variables set on top of the agent's own environment
+    pub env: Vec<(String, String)>,
+    /// Variables removed from the inherited environment
+    pub env_remove: Vec<String>,
+}
+
+/// Launch of `command args` under `policy` on `os` (see `std::env::consts::OS`)
+pub fn prepare_launch(
+    policy: NetworkPolicy,
+    command: &str,
+    args: &[String],
+    proxy: &ProxyConfig,
+    os: &str,
+) -> Result<AgentLaunch> {
+    let mut launch = AgentLaunch {
+        program: command.to_string(),
+        args: args.to_vec(),
+        env: Vec::new(),
+        env_remove: Vec::new(),
+    };
+    let restrict_proxy = |launch: &mut AgentLaunch, url: &str| {
+        launch.env = PROXY_VARS
+            .iter()
+            .map(|var| (var.to_string(), url.to_string()))
+            .collect();
+        launch.env_remove = NO_PROXY_VARS.iter().map(|var| var.to_string()).collect();
+    };
+
+    match policy {
+        NetworkPolicy::Unrestricted => {}
+        NetworkPolicy::ProxyOnly => {
+            let Some(url) = proxy.to_env_value() else {
+                bail!("network policy `proxy-only` needs the proxy to be enabled");
+            };
+            restrict_proxy(&mut launch, &url);
+        }
+        NetworkPolicy::Offline => {
+            let (program, mut wrapper_args) = match os {
+                "linux" => (
+                    "unshare",
+                    vec!["--user", "--map-root-user", "--net", "--"],
+                ),
+                "macos" => ("sandbox-exec", vec!["-p", MACOS_OFFLINE_PROFILE]),
+                _ => bail!("network policy `offline` is not supported on {}", os),
+            };
+            wrapper_args.push(command);
+            launch.program = program.to_string();
+            launch.args = wrapper_args
+                .into_iter()
+                .map(str::to_string)
+                .chain(args.iter().cloned())
+                .collect();
+            // In case the sandbox lets something through, leave no usable proxy
+            restrict_proxy(&mut launch, DEAD_PROXY);
+        }
+    }
+    Ok(launch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> Vec<String> {
+        vec!["--acp".to_string()]
+    }
+
+    #[test]
+    fn test_unrestricted_launch_is_unchanged() {
+        let launch = prepare_launch(
+            NetworkPolicy::Unrestricted,
+            "claude",
+            &args(),
+            &ProxyConfig::default(),
+            "linux",
+        )
+        .unwrap();
+        assert_eq!(launch.program, "claude");
+        assert_eq!(launch.args, args());
+        assert!(launch.env.is_empty() && launch.env_remove.is_empty());
+    }
+
+    #[test]
+    fn test_proxy_only_requires_proxy() {
+        let mut proxy = ProxyConfig::default();
+        assert!(prepare_launch(NetworkPolicy::ProxyOnly, "claude", &[], &proxy, "linux").is_err());
+
+        proxy.enabled = true;
+        proxy.proxy_type = "http".to_string();
+        proxy.host = "proxy.local".to_string();
+        proxy.port = 3128;
+        let launch =
+            prepare_launch(NetworkPolicy::ProxyOnly, "claude", &[], &proxy, "linux").unwrap();
+        assert!(
+            launch
+                .env
+                .contains(&("HTTPS_PROXY".to_string(), "http://proxy.local:3128".to_string()))
+        );
+        assert!(launch.env_remove.contains(&"NO_PROXY".to_string()));
+    }
+
+    #[test]
+    fn test_offline_wraps_command() {
+        let proxy = ProxyConfig::default();
+        let linux =
+            prepare_launch(NetworkPolicy::Offline, "claude", &args(), &proxy, "linux").unwrap();
+        assert_eq!(linux.program, "unshare");
+        assert_eq!(
+            linux.args,
+            vec!["--user", "--map-root-user", "--net", "--", "claude", "--acp"]
+        );
+
+        let macos =
+            prepare_launch(NetworkPolicy::Offline, "claude", &args(), &proxy, "macos").unwrap();
+        assert_eq!(macos.program, "sandbox-exec");
+        assert_eq!(macos.args[2..], ["claude", "--acp"]);
+
+        assert!(prepare_launch(NetworkPolicy::Offline, "claude", &[], &proxy, "windows").is_err());
+    }
+}
@@ -0,0 +1,225 @@
+//! Confirmation policies for tool kinds
+//!
+//! `permission_policies` in config.json answer permission requests without
+//! asking, based on the kind of tool the agent wants to run. A rule can be
+//! limited to one agent and/or one workspace; the most specific matching rule
+//! wins, and requests matching no rule are asked as usual.
+
+use std::path::Path;
+
+use agent_client_protocol as acp;
+use globset::Glob;
+
+use crate::core::config::{PermissionDecision, PermissionRule};
+
+/// Name of a tool kind as written in config.json, e.g. `execute` or `delete`
+///
+/// Tool calls without a kind count as `other`.
+pub fn tool_kind_name(kind: Option<&acp::ToolKind>) -> String {
+    kind.and_then(|kind| serde_json::to_value(kind).ok())
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_else(|| "other".to_string())
+}
+
+/// Whether a `kind` tool call only reads, so it may run in a locked session
+///
+/// Tool calls without a kind are assumed to write.
+pub fn is_read_only(kind: Option<&acp::ToolKind>) -> bool {
+    matches!(
+        kind,
+        Some(
+            acp::ToolKind::Read
+                | acp::ToolKind::Search
+                | acp::ToolKind::Think
+                | acp::ToolKind::Fetch
+        )
+    )
+}
+
+/// Whether a `kind` tool call can change files or run commands, so the user
+/// is asked about it in the tool call itself
+pub fn is_destructive(kind: Option<&acp::ToolKind>) -> bool {
+    matches!(
+        kind,
+        Some(
+            acp::ToolKind::Edit
+                | acp::ToolKind::Delete
+                | acp::ToolKind::Move
+                | acp::ToolKind::Execute
+        )
+    )
+}
+
+/// Decision for a `kind` tool call of `agent` in a session working in `workspace`
+pub fn decide(
+    rules: &[PermissionRule],
+    kind: &str,
+    agent: &str,
+    workspace: Option<&Path>,
+) -> PermissionDecision {
+    rules
+        .iter()
+        .enumerate()
+        .filter_map(|(ix, rule)| rule_specificity(rule, kind, agent, workspace).map(|s| (s, ix)))
+        // Most specific first; the earlier rule wins a tie
+        .min_by(|(a, a_ix), (b, b_ix)| b.cmp(a).then(a_ix.cmp(b_ix)))
+        .map_or(PermissionDecision::AlwaysAsk, |(_, ix)| rules[ix].decision)
+}
+
+/// How specific `rule` is for the request, or `None` when it does not apply
+///
+/// Workspace rules beat agent rules, which beat global ones; a kind named
+/// exactly beats a pattern.
+fn rule_specificity(
+    rule: &PermissionRule,
+    kind: &str,
+    agent: &str,
+    workspace: Option<&Path>,
+) -> Option<u8> {
+    let exact_kind = rule.kinds.iter().any(|k| k.eq_ignore_ascii_case(kind));
+    if !exact_kind && !rule.kinds.iter().any(|pattern| kind_matches(pattern, kind)) {
+        return None;
+    }
+    if rule.agent.as_deref().is_some_and(|name| name != agent) {
+        return None;
+    }
+    if let Some(root) = &rule.workspace {
+        if !workspace.is_some_and(|dir| dir.starts_with(root)) {
+            return None;
+        }
+    }
+    Some(
+        u8::from(rule.workspace.is_some()) * 4
+            + u8::from(rule.agent.is_some()) * 2
+            + u8::from(exact_kind),
+    )
+}
+
+fn kind_matches(pattern: &str, kind: &str) -> bool {
+    Glob::new(&pattern.to_ascii_lowercase())
+        .map(|glob| glob.compile_matcher().is_match(kind.to_ascii_lowercase()))
+        .unwrap_or(false)
+}
+
+/// Response answering `options` with `decision` without asking the user
+///
+/// Prefers one-off options so the agent keeps asking for later calls, and
+/// returns `None` when the request has to be asked (or offers no option of
+/// the right kind).
+pub fn automatic_response(
+    decision: PermissionDecision,
+    options: &[acp::PermissionOption],
+) -> Option<acp::RequestPermissionResponse> {
+    let preferred = match decision {
+        PermissionDecision::AlwaysAsk => return None,
+        PermissionDecision::AutoApprove => [
+            acp::PermissionOptionKind::AllowOnce,
+            acp::PermissionOptionKind::AllowAlways,
+        ],
+        PermissionDecision::AutoDeny => [
+            acp::PermissionOptionKind::RejectOnce,
+            acp::PermissionOptionKind::RejectAlways,
+        ],
+    };
+    let option = preferred
+        .iter()
+        .find_map(|kind| options.iter().find(|option| option.kind == *kind));
+    match (option, decision) {
+        (Some(option), _) => Some(acp::RequestPermissionResponse::new(
+            acp::RequestPermissionOutcome::Selected(acp::SelectedPermissionOutcome::new(
+                option.option_id.clone(),
+            )),
+        )),
+        // Nothing to reject with: cancelling the request refuses it as well
+        (None, PermissionDecision::AutoDeny) => Some(acp::RequestPermissionResponse::new(
+            acp::RequestPermissionOutcome::Cancelled,
+        )),
+        (None, _) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn rule(kinds: &[&str], decision: PermissionDecision) -> PermissionRule {
+        PermissionRule {
+            kinds: kinds.iter().map(|k| k.to_string()).collect(),
+            decision,
+            agent: None,
+            workspace: None,
+        }
+    }
+
+    #[test]
+    fn test_tool_kind_name() {
+        assert_eq!(tool_kind_name(Some(&acp::ToolKind::Execute)), "execute");
+        assert_eq!(tool_kind_name(Some(&acp::ToolKind::Delete)), "delete");
+        assert_eq!(tool_kind_name(None), "other");
+        assert!(is_read_only(Some(&acp::ToolKind::Search)));
+        assert!(!is_read_only(Some(&acp::ToolKind::Edit)));
+        assert!(!is_read_only(None));
+        assert!(is_destructive(Some(&acp::ToolKind::Execute)));
+        assert!(is_destructive(Some(&acp::ToolKind::Move)));
+        assert!(!is_destructive(Some(&acp::ToolKind::Read)));
+        assert!(!is_destructive(None));
+    }
+
+    #[test]
+    fn test_decide_most_specific_rule() {
+        let project = PathBuf::from("/home/me/project");
+        let rules = vec![
+            rule(&["*"], PermissionDecision::AutoApprove),
+            rule(&["execute", "delete"], PermissionDecision::AlwaysAsk),
+            PermissionRule {
+                agent: Some("codex".into()),
+                ..rule(&["execute"], PermissionDecision::AutoDeny)
+            },
+            PermissionRule {
+                workspace: Some(project.clone()),
+                ..rule(&["exec*"], PermissionDecision::AutoApprove)
+            },
+        ];
+
+        assert_eq!(decide(&rules, "read", "claude", None), PermissionDecision::AutoApprove);
+        assert_eq!(decide(&rules, "delete", "claude", None), PermissionDecision::AlwaysAsk);
+        assert_eq!(decide(&rules, "execute", "codex", None), PermissionDecision::AutoDeny);
+        assert_eq!(
+            decide(&rules, "execute", "codex", Some(&project.join("src"))),
+            PermissionDecision::AutoApprove
+        );
+        assert_eq!(decide(&[], "execute", "codex", None), PermissionDecision::AlwaysAsk);
+    }
+
+    #[test]
+    fn test_automatic_response() {
+        let options = vec![
+            acp::PermissionOption::new("allow", "Allow", acp::PermissionOptionKind::AllowOnce),
+            acp::PermissionOption::new(
+                "always",
+                "Always allow",
+                acp::PermissionOptionKind::AllowAlways,
+            ),
+        ];
+        let selected = |response: Option<acp::RequestPermissionResponse>| match response
+            .map(|response| response.outcome)
+        {
+            Some(acp::RequestPermissionOutcome::Selected(selected)) => {
+                Some(selected.option_id.to_string())
+            }
+            _ => None,
+        };
+
+        assert_eq!(
+            selected(automatic_response(PermissionDecision::AutoApprove, &options)),
+            Some("allow".to_string())
+        );
+        assert!(automatic_response(PermissionDecision::AlwaysAsk, &options).is_none());
+        assert!(matches!(
+            automatic_response(PermissionDecision::AutoDeny, &options).map(|r| r.outcome),
+            Some(acp::RequestPermissionOutcome::Cancelled)
+        ));
+    }
+}
@@ -1,6 +1,9 @@
 use agent_client_protocol as acp;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
@@ -13,6 +16,9 @@ pub struct Config {
     pub mcp_servers: HashMap<String, McpServerConfig>,
     #[serde(default)]
     pub commands: HashMap<String, CommandConfig>,
+    /// Text inserted into the prompt input from its snippet menu
+    #[serde(default)]
+    pub snippets: Vec<Snippet>,
     /// Global system prompts for AI features
     /// Keys: "doc_comment", "inline_comment", "explain", "improve"
     #[serde(default)]
@@ -23,6 +29,366 @@ pub struct Config {
     /// Network proxy configuration
     #[serde(default)]
     pub proxy: ProxyConfig,
+    /// Seconds an agent may sit with only idle sessions before its process is
+    /// hibernated (0 disables hibernation)
+    #[serde(default)]
+    pub agent_hibernate_after_secs: u64,
+    /// Retry policy for transient prompt failures
+    #[serde(default)]
+    pub prompt_retry: RetryConfig,
+    /// Sessions pre-created at startup for instant first prompts
+    #[serde(default)]
+    pub warm_pool: WarmPoolConfig,
+    /// Per-turn limit on streamed agent output
+    #[serde(default)]
+    pub output_guard: OutputGuardConfig,
+    /// Paths skipped when scanning workspaces, on top of .gitignore
+    #[serde(default)]
+    pub indexing: IndexingConfig,
+    /// Embeddings index behind the `/search` command
+    #[serde(default)]
+    pub embeddings: EmbeddingConfig,
+    /// Model used by internal features (titles, summaries, commit messages)
+    #[serde(default)]
+    pub utility_model: UtilityModelConfig,
+    /// Automatic answers to permission requests by tool kind
+    #[serde(default)]
+    pub permission_policies: Vec<PermissionRule>,
+    /// Regex allow/deny lists for shell commands run by agents
+    #[serde(default)]
+    pub command_policy: CommandPolicyConfig,
+    /// Sync of settings, prompt templates and memories across machines
+    #[serde(default)]
+    pub sync: SyncConfig,
+    /// URLs notified of finished turns, errors and finished tasks
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    /// Slack or Discord channels sessions can be prompted from
+    #[serde(default)]
+    pub chat_bridges: Vec<ChatBridgeConfig>,
+    /// Let editor plugins send code selections over a local endpoint
+    #[serde(default = "default_true")]
+    pub editor_bridge: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RetryConfig {
+    /// Total attempts including the first one (1 disables retries)
+    #[serde(default = "default_retry_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles on every further attempt
+    #[serde(default = "default_retry_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    /// Upper bound for the delay between attempts
+    #[serde(default = "default_retry_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+pub const DEFAULT_RETRY_MAX_ATTEMPTS: u32 = 3;
+
+fn default_retry_max_attempts() -> u32 {
+    DEFAULT_RETRY_MAX_ATTEMPTS
+}
+
+fn default_retry_initial_backoff_ms() -> u64 {
+    500
+}
+
+fn default_retry_max_backoff_ms() -> u64 {
+    8000
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_retry_max_attempts(),
+            initial_backoff_ms: default_retry_initial_backoff_ms(),
+            max_backoff_ms: default_retry_max_backoff_ms(),
+        }
+    }
+}
+
+/// Startup warm-pool configuration
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct WarmPoolConfig {
+    /// Number of agents to keep a warm session for (0 disables the pool)
+    #[serde(default)]
+    pub size: usize,
+    /// Agents to always warm up first; remaining slots go to the most used agents
+    #[serde(default)]
+    pub agents: Vec<String>,
+}
+
+/// Guard against agents stuck in endless generation
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OutputGuardConfig {
+    /// Characters an agent may stream in one turn before it is cancelled (0 disables)
+    #[serde(default = "default_max_output_chars")]
+    pub max_output_chars: usize,
+}
+
+pub const DEFAULT_MAX_OUTPUT_CHARS: usize = 200_000;
+
+fn default_max_output_chars() -> usize {
+    DEFAULT_MAX_OUTPUT_CHARS
+}
+
+impl Default for OutputGuardConfig {
+    fn default() -> Self {
+        Self {
+            max_output_chars: default_max_output_chars(),
+        }
+    }
+}
+
+/// Exclusions for the file tree, file picker, symbol index and repo map
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IndexingConfig {
+    /// Globs excluded in every workspace; patterns without `/` match at any depth
+    #[serde(default = "default_exclude_globs")]
+    pub exclude_globs: Vec<String>,
+    /// Per-workspace replacements for `exclude_globs`, keyed by workspace path
+    #[serde(default)]
+    pub workspaces: HashMap<PathBuf, Vec<String>>,
+}
+
+impl IndexingConfig {
+    /// Exclude globs that apply below `root`
+    pub fn exclude_globs_for(&self, root: &Path) -> &[String] {
+        self.workspaces.get(root).unwrap_or(&self.exclude_globs)
+    }
+}
+
+fn default_exclude_globs() -> Vec<String> {
+    [
+        "node_modules",
+        "target",
+        "dist",
+        "build",
+        ".next",
+        ".cache",
+        ".venv",
+        "__pycache__",
+        ".vscode",
+        ".idea",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+impl Default for IndexingConfig {
+    fn default() -> Self {
+        Self {
+            exclude_globs: default_exclude_globs(),
+            workspaces: HashMap::new(),
+        }
+    }
+}
+
+/// Semantic search over workspace files
+///
+/// Any OpenAI-compatible `/embeddings` endpoint works, including local servers
+/// such as Ollama (`"base_url": "http://localhost:11434/v1"`).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EmbeddingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Entry in `models` that provides the endpoint and API key (default model if unset)
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Embeddings model requested from the provider
+    #[serde(default = "default_embedding_model")]
+    pub model_name: String,
+    /// Snippets attached per `/search`
+    #[serde(default = "default_search_top_k")]
+    pub top_k: usize,
+}
+
+fn default_embedding_model() -> String {
+    "text-embedding-3-small".to_string()
+}
+
+fn default_search_top_k() -> usize {
+    5
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: None,
+            model_name: default_embedding_model(),
+            top_k: default_search_top_k(),
+        }
+    }
+}
+
+/// Which of `models` serves internal features instead of the default model
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct UtilityModelConfig {
+    /// Entry in `models` tried first (the default model when unset)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Entries tried in order when the previous one fails
+    #[serde(default)]
+    pub fallbacks: Vec<String>,
+}
+
+/// How permission requests for a tool kind are answered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PermissionDecision {
+    /// Ask the user every time
+    #[default]
+    AlwaysAsk,
+    AutoApprove,
+    AutoDeny,
+}
+
+/// Confirmation policy for some tool kinds, e.g.
+/// `{ "kinds": ["execute", "delete"], "decision": "always-ask" }`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PermissionRule {
+    /// Tool kinds (`read`, `edit`, `delete`, `move`, `search`, `execute`,
+    /// `think`, `fetch`, `switch_mode`, `other`); globs such as `*` are allowed
+    pub kinds: Vec<String>,
+    pub decision: PermissionDecision,
+    /// Only applies to this agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// Only applies to sessions working in this directory or below
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<PathBuf>,
+}
+
+/// Where settings, prompt templates and memories are synced to (see
+/// `SyncService`); sync is off while `backend` is unset
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SyncConfig {
+    #[serde(default)]
+    pub backend: Option<SyncBackendKind>,
+    /// Folder kept in sync by another tool (Dropbox, iCloud Drive, a share)
+    #[serde(default)]
+    pub folder: Option<PathBuf>,
+    /// WebDAV collection URL
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    /// Minutes between automatic syncs (0 syncs only on demand)
+    #[serde(default = "default_sync_interval_minutes")]
+    pub interval_minutes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncBackendKind {
+    Folder,
+    Webdav,
+}
+
+fn default_sync_interval_minutes() -> u64 {
+    15
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            backend: None,
+            folder: None,
+            url: String::new(),
+            username: String::new(),
+            password: String::new(),
+            interval_minutes: default_sync_interval_minutes(),
+        }
+    }
+}
+
+/// An HTTP endpoint notified of session events (see `WebhookService`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Events sent to the URL; all of them when empty
+    #[serde(default)]
+    pub events: Vec<WebhookEventKind>,
+    /// Shape of the request body
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Extra request headers, e.g. `Authorization`
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+impl WebhookConfig {
+    pub fn accepts(&self, kind: WebhookEventKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventKind {
+    /// An agent finished a turn
+    TurnCompleted,
+    /// A prompt failed or a turn was cut off at the output limit
+    Error,
+    /// A task was locked, i.e. marked done
+    TaskDone,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// The event as a JSON object
+    #[default]
+    Json,
+    /// `{"text": ...}`, for Slack incoming webhooks
+    Slack,
+    /// `{"content": ...}`, for Discord webhooks
+    Discord,
+}
+
+/// A Slack or Discord channel polled for prompts to existing sessions (see
+/// `ChatBridgeService`); the replies are posted back to it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChatBridgeConfig {
+    pub platform: ChatPlatform,
+    /// Bot token: `xoxb-...` for Slack, the bot's token for Discord
+    pub token: String,
+    /// Channel ID
+    pub channel: String,
+    /// Messages starting with it are commands
+    #[serde(default = "default_chat_bridge_prefix")]
+    pub prefix: String,
+    /// User IDs allowed to send commands; nobody when empty, since prompts
+    /// can make agents run commands
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+}
+
+fn default_chat_bridge_prefix() -> String {
+    "!agentx".to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatPlatform {
+    Slack,
+    Discord,
+}
+
+/// Shell commands agents may run (see `core::agent::command_policy`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CommandPolicyConfig {
+    /// When not empty, only commands matching one of these patterns may run
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Commands matching any of these patterns are refused, even if allowed
+    #[serde(default)]
+    pub deny: Vec<String>,
 }
 
 fn default_upload_dir() -> PathBuf {
@@ -37,17 +403,207 @@ fn default_tool_call_preview_max_lines() -> usize {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AgentProcessConfig {
+    /// Command, args and env values may use the variables of [`TemplateVars`]
     pub command: String,
     #[serde(default)]
     pub args: Vec<String>,
     #[serde(default)]
     pub env: HashMap<String, String>,
 
+    /// Maximum number of sessions working concurrently (unset = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_sessions: Option<usize>,
+
+    /// Where the agent process may connect (see `core::agent::network_policy`)
+    #[serde(default, skip_serializing_if = "NetworkPolicy::is_unrestricted")]
+    pub network: NetworkPolicy,
+
+    /// Name, color and avatar shown for the agent
+    #[serde(default, skip_serializing_if = "AgentDisplay::is_empty")]
+    pub display: AgentDisplay,
+
+    /// Launch variants by label, e.g. "opus": each one runs as the agent
+    /// `<name> (<label>)` with this config and its own args and env
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variants: BTreeMap<String, AgentVariant>,
+
     /// Custom Node.js path (populated at runtime from AppSettings)
     #[serde(skip)]
     pub nodejs_path: Option<String>,
 }
 
+impl AgentProcessConfig {
+    /// The config with the variables in its command, args and env values
+    /// expanded, as the agent process is started with it
+    pub fn resolve_templates(&self, vars: &TemplateVars) -> Self {
+        let mut config = self.clone();
+        config.command = vars.expand(&self.command);
+        config.args = self.args.iter().map(|arg| vars.expand(arg)).collect();
+        config.env = self
+            .env
+            .iter()
+            .map(|(key, value)| (key.clone(), vars.expand(value)))
+            .collect();
+        config
+    }
+
+    /// The config the variant `label` runs with
+    pub fn with_variant(&self, label: &str, variant: &AgentVariant) -> Self {
+        let mut config = self.clone();
+        config.variants.clear();
+        if let Some(args) = &variant.args {
+            config.args = args.clone();
+        }
+        config.args.extend(variant.extra_args.iter().cloned());
+        config.env.extend(variant.env.clone());
+        if let Some(name) = &self.display.name {
+            config.display.name = Some(variant_agent_name(name, label));
+        }
+        config
+    }
+
+    /// The agents this entry runs: itself as `name`, then its variants
+    pub fn launch_configs(&self, name: &str) -> Vec<(String, Self)> {
+        let mut configs = vec![(name.to_string(), self.clone())];
+        configs.extend(self.variants.iter().map(|(label, variant)| {
+            (
+                variant_agent_name(name, label),
+                self.with_variant(label, variant),
+            )
+        }));
+        configs
+    }
+}
+
+/// Args and env a launch variant changes in its agent's config
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AgentVariant {
+    /// Replace the agent's args (unset = keep them)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+    /// Added after the args
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_args: Vec<String>,
+    /// Added to the agent's env, replacing variables of the same name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+}
+
+/// Name of the agent a launch variant runs as, e.g. "claude (opus)"
+pub fn variant_agent_name(agent_name: &str, label: &str) -> String {
+    format!("{} ({})", agent_name, label)
+}
+
+/// Every agent the entries of `agent_servers` run, variants included
+pub fn launch_configs(
+    agent_servers: &HashMap<String, AgentProcessConfig>,
+) -> HashMap<String, AgentProcessConfig> {
+    agent_servers
+        .iter()
+        .flat_map(|(name, config)| config.launch_configs(name))
+        .collect()
+}
+
+/// Values of the variables agent configs use to stay portable across machines
+///
+/// - `${env:VAR}`: the environment variable `VAR`, empty when it is unset
+/// - `${workspace}`: folder of the active workspace
+/// - `${config_dir}`: folder of the config file
+///
+/// Other `${...}` text is kept as is, e.g. for shell scripts in args.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateVars {
+    pub workspace: PathBuf,
+    pub config_dir: PathBuf,
+}
+
+impl TemplateVars {
+    /// `value` with its variables replaced
+    pub fn expand(&self, value: &str) -> String {
+        let mut expanded = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 2..start + len];
+            expanded.push_str(&rest[..start]);
+            match self.lookup(name) {
+                Some(resolved) => expanded.push_str(&resolved),
+                None => expanded.push_str(&rest[start..=start + len]),
+            }
+            rest = &rest[start + len + 1..];
+        }
+        expanded.push_str(rest);
+        expanded
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        if let Some(var) = name.strip_prefix("env:") {
+            return Some(std::env::var(var).unwrap_or_else(|_| {
+                log::warn!(
+                    "Environment variable {} used in an agent config is not set",
+                    var
+                );
+                String::new()
+            }));
+        }
+        match name {
+            "workspace" => Some(self.workspace.display().to_string()),
+            "config_dir" => Some(self.config_dir.display().to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Network access of an agent process
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NetworkPolicy {
+    #[default]
+    Unrestricted,
+    /// Only through the configured proxy
+    ProxyOnly,
+    /// No network access at all
+    Offline,
+}
+
+impl NetworkPolicy {
+    pub fn is_unrestricted(&self) -> bool {
+        *self == Self::Unrestricted
+    }
+}
+
+/// How an agent appears in avatars and headers; unset fields fall back to
+/// the agent's configured name and a color derived from it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AgentDisplay {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Hex color, e.g. `#d97757`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Image shown instead of the initial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<PathBuf>,
+}
+
+impl AgentDisplay {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.color.is_none() && self.avatar.is_none()
+    }
+
+    /// Name to show for the agent configured as `agent_name`
+    pub fn label(&self, agent_name: &str) -> String {
+        self.name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(agent_name)
+            .to_string()
+    }
+}
+
 /// Model configuration for LLM providers
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ModelConfig {
@@ -58,6 +614,29 @@ pub struct ModelConfig {
     pub model_name: String,
 }
 
+/// Provider name of models served by a local Ollama server
+pub const OLLAMA_PROVIDER: &str = "ollama";
+/// Address Ollama listens on by default
+pub const OLLAMA_DEFAULT_URL: &str = "http://localhost:11434";
+
+impl ModelConfig {
+    pub fn is_ollama(&self) -> bool {
+        self.provider.eq_ignore_ascii_case(OLLAMA_PROVIDER)
+    }
+
+    /// Base URL of the model's OpenAI-compatible API
+    ///
+    /// Ollama serves it under `/v1`; its server address is accepted as well.
+    pub fn api_base(&self) -> String {
+        let base = self.base_url.trim_end_matches('/');
+        if self.is_ollama() && !base.ends_with("/v1") {
+            format!("{}/v1", base)
+        } else {
+            base.to_string()
+        }
+    }
+}
+
 /// MCP (Model Context Protocol) server configuration
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct McpServerConfig {
@@ -74,9 +653,11 @@ impl McpServerConfig {
     /// Convert to agent_client_protocol::McpServer
     pub fn to_acp_mcp_server(&self, name: String) -> acp::McpServer {
         // Try to deserialize into McpServerStdio via JSON
-        let env_vars: Vec<serde_json::Value> = self
-            .env
-            .iter()
+        // Sorted so the same config always yields the same server
+        let mut env: Vec<_> = self.env.iter().collect();
+        env.sort();
+        let env_vars: Vec<serde_json::Value> = env
+            .into_iter()
             .map(|(k, v)| {
                 serde_json::json!({
                     "name": k,
@@ -122,6 +703,14 @@ pub struct CommandConfig {
     pub template: String,
 }
 
+/// Reusable text for the prompt input; unlike a command it is inserted
+/// where the cursor is rather than run
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Snippet {
+    pub name: String,
+    pub text: String,
+}
+
 /// Network proxy configuration
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct ProxyConfig {
@@ -168,3 +757,67 @@ impl ProxyConfig {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_template_vars() {
+        let vars = TemplateVars {
+            workspace: PathBuf::from("/work/app"),
+            config_dir: PathBuf::from("/home/me/.agentx"),
+        };
+        assert_eq!(
+            vars.expand("--cwd=${workspace} --rules ${config_dir}/rules.md"),
+            "--cwd=/work/app --rules /home/me/.agentx/rules.md"
+        );
+        assert_eq!(vars.expand("${env:AGENTX_TEMPLATE_TEST_UNSET}-x"), "-x");
+        assert_eq!(
+            vars.expand("echo ${HOME} ${workspace"),
+            "echo ${HOME} ${workspace"
+        );
+    }
+
+    #[test]
+    fn test_resolve_templates() {
+        let config: AgentProcessConfig = serde_json::from_value(serde_json::json!({
+            "command": "${config_dir}/bin/agent",
+            "args": ["--root", "${workspace}"],
+            "env": { "PROJECT": "${workspace}" },
+        }))
+        .unwrap();
+        let vars = TemplateVars {
+            workspace: PathBuf::from("/work/app"),
+            config_dir: PathBuf::from("/cfg"),
+        };
+        let resolved = config.resolve_templates(&vars);
+        assert_eq!(resolved.command, "/cfg/bin/agent");
+        assert_eq!(resolved.args, vec!["--root", "/work/app"]);
+        assert_eq!(resolved.env["PROJECT"], "/work/app");
+        assert_eq!(config.command, "${config_dir}/bin/agent");
+    }
+
+    #[test]
+    fn test_launch_variants() {
+        let config: AgentProcessConfig = serde_json::from_value(serde_json::json!({
+            "command": "claude-code-acp",
+            "args": ["--model", "sonnet"],
+            "env": { "LOG": "info" },
+            "variants": {
+                "opus": { "args": ["--model", "opus"] },
+                "yolo": { "extra_args": ["--yolo"], "env": { "LOG": "debug" } },
+            },
+        }))
+        .unwrap();
+        let agents = launch_configs(&HashMap::from([("claude".to_string(), config)]));
+        assert_eq!(agents.len(), 3);
+        assert_eq!(agents["claude"].args, vec!["--model", "sonnet"]);
+        assert_eq!(agents["claude (opus)"].args, vec!["--model", "opus"]);
+        assert_eq!(agents["claude (opus)"].env["LOG"], "info");
+        let yolo = &agents["claude (yolo)"];
+        assert_eq!(yolo.args, vec!["--model", "sonnet", "--yolo"]);
+        assert_eq!(yolo.env["LOG"], "debug");
+        assert!(yolo.variants.is_empty());
+    }
+}
@@ -1,11 +1,65 @@
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use crate::core::config::TemplateVars;
+
+/// Persistence root of the profile this process runs with (see `ProfileService`)
+static PROFILE_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Data directory given with `--data-dir` or found next to the executable
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Config file loaded at startup (`--config` or the profile's config.json)
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Folder of the active workspace, for `${workspace}` in agent configs
+static WORKSPACE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Directory next to the executable that turns on portable mode
+pub const PORTABLE_DIR_NAME: &str = "agentx-data";
+
+/// Keep everything under `dir` instead of the platform's user data directory
+///
+/// Called once at startup, before anything reads a path. Relative paths are
+/// taken relative to the working directory.
+pub fn set_data_dir(dir: PathBuf) {
+    let dir = absolute_dir(dir, &std::env::current_dir().unwrap_or_default());
+    if DATA_DIR.set(dir).is_err() {
+        log::warn!("Data directory was already set");
+    }
+}
+
+/// Data directory of portable mode: an `agentx-data` directory next to the executable
+pub fn portable_data_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?.join(PORTABLE_DIR_NAME);
+    dir.is_dir().then_some(dir)
+}
+
+/// Whether the data directory was relocated with `--data-dir` or portable mode
+pub fn is_data_dir_relocated() -> bool {
+    DATA_DIR.get().is_some()
+}
+
+fn absolute_dir(dir: PathBuf, cwd: &Path) -> PathBuf {
+    if dir.is_absolute() {
+        dir
+    } else {
+        cwd.join(dir)
+    }
+}
 
 /// Get the user data directory for AgentX
+/// - `--data-dir` or portable mode: the relocated directory
 /// - macOS: ~/.agentx/
 /// - Windows: %APPDATA%\agentx\
 /// - Linux: ~/.config/agentx/
 pub fn get_user_data_dir() -> Result<PathBuf> {
+    if let Some(dir) = DATA_DIR.get() {
+        return Ok(dir.clone());
+    }
+
     #[cfg(target_os = "macos")]
     {
         let home =
@@ -62,6 +116,40 @@ pub fn user_data_dir_or_temp() -> PathBuf {
     }
 }
 
+/// Keep the config and data of this process under `root`
+///
+/// Called once at startup with the selected profile's root; without it the
+/// user data directory is used.
+pub fn set_profile_root(root: PathBuf) {
+    if PROFILE_ROOT.set(root).is_err() {
+        log::warn!("Profile root was already set");
+    }
+}
+
+/// Ensure the current profile's directory exists
+pub fn ensure_profile_dir() -> Result<PathBuf> {
+    let Some(root) = PROFILE_ROOT.get() else {
+        return ensure_user_data_dir();
+    };
+    if !root.exists() {
+        log::info!("Creating profile directory: {:?}", root);
+        std::fs::create_dir_all(root)
+            .with_context(|| format!("Failed to create directory: {:?}", root))?;
+    }
+    Ok(root.clone())
+}
+
+/// Directory holding the config and data of the current profile
+pub fn profile_dir_or_temp() -> PathBuf {
+    match ensure_profile_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("Failed to resolve profile directory: {}", e);
+            fallback_data_dir()
+        }
+    }
+}
+
 pub fn ensure_default_config_at(path: &Path) -> Result<()> {
     if path.exists() {
         return Ok(());
@@ -77,20 +165,23 @@ pub fn ensure_default_config_at(path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Get the config file path in the user data directory
+/// Get the config file path of the current profile
 pub fn get_user_config_path() -> Result<PathBuf> {
-    Ok(get_user_data_dir()?.join("config.json"))
+    match PROFILE_ROOT.get() {
+        Some(root) => Ok(root.join("config.json")),
+        None => Ok(get_user_data_dir()?.join("config.json")),
+    }
 }
 
 pub fn get_user_config_path_or_temp() -> PathBuf {
-    user_data_dir_or_temp().join("config.json")
+    profile_dir_or_temp().join("config.json")
 }
 
-/// Initialize user config directory and config file
+/// Initialize the current profile's directory and config file
 /// If config file doesn't exist, create it from the embedded default config
 pub fn initialize_user_config() -> Result<PathBuf> {
-    let user_data_dir = ensure_user_data_dir()?;
-    let config_path = user_data_dir.join("config.json");
+    let profile_dir = ensure_profile_dir()?;
+    let config_path = profile_dir.join("config.json");
 
     if !config_path.exists() {
         log::info!(
@@ -167,9 +258,9 @@ pub fn get_state_file_path() -> PathBuf {
 }
 
 /// Get workspace config file path
-/// Always uses user data directory: <user_data_dir>/workspace-config.json
+/// Uses the current profile's directory: <profile_dir>/workspace-config.json
 pub fn get_workspace_config_path() -> PathBuf {
-    user_data_dir_or_temp().join("workspace-config.json")
+    profile_dir_or_temp().join("workspace-config.json")
 }
 
 /// Get docks layout file path
@@ -179,7 +270,112 @@ pub fn get_docks_layout_path() -> PathBuf {
 }
 
 /// Get sessions directory path
-/// Always uses user data directory: <user_data_dir>/sessions
+/// Uses the current profile's directory: <profile_dir>/sessions
 pub fn get_sessions_dir() -> PathBuf {
-    user_data_dir_or_temp().join("sessions")
+    profile_dir_or_temp().join("sessions")
+}
+
+/// Get sync state file path
+/// Uses the current profile's directory: <profile_dir>/sync-state.json
+pub fn get_sync_state_path() -> PathBuf {
+    profile_dir_or_temp().join("sync-state.json")
+}
+
+/// Get audit log file path
+/// Uses the current profile's directory: <profile_dir>/audit.jsonl
+pub fn get_audit_log_path() -> PathBuf {
+    profile_dir_or_temp().join("audit.jsonl")
+}
+
+/// Get the file telling editor plugins where the editor bridge listens
+/// Always uses user data directory: <user_data_dir>/editor-bridge.json
+pub fn get_editor_bridge_path() -> PathBuf {
+    user_data_dir_or_temp().join("editor-bridge.json")
+}
+
+/// Get the directory for pasted images and other uploaded files
+/// Uses <user_data_dir>/uploads when the data directory was relocated, the
+/// system temp directory otherwise
+pub fn get_uploads_dir() -> PathBuf {
+    if !is_data_dir_relocated() {
+        return std::env::temp_dir();
+    }
+    let dir = user_data_dir_or_temp().join("uploads");
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::warn!("Failed to create uploads directory {:?}: {}", dir, e);
+        return std::env::temp_dir();
+    }
+    dir
+}
+
+/// Open the log file, <user_data_dir>/logs/agentx.log
+/// Only used when the data directory was relocated; logs go to stdout otherwise
+pub fn open_log_file() -> Option<std::fs::File> {
+    if !is_data_dir_relocated() {
+        return None;
+    }
+    let dir = user_data_dir_or_temp().join("logs");
+    let file = std::fs::create_dir_all(&dir).and_then(|_| {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join("agentx.log"))
+    });
+    match file {
+        Ok(file) => Some(file),
+        Err(e) => {
+            eprintln!("Failed to open log file in {}: {}", dir.display(), e);
+            None
+        }
+    }
+}
+
+/// Remember the config file loaded at startup, for `${config_dir}` in agent configs
+pub fn set_config_path(path: PathBuf) {
+    let path = absolute_dir(path, &std::env::current_dir().unwrap_or_default());
+    if CONFIG_PATH.set(path).is_err() {
+        log::warn!("Config path was already set");
+    }
+}
+
+/// Folder of the config file loaded at startup, or of the profile's config.json
+pub fn get_config_dir() -> PathBuf {
+    CONFIG_PATH
+        .get()
+        .and_then(|path| path.parent())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(profile_dir_or_temp)
+}
+
+/// Follow the active workspace, for `${workspace}` in agent configs
+pub fn set_workspace_dir(dir: PathBuf) {
+    *WORKSPACE_DIR.write().unwrap() = Some(dir);
+}
+
+/// Values of the variables in agent configs; `${workspace}` is the working
+/// directory until a workspace is active
+pub fn template_vars() -> TemplateVars {
+    let workspace = WORKSPACE_DIR.read().unwrap().clone();
+    TemplateVars {
+        workspace: workspace.unwrap_or_else(|| std::env::current_dir().unwrap_or_default()),
+        config_dir: get_config_dir(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absolute_dir() {
+        let cwd = PathBuf::from("/work/repo");
+        assert_eq!(
+            absolute_dir(PathBuf::from(".agentx"), &cwd),
+            cwd.join(".agentx")
+        );
+        assert_eq!(
+            absolute_dir(PathBuf::from("/media/usb/agentx"), &cwd),
+            PathBuf::from("/media/usb/agentx")
+        );
+    }
 }
@@ -0,0 +1,345 @@
+//! Crash reports for panics
+//!
+//! The panic hook first writes what the PersistenceService still holds in
+//! memory to the session histories, then saves a report with the app version,
+//! the open sessions, the last log lines and a backtrace to
+//! `<data_dir>/crashes`. The next launch offers to open the report or submit
+//! it as an issue, once; offered reports are renamed to `*.seen.md`.
+
+use std::collections::VecDeque;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+
+use chrono::{DateTime, Local};
+
+use crate::core::config_manager;
+use crate::core::services::{AgentService, PersistenceService};
+
+/// Log lines kept for a report
+pub const LOG_TAIL_LINES: usize = 200;
+
+/// Where reports are submitted
+pub const ISSUES_URL: &str = "https://github.com/sxhxliang/agent-studio/issues/new";
+
+/// Longest part of a report put into an issue URL; browsers refuse longer ones
+const MAX_ISSUE_BODY_CHARS: usize = 6000;
+
+const PANIC_PREFIX: &str = "- Panic: ";
+
+static LOG_TAIL: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static PERSISTENCE_SERVICE: RwLock<Option<Weak<PersistenceService>>> = RwLock::new(None);
+static AGENT_SERVICE: RwLock<Option<Weak<AgentService>>> = RwLock::new(None);
+/// Set by the first panic, so that panics while reporting or on other
+/// threads do not write more reports
+static REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Install the panic hook; the previous hook still runs after the report
+pub fn install() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if !REPORTED.swap(true, Ordering::SeqCst) {
+            report(info);
+        }
+        previous(info);
+    }));
+}
+
+/// Flush `service` before writing a report
+pub fn watch_persistence_service(service: &Arc<PersistenceService>) {
+    *PERSISTENCE_SERVICE.write().unwrap() = Some(Arc::downgrade(service));
+}
+
+/// List the sessions of `service` in reports
+pub fn watch_agent_service(service: &Arc<AgentService>) {
+    *AGENT_SERVICE.write().unwrap() = Some(Arc::downgrade(service));
+}
+
+/// Writer for a tracing layer, keeping the last `LOG_TAIL_LINES` log lines
+pub struct LogTail;
+
+impl std::io::Write for LogTail {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Ok(mut tail) = LOG_TAIL.lock() {
+            push_lines(&mut tail, &String::from_utf8_lossy(buf), LOG_TAIL_LINES);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn push_lines(tail: &mut VecDeque<String>, text: &str, max_lines: usize) {
+    tail.extend(
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::to_string),
+    );
+    while tail.len() > max_lines {
+        tail.pop_front();
+    }
+}
+
+/// Service behind `slot`, unless another thread is replacing it
+fn watched<T>(slot: &RwLock<Option<Weak<T>>>) -> Option<Arc<T>> {
+    slot.try_read().ok()?.as_ref()?.upgrade()
+}
+
+fn report(info: &PanicHookInfo) {
+    if let Some(service) = watched(&PERSISTENCE_SERVICE) {
+        let flushed = service.flush_all_blocking();
+        eprintln!("Flushed pending updates of {} sessions", flushed);
+    }
+    let report = CrashReport::capture(info);
+    match write_report(&crash_dir(), &report) {
+        Ok(path) => eprintln!("Crash report written to {}", path.display()),
+        Err(e) => eprintln!("Failed to write crash report: {}", e),
+    }
+}
+
+/// Directory crash reports are written to
+pub fn crash_dir() -> PathBuf {
+    config_manager::user_data_dir_or_temp().join("crashes")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrashReport {
+    pub version: String,
+    pub time: DateTime<Local>,
+    pub thread: String,
+    pub message: String,
+    /// `file:line:column` of the panic
+    pub location: Option<String>,
+    /// `agent session_id (status)` of each open session
+    pub sessions: Vec<String>,
+    pub log_tail: Vec<String>,
+    pub backtrace: String,
+}
+
+impl CrashReport {
+    fn capture(info: &PanicHookInfo) -> Self {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let sessions = watched(&AGENT_SERVICE)
+            .and_then(|service| service.try_list_sessions())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|session| {
+                format!(
+                    "{} {} ({:?})",
+                    session.agent_name, session.session_id, session.status
+                )
+            })
+            .collect();
+        let log_tail = match LOG_TAIL.try_lock() {
+            Ok(tail) => tail.iter().cloned().collect(),
+            Err(_) => Vec::new(),
+        };
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            time: Local::now(),
+            thread: std::thread::current()
+                .name()
+                .unwrap_or("<unnamed>")
+                .to_string(),
+            message,
+            location: info.location().map(|location| location.to_string()),
+            sessions,
+            log_tail,
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        }
+    }
+
+    /// The report as Markdown, ready to paste into an issue
+    pub fn render(&self) -> String {
+        let mut out = String::from("# AgentX crash report\n\n");
+        out.push_str(&format!("- Version: {}\n", self.version));
+        out.push_str(&format!("- Time: {}\n", self.time.to_rfc3339()));
+        out.push_str(&format!(
+            "- OS: {} {}\n",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ));
+        out.push_str(&format!("- Thread: {}\n", self.thread));
+        out.push_str(&format!("{}{}\n", PANIC_PREFIX, first_line(&self.message)));
+        if let Some(location) = &self.location {
+            out.push_str(&format!("- Location: {}\n", location));
+        }
+
+        out.push_str("\n## Open sessions\n\n");
+        if self.sessions.is_empty() {
+            out.push_str("None\n");
+        }
+        for session in &self.sessions {
+            out.push_str(&format!("- {}\n", session));
+        }
+
+        out.push_str(&format!(
+            "\n## Log (last {} lines)\n\n```\n",
+            self.log_tail.len()
+        ));
+        for line in &self.log_tail {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str("```\n\n## Backtrace\n\n```\n");
+        out.push_str(self.backtrace.trim_end());
+        out.push_str("\n```\n");
+        out
+    }
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+/// Save `report` in `dir`, returning its path
+pub fn write_report(dir: &Path, report: &CrashReport) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("crash-{}.md", report.time.format("%Y%m%d-%H%M%S")));
+    std::fs::write(&path, report.render())?;
+    Ok(path)
+}
+
+/// The newest report in the crash directory not offered yet, see
+/// [`take_pending_report_in`]
+pub fn take_pending_report() -> Option<PathBuf> {
+    take_pending_report_in(&crash_dir())
+}
+
+/// The newest report in `dir` not offered yet; every pending report is
+/// marked as offered, and the returned path points to the renamed file
+pub fn take_pending_report_in(dir: &Path) -> Option<PathBuf> {
+    let mut pending: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with("crash-")
+                        && name.ends_with(".md")
+                        && !name.ends_with(".seen.md")
+                })
+        })
+        .collect();
+    // Names hold the time, so they sort by it
+    pending.sort();
+
+    let mut newest = None;
+    for path in pending {
+        let seen = path.with_extension("seen.md");
+        match std::fs::rename(&path, &seen) {
+            Ok(()) => newest = Some(seen),
+            Err(e) => log::warn!("Failed to mark {} as seen: {}", path.display(), e),
+        }
+    }
+    newest
+}
+
+/// URL opening a new issue prefilled with `report`, cut to fit in a URL
+pub fn issue_url(report: &str) -> String {
+    let panic = report
+        .lines()
+        .find_map(|line| line.strip_prefix(PANIC_PREFIX))
+        .unwrap_or("unknown panic");
+    let title = format!("Crash: {}", panic);
+    let body = if report.chars().count() > MAX_ISSUE_BODY_CHARS {
+        let cut: String = report.chars().take(MAX_ISSUE_BODY_CHARS).collect();
+        format!("{}\n\n(cut; the full report is attached)", cut)
+    } else {
+        report.to_string()
+    };
+    format!(
+        "{}?title={}&body={}",
+        ISSUES_URL,
+        percent_encode(&title),
+        percent_encode(&body)
+    )
+}
+
+fn percent_encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> CrashReport {
+        CrashReport {
+            version: "0.5.0".to_string(),
+            time: DateTime::parse_from_rfc3339("2026-03-02T10:00:00+00:00")
+                .unwrap()
+                .with_timezone(&Local),
+            thread: "main".to_string(),
+            message: "index out of bounds".to_string(),
+            location: Some("src/lib.rs:1:2".to_string()),
+            sessions: vec!["claude s-1 (InProgress)".to_string()],
+            log_tail: vec!["INFO started".to_string()],
+            backtrace: "0: main".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_push_lines() {
+        let mut tail = VecDeque::new();
+        push_lines(&mut tail, "a\n\nb\n", 3);
+        push_lines(&mut tail, "c\nd\n", 3);
+        assert_eq!(tail, VecDeque::from(["b", "c", "d"].map(String::from)));
+    }
+
+    #[test]
+    fn test_render_and_issue_url() {
+        let rendered = report().render();
+        assert!(rendered.contains("- Panic: index out of bounds\n"));
+        assert!(rendered.contains("- claude s-1 (InProgress)\n"));
+        assert!(rendered.contains("```\nINFO started\n```"));
+
+        let url = issue_url(&rendered);
+        assert!(url.starts_with(&format!(
+            "{}?title=Crash%3A%20index%20out%20of%20bounds&body=%23%20AgentX",
+            ISSUES_URL
+        )));
+        let long = issue_url(&"x".repeat(MAX_ISSUE_BODY_CHARS * 2));
+        assert!(long.contains("title=Crash%3A%20unknown%20panic"));
+        assert!(long.len() < MAX_ISSUE_BODY_CHARS + 200);
+    }
+
+    #[test]
+    fn test_take_pending_report() {
+        let dir = std::env::temp_dir().join(format!("agentx-crashes-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(take_pending_report_in(&dir), None);
+
+        let mut older = report();
+        older.time -= chrono::Duration::hours(1);
+        write_report(&dir, &older).unwrap();
+        let newest = write_report(&dir, &report()).unwrap();
+        std::fs::write(dir.join("notes.md"), "").unwrap();
+
+        let taken = take_pending_report_in(&dir).unwrap();
+        assert_eq!(taken, newest.with_extension("seen.md"));
+        assert!(taken.exists());
+        assert_eq!(take_pending_report_in(&dir), None);
+        _ = std::fs::remove_dir_all(&dir);
+    }
+}
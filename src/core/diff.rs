@@ -0,0 +1,534 @@
+//! Line diffs of file edits
+//!
+//! The diff views share this engine: the lines of a diff with their numbers,
+//! runs of unchanged lines collapsed down to some context, the same lines
+//! paired up for a side-by-side view, and the edits that agents describe
+//! only in a tool call's arguments.
+
+use std::path::PathBuf;
+
+use agent_client_protocol::Diff;
+use serde_json::{Map, Value};
+use similar::{ChangeTag, TextDiff};
+
+/// Represents a single line in a diff view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Unchanged line (context)
+    Context {
+        line: String,
+        old_num: usize,
+        new_num: usize,
+    },
+    /// Line added in new version
+    Insert { line: String, new_num: usize },
+    /// Line deleted from old version
+    Delete { line: String, old_num: usize },
+}
+
+/// Represents a display item in the diff view (can be a line or a collapsed section)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffDisplayItem {
+    /// A regular diff line
+    Line(DiffLine),
+    /// A collapsed section of unchanged lines
+    Collapsed {
+        start_old: usize,
+        start_new: usize,
+        count: usize,
+    },
+}
+
+/// Lines added and removed by an edit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Lines of the diff from `old_text` to `new_text`; every line is an
+/// insertion for a new file (`None`), and there are none when the texts are
+/// the same
+pub fn diff_lines(old_text: Option<&str>, new_text: &str) -> Vec<DiffLine> {
+    let Some(old_text) = old_text else {
+        return new_text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| DiffLine::Insert {
+                line: line.to_string(),
+                new_num: i + 1,
+            })
+            .collect();
+    };
+    if old_text == new_text {
+        return Vec::new();
+    }
+
+    let diff = TextDiff::from_lines(old_text, new_text);
+    let mut result = Vec::new();
+    let mut old_line_num = 1;
+    let mut new_line_num = 1;
+
+    for change in diff.iter_all_changes() {
+        // Remove trailing newlines
+        let line = change.value().trim_end_matches('\n').to_string();
+
+        match change.tag() {
+            ChangeTag::Equal => {
+                result.push(DiffLine::Context {
+                    line,
+                    old_num: old_line_num,
+                    new_num: new_line_num,
+                });
+                old_line_num += 1;
+                new_line_num += 1;
+            }
+            ChangeTag::Delete => {
+                result.push(DiffLine::Delete {
+                    line,
+                    old_num: old_line_num,
+                });
+                old_line_num += 1;
+            }
+            ChangeTag::Insert => {
+                result.push(DiffLine::Insert {
+                    line,
+                    new_num: new_line_num,
+                });
+                new_line_num += 1;
+            }
+        }
+    }
+
+    result
+}
+
+/// Lines added and removed from `old_text` to `new_text`
+pub fn diff_stats(old_text: Option<&str>, new_text: &str) -> DiffStats {
+    let Some(old_text) = old_text else {
+        return DiffStats {
+            additions: new_text.lines().count(),
+            deletions: 0,
+        };
+    };
+    let mut stats = DiffStats::default();
+    for change in TextDiff::from_lines(old_text, new_text).iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => stats.additions += 1,
+            ChangeTag::Delete => stats.deletions += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+    stats
+}
+
+/// Only show changed lines with `context_lines` lines of context
+/// before/after; longer unchanged runs between changes are collapsed, and
+/// those at the start and end of the file too with `edge_collapsed`
+pub fn collapse_context(
+    diff_lines: &[DiffLine],
+    context_lines: usize,
+    edge_collapsed: bool,
+) -> Vec<DiffDisplayItem> {
+    let min_collapse_size = context_lines * 2 + 1; // Minimum lines to collapse
+
+    let mut display_items: Vec<DiffDisplayItem> = Vec::new();
+    let mut context_buffer: Vec<DiffLine> = Vec::new();
+    let mut last_change_index: Option<usize> = None;
+
+    for (i, line) in diff_lines.iter().enumerate() {
+        match line {
+            DiffLine::Context { .. } => {
+                // Accumulate context lines
+                context_buffer.push(line.clone());
+            }
+            DiffLine::Insert { .. } | DiffLine::Delete { .. } => {
+                // Found a change - process buffered context
+                if !context_buffer.is_empty() {
+                    if let Some(last_idx) = last_change_index {
+                        // There was a previous change
+                        let distance = i - last_idx - 1;
+
+                        if distance >= min_collapse_size {
+                            // Show context_lines after previous change
+                            for ctx in context_buffer.iter().take(context_lines) {
+                                display_items.push(DiffDisplayItem::Line(ctx.clone()));
+                            }
+
+                            // Collapse the middle
+                            let collapsed_count = distance - context_lines * 2;
+                            if collapsed_count > 0 {
+                                if let DiffLine::Context {
+                                    old_num, new_num, ..
+                                } = &context_buffer[context_lines]
+                                {
+                                    display_items.push(DiffDisplayItem::Collapsed {
+                                        start_old: *old_num,
+                                        start_new: *new_num,
+                                        count: collapsed_count,
+                                    });
+                                }
+                            }
+
+                            // Show context_lines before current change
+                            let start = context_buffer.len().saturating_sub(context_lines);
+                            for ctx in context_buffer.iter().skip(start) {
+                                display_items.push(DiffDisplayItem::Line(ctx.clone()));
+                            }
+                        } else {
+                            // Distance is small, show all context
+                            for ctx in &context_buffer {
+                                display_items.push(DiffDisplayItem::Line(ctx.clone()));
+                            }
+                        }
+                    } else {
+                        // This is the first change
+                        if context_buffer.len() > context_lines {
+                            // Collapse leading context, only show last context_lines
+                            if edge_collapsed {
+                                let collapsed_count = context_buffer.len() - context_lines;
+                                if let DiffLine::Context {
+                                    old_num, new_num, ..
+                                } = &context_buffer[0]
+                                {
+                                    display_items.push(DiffDisplayItem::Collapsed {
+                                        start_old: *old_num,
+                                        start_new: *new_num,
+                                        count: collapsed_count,
+                                    });
+                                }
+                            }
+
+                            let start = context_buffer.len() - context_lines;
+                            for ctx in context_buffer.iter().skip(start) {
+                                display_items.push(DiffDisplayItem::Line(ctx.clone()));
+                            }
+                        } else {
+                            // Show all leading context
+                            for ctx in &context_buffer {
+                                display_items.push(DiffDisplayItem::Line(ctx.clone()));
+                            }
+                        }
+                    }
+
+                    context_buffer.clear();
+                }
+
+                // Add the change line
+                display_items.push(DiffDisplayItem::Line(line.clone()));
+                last_change_index = Some(i);
+            }
+        }
+    }
+
+    // Handle trailing context
+    if !context_buffer.is_empty() {
+        if context_buffer.len() > context_lines {
+            // Show first context_lines, collapse the rest
+            for ctx in context_buffer.iter().take(context_lines) {
+                display_items.push(DiffDisplayItem::Line(ctx.clone()));
+            }
+            if edge_collapsed {
+                let collapsed_count = context_buffer.len() - context_lines;
+                if let DiffLine::Context {
+                    old_num, new_num, ..
+                } = &context_buffer[context_lines]
+                {
+                    display_items.push(DiffDisplayItem::Collapsed {
+                        start_old: *old_num,
+                        start_new: *new_num,
+                        count: collapsed_count,
+                    });
+                }
+            }
+        } else {
+            // Show all trailing context
+            for ctx in &context_buffer {
+                display_items.push(DiffDisplayItem::Line(ctx.clone()));
+            }
+        }
+    }
+
+    display_items
+}
+
+/// One side of a row in a side-by-side diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitCell {
+    pub num: usize,
+    pub line: String,
+}
+
+/// A row of a side-by-side diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SplitRow {
+    /// The old line on the left and the new one on the right; a side is
+    /// empty where lines were only added or only removed
+    Line {
+        old: Option<SplitCell>,
+        new: Option<SplitCell>,
+        changed: bool,
+    },
+    /// A collapsed section of unchanged lines
+    Collapsed {
+        start_old: usize,
+        start_new: usize,
+        count: usize,
+    },
+}
+
+/// `items` as side-by-side rows: unchanged lines face themselves, and each
+/// run of removed lines faces the lines added in their place
+pub fn split_rows(items: &[DiffDisplayItem]) -> Vec<SplitRow> {
+    let mut rows = Vec::new();
+    let mut deleted: Vec<SplitCell> = Vec::new();
+    let mut inserted: Vec<SplitCell> = Vec::new();
+
+    for item in items {
+        match item {
+            DiffDisplayItem::Line(DiffLine::Delete { line, old_num }) => {
+                // Removals after additions start another run
+                if !inserted.is_empty() {
+                    push_changed_rows(&mut rows, &mut deleted, &mut inserted);
+                }
+                deleted.push(SplitCell {
+                    num: *old_num,
+                    line: line.clone(),
+                });
+            }
+            DiffDisplayItem::Line(DiffLine::Insert { line, new_num }) => {
+                inserted.push(SplitCell {
+                    num: *new_num,
+                    line: line.clone(),
+                });
+            }
+            DiffDisplayItem::Line(DiffLine::Context {
+                line,
+                old_num,
+                new_num,
+            }) => {
+                push_changed_rows(&mut rows, &mut deleted, &mut inserted);
+                rows.push(SplitRow::Line {
+                    old: Some(SplitCell {
+                        num: *old_num,
+                        line: line.clone(),
+                    }),
+                    new: Some(SplitCell {
+                        num: *new_num,
+                        line: line.clone(),
+                    }),
+                    changed: false,
+                });
+            }
+            DiffDisplayItem::Collapsed {
+                start_old,
+                start_new,
+                count,
+            } => {
+                push_changed_rows(&mut rows, &mut deleted, &mut inserted);
+                rows.push(SplitRow::Collapsed {
+                    start_old: *start_old,
+                    start_new: *start_new,
+                    count: *count,
+                });
+            }
+        }
+    }
+    push_changed_rows(&mut rows, &mut deleted, &mut inserted);
+    rows
+}
+
+fn push_changed_rows(
+    rows: &mut Vec<SplitRow>,
+    deleted: &mut Vec<SplitCell>,
+    inserted: &mut Vec<SplitCell>,
+) {
+    let count = deleted.len().max(inserted.len());
+    let mut deleted = deleted.drain(..);
+    let mut inserted = inserted.drain(..);
+    for _ in 0..count {
+        rows.push(SplitRow::Line {
+            old: deleted.next(),
+            new: inserted.next(),
+            changed: true,
+        });
+    }
+}
+
+/// The edits a tool call's raw input describes, as diffs of the replaced
+/// text
+///
+/// Agents that send no diff content often describe an edit by its
+/// arguments: `file_path` (or `path`) with `old_string` and `new_string`
+/// (or `old_text` and `new_text`), or a list of these pairs in `edits`. Line
+/// numbers of the diffs count from the start of the replaced text.
+pub fn edit_diffs(raw_input: &Value) -> Vec<Diff> {
+    let Some(input) = raw_input.as_object() else {
+        return Vec::new();
+    };
+    let Some(path) = text_field(input, &["file_path", "path"]) else {
+        return Vec::new();
+    };
+    let edits: Vec<&Map<String, Value>> = match input.get("edits").and_then(Value::as_array) {
+        Some(edits) => edits.iter().filter_map(Value::as_object).collect(),
+        None => vec![input],
+    };
+    edits
+        .into_iter()
+        .filter_map(|edit| {
+            let old_text = text_field(edit, &["old_string", "old_text"])?;
+            let new_text = text_field(edit, &["new_string", "new_text"])?;
+            Some(Diff::new(PathBuf::from(path), new_text).old_text(Some(old_text.to_string())))
+        })
+        .collect()
+}
+
+/// The first of `keys` in `object` with a string value
+fn text_field<'a>(object: &'a Map<String, Value>, keys: &[&str]) -> Option<&'a str> {
+    keys.iter().find_map(|key| object.get(*key)?.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn context(line: &str, old_num: usize, new_num: usize) -> DiffLine {
+        DiffLine::Context {
+            line: line.to_string(),
+            old_num,
+            new_num,
+        }
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let lines = diff_lines(Some("a\nb\nc\n"), "a\nB\nc\nd\n");
+        assert_eq!(
+            lines,
+            vec![
+                context("a", 1, 1),
+                DiffLine::Delete {
+                    line: "b".to_string(),
+                    old_num: 2
+                },
+                DiffLine::Insert {
+                    line: "B".to_string(),
+                    new_num: 2
+                },
+                context("c", 3, 3),
+                DiffLine::Insert {
+                    line: "d".to_string(),
+                    new_num: 4
+                },
+            ]
+        );
+        assert!(diff_lines(Some("same\n"), "same\n").is_empty());
+        assert_eq!(diff_lines(None, "x\ny").len(), 2);
+        assert_eq!(
+            diff_stats(Some("a\nb\nc\n"), "a\nB\nc\nd\n"),
+            DiffStats {
+                additions: 2,
+                deletions: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_collapse_context() {
+        let old: String = (1..=20).map(|n| format!("{}\n", n)).collect();
+        let new: String = (1..=20)
+            .map(|n| match n {
+                3 => "three\n".to_string(),
+                18 => "eighteen\n".to_string(),
+                n => format!("{}\n", n),
+            })
+            .collect();
+        let items = collapse_context(&diff_lines(Some(&old), &new), 2, true);
+
+        let collapsed: Vec<_> = items
+            .iter()
+            .filter_map(|item| match item {
+                DiffDisplayItem::Collapsed {
+                    start_old, count, ..
+                } => Some((*start_old, *count)),
+                DiffDisplayItem::Line(_) => None,
+            })
+            .collect();
+        // Lines 6 to 15 sit between the two changes' context
+        assert_eq!(collapsed, vec![(6, 10)]);
+        assert_eq!(items.len(), 13);
+    }
+
+    #[test]
+    fn test_split_rows() {
+        let items: Vec<_> = diff_lines(Some("a\nb\nc\nd\n"), "a\nB\nd\ne\n")
+            .into_iter()
+            .map(DiffDisplayItem::Line)
+            .collect();
+        let rows = split_rows(&items);
+        let cell = |num, line: &str| {
+            Some(SplitCell {
+                num,
+                line: line.to_string(),
+            })
+        };
+        assert_eq!(
+            rows,
+            vec![
+                SplitRow::Line {
+                    old: cell(1, "a"),
+                    new: cell(1, "a"),
+                    changed: false
+                },
+                SplitRow::Line {
+                    old: cell(2, "b"),
+                    new: cell(2, "B"),
+                    changed: true
+                },
+                SplitRow::Line {
+                    old: cell(3, "c"),
+                    new: None,
+                    changed: true
+                },
+                SplitRow::Line {
+                    old: cell(4, "d"),
+                    new: cell(3, "d"),
+                    changed: false
+                },
+                SplitRow::Line {
+                    old: None,
+                    new: cell(4, "e"),
+                    changed: true
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_edit_diffs() {
+        let single = json!({
+            "file_path": "/src/main.rs",
+            "old_string": "let a = 1;",
+            "new_string": "let a = 2;",
+        });
+        let diffs = edit_diffs(&single);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].path, PathBuf::from("/src/main.rs"));
+        assert_eq!(diffs[0].old_text.as_deref(), Some("let a = 1;"));
+        assert_eq!(diffs[0].new_text, "let a = 2;");
+
+        let multi = json!({
+            "path": "lib.rs",
+            "edits": [
+                { "old_text": "a", "new_text": "b" },
+                { "old_text": "c" },
+                { "old_text": "d", "new_text": "e" },
+            ],
+        });
+        assert_eq!(edit_diffs(&multi).len(), 2);
+
+        assert!(edit_diffs(&json!({ "file_path": "a.rs", "content": "new" })).is_empty());
+        assert!(edit_diffs(&json!({ "command": "ls" })).is_empty());
+    }
+}
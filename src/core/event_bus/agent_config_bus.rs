@@ -280,6 +280,9 @@ mod tests {
             command: "test-command".to_string(),
             args: vec![],
             env: HashMap::new(),
+            max_concurrent_sessions: None,
+            network: Default::default(),
+            display: Default::default(),
             nodejs_path: None,
         };
 
@@ -8,6 +8,9 @@ use crate::app::actions::AddCodeSelection;
 #[derive(Clone, Debug)]
 pub struct CodeSelectionEvent {
     pub selection: AddCodeSelection,
+    /// Only the conversation of this session takes the selection (e.g. one
+    /// sent by an external editor); all panels do when unset
+    pub session_id: Option<String>,
 }
 
 /// Event bus for broadcasting code selection events
@@ -61,6 +64,7 @@ pub type CodeSelectionBusContainer = Arc<Mutex<CodeSelectionBus>>;
 /// * `entity` - The panel entity that will receive code selections
 /// * `bus_container` - The global CodeSelectionBus container
 /// * `panel_name` - Name for logging (e.g., "WelcomePanel", "ConversationPanel")
+/// * `session_id` - Session of the panel; selections for other sessions are skipped
 /// * `on_selection` - Callback to handle the code selection (receives mutable reference to panel)
 /// * `cx` - GPUI App context
 ///
@@ -70,6 +74,7 @@ pub type CodeSelectionBusContainer = Arc<Mutex<CodeSelectionBus>>;
 ///     &entity,
 ///     bus_container,
 ///     "MyPanel",
+///     None,
 ///     |panel, selection, cx| {
 ///         panel.code_selections.push(selection);
 ///         cx.notify();
@@ -81,6 +86,7 @@ pub fn subscribe_entity_to_code_selections<T, F>(
     entity: &Entity<T>,
     bus_container: CodeSelectionBusContainer,
     panel_name: &'static str,
+    session_id: Option<String>,
     on_selection: F,
     cx: &mut App,
 ) where
@@ -96,6 +102,9 @@ pub fn subscribe_entity_to_code_selections<T, F>(
         log::info!("[{}] Subscribing to CodeSelectionBus", panel_name);
 
         bus.subscribe(move |event| {
+            if event.session_id.is_some() && event.session_id != session_id {
+                return;
+            }
             log::debug!(
                 "[{}] Received selection: {}:{}~{}",
                 panel_name,
@@ -153,6 +162,7 @@ mod tests {
                 end_column: 1,
                 content: "test content".to_string(),
             },
+            session_id: None,
         });
 
         assert_eq!(received.lock().unwrap().len(), 1);
@@ -6,6 +6,7 @@ use agent_client_protocol as acp;
 use std::sync::Arc;
 
 use super::core::{EventBusContainer, SubscriptionId};
+use crate::core::agent::command_policy::CommandRefusal;
 
 /// Permission request event that can be broadcast to subscribers
 #[derive(Clone, Debug)]
@@ -20,6 +21,9 @@ pub struct PermissionRequestEvent {
     pub tool_call: acp::ToolCallUpdate,
     /// Available permission options
     pub options: Vec<acp::PermissionOption>,
+    /// Set when the command policy already refused the tool call; nothing
+    /// is left to answer and the event only informs the user
+    pub refusal: Option<CommandRefusal>,
 }
 
 /// Specialized container for permission request events
@@ -150,6 +154,7 @@ mod tests {
             agent_name: agent_name.to_string(),
             tool_call,
             options: vec![],
+            refusal: None,
         }
     }
 
@@ -6,7 +6,7 @@ use chrono::{DateTime, Utc};
 use std::sync::Arc;
 
 use super::core::{EventBusContainer, SubscriptionId};
-use crate::core::services::SessionStatus;
+use crate::core::services::{SessionStatus, TurnLatency};
 
 /// Workspace update events
 #[derive(Clone, Debug)]
@@ -18,6 +18,13 @@ pub enum WorkspaceUpdateEvent {
     },
     /// A task was updated
     TaskUpdated { task_id: String },
+    /// A task was locked (read-only) or unlocked
+    TaskLockChanged {
+        task_id: String,
+        /// The task's sessions (see `WorkspaceTask::session_ids`)
+        session_ids: Vec<String>,
+        locked: bool,
+    },
     /// A task was removed
     TaskRemoved {
         workspace_id: String,
@@ -27,6 +34,14 @@ pub enum WorkspaceUpdateEvent {
     WorkspaceAdded { workspace_id: String },
     /// A workspace was removed
     WorkspaceRemoved { workspace_id: String },
+    /// A workspace's remembered facts changed
+    MemoryUpdated { workspace_id: String },
+    /// An agent reply in a session was rated
+    FeedbackUpdated { session_id: String },
+    /// A comment was left on a message of a session
+    CommentsUpdated { session_id: String },
+    /// A session was shown in a conversation panel
+    SessionOpened { session_id: String },
     /// A session status was updated
     SessionStatusUpdated {
         session_id: String,
@@ -35,6 +50,45 @@ pub enum WorkspaceUpdateEvent {
         last_active: DateTime<Utc>,
         message_count: usize,
     },
+    /// A prompt failed transiently and is being retried
+    SessionRetrying {
+        session_id: String,
+        agent_name: String,
+        /// The attempt about to be made (2 = first retry)
+        attempt: u32,
+        max_attempts: u32,
+    },
+    /// A turn streamed more than the session's output limit and was cancelled
+    SessionOutputLimited {
+        session_id: String,
+        agent_name: String,
+        /// The limit that was exceeded, in characters
+        limit: usize,
+    },
+    /// An agent finished a turn
+    TurnCompleted {
+        session_id: String,
+        agent_name: String,
+        /// Time from sending the prompt to the end of the turn
+        busy_ms: u64,
+        prompt_chars: usize,
+        /// Characters the agent streamed (messages and thoughts)
+        output_chars: usize,
+    },
+    /// The latency of a finished turn was measured
+    TurnTimed {
+        session_id: String,
+        latency: TurnLatency,
+    },
+    /// A session's position in its agent's concurrency queue changed (0 = running)
+    SessionQueueUpdated {
+        session_id: String,
+        agent_name: String,
+        position: usize,
+    },
+    /// A new session's position in its agent's concurrency queue changed
+    /// (0 = being created)
+    SessionCreationQueued { agent_name: String, position: usize },
 }
 
 /// Specialized container for workspace update events
@@ -85,7 +139,8 @@ impl WorkspaceUpdateBusContainer {
                     workspace_id: wid, ..
                 }
                 | WorkspaceUpdateEvent::WorkspaceAdded { workspace_id: wid }
-                | WorkspaceUpdateEvent::WorkspaceRemoved { workspace_id: wid } => {
+                | WorkspaceUpdateEvent::WorkspaceRemoved { workspace_id: wid }
+                | WorkspaceUpdateEvent::MemoryUpdated { workspace_id: wid } => {
                     wid == &workspace_id
                 }
                 _ => false,
@@ -0,0 +1,396 @@
+//! MCP tool filtering for sessions
+//!
+//! ACP passes whole MCP servers to an agent, so a session cannot leave out
+//! single tools. A server with disabled tools is instead launched through
+//! `agentx mcp-proxy`, which relays its stdio and drops the disabled tools
+//! from `tools/list` results, refusing calls to them.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use agent_client_protocol as acp;
+use serde_json::{Value, json};
+
+use crate::core::config::McpServerConfig;
+
+/// First argument that runs the app as a proxy instead of opening a window
+pub const SUBCOMMAND: &str = "mcp-proxy";
+
+/// Tools a session leaves out, by MCP server name
+pub type DisabledTools = BTreeMap<String, BTreeSet<String>>;
+
+/// The MCP servers to create a session with: the `selected` enabled ones,
+/// proxied when some of their tools are disabled; none in safe mode
+pub fn session_servers(
+    available: &[(String, McpServerConfig)],
+    selected: &[String],
+    disabled_tools: &DisabledTools,
+) -> Vec<acp::McpServer> {
+    if crate::core::safe_mode::is_active() {
+        log::info!("Safe mode: starting the session without MCP servers");
+        return Vec::new();
+    }
+    let exe = std::env::current_exe();
+    available
+        .iter()
+        .filter(|(name, config)| config.enabled && selected.contains(name))
+        .map(|(name, config)| {
+            let tools = disabled_tools.get(name).filter(|tools| !tools.is_empty());
+            let config = match (tools, &exe) {
+                (Some(tools), Ok(exe)) => proxied(config, tools, exe),
+                (Some(_), Err(e)) => {
+                    log::warn!("Cannot filter tools of MCP server '{}': {}", name, e);
+                    config.clone()
+                }
+                (None, _) => config.clone(),
+            };
+            config.to_acp_mcp_server(name.clone())
+        })
+        .collect()
+}
+
+/// The selected servers and disabled tools a session was created with
+pub fn session_scope(servers: &[acp::McpServer]) -> (Vec<String>, DisabledTools) {
+    let mut selected = Vec::new();
+    let mut disabled_tools = DisabledTools::new();
+    for server in servers {
+        let Ok(server) = serde_json::to_value(server) else {
+            continue;
+        };
+        let Some(name) = server.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        selected.push(name.to_string());
+        let args: Vec<String> = server
+            .get("args")
+            .and_then(Value::as_array)
+            .map(|args| {
+                args.iter()
+                    .filter_map(|arg| arg.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(launch) = args
+            .split_first()
+            .filter(|(first, _)| first.as_str() == SUBCOMMAND)
+            .and_then(|(_, rest)| ProxyArgs::parse(rest))
+        {
+            disabled_tools.insert(name.to_string(), launch.disabled.into_iter().collect());
+        }
+    }
+    (selected, disabled_tools)
+}
+
+/// `config` launched through the proxy of `exe`
+fn proxied(config: &McpServerConfig, tools: &BTreeSet<String>, exe: &Path) -> McpServerConfig {
+    let mut args = vec![SUBCOMMAND.to_string()];
+    for tool in tools {
+        args.push("--disable".to_string());
+        args.push(tool.clone());
+    }
+    args.push("--".to_string());
+    args.push(config.command.clone());
+    args.extend(config.args.iter().cloned());
+    McpServerConfig {
+        enabled: config.enabled,
+        command: exe.to_string_lossy().to_string(),
+        args,
+        env: config.env.clone(),
+    }
+}
+
+/// `[--disable TOOL]... -- COMMAND [ARGS]...`
+#[derive(Debug, PartialEq)]
+struct ProxyArgs {
+    disabled: Vec<String>,
+    command: String,
+    args: Vec<String>,
+}
+
+impl ProxyArgs {
+    fn parse(args: &[String]) -> Option<Self> {
+        let mut disabled = Vec::new();
+        let mut args = args.iter();
+        loop {
+            match args.next()?.as_str() {
+                "--disable" => disabled.push(args.next()?.clone()),
+                "--" => break,
+                _ => return None,
+            }
+        }
+        let command = args.next()?.clone();
+        Some(Self {
+            disabled,
+            command,
+            args: args.cloned().collect(),
+        })
+    }
+}
+
+/// Where a message from the client goes
+#[derive(Debug, PartialEq)]
+enum Route {
+    Server(String),
+    /// Answered by the proxy itself
+    Client(String),
+}
+
+/// Rewrites the messages of one proxied server
+struct ToolFilter {
+    disabled: HashSet<String>,
+    /// Ids of `tools/list` requests waiting for their response
+    list_requests: HashSet<String>,
+}
+
+impl ToolFilter {
+    fn new(disabled: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            disabled: disabled.into_iter().collect(),
+            list_requests: HashSet::new(),
+        }
+    }
+
+    fn client_message(&mut self, line: &str) -> Route {
+        let Ok(message) = serde_json::from_str::<Value>(line) else {
+            return Route::Server(line.to_string());
+        };
+        let Some(id) = message.get("id") else {
+            return Route::Server(line.to_string());
+        };
+        match message.get("method").and_then(Value::as_str) {
+            Some("tools/list") => {
+                self.list_requests.insert(id.to_string());
+            }
+            Some("tools/call") => {
+                let tool = message.pointer("/params/name").and_then(Value::as_str);
+                if let Some(tool) = tool.filter(|tool| self.disabled.contains(*tool)) {
+                    let refusal = json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": {
+                            "code": -32602,
+                            "message": format!("Tool '{}' is disabled for this session", tool),
+                        },
+                    });
+                    return Route::Client(refusal.to_string());
+                }
+            }
+            _ => {}
+        }
+        Route::Server(line.to_string())
+    }
+
+    fn server_message(&mut self, line: &str) -> String {
+        let Ok(mut message) = serde_json::from_str::<Value>(line) else {
+            return line.to_string();
+        };
+        let is_list_response = message.get("method").is_none()
+            && message
+                .get("id")
+                .is_some_and(|id| self.list_requests.remove(&id.to_string()));
+        if !is_list_response {
+            return line.to_string();
+        }
+        let Some(tools) = message
+            .pointer_mut("/result/tools")
+            .and_then(Value::as_array_mut)
+        else {
+            return line.to_string();
+        };
+        tools.retain(|tool| {
+            tool.get("name")
+                .and_then(Value::as_str)
+                .is_none_or(|name| !self.disabled.contains(name))
+        });
+        message.to_string()
+    }
+}
+
+/// Run `agentx mcp-proxy [--disable TOOL]... -- COMMAND [ARGS]...` until the
+/// client or the server closes; returns the exit code
+pub fn run(args: impl Iterator<Item = String>) -> i32 {
+    let args: Vec<String> = args.collect();
+    let Some(launch) = ProxyArgs::parse(&args) else {
+        eprintln!(
+            "Usage: agentx {} [--disable TOOL]... -- COMMAND [ARGS]...",
+            SUBCOMMAND
+        );
+        return 2;
+    };
+
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(&launch.command);
+        command
+    } else {
+        Command::new(&launch.command)
+    };
+    let mut child = match command
+        .args(&launch.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Failed to start {}: {}", launch.command, e);
+            return 1;
+        }
+    };
+    let (Some(mut server_in), Some(server_out)) = (child.stdin.take(), child.stdout.take()) else {
+        eprintln!("{} has no stdio", launch.command);
+        return 1;
+    };
+
+    let filter = Arc::new(Mutex::new(ToolFilter::new(launch.disabled)));
+    let client_out = Arc::new(Mutex::new(std::io::stdout()));
+
+    let relay = {
+        let filter = filter.clone();
+        let client_out = client_out.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(server_out).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let line = filter.lock().unwrap().server_message(&line);
+                let mut client_out = client_out.lock().unwrap();
+                if writeln!(client_out, "{}", line)
+                    .and_then(|_| client_out.flush())
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+    };
+
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let route = filter.lock().unwrap().client_message(&line);
+        let written = match route {
+            Route::Server(line) => writeln!(server_in, "{}", line).and_then(|_| server_in.flush()),
+            Route::Client(reply) => {
+                let mut client_out = client_out.lock().unwrap();
+                writeln!(client_out, "{}", reply).and_then(|_| client_out.flush())
+            }
+        };
+        if written.is_err() {
+            break;
+        }
+    }
+
+    // Closing its stdin asks the server to exit
+    drop(server_in);
+    let status = child.wait();
+    _ = relay.join();
+    status.ok().and_then(|status| status.code()).unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(command: &str) -> McpServerConfig {
+        McpServerConfig {
+            enabled: true,
+            command: command.to_string(),
+            args: vec!["--stdio".to_string()],
+            env: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_session_servers_and_scope() {
+        let available = vec![
+            ("files".to_string(), config("files-server")),
+            ("web".to_string(), config("web-server")),
+            ("db".to_string(), config("db-server")),
+        ];
+        let selected = vec!["files".to_string(), "web".to_string()];
+        let disabled_tools = DisabledTools::from([(
+            "files".to_string(),
+            BTreeSet::from(["delete".to_string(), "write".to_string()]),
+        )]);
+
+        let servers = session_servers(&available, &selected, &disabled_tools);
+        assert_eq!(servers.len(), 2);
+        let (scope, tools) = session_scope(&servers);
+        assert_eq!(scope, selected);
+        assert_eq!(tools, disabled_tools);
+
+        let files = serde_json::to_value(&servers[0]).unwrap();
+        let args: Vec<&str> = files["args"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        assert_eq!(
+            args,
+            vec![
+                SUBCOMMAND,
+                "--disable",
+                "delete",
+                "--disable",
+                "write",
+                "--",
+                "files-server",
+                "--stdio"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_proxy_args() {
+        let args: Vec<String> = ["--disable", "a", "--", "npx", "-y", "server"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            ProxyArgs::parse(&args),
+            Some(ProxyArgs {
+                disabled: vec!["a".to_string()],
+                command: "npx".to_string(),
+                args: vec!["-y".to_string(), "server".to_string()],
+            })
+        );
+        assert_eq!(ProxyArgs::parse(&["npx".to_string()]), None);
+        assert_eq!(ProxyArgs::parse(&["--".to_string()]), None);
+    }
+
+    #[test]
+    fn test_tool_filter() {
+        let mut filter = ToolFilter::new(["write".to_string()]);
+
+        let list = r#"{"jsonrpc":"2.0","id":3,"method":"tools/list","params":{}}"#;
+        assert_eq!(filter.client_message(list), Route::Server(list.to_string()));
+        let tools =
+            r#"{"jsonrpc":"2.0","id":3,"result":{"tools":[{"name":"read"},{"name":"write"}]}}"#;
+        let filtered: Value = serde_json::from_str(&filter.server_message(tools)).unwrap();
+        assert_eq!(filtered["result"]["tools"], json!([{"name": "read"}]));
+        // Only responses to `tools/list` are rewritten
+        assert_eq!(filter.server_message(tools), tools);
+
+        let call = r#"{"jsonrpc":"2.0","id":"c","method":"tools/call","params":{"name":"write"}}"#;
+        let Route::Client(refusal) = filter.client_message(call) else {
+            panic!("calls to disabled tools are refused");
+        };
+        let refusal: Value = serde_json::from_str(&refusal).unwrap();
+        assert_eq!(refusal["id"], "c");
+        assert_eq!(refusal["error"]["code"], -32602);
+
+        let allowed = r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"read"}}"#;
+        assert_eq!(
+            filter.client_message(allowed),
+            Route::Server(allowed.to_string())
+        );
+    }
+}
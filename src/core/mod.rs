@@ -2,9 +2,14 @@
 pub mod agent;
 pub mod config;
 pub mod config_manager;
+pub mod crash_report;
+pub mod diff;
 pub mod event_bus;
+pub mod mcp_proxy;
 pub mod nodejs;
+pub mod safe_mode;
 pub mod services;
 pub mod updater;
+pub mod url_scheme;
 
 // Re-export commonly used types
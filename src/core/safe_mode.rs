@@ -0,0 +1,132 @@
+//! Safe mode, for recovering from a bad config or state file
+//!
+//! Every launch counts itself in the profile's `startup.json` and clears the
+//! count once it ran for `STABLE_AFTER` or quit normally, so a count left
+//! over means the launches before crashed while starting. After
+//! `CRASH_THRESHOLD` of them the next launch offers safe mode; `--safe-mode`
+//! turns it on directly.
+//!
+//! Safe mode registers the agents as hibernated instead of spawning them, so
+//! each one starts only when a session needs it, leaves out the warm pool and
+//! the MCP servers of new sessions, and opens the default layout instead of
+//! the saved one.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+
+use crate::core::config_manager;
+
+/// Command line flag that starts in safe mode
+pub const FLAG: &str = "--safe-mode";
+
+/// Unfinished launches in a row after which safe mode is offered
+pub const CRASH_THRESHOLD: u32 = 2;
+
+/// A launch that ran this long counts as started
+pub const STABLE_AFTER: Duration = Duration::from_secs(30);
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StartupRecord {
+    /// Launches in a row that did not finish starting
+    unfinished: u32,
+}
+
+/// Whether this process runs in safe mode
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Run in safe mode; called once at startup
+pub fn activate() {
+    log::warn!("Starting in safe mode");
+    ACTIVE.store(true, Ordering::Relaxed);
+}
+
+/// Whether `unfinished` launches in a row call for offering safe mode
+pub fn should_offer(unfinished: u32) -> bool {
+    unfinished >= CRASH_THRESHOLD
+}
+
+/// Ask whether to start in safe mode after `unfinished` launches crashed;
+/// a native dialog, as no window is open yet
+pub fn ask(unfinished: u32) -> bool {
+    let answer = rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title(t!("safe_mode.offer.title").to_string())
+        .set_description(t!("safe_mode.offer.message", count = unfinished).to_string())
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show();
+    matches!(answer, rfd::MessageDialogResult::Yes)
+}
+
+fn startup_file() -> PathBuf {
+    config_manager::profile_dir_or_temp().join("startup.json")
+}
+
+/// Count this launch, see [`begin_startup_at`]
+pub fn begin_startup() -> u32 {
+    begin_startup_at(&startup_file())
+}
+
+/// Count a launch in the record at `path`; returns how many launches before
+/// it did not finish starting
+pub fn begin_startup_at(path: &Path) -> u32 {
+    // An unreadable record counts as a clean one
+    let mut record: StartupRecord = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+    let unfinished = record.unfinished;
+    record.unfinished += 1;
+    write_record(path, &record);
+    unfinished
+}
+
+/// Mark this launch as started, see [`finish_startup_at`]
+pub fn finish_startup() {
+    finish_startup_at(&startup_file());
+}
+
+/// Clear the count of unfinished launches in the record at `path`
+pub fn finish_startup_at(path: &Path) {
+    write_record(path, &StartupRecord::default());
+}
+
+fn write_record(path: &Path, record: &StartupRecord) {
+    let written = serde_json::to_string(record)
+        .map_err(std::io::Error::other)
+        .and_then(|json| std::fs::write(path, json));
+    if let Err(e) = written {
+        log::warn!("Failed to write {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_startup_record() {
+        let path = std::env::temp_dir().join(format!("agentx-startup-{}.json", std::process::id()));
+        _ = std::fs::remove_file(&path);
+
+        assert_eq!(begin_startup_at(&path), 0);
+        assert_eq!(begin_startup_at(&path), 1);
+        assert!(!should_offer(1));
+        assert_eq!(begin_startup_at(&path), 2);
+        assert!(should_offer(2));
+
+        finish_startup_at(&path);
+        assert_eq!(begin_startup_at(&path), 0);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(begin_startup_at(&path), 0);
+        _ = std::fs::remove_file(&path);
+    }
+}
@@ -7,8 +7,8 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::core::agent::AgentManager;
-use crate::core::config::{AgentProcessConfig, Config};
+use crate::core::agent::{AgentDiagnosis, AgentManager, DEFAULT_DOCTOR_TIMEOUT, diagnose_agents};
+use crate::core::config::{AgentProcessConfig, Config, launch_configs, variant_agent_name};
 use crate::core::event_bus::{AgentConfigBusContainer, AgentConfigEvent};
 use crate::core::services::AgentService;
 use anyhow::{Context, Result, anyhow};
@@ -65,10 +65,14 @@ impl AgentConfigService {
         agents
     }
 
-    /// Get a specific agent's configuration
+    /// Get a specific agent's configuration; a launch variant's is the one
+    /// it runs with
     pub async fn get_agent(&self, name: &str) -> Option<AgentProcessConfig> {
         let config = self.config.read().await;
-        config.agent_servers.get(name).cloned()
+        match config.agent_servers.get(name) {
+            Some(agent) => Some(agent.clone()),
+            None => launch_configs(&config.agent_servers).remove(name),
+        }
     }
 
     /// Get the upload directory
@@ -210,6 +214,7 @@ impl AgentConfigService {
         self.agent_manager
             .add_agent(name.clone(), config.clone())
             .await?;
+        self.start_variants(&name, &config).await;
 
         // Update config
         {
@@ -233,22 +238,40 @@ impl AgentConfigService {
     }
 
     /// Update an existing agent's configuration
-    pub async fn update_agent(&self, name: &str, config: AgentProcessConfig) -> Result<()> {
+    pub async fn update_agent(&self, name: &str, mut config: AgentProcessConfig) -> Result<()> {
         // Validate command
         self.validate_command(&config.command)?;
 
         // Check if agent exists
-        {
+        let previous = {
             let current_config = self.config.read().await;
-            if !current_config.agent_servers.contains_key(name) {
+            let Some(existing) = current_config.agent_servers.get(name) else {
                 return Err(anyhow!("Agent '{}' not found", name));
+            };
+            // The settings dialog doesn't edit the session limit, network
+            // policy, display or variants; keep the configured ones
+            if config.max_concurrent_sessions.is_none() {
+                config.max_concurrent_sessions = existing.max_concurrent_sessions;
             }
-        }
+            if config.network.is_unrestricted() {
+                config.network = existing.network;
+            }
+            if config.display.is_empty() {
+                config.display = existing.display.clone();
+            }
+            if config.variants.is_empty() {
+                config.variants = existing.variants.clone();
+            }
+            existing.clone()
+        };
 
         // Restart agent with new config (hot-reload)
         self.agent_manager
             .restart_agent(name, config.clone())
             .await?;
+        self.discard_warm_session(name);
+        self.stop_variants(name, &previous).await;
+        self.start_variants(name, &config).await;
 
         // Update config
         {
@@ -274,15 +297,19 @@ impl AgentConfigService {
     /// Remove an agent
     pub async fn remove_agent(&self, name: &str) -> Result<()> {
         // Check if agent exists
-        {
+        let existing = {
             let current_config = self.config.read().await;
-            if !current_config.agent_servers.contains_key(name) {
-                return Err(anyhow!("Agent '{}' not found", name));
-            }
-        }
+            current_config
+                .agent_servers
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow!("Agent '{}' not found", name))?
+        };
 
         // Remove from AgentManager (shuts down process)
         self.agent_manager.remove_agent(name).await?;
+        self.discard_warm_session(name);
+        self.stop_variants(name, &existing).await;
 
         // Update config
         {
@@ -302,6 +329,55 @@ impl AgentConfigService {
         Ok(())
     }
 
+    /// Start the launch variants of the agent `name`
+    async fn start_variants(&self, name: &str, config: &AgentProcessConfig) {
+        for (variant_name, variant_config) in config.launch_configs(name).into_iter().skip(1) {
+            if let Err(e) = self
+                .agent_manager
+                .add_agent(variant_name.clone(), variant_config)
+                .await
+            {
+                log::warn!("Failed to start agent variant '{}': {}", variant_name, e);
+            }
+        }
+    }
+
+    /// Stop the launch variants of the agent `name`
+    async fn stop_variants(&self, name: &str, config: &AgentProcessConfig) {
+        for label in config.variants.keys() {
+            let variant_name = variant_agent_name(name, label);
+            if let Err(e) = self.agent_manager.remove_agent(&variant_name).await {
+                log::warn!("Failed to stop agent variant '{}': {}", variant_name, e);
+            }
+            self.discard_warm_session(&variant_name);
+        }
+    }
+
+    /// Drop the session parked for the agent; it belonged to the process that went away
+    fn discard_warm_session(&self, name: &str) {
+        if let Some(agent_service) = &self.agent_service {
+            agent_service.discard_warm_session(name);
+        }
+    }
+
+    // ========== Diagnostics ==========
+
+    /// Health-check every configured agent (spawn + ACP initialize)
+    ///
+    /// `nodejs_path` mirrors the runtime override from AppSettings so the probe
+    /// runs agents exactly as the manager would.
+    pub async fn diagnose_agents(&self, nodejs_path: Option<String>) -> Vec<AgentDiagnosis> {
+        let mut agents = launch_configs(&self.config.read().await.agent_servers);
+        if let Some(nodejs_path) = nodejs_path.filter(|path| !path.is_empty()) {
+            for config in agents.values_mut() {
+                config.nodejs_path = Some(nodejs_path.clone());
+            }
+        }
+        let proxy_config = self.agent_manager.get_proxy_config().await;
+
+        diagnose_agents(&agents, &proxy_config, DEFAULT_DOCTOR_TIMEOUT).await
+    }
+
     // ========== Model Configuration Operations ==========
 
     /// Add a new model configuration
@@ -598,16 +674,12 @@ impl AgentConfigService {
         Ok(())
     }
 
-    /// Restart an agent with its current configuration
+    /// Restart an agent, or a launch variant, with its current configuration
     pub async fn restart_agent(&self, name: &str) -> Result<()> {
-        let config = {
-            let current_config = self.config.read().await;
-            current_config
-                .agent_servers
-                .get(name)
-                .cloned()
-                .ok_or_else(|| anyhow!("Agent '{}' not found", name))?
-        };
+        let config = self
+            .get_agent(name)
+            .await
+            .ok_or_else(|| anyhow!("Agent '{}' not found", name))?;
 
         // Validate command before restart
         self.validate_command(&config.command)?;
@@ -616,6 +688,7 @@ impl AgentConfigService {
         self.agent_manager
             .restart_agent(name, config.clone())
             .await?;
+        self.discard_warm_session(name);
 
         log::info!("Successfully restarted agent '{}'", name);
         Ok(())
@@ -660,6 +733,45 @@ impl AgentConfigService {
         Ok(())
     }
 
+    /// Current configuration
+    pub async fn config(&self) -> Config {
+        self.config.read().await.clone()
+    }
+
+    /// Change the configuration with `update`, save it and announce the reload
+    pub async fn update_config(&self, update: impl FnOnce(&mut Config)) -> Result<()> {
+        {
+            let mut current_config = self.config.write().await;
+            update(&mut current_config);
+        }
+
+        self.save_to_file().await?;
+
+        let config = self.config.read().await;
+        self.event_bus.publish(AgentConfigEvent::ConfigReloaded {
+            config: config.clone(),
+        });
+        Ok(())
+    }
+
+    /// Set the model that serves internal features (None = the default model)
+    pub async fn set_utility_model(&self, model: Option<String>) -> Result<()> {
+        {
+            let mut current_config = self.config.write().await;
+            current_config.utility_model.model = model.clone();
+        }
+
+        self.save_to_file().await?;
+
+        let config = self.config.read().await;
+        self.event_bus.publish(AgentConfigEvent::ConfigReloaded {
+            config: config.clone(),
+        });
+
+        log::info!("Utility model set to {:?}", model);
+        Ok(())
+    }
+
     // ========== Persistence ==========
 
     /// Save configuration to file
@@ -756,6 +868,10 @@ mod tests {
             },
             args: vec![],
             env: HashMap::new(),
+            max_concurrent_sessions: None,
+            network: Default::default(),
+            display: Default::default(),
+            variants: Default::default(),
             nodejs_path: None,
         };
 
@@ -772,9 +888,23 @@ mod tests {
             models: HashMap::new(),
             mcp_servers: HashMap::new(),
             commands: HashMap::new(),
+            snippets: Vec::new(),
             system_prompts: HashMap::new(),
             tool_call_preview_max_lines: 10,
             proxy: ProxyConfig::default(),
+            agent_hibernate_after_secs: 0,
+            prompt_retry: Default::default(),
+            warm_pool: Default::default(),
+            output_guard: Default::default(),
+            indexing: Default::default(),
+            embeddings: Default::default(),
+            utility_model: Default::default(),
+            permission_policies: Vec::new(),
+            command_policy: Default::default(),
+            sync: Default::default(),
+            webhooks: Vec::new(),
+            chat_bridges: Vec::new(),
+            editor_bridge: true,
         };
 
         let _event_bus = AgentConfigBusContainer::new();
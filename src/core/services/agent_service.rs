@@ -6,16 +6,22 @@
 
 use std::{
     collections::{HashMap, hash_map::Entry},
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
     time::Duration,
 };
 
 use agent_client_protocol::{self as acp, AvailableCommand, PromptResponse};
-use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::core::agent::{AgentHandle, AgentManager};
+use super::ai_service::ChatOptions;
+use super::direct_chat::{DirectChatService, model_for_agent};
+use super::error::{ServiceError, ServiceResult};
+use super::output_guard::OutputGuard;
+use super::retry_policy::RetryPolicy;
+use super::session_limiter::{SessionLimiter, SessionSlot};
+use super::warm_pool::{WarmPool, WarmSession};
+use crate::core::agent::{AgentHandle, AgentManager, capability_names};
 use crate::core::event_bus::workspace_bus::{WorkspaceUpdateBusContainer, WorkspaceUpdateEvent};
 
 /// Agent service - manages agents and their sessions
@@ -25,6 +31,24 @@ pub struct AgentService {
     sessions: Arc<RwLock<HashMap<String, HashMap<String, AgentSessionInfo>>>>,
     /// Workspace event bus for publishing status updates
     workspace_bus: Option<WorkspaceUpdateBusContainer>,
+    /// Idle period after which an agent process is hibernated (None = never)
+    hibernate_after: Option<Duration>,
+    /// Held shared while a prompt wakes its agent and starts its turn, and exclusively while
+    /// an agent is hibernated, so no turn starts on a process being shut down
+    hibernation_lock: tokio::sync::RwLock<()>,
+    /// Per-agent locks held while waking a hibernated agent, so concurrent requests respawn
+    /// its process once
+    wake_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    /// Per-agent concurrency limiter for session creation and prompts
+    limiter: SessionLimiter,
+    /// Retry policy for transient prompt failures
+    retry_policy: RetryPolicy,
+    /// Sessions created ahead of time, handed to the next matching request
+    warm_pool: WarmPool,
+    /// Per-turn limit on streamed output
+    output_guard: OutputGuard,
+    /// Sessions of `llm:<model>` agents, which talk to a model without an ACP process
+    direct_chat: Option<Arc<DirectChatService>>,
 }
 
 /// Agent session information
@@ -39,6 +63,118 @@ pub struct AgentSessionInfo {
     pub new_session_response: Option<acp::NewSessionResponse>,
     /// Available commands for this session (slash commands, etc.)
     pub available_commands: Vec<AvailableCommand>,
+    /// Working directory the session was created with
+    pub cwd: Option<std::path::PathBuf>,
+    /// MCP servers the session was created with
+    pub mcp_servers: Vec<acp::McpServer>,
+    /// The agent process was hibernated; the session must be loaded before the next prompt
+    pub hibernated: bool,
+}
+
+/// What the hover card of an agent's message tells about the agent and its
+/// session
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AgentIdentity {
+    pub agent_name: String,
+    /// `name version` the agent reported on initialize
+    pub agent_info: Option<String>,
+    pub model: Option<String>,
+    pub mode: Option<String>,
+    pub session_id: String,
+    /// `None` if the session is not known (any more)
+    pub status: Option<SessionStatus>,
+    /// Optional capabilities the agent reported, see [`capability_names`]
+    pub capabilities: Vec<String>,
+}
+
+impl AgentIdentity {
+    fn new(
+        agent_name: &str,
+        session_id: &str,
+        info: Option<&AgentSessionInfo>,
+        init: Option<&acp::InitializeResponse>,
+    ) -> Self {
+        let response = info.and_then(|info| info.new_session_response.as_ref());
+        // Names of the current mode and model, their IDs if they have none
+        let mode = response
+            .and_then(|response| response.modes.as_ref())
+            .map(|modes| {
+                modes
+                    .available_modes
+                    .iter()
+                    .find(|mode| mode.id == modes.current_mode_id)
+                    .map(|mode| mode.name.clone())
+                    .unwrap_or_else(|| modes.current_mode_id.to_string())
+            });
+        let model = response
+            .and_then(|response| response.models.as_ref())
+            .map(|models| {
+                models
+                    .available_models
+                    .iter()
+                    .find(|model| model.model_id == models.current_model_id)
+                    .map(|model| model.name.clone())
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or_else(|| models.current_model_id.to_string())
+            })
+            .or_else(|| model_for_agent(agent_name).map(str::to_string));
+
+        Self {
+            agent_name: agent_name.to_string(),
+            agent_info: init
+                .and_then(|init| init.agent_info.as_ref())
+                .map(|info| format!("{} {}", info.name, info.version)),
+            model,
+            mode,
+            session_id: session_id.to_string(),
+            status: info.map(|info| info.status.clone()),
+            capabilities: init
+                .map(|init| capability_names(&init.agent_capabilities))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Modes and models an agent offers new sessions, as `(id, name)`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionChoices {
+    pub modes: Vec<(String, String)>,
+    pub models: Vec<(String, String)>,
+}
+
+impl SessionChoices {
+    /// What the most recently active of `sessions` reported on creation;
+    /// agents only tell their modes and models when a session is created
+    pub fn from_sessions(sessions: &[AgentSessionInfo]) -> Self {
+        let Some(response) = sessions
+            .iter()
+            .filter(|info| info.new_session_response.is_some())
+            .max_by_key(|info| info.last_active)
+            .and_then(|info| info.new_session_response.as_ref())
+        else {
+            return Self::default();
+        };
+        let modes = response
+            .modes
+            .iter()
+            .flat_map(|modes| &modes.available_modes)
+            .map(|mode| (mode.id.to_string(), mode.name.clone()))
+            .collect();
+        let models = response
+            .models
+            .iter()
+            .flat_map(|models| &models.available_models)
+            .map(|model| {
+                let name = if model.name.is_empty() {
+                    model.model_id.to_string()
+                } else {
+                    model.name.clone()
+                };
+                (model.model_id.to_string(), name)
+            })
+            .collect();
+        Self { modes, models }
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
@@ -59,20 +195,64 @@ impl AgentService {
             agent_manager,
             sessions: Arc::new(RwLock::new(HashMap::new())),
             workspace_bus: None,
+            hibernate_after: None,
+            hibernation_lock: tokio::sync::RwLock::new(()),
+            wake_locks: Mutex::new(HashMap::new()),
+            limiter: SessionLimiter::new(),
+            retry_policy: RetryPolicy::default(),
+            warm_pool: WarmPool::new(),
+            output_guard: OutputGuard::default(),
+            direct_chat: None,
         }
     }
 
+    /// Cancel turns that stream more than `max_chars` characters (0 disables the guard)
+    pub fn set_output_limit(&mut self, max_chars: usize) {
+        self.output_guard = OutputGuard::new(max_chars);
+    }
+
+    /// Set the retry policy for transient prompt failures
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Enable hibernation of agent processes whose sessions have all been idle for `idle`
+    pub fn set_hibernate_after(&mut self, idle: Duration) {
+        log::info!("AgentService: Hibernating idle agents after {:?}", idle);
+        self.hibernate_after = Some(idle);
+    }
+
+    /// Offer the configured models as `llm:<model>` agents
+    pub fn set_direct_chat(&mut self, service: Arc<DirectChatService>) {
+        self.direct_chat = Some(service);
+    }
+
+    /// The direct chat service, if `agent_name` is a model agent
+    fn direct_chat_for(&self, agent_name: &str) -> Option<ServiceResult<&DirectChatService>> {
+        model_for_agent(agent_name)?;
+        Some(
+            self.direct_chat
+                .as_deref()
+                .ok_or_else(|| ServiceError::AgentNotFound(agent_name.to_string())),
+        )
+    }
+
     /// Set the workspace event bus for publishing status updates
     pub fn set_workspace_bus(&mut self, bus: WorkspaceUpdateBusContainer) {
         log::info!("AgentService: Setting workspace event bus");
+        self.limiter.set_workspace_bus(bus.clone());
         self.workspace_bus = Some(bus);
     }
 
     // ========== Agent Operations ==========
 
-    /// List all available agents
+    /// List all available agents, followed by the direct chat models
     pub async fn list_agents(&self) -> Vec<String> {
-        self.agent_manager.list_agents().await
+        let mut agents = self.agent_manager.list_agents().await;
+        if let Some(direct_chat) = self.direct_chat.as_ref() {
+            agents.extend(direct_chat.model_agents());
+        }
+        agents
     }
 
     /// Get the initialize response for a specific agent
@@ -89,17 +269,66 @@ impl AgentService {
     }
 
     /// Get agent handle (internal use)
-    async fn get_agent_handle(&self, name: &str) -> Result<Arc<AgentHandle>> {
+    ///
+    /// Transparently respawns the agent if its process was hibernated.
+    async fn get_agent_handle(&self, name: &str) -> ServiceResult<Arc<AgentHandle>> {
+        if self.agent_manager.is_hibernated(name).await {
+            let wake_lock = self
+                .wake_locks
+                .lock()
+                .unwrap()
+                .entry(name.to_string())
+                .or_default()
+                .clone();
+            let _waking = wake_lock.lock().await;
+            // Another request may have woken it while we waited
+            if self.agent_manager.is_hibernated(name).await {
+                log::info!("Waking hibernated agent {}", name);
+                return self.agent_manager.wake_agent(name).await.map_err(|e| {
+                    ServiceError::AgentUnavailable {
+                        agent: name.to_string(),
+                        reason: format!("{:#}", e),
+                    }
+                });
+            }
+        }
+
         self.agent_manager
             .get(name)
             .await
-            .ok_or_else(|| anyhow!("Agent not found: {}", name))
+            .ok_or_else(|| ServiceError::AgentNotFound(name.to_string()))
+    }
+
+    /// Wait for a free concurrency slot on the agent (see `max_concurrent_sessions`)
+    ///
+    /// Sessions keep their slot until they are closed or removed.
+    async fn acquire_slot(&self, agent_name: &str, session_id: Option<&str>) -> SessionSlot {
+        let limit = self
+            .agent_manager
+            .get_config(agent_name)
+            .await
+            .and_then(|config| config.max_concurrent_sessions);
+        self.limiter.acquire(agent_name, session_id, limit).await
+    }
+
+    /// Give a session that has no concurrency slot one, waiting in line if needed
+    async fn ensure_slot(&self, agent_name: &str, session_id: &str) {
+        if self.limiter.holds(session_id) {
+            return;
+        }
+        let slot = self.acquire_slot(agent_name, Some(session_id)).await;
+        self.limiter.hold(session_id, slot);
+    }
+
+    /// Number of requests waiting for a slot on the agent
+    pub fn queued_count(&self, agent_name: &str) -> usize {
+        self.limiter.queue_len(agent_name)
     }
 
     // ========== Session Operations ==========
 
     /// Create a new session for the agent
-    pub async fn create_session(&self, agent_name: &str) -> Result<String> {
+    pub async fn create_session(&self, agent_name: &str) -> ServiceResult<String> {
         self.create_session_with_mcp(agent_name, Vec::new()).await
     }
 
@@ -108,7 +337,7 @@ impl AgentService {
         &self,
         agent_name: &str,
         mcp_servers: Vec<acp::McpServer>,
-    ) -> Result<String> {
+    ) -> ServiceResult<String> {
         self.create_session_with_mcp_and_cwd(
             agent_name,
             mcp_servers,
@@ -123,7 +352,58 @@ impl AgentService {
         agent_name: &str,
         mcp_servers: Vec<acp::McpServer>,
         cwd: std::path::PathBuf,
-    ) -> Result<String> {
+    ) -> ServiceResult<String> {
+        if let Some(direct_chat) = self.direct_chat_for(agent_name) {
+            let model = model_for_agent(agent_name).unwrap_or_default();
+            let response = direct_chat?.open_session(model, None)?;
+            let session_id = response.session_id.to_string();
+            self.register_session(agent_name, session_id.clone(), response, cwd, Vec::new());
+            return Ok(session_id);
+        }
+
+        // Queued creations report their position via SessionCreationQueued events
+        let slot = self.acquire_slot(agent_name, None).await;
+        if let Some(warm) = self.warm_pool.take(agent_name, &cwd, &mcp_servers) {
+            log::info!(
+                "Handing out warm session {} for agent {}",
+                warm.session_id,
+                agent_name
+            );
+            let session_id = warm.session_id.clone();
+            self.register_session(
+                agent_name,
+                session_id.clone(),
+                warm.response,
+                warm.cwd,
+                warm.mcp_servers,
+            );
+            self.limiter.hold(&session_id, slot);
+            return Ok(session_id);
+        }
+
+        let new_session_response = self
+            .request_new_session(agent_name, mcp_servers.clone(), cwd.clone())
+            .await?;
+        let session_id = new_session_response.session_id.to_string();
+
+        self.register_session(
+            agent_name,
+            session_id.clone(),
+            new_session_response,
+            cwd,
+            mcp_servers,
+        );
+        self.limiter.hold(&session_id, slot);
+        Ok(session_id)
+    }
+
+    /// Ask the agent for a new session without registering it
+    async fn request_new_session(
+        &self,
+        agent_name: &str,
+        mcp_servers: Vec<acp::McpServer>,
+        cwd: std::path::PathBuf,
+    ) -> ServiceResult<acp::NewSessionResponse> {
         let agent_handle = self.get_agent_handle(agent_name).await?;
 
         let mut request = acp::NewSessionRequest::new(cwd.clone());
@@ -131,13 +411,21 @@ impl AgentService {
         request.mcp_servers = mcp_servers;
         request.meta = None;
 
-        let new_session_response: acp::NewSessionResponse = agent_handle
+        agent_handle
             .new_session(request)
             .await
-            .map_err(|e| anyhow!("Failed to create session: {}", e))?;
-
-        let session_id = new_session_response.session_id.to_string();
+            .map_err(|e| ServiceError::from_agent(agent_name, "Failed to create session", e))
+    }
 
+    /// Record a freshly created session
+    fn register_session(
+        &self,
+        agent_name: &str,
+        session_id: String,
+        new_session_response: acp::NewSessionResponse,
+        cwd: std::path::PathBuf,
+        mcp_servers: Vec<acp::McpServer>,
+    ) {
         let now = Utc::now();
 
         // Insert into nested HashMap structure
@@ -154,6 +442,9 @@ impl AgentService {
                 info.last_active = now;
                 info.status = SessionStatus::Active;
                 info.new_session_response = Some(new_session_response);
+                info.cwd = Some(cwd);
+                info.mcp_servers = mcp_servers;
+                info.hibernated = false;
                 log::info!(
                     "Session {} for agent {} already exists; refreshed metadata",
                     session_id,
@@ -169,15 +460,21 @@ impl AgentService {
                     status: SessionStatus::Active,
                     new_session_response: Some(new_session_response),
                     available_commands: Vec::new(), // Will be populated by AvailableCommandsUpdate
+                    cwd: Some(cwd),
+                    mcp_servers,
+                    hibernated: false,
                 });
                 log::info!("Created session {} for agent {}", session_id, agent_name);
             }
         }
-        Ok(session_id)
     }
 
     /// Resume an existing session with specified session_id
-    pub async fn resume_session(&self, agent_name: &str, session_id: &str) -> Result<String> {
+    pub async fn resume_session(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+    ) -> ServiceResult<String> {
         self.resume_session_with_mcp(agent_name, session_id, Vec::new())
             .await
     }
@@ -188,7 +485,7 @@ impl AgentService {
         agent_name: &str,
         session_id: &str,
         mcp_servers: Vec<acp::McpServer>,
-    ) -> Result<String> {
+    ) -> ServiceResult<String> {
         self.resume_session_with_mcp_and_cwd(
             agent_name,
             session_id,
@@ -205,21 +502,29 @@ impl AgentService {
         session_id: &str,
         mcp_servers: Vec<acp::McpServer>,
         cwd: std::path::PathBuf,
-    ) -> Result<String> {
+    ) -> ServiceResult<String> {
+        if let Some(direct_chat) = self.direct_chat_for(agent_name) {
+            let model = model_for_agent(agent_name).unwrap_or_default();
+            let response = direct_chat?.open_session(model, Some(session_id))?;
+            self.register_session(agent_name, session_id.to_string(), response, cwd, Vec::new());
+            return Ok(session_id.to_string());
+        }
+
+        self.ensure_slot(agent_name, session_id).await;
         let agent_handle = self.get_agent_handle(agent_name).await?;
 
         let mut request = acp::ResumeSessionRequest::new(
             acp::SessionId::from(session_id.to_string()),
             cwd.clone(),
         );
-        request.cwd = cwd;
-        request.mcp_servers = mcp_servers;
+        request.cwd = cwd.clone();
+        request.mcp_servers = mcp_servers.clone();
         request.meta = None;
 
         let resume_session_response: acp::ResumeSessionResponse = agent_handle
             .resume_session(request)
             .await
-            .map_err(|e| anyhow!("Failed to resume session: {}", e))?;
+            .map_err(|e| ServiceError::from_agent(agent_name, "Failed to resume session", e))?;
 
         // Convert ResumeSessionResponse to NewSessionResponse for consistency
         let new_session_response = acp::NewSessionResponse::new(session_id.to_string())
@@ -243,6 +548,9 @@ impl AgentService {
                 info.last_active = now;
                 info.status = SessionStatus::Active;
                 info.new_session_response = Some(new_session_response);
+                info.cwd = Some(cwd);
+                info.mcp_servers = mcp_servers;
+                info.hibernated = false;
                 log::info!("Resumed session {} for agent {}", session_id, agent_name);
             }
             Entry::Vacant(entry) => {
@@ -254,6 +562,9 @@ impl AgentService {
                     status: SessionStatus::Active,
                     new_session_response: Some(new_session_response),
                     available_commands: Vec::new(),
+                    cwd: Some(cwd),
+                    mcp_servers,
+                    hibernated: false,
                 });
                 log::info!(
                     "Resumed session {} for agent {} (created new entry)",
@@ -265,6 +576,75 @@ impl AgentService {
         Ok(session_id.to_string())
     }
 
+    /// Change the MCP servers of a running session by resuming it with them;
+    /// fails for agents that cannot resume sessions
+    pub async fn set_session_mcp_servers(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        mcp_servers: Vec<acp::McpServer>,
+    ) -> ServiceResult<()> {
+        let cwd = self
+            .get_session_info(agent_name, session_id)
+            .ok_or_else(|| ServiceError::SessionNotFound(session_id.to_string()))?
+            .cwd
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+        self.resume_session_with_mcp_and_cwd(agent_name, session_id, mcp_servers, cwd)
+            .await?;
+        log::info!("Updated MCP servers of session {}", session_id);
+        Ok(())
+    }
+
+    /// Kill and respawn the session's agent process and continue in a fresh
+    /// session with the same working directory and MCP servers; returns its ID
+    ///
+    /// The agent's other sessions died with the process and are loaded again
+    /// before their next prompt, as after hibernation.
+    pub async fn restart_session(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+    ) -> ServiceResult<String> {
+        if self.direct_chat_for(agent_name).is_some() {
+            return Err(ServiceError::InvalidInput(format!(
+                "{} has no agent process to restart",
+                agent_name
+            )));
+        }
+        let info = self
+            .get_session_info(agent_name, session_id)
+            .ok_or_else(|| ServiceError::SessionNotFound(session_id.to_string()))?;
+        let config = self
+            .agent_manager
+            .get_config(agent_name)
+            .await
+            .ok_or_else(|| ServiceError::AgentNotFound(agent_name.to_string()))?;
+
+        log::info!("Restarting agent {} for session {}", agent_name, session_id);
+        self.agent_manager
+            .restart_agent(agent_name, config)
+            .await
+            .map_err(|e| ServiceError::AgentUnavailable {
+                agent: agent_name.to_string(),
+                reason: format!("{:#}", e),
+            })?;
+        self.warm_pool.discard(agent_name);
+        self.limiter.release_session(session_id);
+        if let Some(agent_sessions) = self.sessions.write().unwrap().get_mut(agent_name) {
+            agent_sessions.remove(session_id);
+            for info in agent_sessions.values_mut() {
+                info.hibernated = true;
+            }
+        }
+        self.output_guard.remove_session(session_id);
+
+        let cwd = info
+            .cwd
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+        self.create_session_with_mcp_and_cwd(agent_name, info.mcp_servers, cwd)
+            .await
+    }
+
     /// Get session information
     pub fn get_session_info(&self, agent_name: &str, session_id: &str) -> Option<AgentSessionInfo> {
         self.sessions
@@ -276,7 +656,7 @@ impl AgentService {
     }
 
     /// Close an agent's session
-    pub async fn close_session(&self, agent_name: &str, session_id: &str) -> Result<()> {
+    pub async fn close_session(&self, agent_name: &str, session_id: &str) -> ServiceResult<()> {
         let mut sessions = self.sessions.write().unwrap();
         if let Some(agent_sessions) = sessions.get_mut(agent_name) {
             if let Some(info) = agent_sessions.get_mut(session_id) {
@@ -284,24 +664,92 @@ impl AgentService {
                 log::info!("Closed session {} for agent {}", session_id, agent_name);
             }
         }
+        if let Some(Ok(direct_chat)) = self.direct_chat_for(agent_name) {
+            direct_chat.close_session(session_id);
+        }
+        self.limiter.release_session(session_id);
+        self.output_guard.remove_session(session_id);
+        self.agent_manager
+            .permission_store()
+            .clear_session_approvals(session_id);
+        Ok(())
+    }
+
+    /// Close every open session that is not running or waiting for a turn;
+    /// returns their IDs
+    pub async fn close_idle_sessions(&self) -> Vec<String> {
+        let idle: Vec<(String, String)> = self
+            .list_sessions()
+            .into_iter()
+            .filter(|info| {
+                !matches!(
+                    info.status,
+                    SessionStatus::InProgress | SessionStatus::Pending | SessionStatus::Closed
+                )
+            })
+            .map(|info| (info.agent_name, info.session_id))
+            .collect();
+
+        let mut closed = Vec::new();
+        for (agent_name, session_id) in idle {
+            match self.close_session(&agent_name, &session_id).await {
+                Ok(()) => closed.push(session_id),
+                Err(e) => log::warn!("Failed to close idle session {}: {}", session_id, e),
+            }
+        }
+        closed
+    }
+
+    /// Forget a session, cancelling its turn if one is running
+    ///
+    /// Unknown sessions are ignored.
+    pub async fn remove_session(&self, session_id: &str) -> ServiceResult<()> {
+        let Some(info) = self.get_session_by_id(session_id) else {
+            return Ok(());
+        };
+        if info.status == SessionStatus::InProgress
+            && let Err(e) = self.cancel_session(&info.agent_name, session_id).await
+        {
+            log::warn!("Failed to cancel session {} before removing it: {}", session_id, e);
+        }
+
+        if let Some(agent_sessions) = self.sessions.write().unwrap().get_mut(&info.agent_name) {
+            agent_sessions.remove(session_id);
+        }
+        if let Some(Ok(direct_chat)) = self.direct_chat_for(&info.agent_name) {
+            direct_chat.close_session(session_id);
+        }
+        self.limiter.release_session(session_id);
+        self.output_guard.remove_session(session_id);
+        self.agent_manager
+            .permission_store()
+            .clear_session_approvals(session_id);
+        log::info!("Removed session {} of agent {}", session_id, info.agent_name);
         Ok(())
     }
 
     /// Cancel an ongoing session operation
-    pub async fn cancel_session(&self, agent_name: &str, session_id: &str) -> Result<()> {
+    pub async fn cancel_session(&self, agent_name: &str, session_id: &str) -> ServiceResult<()> {
         log::info!(
             "AgentService: cancel_session called for agent={}, session={}",
             agent_name,
             session_id
         );
 
-        // Get the agent handle
-        let agent_handle = self.get_agent_handle(agent_name).await?;
-        log::info!("AgentService: Got agent handle for {}", agent_name);
+        if let Some(direct_chat) = self.direct_chat_for(agent_name) {
+            direct_chat?.cancel(session_id)?;
+        } else {
+            // Get the agent handle
+            let agent_handle = self.get_agent_handle(agent_name).await?;
+            log::info!("AgentService: Got agent handle for {}", agent_name);
 
-        // Send cancel request to the agent
-        agent_handle.cancel(session_id.to_string()).await?;
-        log::info!("AgentService: Sent cancel request to agent");
+            // Send cancel request to the agent
+            agent_handle
+                .cancel(session_id.to_string())
+                .await
+                .map_err(|e| ServiceError::from_agent(agent_name, "Failed to cancel session", e))?;
+            log::info!("AgentService: Sent cancel request to agent");
+        }
 
         // Update session status to Idle
         let mut sessions = self.sessions.write().unwrap();
@@ -341,10 +789,10 @@ impl AgentService {
     }
 
     /// Cancel a session by ID without requiring the caller to know the agent name
-    pub async fn cancel_session_by_id(&self, session_id: &str) -> Result<()> {
+    pub async fn cancel_session_by_id(&self, session_id: &str) -> ServiceResult<()> {
         let agent_name = self
             .get_agent_for_session(session_id)
-            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+            .ok_or_else(|| ServiceError::SessionNotFound(session_id.to_string()))?;
 
         self.cancel_session(&agent_name, session_id).await
     }
@@ -359,6 +807,17 @@ impl AgentService {
             .collect()
     }
 
+    /// List all sessions, or `None` when another thread is changing them
+    pub fn try_list_sessions(&self) -> Option<Vec<AgentSessionInfo>> {
+        let sessions = self.sessions.try_read().ok()?;
+        Some(
+            sessions
+                .values()
+                .flat_map(|agent_sessions| agent_sessions.values().cloned())
+                .collect(),
+        )
+    }
+
     /// Update session's last active time
     pub fn update_session_activity(&self, agent_name: &str, session_id: &str) {
         if let Some(agent_sessions) = self.sessions.write().unwrap().get_mut(agent_name) {
@@ -403,11 +862,51 @@ impl AgentService {
                     status: SessionStatus::Active,
                     new_session_response: None,
                     available_commands: commands,
+                    cwd: None,
+                    mcp_servers: Vec::new(),
+                    hibernated: false,
                 });
             }
         }
     }
 
+    /// Record the mode a session switched to, so its session info keeps
+    /// telling the current one
+    pub fn update_session_mode(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        mode_id: acp::SessionModeId,
+    ) {
+        let mut sessions = self.sessions.write().unwrap();
+        let modes = sessions
+            .get_mut(agent_name)
+            .and_then(|agent_sessions| agent_sessions.get_mut(session_id))
+            .and_then(|info| info.new_session_response.as_mut())
+            .and_then(|response| response.modes.as_mut());
+        if let Some(modes) = modes {
+            log::debug!(
+                "Session {}:{} switched to mode {}",
+                agent_name,
+                session_id,
+                mode_id
+            );
+            modes.current_mode_id = mode_id;
+        }
+    }
+
+    /// Agent and session details for the hover card of the agent's messages;
+    /// does not wait for locks, so it can be called while rendering
+    pub fn identity(&self, agent_name: &str, session_id: &str) -> AgentIdentity {
+        let info = self
+            .sessions
+            .try_read()
+            .ok()
+            .and_then(|sessions| sessions.get(agent_name)?.get(session_id).cloned());
+        let init = self.agent_manager.try_get_agent_init_response(agent_name);
+        AgentIdentity::new(agent_name, session_id, info.as_ref(), init.as_ref())
+    }
+
     /// Get available commands for a session
     pub fn get_session_commands(
         &self,
@@ -421,6 +920,12 @@ impl AgentService {
             .get(session_id)
             .map(|info| info.available_commands.clone())
     }
+
+    /// Modes and models `agent_name` offers, as known from its sessions
+    pub fn session_choices(&self, agent_name: &str) -> SessionChoices {
+        SessionChoices::from_sessions(&self.list_sessions_for_agent(agent_name))
+    }
+
     pub fn update_session_status(&self, agent_name: &str, session_id: &str, status: SessionStatus) {
         if let Some(agent_sessions) = self.sessions.write().unwrap().get_mut(agent_name) {
             if let Some(info) = agent_sessions.get_mut(session_id) {
@@ -456,16 +961,78 @@ impl AgentService {
         agent_name: &str,
         session_id: &str,
         prompt: Vec<acp::ContentBlock>,
-    ) -> Result<PromptResponse> {
-        let agent_handle = self.get_agent_handle(agent_name).await?;
-        self.update_session_status(agent_name, session_id, SessionStatus::InProgress);
-        let request = acp::PromptRequest::new(acp::SessionId::from(session_id.to_string()), prompt);
+    ) -> ServiceResult<PromptResponse> {
+        if self.agent_manager.permission_store().is_session_locked(session_id) {
+            return Err(ServiceError::SessionLocked(session_id.to_string()));
+        }
+        if let Some(direct_chat) = self.direct_chat_for(agent_name) {
+            let direct_chat = direct_chat?;
+            self.update_session_status(agent_name, session_id, SessionStatus::InProgress);
+            self.output_guard.begin_turn(session_id);
+            let started = std::time::Instant::now();
+            let result = direct_chat.prompt(agent_name, session_id, &prompt).await;
+            let status = if result.is_ok() {
+                self.publish_turn_completed(agent_name, session_id, started, &prompt);
+                SessionStatus::Completed
+            } else {
+                SessionStatus::Failed
+            };
+            self.update_session_status(agent_name, session_id, status);
+            self.update_session_activity(agent_name, session_id);
+            return result;
+        }
 
-        let result = agent_handle
-            .prompt(request)
-            .await
-            .map_err(|e| anyhow!("Failed to send prompt: {}", e))?;
+        // Sessions without a slot queue for one and report their position via
+        // SessionQueueUpdated events
+        self.ensure_slot(agent_name, session_id).await;
+
+        let agent_handle = {
+            // Hibernation waits until the turn is marked in progress
+            let _awake = self.hibernation_lock.read().await;
+            let agent_handle = self.get_agent_handle(agent_name).await?;
+            self.reload_if_hibernated(&agent_handle, agent_name, session_id)
+                .await?;
+            self.update_session_status(agent_name, session_id, SessionStatus::InProgress);
+            agent_handle
+        };
+        self.output_guard.begin_turn(session_id);
+        let started = std::time::Instant::now();
+
+        let mut attempt = 1;
+        let result = loop {
+            let request = acp::PromptRequest::new(
+                acp::SessionId::from(session_id.to_string()),
+                prompt.clone(),
+            );
+            match agent_handle.prompt(request).await {
+                Ok(result) => break result,
+                Err(e) if self.retry_policy.should_retry(attempt, &e) => {
+                    let delay = self.retry_policy.backoff(attempt);
+                    attempt += 1;
+                    log::warn!(
+                        "Prompt to {}:{} failed ({}), retrying in {:?} ({}/{})",
+                        agent_name,
+                        session_id,
+                        e,
+                        delay,
+                        attempt,
+                        self.retry_policy.max_attempts
+                    );
+                    self.publish_retry(agent_name, session_id, attempt);
+                    smol::Timer::after(delay).await;
+                }
+                Err(e) => {
+                    self.update_session_status(agent_name, session_id, SessionStatus::Failed);
+                    return Err(ServiceError::from_agent(
+                        agent_name,
+                        "Failed to send prompt",
+                        e,
+                    ));
+                }
+            }
+        };
 
+        self.publish_turn_completed(agent_name, session_id, started, &prompt);
         self.update_session_status(agent_name, session_id, SessionStatus::Completed);
         // Update activity time
         self.update_session_activity(agent_name, session_id);
@@ -474,6 +1041,384 @@ impl AgentService {
         Ok(result)
     }
 
+    /// Answer the last prompt of a direct chat session again
+    ///
+    /// Only model sessions (`llm:<model>`) can regenerate; an ACP agent's turn
+    /// may have changed files and cannot simply be re-run. Returns the
+    /// replies beyond the streamed one when several `variants` were asked for.
+    pub async fn regenerate_reply(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        options: ChatOptions,
+    ) -> ServiceResult<(PromptResponse, Vec<String>)> {
+        let Some(direct_chat) = self.direct_chat_for(agent_name) else {
+            return Err(ServiceError::InvalidInput(format!(
+                "{} is not a direct chat agent",
+                agent_name
+            )));
+        };
+        let direct_chat = direct_chat?;
+        if self
+            .agent_manager
+            .permission_store()
+            .is_session_locked(session_id)
+        {
+            return Err(ServiceError::SessionLocked(session_id.to_string()));
+        }
+        self.update_session_status(agent_name, session_id, SessionStatus::InProgress);
+        self.output_guard.begin_turn(session_id);
+        let started = std::time::Instant::now();
+        let result = direct_chat
+            .regenerate(agent_name, session_id, options)
+            .await;
+        let status = if result.is_ok() {
+            self.publish_turn_completed(agent_name, session_id, started, &[]);
+            SessionStatus::Completed
+        } else {
+            SessionStatus::Failed
+        };
+        self.update_session_status(agent_name, session_id, status);
+        self.update_session_activity(agent_name, session_id);
+        result
+    }
+
+    /// Continue a direct chat session from an earlier reply to its last prompt
+    pub fn use_reply(&self, agent_name: &str, session_id: &str, reply: &str) -> ServiceResult<()> {
+        match self.direct_chat_for(agent_name) {
+            Some(direct_chat) => direct_chat?.use_reply(session_id, reply),
+            None => Err(ServiceError::InvalidInput(format!(
+                "{} is not a direct chat agent",
+                agent_name
+            ))),
+        }
+    }
+
+    /// Report a finished turn's duration and size, for task usage
+    fn publish_turn_completed(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        started: std::time::Instant,
+        prompt: &[acp::ContentBlock],
+    ) {
+        let Some(ref workspace_bus) = self.workspace_bus else {
+            return;
+        };
+        let prompt_chars = prompt
+            .iter()
+            .map(|block| match block {
+                acp::ContentBlock::Text(text) => text.text.chars().count(),
+                acp::ContentBlock::Resource(resource) => match &resource.resource {
+                    acp::EmbeddedResourceResource::TextResourceContents(contents) => {
+                        contents.text.chars().count()
+                    }
+                    _ => 0,
+                },
+                _ => 0,
+            })
+            .sum();
+        workspace_bus.publish(WorkspaceUpdateEvent::TurnCompleted {
+            session_id: session_id.to_string(),
+            agent_name: agent_name.to_string(),
+            busy_ms: started.elapsed().as_millis() as u64,
+            prompt_chars,
+            output_chars: self.output_guard.streamed(session_id),
+        });
+    }
+
+    fn publish_retry(&self, agent_name: &str, session_id: &str, attempt: u32) {
+        if let Some(ref workspace_bus) = self.workspace_bus {
+            workspace_bus.publish(WorkspaceUpdateEvent::SessionRetrying {
+                session_id: session_id.to_string(),
+                agent_name: agent_name.to_string(),
+                attempt,
+                max_attempts: self.retry_policy.max_attempts,
+            });
+        }
+    }
+
+    // ========== Permission Operations ==========
+
+    /// Answer a permission request the user was asked about
+    ///
+    /// The agent's tool call waits until this is called; `for_session`
+    /// approves later calls of the same tool kind in the session as well.
+    pub async fn answer_permission(
+        &self,
+        permission_id: &str,
+        approve: bool,
+        for_session: bool,
+    ) -> ServiceResult<()> {
+        self.agent_manager
+            .permission_store()
+            .answer(permission_id, approve, for_session)
+            .await
+            .map_err(ServiceError::Other)
+    }
+
+    // ========== Output Guard ==========
+
+    /// Count streamed output and cancel the turn once it exceeds the session's limit
+    pub fn guard_output(self: &Arc<Self>, agent_name: &str, session_id: &str, chars: usize) {
+        let Some(limit) = self.output_guard.record(session_id, chars) else {
+            return;
+        };
+        log::warn!(
+            "Session {} of agent {} streamed more than {} characters, cancelling turn",
+            session_id,
+            agent_name,
+            limit
+        );
+
+        if let Some(ref workspace_bus) = self.workspace_bus {
+            workspace_bus.publish(WorkspaceUpdateEvent::SessionOutputLimited {
+                session_id: session_id.to_string(),
+                agent_name: agent_name.to_string(),
+                limit,
+            });
+        }
+
+        let service = self.clone();
+        let agent_name = agent_name.to_string();
+        let session_id = session_id.to_string();
+        smol::spawn(async move {
+            if let Err(e) = service.cancel_session(&agent_name, &session_id).await {
+                log::error!("Failed to cancel runaway session {}: {}", session_id, e);
+            }
+        })
+        .detach();
+    }
+
+    /// Output limit applied to the session's turns (0 = unlimited)
+    pub fn output_limit(&self, session_id: &str) -> usize {
+        self.output_guard.limit_for(session_id)
+    }
+
+    /// Double the session's output limit, e.g. before resuming a cancelled turn
+    pub fn raise_output_limit(&self, session_id: &str) -> usize {
+        self.output_guard.raise_session_limit(session_id)
+    }
+
+    // ========== Warm Pool ==========
+
+    /// Pre-create one idle session for each agent in the background
+    ///
+    /// Agents start asynchronously, so each one is waited for (up to `ready_timeout`)
+    /// before its session is requested. Failures only cost the speed-up.
+    pub fn start_warm_pool(
+        self: &Arc<Self>,
+        agents: Vec<String>,
+        cwd: std::path::PathBuf,
+        mcp_servers: Vec<acp::McpServer>,
+        ready_timeout: Duration,
+    ) {
+        if agents.is_empty() {
+            return;
+        }
+        log::info!("Warming sessions for {:?} in {:?}", agents, cwd);
+
+        for agent_name in agents {
+            let service = Arc::downgrade(self);
+            let cwd = cwd.clone();
+            let mcp_servers = mcp_servers.clone();
+            smol::spawn(async move {
+                let deadline = std::time::Instant::now() + ready_timeout;
+                loop {
+                    let Some(service) = service.upgrade() else {
+                        return;
+                    };
+                    if service.agent_manager.get(&agent_name).await.is_some() {
+                        service.warm_session(&agent_name, cwd, mcp_servers).await;
+                        return;
+                    }
+                    if std::time::Instant::now() >= deadline {
+                        log::warn!("Agent {} not ready, skipping warm session", agent_name);
+                        return;
+                    }
+                    drop(service);
+                    smol::Timer::after(Duration::from_millis(500)).await;
+                }
+            })
+            .detach();
+        }
+    }
+
+    async fn warm_session(
+        &self,
+        agent_name: &str,
+        cwd: std::path::PathBuf,
+        mcp_servers: Vec<acp::McpServer>,
+    ) {
+        let started = std::time::Instant::now();
+        match self
+            .request_new_session(agent_name, mcp_servers.clone(), cwd.clone())
+            .await
+        {
+            Ok(response) => {
+                let session_id = response.session_id.to_string();
+                log::info!(
+                    "Warm session {} ready for agent {} in {:?}",
+                    session_id,
+                    agent_name,
+                    started.elapsed()
+                );
+                self.warm_pool.insert(
+                    agent_name,
+                    WarmSession {
+                        session_id,
+                        cwd,
+                        mcp_servers,
+                        response,
+                    },
+                );
+            }
+            Err(e) => {
+                log::warn!("Failed to warm session for agent {}: {}", agent_name, e);
+            }
+        }
+    }
+
+    /// Drop the session parked for the agent, e.g. when its process is restarted
+    pub fn discard_warm_session(&self, agent_name: &str) {
+        if let Some(warm) = self.warm_pool.discard(agent_name) {
+            log::debug!(
+                "Discarded warm session {} of agent {}",
+                warm.session_id,
+                agent_name
+            );
+        }
+    }
+
+    /// Whether a pre-created session is waiting for the agent
+    pub fn has_warm_session(&self, agent_name: &str) -> bool {
+        self.warm_pool.contains(agent_name)
+    }
+
+    // ========== Hibernation Operations ==========
+
+    /// Replay a hibernated session into the freshly respawned agent (ACP `session/load`)
+    async fn reload_if_hibernated(
+        &self,
+        agent_handle: &AgentHandle,
+        agent_name: &str,
+        session_id: &str,
+    ) -> ServiceResult<()> {
+        let Some(info) = self.get_session_info(agent_name, session_id) else {
+            return Ok(());
+        };
+        if !info.hibernated {
+            return Ok(());
+        }
+
+        log::info!(
+            "Loading hibernated session {} into agent {}",
+            session_id,
+            agent_name
+        );
+        let cwd = info
+            .cwd
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+        let mut request =
+            acp::LoadSessionRequest::new(acp::SessionId::from(session_id.to_string()), cwd);
+        request.mcp_servers = info.mcp_servers;
+        agent_handle
+            .load_session(request)
+            .await
+            .map_err(|e| {
+                ServiceError::from_agent(agent_name, "Failed to reload hibernated session", e)
+            })?;
+
+        if let Some(agent_sessions) = self.sessions.write().unwrap().get_mut(agent_name) {
+            if let Some(info) = agent_sessions.get_mut(session_id) {
+                info.hibernated = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// Hibernate every agent whose sessions have all been idle for the configured period
+    pub async fn hibernate_idle_agents(&self) {
+        let Some(idle) = self.hibernate_after else {
+            return;
+        };
+        let now = Utc::now();
+        let candidates: Vec<String> = self
+            .sessions
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(agent_name, agent_sessions)| {
+                model_for_agent(agent_name).is_none()
+                    && should_hibernate(agent_sessions.values(), now, idle)
+            })
+            .map(|(agent_name, _)| agent_name.clone())
+            .collect();
+
+        for agent_name in candidates {
+            if self.agent_manager.is_hibernated(&agent_name).await {
+                continue;
+            }
+            // Without session/load the sessions would die with the process
+            let can_load = self
+                .agent_manager
+                .get_agent_init_response(&agent_name)
+                .await
+                .is_some_and(|init| init.agent_capabilities.load_session);
+            if !can_load {
+                log::debug!("Not hibernating {}: it cannot load sessions", agent_name);
+                continue;
+            }
+
+            let _exclusive = self.hibernation_lock.write().await;
+            // A prompt may have started since the candidates were picked
+            if !self.is_idle(&agent_name, idle) {
+                continue;
+            }
+            if let Err(e) = self.agent_manager.hibernate_agent(&agent_name).await {
+                log::warn!("Failed to hibernate agent {}: {}", agent_name, e);
+                continue;
+            }
+            // The parked session died with the process
+            self.warm_pool.discard(&agent_name);
+            if let Some(agent_sessions) = self.sessions.write().unwrap().get_mut(&agent_name) {
+                for info in agent_sessions.values_mut() {
+                    info.hibernated = true;
+                }
+            }
+        }
+    }
+
+    /// Whether all sessions of the agent have been quiet for at least `idle`
+    fn is_idle(&self, agent_name: &str, idle: Duration) -> bool {
+        self.sessions
+            .read()
+            .unwrap()
+            .get(agent_name)
+            .is_some_and(|agent_sessions| {
+                should_hibernate(agent_sessions.values(), Utc::now(), idle)
+            })
+    }
+
+    /// Periodically hibernate idle agents in the background (no-op when disabled)
+    pub fn start_hibernation_monitor(self: &Arc<Self>) {
+        let Some(idle) = self.hibernate_after else {
+            return;
+        };
+        let interval = (idle / 4).clamp(Duration::from_secs(5), Duration::from_secs(60));
+        let service = Arc::downgrade(self);
+        smol::spawn(async move {
+            loop {
+                smol::Timer::after(interval).await;
+                let Some(service) = service.upgrade() else {
+                    break;
+                };
+                service.hibernate_idle_agents().await;
+            }
+        })
+        .detach();
+    }
+
     // ========== Cleanup Operations ==========
 
     /// Clean up idle sessions
@@ -487,6 +1432,7 @@ impl AgentService {
                 let should_keep = idle_time.num_seconds() < idle_duration.as_secs() as i64;
 
                 if !should_keep {
+                    self.limiter.release_session(session_id);
                     log::info!(
                         "Cleaning up idle session {} for agent {} (idle for {}s)",
                         session_id,
@@ -523,9 +1469,131 @@ impl AgentService {
             .cloned()
     }
 
+    /// Whether prompts can be sent to `session_id` without resuming it first
+    ///
+    /// Sessions of an earlier run are unknown until resumed; closed ones have
+    /// to be resumed again.
+    pub fn is_session_open(&self, session_id: &str) -> bool {
+        self.get_session_by_id(session_id)
+            .is_some_and(|info| info.status != SessionStatus::Closed)
+    }
+
     /// Get the agent name for a given session ID
     pub fn get_agent_for_session(&self, session_id: &str) -> Option<String> {
         self.get_session_by_id(session_id)
             .map(|info| info.agent_name)
     }
 }
+
+/// Whether an agent's sessions have all been quiet for at least `idle`
+///
+/// Agents without sessions are left alone; they have nothing to restore on wake-up.
+fn should_hibernate<'a>(
+    sessions: impl IntoIterator<Item = &'a AgentSessionInfo>,
+    now: DateTime<Utc>,
+    idle: Duration,
+) -> bool {
+    let mut any = false;
+    for info in sessions {
+        any = true;
+        if info.hibernated
+            || matches!(info.status, SessionStatus::InProgress | SessionStatus::Pending)
+        {
+            return false;
+        }
+        let idle_secs = now.signed_duration_since(info.last_active).num_seconds();
+        if idle_secs < idle.as_secs() as i64 {
+            return false;
+        }
+    }
+    any
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(status: SessionStatus, idle_secs: i64, now: DateTime<Utc>) -> AgentSessionInfo {
+        AgentSessionInfo {
+            session_id: "s".to_string(),
+            agent_name: "agent".to_string(),
+            created_at: now,
+            last_active: now - chrono::Duration::seconds(idle_secs),
+            status,
+            new_session_response: None,
+            available_commands: Vec::new(),
+            cwd: None,
+            mcp_servers: Vec::new(),
+            hibernated: false,
+        }
+    }
+
+    #[test]
+    fn test_should_hibernate_when_all_idle() {
+        let now = Utc::now();
+        let sessions = vec![
+            session(SessionStatus::Completed, 600, now),
+            session(SessionStatus::Idle, 400, now),
+        ];
+        assert!(should_hibernate(&sessions, now, Duration::from_secs(300)));
+    }
+
+    #[test]
+    fn test_should_not_hibernate_busy_or_recent() {
+        let now = Utc::now();
+        let busy = vec![session(SessionStatus::InProgress, 600, now)];
+        assert!(!should_hibernate(&busy, now, Duration::from_secs(300)));
+
+        let recent = vec![session(SessionStatus::Completed, 10, now)];
+        assert!(!should_hibernate(&recent, now, Duration::from_secs(300)));
+
+        let none: Vec<AgentSessionInfo> = Vec::new();
+        assert!(!should_hibernate(&none, now, Duration::from_secs(300)));
+    }
+
+    #[test]
+    fn test_identity_without_agent_details() {
+        let info = session(SessionStatus::Idle, 0, Utc::now());
+        let identity = AgentIdentity::new("llm:gpt-4o", "s", Some(&info), None);
+        assert_eq!(identity.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(identity.mode, None);
+        assert_eq!(identity.status, Some(SessionStatus::Idle));
+        assert!(identity.capabilities.is_empty());
+
+        let unknown = AgentIdentity::new("claude", "s", None, None);
+        assert_eq!(unknown.model, None);
+        assert_eq!(unknown.status, None);
+    }
+
+    #[test]
+    fn test_session_choices_from_latest_session() {
+        let now = Utc::now();
+        let modes = |current: &str, ids: &[&str]| {
+            let available = ids
+                .iter()
+                .map(|id| acp::SessionMode::new(id.to_string(), id.to_uppercase()))
+                .collect();
+            acp::NewSessionResponse::new("s")
+                .modes(acp::SessionModeState::new(current.to_string(), available))
+        };
+        let mut old = session(SessionStatus::Idle, 600, now);
+        old.new_session_response = Some(modes("ask", &["ask"]));
+        let mut recent = session(SessionStatus::Idle, 10, now);
+        recent.new_session_response = Some(modes("code", &["ask", "code"]));
+        let unknown = session(SessionStatus::Idle, 0, now);
+
+        let choices = SessionChoices::from_sessions(&[old, recent, unknown.clone()]);
+        assert_eq!(
+            choices.modes,
+            vec![
+                ("ask".to_string(), "ASK".to_string()),
+                ("code".to_string(), "CODE".to_string())
+            ]
+        );
+        assert!(choices.models.is_empty());
+        assert_eq!(
+            SessionChoices::from_sessions(&[unknown]),
+            SessionChoices::default()
+        );
+    }
+}
@@ -4,18 +4,27 @@
 //! - Code documentation generation
 //! - Code explanation
 //! - Optimization suggestions
+//! - Streamed chat completions for direct model sessions
+//! - Commit messages for staged changes
 
 use std::collections::HashMap;
 use std::sync::{Arc, OnceLock, RwLock};
 
 use anyhow::{Context, Result, anyhow};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
 
-use crate::core::config::ModelConfig;
+use crate::core::config::{ModelConfig, UtilityModelConfig};
+
+use super::model_router::{ModelPurpose, ModelRouter};
 
 /// Global Tokio runtime for HTTP requests
 static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
 
+/// Upper bound for a streamed completion (the client's 30s timeout is too short)
+const STREAM_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+
 /// AI service for code annotation and analysis
 pub struct AiService {
     /// Shared HTTP client for making API requests
@@ -34,8 +43,43 @@ pub struct AiServiceConfig {
     /// Default model to use (first enabled model)
     pub default_model: Option<String>,
     /// Global system prompts for AI features
-    /// Keys: "doc_comment", "inline_comment", "explain", "improve"
+    /// Keys: "doc_comment", "inline_comment", "explain", "improve", "chat",
+    /// "commit_message", "pr_description", "follow_ups"
     pub system_prompts: HashMap<String, String>,
+    /// Picks the models that serve each kind of request
+    pub router: ModelRouter,
+}
+
+/// Staged diffs longer than this are truncated before being sent
+const MAX_COMMIT_DIFF_CHARS: usize = 24_000;
+
+/// Session digests longer than this are truncated before being sent
+const MAX_PR_SESSION_CHARS: usize = 32_000;
+
+/// The prompt and reply sent for follow-up suggestions are truncated to this
+const MAX_FOLLOW_UP_CONTEXT_CHARS: usize = 4_000;
+
+/// Replies longer than this are truncated before being translated
+const MAX_TRANSLATE_CHARS: usize = 16_000;
+
+/// Formatting options for generated commit messages
+#[derive(Clone, Debug, Default)]
+pub struct CommitMessageOptions {
+    /// Use the Conventional Commits format, `type(scope): summary`
+    pub conventional: bool,
+    /// Conventional commit scope; the model picks one when unset
+    pub scope: Option<String>,
+}
+
+/// Sampling parameters for a streamed chat; unset ones are left to the
+/// provider's defaults
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChatOptions {
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    /// Number of replies to generate (`n`); providers without support for
+    /// it answer once
+    pub variants: Option<u32>,
 }
 
 /// Style of code comment to generate
@@ -58,10 +102,46 @@ struct ChatCompletionRequest {
     temperature: Option<f32>,
 }
 
+/// Request body for a streamed chat completion
 #[derive(Debug, Serialize)]
-struct ChatMessage {
-    role: String,
-    content: String,
+struct StreamingChatRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<u32>,
+}
+
+/// One message of a chat completion conversation
+#[derive(Clone, Debug, Serialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new("system", content)
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new("user", content)
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new("assistant", content)
+    }
+
+    fn new(role: &str, content: impl Into<String>) -> Self {
+        Self {
+            role: role.to_string(),
+            content: content.into(),
+        }
+    }
 }
 
 /// Response from OpenAI Chat Completions API
@@ -80,24 +160,64 @@ struct ChatMessageResponse {
     content: String,
 }
 
+/// One server-sent event of a streamed chat completion
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChunk {
+    choices: Vec<ChatChunkChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChunkChoice {
+    #[serde(default)]
+    index: usize,
+    #[serde(default)]
+    delta: ChatChunkDelta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChatChunkDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// Request body for OpenAI Embeddings API
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+/// Response from OpenAI Embeddings API
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Response of Ollama's `/api/tags` (locally available models)
+#[derive(Debug, Deserialize)]
+struct OllamaTagsResponse {
+    #[serde(default)]
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
 impl AiService {
     /// Create a new AI service with the given model configurations and system prompts
     pub fn new(
         models: HashMap<String, ModelConfig>,
         system_prompts: HashMap<String, String>,
     ) -> Self {
-        // Get or create Tokio runtime
-        let runtime_handle = tokio::runtime::Handle::try_current().unwrap_or_else(|_| {
-            log::debug!("No Tokio runtime found, creating one for AI service...");
-            let runtime = RUNTIME.get_or_init(|| {
-                tokio::runtime::Builder::new_multi_thread()
-                    .worker_threads(2)
-                    .enable_all()
-                    .build()
-                    .expect("Failed to initialize Tokio runtime for AI service")
-            });
-            runtime.handle().clone()
-        });
+        let runtime_handle = runtime_handle();
 
         // Build HTTP client with timeout
         let http_client = reqwest::Client::builder()
@@ -121,6 +241,7 @@ impl AiService {
             models,
             default_model,
             system_prompts,
+            router: ModelRouter::default(),
         };
 
         Self {
@@ -149,8 +270,19 @@ impl AiService {
         log::info!("AI Service configuration updated");
     }
 
+    /// Route internal features to the configured utility model
+    pub fn set_utility_model(&self, utility: UtilityModelConfig) {
+        log::info!("Utility AI model: {:?}", utility.model);
+        self.config.write().unwrap().router = ModelRouter::new(utility);
+    }
+
+    /// Whether a utility model is configured for internal features
+    pub fn has_utility_model(&self) -> bool {
+        self.config.read().unwrap().router.utility_model().is_some()
+    }
+
     /// Get system prompt from config or use default
-    fn get_system_prompt(&self, prompt_key: &str, default_prompt: &str) -> String {
+    pub(crate) fn get_system_prompt(&self, prompt_key: &str, default_prompt: &str) -> String {
         let config = self.config.read().unwrap();
 
         if let Some(custom_prompt) = config.system_prompts.get(prompt_key) {
@@ -162,22 +294,66 @@ impl AiService {
         default_prompt.to_string()
     }
 
-    /// Call OpenAI-compatible API with system and user prompts
+    /// Call OpenAI-compatible API with system and user prompts on the default model
     async fn call_api(
         &self,
         system_prompt: &str,
         user_prompt: &str,
         max_tokens: Option<u32>,
+    ) -> Result<String> {
+        self.complete(ModelPurpose::Assist, system_prompt, user_prompt, max_tokens)
+            .await
+    }
+
+    /// Run a chat completion on the models routed for `purpose`
+    ///
+    /// Each candidate is tried in turn until one answers; the last failure is
+    /// returned when none does.
+    pub async fn complete(
+        &self,
+        purpose: ModelPurpose,
+        system_prompt: &str,
+        user_prompt: &str,
+        max_tokens: Option<u32>,
+    ) -> Result<String> {
+        let candidates = {
+            let config = self.config.read().unwrap();
+            config
+                .router
+                .candidates(purpose, &config.models, config.default_model.as_deref())
+        };
+        if candidates.is_empty() {
+            return Err(anyhow!("No default AI model configured"));
+        }
+
+        let mut last_error = None;
+        for model_name in candidates {
+            match self
+                .call_model(&model_name, system_prompt, user_prompt, max_tokens)
+                .await
+            {
+                Ok(content) => return Ok(content),
+                Err(e) => {
+                    log::warn!("AI model '{}' failed for {:?}: {:#}", model_name, purpose, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No AI model answered")))
+    }
+
+    /// Call one configured model's chat completions endpoint
+    async fn call_model(
+        &self,
+        model_name: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+        max_tokens: Option<u32>,
     ) -> Result<String> {
         // Extract config data and release lock immediately
         let (url, model_name, api_key) = {
             let config = self.config.read().unwrap();
 
-            let model_name = config
-                .default_model
-                .as_ref()
-                .ok_or_else(|| anyhow!("No default AI model configured"))?;
-
             let model_config = config
                 .models
                 .get(model_name)
@@ -187,10 +363,7 @@ impl AiService {
                 return Err(anyhow!("Model '{}' is disabled", model_name));
             }
 
-            let url = format!(
-                "{}/chat/completions",
-                model_config.base_url.trim_end_matches('/')
-            );
+            let url = format!("{}/chat/completions", model_config.api_base());
 
             (
                 url,
@@ -244,12 +417,7 @@ impl AiService {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
 
-            return Err(match status.as_u16() {
-                401 => anyhow!("Invalid API key. Please check your config.json"),
-                429 => anyhow!("API rate limit reached. Please try again later"),
-                500..=599 => anyhow!("AI service error: {}", error_text),
-                _ => anyhow!("API request failed ({}): {}", status, error_text),
-            });
+            return Err(api_error(status, &error_text));
         }
 
         let response_text = response
@@ -269,6 +437,187 @@ impl AiService {
         Ok(content)
     }
 
+    /// Stream a chat completion from the model configured as `model`
+    ///
+    /// Text deltas are sent to the returned channel as they arrive, with the
+    /// index of the reply they belong to (always 0 unless several
+    /// `variants` were asked for). The channel closes when the completion
+    /// ends; a failed request yields one error. Dropping the receiver stops
+    /// reading the response.
+    pub fn stream_chat(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        options: ChatOptions,
+    ) -> Result<mpsc::UnboundedReceiver<Result<(usize, String)>>> {
+        let (url, model_name, api_key) = {
+            let config = self.config.read().unwrap();
+            let model_config = config
+                .models
+                .get(model)
+                .ok_or_else(|| anyhow!("Model '{}' not found in configuration", model))?;
+            if !model_config.enabled {
+                return Err(anyhow!("Model '{}' is disabled", model));
+            }
+            (
+                format!("{}/chat/completions", model_config.api_base()),
+                model_config.model_name.clone(),
+                model_config.api_key.clone(),
+            )
+        };
+
+        let body = serde_json::to_string(&StreamingChatRequest {
+            model: &model_name,
+            messages,
+            stream: true,
+            max_tokens: options.max_tokens,
+            temperature: options.temperature,
+            n: options.variants.filter(|&n| n > 1),
+        })
+        .context("Failed to serialize request")?;
+
+        log::debug!("Streaming chat completion: {} (model: {})", url, model_name);
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let http_client = self.http_client.clone();
+        self.runtime_handle.spawn(async move {
+            let response = match http_client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", api_key))
+                .timeout(STREAM_TIMEOUT)
+                .body(body)
+                .send()
+                .await
+            {
+                Ok(response) => response,
+                Err(e) => {
+                    let _ = tx.send(Err(anyhow!("Failed to send request to AI service: {}", e)));
+                    return;
+                }
+            };
+
+            let status = response.status();
+            if !status.is_success() {
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+                let _ = tx.send(Err(api_error(status, &error_text)));
+                return;
+            }
+
+            // Events are newline-delimited; a network chunk may end mid-line
+            let mut buffer: Vec<u8> = Vec::new();
+            let mut stream = response.bytes_stream();
+            while let Some(chunk) = stream.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        let _ = tx.send(Err(anyhow!("AI service stream failed: {}", e)));
+                        return;
+                    }
+                };
+                buffer.extend_from_slice(&chunk);
+                while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=end).collect();
+                    if let Some(delta) = parse_stream_line(&String::from_utf8_lossy(&line))
+                        && tx.send(Ok(delta)).is_err()
+                    {
+                        // Receiver dropped: the turn was cancelled
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+
+    /// Embed `inputs` with an OpenAI-compatible `/embeddings` endpoint
+    ///
+    /// `provider` names the entry in `models` whose endpoint and key are used
+    /// (the default model when `None`); `model_name` is the embeddings model.
+    /// Returns one vector per input, in input order.
+    pub async fn embed(
+        &self,
+        provider: Option<&str>,
+        model_name: &str,
+        inputs: &[String],
+    ) -> Result<Vec<Vec<f32>>> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (url, api_key) = {
+            let config = self.config.read().unwrap();
+            let provider = provider
+                .or(config.default_model.as_deref())
+                .ok_or_else(|| anyhow!("No AI model configured for embeddings"))?;
+            let model_config = config
+                .models
+                .get(provider)
+                .ok_or_else(|| anyhow!("Model '{}' not found in configuration", provider))?;
+            (
+                format!("{}/embeddings", model_config.api_base()),
+                model_config.api_key.clone(),
+            )
+        };
+
+        let body = serde_json::to_string(&EmbeddingRequest {
+            model: model_name,
+            input: inputs,
+        })
+        .context("Failed to serialize embeddings request")?;
+
+        log::debug!(
+            "Calling embeddings API: {} (model: {}, inputs: {})",
+            url,
+            model_name,
+            inputs.len()
+        );
+
+        let http_client = self.http_client.clone();
+        let response = self
+            .runtime_handle
+            .spawn(async move {
+                http_client
+                    .post(&url)
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .body(body)
+                    .send()
+                    .await
+            })
+            .await
+            .context("Failed to spawn HTTP request task")?
+            .context("Failed to send request to embeddings service")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow!("Embeddings request failed ({}): {}", status, error_text));
+        }
+
+        let response_text = response
+            .text()
+            .await
+            .context("Failed to read embeddings response")?;
+        let mut response: EmbeddingResponse = serde_json::from_str(&response_text)
+            .context("Failed to parse embeddings response")?;
+        if response.data.len() != inputs.len() {
+            return Err(anyhow!(
+                "Expected {} embeddings, got {}",
+                inputs.len(),
+                response.data.len()
+            ));
+        }
+        response.data.sort_by_key(|data| data.index);
+        Ok(response.data.into_iter().map(|data| data.embedding).collect())
+    }
+
     /// Generate code comment in the specified style
     ///
     /// # Arguments
@@ -323,6 +672,106 @@ impl AiService {
             .context("Failed to explain code")
     }
 
+    /// Write a commit message for a staged diff on the utility model
+    pub async fn generate_commit_message(
+        &self,
+        diff: &str,
+        options: &CommitMessageOptions,
+    ) -> Result<String> {
+        let default_system = "You write git commit messages. Describe what the change does and \
+                            why, with an imperative subject line of at most 72 characters, \
+                            followed by a blank line and a short body only when the change needs \
+                            explaining. Return ONLY the commit message, without code fences.";
+
+        let mut system_prompt = self.get_system_prompt("commit_message", default_system);
+        if options.conventional {
+            system_prompt.push_str(
+                "\n\nUse the Conventional Commits format for the subject line: \
+                 `type(scope): summary`, where type is one of feat, fix, docs, style, \
+                 refactor, perf, test, build, ci or chore.",
+            );
+            match options.scope.as_deref().map(str::trim) {
+                Some(scope) if !scope.is_empty() => {
+                    system_prompt.push_str(&format!(" Use the scope `{}`.", scope));
+                }
+                _ => system_prompt.push_str(" Pick a short scope, or omit it if none fits."),
+            }
+        }
+
+        let user_prompt = format!(
+            "Write a commit message for this staged diff:\n\n{}",
+            truncate_chars(diff, MAX_COMMIT_DIFF_CHARS)
+        );
+
+        let message = self
+            .complete(ModelPurpose::Utility, &system_prompt, &user_prompt, Some(300))
+            .await
+            .context("Failed to generate commit message")?;
+        Ok(strip_code_fence(&message))
+    }
+
+    /// Draft a pull request description from a digest of an agent session
+    ///
+    /// The digest lists the session's prompts, plan, changed files and the
+    /// agent's final reply; see `session_digest` in the conversation panel.
+    pub async fn generate_pr_description(&self, session: &str) -> Result<String> {
+        let default_system = "You write pull request descriptions in Markdown. Start with one or \
+                            two sentences on what the change does and why, then a \"Changes\" \
+                            list and a \"Testing\" section stating only what the session shows \
+                            was verified. Do not invent details. Return ONLY the description.";
+
+        let system_prompt = self.get_system_prompt("pr_description", default_system);
+        let user_prompt = format!(
+            "Write a pull request description for the work done in this session:\n\n{}",
+            truncate_chars(session, MAX_PR_SESSION_CHARS)
+        );
+
+        let description = self
+            .complete(ModelPurpose::Utility, &system_prompt, &user_prompt, Some(1000))
+            .await
+            .context("Failed to generate pull request description")?;
+        Ok(strip_code_fence(&description))
+    }
+
+    /// Suggest up to three prompts the user might send after an agent's reply
+    pub async fn suggest_follow_ups(&self, prompt: &str, reply: &str) -> Result<Vec<String>> {
+        let default_system = "You suggest what a developer might ask a coding agent next. Given \
+                            their last request and the agent's reply, return up to 3 short \
+                            follow-up prompts (under 8 words each), one per line, without \
+                            numbering or commentary, in the language of the conversation.";
+
+        let system_prompt = self.get_system_prompt("follow_ups", default_system);
+        let user_prompt = format!(
+            "Request:\n{}\n\nReply:\n{}",
+            truncate_chars(prompt, MAX_FOLLOW_UP_CONTEXT_CHARS),
+            truncate_chars(reply, MAX_FOLLOW_UP_CONTEXT_CHARS)
+        );
+
+        let suggestions = self
+            .complete(ModelPurpose::Utility, &system_prompt, &user_prompt, Some(100))
+            .await
+            .context("Failed to suggest follow-ups")?;
+        Ok(parse_suggestions(&suggestions))
+    }
+
+    /// Translate an agent reply into `language` on the utility model
+    pub async fn translate(&self, text: &str, language: &str) -> Result<String> {
+        let default_system = "You translate replies of a coding agent. Keep the Markdown \
+                            structure, and leave code blocks, inline code, identifiers, paths \
+                            and commands untranslated. Return ONLY the translation.";
+
+        let system_prompt = self.get_system_prompt("translate", default_system);
+        let user_prompt = format!(
+            "Translate into {}:\n\n{}",
+            language,
+            truncate_chars(text, MAX_TRANSLATE_CHARS)
+        );
+
+        self.complete(ModelPurpose::Utility, &system_prompt, &user_prompt, None)
+            .await
+            .context("Failed to translate")
+    }
+
     /// Suggest improvements for code
     ///
     /// # Arguments
@@ -347,10 +796,190 @@ impl AiService {
     }
 }
 
+/// Current Tokio runtime, or the AI service's own one outside of Tokio
+pub(crate) fn runtime_handle() -> tokio::runtime::Handle {
+    tokio::runtime::Handle::try_current().unwrap_or_else(|_| {
+        log::debug!("No Tokio runtime found, creating one for AI service...");
+        let runtime = RUNTIME.get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(2)
+                .enable_all()
+                .build()
+                .expect("Failed to initialize Tokio runtime for AI service")
+        });
+        runtime.handle().clone()
+    })
+}
+
+/// Names of the models pulled on the Ollama server at `server_url`
+///
+/// Works without a configured model, so settings can offer local models
+/// before any exist.
+pub async fn discover_ollama_models(server_url: &str) -> Result<Vec<String>> {
+    let url = format!("{}/api/tags", ollama_root(server_url));
+    log::debug!("Discovering Ollama models: {}", url);
+
+    let response = runtime_handle()
+        .spawn(async move {
+            reqwest::Client::new()
+                .get(&url)
+                .timeout(std::time::Duration::from_secs(5))
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await
+        })
+        .await
+        .context("Failed to spawn HTTP request task")?
+        .context("Failed to reach the Ollama server")?;
+
+    let tags: OllamaTagsResponse =
+        serde_json::from_str(&response).context("Failed to parse Ollama model list")?;
+    let mut models: Vec<String> = tags.models.into_iter().map(|model| model.name).collect();
+    models.sort();
+    Ok(models)
+}
+
+/// Ollama server address for a configured URL, which may point at its `/v1` API
+fn ollama_root(url: &str) -> &str {
+    let url = url.trim().trim_end_matches('/');
+    url.strip_suffix("/v1").unwrap_or(url)
+}
+
+/// `text` cut to `max_chars` characters, with a marker when something was cut
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}\n[... truncated ...]", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// A model's reply without the code fence it is sometimes wrapped in
+fn strip_code_fence(message: &str) -> String {
+    let message = message.trim();
+    let unfenced = message
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map(|inner| {
+            // Drop a language tag on the opening fence
+            inner.split_once('\n').map_or(inner, |(tag, body)| {
+                if tag.trim().chars().all(char::is_alphanumeric) {
+                    body
+                } else {
+                    inner
+                }
+            })
+        })
+        .unwrap_or(message);
+    unfenced.trim().to_string()
+}
+
+/// One suggestion per line, without list markers or numbering, at most three
+fn parse_suggestions(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(['-', '*', '•'])
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .trim_start_matches(['.', ')'])
+                .trim()
+                .trim_matches('"')
+                .to_string()
+        })
+        .filter(|line| !line.is_empty())
+        .take(3)
+        .collect()
+}
+
+/// Error for a non-success response of the chat completions endpoint
+fn api_error(status: reqwest::StatusCode, error_text: &str) -> anyhow::Error {
+    match status.as_u16() {
+        401 => anyhow!("Invalid API key. Please check your config.json"),
+        429 => anyhow!("API rate limit reached. Please try again later"),
+        500..=599 => anyhow!("AI service error: {}", error_text),
+        _ => anyhow!("API request failed ({}): {}", status, error_text),
+    }
+}
+
+/// Reply index and text carried by one `data:` line of a streamed
+/// completion, if any
+fn parse_stream_line(line: &str) -> Option<(usize, String)> {
+    let data = line.trim().strip_prefix("data:")?.trim();
+    if data == "[DONE]" {
+        return None;
+    }
+    let chunk: ChatCompletionChunk = serde_json::from_str(data).ok()?;
+    chunk.choices.into_iter().find_map(|choice| {
+        let content = choice.delta.content.filter(|content| !content.is_empty())?;
+        Some((choice.index, content))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_stream_line() {
+        let line = r#"data: {"choices":[{"index":0,"delta":{"content":"Hel"}}]}"#;
+        assert_eq!(parse_stream_line(line), Some((0, "Hel".to_string())));
+
+        let variant = r#"data: {"choices":[{"index":2,"delta":{"content":"lo"}}]}"#;
+        assert_eq!(parse_stream_line(variant), Some((2, "lo".to_string())));
+
+        let role_only = r#"data: {"choices":[{"index":0,"delta":{"role":"assistant"}}]}"#;
+        assert_eq!(parse_stream_line(role_only), None);
+        assert_eq!(parse_stream_line("data: [DONE]"), None);
+        assert_eq!(parse_stream_line(": keep-alive"), None);
+        assert_eq!(parse_stream_line(""), None);
+    }
+
+    #[test]
+    fn test_generated_text_helpers() {
+        assert_eq!(
+            strip_code_fence("```text\nfix: handle empty input\n```"),
+            "fix: handle empty input"
+        );
+        assert_eq!(
+            strip_code_fence("  Add retries\n\nBody  "),
+            "Add retries\n\nBody"
+        );
+
+        assert_eq!(truncate_chars("short", 10), "short");
+        assert!(truncate_chars("0123456789", 4).starts_with("0123\n[..."));
+    }
+
+    #[test]
+    fn test_parse_suggestions() {
+        let text = "1. Add tests\n- \"Explain this change\"\n\n2) Run clippy\n* One more";
+        assert_eq!(
+            parse_suggestions(text),
+            vec!["Add tests", "Explain this change", "Run clippy"]
+        );
+    }
+
+    #[test]
+    fn test_ollama_urls() {
+        assert_eq!(ollama_root("http://localhost:11434/v1/"), "http://localhost:11434");
+        assert_eq!(ollama_root("http://localhost:11434"), "http://localhost:11434");
+
+        let mut config = ModelConfig {
+            enabled: true,
+            provider: "Ollama".to_string(),
+            base_url: "http://localhost:11434/".to_string(),
+            api_key: String::new(),
+            model_name: "llama3.2".to_string(),
+        };
+        assert_eq!(config.api_base(), "http://localhost:11434/v1");
+        config.base_url = "http://localhost:11434/v1".to_string();
+        assert_eq!(config.api_base(), "http://localhost:11434/v1");
+
+        config.provider = "openai".to_string();
+        config.base_url = "https://api.openai.com/v1/".to_string();
+        assert_eq!(config.api_base(), "https://api.openai.com/v1");
+    }
+
     fn create_test_config() -> HashMap<String, ModelConfig> {
         let mut models = HashMap::new();
         models.insert(
@@ -0,0 +1,482 @@
+//! Audit Service - Append-only log of agent side effects
+//!
+//! Records every file an agent writes, every command it runs and every
+//! permission decision, with the time and the session, to `audit.jsonl` in
+//! the user data directory. The log is kept apart from session histories:
+//! entries are only ever appended, and deleting a session leaves them in place.
+
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use agent_client_protocol::{SessionUpdate, ToolCallContent, ToolCallStatus, ToolKind};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::metrics_service::{ExportFormat, csv_field};
+use crate::core::agent::{command_policy::command_from_input, permission_policy::tool_kind_name};
+use crate::core::event_bus::SessionUpdateBusContainer;
+
+/// Who answered a permission request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionSource {
+    User,
+    /// A `permission_policies` rule
+    Policy,
+    /// The `command_policy` allow/deny lists
+    CommandPolicy,
+    /// The session's task is locked
+    SessionLock,
+    /// The user allowed the tool kind for the rest of the session
+    SessionApproval,
+}
+
+/// What an agent did
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A file was edited, deleted or moved
+    FileWrite { path: String, operation: String },
+    /// A shell command finished
+    Command { command: String, succeeded: bool },
+    /// A permission request was answered
+    PermissionDecision {
+        tool: String,
+        decision: String,
+        decided_by: DecisionSource,
+    },
+}
+
+/// Kinds of entries, for filtering
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditKind {
+    FileWrite,
+    Command,
+    PermissionDecision,
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub session_id: String,
+    pub agent_name: String,
+    #[serde(flatten)]
+    pub action: AuditAction,
+}
+
+impl AuditEntry {
+    pub fn new(session_id: &str, agent_name: &str, action: AuditAction) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            session_id: session_id.to_string(),
+            agent_name: agent_name.to_string(),
+            action,
+        }
+    }
+
+    pub fn kind(&self) -> AuditKind {
+        match self.action {
+            AuditAction::FileWrite { .. } => AuditKind::FileWrite,
+            AuditAction::Command { .. } => AuditKind::Command,
+            AuditAction::PermissionDecision { .. } => AuditKind::PermissionDecision,
+        }
+    }
+
+    /// The file, command or tool the entry is about
+    pub fn subject(&self) -> &str {
+        match &self.action {
+            AuditAction::FileWrite { path, .. } => path,
+            AuditAction::Command { command, .. } => command,
+            AuditAction::PermissionDecision { tool, .. } => tool,
+        }
+    }
+
+    /// What happened to the subject: the file operation, the command's
+    /// outcome or the decision taken
+    pub fn outcome(&self) -> String {
+        match &self.action {
+            AuditAction::FileWrite { operation, .. } => operation.clone(),
+            AuditAction::Command {
+                succeeded: true, ..
+            } => "succeeded".to_string(),
+            AuditAction::Command {
+                succeeded: false, ..
+            } => "failed".to_string(),
+            AuditAction::PermissionDecision {
+                decision,
+                decided_by,
+                ..
+            } => {
+                let by = serde_json::to_value(decided_by)
+                    .ok()
+                    .and_then(|value| value.as_str().map(str::to_string))
+                    .unwrap_or_default();
+                format!("{} ({})", decision, by)
+            }
+        }
+    }
+}
+
+/// Entries shown in the Audit panel
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// Only entries of this kind
+    pub kind: Option<AuditKind>,
+    /// Only entries whose session, agent or subject contains this text
+    pub query: String,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        if self.kind.is_some_and(|kind| kind != entry.kind()) {
+            return false;
+        }
+        let query = self.query.trim().to_lowercase();
+        query.is_empty()
+            || [&entry.session_id, &entry.agent_name, entry.subject()]
+                .iter()
+                .any(|text| text.to_lowercase().contains(&query))
+    }
+}
+
+/// Render `entries` for export
+pub fn render_entries(entries: &[AuditEntry], format: ExportFormat) -> anyhow::Result<String> {
+    match format {
+        ExportFormat::Json => Ok(serde_json::to_string_pretty(entries)?),
+        ExportFormat::Csv => {
+            let mut csv = String::from("timestamp,session_id,agent_name,kind,subject,outcome\n");
+            for entry in entries {
+                let kind = match entry.kind() {
+                    AuditKind::FileWrite => "file_write",
+                    AuditKind::Command => "command",
+                    AuditKind::PermissionDecision => "permission_decision",
+                };
+                let fields = [
+                    entry.timestamp.to_rfc3339(),
+                    csv_field(&entry.session_id),
+                    csv_field(&entry.agent_name),
+                    kind.to_string(),
+                    csv_field(entry.subject()),
+                    csv_field(&entry.outcome()),
+                ];
+                csv.push_str(&fields.join(","));
+                csv.push('\n');
+            }
+            Ok(csv)
+        }
+    }
+}
+
+/// A tool call whose side effect is recorded once it finishes
+#[derive(Debug, Clone, Default)]
+struct TrackedToolCall {
+    kind: Option<ToolKind>,
+    title: String,
+    paths: Vec<PathBuf>,
+    command: Option<String>,
+}
+
+impl TrackedToolCall {
+    /// Entries for the finished tool call
+    fn entries(self, succeeded: bool) -> Vec<AuditAction> {
+        match self.kind {
+            Some(ToolKind::Execute) => vec![AuditAction::Command {
+                command: self.command.unwrap_or(self.title),
+                succeeded,
+            }],
+            Some(kind @ (ToolKind::Edit | ToolKind::Delete | ToolKind::Move)) if succeeded => {
+                let operation = tool_kind_name(Some(&kind));
+                let mut paths: Vec<String> = self
+                    .paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                if paths.is_empty() {
+                    paths.push(self.title);
+                }
+                paths
+                    .into_iter()
+                    .map(|path| AuditAction::FileWrite {
+                        path,
+                        operation: operation.clone(),
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Audit service - appends side effects to the audit log and reads it back
+pub struct AuditService {
+    path: PathBuf,
+    /// Serializes appends from the agent threads
+    write_lock: Mutex<()>,
+    /// Unfinished tool calls, by session and tool call id
+    tool_calls: Mutex<HashMap<(String, String), TrackedToolCall>>,
+    /// Bumped on every append, so views know when to reload
+    revision: AtomicU64,
+}
+
+impl AuditService {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            write_lock: Mutex::new(()),
+            tool_calls: Mutex::new(HashMap::new()),
+            revision: AtomicU64::new(0),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::SeqCst)
+    }
+
+    /// Append `entry` to the log
+    pub fn record(&self, entry: AuditEntry) {
+        let _guard = self.write_lock.lock().unwrap();
+        let result = (|| -> anyhow::Result<()> {
+            if let Some(parent) = self.path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+            Ok(())
+        })();
+        match result {
+            Ok(()) => {
+                self.revision.fetch_add(1, Ordering::SeqCst);
+            }
+            Err(e) => log::error!("Failed to write audit log {:?}: {}", self.path, e),
+        }
+    }
+
+    /// All entries, oldest first; unreadable lines are skipped
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        let Ok(content) = std::fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    /// Record file writes and commands as the agents report their tool calls
+    pub fn start(self: &std::sync::Arc<Self>, session_bus: &SessionUpdateBusContainer) {
+        let service = self.clone();
+        session_bus.subscribe(move |event| {
+            let agent_name = event.agent_name.as_deref().unwrap_or_default();
+            service.observe(&event.session_id, agent_name, &event.update);
+        });
+    }
+
+    /// Track a session update, recording the tool calls it finishes
+    pub fn observe(&self, session_id: &str, agent_name: &str, update: &SessionUpdate) {
+        let (id, status) = {
+            let mut tool_calls = self.tool_calls.lock().unwrap();
+            match update {
+                SessionUpdate::ToolCall(tool_call) => {
+                    let id = tool_call.tool_call_id.to_string();
+                    let tracked = tool_calls
+                        .entry((session_id.to_string(), id.clone()))
+                        .or_default();
+                    tracked.kind = Some(tool_call.kind);
+                    tracked.title = tool_call.title.clone();
+                    track_paths(tracked, &tool_call.locations, &tool_call.content);
+                    if let Some(input) = &tool_call.raw_input {
+                        tracked.command = command_from_input(input).or(tracked.command.take());
+                    }
+                    (id, Some(tool_call.status))
+                }
+                SessionUpdate::ToolCallUpdate(update) => {
+                    let id = update.tool_call_id.to_string();
+                    let tracked = tool_calls
+                        .entry((session_id.to_string(), id.clone()))
+                        .or_default();
+                    let fields = &update.fields;
+                    if let Some(kind) = fields.kind {
+                        tracked.kind = Some(kind);
+                    }
+                    if let Some(title) = &fields.title {
+                        tracked.title = title.clone();
+                    }
+                    track_paths(
+                        tracked,
+                        fields.locations.as_deref().unwrap_or_default(),
+                        fields.content.as_deref().unwrap_or_default(),
+                    );
+                    if let Some(input) = &fields.raw_input {
+                        tracked.command = command_from_input(input).or(tracked.command.take());
+                    }
+                    (id, fields.status)
+                }
+                _ => return,
+            }
+        };
+
+        let succeeded = match status {
+            Some(ToolCallStatus::Completed) => true,
+            Some(ToolCallStatus::Failed) => false,
+            _ => return,
+        };
+        let finished = self
+            .tool_calls
+            .lock()
+            .unwrap()
+            .remove(&(session_id.to_string(), id));
+        for action in finished
+            .into_iter()
+            .flat_map(|call| call.entries(succeeded))
+        {
+            self.record(AuditEntry::new(session_id, agent_name, action));
+        }
+    }
+}
+
+fn track_paths(
+    tracked: &mut TrackedToolCall,
+    locations: &[agent_client_protocol::ToolCallLocation],
+    content: &[ToolCallContent],
+) {
+    let diff_paths = content.iter().filter_map(|content| match content {
+        ToolCallContent::Diff(diff) => Some(diff.path.clone()),
+        _ => None,
+    });
+    for path in locations
+        .iter()
+        .map(|location| location.path.clone())
+        .chain(diff_paths)
+    {
+        if !tracked.paths.contains(&path) {
+            tracked.paths.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use agent_client_protocol::{ToolCall, ToolCallId, ToolCallUpdate, ToolCallUpdateFields};
+
+    use super::*;
+
+    fn temp_log(name: &str) -> AuditService {
+        let path = std::env::temp_dir().join(format!(
+            "agentx-audit-{}-{}.jsonl",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        AuditService::new(path)
+    }
+
+    fn finish(id: &str, status: ToolCallStatus) -> SessionUpdate {
+        let mut fields = ToolCallUpdateFields::default();
+        fields.status = Some(status);
+        SessionUpdate::ToolCallUpdate(ToolCallUpdate::new(
+            ToolCallId::from(id.to_string()),
+            fields,
+        ))
+    }
+
+    #[test]
+    fn test_records_finished_tool_calls() {
+        let service = temp_log("tool-calls");
+
+        let mut edit = ToolCall::new(ToolCallId::from("1".to_string()), "Edit".to_string());
+        edit.kind = ToolKind::Edit;
+        edit.locations = vec![agent_client_protocol::ToolCallLocation::new(
+            "/repo/src/lib.rs",
+        )];
+        let mut command = ToolCall::new(ToolCallId::from("2".to_string()), "Run".to_string());
+        command.kind = ToolKind::Execute;
+        command.raw_input = Some(serde_json::json!({ "command": "cargo test" }));
+        let mut read = ToolCall::new(ToolCallId::from("3".to_string()), "Read".to_string());
+        read.kind = ToolKind::Read;
+
+        for update in [
+            SessionUpdate::ToolCall(edit),
+            SessionUpdate::ToolCall(command),
+            SessionUpdate::ToolCall(read),
+        ] {
+            service.observe("s1", "claude", &update);
+        }
+        assert!(service.entries().is_empty());
+
+        service.observe("s1", "claude", &finish("1", ToolCallStatus::Completed));
+        service.observe("s1", "claude", &finish("2", ToolCallStatus::Failed));
+        service.observe("s1", "claude", &finish("3", ToolCallStatus::Completed));
+
+        let actions: Vec<AuditAction> = service.entries().into_iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                AuditAction::FileWrite {
+                    path: "/repo/src/lib.rs".to_string(),
+                    operation: "edit".to_string(),
+                },
+                AuditAction::Command {
+                    command: "cargo test".to_string(),
+                    succeeded: false,
+                },
+            ]
+        );
+        assert_eq!(service.revision(), 2);
+        let _ = std::fs::remove_file(service.path());
+    }
+
+    #[test]
+    fn test_filter_and_csv() {
+        let entries = vec![
+            AuditEntry::new(
+                "s1",
+                "claude",
+                AuditAction::PermissionDecision {
+                    tool: "rm -rf build".to_string(),
+                    decision: "reject_once".to_string(),
+                    decided_by: DecisionSource::CommandPolicy,
+                },
+            ),
+            AuditEntry::new(
+                "s2",
+                "codex",
+                AuditAction::FileWrite {
+                    path: "/repo/a, b.txt".to_string(),
+                    operation: "edit".to_string(),
+                },
+            ),
+        ];
+        let filter = AuditFilter {
+            kind: Some(AuditKind::FileWrite),
+            query: String::new(),
+        };
+        assert_eq!(entries.iter().filter(|e| filter.matches(e)).count(), 1);
+        let filter = AuditFilter {
+            kind: None,
+            query: "RM -RF".to_string(),
+        };
+        assert!(filter.matches(&entries[0]) && !filter.matches(&entries[1]));
+
+        let csv = render_entries(&entries, ExportFormat::Csv).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert!(
+            rows[1].ends_with(
+                ",s1,claude,permission_decision,rm -rf build,reject_once (command_policy)"
+            )
+        );
+        assert!(rows[2].contains(",\"/repo/a, b.txt\",edit"));
+    }
+}
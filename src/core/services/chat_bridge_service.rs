@@ -0,0 +1,573 @@
+//! Chat Bridge Service - Prompt sessions from Slack or Discord
+//!
+//! Each bridge in config.json names a channel and a bot token. The channel is
+//! polled through the platform's web API, so no public endpoint is needed.
+//! Messages starting with the bridge's prefix (`!agentx` by default) are
+//! commands:
+//!
+//! - `!agentx list`: the tasks that can be prompted, with short session IDs
+//! - `!agentx <session ID prefix> <prompt>`: send the prompt to the task's
+//!   session and reply with the agent's answer once the turn is done
+//!
+//! Only the users in `allowed_users` may send commands. Messages from before
+//! the bridge started, and those of bots, are ignored.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use agent_client_protocol::{ContentBlock, TextContent};
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::{Value, json};
+
+use super::agent_service::AgentService;
+use super::ai_service::runtime_handle;
+use super::message_service::MessageService;
+use super::persistence_service::PersistenceService;
+use super::session_export::{SessionTranscript, TranscriptItem};
+use super::workspace_service::WorkspaceService;
+use crate::core::config::{ChatBridgeConfig, ChatPlatform};
+use crate::schemas::workspace_schema::WorkspaceTask;
+
+/// Time between two polls of the bridged channels
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+/// Messages read per poll
+const PAGE_SIZE: usize = 50;
+/// Shortest session ID prefix accepted as a target
+const MIN_TARGET_CHARS: usize = 4;
+/// Tasks listed by `list`
+const MAX_LISTED_TASKS: usize = 20;
+/// Characters of a session ID shown by `list`
+const SHORT_ID_CHARS: usize = 8;
+
+/// A command sent in a bridged channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BridgeCommand {
+    Help,
+    List,
+    Prompt { target: String, prompt: String },
+}
+
+impl BridgeCommand {
+    /// The command in `text`, if it starts with `prefix`
+    pub fn parse(text: &str, prefix: &str) -> Option<Self> {
+        let rest = text.trim().strip_prefix(prefix)?;
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let rest = rest.trim_start();
+        let (first, prompt) = match rest.split_once(char::is_whitespace) {
+            Some((first, prompt)) => (first, prompt.trim()),
+            None => (rest, ""),
+        };
+        Some(match (first, prompt) {
+            ("list", "") => Self::List,
+            ("" | "help", _) => Self::Help,
+            (_, "") => Self::Help,
+            (target, prompt) => Self::Prompt {
+                target: target.to_string(),
+                prompt: prompt.to_string(),
+            },
+        })
+    }
+}
+
+/// A message read from a bridged channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatMessage {
+    /// `ts` on Slack, the snowflake ID on Discord
+    pub id: String,
+    pub user: String,
+    pub text: String,
+    pub from_bot: bool,
+}
+
+/// Messages of a Slack `conversations.history` response, oldest first
+fn parse_slack_history(response: &Value) -> Result<Vec<ChatMessage>> {
+    check_slack_response(response)?;
+    let messages = response["messages"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Slack response without messages"))?;
+    Ok(messages
+        .iter()
+        .rev()
+        .filter_map(|message| {
+            Some(ChatMessage {
+                id: message["ts"].as_str()?.to_string(),
+                user: message["user"].as_str().unwrap_or_default().to_string(),
+                text: slack_unescape(message["text"].as_str().unwrap_or_default()),
+                from_bot: message.get("bot_id").is_some()
+                    || message["subtype"].as_str() == Some("bot_message"),
+            })
+        })
+        .collect())
+}
+
+/// Slack answers errors with `ok: false` and a 200 status
+fn check_slack_response(response: &Value) -> Result<()> {
+    match response["ok"].as_bool() {
+        Some(true) => Ok(()),
+        _ => bail!(
+            "Slack API error: {}",
+            response["error"].as_str().unwrap_or("unknown")
+        ),
+    }
+}
+
+/// Slack escapes these three in message text
+fn slack_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Messages of a Discord channel messages response, oldest first
+fn parse_discord_messages(response: &Value) -> Result<Vec<ChatMessage>> {
+    let messages = response
+        .as_array()
+        .ok_or_else(|| anyhow!("Discord response is not a list of messages"))?;
+    Ok(messages
+        .iter()
+        .rev()
+        .filter_map(|message| {
+            Some(ChatMessage {
+                id: message["id"].as_str()?.to_string(),
+                user: message["author"]["id"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                text: message["content"].as_str().unwrap_or_default().to_string(),
+                from_bot: message["author"]["bot"].as_bool().unwrap_or(false),
+            })
+        })
+        .collect())
+}
+
+/// Longest message the platform accepts, in characters
+fn max_reply_chars(platform: ChatPlatform) -> usize {
+    match platform {
+        ChatPlatform::Slack => 3900,
+        ChatPlatform::Discord => 2000,
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// The agent's answer to the last prompt of a transcript
+fn last_reply(items: &[TranscriptItem]) -> String {
+    let start = items
+        .iter()
+        .rposition(|item| matches!(item, TranscriptItem::User { .. }))
+        .map_or(0, |ix| ix + 1);
+    items[start..]
+        .iter()
+        .filter_map(|item| match item {
+            TranscriptItem::Agent { text, .. } => Some(text.trim()),
+            _ => None,
+        })
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// The task whose session ID starts with `target`
+fn find_task<'a>(
+    tasks: &'a [WorkspaceTask],
+    target: &str,
+) -> std::result::Result<&'a WorkspaceTask, String> {
+    if target.chars().count() < MIN_TARGET_CHARS {
+        return Err(format!(
+            "Use at least {} characters of the session ID",
+            MIN_TARGET_CHARS
+        ));
+    }
+    let target = target.to_lowercase();
+    let mut matches = tasks.iter().filter(|task| {
+        task.session_id
+            .as_deref()
+            .is_some_and(|id| id.to_lowercase().starts_with(&target))
+    });
+    match (matches.next(), matches.next()) {
+        (Some(task), None) => Ok(task),
+        (None, _) => Err(format!("No session starts with `{}`", target)),
+        (Some(_), Some(_)) => Err(format!(
+            "Several sessions start with `{}`, use more of the ID",
+            target
+        )),
+    }
+}
+
+fn help(prefix: &str) -> String {
+    format!(
+        "`{0} list` lists the tasks, `{0} <session ID> <prompt>` prompts one of them",
+        prefix
+    )
+}
+
+/// Lets bridged Slack and Discord channels prompt existing sessions
+pub struct ChatBridgeService {
+    bridges: RwLock<Vec<ChatBridgeConfig>>,
+    /// Newest message seen, by platform and channel
+    cursors: Mutex<HashMap<(ChatPlatform, String), String>>,
+    agent_service: Arc<AgentService>,
+    message_service: Arc<MessageService>,
+    workspace_service: Arc<WorkspaceService>,
+    persistence_service: Arc<PersistenceService>,
+}
+
+impl ChatBridgeService {
+    pub fn new(
+        bridges: Vec<ChatBridgeConfig>,
+        agent_service: Arc<AgentService>,
+        message_service: Arc<MessageService>,
+        workspace_service: Arc<WorkspaceService>,
+        persistence_service: Arc<PersistenceService>,
+    ) -> Self {
+        warn_without_users(&bridges);
+        Self {
+            bridges: RwLock::new(bridges),
+            cursors: Mutex::new(HashMap::new()),
+            agent_service,
+            message_service,
+            workspace_service,
+            persistence_service,
+        }
+    }
+
+    /// Replace the bridges, e.g. after config.json was reloaded
+    pub fn set_bridges(&self, bridges: Vec<ChatBridgeConfig>) {
+        warn_without_users(&bridges);
+        *self.bridges.write().unwrap() = bridges;
+    }
+
+    /// Poll the bridged channels in the background
+    pub fn start(self: &Arc<Self>) {
+        let service = Arc::downgrade(self);
+        smol::spawn(async move {
+            loop {
+                let Some(service) = service.upgrade() else {
+                    break;
+                };
+                let bridges = service.bridges.read().unwrap().clone();
+                for bridge in bridges {
+                    if let Err(e) = service.poll(&bridge).await {
+                        log::warn!(
+                            "Polling {:?} channel {} failed: {:#}",
+                            bridge.platform,
+                            bridge.channel,
+                            e
+                        );
+                    }
+                }
+                drop(service);
+                smol::Timer::after(POLL_INTERVAL).await;
+            }
+        })
+        .detach();
+    }
+
+    /// Handle the messages posted in `bridge`'s channel since the last poll
+    async fn poll(self: &Arc<Self>, bridge: &ChatBridgeConfig) -> Result<()> {
+        let key = (bridge.platform, bridge.channel.clone());
+        let cursor = self.cursors.lock().unwrap().get(&key).cloned();
+        let messages = fetch_messages(bridge, cursor.as_deref()).await?;
+        // "0" reads the whole channel, for channels that were empty at first
+        let newest = messages
+            .last()
+            .map(|message| message.id.clone())
+            .or(cursor.clone())
+            .unwrap_or_else(|| "0".to_string());
+        self.cursors.lock().unwrap().insert(key, newest);
+        // The first poll only finds where the channel is at
+        if cursor.is_none() {
+            return Ok(());
+        }
+
+        for message in messages {
+            if message.from_bot {
+                continue;
+            }
+            let Some(command) = BridgeCommand::parse(&message.text, &bridge.prefix) else {
+                continue;
+            };
+            if !bridge.allowed_users.contains(&message.user) {
+                log::warn!(
+                    "Ignored chat bridge command of user {} in {}",
+                    message.user,
+                    bridge.channel
+                );
+                reply(
+                    bridge,
+                    &message.id,
+                    "You are not allowed to prompt sessions",
+                )
+                .await;
+                continue;
+            }
+            let (service, bridge) = (self.clone(), bridge.clone());
+            smol::spawn(async move { service.handle(&bridge, &message.id, command).await })
+                .detach();
+        }
+        Ok(())
+    }
+
+    async fn handle(&self, bridge: &ChatBridgeConfig, message_id: &str, command: BridgeCommand) {
+        let text = match command {
+            BridgeCommand::Help => help(&bridge.prefix),
+            BridgeCommand::List => self.list_tasks().await,
+            BridgeCommand::Prompt { target, prompt } => {
+                let tasks = self.workspace_service.get_all_tasks().await;
+                match find_task(&tasks, &target) {
+                    Ok(task) if task.locked => format!("\"{}\" is locked", task.name),
+                    Ok(task) => {
+                        reply(bridge, message_id, &format!("Sent to \"{}\"", task.name)).await;
+                        match self.prompt(task, &prompt).await {
+                            Ok(answer) => format!("*{}*\n{}", task.name, answer),
+                            Err(e) => format!("Prompting \"{}\" failed: {:#}", task.name, e),
+                        }
+                    }
+                    Err(reason) => reason,
+                }
+            }
+        };
+        reply(bridge, message_id, &text).await;
+    }
+
+    async fn list_tasks(&self) -> String {
+        let mut tasks: Vec<_> = self
+            .workspace_service
+            .get_all_tasks()
+            .await
+            .into_iter()
+            .filter(|task| task.session_id.is_some() && !task.locked)
+            .collect();
+        if tasks.is_empty() {
+            return "No task can be prompted".to_string();
+        }
+        tasks.sort_by(|a, b| b.last_updated.cmp(&a.last_updated));
+        tasks
+            .iter()
+            .take(MAX_LISTED_TASKS)
+            .map(|task| {
+                let session_id = task.session_id.as_deref().unwrap_or_default();
+                let short_id: String = session_id.chars().take(SHORT_ID_CHARS).collect();
+                format!("`{}` {} ({})", short_id, task.name, task.agent_name)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Send `prompt` to the task's session, resuming it first if needed, and
+    /// return the agent's answer
+    async fn prompt(&self, task: &WorkspaceTask, prompt: &str) -> Result<String> {
+        let session_id = task
+            .session_id
+            .as_deref()
+            .ok_or_else(|| anyhow!("The task has no session"))?;
+        if !self.agent_service.is_session_open(session_id) {
+            let cwd = match self
+                .workspace_service
+                .get_workspace(&task.workspace_id)
+                .await
+            {
+                Some(workspace) => workspace.path,
+                None => std::env::current_dir().unwrap_or_default(),
+            };
+            self.agent_service
+                .resume_session_with_mcp_and_cwd(&task.agent_name, session_id, Vec::new(), cwd)
+                .await
+                .context("Failed to resume the session")?;
+        }
+
+        let content = vec![ContentBlock::Text(TextContent::new(prompt.to_string()))];
+        self.message_service
+            .send_message_to_session(&task.agent_name, session_id, content)
+            .await?;
+
+        self.persistence_service.flush_session(session_id).await?;
+        let messages = self.persistence_service.load_messages(session_id).await?;
+        let transcript = SessionTranscript::from_history(session_id, &task.name, &messages);
+        let answer = last_reply(&transcript.items);
+        Ok(match answer.is_empty() {
+            true => "(no answer)".to_string(),
+            false => answer,
+        })
+    }
+}
+
+fn warn_without_users(bridges: &[ChatBridgeConfig]) {
+    for bridge in bridges
+        .iter()
+        .filter(|bridge| bridge.allowed_users.is_empty())
+    {
+        log::warn!(
+            "Chat bridge for {} has no allowed_users; its commands are ignored",
+            bridge.channel
+        );
+    }
+}
+
+async fn fetch_messages(
+    bridge: &ChatBridgeConfig,
+    after: Option<&str>,
+) -> Result<Vec<ChatMessage>> {
+    // Only the newest message is needed to find where the channel is at
+    let limit = if after.is_some() { PAGE_SIZE } else { 1 };
+    let client = reqwest::Client::new();
+    match bridge.platform {
+        ChatPlatform::Slack => {
+            let mut url = format!(
+                "https://slack.com/api/conversations.history?channel={}&limit={}",
+                bridge.channel, limit
+            );
+            if let Some(after) = after {
+                url.push_str(&format!("&oldest={}", after));
+            }
+            let request = client
+                .get(url)
+                .header("Authorization", format!("Bearer {}", bridge.token));
+            parse_slack_history(&request_json(request).await?)
+        }
+        ChatPlatform::Discord => {
+            let mut url = format!(
+                "https://discord.com/api/v10/channels/{}/messages?limit={}",
+                bridge.channel, limit
+            );
+            if let Some(after) = after {
+                url.push_str(&format!("&after={}", after));
+            }
+            let request = client
+                .get(url)
+                .header("Authorization", format!("Bot {}", bridge.token));
+            parse_discord_messages(&request_json(request).await?)
+        }
+    }
+}
+
+/// Post `text` in reply to the message `reply_to`; failures are logged
+async fn reply(bridge: &ChatBridgeConfig, reply_to: &str, text: &str) {
+    if let Err(e) = post_message(bridge, reply_to, text).await {
+        log::warn!("Replying in {} failed: {:#}", bridge.channel, e);
+    }
+}
+
+async fn post_message(bridge: &ChatBridgeConfig, reply_to: &str, text: &str) -> Result<()> {
+    let text = truncate(text, max_reply_chars(bridge.platform));
+    let client = reqwest::Client::new();
+    let request = match bridge.platform {
+        ChatPlatform::Slack => client
+            .post("https://slack.com/api/chat.postMessage")
+            .header("Authorization", format!("Bearer {}", bridge.token))
+            .body(
+                json!({ "channel": bridge.channel, "text": text, "thread_ts": reply_to })
+                    .to_string(),
+            ),
+        ChatPlatform::Discord => client
+            .post(format!(
+                "https://discord.com/api/v10/channels/{}/messages",
+                bridge.channel
+            ))
+            .header("Authorization", format!("Bot {}", bridge.token))
+            .body(
+                json!({ "content": text, "message_reference": { "message_id": reply_to } })
+                    .to_string(),
+            ),
+    };
+    let response = request_json(request.header("Content-Type", "application/json")).await?;
+    match bridge.platform {
+        ChatPlatform::Slack => check_slack_response(&response),
+        ChatPlatform::Discord => Ok(()),
+    }
+}
+
+async fn request_json(request: reqwest::RequestBuilder) -> Result<Value> {
+    let request = request.timeout(REQUEST_TIMEOUT);
+    let body = runtime_handle()
+        .spawn(async move { request.send().await?.error_for_status()?.text().await })
+        .await
+        .context("Failed to spawn chat bridge request task")?
+        .context("Chat bridge request failed")?;
+    serde_json::from_str(&body).context("Invalid chat API response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        let parse = |text| BridgeCommand::parse(text, "!agentx");
+        assert_eq!(parse("!agentx list"), Some(BridgeCommand::List));
+        assert_eq!(parse("  !agentx "), Some(BridgeCommand::Help));
+        assert_eq!(parse("!agentx 1a2b3c4d"), Some(BridgeCommand::Help));
+        assert_eq!(
+            parse("!agentx 1a2b3c4d  fix the\nlogin bug "),
+            Some(BridgeCommand::Prompt {
+                target: "1a2b3c4d".to_string(),
+                prompt: "fix the\nlogin bug".to_string(),
+            })
+        );
+        assert_eq!(parse("!agentxlist"), None);
+        assert_eq!(parse("hello !agentx list"), None);
+    }
+
+    #[test]
+    fn test_parse_messages() {
+        let slack = json!({
+            "ok": true,
+            "messages": [
+                { "ts": "2.0", "user": "U2", "text": "a &lt;b&gt; &amp; c" },
+                { "ts": "1.0", "bot_id": "B1", "text": "done" },
+            ],
+        });
+        let messages = parse_slack_history(&slack).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].from_bot);
+        assert_eq!(messages[1].id, "2.0");
+        assert_eq!(messages[1].text, "a <b> & c");
+        assert!(parse_slack_history(&json!({ "ok": false, "error": "not_in_channel" })).is_err());
+
+        let discord = json!([
+            { "id": "20", "content": "hi", "author": { "id": "7" } },
+            { "id": "10", "content": "done", "author": { "id": "9", "bot": true } },
+        ]);
+        let messages = parse_discord_messages(&discord).unwrap();
+        assert_eq!(messages[0].id, "10");
+        assert!(messages[0].from_bot);
+        assert_eq!(messages[1].user, "7");
+        assert!(!messages[1].from_bot);
+    }
+
+    #[test]
+    fn test_last_reply() {
+        let item = |user: bool, text: &str| match user {
+            true => TranscriptItem::User {
+                timestamp: String::new(),
+                text: text.to_string(),
+            },
+            false => TranscriptItem::Agent {
+                timestamp: String::new(),
+                text: text.to_string(),
+            },
+        };
+        let items = vec![
+            item(true, "first"),
+            item(false, "old answer"),
+            item(true, "second"),
+            item(false, "new"),
+            item(false, "answer "),
+        ];
+        assert_eq!(last_reply(&items), "new\n\nanswer");
+        assert_eq!(last_reply(&items[..3]), "");
+        assert_eq!(truncate("abcdef", 4), "abc…");
+        assert_eq!(truncate("abc", 4), "abc");
+    }
+}
@@ -0,0 +1,352 @@
+//! Direct Chat Service - Chats with the models in `Config.models` without an ACP agent
+//!
+//! Every enabled model is offered as an agent named `llm:<model>`, for quick
+//! questions that don't need a full coding agent. Prompts are flattened to text
+//! and sent with the session's history to the model's OpenAI-compatible
+//! endpoint; the streamed reply is published to the session bus as agent
+//! message chunks, so it renders through the same ConversationPanel pipeline
+//! as any agent's.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+use agent_client_protocol::{
+    self as acp, ContentBlock, ContentChunk, EmbeddedResourceResource, SessionUpdate,
+};
+
+use crate::core::event_bus::session_bus::{SessionUpdateBusContainer, SessionUpdateEvent};
+
+use super::ai_service::{AiService, ChatMessage, ChatOptions};
+use super::error::{ServiceError, ServiceResult};
+
+/// Agent name prefix that marks a direct model session
+pub const MODEL_AGENT_PREFIX: &str = "llm:";
+
+const DEFAULT_CHAT_PROMPT: &str = "You are a helpful assistant for software developers. \
+    Answer concisely and use Markdown code blocks for code.";
+
+/// The model named by a direct chat agent name (`llm:<model>`)
+pub fn model_for_agent(agent_name: &str) -> Option<&str> {
+    agent_name
+        .strip_prefix(MODEL_AGENT_PREFIX)
+        .filter(|model| !model.is_empty())
+}
+
+/// Direct chat service - runs chat sessions against configured models
+pub struct DirectChatService {
+    ai_service: Arc<AiService>,
+    session_bus: SessionUpdateBusContainer,
+    /// session_id -> conversation
+    sessions: RwLock<HashMap<String, ChatSession>>,
+}
+
+struct ChatSession {
+    model: String,
+    history: Vec<ChatMessage>,
+    /// Set to stop the turn in flight
+    cancelled: Arc<AtomicBool>,
+}
+
+impl DirectChatService {
+    pub fn new(ai_service: Arc<AiService>, session_bus: SessionUpdateBusContainer) -> Self {
+        Self {
+            ai_service,
+            session_bus,
+            sessions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Agent names of the enabled models, sorted
+    pub fn model_agents(&self) -> Vec<String> {
+        let config = self.ai_service.config.read().unwrap();
+        let mut agents: Vec<String> = config
+            .models
+            .iter()
+            .filter(|(_, model)| model.enabled)
+            .map(|(name, _)| format!("{}{}", MODEL_AGENT_PREFIX, name))
+            .collect();
+        agents.sort();
+        agents
+    }
+
+    /// Start a conversation with `model`
+    ///
+    /// Passing the ID of a restored session starts it over with an empty
+    /// history; earlier messages stay visible but are not resent.
+    pub fn open_session(
+        &self,
+        model: &str,
+        session_id: Option<&str>,
+    ) -> ServiceResult<acp::NewSessionResponse> {
+        let enabled = self
+            .ai_service
+            .config
+            .read()
+            .unwrap()
+            .models
+            .get(model)
+            .is_some_and(|config| config.enabled);
+        if !enabled {
+            return Err(ServiceError::AgentNotFound(format!(
+                "{}{}",
+                MODEL_AGENT_PREFIX, model
+            )));
+        }
+
+        let session_id = session_id
+            .map(str::to_string)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        self.sessions.write().unwrap().insert(
+            session_id.clone(),
+            ChatSession {
+                model: model.to_string(),
+                history: Vec::new(),
+                cancelled: Arc::new(AtomicBool::new(false)),
+            },
+        );
+        log::info!("Opened direct chat session {} with {}", session_id, model);
+        Ok(acp::NewSessionResponse::new(session_id))
+    }
+
+    /// Send a prompt and stream the model's reply to the session bus
+    pub async fn prompt(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        prompt: &[ContentBlock],
+    ) -> ServiceResult<acp::PromptResponse> {
+        let system_prompt = self
+            .ai_service
+            .get_system_prompt("chat", DEFAULT_CHAT_PROMPT);
+        let (model, messages, cancelled) = {
+            let mut sessions = self.sessions.write().unwrap();
+            let session = sessions
+                .get_mut(session_id)
+                .ok_or_else(|| ServiceError::SessionNotFound(session_id.to_string()))?;
+            session.history.push(ChatMessage::user(prompt_text(prompt)));
+            session.cancelled.store(false, Ordering::SeqCst);
+
+            let mut messages = vec![ChatMessage::system(system_prompt)];
+            messages.extend(session.history.iter().cloned());
+            (session.model.clone(), messages, session.cancelled.clone())
+        };
+
+        let result = self
+            .stream_reply(
+                agent_name,
+                session_id,
+                &model,
+                &messages,
+                ChatOptions::default(),
+                &cancelled,
+            )
+            .await;
+
+        let mut sessions = self.sessions.write().unwrap();
+        if let Some(session) = sessions.get_mut(session_id) {
+            match &result {
+                Ok((replies, _)) => session.history.push(ChatMessage::assistant(&replies[0])),
+                // Drop the unanswered prompt so the next one is not sent twice
+                Err(_) => {
+                    session.history.pop();
+                }
+            }
+        }
+        result.map(|(_, stop_reason)| acp::PromptResponse::new(stop_reason))
+    }
+
+    /// Answer the last prompt again, with `options` overriding the model's
+    /// sampling defaults
+    ///
+    /// The new reply is streamed to the session bus like any other and
+    /// replaces the previous one in the history; if it fails, the previous
+    /// reply is kept. When `options.variants` asks for more than one reply,
+    /// the others are returned, not streamed; `use_reply` switches to one.
+    pub async fn regenerate(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        options: ChatOptions,
+    ) -> ServiceResult<(acp::PromptResponse, Vec<String>)> {
+        let system_prompt = self
+            .ai_service
+            .get_system_prompt("chat", DEFAULT_CHAT_PROMPT);
+        let (model, messages, cancelled, previous) = {
+            let mut sessions = self.sessions.write().unwrap();
+            let session = sessions
+                .get_mut(session_id)
+                .ok_or_else(|| ServiceError::SessionNotFound(session_id.to_string()))?;
+            let has_reply = session
+                .history
+                .last()
+                .is_some_and(|message| message.role == "assistant");
+            if !has_reply {
+                return Err(ServiceError::InvalidInput(
+                    "There is no reply to regenerate".to_string(),
+                ));
+            }
+            let previous = session.history.pop();
+            session.cancelled.store(false, Ordering::SeqCst);
+
+            let mut messages = vec![ChatMessage::system(system_prompt)];
+            messages.extend(session.history.iter().cloned());
+            (
+                session.model.clone(),
+                messages,
+                session.cancelled.clone(),
+                previous,
+            )
+        };
+
+        let result = self
+            .stream_reply(
+                agent_name, session_id, &model, &messages, options, &cancelled,
+            )
+            .await;
+
+        let mut sessions = self.sessions.write().unwrap();
+        if let Some(session) = sessions.get_mut(session_id) {
+            match &result {
+                Ok((replies, _)) => session.history.push(ChatMessage::assistant(&replies[0])),
+                Err(_) => session.history.extend(previous),
+            }
+        }
+        result.map(|(replies, stop_reason)| {
+            let variants = replies
+                .into_iter()
+                .skip(1)
+                .filter(|reply| !reply.trim().is_empty())
+                .collect();
+            (acp::PromptResponse::new(stop_reason), variants)
+        })
+    }
+
+    /// Make `reply` the answer to the last prompt, so the conversation goes
+    /// on from an earlier attempt the user picked
+    pub fn use_reply(&self, session_id: &str, reply: &str) -> ServiceResult<()> {
+        let mut sessions = self.sessions.write().unwrap();
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| ServiceError::SessionNotFound(session_id.to_string()))?;
+        match session.history.last_mut() {
+            Some(message) if message.role == "assistant" => {
+                message.content = reply.to_string();
+                Ok(())
+            }
+            _ => Err(ServiceError::InvalidInput(
+                "There is no reply to replace".to_string(),
+            )),
+        }
+    }
+
+    async fn stream_reply(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        model: &str,
+        messages: &[ChatMessage],
+        options: ChatOptions,
+        cancelled: &AtomicBool,
+    ) -> ServiceResult<(Vec<String>, acp::StopReason)> {
+        let mut deltas = self
+            .ai_service
+            .stream_chat(model, messages, options)
+            .map_err(ServiceError::Other)?;
+
+        // Only the first reply is streamed; other variants are collected
+        let mut replies = vec![String::new()];
+        while let Some(delta) = deltas.recv().await {
+            if cancelled.load(Ordering::SeqCst) {
+                return Ok((replies, acp::StopReason::Cancelled));
+            }
+            let (index, delta) = delta.map_err(ServiceError::Other)?;
+            if index >= replies.len() {
+                replies.resize(index + 1, String::new());
+            }
+            if index > 0 {
+                replies[index].push_str(&delta);
+                continue;
+            }
+            self.session_bus.publish(SessionUpdateEvent {
+                session_id: session_id.to_string(),
+                agent_name: Some(agent_name.to_string()),
+                update: Arc::new(SessionUpdate::AgentMessageChunk(ContentChunk::new(
+                    ContentBlock::from(delta.clone()),
+                ))),
+            });
+            replies[0].push_str(&delta);
+        }
+        Ok((replies, acp::StopReason::EndTurn))
+    }
+
+    /// Stop the session's turn in flight; the partial reply is kept
+    pub fn cancel(&self, session_id: &str) -> ServiceResult<()> {
+        let sessions = self.sessions.read().unwrap();
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| ServiceError::SessionNotFound(session_id.to_string()))?;
+        session.cancelled.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub fn close_session(&self, session_id: &str) {
+        self.sessions.write().unwrap().remove(session_id);
+    }
+}
+
+/// A prompt's content as one chat message
+///
+/// Embedded text resources are inlined and links are kept as Markdown links;
+/// images are dropped since chat models are not assumed to accept them.
+fn prompt_text(prompt: &[ContentBlock]) -> String {
+    let mut parts = Vec::new();
+    for block in prompt {
+        match block {
+            ContentBlock::Text(text) => parts.push(text.text.clone()),
+            ContentBlock::Resource(resource) => {
+                if let EmbeddedResourceResource::TextResourceContents(contents) =
+                    &resource.resource
+                {
+                    parts.push(format!("{}:\n```\n{}\n```", contents.uri, contents.text));
+                }
+            }
+            ContentBlock::ResourceLink(link) => {
+                parts.push(format!("[{}]({})", link.name, link.uri));
+            }
+            _ => {}
+        }
+    }
+    parts.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::context_item::ContextItem;
+
+    #[test]
+    fn test_model_for_agent() {
+        assert_eq!(model_for_agent("llm:gpt-4o"), Some("gpt-4o"));
+        assert_eq!(model_for_agent("llm:"), None);
+        assert_eq!(model_for_agent("claude-code"), None);
+    }
+
+    #[test]
+    fn test_prompt_text_inlines_resources() {
+        let selection = ContextItem::Selection {
+            file_path: "/repo/src/lib.rs".into(),
+            start_line: 1,
+            end_line: 2,
+            content: "pub fn a() {}".into(),
+        };
+        let prompt = vec![
+            ContentBlock::from("What does this do?".to_string()),
+            selection.to_content_block(),
+        ];
+        assert_eq!(
+            prompt_text(&prompt),
+            "What does this do?\n\nfile:///repo/src/lib.rs#L1-L2:\n```\npub fn a() {}\n```"
+        );
+    }
+}
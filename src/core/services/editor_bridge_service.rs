@@ -0,0 +1,433 @@
+//! Editor Bridge Service - Code selections from external editors
+//!
+//! A small HTTP server on 127.0.0.1 lets editor plugins (VS Code, JetBrains,
+//! ...) send the user's selection to AgentX, the same way the built-in code
+//! editor does. On start the port and a random token are written to
+//! `editor-bridge.json` in the user data directory; every request must carry
+//! the token in an `X-AgentX-Token` header. Routes:
+//!
+//! - `GET /sessions`: the tasks with a session, to pick a target from
+//! - `POST /selection`: `{file_path, start_line, end_line, content}`, with
+//!   optional `start_column`, `end_column` and `session_id`
+//! - `POST /focus`: `{session_id}`
+//! - `POST /open`: `{url}`, an `agent-studio://` link (see `url_scheme`)
+//!
+//! Selections, focus requests and links are queued as [`EditorRequest`]s for
+//! the workspace, which opens the target session before adding the selection.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicUsize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+use super::http_server::{ConnectionSlot, read_head, reason_phrase};
+use super::workspace_service::WorkspaceService;
+use crate::app::actions::AddCodeSelection;
+use crate::core::url_scheme::OpenLink;
+
+/// Port tried first, so plugins usually find the same one; any free port is
+/// used when it is taken
+pub const DEFAULT_EDITOR_BRIDGE_PORT: u16 = 7472;
+
+/// Header carrying the token
+const TOKEN_HEADER: &str = "x-agentx-token";
+/// Longest request body accepted
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Something an editor asked the workspace to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorRequest {
+    /// Session to open and focus; the selection goes to the focused input
+    /// when there is none
+    pub session_id: Option<String>,
+    pub selection: Option<AddCodeSelection>,
+    /// Folder to open as a workspace
+    pub workspace: Option<PathBuf>,
+}
+
+impl EditorRequest {
+    /// The request an `agent-studio://` link makes
+    pub fn from_link(url: &str) -> Option<Self> {
+        let (session_id, workspace) = match OpenLink::parse(url)? {
+            OpenLink::Session(session_id) => (Some(session_id), None),
+            OpenLink::Workspace(path) => (None, Some(path)),
+        };
+        Some(Self {
+            session_id,
+            selection: None,
+            workspace,
+        })
+    }
+}
+
+/// What `editor-bridge.json` tells plugins
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditorBridgeInfo {
+    pub port: u16,
+    pub token: String,
+}
+
+/// A task as listed by `GET /sessions`
+#[derive(Debug, Serialize)]
+struct SessionEntry {
+    session_id: String,
+    task_name: String,
+    agent_name: String,
+    workspace_path: Option<PathBuf>,
+}
+
+/// Body of `POST /selection`
+#[derive(Debug, Deserialize)]
+struct SelectionRequest {
+    file_path: String,
+    start_line: u32,
+    #[serde(default = "first_column")]
+    start_column: u32,
+    end_line: u32,
+    #[serde(default = "first_column")]
+    end_column: u32,
+    content: String,
+    #[serde(default)]
+    session_id: Option<String>,
+}
+
+fn first_column() -> u32 {
+    1
+}
+
+/// Body of `POST /focus`
+#[derive(Debug, Deserialize)]
+struct FocusRequest {
+    session_id: String,
+}
+
+/// Body of `POST /open`
+#[derive(Debug, Serialize, Deserialize)]
+struct OpenRequest {
+    url: String,
+}
+
+/// What a request asks for
+#[derive(Debug, PartialEq, Eq)]
+enum Route {
+    Sessions,
+    Selection,
+    Focus,
+    Open,
+}
+
+/// Parse a request line like `POST /selection HTTP/1.1`
+fn parse_route(request_line: &str) -> std::result::Result<Route, u16> {
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(400);
+    };
+    let path = target.split_once('?').map_or(target, |(path, _)| path);
+    match (method, path) {
+        ("GET", "/sessions") => Ok(Route::Sessions),
+        ("POST", "/selection") => Ok(Route::Selection),
+        ("POST", "/focus") => Ok(Route::Focus),
+        ("POST", "/open") => Ok(Route::Open),
+        (_, "/sessions" | "/selection" | "/focus" | "/open") => Err(405),
+        _ => Err(404),
+    }
+}
+
+/// The request a `POST` body asks for
+fn parse_request(route: &Route, body: &[u8]) -> Option<EditorRequest> {
+    match route {
+        Route::Selection => {
+            let request: SelectionRequest = serde_json::from_slice(body).ok()?;
+            if request.file_path.is_empty() || request.start_line > request.end_line {
+                return None;
+            }
+            Some(EditorRequest {
+                session_id: request.session_id.filter(|id| !id.is_empty()),
+                selection: Some(AddCodeSelection {
+                    file_path: request.file_path,
+                    start_line: request.start_line,
+                    start_column: request.start_column,
+                    end_line: request.end_line,
+                    end_column: request.end_column,
+                    content: request.content,
+                }),
+                workspace: None,
+            })
+        }
+        Route::Focus => {
+            let request: FocusRequest = serde_json::from_slice(body).ok()?;
+            if request.session_id.is_empty() {
+                return None;
+            }
+            Some(EditorRequest {
+                session_id: Some(request.session_id),
+                selection: None,
+                workspace: None,
+            })
+        }
+        Route::Open => {
+            let request: OpenRequest = serde_json::from_slice(body).ok()?;
+            EditorRequest::from_link(&request.url)
+        }
+        Route::Sessions => None,
+    }
+}
+
+/// What the server threads work with
+struct ServerContext {
+    token: String,
+    requests: UnboundedSender<EditorRequest>,
+    workspace_service: Arc<WorkspaceService>,
+}
+
+pub struct EditorBridgeService {
+    workspace_service: Arc<WorkspaceService>,
+    requests: UnboundedSender<EditorRequest>,
+    /// Taken by the workspace, which carries the requests out
+    receiver: Mutex<Option<UnboundedReceiver<EditorRequest>>>,
+    /// Address of the server, once started
+    server: Mutex<Option<SocketAddr>>,
+}
+
+impl EditorBridgeService {
+    pub fn new(workspace_service: Arc<WorkspaceService>) -> Self {
+        let (requests, receiver) = unbounded_channel();
+        Self {
+            workspace_service,
+            requests,
+            receiver: Mutex::new(Some(receiver)),
+            server: Mutex::new(None),
+        }
+    }
+
+    /// Requests sent by editors; only the first caller gets them
+    pub fn take_requests(&self) -> Option<UnboundedReceiver<EditorRequest>> {
+        self.receiver.lock().unwrap().take()
+    }
+
+    /// Queue what an `agent-studio://` link asks for; false for other URLs
+    pub fn open_link(&self, url: &str) -> bool {
+        match EditorRequest::from_link(url) {
+            Some(request) => self.requests.send(request).is_ok(),
+            None => {
+                log::warn!("Ignoring unsupported link: {}", url);
+                false
+            }
+        }
+    }
+
+    /// Start the server and tell plugins about it in `info_path`
+    pub fn start(&self, info_path: &Path) -> Result<SocketAddr> {
+        let mut server = self.server.lock().unwrap();
+        if let Some(addr) = *server {
+            return Ok(addr);
+        }
+        let listener = TcpListener::bind(("127.0.0.1", DEFAULT_EDITOR_BRIDGE_PORT))
+            .or_else(|_| TcpListener::bind(("127.0.0.1", 0)))
+            .context("Failed to start the editor bridge")?;
+        let addr = listener.local_addr()?;
+        let info = EditorBridgeInfo {
+            port: addr.port(),
+            token: uuid::Uuid::new_v4().simple().to_string(),
+        };
+        write_private(info_path, &serde_json::to_string_pretty(&info)?)
+            .with_context(|| format!("Failed to write {}", info_path.display()))?;
+
+        let context = Arc::new(ServerContext {
+            token: info.token,
+            requests: self.requests.clone(),
+            workspace_service: self.workspace_service.clone(),
+        });
+        std::thread::Builder::new()
+            .name("editor-bridge".into())
+            .spawn(move || {
+                let active = Arc::new(AtomicUsize::new(0));
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else { continue };
+                    let Some(slot) = ConnectionSlot::acquire(&active) else {
+                        log::debug!("Editor bridge busy, closing a connection");
+                        continue;
+                    };
+                    let context = context.clone();
+                    std::thread::spawn(move || {
+                        let _slot = slot;
+                        if let Err(e) = handle_connection(stream, &context) {
+                            log::debug!("Editor bridge connection failed: {}", e);
+                        }
+                    });
+                }
+            })?;
+        log::info!("Editor bridge listening on {}", addr);
+        *server = Some(addr);
+        Ok(addr)
+    }
+}
+
+/// Write `contents` to `path`, readable only by the current user, since it
+/// holds the token
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // The mode only applies to new files; tighten one left by an earlier run
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
+/// Hand an `agent-studio://` link to the AgentX that wrote `info_path`
+///
+/// Returns false when no instance answers, e.g. the file is left over from
+/// one that quit, so the caller opens the link itself.
+pub fn forward_link(info_path: &Path, url: &str) -> bool {
+    let Some(info) = std::fs::read_to_string(info_path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<EditorBridgeInfo>(&raw).ok())
+    else {
+        return false;
+    };
+    let send = || -> std::io::Result<bool> {
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, info.port));
+        let mut stream = TcpStream::connect_timeout(&addr, Duration::from_secs(1))?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        let body = serde_json::to_string(&OpenRequest {
+            url: url.to_string(),
+        })?;
+        write!(
+            stream,
+            "POST /open HTTP/1.1\r\nHost: {}\r\n{}: {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            addr,
+            TOKEN_HEADER,
+            info.token,
+            body.len(),
+            body
+        )?;
+        let mut status_line = String::new();
+        BufReader::new(stream).read_line(&mut status_line)?;
+        Ok(status_line.split_whitespace().nth(1) == Some("200"))
+    };
+    send().unwrap_or(false)
+}
+
+/// The tasks with a session, most recently updated first
+fn list_sessions(workspace_service: &WorkspaceService) -> String {
+    let (mut tasks, workspaces) = smol::block_on(async {
+        (
+            workspace_service.get_all_tasks().await,
+            workspace_service.list_workspaces().await,
+        )
+    });
+    tasks.sort_by(|a, b| b.last_updated.cmp(&a.last_updated));
+    let entries: Vec<SessionEntry> = tasks
+        .into_iter()
+        .filter_map(|task| {
+            Some(SessionEntry {
+                session_id: task.session_id?,
+                workspace_path: workspaces
+                    .iter()
+                    .find(|workspace| workspace.id == task.workspace_id)
+                    .map(|workspace| workspace.path.clone()),
+                task_name: task.name,
+                agent_name: task.agent_name,
+            })
+        })
+        .collect();
+    serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn handle_connection(stream: TcpStream, context: &ServerContext) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(10)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let (status, body) = match read_head(&mut reader)? {
+        Err(status) => (status, String::new()),
+        Ok(head) => match parse_route(&head.request_line) {
+            Err(status) => (status, String::new()),
+            Ok(_) if head.header(TOKEN_HEADER) != Some(context.token.as_str()) => {
+                (401, String::new())
+            }
+            Ok(Route::Sessions) => (200, list_sessions(&context.workspace_service)),
+            Ok(_) if head.content_length() > MAX_BODY_BYTES => (413, String::new()),
+            Ok(route) => {
+                let mut body = vec![0; head.content_length()];
+                reader.read_exact(&mut body)?;
+                match parse_request(&route, &body) {
+                    Some(request) => match context.requests.send(request) {
+                        Ok(()) => (200, "{}".to_string()),
+                        Err(_) => (503, String::new()),
+                    },
+                    None => (400, String::new()),
+                }
+            }
+        },
+    };
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        reason_phrase(status),
+        body.len()
+    )?;
+    stream.write_all(body.as_bytes())?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_route() {
+        assert_eq!(parse_route("GET /sessions HTTP/1.1"), Ok(Route::Sessions));
+        assert_eq!(
+            parse_route("POST /selection HTTP/1.1"),
+            Ok(Route::Selection)
+        );
+        assert_eq!(parse_route("POST /focus?x=1 HTTP/1.1"), Ok(Route::Focus));
+        assert_eq!(parse_route("POST /open HTTP/1.1"), Ok(Route::Open));
+        assert_eq!(parse_route("GET /selection HTTP/1.1"), Err(405));
+        assert_eq!(parse_route("GET / HTTP/1.1"), Err(404));
+        assert_eq!(parse_route(""), Err(400));
+    }
+
+    #[test]
+    fn test_parse_request() {
+        let body = br#"{"file_path": "src/main.rs", "start_line": 3, "end_line": 5,
+            "content": "fn main() {}", "session_id": "s1"}"#;
+        let request = parse_request(&Route::Selection, body).unwrap();
+        assert_eq!(request.session_id.as_deref(), Some("s1"));
+        let selection = request.selection.unwrap();
+        assert_eq!(selection.start_column, 1);
+        assert_eq!(selection.end_line, 5);
+
+        let backwards = br#"{"file_path": "a.rs", "start_line": 5, "end_line": 3, "content": ""}"#;
+        assert!(parse_request(&Route::Selection, backwards).is_none());
+        assert!(parse_request(&Route::Selection, b"not json").is_none());
+
+        let focus = parse_request(&Route::Focus, br#"{"session_id": "s2"}"#).unwrap();
+        assert_eq!(focus.session_id.as_deref(), Some("s2"));
+        assert!(focus.selection.is_none());
+        assert!(parse_request(&Route::Focus, br#"{"session_id": ""}"#).is_none());
+
+        let open = br#"{"url": "agent-studio://workspace?path=%2Ftmp%2Fapp"}"#;
+        let request = parse_request(&Route::Open, open).unwrap();
+        assert_eq!(request.workspace, Some(PathBuf::from("/tmp/app")));
+        assert!(request.session_id.is_none());
+        let other = br#"{"url": "https://example.com"}"#;
+        assert!(parse_request(&Route::Open, other).is_none());
+    }
+}
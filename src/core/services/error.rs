@@ -0,0 +1,164 @@
+//! Service Errors - Typed failures shared by the service layer
+//!
+//! AgentService, PersistenceService and WorkspaceService return `ServiceError`
+//! so callers can branch on what went wrong (e.g. offer to restart a crashed
+//! agent, or reuse a workspace that already exists) instead of matching on
+//! message strings. The type implements `std::error::Error`, so `?` still
+//! converts it into `anyhow::Error` where callers don't care about the kind.
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// Message fragment the ACP connection reports when the agent process exits
+const AGENT_EXITED_MARKER: &str = "server shut down unexpectedly";
+
+pub type ServiceResult<T> = Result<T, ServiceError>;
+
+#[derive(Debug)]
+pub enum ServiceError {
+    /// No session with this ID is known
+    SessionNotFound(String),
+    /// No agent with this name is configured
+    AgentNotFound(String),
+    /// The agent exists but its process is not usable (crashed, failed to spawn)
+    AgentUnavailable { agent: String, reason: String },
+    /// The agent answered with an error or an unexpected response
+    ProtocolError(String),
+    WorkspaceNotFound(String),
+    WorkspaceExists(PathBuf),
+    TaskNotFound(String),
+    /// The session's task is locked and takes no more prompts
+    SessionLocked(String),
+    /// The caller passed something the service cannot work with
+    InvalidInput(String),
+    Io {
+        context: &'static str,
+        source: std::io::Error,
+    },
+    Serialization {
+        context: &'static str,
+        source: serde_json::Error,
+    },
+    Other(anyhow::Error),
+}
+
+impl ServiceError {
+    pub fn io(context: &'static str, source: std::io::Error) -> Self {
+        Self::Io { context, source }
+    }
+
+    pub fn serialization(context: &'static str, source: serde_json::Error) -> Self {
+        Self::Serialization { context, source }
+    }
+
+    /// Classify an error returned by an agent RPC call
+    ///
+    /// A closed connection means the process is gone; everything else is a
+    /// protocol-level failure reported by the agent.
+    pub fn from_agent(agent: &str, action: &str, error: impl fmt::Display) -> Self {
+        let message = error.to_string();
+        if message.contains(AGENT_EXITED_MARKER) {
+            Self::AgentUnavailable {
+                agent: agent.to_string(),
+                reason: message,
+            }
+        } else {
+            Self::ProtocolError(format!("{}: {}", action, message))
+        }
+    }
+
+    /// Whether restarting the agent is a sensible recovery
+    pub fn is_agent_failure(&self) -> bool {
+        matches!(self, Self::AgentUnavailable { .. })
+    }
+
+    /// Whether the error refers to something that no longer exists
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Self::SessionNotFound(_)
+                | Self::AgentNotFound(_)
+                | Self::WorkspaceNotFound(_)
+                | Self::TaskNotFound(_)
+        )
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SessionNotFound(id) => write!(f, "Session not found: {}", id),
+            Self::AgentNotFound(name) => write!(f, "Agent not found: {}", name),
+            Self::AgentUnavailable { agent, reason } => {
+                write!(f, "Agent '{}' is unavailable: {}", agent, reason)
+            }
+            Self::ProtocolError(message) => write!(f, "{}", message),
+            Self::WorkspaceNotFound(id) => write!(f, "Workspace not found: {}", id),
+            Self::WorkspaceExists(path) => {
+                write!(f, "Workspace already exists for path: {:?}", path)
+            }
+            Self::TaskNotFound(id) => write!(f, "Task not found: {}", id),
+            Self::SessionLocked(id) => write!(f, "Session is locked: {}", id),
+            Self::InvalidInput(message) => write!(f, "{}", message),
+            Self::Io { context, source } => write!(f, "{}: {}", context, source),
+            Self::Serialization { context, source } => write!(f, "{}: {}", context, source),
+            Self::Other(error) => write!(f, "{:#}", error),
+        }
+    }
+}
+
+impl std::error::Error for ServiceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Serialization { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ServiceError {
+    fn from(source: std::io::Error) -> Self {
+        Self::io("I/O error", source)
+    }
+}
+
+impl From<anyhow::Error> for ServiceError {
+    fn from(error: anyhow::Error) -> Self {
+        // Keep the kind when a ServiceError round-tripped through anyhow
+        match error.downcast::<ServiceError>() {
+            Ok(error) => error,
+            Err(error) => Self::Other(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_agent_detects_exited_process() {
+        let error = ServiceError::from_agent(
+            "claude",
+            "Failed to create session",
+            "server shut down unexpectedly",
+        );
+        assert!(error.is_agent_failure());
+
+        let error =
+            ServiceError::from_agent("claude", "Failed to create session", "Invalid params");
+        assert!(matches!(
+            error,
+            ServiceError::ProtocolError(ref m) if m == "Failed to create session: Invalid params"
+        ));
+    }
+
+    #[test]
+    fn test_anyhow_round_trip_keeps_kind() {
+        let error: anyhow::Error = ServiceError::SessionNotFound("s1".into()).into();
+        let error = ServiceError::from(error);
+        assert!(matches!(error, ServiceError::SessionNotFound(ref id) if id == "s1"));
+        assert!(error.is_not_found());
+    }
+}
@@ -0,0 +1,151 @@
+//! HTTP Server - Request reading shared by the small built-in servers
+//!
+//! The share server and the editor bridge answer a handful of routes over
+//! plain HTTP/1.1, one request per connection. This module reads a request's
+//! head within fixed limits and bounds the connections served at once, so a
+//! client that trickles bytes or opens many connections cannot exhaust memory
+//! or threads.
+
+use std::io::{BufRead, Read};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Longest request or header line accepted
+pub const MAX_LINE_BYTES: usize = 8 * 1024;
+/// Most header lines read from a request
+pub const MAX_HEADERS: usize = 64;
+/// Most connections served at once; further ones are closed right away
+pub const MAX_CONNECTIONS: usize = 16;
+
+/// Request line and headers of a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestHead {
+    pub request_line: String,
+    /// Headers as sent, names not normalized
+    pub headers: Vec<(String, String)>,
+}
+
+impl RequestHead {
+    /// Value of the header `name`, matched case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Length of the body; `usize::MAX` when it cannot be read, so it is
+    /// rejected as too large
+    pub fn content_length(&self) -> usize {
+        self.header("content-length")
+            .map_or(0, |value| value.parse().unwrap_or(usize::MAX))
+    }
+}
+
+/// Read one line of at most `MAX_LINE_BYTES`; `false` when it is longer
+fn read_line_capped(reader: &mut impl BufRead, line: &mut String) -> std::io::Result<bool> {
+    let read = reader.take(MAX_LINE_BYTES as u64 + 1).read_line(line)?;
+    Ok(read <= MAX_LINE_BYTES)
+}
+
+/// Read the request line and headers. `Err(431)` when a line or the header
+/// count is over the limit.
+pub fn read_head(reader: &mut impl BufRead) -> std::io::Result<Result<RequestHead, u16>> {
+    let mut request_line = String::new();
+    if !read_line_capped(reader, &mut request_line)? {
+        return Ok(Err(431));
+    }
+    let mut headers = Vec::new();
+    let mut line = String::new();
+    for _ in 0..=MAX_HEADERS {
+        line.clear();
+        if !read_line_capped(reader, &mut line)? {
+            return Ok(Err(431));
+        }
+        if line.trim().is_empty() {
+            return Ok(Ok(RequestHead {
+                request_line,
+                headers,
+            }));
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    Ok(Err(431))
+}
+
+/// Reason phrase of the statuses the servers answer with
+pub fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        429 => "Too Many Requests",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+/// A connection being served; frees its place when dropped
+pub struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    /// Take a place among `active` connections, unless all are in use
+    pub fn acquire(active: &Arc<AtomicUsize>) -> Option<Self> {
+        active
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                (count < MAX_CONNECTIONS).then_some(count + 1)
+            })
+            .ok()?;
+        Some(Self(active.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_head_caps_lines_and_headers() {
+        let mut request =
+            "POST /share/abc/comments HTTP/1.1\r\nContent-Length: 12\r\nX-Token: t\r\n\r\n{}"
+                .as_bytes();
+        let head = read_head(&mut request).unwrap().unwrap();
+        assert_eq!(head.request_line, "POST /share/abc/comments HTTP/1.1\r\n");
+        assert_eq!(head.content_length(), 12);
+        assert_eq!(head.header("x-token"), Some("t"));
+        assert_eq!(head.header("x-other"), None);
+
+        let long_line = format!("GET /share/{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE_BYTES));
+        assert_eq!(read_head(&mut long_line.as_bytes()).unwrap(), Err(431));
+
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-A: b\r\n".repeat(MAX_HEADERS + 1)
+        );
+        assert_eq!(read_head(&mut many_headers.as_bytes()).unwrap(), Err(431));
+    }
+
+    #[test]
+    fn test_connection_slots_are_bounded() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let slots: Vec<_> = (0..MAX_CONNECTIONS)
+            .map(|_| ConnectionSlot::acquire(&active).unwrap())
+            .collect();
+        assert!(ConnectionSlot::acquire(&active).is_none());
+        drop(slots);
+        assert_eq!(active.load(Ordering::SeqCst), 0);
+        assert!(ConnectionSlot::acquire(&active).is_some());
+    }
+}
@@ -0,0 +1,951 @@
+//! Import Service - Conversations exported from other tools as tasks
+//!
+//! Reads Claude Code session logs (`~/.claude/projects/*/*.jsonl`), Aider chat
+//! histories (`.aider.chat.history.md`) and Markdown transcripts, and adds
+//! each conversation found as a task with a session history of its own, so
+//! old conversations are kept and searched next to new ones.
+//!
+//! Imported tasks are locked: no agent knows their sessions, so they take no
+//! prompts. Their session IDs are hashes of the conversations, so importing
+//! a file again skips the conversations already imported.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use agent_client_protocol::{
+    Content, ContentBlock, ContentChunk, SessionUpdate, ToolCall, ToolCallContent, ToolCallId,
+    ToolCallStatus,
+};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde_json::Value;
+
+use super::persistence_service::{PersistedMessage, PersistenceService};
+use super::workspace_service::WorkspaceService;
+use super::{ServiceError, ServiceResult, SessionStatus};
+use crate::schemas::workspace_schema::WorkspaceTask;
+
+/// Longest name of an imported task, in characters
+const TASK_NAME_CHARS: usize = 80;
+
+/// Longest detail shown after a tool's name in an imported tool call title
+const TOOL_DETAIL_CHARS: usize = 60;
+
+/// Line starting each conversation of an Aider chat history
+const AIDER_HEADER: &str = "# aider chat started at ";
+
+/// Tool a conversation was exported from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// A Claude Code session log: one JSON event per line
+    ClaudeCode,
+    /// An Aider chat history, holding every conversation in a repository
+    Aider,
+    /// A transcript with a heading or bold line such as `**You**` before each message
+    Markdown,
+}
+
+impl ImportFormat {
+    /// The format of `content`, read from `path`; `None` if it is no known format
+    pub fn detect(path: &Path, content: &str) -> Option<Self> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("jsonl") => Some(Self::ClaudeCode),
+            _ if content.lines().any(|line| line.starts_with(AIDER_HEADER)) => Some(Self::Aider),
+            Some("md" | "markdown" | "txt") => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Agent imported tasks are attributed to unless another is chosen
+    pub fn default_agent(&self) -> &'static str {
+        match self {
+            Self::ClaudeCode => "Claude Code",
+            Self::Aider => "Aider",
+            Self::Markdown => "Agent",
+        }
+    }
+
+    /// Parse `content` into the conversations it holds
+    pub fn parse(&self, content: &str) -> Vec<ImportedConversation> {
+        match self {
+            Self::ClaudeCode => vec![parse_claude_code(content)],
+            Self::Aider => parse_aider(content),
+            Self::Markdown => vec![parse_markdown(content)],
+        }
+        .into_iter()
+        .filter(|conversation| !conversation.messages.is_empty())
+        .collect()
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "claude-code" => Ok(Self::ClaudeCode),
+            "aider" => Ok(Self::Aider),
+            "markdown" | "md" => Ok(Self::Markdown),
+            other => Err(format!(
+                "Unknown import format '{}' (expected claude-code, aider or markdown)",
+                other
+            )),
+        }
+    }
+}
+
+/// What a message of an imported conversation holds
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportedContent {
+    User(String),
+    Agent(String),
+    Thought(String),
+    ToolCall {
+        id: String,
+        title: String,
+        output: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedMessage {
+    pub timestamp: Option<DateTime<Utc>>,
+    pub content: ImportedContent,
+}
+
+impl ImportedMessage {
+    fn new(timestamp: Option<DateTime<Utc>>, content: ImportedContent) -> Self {
+        Self { timestamp, content }
+    }
+
+    fn to_update(&self) -> SessionUpdate {
+        let chunk = |text: &String| ContentChunk::new(ContentBlock::from(text.clone()));
+        match &self.content {
+            ImportedContent::User(text) => SessionUpdate::UserMessageChunk(chunk(text)),
+            ImportedContent::Agent(text) => SessionUpdate::AgentMessageChunk(chunk(text)),
+            ImportedContent::Thought(text) => SessionUpdate::AgentThoughtChunk(chunk(text)),
+            ImportedContent::ToolCall { id, title, output } => {
+                let mut tool_call = ToolCall::new(ToolCallId::from(id.clone()), title.clone());
+                tool_call.status = ToolCallStatus::Completed;
+                tool_call.content = output
+                    .iter()
+                    .map(|output| {
+                        ToolCallContent::Content(Content::new(ContentBlock::from(output.clone())))
+                    })
+                    .collect();
+                SessionUpdate::ToolCall(tool_call)
+            }
+        }
+    }
+}
+
+/// A conversation read from an exported file
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportedConversation {
+    /// Title given by the tool; the first prompt names the task otherwise
+    pub title: Option<String>,
+    /// Directory the conversation took place in, to find its workspace
+    pub cwd: Option<PathBuf>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub messages: Vec<ImportedMessage>,
+}
+
+impl ImportedConversation {
+    /// Name of the task the conversation is imported as
+    pub fn task_name(&self) -> String {
+        let prompt = self
+            .messages
+            .iter()
+            .find_map(|message| match &message.content {
+                ImportedContent::User(text) => Some(text.as_str()),
+                _ => None,
+            });
+        let text = self.title.as_deref().or(prompt).unwrap_or_default();
+        let line = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("Imported conversation");
+        truncate(line, TASK_NAME_CHARS)
+    }
+
+    /// Session ID of the conversation, the same on every import of it
+    pub fn session_id(&self) -> String {
+        // FNV-1a, which unlike `DefaultHasher` is stable across Rust releases
+        let mut hash = 0xcbf29ce484222325u64;
+        for message in &self.messages {
+            let text = match &message.content {
+                ImportedContent::User(text)
+                | ImportedContent::Agent(text)
+                | ImportedContent::Thought(text) => text,
+                ImportedContent::ToolCall { title, .. } => title,
+            };
+            for byte in text.bytes().chain([0]) {
+                hash = (hash ^ byte as u64).wrapping_mul(0x100000001b3);
+            }
+        }
+        format!("import-{:016x}", hash)
+    }
+
+    /// The messages as session history; messages without a time take that
+    /// of the message before them, or `fallback` at the start
+    pub fn to_history(&self, fallback: DateTime<Utc>) -> Vec<PersistedMessage> {
+        let mut time = self.started_at.unwrap_or(fallback);
+        self.messages
+            .iter()
+            .map(|message| {
+                time = message.timestamp.unwrap_or(time);
+                PersistedMessage::with_timestamp(time.to_rfc3339(), message.to_update())
+            })
+            .collect()
+    }
+}
+
+/// Parse a Claude Code session log
+///
+/// Sidechain (subagent) and meta events are left out, as are slash command
+/// echoes. Tool results are attached to the tool calls they answer.
+pub fn parse_claude_code(content: &str) -> ImportedConversation {
+    let mut conversation = ImportedConversation::default();
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let Ok(event) = serde_json::from_str::<Value>(line) else {
+            log::debug!("Skipping unreadable line of Claude Code log");
+            continue;
+        };
+        let flag = |key: &str| event.get(key).and_then(Value::as_bool).unwrap_or(false);
+        if flag("isSidechain") || flag("isMeta") {
+            continue;
+        }
+        let timestamp = event
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            .map(|time| time.with_timezone(&Utc));
+        if conversation.cwd.is_none() {
+            conversation.cwd = event.get("cwd").and_then(Value::as_str).map(PathBuf::from);
+        }
+
+        let content = event.pointer("/message/content");
+        match event.get("type").and_then(Value::as_str) {
+            Some("summary") => {
+                if conversation.title.is_none() {
+                    conversation.title = event
+                        .get("summary")
+                        .and_then(Value::as_str)
+                        .map(str::to_string);
+                }
+            }
+            Some("user") => match content {
+                Some(Value::String(text)) if !is_command_echo(text) => {
+                    push_text(
+                        &mut conversation,
+                        timestamp,
+                        ImportedContent::User(text.clone()),
+                    );
+                }
+                Some(Value::Array(blocks)) => {
+                    for block in blocks {
+                        match block.get("type").and_then(Value::as_str) {
+                            Some("text") => {
+                                let text = block_text(block, "text");
+                                if !is_command_echo(&text) {
+                                    let user = ImportedContent::User(text);
+                                    push_text(&mut conversation, timestamp, user);
+                                }
+                            }
+                            Some("tool_result") => attach_tool_result(&mut conversation, block),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Some("assistant") => {
+                for block in content.and_then(Value::as_array).into_iter().flatten() {
+                    let content = match block.get("type").and_then(Value::as_str) {
+                        Some("text") => ImportedContent::Agent(block_text(block, "text")),
+                        Some("thinking") => ImportedContent::Thought(block_text(block, "thinking")),
+                        Some("tool_use") => ImportedContent::ToolCall {
+                            id: block_text(block, "id"),
+                            title: tool_title(block),
+                            output: None,
+                        },
+                        _ => continue,
+                    };
+                    push_text(&mut conversation, timestamp, content);
+                }
+            }
+            _ => {}
+        }
+        if conversation.started_at.is_none() {
+            conversation.started_at = timestamp;
+        }
+    }
+    conversation
+}
+
+/// Slash commands and their output, which Claude Code logs as user messages
+fn is_command_echo(text: &str) -> bool {
+    let text = text.trim_start();
+    text.starts_with("<command-") || text.starts_with("<local-command-")
+}
+
+fn block_text(block: &Value, key: &str) -> String {
+    block
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Tool name and its main argument, e.g. `Bash cargo test`
+fn tool_title(block: &Value) -> String {
+    let name = block.get("name").and_then(Value::as_str).unwrap_or("Tool");
+    let detail = [
+        "command",
+        "file_path",
+        "path",
+        "pattern",
+        "url",
+        "description",
+    ]
+    .iter()
+    .find_map(|key| block.pointer(&format!("/input/{}", key))?.as_str());
+    match detail.and_then(|detail| detail.lines().next()) {
+        Some(detail) => format!("{} {}", name, truncate(detail, TOOL_DETAIL_CHARS)),
+        None => name.to_string(),
+    }
+}
+
+fn attach_tool_result(conversation: &mut ImportedConversation, block: &Value) {
+    let id = block_text(block, "tool_use_id");
+    let output = match block.get("content") {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|part| part.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => return,
+    };
+    let tool_call =
+        conversation
+            .messages
+            .iter_mut()
+            .rev()
+            .find_map(|message| match &mut message.content {
+                ImportedContent::ToolCall {
+                    id: call_id,
+                    output: slot,
+                    ..
+                } if *call_id == id => Some(slot),
+                _ => None,
+            });
+    if let Some(slot) = tool_call
+        && !output.trim().is_empty()
+    {
+        *slot = Some(output);
+    }
+}
+
+/// Add a message unless its text is empty
+fn push_text(
+    conversation: &mut ImportedConversation,
+    timestamp: Option<DateTime<Utc>>,
+    content: ImportedContent,
+) {
+    let empty = match &content {
+        ImportedContent::User(text)
+        | ImportedContent::Agent(text)
+        | ImportedContent::Thought(text) => text.trim().is_empty(),
+        ImportedContent::ToolCall { .. } => false,
+    };
+    if !empty {
+        conversation
+            .messages
+            .push(ImportedMessage::new(timestamp, content));
+    }
+}
+
+/// Parse an Aider chat history into its conversations
+///
+/// Prompts are the lines starting with `####`; Aider's own output, quoted
+/// with `>`, is left out, and everything else is the model's reply.
+pub fn parse_aider(content: &str) -> Vec<ImportedConversation> {
+    let mut conversations = Vec::new();
+    let mut conversation = ImportedConversation::default();
+    let mut prompt: Vec<&str> = Vec::new();
+    let mut reply: Vec<&str> = Vec::new();
+
+    fn flush(
+        conversation: &mut ImportedConversation,
+        prompt: &mut Vec<&str>,
+        reply: &mut Vec<&str>,
+    ) {
+        if !prompt.is_empty() {
+            let text = prompt.join("\n");
+            push_text(conversation, None, ImportedContent::User(text));
+            prompt.clear();
+        }
+        if !reply.is_empty() {
+            let text = reply.join("\n").trim().to_string();
+            push_text(conversation, None, ImportedContent::Agent(text));
+            reply.clear();
+        }
+    }
+
+    for line in content.lines() {
+        if let Some(started) = line.strip_prefix(AIDER_HEADER) {
+            flush(&mut conversation, &mut prompt, &mut reply);
+            conversations.push(std::mem::take(&mut conversation));
+            conversation.started_at =
+                NaiveDateTime::parse_from_str(started.trim(), "%Y-%m-%d %H:%M:%S")
+                    .ok()
+                    .and_then(|time| Local.from_local_datetime(&time).single())
+                    .map(|time| time.with_timezone(&Utc));
+        } else if let Some(text) = line.strip_prefix("####") {
+            if !reply.is_empty() {
+                flush(&mut conversation, &mut prompt, &mut reply);
+            }
+            prompt.push(text.strip_prefix(' ').unwrap_or(text));
+        } else if line.starts_with('>') {
+            flush(&mut conversation, &mut prompt, &mut reply);
+        } else {
+            if !prompt.is_empty() {
+                flush(&mut conversation, &mut prompt, &mut reply);
+            }
+            reply.push(line);
+        }
+    }
+    flush(&mut conversation, &mut prompt, &mut reply);
+    conversations.push(conversation);
+    conversations
+}
+
+/// Speaker of the messages following a line of a Markdown transcript
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Speaker {
+    User,
+    Agent,
+    Thought,
+}
+
+/// Speaker named by `line` if it is only a heading or emphasized name, e.g.
+/// `## User`, `**Assistant:**` or `*Thinking*` as written by AgentX's own
+/// transcripts
+fn speaker(line: &str) -> Option<Speaker> {
+    let name = line
+        .trim()
+        .trim_start_matches('#')
+        .trim()
+        .trim_matches('*')
+        .trim_end_matches(':')
+        .trim()
+        .to_lowercase();
+    match name.as_str() {
+        "you" | "user" | "human" | "me" | "prompt" => Some(Speaker::User),
+        "assistant" | "agent" | "ai" | "model" | "bot" | "response" | "answer" | "claude"
+        | "claude code" | "codex" | "gemini" | "aider" => Some(Speaker::Agent),
+        "thinking" => Some(Speaker::Thought),
+        _ => None,
+    }
+}
+
+/// Parse a Markdown transcript; a `# Title` before the first message titles it
+pub fn parse_markdown(content: &str) -> ImportedConversation {
+    let mut conversation = ImportedConversation::default();
+    let mut current: Option<Speaker> = None;
+    let mut lines: Vec<&str> = Vec::new();
+
+    let flush = |conversation: &mut ImportedConversation, speaker, lines: &mut Vec<&str>| {
+        let text = lines.join("\n").trim().to_string();
+        lines.clear();
+        let content = match speaker {
+            Some(Speaker::User) => ImportedContent::User(text),
+            Some(Speaker::Agent) => ImportedContent::Agent(text),
+            Some(Speaker::Thought) => {
+                let unquoted: Vec<&str> = text
+                    .lines()
+                    .map(|line| line.strip_prefix('>').map_or(line, str::trim_start))
+                    .collect();
+                ImportedContent::Thought(unquoted.join("\n"))
+            }
+            None => return,
+        };
+        push_text(conversation, None, content);
+    };
+
+    for line in content.lines() {
+        if let Some(next) = speaker(line) {
+            flush(&mut conversation, current, &mut lines);
+            current = Some(next);
+        } else if current.is_none() {
+            if let Some(title) = line.strip_prefix("# ")
+                && conversation.title.is_none()
+            {
+                conversation.title = Some(title.trim().to_string());
+            }
+        } else {
+            lines.push(line);
+        }
+    }
+    flush(&mut conversation, current, &mut lines);
+    conversation
+}
+
+/// `text` cut to `max_chars` characters, ending in "…" if cut
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Files to import from `paths`: files as given, and the session logs and
+/// Markdown files anywhere in directories
+pub fn collect_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            collect_dir(path, &mut files);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    files
+}
+
+fn collect_dir(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        log::warn!("Cannot read directory {}", dir.display());
+        return;
+    };
+    let mut entries: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_dir(&path, files);
+        } else if matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("jsonl" | "md" | "markdown")
+        ) {
+            files.push(path);
+        }
+    }
+}
+
+/// What to import into
+#[derive(Clone, Debug, Default)]
+pub struct ImportOptions {
+    /// Read every file as this format instead of detecting it
+    pub format: Option<ImportFormat>,
+    /// Workspace to add the tasks to; otherwise the workspace whose folder a
+    /// conversation took place in, else the active one
+    pub workspace_id: Option<String>,
+    /// Agent the tasks are attributed to instead of the format's default
+    pub agent_name: Option<String>,
+}
+
+/// A file picked for import and the conversations found in it
+#[derive(Clone, Debug)]
+pub struct ImportFile {
+    pub path: PathBuf,
+    pub format: ImportFormat,
+    pub conversations: Vec<ImportedConversation>,
+}
+
+impl ImportFile {
+    /// Read and parse `path`, detecting its format unless `format` is given
+    pub fn read(path: &Path, format: Option<ImportFormat>) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let format = format
+            .or_else(|| ImportFormat::detect(path, &content))
+            .ok_or_else(|| "unknown format".to_string())?;
+        let conversations = format.parse(&content);
+        if conversations.is_empty() {
+            return Err("no conversation found".to_string());
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+            conversations,
+        })
+    }
+
+    /// When the file was last changed, the time of messages without one
+    fn modified(&self) -> DateTime<Utc> {
+        std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now())
+    }
+}
+
+/// Outcome of an import
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub imported: usize,
+    /// Conversations imported before
+    pub skipped: usize,
+    /// Files or conversations that could not be imported, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Import service - adds conversations exported from other tools as tasks
+pub struct ImportService {
+    workspace_service: Arc<WorkspaceService>,
+    persistence_service: Arc<PersistenceService>,
+}
+
+impl ImportService {
+    pub fn new(
+        workspace_service: Arc<WorkspaceService>,
+        persistence_service: Arc<PersistenceService>,
+    ) -> Self {
+        Self {
+            workspace_service,
+            persistence_service,
+        }
+    }
+
+    /// Read every file of `paths` (see [`collect_files`]) and import what it holds
+    pub async fn import_paths(&self, paths: &[PathBuf], options: &ImportOptions) -> ImportReport {
+        let mut report = ImportReport::default();
+        for path in collect_files(paths) {
+            let format = options.format;
+            let read_path = path.clone();
+            match smol::unblock(move || ImportFile::read(&read_path, format)).await {
+                Ok(file) => self.import_file(&file, options, &mut report).await,
+                Err(e) => {
+                    log::warn!("Cannot import {}: {}", path.display(), e);
+                    report.failed.push((path, e));
+                }
+            }
+        }
+        report
+    }
+
+    /// Import the conversations of a file already read
+    pub async fn import_file(
+        &self,
+        file: &ImportFile,
+        options: &ImportOptions,
+        report: &mut ImportReport,
+    ) {
+        let modified = file.modified();
+        for conversation in &file.conversations {
+            match self
+                .import_conversation(file.format, conversation, options, modified)
+                .await
+            {
+                Ok(true) => report.imported += 1,
+                Ok(false) => report.skipped += 1,
+                Err(e) => {
+                    log::warn!("Cannot import from {}: {}", file.path.display(), e);
+                    report.failed.push((file.path.clone(), e.to_string()));
+                }
+            }
+        }
+    }
+
+    /// Add `conversation` as a locked task; `false` if it was imported before
+    async fn import_conversation(
+        &self,
+        format: ImportFormat,
+        conversation: &ImportedConversation,
+        options: &ImportOptions,
+        fallback_time: DateTime<Utc>,
+    ) -> ServiceResult<bool> {
+        let session_id = conversation.session_id();
+        if self
+            .workspace_service
+            .get_task_by_session(&session_id)
+            .await
+            .is_some()
+        {
+            return Ok(false);
+        }
+        let workspace_id = self.workspace_for(conversation, options).await?;
+        let history = conversation.to_history(fallback_time);
+
+        let agent_name = options
+            .agent_name
+            .clone()
+            .unwrap_or_else(|| format.default_agent().to_string());
+        let mut task = WorkspaceTask::new(
+            workspace_id,
+            conversation.task_name(),
+            agent_name,
+            "Auto".to_string(),
+        );
+        task.set_session(session_id.clone());
+        task.status = SessionStatus::Completed;
+        task.locked = true;
+        let times: Vec<DateTime<Utc>> = history
+            .iter()
+            .filter_map(|message| DateTime::parse_from_rfc3339(&message.timestamp).ok())
+            .map(|time| time.with_timezone(&Utc))
+            .collect();
+        task.created_at = times.iter().min().copied().unwrap_or(fallback_time);
+        task.last_updated = times.iter().max().copied().unwrap_or(fallback_time);
+        for message in &conversation.messages {
+            match &message.content {
+                ImportedContent::User(text) => {
+                    task.usage.turns += 1;
+                    task.usage.prompt_chars += text.chars().count() as u64;
+                    task.message_count += 1;
+                }
+                ImportedContent::Agent(text) => {
+                    task.usage.output_chars += text.chars().count() as u64;
+                    task.message_count += 1;
+                }
+                _ => {}
+            }
+        }
+
+        self.persistence_service
+            .write_session(&session_id, history)
+            .await?;
+        self.workspace_service.import_task(task).await?;
+        Ok(true)
+    }
+
+    async fn workspace_for(
+        &self,
+        conversation: &ImportedConversation,
+        options: &ImportOptions,
+    ) -> ServiceResult<String> {
+        if let Some(workspace_id) = &options.workspace_id {
+            return Ok(workspace_id.clone());
+        }
+        let workspaces = self.workspace_service.list_workspaces().await;
+        if let Some(cwd) = &conversation.cwd
+            && let Some(workspace) = workspaces.iter().find(|w| &w.path == cwd)
+        {
+            return Ok(workspace.id.clone());
+        }
+        self.workspace_service
+            .get_active_workspace()
+            .await
+            .map(|workspace| workspace.id)
+            .ok_or_else(|| ServiceError::InvalidInput("No workspace to import into".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(conversation: &ImportedConversation) -> Vec<ImportedContent> {
+        conversation
+            .messages
+            .iter()
+            .map(|message| message.content.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_detect() {
+        let aider = "# aider chat started at 2024-05-01 10:00:00\n\n#### hi\n";
+        assert_eq!(
+            ImportFormat::detect(Path::new("a/session.jsonl"), "{}"),
+            Some(ImportFormat::ClaudeCode)
+        );
+        assert_eq!(
+            ImportFormat::detect(Path::new(".aider.chat.history.md"), aider),
+            Some(ImportFormat::Aider)
+        );
+        assert_eq!(
+            ImportFormat::detect(Path::new("chat.md"), "**You**\n\nhi"),
+            Some(ImportFormat::Markdown)
+        );
+        assert_eq!(ImportFormat::detect(Path::new("chat.pdf"), ""), None);
+        assert_eq!(
+            "Claude-Code".parse::<ImportFormat>(),
+            Ok(ImportFormat::ClaudeCode)
+        );
+        assert!("html".parse::<ImportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_parse_claude_code() {
+        let event = |kind: &str, time: Option<&str>, content: serde_json::Value| {
+            let mut event = serde_json::json!({
+                "type": kind,
+                "message": {"role": kind, "content": content},
+            });
+            if let Some(time) = time {
+                event["timestamp"] = time.into();
+            }
+            event
+        };
+        let mut first = event(
+            "user",
+            Some("2026-03-02T10:00:00Z"),
+            "Why does it fail?".into(),
+        );
+        first["cwd"] = "/repo".into();
+        let mut sidechain = event("user", None, "subagent".into());
+        sidechain["isSidechain"] = true.into();
+        let log = [
+            serde_json::json!({"type": "summary", "summary": "Fix the flaky test"}).to_string(),
+            first.to_string(),
+            event(
+                "assistant",
+                Some("2026-03-02T10:00:05Z"),
+                serde_json::json!([
+                    {"type": "thinking", "thinking": "Look at it"},
+                    {"type": "tool_use", "id": "t1", "name": "Bash",
+                     "input": {"command": "cargo test\nmore"}},
+                ]),
+            )
+            .to_string(),
+            event(
+                "user",
+                Some("2026-03-02T10:00:09Z"),
+                serde_json::json!([
+                    {"type": "tool_result", "tool_use_id": "t1", "content": "1 failed"},
+                ]),
+            )
+            .to_string(),
+            sidechain.to_string(),
+            event("user", None, "<command-name>/clear</command-name>".into()).to_string(),
+            "not json".to_string(),
+            event(
+                "assistant",
+                Some("2026-03-02T10:00:12Z"),
+                serde_json::json!([{"type": "text", "text": "A race."}]),
+            )
+            .to_string(),
+        ]
+        .join("\n");
+
+        let conversation = parse_claude_code(&log);
+        assert_eq!(conversation.title.as_deref(), Some("Fix the flaky test"));
+        assert_eq!(conversation.cwd, Some(PathBuf::from("/repo")));
+        assert_eq!(
+            texts(&conversation),
+            vec![
+                ImportedContent::User("Why does it fail?".into()),
+                ImportedContent::Thought("Look at it".into()),
+                ImportedContent::ToolCall {
+                    id: "t1".into(),
+                    title: "Bash cargo test".into(),
+                    output: Some("1 failed".into()),
+                },
+                ImportedContent::Agent("A race.".into()),
+            ]
+        );
+        assert_eq!(conversation.task_name(), "Fix the flaky test");
+    }
+
+    #[test]
+    fn test_parse_aider() {
+        let history = "\
+# aider chat started at 2024-05-01 10:00:00
+
+> aider --model sonnet
+> Aider v0.50
+
+#### add a test
+#### for parse()
+
+Here is the test.
+
+```python
+def test(): pass
+```
+
+> Applied edit to test.py
+
+#### thanks
+
+You're welcome.
+
+# aider chat started at 2024-05-02 09:00:00
+
+> aider
+";
+        let conversations = ImportFormat::Aider.parse(history);
+        assert_eq!(conversations.len(), 1);
+        let conversation = &conversations[0];
+        assert!(conversation.started_at.is_some());
+        assert_eq!(
+            texts(conversation),
+            vec![
+                ImportedContent::User("add a test\nfor parse()".into()),
+                ImportedContent::Agent(
+                    "Here is the test.\n\n```python\ndef test(): pass\n```".into()
+                ),
+                ImportedContent::User("thanks".into()),
+                ImportedContent::Agent("You're welcome.".into()),
+            ]
+        );
+        assert_eq!(conversation.task_name(), "add a test");
+    }
+
+    #[test]
+    fn test_parse_markdown() {
+        let transcript = "\
+# Refactor notes
+
+**You**
+
+Split the module
+
+*Thinking*
+
+> first
+> second
+
+## Assistant:
+
+Done. The heading below stays in the reply.
+
+## Plan
+
+- one
+";
+        let conversation = parse_markdown(transcript);
+        assert_eq!(conversation.title.as_deref(), Some("Refactor notes"));
+        assert_eq!(
+            texts(&conversation),
+            vec![
+                ImportedContent::User("Split the module".into()),
+                ImportedContent::Thought("first\nsecond".into()),
+                ImportedContent::Agent(
+                    "Done. The heading below stays in the reply.\n\n## Plan\n\n- one".into()
+                ),
+            ]
+        );
+        assert!(ImportFormat::Markdown.parse("just notes").is_empty());
+    }
+
+    #[test]
+    fn test_session_id_and_history() {
+        let conversation = parse_markdown("**You**\n\nhi\n\n**Agent**\n\nhello");
+        assert_eq!(
+            conversation.session_id(),
+            parse_markdown("# Other title\n\n**User**\nhi\n**Assistant**\nhello").session_id()
+        );
+        assert_ne!(
+            conversation.session_id(),
+            parse_markdown("**You**\n\nhi").session_id()
+        );
+
+        let fallback = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let history = conversation.to_history(fallback);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].timestamp, fallback.to_rfc3339());
+        assert!(matches!(
+            history[1].update,
+            SessionUpdate::AgentMessageChunk(_)
+        ));
+    }
+}
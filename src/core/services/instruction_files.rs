@@ -0,0 +1,102 @@
+//! Instruction Files - Project guidance written for coding agents
+//!
+//! Repositories often keep instructions for agents at their root: `AGENTS.md`,
+//! `CLAUDE.md` or `.cursorrules`. The ones found there are attached to the
+//! first prompt of new sessions, except those the user left out for the
+//! workspace, so every agent starts with the same conventions.
+
+use std::path::Path;
+
+use agent_client_protocol::{
+    ContentBlock, EmbeddedResource, EmbeddedResourceResource, TextResourceContents,
+};
+
+/// Files looked for in the workspace root, in the order they are attached
+pub const INSTRUCTION_FILE_NAMES: &[&str] = &["AGENTS.md", "CLAUDE.md", ".cursorrules"];
+/// Size cap of an attached file, in characters
+const MAX_INSTRUCTION_CHARS: usize = 32_000;
+
+/// Names of the instruction files in `root`
+pub fn find_instruction_files(root: &Path) -> Vec<String> {
+    INSTRUCTION_FILE_NAMES
+        .iter()
+        .filter(|name| root.join(name).is_file())
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// The instruction files in `root` not named in `excluded`, as embedded
+/// resources for a prompt
+///
+/// Blocking: reads the files.
+pub fn instruction_blocks(root: &Path, excluded: &[String]) -> Vec<ContentBlock> {
+    find_instruction_files(root)
+        .into_iter()
+        .filter(|name| !excluded.contains(name))
+        .filter_map(|name| {
+            let path = root.join(&name);
+            match std::fs::read_to_string(&path) {
+                Ok(content) => Some(instruction_block(&path, content)),
+                Err(e) => {
+                    log::warn!("Failed to read instruction file {:?}: {}", path, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+fn instruction_block(path: &Path, content: String) -> ContentBlock {
+    let text = truncate_instructions(content, MAX_INSTRUCTION_CHARS);
+    let resource = TextResourceContents::new(text, format!("file://{}", path.display()))
+        .mime_type("text/markdown".to_string());
+    ContentBlock::Resource(EmbeddedResource::new(
+        EmbeddedResourceResource::TextResourceContents(resource),
+    ))
+}
+
+/// `content` cut at `max_chars` characters, with a note when it was
+fn truncate_instructions(content: String, max_chars: usize) -> String {
+    match content.char_indices().nth(max_chars) {
+        Some((end, _)) => format!(
+            "{}\n… (truncated at {} characters)\n",
+            &content[..end],
+            max_chars
+        ),
+        None => content,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_instruction_files() {
+        let root = std::env::temp_dir().join(format!("agentx-instructions-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("CLAUDE.md")).unwrap();
+        std::fs::write(root.join(".cursorrules"), "Use tabs").unwrap();
+        std::fs::write(root.join("AGENTS.md"), "Run the tests").unwrap();
+
+        assert_eq!(
+            find_instruction_files(&root),
+            vec!["AGENTS.md", ".cursorrules"]
+        );
+        assert_eq!(instruction_blocks(&root, &[]).len(), 2);
+        assert_eq!(
+            instruction_blocks(&root, &["AGENTS.md".to_string()]).len(),
+            1
+        );
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_truncate_instructions() {
+        assert_eq!(truncate_instructions("短い".to_string(), 2), "短い");
+        assert_eq!(
+            truncate_instructions("abcdef".to_string(), 3),
+            "abc\n… (truncated at 3 characters)\n"
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Maintenance Service - Cleanup of accumulated tasks
+//!
+//! Finds duplicate tasks in a workspace, i.e. tasks of the same agent whose
+//! sessions started with the same prompt (ignoring case and whitespace), and
+//! merges each group into one task: the kept task takes over the usage,
+//! active time and feedback of the others, which are removed and their
+//! session histories moved to the trash.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use agent_client_protocol::{ContentBlock, SessionUpdate};
+
+use super::persistence_service::{PersistedMessage, PersistenceService};
+use super::workspace_service::WorkspaceService;
+use super::{ServiceResult, SessionStatus};
+use crate::schemas::workspace_schema::WorkspaceTask;
+
+/// Tasks of one agent that started with the same prompt
+#[derive(Clone, Debug, PartialEq)]
+pub struct DuplicateGroup {
+    pub agent_name: String,
+    /// First prompt of the kept task
+    pub prompt: String,
+    /// Task the others are merged into: the one with the most turns, then the newest
+    pub keep: WorkspaceTask,
+    /// Newest first
+    pub duplicates: Vec<WorkspaceTask>,
+}
+
+/// Text of the first user message in a session's history
+pub fn first_prompt(messages: &[PersistedMessage]) -> Option<String> {
+    let mut prompt = String::new();
+    let mut started = false;
+    for message in messages {
+        match &message.update {
+            SessionUpdate::UserMessageChunk(chunk) => {
+                started = true;
+                if let ContentBlock::Text(text) = &chunk.content {
+                    prompt.push_str(&text.text);
+                }
+            }
+            _ if started => break,
+            _ => {}
+        }
+    }
+    let prompt = prompt.trim();
+    (!prompt.is_empty()).then(|| prompt.to_string())
+}
+
+/// Key under which prompts count as the same
+fn normalize_prompt(prompt: &str) -> String {
+    prompt
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Group `tasks` (with their first prompts) by agent and prompt
+///
+/// Only groups with more than one task are returned, largest first.
+pub fn find_duplicates(tasks: Vec<(WorkspaceTask, String)>) -> Vec<DuplicateGroup> {
+    let mut groups: HashMap<(String, String), Vec<(WorkspaceTask, String)>> = HashMap::new();
+    for (task, prompt) in tasks {
+        let key = (task.agent_name.clone(), normalize_prompt(&prompt));
+        groups.entry(key).or_default().push((task, prompt));
+    }
+
+    let mut duplicates: Vec<DuplicateGroup> = groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .map(|mut group| {
+            group.sort_by(|(a, _), (b, _)| {
+                b.usage
+                    .turns
+                    .cmp(&a.usage.turns)
+                    .then(b.created_at.cmp(&a.created_at))
+            });
+            let (keep, prompt) = group.remove(0);
+            let mut duplicates: Vec<_> = group.into_iter().map(|(task, _)| task).collect();
+            duplicates.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+            DuplicateGroup {
+                agent_name: keep.agent_name.clone(),
+                prompt,
+                keep,
+                duplicates,
+            }
+        })
+        .collect();
+    duplicates.sort_by(|a, b| {
+        b.duplicates
+            .len()
+            .cmp(&a.duplicates.len())
+            .then(b.keep.created_at.cmp(&a.keep.created_at))
+    });
+    duplicates
+}
+
+/// Maintenance service - finds and merges duplicate tasks
+pub struct MaintenanceService {
+    workspace_service: Arc<WorkspaceService>,
+    persistence_service: Arc<PersistenceService>,
+}
+
+impl MaintenanceService {
+    pub fn new(
+        workspace_service: Arc<WorkspaceService>,
+        persistence_service: Arc<PersistenceService>,
+    ) -> Self {
+        Self {
+            workspace_service,
+            persistence_service,
+        }
+    }
+
+    /// Duplicate tasks in the workspace
+    ///
+    /// Tasks without a session history and tasks whose agent is still working
+    /// are left out.
+    pub async fn find_duplicates(&self, workspace_id: &str) -> Vec<DuplicateGroup> {
+        let mut tasks = Vec::new();
+        for task in self.workspace_service.get_workspace_tasks(workspace_id).await {
+            if matches!(task.status, SessionStatus::InProgress | SessionStatus::Pending) {
+                continue;
+            }
+            let Some(session_id) = task.session_id.clone() else {
+                continue;
+            };
+            match self.persistence_service.load_messages(&session_id).await {
+                Ok(messages) => {
+                    if let Some(prompt) = first_prompt(&messages) {
+                        tasks.push((task, prompt));
+                    }
+                }
+                Err(e) => log::warn!("Failed to load history of session {}: {}", session_id, e),
+            }
+        }
+        find_duplicates(tasks)
+    }
+
+    /// Merge the duplicates of `group` into its kept task and trash their sessions
+    pub async fn merge_duplicates(&self, group: &DuplicateGroup) -> ServiceResult<()> {
+        let duplicate_ids: Vec<String> =
+            group.duplicates.iter().map(|task| task.id.clone()).collect();
+        let removed = self
+            .workspace_service
+            .merge_tasks(&group.keep.id, &duplicate_ids)
+            .await?;
+
+        for task in removed {
+            let sessions = task.session_id.iter().chain(task.agent_sessions.values());
+            for session_id in sessions {
+                if let Err(e) = self.persistence_service.trash_session(session_id).await {
+                    log::warn!("Failed to move session {} to trash: {}", session_id, e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::ContentChunk;
+
+    fn message(update: SessionUpdate) -> PersistedMessage {
+        PersistedMessage::with_timestamp("2026-03-02T10:00:00Z".into(), update)
+    }
+
+    fn task(agent: &str, turns: u32, day: u32) -> WorkspaceTask {
+        let mut task = WorkspaceTask::new("ws".into(), "t".into(), agent.into(), "code".into());
+        task.usage.turns = turns;
+        task.created_at = chrono::NaiveDate::from_ymd_opt(2026, 3, day)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+            .and_utc();
+        task
+    }
+
+    #[test]
+    fn test_first_prompt() {
+        let user = |text: &str| {
+            message(SessionUpdate::UserMessageChunk(ContentChunk::new(
+                ContentBlock::from(text.to_string()),
+            )))
+        };
+        let agent = message(SessionUpdate::AgentMessageChunk(ContentChunk::new(
+            ContentBlock::from("Sure".to_string()),
+        )));
+        let messages = vec![user("Fix the "), user("tests "), agent, user("Thanks")];
+        assert_eq!(first_prompt(&messages).as_deref(), Some("Fix the tests"));
+        assert_eq!(first_prompt(&[]), None);
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let once = task("claude", 1, 1);
+        let most_turns = task("claude", 3, 2);
+        let newest = task("claude", 1, 3);
+        let other_agent = task("codex", 1, 3);
+        let other_prompt = task("claude", 1, 3);
+
+        let groups = find_duplicates(vec![
+            (once.clone(), "Fix the tests".into()),
+            (most_turns.clone(), "fix  the tests\n".into()),
+            (newest.clone(), "Fix the tests".into()),
+            (other_agent, "Fix the tests".into()),
+            (other_prompt, "Write docs".into()),
+        ]);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].agent_name, "claude");
+        assert_eq!(groups[0].keep.id, most_turns.id);
+        let ids: Vec<_> = groups[0].duplicates.iter().map(|t| t.id.clone()).collect();
+        assert_eq!(ids, vec![newest.id, once.id]);
+    }
+}
@@ -0,0 +1,541 @@
+//! MCP Service - MCP servers run by the app itself
+//!
+//! Agents start their own copies of the configured MCP servers. This service
+//! starts another copy of a server on first use, so its tools can be called
+//! by hand while debugging its configuration, without going through an
+//! agent. Servers speak JSON-RPC over stdio, one message per line; requests
+//! to one server are sent one at a time.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::{Value, json};
+use smol::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use smol::lock::Mutex;
+use smol::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use crate::core::config::McpServerConfig;
+
+/// MCP revision announced in `initialize`
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// A server that does not answer a request in time is stopped
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Pages of a list read at most, in case a server keeps returning cursors
+const MAX_LIST_PAGES: usize = 20;
+
+/// Resources larger than this are not attached to conversations
+const MAX_RESOURCE_BYTES: usize = 256 * 1024;
+
+/// A tool, prompt, resource or resource template listed by a server
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpEntry {
+    /// Tool or prompt name, resource URI or URI template
+    pub id: String,
+    /// `title`, else `name`, else the id
+    pub title: String,
+    pub description: String,
+    /// The entry as listed, schemas included
+    pub raw: Value,
+}
+
+/// What a server offers; lists it does not announce stay empty
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct McpCatalog {
+    pub tools: Vec<McpEntry>,
+    pub resources: Vec<McpEntry>,
+    pub resource_templates: Vec<McpEntry>,
+    pub prompts: Vec<McpEntry>,
+}
+
+/// Text contents of a resource read from a server
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpResourceText {
+    pub mime_type: Option<String>,
+    pub text: String,
+}
+
+/// A running server and the pipes to talk to it
+struct McpConnection {
+    /// Kept so dropping the connection kills the process
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+    /// Result of `initialize`: server info and capabilities
+    server_info: Value,
+}
+
+pub struct McpService {
+    servers: RwLock<HashMap<String, McpServerConfig>>,
+    connections: RwLock<HashMap<String, Arc<Mutex<McpConnection>>>>,
+}
+
+impl McpService {
+    pub fn new(servers: HashMap<String, McpServerConfig>) -> Self {
+        Self {
+            servers: RwLock::new(servers),
+            connections: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Replace the configured servers; running servers whose configuration
+    /// changed or was removed are stopped
+    pub fn set_servers(&self, servers: HashMap<String, McpServerConfig>) {
+        let mut current = self.servers.write().unwrap();
+        let stale: Vec<String> = current
+            .iter()
+            .filter(|(name, config)| {
+                servers
+                    .get(*name)
+                    .is_none_or(|new| !same_launch(config, new))
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        *current = servers;
+        drop(current);
+        for name in stale {
+            self.stop(&name);
+        }
+    }
+
+    /// Add or update one server
+    pub fn set_server(&self, name: &str, config: McpServerConfig) {
+        let mut servers = self.servers.read().unwrap().clone();
+        servers.insert(name.to_string(), config);
+        self.set_servers(servers);
+    }
+
+    /// Remove one server, stopping it if it runs
+    pub fn remove_server(&self, name: &str) {
+        let mut servers = self.servers.read().unwrap().clone();
+        servers.remove(name);
+        self.set_servers(servers);
+    }
+
+    /// Names of all configured servers, enabled or not, sorted
+    pub fn server_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.servers.read().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// All configured servers with their configuration, sorted by name
+    pub fn servers(&self) -> Vec<(String, McpServerConfig)> {
+        let mut servers: Vec<(String, McpServerConfig)> = self
+            .servers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, config)| (name.clone(), config.clone()))
+            .collect();
+        servers.sort_by(|a, b| a.0.cmp(&b.0));
+        servers
+    }
+
+    pub fn is_running(&self, name: &str) -> bool {
+        self.connections.read().unwrap().contains_key(name)
+    }
+
+    /// Stop a server started by the service; the next request starts it again
+    pub fn stop(&self, name: &str) {
+        // Dropping the connection kills the process
+        if self.connections.write().unwrap().remove(name).is_some() {
+            log::info!("Stopped MCP server '{}'", name);
+        }
+    }
+
+    /// Result of the server's `initialize`, starting it if needed
+    pub async fn server_info(&self, name: &str) -> Result<Value> {
+        let connection = self.connection(name).await?;
+        let info = connection.lock().await.server_info.clone();
+        Ok(info)
+    }
+
+    /// Call a tool of a server, starting the server if needed
+    pub async fn call_tool(&self, name: &str, tool: &str, arguments: Value) -> Result<Value> {
+        let params = json!({ "name": tool, "arguments": arguments });
+        self.request(name, "tools/call", params).await
+    }
+
+    /// Tools, resources, resource templates and prompts of a server,
+    /// starting it if needed
+    pub async fn catalog(&self, name: &str) -> Result<McpCatalog> {
+        let info = self.server_info(name).await?;
+        let capabilities = info.get("capabilities").cloned().unwrap_or_default();
+        let mut catalog = McpCatalog::default();
+        if capabilities.get("tools").is_some() {
+            catalog.tools = self.list(name, "tools/list", "tools", "name").await?;
+        }
+        if capabilities.get("resources").is_some() {
+            catalog.resources = self
+                .list(name, "resources/list", "resources", "uri")
+                .await?;
+            // Not every server with resources implements templates
+            catalog.resource_templates = self
+                .list(
+                    name,
+                    "resources/templates/list",
+                    "resourceTemplates",
+                    "uriTemplate",
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    log::debug!("MCP server '{}' lists no resource templates: {}", name, e);
+                    Vec::new()
+                });
+        }
+        if capabilities.get("prompts").is_some() {
+            catalog.prompts = self.list(name, "prompts/list", "prompts", "name").await?;
+        }
+        Ok(catalog)
+    }
+
+    /// Read a resource of a server as text
+    pub async fn read_resource(&self, name: &str, uri: &str) -> Result<McpResourceText> {
+        let result = self
+            .request(name, "resources/read", json!({ "uri": uri }))
+            .await?;
+        resource_text(&result, uri)
+    }
+
+    /// Every entry of a `*/list` method, following `nextCursor`
+    async fn list(
+        &self,
+        name: &str,
+        method: &str,
+        key: &str,
+        id_key: &str,
+    ) -> Result<Vec<McpEntry>> {
+        let mut entries = Vec::new();
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_LIST_PAGES {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let page = self.request(name, method, params).await?;
+            entries.extend(list_entries(&page, key, id_key));
+            cursor = page
+                .get("nextCursor")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Send a JSON-RPC request to a server and return its result, starting
+    /// the server if needed
+    ///
+    /// A timeout or a broken pipe stops the server, as its later output could
+    /// no longer be matched to requests; errors the server answers with do
+    /// not.
+    pub async fn request(&self, name: &str, method: &str, params: Value) -> Result<Value> {
+        let connection = self.connection(name).await?;
+        let mut connection = connection.lock().await;
+        let response = smol::future::or(connection.request(method, params), async {
+            smol::Timer::after(REQUEST_TIMEOUT).await;
+            Err(anyhow!(
+                "MCP server '{}' did not answer {} within {}s",
+                name,
+                method,
+                REQUEST_TIMEOUT.as_secs()
+            ))
+        })
+        .await;
+        drop(connection);
+        match response {
+            Ok(result) => result,
+            Err(e) => {
+                self.stop(name);
+                Err(e)
+            }
+        }
+    }
+
+    async fn connection(&self, name: &str) -> Result<Arc<Mutex<McpConnection>>> {
+        if let Some(connection) = self.connections.read().unwrap().get(name) {
+            return Ok(connection.clone());
+        }
+        let config = self
+            .servers
+            .read()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("MCP server '{}' is not configured", name))?;
+
+        let connection = Arc::new(Mutex::new(McpConnection::start(name, &config).await?));
+        let mut connections = self.connections.write().unwrap();
+        // Another request may have started the server meanwhile
+        Ok(connections
+            .entry(name.to_string())
+            .or_insert(connection)
+            .clone())
+    }
+}
+
+impl McpConnection {
+    async fn start(name: &str, config: &McpServerConfig) -> Result<Self> {
+        let mut command = if cfg!(target_os = "windows") {
+            let mut command = Command::new("cmd");
+            command.arg("/C").arg(&config.command);
+            command
+        } else {
+            Command::new(&config.command)
+        };
+        let mut child = command
+            .args(&config.args)
+            .envs(&config.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to start MCP server '{}'", name))?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("MCP server '{}' has no stdin", name))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("MCP server '{}' has no stdout", name))?;
+
+        let mut connection = Self {
+            _child: child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            next_id: 1,
+            server_info: Value::Null,
+        };
+        let params = json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "agentx", "version": env!("CARGO_PKG_VERSION") },
+        });
+        let initialize = smol::future::or(connection.request("initialize", params), async {
+            smol::Timer::after(REQUEST_TIMEOUT).await;
+            Err(anyhow!("MCP server '{}' did not initialize", name))
+        });
+        connection.server_info = initialize.await??;
+        connection
+            .send(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .await?;
+        log::info!("Started MCP server '{}'", name);
+        Ok(connection)
+    }
+
+    async fn send(&mut self, message: &Value) -> Result<()> {
+        let mut line = message.to_string();
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes()).await?;
+        self.stdin.flush().await?;
+        Ok(())
+    }
+
+    /// The outer error is a failure to talk to the server, the inner one an
+    /// error the server answered with
+    async fn request(&mut self, method: &str, params: Value) -> Result<Result<Value>> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await?;
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.stdout.read_line(&mut line).await? == 0 {
+                bail!("MCP server closed its output");
+            }
+            let Ok(message) = serde_json::from_str::<Value>(line.trim()) else {
+                // Servers may log to stdout
+                log::debug!("Ignoring MCP output: {}", line.trim());
+                continue;
+            };
+            // Requests from the server: only pings are answered
+            if message.get("method").and_then(Value::as_str) == Some("ping")
+                && let Some(ping_id) = message.get("id")
+            {
+                self.send(&json!({ "jsonrpc": "2.0", "id": ping_id, "result": {} }))
+                    .await?;
+                continue;
+            }
+            if let Some(response) = parse_response(&message, id) {
+                return Ok(response);
+            }
+        }
+    }
+}
+
+/// Tool arguments typed as JSON; empty text is no arguments
+pub fn parse_tool_arguments(text: &str) -> Result<Value> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(json!({}));
+    }
+    let arguments: Value = serde_json::from_str(text).context("arguments are not valid JSON")?;
+    if !arguments.is_object() {
+        bail!("arguments must be a JSON object");
+    }
+    Ok(arguments)
+}
+
+/// Entries of one `*/list` page; entries without an `id_key` are skipped
+fn list_entries(page: &Value, key: &str, id_key: &str) -> Vec<McpEntry> {
+    let Some(items) = page.get(key).and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            let field = |name: &str| item.get(name).and_then(Value::as_str);
+            let id = field(id_key)?.to_string();
+            Some(McpEntry {
+                title: field("title")
+                    .or_else(|| field("name"))
+                    .unwrap_or(&id)
+                    .to_string(),
+                description: field("description").unwrap_or_default().to_string(),
+                id,
+                raw: item.clone(),
+            })
+        })
+        .collect()
+}
+
+/// The text contents of a `resources/read` result, joined
+fn resource_text(result: &Value, uri: &str) -> Result<McpResourceText> {
+    let contents = result
+        .get("contents")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let texts: Vec<&Value> = contents
+        .iter()
+        .filter(|content| content.get("text").is_some_and(Value::is_string))
+        .collect();
+    if texts.is_empty() {
+        bail!("resource {} has no text contents", uri);
+    }
+    let text = texts
+        .iter()
+        .filter_map(|content| content.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if text.len() > MAX_RESOURCE_BYTES {
+        bail!(
+            "resource {} is too large to attach ({} KB, at most {} KB)",
+            uri,
+            text.len() / 1024,
+            MAX_RESOURCE_BYTES / 1024
+        );
+    }
+    let mime_type = texts[0]
+        .get("mimeType")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    Ok(McpResourceText { mime_type, text })
+}
+
+/// Whether two configurations start the same process
+fn same_launch(a: &McpServerConfig, b: &McpServerConfig) -> bool {
+    a.command == b.command && a.args == b.args && a.env == b.env
+}
+
+/// The outcome of request `id` if `message` is its response
+fn parse_response(message: &Value, id: u64) -> Option<Result<Value>> {
+    if message.get("id").and_then(Value::as_u64) != Some(id) || message.get("method").is_some() {
+        return None;
+    }
+    if let Some(error) = message.get("error") {
+        let code = error
+            .get("code")
+            .and_then(Value::as_i64)
+            .unwrap_or_default();
+        let text = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error");
+        let mut description = format!("MCP error {}: {}", code, text);
+        if let Some(data) = error.get("data") {
+            description.push_str(&format!(" ({})", data));
+        }
+        return Some(Err(anyhow!(description)));
+    }
+    Some(Ok(message.get("result").cloned().unwrap_or(Value::Null)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tool_arguments() {
+        assert_eq!(parse_tool_arguments("  ").unwrap(), json!({}));
+        assert_eq!(
+            parse_tool_arguments(r#"{"path": "a"}"#).unwrap(),
+            json!({"path": "a"})
+        );
+        assert!(parse_tool_arguments("[1]").is_err());
+        assert!(parse_tool_arguments("{path").is_err());
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let ok = json!({"jsonrpc": "2.0", "id": 2, "result": {"tools": []}});
+        assert_eq!(
+            parse_response(&ok, 2).unwrap().unwrap(),
+            json!({"tools": []})
+        );
+        assert!(parse_response(&ok, 3).is_none());
+
+        let notification = json!({"jsonrpc": "2.0", "method": "notifications/progress"});
+        assert!(parse_response(&notification, 2).is_none());
+
+        let error = json!({"jsonrpc": "2.0", "id": 2, "error": {"code": -32601, "message": "no"}});
+        let error = parse_response(&error, 2).unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "MCP error -32601: no");
+    }
+
+    #[test]
+    fn test_list_entries() {
+        let page = json!({"tools": [
+            {"name": "read", "title": "Read File", "inputSchema": {"type": "object"}},
+            {"name": "list"},
+            {"title": "no name"},
+        ]});
+        let entries = list_entries(&page, "tools", "name");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].title, "Read File");
+        assert_eq!(entries[0].raw["inputSchema"]["type"], "object");
+        assert_eq!(entries[1].title, "list");
+        assert_eq!(entries[1].description, "");
+
+        let page = json!({"resourceTemplates": [{"uriTemplate": "db://{table}"}]});
+        let entries = list_entries(&page, "resourceTemplates", "uriTemplate");
+        assert_eq!(entries[0].id, "db://{table}");
+        assert_eq!(entries[0].title, "db://{table}");
+    }
+
+    #[test]
+    fn test_resource_text() {
+        let result = json!({"contents": [
+            {"uri": "a", "mimeType": "text/markdown", "text": "one"},
+            {"uri": "a", "blob": "AAAA"},
+            {"uri": "a", "text": "two"},
+        ]});
+        let resource = resource_text(&result, "a").unwrap();
+        assert_eq!(resource.text, "one\n\ntwo");
+        assert_eq!(resource.mime_type.as_deref(), Some("text/markdown"));
+
+        let binary = json!({"contents": [{"uri": "b", "blob": "AAAA"}]});
+        assert!(resource_text(&binary, "b").is_err());
+    }
+}
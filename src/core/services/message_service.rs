@@ -2,21 +2,139 @@
 //!
 //! This service provides a high-level API for sending messages and subscribing
 //! to session updates. It orchestrates between AgentService and SessionBus.
+//!
+//! It also times each turn it sends: how long the prompt waited before going
+//! to the agent, how long the agent took to send its first update, and how
+//! long the whole turn took. The timings are saved next to the session's
+//! history and reported as `WorkspaceUpdateEvent::TurnTimed`.
+//!
+//! Subscriptions pass through a `NetworkSimulation`, which can hold updates
+//! back to imitate a slow agent or connection while testing the UI.
+//!
+//! The updates of each running turn are kept in memory until it ends, since
+//! the history on disk lags behind a streaming turn. A conversation rebuilt
+//! mid-turn, e.g. after its window was recreated, loads the history up to the
+//! turn and continues from these updates instead of losing the turn.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
 use agent_client_protocol::{
     AvailableCommand, ContentBlock, ContentChunk, ImageContent, PromptResponse, SessionUpdate,
     TextContent,
 };
-use anyhow::{Result, anyhow};
 
 use crate::core::event_bus::session_bus::{SessionUpdateBusContainer, SessionUpdateEvent};
 use crate::core::event_bus::workspace_bus::{WorkspaceUpdateBusContainer, WorkspaceUpdateEvent};
 use crate::core::services::SessionStatus;
 
 use super::agent_service::AgentService;
-use super::persistence_service::{PersistedMessage, PersistenceService};
+use super::error::{ServiceError, ServiceResult};
+use super::network_simulation::{self, NetworkSimulation};
+use super::persistence_service::{HistoryEntry, PersistedMessage, PersistenceService, TurnLatency};
+
+/// Timing of a turn that is running
+#[derive(Debug, Clone)]
+struct TurnClock {
+    turn: usize,
+    /// When the message was sent
+    started: Instant,
+    /// When the prompt went to the agent
+    prompted: Option<Instant>,
+    /// When the agent's first update arrived
+    first_update: Option<Instant>,
+}
+
+impl TurnClock {
+    fn new(turn: usize, started: Instant) -> Self {
+        Self {
+            turn,
+            started,
+            prompted: None,
+            first_update: None,
+        }
+    }
+
+    fn latency(&self, finished: Instant) -> TurnLatency {
+        let ms = |from: Instant, to: Instant| to.saturating_duration_since(from).as_millis() as u64;
+        let prompted = self.prompted.unwrap_or(self.started);
+        TurnLatency {
+            turn: self.turn,
+            queue_ms: ms(self.started, prompted),
+            first_chunk_ms: self.first_update.map(|at| ms(prompted, at)),
+            total_ms: ms(self.started, finished),
+            finished_at: chrono::Utc::now(),
+        }
+    }
+}
+
+/// Updates of a turn that is running
+#[derive(Debug, Clone)]
+pub struct LiveTurn {
+    /// When the turn was sent; persisted messages from then on belong to it
+    pub started: DateTime<Utc>,
+    pub updates: Vec<SessionUpdate>,
+}
+
+/// Running turns, and the subscriptions that continue from them
+#[derive(Default)]
+struct LiveTurns {
+    turns: HashMap<String, LiveTurn>,
+    followers: HashMap<String, Vec<UnboundedSender<(Instant, SessionUpdateEvent)>>>,
+}
+
+impl LiveTurns {
+    fn record(&mut self, event: &SessionUpdateEvent) {
+        if let Some(turn) = self.turns.get_mut(&event.session_id) {
+            turn.updates.push((*event.update).clone());
+        }
+        if let Some(followers) = self.followers.get_mut(&event.session_id) {
+            followers.retain(|tx| tx.send((Instant::now(), event.clone())).is_ok());
+        }
+    }
+}
+
+/// `history` up to `live_turn`, followed by the turn's updates; the turn's
+/// messages on disk may be incomplete
+fn with_live_turn(
+    mut history: Vec<HistoryEntry>,
+    live_turn: Option<&LiveTurn>,
+) -> Vec<HistoryEntry> {
+    let Some(live_turn) = live_turn else {
+        return history;
+    };
+    history.retain(|entry| {
+        !DateTime::parse_from_rfc3339(entry.timestamp())
+            .is_ok_and(|timestamp| timestamp.with_timezone(&Utc) >= live_turn.started)
+    });
+    let timestamp = live_turn.started.to_rfc3339();
+    history.extend(live_turn.updates.iter().map(|update| {
+        HistoryEntry::Message(PersistedMessage::with_timestamp(
+            timestamp.clone(),
+            update.clone(),
+        ))
+    }));
+    history
+}
+
+/// Number of prompts in a session's history, consecutive user chunks
+/// counting as one prompt
+fn count_prompts(messages: &[PersistedMessage]) -> usize {
+    let mut count = 0;
+    let mut in_prompt = false;
+    for message in messages {
+        let is_prompt = matches!(message.update, SessionUpdate::UserMessageChunk(_));
+        if is_prompt && !in_prompt {
+            count += 1;
+        }
+        in_prompt = is_prompt;
+    }
+    count
+}
 
 /// Message service - handles message sending and event bus interaction
 pub struct MessageService {
@@ -24,6 +142,14 @@ pub struct MessageService {
     agent_service: Arc<AgentService>,
     persistence_service: Arc<PersistenceService>,
     workspace_bus: WorkspaceUpdateBusContainer,
+    /// Turns being timed, by session
+    turn_clocks: Arc<Mutex<HashMap<String, TurnClock>>>,
+    /// Prompts sent in each session, counted from its history on first use
+    prompt_counts: Mutex<HashMap<String, usize>>,
+    /// Delays applied to subscriptions, off unless set from the settings
+    network_simulation: Arc<RwLock<NetworkSimulation>>,
+    /// Updates of the running turns, by session
+    live_turns: Arc<Mutex<LiveTurns>>,
 }
 
 impl MessageService {
@@ -38,6 +164,10 @@ impl MessageService {
             agent_service,
             persistence_service,
             workspace_bus,
+            turn_clocks: Arc::new(Mutex::new(HashMap::new())),
+            prompt_counts: Mutex::new(HashMap::new()),
+            network_simulation: Arc::new(RwLock::new(NetworkSimulation::default())),
+            live_turns: Arc::new(Mutex::new(LiveTurns::default())),
         }
     }
 
@@ -49,9 +179,13 @@ impl MessageService {
         let persistence_service = self.persistence_service.clone();
         let session_bus = self.session_bus.clone();
         let agent_service = self.agent_service.clone();
+        let turn_clocks = self.turn_clocks.clone();
+        let live_turns = self.live_turns.clone();
 
         // Subscribe to session bus for all session updates
         session_bus.subscribe(move |event| {
+            live_turns.lock().unwrap().record(event);
+
             let session_id = event.session_id.clone();
             let update = (*event.update).clone();
             let agent_name = event.agent_name.clone();
@@ -67,8 +201,9 @@ impl MessageService {
                 );
 
                 // Get agent name for this session (prefer event metadata if available)
-                let agent_name =
-                    agent_name.or_else(|| agent_svc.get_agent_for_session(&session_id));
+                let agent_name = agent_name
+                    .clone()
+                    .or_else(|| agent_svc.get_agent_for_session(&session_id));
 
                 if let Some(agent_name) = agent_name {
                     agent_svc.update_session_commands(
@@ -84,6 +219,48 @@ impl MessageService {
                 }
             }
 
+            // Keep the session's current mode for hover cards
+            if let SessionUpdate::CurrentModeUpdate(ref mode_update) = update
+                && let Some(agent_name) = agent_name
+                    .clone()
+                    .or_else(|| agent_svc.get_agent_for_session(&session_id))
+            {
+                agent_svc.update_session_mode(
+                    &agent_name,
+                    &session_id,
+                    mode_update.current_mode_id.clone(),
+                );
+            }
+
+            // Cancel turns that stream past the session's output limit
+            if let SessionUpdate::AgentMessageChunk(ContentChunk {
+                content: ContentBlock::Text(ref text),
+                ..
+            })
+            | SessionUpdate::AgentThoughtChunk(ContentChunk {
+                content: ContentBlock::Text(ref text),
+                ..
+            }) = update
+                && let Some(agent_name) = agent_name
+                    .clone()
+                    .or_else(|| agent_svc.get_agent_for_session(&session_id))
+            {
+                agent_svc.guard_output(&agent_name, &session_id, text.text.chars().count());
+            }
+
+            // The first update of a timed turn, once its prompt was sent
+            // (a session loaded before the prompt replays its history)
+            if matches!(
+                update,
+                SessionUpdate::AgentMessageChunk(_)
+                    | SessionUpdate::AgentThoughtChunk(_)
+                    | SessionUpdate::ToolCall(_)
+            ) && let Some(clock) = turn_clocks.lock().unwrap().get_mut(&session_id)
+                && clock.prompted.is_some()
+            {
+                clock.first_update.get_or_insert_with(Instant::now);
+            }
+
             // Spawn async task using smol to save message
             smol::spawn(async move {
                 if let Err(e) = service.save_update(&session_id, update).await {
@@ -100,12 +277,20 @@ impl MessageService {
         // Subscribe to workspace bus for session status changes
         let persistence_service_ws = self.persistence_service.clone();
         let workspace_bus = self.workspace_bus.clone();
+        let turn_clocks = self.turn_clocks.clone();
 
         workspace_bus.subscribe(move |event| {
             if let WorkspaceUpdateEvent::SessionStatusUpdated {
                 session_id, status, ..
             } = event
             {
+                // A timed turn's prompt goes to the agent
+                if *status == SessionStatus::InProgress
+                    && let Some(clock) = turn_clocks.lock().unwrap().get_mut(session_id)
+                {
+                    clock.prompted.get_or_insert_with(Instant::now);
+                }
+
                 // Flush accumulator when session completes or becomes idle
                 if matches!(status, SessionStatus::Completed | SessionStatus::Idle) {
                     let service = persistence_service_ws.clone();
@@ -136,6 +321,7 @@ impl MessageService {
     /// 1. Verify the session exists
     /// 2. Publish the user message to the event bus (immediate UI feedback)
     /// 3. Send the prompt to the agent
+    /// 4. Save the latency of the turn, if it succeeded
     ///
     /// Use this when you already have a session ID and want to ensure
     /// the UI panel has subscribed before the message is sent.
@@ -144,16 +330,29 @@ impl MessageService {
         agent_name: &str,
         session_id: &str,
         content_blocks: Vec<ContentBlock>,
-    ) -> Result<PromptResponse> {
+    ) -> ServiceResult<PromptResponse> {
         // 1. Verify session exists
         if self
             .agent_service
             .get_session_info(agent_name, session_id)
             .is_none()
         {
-            return Err(anyhow!("Session not found: {}", session_id));
+            return Err(ServiceError::SessionNotFound(session_id.to_string()));
         }
 
+        let turn = self.next_turn(session_id).await;
+        self.turn_clocks
+            .lock()
+            .unwrap()
+            .insert(session_id.to_string(), TurnClock::new(turn, Instant::now()));
+        self.live_turns.lock().unwrap().turns.insert(
+            session_id.to_string(),
+            LiveTurn {
+                started: Utc::now(),
+                updates: Vec::new(),
+            },
+        );
+
         // 2. Publish user message blocks to event bus (immediate UI feedback)
         for block in &content_blocks {
             self.publish_user_content_block(session_id, block);
@@ -163,10 +362,47 @@ impl MessageService {
         let result = self
             .agent_service
             .send_prompt(agent_name, session_id, content_blocks)
-            .await
-            .map_err(|e| anyhow!("Failed to send message: {}", e))?;
+            .await;
+
+        self.live_turns.lock().unwrap().turns.remove(session_id);
+
+        // 4. Save the turn's latency
+        let clock = self.turn_clocks.lock().unwrap().remove(session_id);
+        if let Some(clock) = clock
+            && result.is_ok()
+        {
+            let latency = clock.latency(Instant::now());
+            if let Err(e) = self
+                .persistence_service
+                .add_turn_latency(session_id, latency.clone())
+                .await
+            {
+                log::warn!("Failed to save turn latency of {}: {}", session_id, e);
+            }
+            self.workspace_bus.publish(WorkspaceUpdateEvent::TurnTimed {
+                session_id: session_id.to_string(),
+                latency,
+            });
+        }
+        result
+    }
 
-        Ok(result)
+    /// Position of the next prompt of a session among its prompts
+    async fn next_turn(&self, session_id: &str) -> usize {
+        let known = self.prompt_counts.lock().unwrap().get(session_id).copied();
+        let turn = match known {
+            Some(count) => count,
+            None => self
+                .load_history(session_id)
+                .await
+                .map(|messages| count_prompts(&messages))
+                .unwrap_or(0),
+        };
+        self.prompt_counts
+            .lock()
+            .unwrap()
+            .insert(session_id.to_string(), turn + 1);
+        turn
     }
 
     /// Publish a user message to the event bus (immediate UI feedback)
@@ -228,6 +464,7 @@ impl MessageService {
         session_id: Option<String>,
     ) -> tokio::sync::mpsc::UnboundedReceiver<SessionUpdateEvent> {
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let (bus_tx, bus_rx) = tokio::sync::mpsc::unbounded_channel();
 
         self.session_bus.subscribe(move |event| {
             // Filter by session_id if specified
@@ -237,26 +474,82 @@ impl MessageService {
                 }
             }
 
-            let _ = tx.send(event.clone());
+            let _ = bus_tx.send((Instant::now(), event.clone()));
         });
+        smol::spawn(network_simulation::relay(
+            self.network_simulation.clone(),
+            bus_rx,
+            tx,
+        ))
+        .detach();
 
         rx
     }
 
+    /// Subscribe to the updates of a session, along with the turn it is
+    /// running, if any
+    ///
+    /// The returned turn holds every update published before the
+    /// subscription, the receiver every update after it.
+    pub fn subscribe_with_live_turn(
+        &self,
+        session_id: String,
+    ) -> (Option<LiveTurn>, UnboundedReceiver<SessionUpdateEvent>) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let (bus_tx, bus_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let live_turn = {
+            let mut live_turns = self.live_turns.lock().unwrap();
+            let live_turn = live_turns.turns.get(&session_id).cloned();
+            live_turns
+                .followers
+                .entry(session_id)
+                .or_default()
+                .push(bus_tx);
+            live_turn
+        };
+        smol::spawn(network_simulation::relay(
+            self.network_simulation.clone(),
+            bus_rx,
+            tx,
+        ))
+        .detach();
+
+        (live_turn, rx)
+    }
+
+    /// Load the history of a session up to `live_turn`, followed by the
+    /// turn's updates
+    pub async fn load_history_with_live_turn(
+        &self,
+        session_id: &str,
+        live_turn: Option<&LiveTurn>,
+    ) -> ServiceResult<Vec<HistoryEntry>> {
+        // Write the chunks still accumulating, e.g. of a turn that just ended
+        self.persistence_service.flush_session(session_id).await?;
+        let history = self.persistence_service.load_history(session_id).await?;
+        Ok(with_live_turn(history, live_turn))
+    }
+
+    /// Change the simulated network for all subscriptions
+    pub fn set_network_simulation(&self, simulation: NetworkSimulation) {
+        *self.network_simulation.write().unwrap() = simulation;
+    }
+
     /// Load historical messages for a session
     ///
     /// Returns all persisted messages in chronological order
-    pub async fn load_history(&self, session_id: &str) -> Result<Vec<PersistedMessage>> {
+    pub async fn load_history(&self, session_id: &str) -> ServiceResult<Vec<PersistedMessage>> {
         self.persistence_service.load_messages(session_id).await
     }
 
     /// Delete a session's history
-    pub async fn delete_history(&self, session_id: &str) -> Result<()> {
+    pub async fn delete_history(&self, session_id: &str) -> ServiceResult<()> {
         self.persistence_service.delete_session(session_id).await
     }
 
     /// List all available sessions with history
-    pub async fn list_sessions_with_history(&self) -> Result<Vec<String>> {
+    pub async fn list_sessions_with_history(&self) -> ServiceResult<Vec<String>> {
         self.persistence_service.list_sessions().await
     }
 
@@ -283,3 +576,91 @@ impl MessageService {
             .get_session_commands(&agent_name, session_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::services::UnsupportedUpdate;
+    use std::time::Duration;
+
+    fn message(update: SessionUpdate) -> PersistedMessage {
+        PersistedMessage::new(update)
+    }
+
+    fn chunk(text: &str) -> ContentChunk {
+        ContentChunk::new(ContentBlock::from(text.to_string()))
+    }
+
+    #[test]
+    fn test_count_prompts() {
+        let messages = vec![
+            message(SessionUpdate::UserMessageChunk(chunk("Fix"))),
+            message(SessionUpdate::UserMessageChunk(chunk(" it"))),
+            message(SessionUpdate::AgentMessageChunk(chunk("Done"))),
+            message(SessionUpdate::UserMessageChunk(chunk("Thanks"))),
+        ];
+        assert_eq!(count_prompts(&messages), 2);
+        assert_eq!(count_prompts(&[]), 0);
+    }
+
+    #[test]
+    fn test_with_live_turn() {
+        let started = Utc::now();
+        let before = (started - chrono::Duration::seconds(5)).to_rfc3339();
+        let during = (started + chrono::Duration::seconds(1)).to_rfc3339();
+        let history = vec![
+            HistoryEntry::Message(PersistedMessage::with_timestamp(
+                before.clone(),
+                SessionUpdate::UserMessageChunk(chunk("Hi")),
+            )),
+            HistoryEntry::Message(PersistedMessage::with_timestamp(
+                during,
+                SessionUpdate::UserMessageChunk(chunk("Fix it")),
+            )),
+            HistoryEntry::Unsupported(
+                "not a time".to_string(),
+                UnsupportedUpdate::from_raw(serde_json::json!({"sessionUpdate": "hologram"})),
+            ),
+        ];
+        assert_eq!(with_live_turn(history.clone(), None).len(), 3);
+
+        let live_turn = LiveTurn {
+            started,
+            updates: vec![
+                SessionUpdate::UserMessageChunk(chunk("Fix it")),
+                SessionUpdate::AgentMessageChunk(chunk("On it")),
+            ],
+        };
+        let restored = with_live_turn(history, Some(&live_turn));
+        assert_eq!(restored.len(), 4);
+        assert_eq!(restored[0].timestamp(), before);
+        assert!(matches!(restored[1], HistoryEntry::Unsupported(..)));
+        assert_eq!(restored[3].timestamp(), started.to_rfc3339());
+        assert!(matches!(
+            &restored[3],
+            HistoryEntry::Message(PersistedMessage {
+                update: SessionUpdate::AgentMessageChunk(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_turn_clock_latency() {
+        let started = Instant::now();
+        let mut clock = TurnClock::new(3, started);
+        clock.prompted = Some(started + Duration::from_millis(200));
+        clock.first_update = Some(started + Duration::from_millis(1_200));
+
+        let latency = clock.latency(started + Duration::from_millis(5_000));
+        assert_eq!(latency.turn, 3);
+        assert_eq!(latency.queue_ms, 200);
+        assert_eq!(latency.first_chunk_ms, Some(1_000));
+        assert_eq!(latency.total_ms, 5_000);
+
+        // Nothing streamed, and the status change was never seen
+        let latency = TurnClock::new(0, started).latency(started + Duration::from_millis(50));
+        assert_eq!(latency.queue_ms, 0);
+        assert_eq!(latency.first_chunk_ms, None);
+    }
+}
@@ -0,0 +1,552 @@
+//! Metrics Service - Activity statistics computed from persisted data
+//!
+//! Combines the workspace's tasks (sessions, agents, usage) with the session
+//! history files (tool calls, edited files) and turn latencies into per-day
+//! activity, for the Dashboard panel, and into per-task statistics for
+//! `agentx export-stats`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use agent_client_protocol::{SessionUpdate, ToolCallContent};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::Serialize;
+
+use super::persistence_service::{PersistedMessage, PersistenceService, TurnLatency};
+use super::workspace_service::WorkspaceService;
+use crate::schemas::workspace_schema::{FeedbackRating, TaskUsage, WorkspaceTask};
+
+/// Tool calls and edited files found in a task's session histories
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionMetrics {
+    /// Tool calls started, per day (UTC)
+    pub tool_calls: BTreeMap<NaiveDate, usize>,
+    /// Files changed by the sessions' edits
+    pub files_modified: BTreeSet<PathBuf>,
+    /// Latency of the sessions' turns
+    pub latencies: Vec<TurnLatency>,
+}
+
+impl SessionMetrics {
+    pub fn total_tool_calls(&self) -> usize {
+        self.tool_calls.values().sum()
+    }
+
+    fn merge(&mut self, other: SessionMetrics) {
+        for (date, count) in other.tool_calls {
+            *self.tool_calls.entry(date).or_default() += count;
+        }
+        self.files_modified.extend(other.files_modified);
+        self.latencies.extend(other.latencies);
+    }
+}
+
+/// Average latency of turns
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LatencySummary {
+    pub turns: usize,
+    pub queue_ms: u64,
+    /// Over the turns in which the agent sent an update
+    pub first_chunk_ms: Option<u64>,
+    pub total_ms: u64,
+}
+
+/// Average the latency of `latencies`
+pub fn summarize_latency<'a>(
+    latencies: impl IntoIterator<Item = &'a TurnLatency>,
+) -> LatencySummary {
+    let mut summary = LatencySummary::default();
+    let (mut queue_ms, mut total_ms) = (0, 0);
+    let mut first_chunks = Vec::new();
+    for latency in latencies {
+        summary.turns += 1;
+        queue_ms += latency.queue_ms;
+        total_ms += latency.total_ms;
+        first_chunks.extend(latency.first_chunk_ms);
+    }
+    if summary.turns == 0 {
+        return summary;
+    }
+    summary.queue_ms = queue_ms / summary.turns as u64;
+    summary.total_ms = total_ms / summary.turns as u64;
+    summary.first_chunk_ms = (!first_chunks.is_empty())
+        .then(|| first_chunks.iter().sum::<u64>() / first_chunks.len() as u64);
+    summary
+}
+
+/// Activity of one day
+#[derive(Clone, Debug, PartialEq)]
+pub struct DayActivity {
+    pub date: NaiveDate,
+    /// Tasks created that day
+    pub sessions: usize,
+    pub tool_calls: usize,
+    /// Files edited by the tasks created that day
+    pub files_modified: usize,
+}
+
+/// A workspace's activity over the last days
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WorkspaceActivity {
+    /// One entry per day, oldest first, including days without activity
+    pub days: Vec<DayActivity>,
+    pub sessions: usize,
+    pub tool_calls: usize,
+    /// Distinct files edited
+    pub files_modified: usize,
+    pub usage: TaskUsage,
+    /// Agents by number of sessions, most used first
+    pub top_agents: Vec<(String, usize)>,
+    /// Latency of the tasks' timed turns
+    pub latency: LatencySummary,
+}
+
+impl WorkspaceActivity {
+    /// Largest daily session count, for scaling charts
+    pub fn max_sessions_per_day(&self) -> usize {
+        self.days.iter().map(|day| day.sessions).max().unwrap_or(0)
+    }
+
+    /// Largest daily tool call count, for scaling charts
+    pub fn max_tool_calls_per_day(&self) -> usize {
+        self.days.iter().map(|day| day.tool_calls).max().unwrap_or(0)
+    }
+}
+
+/// Count the tool calls and edited files in a session's history
+pub fn session_metrics(messages: &[PersistedMessage]) -> SessionMetrics {
+    let mut metrics = SessionMetrics::default();
+    let mut seen = BTreeSet::new();
+    for message in messages {
+        let content = match &message.update {
+            SessionUpdate::ToolCall(tool_call) => {
+                // Agents may resend a tool call to update it; count each once
+                if seen.insert(tool_call.tool_call_id.to_string()) {
+                    let date = DateTime::parse_from_rfc3339(&message.timestamp)
+                        .map(|timestamp| timestamp.with_timezone(&Utc).date_naive())
+                        .unwrap_or_else(|_| Utc::now().date_naive());
+                    *metrics.tool_calls.entry(date).or_default() += 1;
+                }
+                Some(&tool_call.content)
+            }
+            SessionUpdate::ToolCallUpdate(update) => update.fields.content.as_ref(),
+            _ => None,
+        };
+        for content in content.into_iter().flatten() {
+            if let ToolCallContent::Diff(diff) = content {
+                metrics.files_modified.insert(diff.path.clone());
+            }
+        }
+    }
+    metrics
+}
+
+/// First day of the `days` days ending with `today`
+fn first_day(today: NaiveDate, days: usize) -> NaiveDate {
+    today
+        .checked_sub_days(Days::new(days.saturating_sub(1) as u64))
+        .unwrap_or(today)
+}
+
+/// Total `tasks` over the `days` days ending with `today`
+pub fn summarize_activity(
+    tasks: &[(WorkspaceTask, SessionMetrics)],
+    today: NaiveDate,
+    days: usize,
+) -> WorkspaceActivity {
+    let first = first_day(today, days);
+    let mut by_day: BTreeMap<NaiveDate, DayActivity> = first
+        .iter_days()
+        .take_while(|date| *date <= today)
+        .map(|date| {
+            let day = DayActivity {
+                date,
+                sessions: 0,
+                tool_calls: 0,
+                files_modified: 0,
+            };
+            (date, day)
+        })
+        .collect();
+
+    let mut activity = WorkspaceActivity::default();
+    let mut files = BTreeSet::new();
+    let mut agents: HashMap<&str, usize> = HashMap::new();
+    let mut latencies = Vec::new();
+    for (task, metrics) in tasks {
+        let created = task.created_at.date_naive();
+        if created < first {
+            continue;
+        }
+        if let Some(day) = by_day.get_mut(&created) {
+            day.sessions += 1;
+            day.files_modified += metrics.files_modified.len();
+        }
+        for (date, count) in &metrics.tool_calls {
+            if let Some(day) = by_day.get_mut(date) {
+                day.tool_calls += count;
+            }
+        }
+        activity.sessions += 1;
+        activity.tool_calls += metrics.total_tool_calls();
+        activity.usage.add(&task.usage);
+        files.extend(metrics.files_modified.iter());
+        latencies.extend(metrics.latencies.iter());
+        *agents.entry(task.agent_name.as_str()).or_default() += 1;
+    }
+
+    activity.files_modified = files.len();
+    activity.latency = summarize_latency(latencies);
+    activity.days = by_day.into_values().collect();
+    let mut top_agents: Vec<(String, usize)> = agents
+        .into_iter()
+        .map(|(agent, count)| (agent.to_string(), count))
+        .collect();
+    top_agents.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    activity.top_agents = top_agents;
+    activity
+}
+
+/// File format of a stats export
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    /// One row per task; feedback comments are only in the JSON export
+    Csv,
+}
+
+impl ExportFormat {
+    /// The format matching `path`'s extension, JSON unless it ends in `.csv`
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Self::Csv,
+            _ => Self::Json,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            other => Err(format!("Unknown export format '{}' (expected json or csv)", other)),
+        }
+    }
+}
+
+/// A task's metadata and metrics in a stats export
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TaskStats {
+    pub workspace: String,
+    pub task_id: String,
+    pub name: String,
+    pub agent_name: String,
+    pub mode: String,
+    pub label: Option<String>,
+    pub session_id: Option<String>,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    /// Time the user spent active in the conversation
+    pub active_ms: u64,
+    pub turns: u32,
+    pub busy_ms: u64,
+    pub prompt_chars: u64,
+    pub output_chars: u64,
+    pub estimated_tokens: u64,
+    pub tool_calls: usize,
+    pub files_modified: usize,
+    pub thumbs_up: usize,
+    pub thumbs_down: usize,
+}
+
+/// A rating in a stats export
+#[derive(Clone, Debug, Serialize)]
+pub struct FeedbackStats {
+    pub task_id: String,
+    pub message_id: String,
+    pub agent_name: String,
+    pub rating: FeedbackRating,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Everything `agentx export-stats` writes
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct StatsExport {
+    pub generated_at: DateTime<Utc>,
+    pub tasks: Vec<TaskStats>,
+    pub feedback: Vec<FeedbackStats>,
+}
+
+impl StatsExport {
+    pub fn render(&self, format: ExportFormat) -> anyhow::Result<String> {
+        match format {
+            ExportFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            ExportFormat::Csv => Ok(self.to_csv()),
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "workspace,task_id,name,agent_name,mode,label,session_id,status,created_at,\
+             active_ms,turns,busy_ms,prompt_chars,output_chars,estimated_tokens,tool_calls,\
+             files_modified,thumbs_up,thumbs_down\n",
+        );
+        for task in &self.tasks {
+            let fields = [
+                csv_field(&task.workspace),
+                csv_field(&task.task_id),
+                csv_field(&task.name),
+                csv_field(&task.agent_name),
+                csv_field(&task.mode),
+                csv_field(task.label.as_deref().unwrap_or_default()),
+                csv_field(task.session_id.as_deref().unwrap_or_default()),
+                csv_field(&task.status),
+                task.created_at.to_rfc3339(),
+                task.active_ms.to_string(),
+                task.turns.to_string(),
+                task.busy_ms.to_string(),
+                task.prompt_chars.to_string(),
+                task.output_chars.to_string(),
+                task.estimated_tokens.to_string(),
+                task.tool_calls.to_string(),
+                task.files_modified.to_string(),
+                task.thumbs_up.to_string(),
+                task.thumbs_down.to_string(),
+            ];
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+pub(super) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Metrics service - reads tasks and session histories into statistics
+pub struct MetricsService {
+    workspace_service: Arc<WorkspaceService>,
+    persistence_service: Arc<PersistenceService>,
+}
+
+impl MetricsService {
+    pub fn new(
+        workspace_service: Arc<WorkspaceService>,
+        persistence_service: Arc<PersistenceService>,
+    ) -> Self {
+        Self {
+            workspace_service,
+            persistence_service,
+        }
+    }
+
+    /// Metrics of all sessions of `task`, including @mentioned agents' sessions
+    pub async fn task_metrics(&self, task: &WorkspaceTask) -> SessionMetrics {
+        let mut metrics = SessionMetrics::default();
+        let sessions = task.session_id.iter().chain(task.agent_sessions.values());
+        for session_id in sessions {
+            match self.persistence_service.load_messages(session_id).await {
+                Ok(messages) => metrics.merge(session_metrics(&messages)),
+                Err(e) => log::warn!("Failed to load history of session {}: {}", session_id, e),
+            }
+            match self
+                .persistence_service
+                .load_turn_latencies(session_id)
+                .await
+            {
+                Ok(latencies) => metrics.latencies.extend(latencies),
+                Err(e) => log::warn!("Failed to load latency of session {}: {}", session_id, e),
+            }
+        }
+        metrics
+    }
+
+    /// Activity of the workspace's tasks over the last `days` days
+    pub async fn workspace_activity(&self, workspace_id: &str, days: usize) -> WorkspaceActivity {
+        let today = Utc::now().date_naive();
+        let first = first_day(today, days);
+
+        let mut tasks = Vec::new();
+        for task in self.workspace_service.get_workspace_tasks(workspace_id).await {
+            if task.created_at.date_naive() < first {
+                continue;
+            }
+            let metrics = self.task_metrics(&task).await;
+            tasks.push((task, metrics));
+        }
+        summarize_activity(&tasks, today, days)
+    }
+
+    /// Metadata, usage and feedback of every task, for external analysis
+    pub async fn export_stats(&self) -> StatsExport {
+        let config = self.workspace_service.get_config().await;
+        let mut export = StatsExport {
+            generated_at: Utc::now(),
+            ..Default::default()
+        };
+        for task in &config.tasks {
+            let metrics = self.task_metrics(task).await;
+            let workspace = config
+                .get_workspace(&task.workspace_id)
+                .map(|workspace| workspace.name.clone())
+                .unwrap_or_default();
+            let rated = |rating| task.feedback.iter().filter(|f| f.rating == rating).count();
+            export.tasks.push(TaskStats {
+                workspace,
+                task_id: task.id.clone(),
+                name: task.name.clone(),
+                agent_name: task.agent_name.clone(),
+                mode: task.mode.clone(),
+                label: task.label.clone(),
+                session_id: task.session_id.clone(),
+                status: format!("{:?}", task.status),
+                created_at: task.created_at,
+                active_ms: task.active_ms,
+                turns: task.usage.turns,
+                busy_ms: task.usage.busy_ms,
+                prompt_chars: task.usage.prompt_chars,
+                output_chars: task.usage.output_chars,
+                estimated_tokens: task.usage.estimated_tokens(),
+                tool_calls: metrics.total_tool_calls(),
+                files_modified: metrics.files_modified.len(),
+                thumbs_up: rated(FeedbackRating::Up),
+                thumbs_down: rated(FeedbackRating::Down),
+            });
+            export
+                .feedback
+                .extend(task.feedback.iter().map(|feedback| FeedbackStats {
+                    task_id: task.id.clone(),
+                    message_id: feedback.message_id.clone(),
+                    agent_name: feedback.agent_name.clone(),
+                    rating: feedback.rating,
+                    comment: feedback.comment.clone(),
+                    created_at: feedback.created_at,
+                }));
+        }
+        export
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::{Diff, ToolCall, ToolCallId};
+
+    fn edit(id: &str, path: &str, timestamp: &str) -> PersistedMessage {
+        let mut tool_call = ToolCall::new(ToolCallId::from(id.to_string()), "Edit".to_string());
+        let diff = Diff::new(PathBuf::from(path), "new".to_string());
+        tool_call.content = vec![ToolCallContent::Diff(diff)];
+        PersistedMessage::with_timestamp(timestamp.to_string(), SessionUpdate::ToolCall(tool_call))
+    }
+
+    #[test]
+    fn test_session_metrics_counts_each_tool_call_once() {
+        let messages = vec![
+            edit("1", "src/a.rs", "2026-03-01T10:00:00Z"),
+            edit("2", "src/b.rs", "2026-03-02T10:00:00Z"),
+            edit("2", "src/b.rs", "2026-03-02T11:00:00Z"),
+        ];
+        let metrics = session_metrics(&messages);
+        assert_eq!(metrics.total_tool_calls(), 2);
+        assert_eq!(metrics.files_modified.len(), 2);
+    }
+
+    #[test]
+    fn test_stats_csv_quotes_fields() {
+        let export = StatsExport {
+            tasks: vec![TaskStats {
+                name: "Fix \"login\", then deploy".to_string(),
+                turns: 3,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let csv = export.render(ExportFormat::Csv).unwrap();
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.starts_with(",,\"Fix \"\"login\"\", then deploy\",,,,,,"));
+        assert_eq!(row.split(',').count(), 20);
+        assert_eq!("CSV".parse::<ExportFormat>(), Ok(ExportFormat::Csv));
+        assert_eq!(ExportFormat::from_path(Path::new("stats.json")), ExportFormat::Json);
+    }
+
+    fn latency(queue_ms: u64, first_chunk_ms: Option<u64>, total_ms: u64) -> TurnLatency {
+        TurnLatency {
+            turn: 0,
+            queue_ms,
+            first_chunk_ms,
+            total_ms,
+            finished_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_summarize_latency() {
+        let latencies = [
+            latency(100, Some(800), 4_000),
+            latency(300, Some(1_200), 6_000),
+            latency(200, None, 500),
+        ];
+        assert_eq!(
+            summarize_latency(&latencies),
+            LatencySummary {
+                turns: 3,
+                queue_ms: 200,
+                first_chunk_ms: Some(1_000),
+                total_ms: 3_500,
+            }
+        );
+        assert_eq!(summarize_latency(&[]), LatencySummary::default());
+    }
+
+    #[test]
+    fn test_summarize_activity() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let mut task = WorkspaceTask::new("ws".into(), "a".into(), "claude".into(), "code".into());
+        task.created_at = today.and_hms_opt(9, 0, 0).unwrap().and_utc();
+        let mut old = task.clone();
+        old.created_at = today.and_hms_opt(9, 0, 0).unwrap().and_utc() - chrono::Days::new(30);
+        let mut other = task.clone();
+        other.agent_name = "codex".into();
+
+        let metrics = session_metrics(&[
+            edit("1", "src/a.rs", "2026-03-01T10:00:00Z"),
+            edit("2", "src/a.rs", "2026-03-02T10:00:00Z"),
+        ]);
+        let tasks = vec![
+            (task.clone(), metrics),
+            (task, SessionMetrics::default()),
+            (other, SessionMetrics::default()),
+            (old, SessionMetrics::default()),
+        ];
+
+        let activity = summarize_activity(&tasks, today, 7);
+        assert_eq!(activity.days.len(), 7);
+        assert_eq!(activity.sessions, 3);
+        assert_eq!(activity.tool_calls, 2);
+        assert_eq!(activity.files_modified, 1);
+        assert_eq!(activity.days[6].sessions, 3);
+        assert_eq!(activity.days[5].tool_calls, 1);
+        assert_eq!(activity.max_sessions_per_day(), 3);
+        assert_eq!(activity.top_agents[0], ("claude".to_string(), 2));
+    }
+}
@@ -6,13 +6,89 @@
 mod agent_config_service;
 mod agent_service;
 mod ai_service;
+mod audit_service;
+mod chat_bridge_service;
+mod direct_chat;
+mod editor_bridge_service;
+mod error;
+mod http_server;
+mod import_service;
+mod instruction_files;
+mod maintenance_service;
+mod mcp_service;
 mod message_service;
+mod metrics_service;
+mod model_router;
+mod network_simulation;
+mod output_guard;
 mod persistence_service;
+mod profile_service;
+mod repo_map;
+mod retry_policy;
+mod search_service;
+mod semantic_index;
+mod session_export;
+mod session_limiter;
+mod share_service;
+mod symbol_index;
+mod sync_service;
+mod warm_pool;
+mod webhook_service;
 mod workspace_service;
 
 pub use agent_config_service::AgentConfigService;
-pub use agent_service::{AgentService, AgentSessionInfo, SessionStatus};
-pub use ai_service::{AiService, CommentStyle};
-pub use message_service::MessageService;
-pub use persistence_service::PersistenceService;
+pub use agent_service::{
+    AgentIdentity, AgentService, AgentSessionInfo, SessionChoices, SessionStatus,
+};
+pub use ai_service::{
+    AiService, ChatMessage, ChatOptions, CommentStyle, CommitMessageOptions, discover_ollama_models,
+};
+pub use audit_service::{
+    AuditAction, AuditEntry, AuditFilter, AuditKind, AuditService, DecisionSource, render_entries,
+};
+pub use chat_bridge_service::{BridgeCommand, ChatBridgeService};
+pub use direct_chat::{DirectChatService, MODEL_AGENT_PREFIX, model_for_agent};
+pub use editor_bridge_service::{
+    DEFAULT_EDITOR_BRIDGE_PORT, EditorBridgeInfo, EditorBridgeService, EditorRequest, forward_link,
+};
+pub use error::{ServiceError, ServiceResult};
+pub use import_service::{
+    ImportFile, ImportFormat, ImportOptions, ImportReport, ImportService, ImportedContent,
+    ImportedConversation, ImportedMessage, collect_files,
+};
+pub use instruction_files::{find_instruction_files, instruction_blocks};
+pub use maintenance_service::{DuplicateGroup, MaintenanceService, find_duplicates, first_prompt};
+pub use mcp_service::{
+    McpCatalog, McpEntry, McpResourceText, McpService, parse_tool_arguments,
+};
+pub use message_service::{LiveTurn, MessageService};
+pub use metrics_service::{
+    DayActivity, ExportFormat, FeedbackStats, LatencySummary, MetricsService, SessionMetrics,
+    StatsExport, TaskStats, WorkspaceActivity, session_metrics, summarize_activity,
+    summarize_latency,
+};
+pub use model_router::{ModelPurpose, ModelRouter};
+pub use network_simulation::NetworkSimulation;
+pub use output_guard::OutputGuard;
+pub use persistence_service::{
+    HistoryEntry, PersistedMessage, PersistenceService, SessionComment, TurnLatency,
+    UnsupportedUpdate,
+};
+pub use profile_service::{DEFAULT_PROFILE, Profile, ProfileService};
+pub use repo_map::{DEFAULT_REPO_MAP_CHARS, generate_repo_map, repo_map_block};
+pub use retry_policy::{RetryPolicy, is_transient_error};
+pub use search_service::{
+    SearchHit, SearchHitKind, SearchQuery, SearchService, SessionIndex, SessionInfo,
+    search_sessions,
+};
+pub use semantic_index::SemanticIndex;
+pub use session_export::{
+    SessionExportFormat, SessionTranscript, TranscriptItem, TranscriptPlanEntry,
+};
+pub use session_limiter::{SessionLimiter, SessionSlot};
+pub use share_service::{DEFAULT_SHARE_PORT, ShareLink, ShareService};
+pub use symbol_index::{Symbol, SymbolIndex, SymbolKind, parse_symbols, symbol_mentions};
+pub use sync_service::{SyncBackend, SyncReport, SyncService};
+pub use warm_pool::{WarmPool, WarmSession, pick_warm_agents};
+pub use webhook_service::{WebhookEvent, WebhookService};
 pub use workspace_service::WorkspaceService;
@@ -0,0 +1,118 @@
+//! Model Router - Picks the model that serves each kind of AI request
+//!
+//! Code assistance (comments, explanations) runs on the user's default model.
+//! Internal features such as session titles, summaries and commit messages run
+//! on the "utility model", usually a smaller and cheaper one, and fall back to
+//! the configured fallbacks and then the default model when it fails.
+
+use std::collections::HashMap;
+
+use crate::core::config::{ModelConfig, UtilityModelConfig};
+
+/// What a request is for, which decides the models it is routed to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModelPurpose {
+    /// Code assistance the user asked for directly
+    Assist,
+    /// Internal features: titles, summaries, commit messages
+    Utility,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ModelRouter {
+    utility: UtilityModelConfig,
+}
+
+impl ModelRouter {
+    pub fn new(utility: UtilityModelConfig) -> Self {
+        Self { utility }
+    }
+
+    pub fn utility(&self) -> &UtilityModelConfig {
+        &self.utility
+    }
+
+    pub fn utility_model(&self) -> Option<&str> {
+        self.utility.model.as_deref()
+    }
+
+    /// Models to try for `purpose`, best first
+    ///
+    /// Only enabled entries of `models` are returned, each once. Utility
+    /// requests try the utility model, its fallbacks, then the default model.
+    pub fn candidates(
+        &self,
+        purpose: ModelPurpose,
+        models: &HashMap<String, ModelConfig>,
+        default_model: Option<&str>,
+    ) -> Vec<String> {
+        let preferred: Vec<&str> = match purpose {
+            ModelPurpose::Assist => default_model.into_iter().collect(),
+            ModelPurpose::Utility => self
+                .utility
+                .model
+                .as_deref()
+                .into_iter()
+                .chain(self.utility.fallbacks.iter().map(String::as_str))
+                .chain(default_model)
+                .collect(),
+        };
+
+        let mut candidates: Vec<String> = Vec::new();
+        for name in preferred {
+            let enabled = models.get(name).is_some_and(|config| config.enabled);
+            if enabled && !candidates.iter().any(|candidate| candidate == name) {
+                candidates.push(name.to_string());
+            }
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(enabled: bool) -> ModelConfig {
+        ModelConfig {
+            enabled,
+            provider: "openai".to_string(),
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: "key".to_string(),
+            model_name: "m".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_utility_candidates_fall_back_to_default() {
+        let models = HashMap::from([
+            ("mini".to_string(), model(true)),
+            ("local".to_string(), model(false)),
+            ("big".to_string(), model(true)),
+        ]);
+        let router = ModelRouter::new(UtilityModelConfig {
+            model: Some("mini".to_string()),
+            fallbacks: vec!["local".to_string(), "missing".to_string(), "big".to_string()],
+        });
+
+        assert_eq!(
+            router.candidates(ModelPurpose::Utility, &models, Some("big")),
+            vec!["mini", "big"]
+        );
+        assert_eq!(
+            router.candidates(ModelPurpose::Assist, &models, Some("big")),
+            vec!["big"]
+        );
+    }
+
+    #[test]
+    fn test_unset_utility_model_uses_default() {
+        let models = HashMap::from([("big".to_string(), model(true))]);
+        let router = ModelRouter::default();
+        assert_eq!(
+            router.candidates(ModelPurpose::Utility, &models, Some("big")),
+            vec!["big"]
+        );
+        assert!(router.candidates(ModelPurpose::Utility, &models, None).is_empty());
+    }
+}
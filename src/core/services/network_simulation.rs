@@ -0,0 +1,174 @@
+//! Simulated slow network between the MessageService and the UI
+//!
+//! A developer setting for checking how the conversation view streams when
+//! an agent or its connection is slow. Session updates reach subscribers
+//! after a fixed latency plus random jitter, and can be re-batched: the agent
+//! message and thought chunks that queued up during a delay arrive merged
+//! into one, the way a congested connection delivers them.
+
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use agent_client_protocol::{ContentBlock, ContentChunk, SessionUpdate};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+use crate::core::event_bus::session_bus::SessionUpdateEvent;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NetworkSimulation {
+    pub enabled: bool,
+    /// Delay added to every update
+    pub latency: Duration,
+    /// Up to this much more delay, chosen at random per update
+    pub jitter: Duration,
+    /// Merge the chunks that queued up during a delay
+    pub rebatch: bool,
+}
+
+impl NetworkSimulation {
+    /// Delay of one update, `unit` being a random number from 0 to 1
+    pub fn delay(&self, unit: f64) -> Duration {
+        self.latency + self.jitter.mul_f64(unit.clamp(0., 1.))
+    }
+}
+
+/// Random number from 0 to 1, good enough for jitter
+fn random_unit() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+    // Every `RandomState` is seeded differently
+    let hash = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Merge consecutive agent message or thought text chunks of one session
+pub fn rebatch(events: Vec<SessionUpdateEvent>) -> Vec<SessionUpdateEvent> {
+    let mut batched: Vec<SessionUpdateEvent> = Vec::with_capacity(events.len());
+    for event in events {
+        if let Some(last) = batched.last_mut()
+            && last.session_id == event.session_id
+            && let Some(merged) = merge_chunks(&last.update, &event.update)
+        {
+            last.update = Arc::new(merged);
+            continue;
+        }
+        batched.push(event);
+    }
+    batched
+}
+
+fn merge_chunks(first: &SessionUpdate, second: &SessionUpdate) -> Option<SessionUpdate> {
+    let text = |chunk: &ContentChunk| match &chunk.content {
+        ContentBlock::Text(text) => Some(text.text.clone()),
+        _ => None,
+    };
+    let joined = |a: &ContentChunk, b: &ContentChunk| {
+        Some(ContentChunk::new(ContentBlock::from(text(a)? + &text(b)?)))
+    };
+    match (first, second) {
+        (SessionUpdate::AgentMessageChunk(a), SessionUpdate::AgentMessageChunk(b)) => {
+            joined(a, b).map(SessionUpdate::AgentMessageChunk)
+        }
+        (SessionUpdate::AgentThoughtChunk(a), SessionUpdate::AgentThoughtChunk(b)) => {
+            joined(a, b).map(SessionUpdate::AgentThoughtChunk)
+        }
+        _ => None,
+    }
+}
+
+/// Pass the updates of `events`, stamped with the time they were published,
+/// to `tx` as `simulation` currently says; runs until `tx` is closed
+pub async fn relay(
+    simulation: Arc<RwLock<NetworkSimulation>>,
+    mut events: UnboundedReceiver<(Instant, SessionUpdateEvent)>,
+    tx: UnboundedSender<SessionUpdateEvent>,
+) {
+    while let Some((published, event)) = events.recv().await {
+        let simulation = *simulation.read().unwrap();
+        let mut batch = vec![event];
+        if simulation.enabled {
+            // Updates keep their order, so a short delay waits for a long one
+            smol::Timer::at(published + simulation.delay(random_unit())).await;
+            if simulation.rebatch {
+                while let Ok((_, event)) = events.try_recv() {
+                    batch.push(event);
+                }
+                batch = rebatch(batch);
+            }
+        }
+        for event in batch {
+            if tx.send(event).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(session_id: &str, update: SessionUpdate) -> SessionUpdateEvent {
+        SessionUpdateEvent {
+            session_id: session_id.to_string(),
+            agent_name: None,
+            update: Arc::new(update),
+        }
+    }
+
+    fn message(text: &str) -> SessionUpdate {
+        SessionUpdate::AgentMessageChunk(ContentChunk::new(ContentBlock::from(text.to_string())))
+    }
+
+    fn thought(text: &str) -> SessionUpdate {
+        SessionUpdate::AgentThoughtChunk(ContentChunk::new(ContentBlock::from(text.to_string())))
+    }
+
+    fn text(event: &SessionUpdateEvent) -> &str {
+        match event.update.as_ref() {
+            SessionUpdate::AgentMessageChunk(chunk) | SessionUpdate::AgentThoughtChunk(chunk) => {
+                match &chunk.content {
+                    ContentBlock::Text(text) => &text.text,
+                    _ => "",
+                }
+            }
+            _ => "",
+        }
+    }
+
+    #[test]
+    fn test_delay() {
+        let simulation = NetworkSimulation {
+            enabled: true,
+            latency: Duration::from_millis(200),
+            jitter: Duration::from_millis(100),
+            rebatch: false,
+        };
+        assert_eq!(simulation.delay(0.), Duration::from_millis(200));
+        assert_eq!(simulation.delay(0.5), Duration::from_millis(250));
+        assert_eq!(simulation.delay(7.), Duration::from_millis(300));
+        assert!((0. ..1.).contains(&random_unit()));
+    }
+
+    #[test]
+    fn test_rebatch() {
+        let batched = rebatch(vec![
+            event("a", thought("Let me ")),
+            event("a", thought("look")),
+            event("a", message("Hello")),
+            event("a", message(", world")),
+            event("b", message("!")),
+            event(
+                "a",
+                SessionUpdate::UserMessageChunk(ContentChunk::new(ContentBlock::from(
+                    "?".to_string(),
+                ))),
+            ),
+            event("a", message("Done")),
+        ]);
+        let texts: Vec<&str> = batched.iter().map(text).collect();
+        assert_eq!(texts, vec!["Let me look", "Hello, world", "!", "", "Done"]);
+        assert_eq!(batched[2].session_id, "b");
+    }
+}
@@ -0,0 +1,136 @@
+//! Output Guard - Caps how much an agent may stream in a single turn
+//!
+//! An agent stuck in a generation loop keeps emitting message/thought chunks
+//! until someone notices. The guard counts the characters streamed for each
+//! session's current turn and reports the moment a turn crosses its limit, so
+//! the caller can cancel it. Limits default to `output_guard` in config.json and
+//! can be raised per session (e.g. when the user resumes a cancelled turn).
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+#[derive(Default)]
+struct GuardState {
+    /// Per-session overrides of the default limit
+    limits: HashMap<String, usize>,
+    /// Characters streamed in the current turn
+    streamed: HashMap<String, usize>,
+}
+
+#[derive(Clone, Default)]
+pub struct OutputGuard {
+    /// Default per-turn limit in characters (0 disables the guard)
+    default_limit: usize,
+    state: Arc<Mutex<GuardState>>,
+}
+
+impl OutputGuard {
+    pub fn new(default_limit: usize) -> Self {
+        Self {
+            default_limit,
+            state: Arc::default(),
+        }
+    }
+
+    /// Limit applied to the session's turns (0 = unlimited)
+    pub fn limit_for(&self, session_id: &str) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .limits
+            .get(session_id)
+            .copied()
+            .unwrap_or(self.default_limit)
+    }
+
+    /// Override the limit for one session (`None` restores the default)
+    pub fn set_session_limit(&self, session_id: &str, limit: Option<usize>) {
+        let mut state = self.state.lock().unwrap();
+        match limit {
+            Some(limit) => state.limits.insert(session_id.to_string(), limit),
+            None => state.limits.remove(session_id),
+        };
+    }
+
+    /// Double the session's limit and return the new value
+    pub fn raise_session_limit(&self, session_id: &str) -> usize {
+        let limit = self.limit_for(session_id).saturating_mul(2);
+        self.set_session_limit(session_id, Some(limit));
+        limit
+    }
+
+    /// Start counting a new turn for the session
+    pub fn begin_turn(&self, session_id: &str) {
+        self.state.lock().unwrap().streamed.remove(session_id);
+    }
+
+    /// Count streamed characters; returns the limit when this chunk crosses it
+    ///
+    /// Only the chunk that crosses the limit reports it, so the caller cancels
+    /// the turn once even though chunks keep arriving until the agent stops.
+    /// Characters are counted even without a limit, for `streamed`.
+    pub fn record(&self, session_id: &str, chars: usize) -> Option<usize> {
+        if chars == 0 {
+            return None;
+        }
+        let limit = self.limit_for(session_id);
+        let mut state = self.state.lock().unwrap();
+        let streamed = state.streamed.entry(session_id.to_string()).or_default();
+        let before = *streamed;
+        *streamed += chars;
+        (limit > 0 && before <= limit && *streamed > limit).then_some(limit)
+    }
+
+    /// Characters streamed in the session's current turn
+    pub fn streamed(&self, session_id: &str) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .streamed
+            .get(session_id)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Forget everything about a closed session
+    pub fn remove_session(&self, session_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.limits.remove(session_id);
+        state.streamed.remove(session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_reports_crossing_once_per_turn() {
+        let guard = OutputGuard::new(10);
+        assert_eq!(guard.record("s1", 6), None);
+        assert_eq!(guard.record("s1", 6), Some(10));
+        assert_eq!(guard.record("s1", 6), None);
+        // Other sessions have their own budget
+        assert_eq!(guard.record("s2", 6), None);
+
+        guard.begin_turn("s1");
+        assert_eq!(guard.record("s1", 10), None);
+        assert_eq!(guard.record("s1", 1), Some(10));
+    }
+
+    #[test]
+    fn test_session_limits() {
+        let guard = OutputGuard::new(0);
+        assert_eq!(guard.record("s1", 1_000_000), None);
+        assert_eq!(guard.streamed("s1"), 1_000_000);
+
+        guard.set_session_limit("s1", Some(100));
+        assert_eq!(guard.raise_session_limit("s1"), 200);
+        assert_eq!(guard.limit_for("s1"), 200);
+
+        guard.set_session_limit("s1", None);
+        assert_eq!(guard.limit_for("s1"), 0);
+    }
+}
@@ -2,18 +2,33 @@
 //!
 //! This service saves session updates to disk in JSONL format (one JSON object per line)
 //! and loads historical messages when needed.
+//!
+//! Sessions are stored in the base directory unless they were given a
+//! directory of their own with [`PersistenceService::set_session_dir`], e.g.
+//! inside the repository of a workspace that keeps its sessions there.
+//!
+//! Comments left on a session's messages are kept next to its history, in
+//! `<session_id>.comments.json`, and the latency of its turns in
+//! `<session_id>.latency.json`.
+//!
+//! Session files are only appended to or copied whole, so lines with updates
+//! this version cannot read, e.g. ones written by a newer version, are kept.
+//! [`PersistenceService::load_history`] returns them as
+//! [`UnsupportedUpdate`]s; [`PersistenceService::load_messages`] skips them.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock, TryLockError};
 
 use agent_client_protocol::{
     ContentBlock, ContentChunk, SessionUpdate, TextContent, ToolCallStatus, ToolCallUpdate,
 };
-use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::error::{ServiceError, ServiceResult};
+use super::session_export::{SessionExportFormat, SessionTranscript};
+
 /// Persisted message entry with timestamp
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistedMessage {
@@ -36,6 +51,108 @@ impl PersistedMessage {
     }
 }
 
+/// A session update of a kind this version does not render or cannot read,
+/// kept as JSON
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsupportedUpdate {
+    /// The `sessionUpdate` tag, e.g. `usage_update`
+    pub kind: String,
+    pub raw: serde_json::Value,
+}
+
+impl UnsupportedUpdate {
+    pub fn from_raw(raw: serde_json::Value) -> Self {
+        let kind = raw
+            .get("sessionUpdate")
+            .and_then(|kind| kind.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        Self { kind, raw }
+    }
+
+    pub fn from_update(update: &SessionUpdate) -> Self {
+        Self::from_raw(serde_json::to_value(update).unwrap_or_default())
+    }
+}
+
+/// A line of a session's history
+#[derive(Debug, Clone)]
+pub enum HistoryEntry {
+    Message(PersistedMessage),
+    /// An update this version cannot read, with its timestamp
+    Unsupported(String, UnsupportedUpdate),
+}
+
+impl HistoryEntry {
+    pub fn timestamp(&self) -> &str {
+        match self {
+            HistoryEntry::Message(message) => &message.timestamp,
+            HistoryEntry::Unsupported(timestamp, _) => timestamp,
+        }
+    }
+}
+
+/// Parse a line of a session file; `None` if it is not a history entry at all
+fn parse_history_line(line: &str) -> Option<HistoryEntry> {
+    #[derive(Deserialize)]
+    struct RawMessage {
+        timestamp: String,
+        update: serde_json::Value,
+    }
+
+    match serde_json::from_str::<PersistedMessage>(line) {
+        Ok(message) => Some(HistoryEntry::Message(message)),
+        Err(e) => {
+            let raw: RawMessage = serde_json::from_str(line).ok()?;
+            log::debug!("Keeping unsupported update in session file: {}", e);
+            Some(HistoryEntry::Unsupported(
+                raw.timestamp,
+                UnsupportedUpdate::from_raw(raw.update),
+            ))
+        }
+    }
+}
+
+/// A comment left on a message of a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionComment {
+    pub id: String,
+    /// Position of the message among the session's user and agent messages,
+    /// consecutive chunks of one kind counting as one message
+    pub message_index: usize,
+    pub author: String,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionComment {
+    pub fn new(message_index: usize, author: &str, text: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            message_index,
+            author: author.to_string(),
+            text: text.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// How long a turn of a session took, measured by `MessageService`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnLatency {
+    /// Position of the turn's prompt among the session's user messages
+    pub turn: usize,
+    /// Time from sending the message until the prompt went to the agent,
+    /// waiting for a free slot or for the session to load
+    pub queue_ms: u64,
+    /// Time from the prompt going to the agent until its first update;
+    /// `None` when the agent sent nothing
+    pub first_chunk_ms: Option<u64>,
+    /// Time from sending the message to the end of the turn
+    pub total_ms: u64,
+    pub finished_at: DateTime<Utc>,
+}
+
 /// Type of chunk being accumulated
 #[derive(Debug, Clone, PartialEq)]
 enum AccumulatedChunkType {
@@ -75,6 +192,12 @@ impl ChunkAccumulator {
         }
     }
 
+    /// Whether chunks or tool call updates are waiting to be written
+    fn has_pending(&self) -> bool {
+        !matches!(self.chunk_type, AccumulatedChunkType::Empty)
+            || !self.tool_call_updates.is_empty()
+    }
+
     /// Try to append an AgentMessageChunk
     /// Returns Some(FlushData) if type change requires flush, None if accumulated
     fn try_append_agent_message_chunk(&mut self, chunk: ContentChunk) -> Option<FlushData> {
@@ -338,12 +461,52 @@ fn extract_text_from_content_chunk(chunk: &ContentChunk) -> String {
     }
 }
 
+/// Append `message` as a JSON line to the session file at `file_path`
+fn append_message(
+    session_dir: &Path,
+    file_path: &Path,
+    message: &PersistedMessage,
+) -> ServiceResult<()> {
+    // Ensure directory exists
+    if !session_dir.exists() {
+        std::fs::create_dir_all(session_dir)
+            .map_err(|e| ServiceError::io("Failed to create session directory", e))?;
+    }
+
+    // Serialize to JSON and append newline
+    let json = serde_json::to_string(message)
+        .map_err(|e| ServiceError::serialization("Failed to serialize message", e))?;
+
+    // Open file in append mode
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)
+        .map_err(|e| ServiceError::io("Failed to open session file", e))?;
+
+    // Write JSON line
+    write!(file, "{}\n", json).map_err(|e| ServiceError::io("Failed to write message", e))?;
+
+    log::debug!(
+        "Wrote merged message to session file: {}",
+        file_path.display()
+    );
+    Ok(())
+}
+
 /// Message persistence service
 pub struct PersistenceService {
     /// Base directory for session files
     base_dir: PathBuf,
     /// Thread-safe storage for chunk accumulators per session
     accumulators: Arc<Mutex<HashMap<String, ChunkAccumulator>>>,
+    /// Sessions stored outside the base directory
+    session_dirs: RwLock<HashMap<String, PathBuf>>,
+    /// Held while a comments or latency file is rewritten
+    comments_lock: Arc<Mutex<()>>,
 }
 
 impl PersistenceService {
@@ -355,18 +518,160 @@ impl PersistenceService {
         Self {
             base_dir,
             accumulators: Arc::new(Mutex::new(HashMap::new())),
+            session_dirs: RwLock::new(HashMap::new()),
+            comments_lock: Arc::new(Mutex::new(())),
         }
     }
 
+    /// Directory holding a session's file
+    fn session_dir(&self, session_id: &str) -> PathBuf {
+        self.session_dirs
+            .read()
+            .unwrap()
+            .get(session_id)
+            .cloned()
+            .unwrap_or_else(|| self.base_dir.clone())
+    }
+
     /// Get the file path for a session
     fn session_file_path(&self, session_id: &str) -> PathBuf {
-        self.base_dir.join(format!("{}.jsonl", session_id))
+        self.session_dir(session_id).join(format!("{}.jsonl", session_id))
+    }
+
+    /// Get the path of a session's comments, next to its history
+    fn comments_file_path(&self, session_id: &str) -> PathBuf {
+        self.session_dir(session_id)
+            .join(format!("{}.comments.json", session_id))
+    }
+
+    /// Get the path of a session's turn latencies, next to its history
+    fn latency_file_path(&self, session_id: &str) -> PathBuf {
+        self.session_dir(session_id)
+            .join(format!("{}.latency.json", session_id))
+    }
+
+    /// Store a session in `dir` instead of the base directory; `None` moves
+    /// it back. Existing files are not moved, see [`Self::relocate_session`].
+    pub fn set_session_dir(&self, session_id: &str, dir: Option<PathBuf>) {
+        let mut session_dirs = self.session_dirs.write().unwrap();
+        match dir {
+            Some(dir) => session_dirs.insert(session_id.to_string(), dir),
+            None => session_dirs.remove(session_id),
+        };
+    }
+
+    /// Move a session's file to `dir` (the base directory for `None`) and
+    /// store it there from now on
+    ///
+    /// Flushes pending chunks first. An existing file at the destination is
+    /// kept and the move is skipped.
+    pub async fn relocate_session(
+        &self,
+        session_id: &str,
+        dir: Option<PathBuf>,
+    ) -> ServiceResult<()> {
+        self.flush_session(session_id).await?;
+        let from = [
+            self.session_file_path(session_id),
+            self.comments_file_path(session_id),
+            self.latency_file_path(session_id),
+        ];
+        self.set_session_dir(session_id, dir);
+        let to = [
+            self.session_file_path(session_id),
+            self.comments_file_path(session_id),
+            self.latency_file_path(session_id),
+        ];
+        if from == to {
+            return Ok(());
+        }
+
+        smol::unblock(move || {
+            for (from, to) in from.iter().zip(&to) {
+                if !from.exists() || to.exists() {
+                    continue;
+                }
+                if let Some(parent) = to.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| ServiceError::io("Failed to create session directory", e))?;
+                }
+                // Copy and delete, as the repository may be on another file system
+                if std::fs::rename(from, to).is_err() {
+                    std::fs::copy(from, to)
+                        .map_err(|e| ServiceError::io("Failed to copy session file", e))?;
+                    std::fs::remove_file(from)
+                        .map_err(|e| ServiceError::io("Failed to remove moved session file", e))?;
+                }
+                log::info!("Moved session file to {}", to.display());
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Put the history of `from` in front of that of `to`, for a session that
+    /// continues another one
+    ///
+    /// The history of `from` is kept.
+    pub async fn continue_session(&self, from: &str, to: &str) -> ServiceResult<()> {
+        self.flush_session(from).await?;
+        self.flush_session(to).await?;
+        let from = self.session_file_path(from);
+        let to = self.session_file_path(to);
+
+        smol::unblock(move || {
+            if !from.exists() {
+                return Ok(());
+            }
+            if let Some(parent) = to.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| ServiceError::io("Failed to create session directory", e))?;
+            }
+            let mut history = std::fs::read(&from)
+                .map_err(|e| ServiceError::io("Failed to read session file", e))?;
+            if to.exists() {
+                let continued = std::fs::read(&to)
+                    .map_err(|e| ServiceError::io("Failed to read session file", e))?;
+                history.extend(continued);
+            }
+            std::fs::write(&to, history)
+                .map_err(|e| ServiceError::io("Failed to write session file", e))?;
+            log::info!("Continued {} in {}", from.display(), to.display());
+            Ok(())
+        })
+        .await
+    }
+
+    /// Write the whole history of a session that has no file yet, e.g. one
+    /// imported from another tool
+    pub async fn write_session(
+        &self,
+        session_id: &str,
+        messages: Vec<PersistedMessage>,
+    ) -> ServiceResult<()> {
+        let session_dir = self.session_dir(session_id);
+        let file_path = self.session_file_path(session_id);
+
+        smol::unblock(move || {
+            if file_path.exists() {
+                return Err(ServiceError::InvalidInput(format!(
+                    "Session file already exists: {}",
+                    file_path.display()
+                )));
+            }
+            for message in &messages {
+                append_message(&session_dir, &file_path, message)?;
+            }
+            Ok(())
+        })
+        .await
     }
 
     /// Ensure the base directory exists
-    fn ensure_base_dir_sync(&self) -> Result<()> {
+    fn ensure_base_dir_sync(&self) -> ServiceResult<()> {
         if !self.base_dir.exists() {
-            std::fs::create_dir_all(&self.base_dir).context("Failed to create base directory")?;
+            std::fs::create_dir_all(&self.base_dir)
+                .map_err(|e| ServiceError::io("Failed to create base directory", e))?;
         }
         Ok(())
     }
@@ -375,7 +680,11 @@ impl PersistenceService {
     ///
     /// Accumulates chunk updates and tool_call_updates in memory and flushes when needed.
     /// Non-chunk updates trigger immediate flush and write.
-    pub async fn save_update(&self, session_id: &str, update: SessionUpdate) -> Result<()> {
+    pub async fn save_update(
+        &self,
+        session_id: &str,
+        update: SessionUpdate,
+    ) -> ServiceResult<()> {
         let flush_data = {
             let mut accumulators = self.accumulators.lock().unwrap();
             let accumulator = accumulators
@@ -424,7 +733,7 @@ impl PersistenceService {
     }
 
     /// Write flush data to disk
-    async fn write_flush_data(&self, session_id: &str, data: FlushData) -> Result<()> {
+    async fn write_flush_data(&self, session_id: &str, data: FlushData) -> ServiceResult<()> {
         match data {
             FlushData::Accumulated(boxed_data) => {
                 if let Some((timestamp, update)) = *boxed_data {
@@ -464,40 +773,12 @@ impl PersistenceService {
         session_id: &str,
         update: SessionUpdate,
         timestamp: String,
-    ) -> Result<()> {
-        let file_path = self.session_file_path(session_id);
-        let base_dir = self.base_dir.clone();
+    ) -> ServiceResult<()> {
+        let session_dir = self.session_dir(session_id);
+        let file_path = session_dir.join(format!("{}.jsonl", session_id));
         let message = PersistedMessage::with_timestamp(timestamp, update);
 
-        smol::unblock(move || {
-            // Ensure directory exists
-            if !base_dir.exists() {
-                std::fs::create_dir_all(&base_dir).context("Failed to create base directory")?;
-            }
-
-            // Serialize to JSON and append newline
-            let json = serde_json::to_string(&message).context("Failed to serialize message")?;
-
-            // Open file in append mode
-            use std::fs::OpenOptions;
-            use std::io::Write;
-
-            let mut file = OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&file_path)
-                .context("Failed to open session file")?;
-
-            // Write JSON line
-            write!(file, "{}\n", json).context("Failed to write message")?;
-
-            log::debug!(
-                "Wrote merged message to session file: {}",
-                file_path.display()
-            );
-            Ok(())
-        })
-        .await
+        smol::unblock(move || append_message(&session_dir, &file_path, &message)).await
     }
 
     /// Write update with current timestamp
@@ -505,16 +786,67 @@ impl PersistenceService {
         &self,
         session_id: &str,
         update: SessionUpdate,
-    ) -> Result<()> {
+    ) -> ServiceResult<()> {
         let timestamp = Utc::now().to_rfc3339();
         self.write_with_timestamp(session_id, update, timestamp)
             .await
     }
 
+    /// Write the pending chunks and tool_call_updates of every session right
+    /// away, on the calling thread
+    ///
+    /// For the panic hook: it cannot wait for the executor, and skips the
+    /// accumulators when another thread holds them. Returns the number of
+    /// sessions written.
+    pub fn flush_all_blocking(&self) -> usize {
+        let pending: Vec<(String, Vec<(String, SessionUpdate)>)> = {
+            let mut accumulators = match self.accumulators.try_lock() {
+                Ok(accumulators) => accumulators,
+                Err(TryLockError::Poisoned(e)) => e.into_inner(),
+                Err(TryLockError::WouldBlock) => return 0,
+            };
+            accumulators
+                .iter_mut()
+                .map(|(session_id, acc)| {
+                    let mut updates: Vec<_> = acc.flush().into_iter().collect();
+                    updates.extend(acc.flush_tool_call_updates());
+                    (session_id.clone(), updates)
+                })
+                .filter(|(_, updates)| !updates.is_empty())
+                .collect()
+        };
+
+        for (session_id, updates) in &pending {
+            let session_dir = self.session_dir(session_id);
+            let file_path = session_dir.join(format!("{}.jsonl", session_id));
+            for (timestamp, update) in updates {
+                let message = PersistedMessage::with_timestamp(timestamp.clone(), update.clone());
+                if let Err(e) = append_message(&session_dir, &file_path, &message) {
+                    log::error!("Failed to flush session {}: {}", session_id, e);
+                }
+            }
+        }
+        pending.len()
+    }
+
+    /// Number of sessions with updates not yet written to disk
+    pub fn pending_sessions(&self) -> usize {
+        let accumulators = self.accumulators.lock().unwrap();
+        accumulators.values().filter(|acc| acc.has_pending()).count()
+    }
+
+    /// Whether updates of `session_id` are waiting to be written
+    pub fn has_pending_updates(&self, session_id: &str) -> bool {
+        let accumulators = self.accumulators.lock().unwrap();
+        accumulators
+            .get(session_id)
+            .is_some_and(|acc| acc.has_pending())
+    }
+
     /// Flush accumulated chunks and tool_call_updates for a specific session
     ///
     /// This should be called when a session completes or becomes idle
-    pub async fn flush_session(&self, session_id: &str) -> Result<()> {
+    pub async fn flush_session(&self, session_id: &str) -> ServiceResult<()> {
         let (chunk_flush_data, tool_call_updates) = {
             let mut accumulators = self.accumulators.lock().unwrap();
             if let Some(acc) = accumulators.get_mut(session_id) {
@@ -558,8 +890,21 @@ impl PersistenceService {
 
     /// Load all messages for a session
     ///
-    /// Returns messages in chronological order
-    pub async fn load_messages(&self, session_id: &str) -> Result<Vec<PersistedMessage>> {
+    /// Returns messages in chronological order, without the updates this
+    /// version cannot read
+    pub async fn load_messages(&self, session_id: &str) -> ServiceResult<Vec<PersistedMessage>> {
+        let history = self.load_history(session_id).await?;
+        Ok(history
+            .into_iter()
+            .filter_map(|entry| match entry {
+                HistoryEntry::Message(message) => Some(message),
+                HistoryEntry::Unsupported(..) => None,
+            })
+            .collect())
+    }
+
+    /// Load the whole history of a session, in chronological order
+    pub async fn load_history(&self, session_id: &str) -> ServiceResult<Vec<HistoryEntry>> {
         let file_path = self.session_file_path(session_id);
         let session_id = session_id.to_string(); // Clone for the closure
 
@@ -574,7 +919,8 @@ impl PersistenceService {
             use std::fs::File;
             use std::io::{BufRead, BufReader};
 
-            let file = File::open(&file_path).context("Failed to open session file")?;
+            let file = File::open(&file_path)
+                .map_err(|e| ServiceError::io("Failed to open session file", e))?;
 
             let reader = BufReader::new(file);
             let mut messages = Vec::new();
@@ -585,10 +931,10 @@ impl PersistenceService {
                     continue;
                 }
 
-                match serde_json::from_str::<PersistedMessage>(&line) {
-                    Ok(message) => messages.push(message),
-                    Err(e) => {
-                        log::warn!("Failed to parse line in session file: {}", e);
+                match parse_history_line(&line) {
+                    Some(entry) => messages.push(entry),
+                    None => {
+                        log::warn!("Failed to parse line in session file: {}", line);
                         // Continue reading other lines
                     }
                 }
@@ -604,10 +950,88 @@ impl PersistenceService {
         .await
     }
 
+    /// Export the whole transcript of a session, titled `title`, as Markdown
+    /// or JSON
+    ///
+    /// Updates still being accumulated are flushed first, so a running
+    /// session is exported up to its latest chunk.
+    pub async fn export_session(
+        &self,
+        session_id: &str,
+        title: &str,
+        format: SessionExportFormat,
+    ) -> ServiceResult<String> {
+        self.flush_session(session_id).await?;
+        let messages = self.load_messages(session_id).await?;
+        SessionTranscript::from_history(session_id, title, &messages).render(format)
+    }
+
+    /// Load the comments left on a session's messages, oldest first
+    pub async fn load_comments(&self, session_id: &str) -> ServiceResult<Vec<SessionComment>> {
+        let file_path = self.comments_file_path(session_id);
+        smol::unblock(move || read_list(&file_path)).await
+    }
+
+    /// Add a comment to a session
+    pub async fn add_comment(
+        &self,
+        session_id: &str,
+        comment: SessionComment,
+    ) -> ServiceResult<()> {
+        let file_path = self.comments_file_path(session_id);
+        let comments_lock = self.comments_lock.clone();
+        smol::unblock(move || {
+            let _guard = comments_lock.lock().unwrap();
+            let mut comments: Vec<SessionComment> = read_list(&file_path)?;
+            comments.push(comment);
+            if let Some(parent) = file_path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| ServiceError::io("Failed to create session directory", e))?;
+            }
+            let json = serde_json::to_string_pretty(&comments)
+                .map_err(|e| ServiceError::serialization("Failed to serialize comments", e))?;
+            std::fs::write(&file_path, json)
+                .map_err(|e| ServiceError::io("Failed to write comments file", e))?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Load the latency of a session's turns, oldest first
+    pub async fn load_turn_latencies(&self, session_id: &str) -> ServiceResult<Vec<TurnLatency>> {
+        let file_path = self.latency_file_path(session_id);
+        smol::unblock(move || read_list(&file_path)).await
+    }
+
+    /// Add the latency of a finished turn to a session
+    pub async fn add_turn_latency(
+        &self,
+        session_id: &str,
+        latency: TurnLatency,
+    ) -> ServiceResult<()> {
+        let file_path = self.latency_file_path(session_id);
+        let comments_lock = self.comments_lock.clone();
+        smol::unblock(move || {
+            let _guard = comments_lock.lock().unwrap();
+            let mut latencies: Vec<TurnLatency> = read_list(&file_path)?;
+            latencies.push(latency);
+            if let Some(parent) = file_path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| ServiceError::io("Failed to create session directory", e))?;
+            }
+            let json = serde_json::to_string(&latencies)
+                .map_err(|e| ServiceError::serialization("Failed to serialize latencies", e))?;
+            std::fs::write(&file_path, json)
+                .map_err(|e| ServiceError::io("Failed to write latency file", e))?;
+            Ok(())
+        })
+        .await
+    }
+
     /// Delete a session's history file
     ///
     /// Flushes any pending chunks before deleting
-    pub async fn delete_session(&self, session_id: &str) -> Result<()> {
+    pub async fn delete_session(&self, session_id: &str) -> ServiceResult<()> {
         // Flush pending chunks first
         self.flush_session(session_id).await?;
 
@@ -617,12 +1041,17 @@ impl PersistenceService {
             accumulators.remove(session_id);
         }
 
-        // Delete file
-        let file_path = self.session_file_path(session_id);
+        // Delete the history, its comments and latencies
+        let file_paths = [
+            self.session_file_path(session_id),
+            self.comments_file_path(session_id),
+            self.latency_file_path(session_id),
+        ];
 
         smol::unblock(move || {
-            if file_path.exists() {
-                std::fs::remove_file(&file_path).context("Failed to delete session file")?;
+            for file_path in file_paths.iter().filter(|path| path.exists()) {
+                std::fs::remove_file(file_path)
+                    .map_err(|e| ServiceError::io("Failed to delete session file", e))?;
                 log::info!("Deleted session file: {}", file_path.display());
             }
             Ok(())
@@ -630,26 +1059,78 @@ impl PersistenceService {
         .await
     }
 
+    /// Move a session's history file to the trash directory
+    ///
+    /// Flushes any pending chunks first. The file keeps its name, so it can be
+    /// moved back to restore the session.
+    pub async fn trash_session(&self, session_id: &str) -> ServiceResult<()> {
+        self.flush_session(session_id).await?;
+
+        {
+            let mut accumulators = self.accumulators.lock().unwrap();
+            accumulators.remove(session_id);
+        }
+
+        let file_paths = [
+            self.session_file_path(session_id),
+            self.comments_file_path(session_id),
+            self.latency_file_path(session_id),
+        ];
+        // Trash next to the file, so it never has to cross file systems
+        let trash_dir = self.session_dir(session_id).join("trash");
+
+        smol::unblock(move || {
+            for file_path in file_paths.iter().filter(|path| path.exists()) {
+                std::fs::create_dir_all(&trash_dir)
+                    .map_err(|e| ServiceError::io("Failed to create trash directory", e))?;
+                let trashed = trash_dir.join(file_path.file_name().unwrap_or_default());
+                std::fs::rename(file_path, &trashed)
+                    .map_err(|e| ServiceError::io("Failed to move session file to trash", e))?;
+                log::info!("Moved session file to trash: {}", trashed.display());
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Directory deleted session files of the base directory are moved to
+    pub fn trash_dir(&self) -> PathBuf {
+        self.base_dir.join("trash")
+    }
+
     /// List all available sessions
-    pub async fn list_sessions(&self) -> Result<Vec<String>> {
+    pub async fn list_sessions(&self) -> ServiceResult<Vec<String>> {
         let base_dir = self.base_dir.clone();
+        let elsewhere: Vec<(String, PathBuf)> = self
+            .session_dirs
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(session_id, dir)| (session_id.clone(), dir.clone()))
+            .collect();
 
         smol::unblock(move || {
+            // Sessions stored elsewhere count where they are stored now
+            let mut sessions: Vec<String> = elsewhere
+                .iter()
+                .filter(|(session_id, dir)| dir.join(format!("{}.jsonl", session_id)).exists())
+                .map(|(session_id, _)| session_id.clone())
+                .collect();
             if !base_dir.exists() {
-                return Ok(Vec::new());
+                return Ok(sessions);
             }
 
-            let mut sessions = Vec::new();
-
-            for entry in
-                std::fs::read_dir(&base_dir).context("Failed to read sessions directory")?
+            for entry in std::fs::read_dir(&base_dir)
+                .map_err(|e| ServiceError::io("Failed to read sessions directory", e))?
             {
                 let entry = entry?;
                 let path = entry.path();
                 if let Some(extension) = path.extension() {
                     if extension == "jsonl" {
                         if let Some(stem) = path.file_stem() {
-                            if let Some(session_id) = stem.to_str() {
+                            if let Some(session_id) = stem.to_str()
+                                && !elsewhere.iter().any(|(id, _)| id == session_id)
+                            {
                                 sessions.push(session_id.to_string());
                             }
                         }
@@ -662,3 +1143,47 @@ impl PersistenceService {
         .await
     }
 }
+
+/// Read a comments or latency file; a missing file is empty
+fn read_list<T: serde::de::DeserializeOwned>(file_path: &std::path::Path) -> ServiceResult<Vec<T>> {
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+    let json = std::fs::read_to_string(file_path)
+        .map_err(|e| ServiceError::io("Failed to read session file", e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| ServiceError::serialization("Failed to parse session file", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_history_line() {
+        let known = PersistedMessage::with_timestamp(
+            "2026-03-02T10:00:00+00:00".to_string(),
+            SessionUpdate::AgentMessageChunk(ContentChunk::new(ContentBlock::from(
+                "Hello".to_string(),
+            ))),
+        );
+        let line = serde_json::to_string(&known).unwrap();
+        assert!(matches!(
+            parse_history_line(&line),
+            Some(HistoryEntry::Message(_))
+        ));
+
+        let line = concat!(
+            r#"{"timestamp":"2026-03-02T10:00:01+00:00","#,
+            r#""update":{"sessionUpdate":"hologram","frames":3}}"#
+        );
+        let Some(HistoryEntry::Unsupported(timestamp, update)) = parse_history_line(line) else {
+            panic!("expected an unsupported update");
+        };
+        assert_eq!(timestamp, "2026-03-02T10:00:01+00:00");
+        assert_eq!(update.kind, "hologram");
+        assert_eq!(update.raw["frames"], 3);
+
+        assert!(parse_history_line("not json").is_none());
+    }
+}
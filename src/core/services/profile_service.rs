@@ -0,0 +1,230 @@
+//! Profile Service - Named configurations with their own data
+//!
+//! A profile (e.g. "work", "personal", "demo") has its own `config.json`
+//! (agents, models, API keys), workspaces, session histories and audit log,
+//! all kept under the profile's persistence root. Profiles are listed in
+//! `profiles.json` in the user data directory together with the active one.
+//!
+//! The `default` profile keeps its data directly in the user data directory,
+//! where it was before profiles existed. Other profiles live in
+//! `profiles/<name>/` unless they were given a root of their own.
+
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// Profile used when none was chosen
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// A named configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    /// Where the profile's config and data live, when not the default location
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+}
+
+/// Contents of `profiles.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ProfilesFile {
+    /// Profile opened at startup
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    active: Option<String>,
+    #[serde(default)]
+    profiles: Vec<Profile>,
+}
+
+/// Profile service - lists, creates and selects profiles
+pub struct ProfileService {
+    /// The user data directory
+    base_dir: PathBuf,
+    file: RwLock<ProfilesFile>,
+    /// The profile this process runs with
+    current: RwLock<String>,
+}
+
+impl ProfileService {
+    /// Load `profiles.json` from `base_dir`; a missing or broken file means
+    /// only the default profile exists
+    pub fn load(base_dir: PathBuf) -> Self {
+        let path = base_dir.join("profiles.json");
+        let file = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Ignoring invalid {:?}: {}", path, e);
+                ProfilesFile::default()
+            }),
+            Err(_) => ProfilesFile::default(),
+        };
+        let current = file
+            .active
+            .clone()
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+        Self {
+            base_dir,
+            file: RwLock::new(file),
+            current: RwLock::new(current),
+        }
+    }
+
+    /// All profiles, the default one first
+    pub fn list(&self) -> Vec<Profile> {
+        let file = self.file.read().unwrap();
+        let mut profiles = vec![Profile {
+            name: DEFAULT_PROFILE.to_string(),
+            root: None,
+        }];
+        profiles.extend(
+            file.profiles
+                .iter()
+                .filter(|p| p.name != DEFAULT_PROFILE)
+                .cloned(),
+        );
+        profiles
+    }
+
+    pub fn get(&self, name: &str) -> Option<Profile> {
+        self.list().into_iter().find(|p| p.name == name)
+    }
+
+    /// The profile this process runs with
+    pub fn current(&self) -> String {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Run this process with `name`, creating the profile if it does not exist
+    ///
+    /// Used for `--profile <name>`; unlike [`Self::set_active`] it does not
+    /// change the profile later starts open.
+    pub fn use_profile(&self, name: &str) -> Result<Profile> {
+        let profile = match self.get(name) {
+            Some(profile) => profile,
+            None => self.create(name, None)?,
+        };
+        *self.current.write().unwrap() = profile.name.clone();
+        Ok(profile)
+    }
+
+    /// Open `name` from the next start on
+    pub fn set_active(&self, name: &str) -> Result<()> {
+        if self.get(name).is_none() {
+            bail!("Profile not found: {}", name);
+        }
+        self.file.write().unwrap().active = Some(name.to_string());
+        self.save()
+    }
+
+    /// Add a profile, stored under `root` or in `profiles/<name>/`
+    pub fn create(&self, name: &str, root: Option<PathBuf>) -> Result<Profile> {
+        let name = name.trim();
+        validate_name(name)?;
+        if self.get(name).is_some() {
+            bail!("Profile already exists: {}", name);
+        }
+        let profile = Profile {
+            name: name.to_string(),
+            root,
+        };
+        self.file.write().unwrap().profiles.push(profile.clone());
+        self.save()?;
+        log::info!("Created profile '{}' at {:?}", name, self.root(&profile));
+        Ok(profile)
+    }
+
+    /// Directory holding the config and data of `profile`
+    pub fn root(&self, profile: &Profile) -> PathBuf {
+        profile_root(&self.base_dir, profile)
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = self.base_dir.join("profiles.json");
+        std::fs::create_dir_all(&self.base_dir)
+            .with_context(|| format!("Failed to create {:?}", self.base_dir))?;
+        let content = serde_json::to_string_pretty(&*self.file.read().unwrap())?;
+        std::fs::write(&path, content).with_context(|| format!("Failed to write {:?}", path))
+    }
+}
+
+fn profile_root(base_dir: &Path, profile: &Profile) -> PathBuf {
+    match &profile.root {
+        Some(root) => root.clone(),
+        None if profile.name == DEFAULT_PROFILE => base_dir.to_path_buf(),
+        None => base_dir.join("profiles").join(&profile.name),
+    }
+}
+
+/// Profile names are used as directory names
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("Profile name is empty");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "Profile name '{}' may only contain letters, digits, '-' and '_'",
+            name
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_base(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("agentx-profiles-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_profile_roots() {
+        let base = PathBuf::from("/home/me/.agentx");
+        let profile = |name: &str, root: Option<&str>| Profile {
+            name: name.to_string(),
+            root: root.map(PathBuf::from),
+        };
+        assert_eq!(profile_root(&base, &profile("default", None)), base);
+        assert_eq!(
+            profile_root(&base, &profile("work", None)),
+            base.join("profiles").join("work")
+        );
+        assert_eq!(
+            profile_root(&base, &profile("demo", Some("/tmp/demo"))),
+            PathBuf::from("/tmp/demo")
+        );
+    }
+
+    #[test]
+    fn test_create_and_activate() {
+        let base = temp_base("create");
+        let service = ProfileService::load(base.clone());
+        assert_eq!(service.current(), DEFAULT_PROFILE);
+        assert_eq!(service.list().len(), 1);
+
+        service.create("work", None).unwrap();
+        assert!(service.create("work", None).is_err());
+        assert!(service.create("../etc", None).is_err());
+        assert!(service.create("", None).is_err());
+        service.set_active("work").unwrap();
+        assert!(service.set_active("missing").is_err());
+
+        // A new start opens the active profile
+        let reloaded = ProfileService::load(base.clone());
+        assert_eq!(reloaded.current(), "work");
+        let names: Vec<String> = reloaded.list().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["default", "work"]);
+
+        // --profile creates missing profiles for this run only
+        reloaded.use_profile("demo").unwrap();
+        assert_eq!(reloaded.current(), "demo");
+        assert_eq!(ProfileService::load(base.clone()).current(), "work");
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
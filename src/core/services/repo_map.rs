@@ -0,0 +1,169 @@
+//! Repo Map - A compact orientation summary of a workspace
+//!
+//! Agents starting on an unfamiliar project tend to spend their first turns
+//! listing directories and opening files. The repo map hands them that
+//! orientation up front: the directory layout followed by the public
+//! definitions of each source file, capped so it never crowds out the prompt.
+
+use std::path::Path;
+
+use agent_client_protocol::{
+    ContentBlock, EmbeddedResource, EmbeddedResourceResource, TextResourceContents,
+};
+use super::symbol_index::{Symbol, SymbolIndex};
+use crate::utils::path_filter::PathFilter;
+
+/// Default size cap of a generated map, in characters
+pub const DEFAULT_REPO_MAP_CHARS: usize = 24_000;
+/// Directories nested deeper than this are listed but not expanded
+const MAX_TREE_DEPTH: usize = 4;
+/// Entries shown per directory before the rest are summarized
+const MAX_ENTRIES_PER_DIR: usize = 40;
+
+/// Generate the map for the index's workspace root
+///
+/// Blocking: refreshes the index and walks the directory tree.
+pub fn generate_repo_map(index: &SymbolIndex, max_chars: usize) -> String {
+    index.refresh();
+    let root = index.root();
+    let mut layout = Vec::new();
+    collect_layout(index.filter(), root, 0, &mut layout);
+    render_repo_map(root, &layout, &index.public_symbols(), max_chars)
+}
+
+/// Render the layout lines and public symbols as Markdown, truncated at `max_chars`
+pub fn render_repo_map(
+    root: &Path,
+    layout: &[String],
+    symbols: &[Symbol],
+    max_chars: usize,
+) -> String {
+    let name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| root.display().to_string());
+
+    let mut lines = vec![
+        format!("# Repository map: {}", name),
+        String::new(),
+        "## Layout".to_string(),
+        "```".to_string(),
+    ];
+    lines.extend(layout.iter().cloned());
+    lines.push("```".to_string());
+    lines.push(String::new());
+    lines.push("## Public symbols".to_string());
+
+    let mut current_file = None;
+    for symbol in symbols {
+        if current_file != Some(&symbol.path) {
+            current_file = Some(&symbol.path);
+            let relative = symbol.path.strip_prefix(root).unwrap_or(&symbol.path);
+            lines.push(format!("{}:", relative.display()));
+        }
+        lines.push(format!(
+            "  {} {} (L{})",
+            symbol.kind.label(),
+            symbol.name,
+            symbol.start_line
+        ));
+    }
+
+    let mut map = String::new();
+    for line in lines {
+        if max_chars > 0 && map.len() + line.len() + 1 > max_chars {
+            map.push_str(&format!("… (truncated at {} characters)\n", max_chars));
+            break;
+        }
+        map.push_str(&line);
+        map.push('\n');
+    }
+    map
+}
+
+/// The map as an embedded resource for a prompt
+pub fn repo_map_block(root: &Path, map: String) -> ContentBlock {
+    let resource = TextResourceContents::new(map, format!("file://{}", root.display()))
+        .mime_type("text/markdown".to_string());
+    ContentBlock::Resource(EmbeddedResource::new(
+        EmbeddedResourceResource::TextResourceContents(resource),
+    ))
+}
+
+/// Indented layout lines below `dir`, directories first
+fn collect_layout(filter: &PathFilter, dir: &Path, depth: usize, lines: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<(bool, String, std::path::PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if filter.is_excluded(&path) {
+                return None;
+            }
+            let is_dir = entry.file_type().ok()?.is_dir();
+            Some((is_dir, entry.file_name().to_string_lossy().to_string(), path))
+        })
+        .collect();
+    entries.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    let indent = "  ".repeat(depth);
+    let hidden = entries.len().saturating_sub(MAX_ENTRIES_PER_DIR);
+    for (is_dir, name, path) in entries.into_iter().take(MAX_ENTRIES_PER_DIR) {
+        if is_dir {
+            lines.push(format!("{}{}/", indent, name));
+            if depth + 1 < MAX_TREE_DEPTH {
+                collect_layout(filter, &path, depth + 1, lines);
+            }
+        } else {
+            lines.push(format!("{}{}", indent, name));
+        }
+    }
+    if hidden > 0 {
+        lines.push(format!("{}… {} more", indent, hidden));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::services::SymbolKind;
+
+    fn symbol(path: &str, name: &str, kind: SymbolKind, line: u32) -> Symbol {
+        Symbol {
+            name: name.to_string(),
+            kind,
+            path: Path::new(path).to_path_buf(),
+            start_line: line,
+            end_line: line,
+            start_byte: 0,
+            end_byte: 0,
+            public: true,
+        }
+    }
+
+    #[test]
+    fn test_render_groups_symbols_by_file() {
+        let layout = vec!["src/".to_string(), "  lib.rs".to_string()];
+        let symbols = vec![
+            symbol("/repo/src/lib.rs", "Config", SymbolKind::Struct, 3),
+            symbol("/repo/src/lib.rs", "load", SymbolKind::Function, 10),
+        ];
+        let map = render_repo_map(Path::new("/repo"), &layout, &symbols, 0);
+
+        assert!(map.starts_with("# Repository map: repo\n"));
+        assert!(map.contains("```\nsrc/\n  lib.rs\n```"));
+        assert!(map.contains("src/lib.rs:\n  struct Config (L3)\n  fn load (L10)\n"));
+    }
+
+    #[test]
+    fn test_render_truncates_at_limit() {
+        let layout: Vec<String> = (0..1000).map(|i| format!("file_{}.rs", i)).collect();
+        let map = render_repo_map(Path::new("/repo"), &layout, &[], 500);
+
+        assert!(map.len() < 560);
+        assert!(map.ends_with("… (truncated at 500 characters)\n"));
+        assert!(!map.contains("## Public symbols"));
+    }
+}
@@ -0,0 +1,118 @@
+//! Retry Policy - Exponential backoff for transient agent RPC failures
+//!
+//! Prompts can fail for reasons that go away on their own (agent busy, rate
+//! limited). The policy decides whether an error is worth retrying and how long
+//! to wait between attempts.
+//!
+//! Only errors telling that the agent turned the prompt down are retried. A
+//! timeout may come after the agent already ran the turn, tool calls and file
+//! edits included, and a closed pipe means the process is gone; sending the
+//! prompt again would repeat the turn or fail the same way.
+
+use std::time::Duration;
+
+use crate::core::config::RetryConfig;
+
+/// Error message fragments telling that the prompt was turned down, not run
+const TRANSIENT_MARKERS: &[&str] = &["busy", "try again", "rate limit"];
+
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from(&RetryConfig::default())
+    }
+}
+
+impl From<&RetryConfig> for RetryPolicy {
+    fn from(config: &RetryConfig) -> Self {
+        Self {
+            max_attempts: config.max_attempts.max(1),
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    /// Delay before the given retry (`attempt` is the 1-based attempt that just failed)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Whether another attempt is allowed after `attempt` failed with `error`
+    pub fn should_retry(&self, attempt: u32, error: &anyhow::Error) -> bool {
+        attempt < self.max_attempts && is_transient_error(error)
+    }
+}
+
+/// Whether an error looks transient and worth retrying
+pub fn is_transient_error(error: &anyhow::Error) -> bool {
+    let message = format!("{:#}", error).to_lowercase();
+    TRANSIENT_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_millis(1500),
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = policy();
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(3), Duration::from_millis(1500));
+        assert_eq!(policy.backoff(30), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_should_retry_only_transient_errors() {
+        let policy = policy();
+        assert!(policy.should_retry(1, &anyhow!("Agent is busy")));
+        assert!(policy.should_retry(2, &anyhow!("Rate limit exceeded")));
+        assert!(!policy.should_retry(3, &anyhow!("Agent is busy")));
+        assert!(!policy.should_retry(1, &anyhow!("Invalid params")));
+    }
+
+    #[test]
+    fn test_should_not_retry_prompts_that_may_have_run() {
+        let policy = policy();
+        assert!(!policy.should_retry(1, &anyhow!("Request timed out")));
+        assert!(!policy.should_retry(1, &anyhow!("write failed: Broken pipe")));
+        assert!(!policy.should_retry(1, &anyhow!("channel closed")));
+    }
+
+    #[test]
+    fn test_none_never_retries() {
+        assert!(!RetryPolicy::none().should_retry(1, &anyhow!("busy")));
+    }
+}
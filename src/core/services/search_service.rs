@@ -0,0 +1,498 @@
+//! Search Service - Full-text search over the history of every session
+//!
+//! Each session's history is reduced to a [`SessionTranscript`] whose
+//! messages, thoughts, tool calls and plans are indexed by the words they
+//! contain. The index is built on the first search; afterwards only sessions
+//! the session bus reported updates for are indexed again, and sessions whose
+//! files are gone are dropped.
+//!
+//! Queries match words by prefix, so `conf rs` finds `config.rs`, and take
+//! `agent:<name>` and `session:<id prefix>` filters next to the words.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+
+use super::error::ServiceResult;
+use super::persistence_service::{PersistedMessage, PersistenceService};
+use super::session_export::{SessionTranscript, TranscriptItem};
+use super::workspace_service::WorkspaceService;
+use crate::core::event_bus::SessionUpdateBusContainer;
+
+/// Characters of an item's text shown around its first match
+const SNIPPET_CHARS: usize = 160;
+
+/// What a search hit is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchHitKind {
+    User,
+    Agent,
+    Thought,
+    ToolCall,
+    Plan,
+}
+
+/// A search over the indexed sessions
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchQuery {
+    /// Words each hit contains the start of
+    pub terms: Vec<String>,
+    /// Only sessions whose ID starts with this
+    pub session_id: Option<String>,
+    /// Only sessions of this agent, ignoring case
+    pub agent_name: Option<String>,
+    /// Only items at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only items before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl SearchQuery {
+    /// Query for `text`: words, plus `agent:` and `session:` filters
+    pub fn parse(text: &str) -> Self {
+        let mut query = Self::default();
+        for part in text.split_whitespace() {
+            if let Some(agent) = part.strip_prefix("agent:").filter(|v| !v.is_empty()) {
+                query.agent_name = Some(agent.to_string());
+            } else if let Some(session) = part.strip_prefix("session:").filter(|v| !v.is_empty()) {
+                query.session_id = Some(session.to_string());
+            } else {
+                query.terms.extend(words(part));
+            }
+        }
+        query
+    }
+
+    fn matches_session(&self, session_id: &str, info: Option<&SessionInfo>) -> bool {
+        if let Some(prefix) = &self.session_id
+            && !session_id.starts_with(prefix.as_str())
+        {
+            return false;
+        }
+        match &self.agent_name {
+            Some(agent) => info
+                .and_then(|info| info.agent_name.as_deref())
+                .is_some_and(|name| name.eq_ignore_ascii_case(agent)),
+            None => true,
+        }
+    }
+
+    fn matches_time(&self, timestamp: Option<DateTime<Utc>>) -> bool {
+        match timestamp {
+            Some(time) => {
+                self.since.is_none_or(|since| time >= since)
+                    && self.until.is_none_or(|until| time < until)
+            }
+            // Items without a readable time only match unbounded queries
+            None => self.since.is_none() && self.until.is_none(),
+        }
+    }
+}
+
+/// Task and agent of a session, for filters and display
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionInfo {
+    pub task_name: Option<String>,
+    pub agent_name: Option<String>,
+}
+
+/// An item of a session's history matching a query
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchHit {
+    pub session_id: String,
+    pub info: SessionInfo,
+    /// Index of the item in the session's transcript
+    pub item_index: usize,
+    pub kind: SearchHitKind,
+    pub timestamp: Option<DateTime<Utc>>,
+    /// The item's text around its first match, on one line
+    pub snippet: String,
+}
+
+struct IndexedItem {
+    kind: SearchHitKind,
+    timestamp: Option<DateTime<Utc>>,
+    text: String,
+}
+
+/// The indexed history of one session
+pub struct SessionIndex {
+    items: Vec<IndexedItem>,
+    /// Items by the words they contain
+    words: BTreeMap<String, BTreeSet<usize>>,
+}
+
+impl SessionIndex {
+    pub fn build(session_id: &str, messages: &[PersistedMessage]) -> Self {
+        let transcript = SessionTranscript::from_history(session_id, "", messages);
+        let mut index = Self {
+            items: Vec::new(),
+            words: BTreeMap::new(),
+        };
+        for item in transcript.items {
+            let (kind, timestamp, text) = match item {
+                TranscriptItem::User { timestamp, text } => (SearchHitKind::User, timestamp, text),
+                TranscriptItem::Agent { timestamp, text } => {
+                    (SearchHitKind::Agent, timestamp, text)
+                }
+                TranscriptItem::Thought { timestamp, text } => {
+                    (SearchHitKind::Thought, timestamp, text)
+                }
+                TranscriptItem::ToolCall {
+                    timestamp,
+                    title,
+                    outputs,
+                    ..
+                } => {
+                    let text: Vec<String> = std::iter::once(title).chain(outputs).collect();
+                    (SearchHitKind::ToolCall, timestamp, text.join("\n"))
+                }
+                TranscriptItem::Plan { timestamp, entries } => {
+                    let text: Vec<String> =
+                        entries.into_iter().map(|entry| entry.content).collect();
+                    (SearchHitKind::Plan, timestamp, text.join("\n"))
+                }
+            };
+            let ix = index.items.len();
+            for word in words(&text) {
+                index.words.entry(word).or_default().insert(ix);
+            }
+            index.items.push(IndexedItem {
+                kind,
+                timestamp: DateTime::parse_from_rfc3339(&timestamp)
+                    .ok()
+                    .map(|time| time.with_timezone(&Utc)),
+                text,
+            });
+        }
+        index
+    }
+
+    /// Items containing a word starting with each of `terms`
+    fn matching_items(&self, terms: &[String]) -> BTreeSet<usize> {
+        let mut matching: Option<BTreeSet<usize>> = None;
+        for term in terms {
+            let items: BTreeSet<usize> = self
+                .words
+                .range(term.clone()..)
+                .take_while(|(word, _)| word.starts_with(term.as_str()))
+                .flat_map(|(_, items)| items.iter().copied())
+                .collect();
+            matching = Some(match matching {
+                Some(matching) => matching.intersection(&items).copied().collect(),
+                None => items,
+            });
+        }
+        matching.unwrap_or_default()
+    }
+}
+
+/// The lowercase words of `text`: runs of letters, digits and `_`
+pub fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Up to `max_chars` of `text` on one line, starting a little before the
+/// first word matching one of `terms`
+pub fn snippet(text: &str, terms: &[String], max_chars: usize) -> String {
+    let line: String = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let lower = line.to_lowercase();
+    // Lowercasing can change byte lengths, so positions are counted in chars
+    let first_match = terms
+        .iter()
+        .filter_map(|term| lower.find(term.as_str()))
+        .min()
+        .map(|byte| lower[..byte].chars().count())
+        .unwrap_or(0);
+    let start = first_match.saturating_sub(max_chars / 4);
+    let total = line.chars().count();
+    let mut snippet: String = line.chars().skip(start).take(max_chars).collect();
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if start + max_chars < total {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// Up to `limit` hits for `query` in `sessions`: items containing the words
+/// in that order first, then the rest, newest first within each
+pub fn search_sessions(
+    sessions: &HashMap<String, SessionIndex>,
+    infos: &HashMap<String, SessionInfo>,
+    query: &SearchQuery,
+    limit: usize,
+) -> Vec<SearchHit> {
+    if query.terms.is_empty() {
+        return Vec::new();
+    }
+    let mut hits: Vec<(bool, SearchHit)> = Vec::new();
+    for (session_id, index) in sessions {
+        let info = infos.get(session_id);
+        if !query.matches_session(session_id, info) {
+            continue;
+        }
+        for ix in index.matching_items(&query.terms) {
+            let item = &index.items[ix];
+            if !query.matches_time(item.timestamp) {
+                continue;
+            }
+            let item_words: Vec<String> = words(&item.text).collect();
+            let in_order = item_words.windows(query.terms.len()).any(|window| {
+                window
+                    .iter()
+                    .zip(&query.terms)
+                    .all(|(word, term)| word.starts_with(term.as_str()))
+            });
+            hits.push((
+                in_order,
+                SearchHit {
+                    session_id: session_id.clone(),
+                    info: info.cloned().unwrap_or_default(),
+                    item_index: ix,
+                    kind: item.kind,
+                    timestamp: item.timestamp,
+                    snippet: snippet(&item.text, &query.terms, SNIPPET_CHARS),
+                },
+            ));
+        }
+    }
+    hits.sort_by(|(a_in_order, a), (b_in_order, b)| {
+        b_in_order
+            .cmp(a_in_order)
+            .then(b.timestamp.cmp(&a.timestamp))
+            .then(a.session_id.cmp(&b.session_id))
+            .then(a.item_index.cmp(&b.item_index))
+    });
+    hits.into_iter().take(limit).map(|(_, hit)| hit).collect()
+}
+
+/// Full-text index over the persisted history of all sessions
+pub struct SearchService {
+    persistence_service: Arc<PersistenceService>,
+    workspace_service: Arc<WorkspaceService>,
+    sessions: Arc<Mutex<HashMap<String, SessionIndex>>>,
+    /// Sessions updated since they were indexed
+    dirty: Mutex<HashSet<String>>,
+}
+
+impl SearchService {
+    pub fn new(
+        persistence_service: Arc<PersistenceService>,
+        workspace_service: Arc<WorkspaceService>,
+    ) -> Self {
+        Self {
+            persistence_service,
+            workspace_service,
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            dirty: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Index sessions again after the agents update them
+    pub fn start(self: &Arc<Self>, session_bus: &SessionUpdateBusContainer) {
+        let service = self.clone();
+        session_bus.subscribe(move |event| {
+            service
+                .dirty
+                .lock()
+                .unwrap()
+                .insert(event.session_id.clone());
+        });
+    }
+
+    /// Index sessions that are new, updated or still being written, and drop
+    /// the ones that no longer exist
+    async fn refresh(&self) -> ServiceResult<()> {
+        let session_ids = self.persistence_service.list_sessions().await?;
+        let stale: Vec<String> = {
+            let mut sessions = self.sessions.lock().unwrap();
+            let mut dirty = self.dirty.lock().unwrap();
+            let existing: HashSet<&String> = session_ids.iter().collect();
+            sessions.retain(|session_id, _| existing.contains(session_id));
+            session_ids
+                .iter()
+                .filter(|session_id| {
+                    dirty.remove(*session_id)
+                        || !sessions.contains_key(*session_id)
+                        || self.persistence_service.has_pending_updates(session_id)
+                })
+                .cloned()
+                .collect()
+        };
+        if !stale.is_empty() {
+            log::debug!("Indexing {} sessions for search", stale.len());
+        }
+
+        for session_id in stale {
+            match self.persistence_service.load_messages(&session_id).await {
+                Ok(messages) => {
+                    let sessions = self.sessions.clone();
+                    smol::unblock(move || {
+                        let index = SessionIndex::build(&session_id, &messages);
+                        sessions.lock().unwrap().insert(session_id, index);
+                    })
+                    .await;
+                }
+                Err(e) => log::warn!("Failed to index session {}: {}", session_id, e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Task and agent of every session the workspaces know
+    async fn session_infos(&self) -> HashMap<String, SessionInfo> {
+        let mut infos = HashMap::new();
+        for task in self.workspace_service.get_all_tasks().await {
+            for (agent_name, session_id) in &task.agent_sessions {
+                infos.insert(
+                    session_id.clone(),
+                    SessionInfo {
+                        task_name: Some(task.name.clone()),
+                        agent_name: Some(agent_name.clone()),
+                    },
+                );
+            }
+            if let Some(session_id) = &task.session_id {
+                infos.insert(
+                    session_id.clone(),
+                    SessionInfo {
+                        task_name: Some(task.name.clone()),
+                        agent_name: Some(task.agent_name.clone()),
+                    },
+                );
+            }
+        }
+        infos
+    }
+
+    /// Agents of the tasks that can be searched, sorted
+    pub async fn agent_names(&self) -> Vec<String> {
+        let names: BTreeSet<String> = self
+            .session_infos()
+            .await
+            .into_values()
+            .filter_map(|info| info.agent_name)
+            .collect();
+        names.into_iter().collect()
+    }
+
+    /// Up to `limit` hits for `query`, indexing what changed since the last search
+    pub async fn search(&self, query: &SearchQuery, limit: usize) -> ServiceResult<Vec<SearchHit>> {
+        self.refresh().await?;
+        let infos = self.session_infos().await;
+        let sessions = self.sessions.clone();
+        let query = query.clone();
+        Ok(smol::unblock(move || {
+            let sessions = sessions.lock().unwrap();
+            search_sessions(&sessions, &infos, &query, limit)
+        })
+        .await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::{ContentBlock, ContentChunk, SessionUpdate, ToolCall, ToolCallId};
+
+    fn message(timestamp: &str, update: SessionUpdate) -> PersistedMessage {
+        PersistedMessage::with_timestamp(timestamp.to_string(), update)
+    }
+
+    fn chunk(text: &str) -> ContentChunk {
+        ContentChunk::new(ContentBlock::from(text.to_string()))
+    }
+
+    fn sessions() -> HashMap<String, SessionIndex> {
+        let edit = ToolCall::new(
+            ToolCallId::from("t1".to_string()),
+            "Edit src/config.rs".to_string(),
+        );
+        let first = vec![
+            message(
+                "2026-01-01T10:00:00Z",
+                SessionUpdate::UserMessageChunk(chunk("Load the config lazily")),
+            ),
+            message("2026-01-01T10:00:05Z", SessionUpdate::ToolCall(edit)),
+            message(
+                "2026-01-01T10:00:09Z",
+                SessionUpdate::AgentMessageChunk(chunk("Config now loads lazily.")),
+            ),
+        ];
+        let second = vec![message(
+            "2026-02-01T10:00:00Z",
+            SessionUpdate::AgentMessageChunk(chunk("The rs files config")),
+        )];
+        HashMap::from([
+            ("s1".to_string(), SessionIndex::build("s1", &first)),
+            ("s2".to_string(), SessionIndex::build("s2", &second)),
+        ])
+    }
+
+    fn infos() -> HashMap<String, SessionInfo> {
+        HashMap::from([(
+            "s1".to_string(),
+            SessionInfo {
+                task_name: Some("Lazy config".to_string()),
+                agent_name: Some("claude".to_string()),
+            },
+        )])
+    }
+
+    #[test]
+    fn test_parse() {
+        let query = SearchQuery::parse("agent:Claude config.rs session:abc agent:");
+        assert_eq!(query.terms, vec!["config", "rs", "agent"]);
+        assert_eq!(query.agent_name.as_deref(), Some("Claude"));
+        assert_eq!(query.session_id.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_search_sessions() {
+        let sessions = sessions();
+        let infos = infos();
+        let search = |text: &str| search_sessions(&sessions, &infos, &SearchQuery::parse(text), 10);
+
+        // Words in order rank first, then the newest
+        let hits = search("conf rs");
+        let found: Vec<(&str, SearchHitKind)> = hits
+            .iter()
+            .map(|hit| (hit.session_id.as_str(), hit.kind))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("s1", SearchHitKind::ToolCall),
+                ("s2", SearchHitKind::Agent)
+            ]
+        );
+        assert_eq!(hits[0].info.task_name.as_deref(), Some("Lazy config"));
+
+        assert_eq!(search("lazily").len(), 2);
+        assert_eq!(search("lazily agent:CLAUDE").len(), 2);
+        assert!(search("config agent:codex").is_empty());
+        assert_eq!(search("config session:s2").len(), 1);
+        assert!(search("agent:claude").is_empty());
+
+        let mut query = SearchQuery::parse("config");
+        query.since = DateTime::parse_from_rfc3339("2026-01-15T00:00:00Z")
+            .ok()
+            .map(|time| time.with_timezone(&Utc));
+        let hits = search_sessions(&sessions, &infos, &query, 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session_id, "s2");
+    }
+
+    #[test]
+    fn test_snippet() {
+        let terms = vec!["needle".to_string()];
+        assert_eq!(snippet("a\n  needle b", &terms, 20), "a needle b");
+        let text = format!("{} needle {}", "x".repeat(30), "y".repeat(30));
+        assert_eq!(snippet(&text, &terms, 20), "…xxxx needle yyyyyyyy…");
+    }
+}
@@ -0,0 +1,364 @@
+//! Semantic Index - Embedding-based retrieval over a workspace
+//!
+//! `@symbol` mentions only help when the user already knows what to look for.
+//! The semantic index splits the workspace's text files into line-based
+//! chunks, embeds them with the model configured under `embeddings` and answers
+//! `/search` queries with the most similar chunks. The first search embeds the
+//! whole workspace; afterwards a file watcher marks changed files so only those
+//! are re-embedded before the next search.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::ai_service::AiService;
+use super::error::{ServiceError, ServiceResult};
+use crate::core::config::EmbeddingConfig;
+use crate::utils::{context_item::ContextItem, path_filter::PathFilter};
+
+/// Files larger than this are not embedded (lockfiles, generated code)
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+/// Lines per chunk
+const CHUNK_LINES: usize = 40;
+/// Upper bound on chunks kept in memory for one workspace
+const MAX_CHUNKS: usize = 20_000;
+/// Chunks sent per embeddings request
+const EMBED_BATCH: usize = 32;
+/// Characters of a chunk sent for embedding (minified lines can be huge)
+const MAX_EMBED_CHARS: usize = 6_000;
+
+struct Chunk {
+    /// 1-based, inclusive
+    start_line: u32,
+    end_line: u32,
+    text: String,
+    embedding: Vec<f32>,
+}
+
+struct IndexedFile {
+    modified: SystemTime,
+    chunks: Vec<Chunk>,
+}
+
+#[derive(Default)]
+struct IndexState {
+    /// The whole workspace has been embedded once
+    built: bool,
+    files: HashMap<PathBuf, IndexedFile>,
+    /// Paths the watcher saw change since the last sync
+    dirty: HashSet<PathBuf>,
+}
+
+/// Embeddings of the text files below a workspace root
+pub struct SemanticIndex {
+    filter: PathFilter,
+    ai_service: Arc<AiService>,
+    config: EmbeddingConfig,
+    state: Mutex<IndexState>,
+    /// Serializes syncs so concurrent searches don't embed the same files twice
+    sync_lock: smol::lock::Mutex<()>,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl SemanticIndex {
+    pub fn new(
+        filter: PathFilter,
+        ai_service: Arc<AiService>,
+        config: EmbeddingConfig,
+    ) -> Arc<Self> {
+        let index = Arc::new(Self {
+            filter,
+            ai_service,
+            config,
+            state: Mutex::new(IndexState::default()),
+            sync_lock: smol::lock::Mutex::new(()),
+            watcher: Mutex::new(None),
+        });
+        index.start_watching();
+        index
+    }
+
+    pub fn root(&self) -> &Path {
+        self.filter.root()
+    }
+
+    /// Mark files dirty as the file system reports changes below the root
+    fn start_watching(self: &Arc<Self>) {
+        let index = Arc::downgrade(self);
+        let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let (Ok(event), Some(index)) = (res, index.upgrade()) else {
+                return;
+            };
+            let paths = event
+                .paths
+                .into_iter()
+                .filter(|path| !index.filter.is_excluded(path));
+            index.state.lock().unwrap().dirty.extend(paths);
+        });
+
+        match watcher {
+            Ok(mut watcher) => match watcher.watch(self.root(), RecursiveMode::Recursive) {
+                Ok(()) => *self.watcher.lock().unwrap() = Some(watcher),
+                Err(e) => log::warn!("[SemanticIndex] Cannot watch {:?}: {}", self.root(), e),
+            },
+            Err(e) => log::warn!("[SemanticIndex] Failed to create file watcher: {}", e),
+        }
+    }
+
+    /// Bring the index up to date; returns how many files were embedded
+    ///
+    /// The first call scans the whole workspace. Later calls only re-embed the
+    /// files the watcher reported, or rescan if no watcher could be started.
+    pub async fn sync(self: &Arc<Self>) -> ServiceResult<usize> {
+        let _guard = self.sync_lock.lock().await;
+
+        let full_scan = {
+            let state = self.state.lock().unwrap();
+            !state.built || self.watcher.lock().unwrap().is_none()
+        };
+        let this = self.clone();
+        let (stale, removed) = smol::unblock(move || this.stale_files(full_scan)).await;
+
+        {
+            let mut state = self.state.lock().unwrap();
+            for path in &removed {
+                state.files.remove(path);
+            }
+        }
+
+        let mut embedded = 0;
+        for (path, modified) in stale {
+            let Ok(source) = smol::fs::read_to_string(&path).await else {
+                // Binary or unreadable
+                continue;
+            };
+            let indexed_chunks: usize = {
+                let state = self.state.lock().unwrap();
+                state.files.values().map(|file| file.chunks.len()).sum()
+            };
+            let pieces = chunk_lines(&source, CHUNK_LINES);
+            if indexed_chunks + pieces.len() > MAX_CHUNKS {
+                log::warn!(
+                    "[SemanticIndex] Chunk limit ({}) reached, skipping remaining files",
+                    MAX_CHUNKS
+                );
+                break;
+            }
+
+            let mut chunks = Vec::with_capacity(pieces.len());
+            for batch in pieces.chunks(EMBED_BATCH) {
+                let texts: Vec<String> = batch
+                    .iter()
+                    .map(|(_, _, text)| text.chars().take(MAX_EMBED_CHARS).collect())
+                    .collect();
+                let embeddings = self
+                    .ai_service
+                    .embed(self.config.provider.as_deref(), &self.config.model_name, &texts)
+                    .await?;
+                for ((start_line, end_line, text), embedding) in
+                    batch.iter().cloned().zip(embeddings)
+                {
+                    chunks.push(Chunk {
+                        start_line,
+                        end_line,
+                        text,
+                        embedding,
+                    });
+                }
+            }
+
+            self.state
+                .lock()
+                .unwrap()
+                .files
+                .insert(path, IndexedFile { modified, chunks });
+            embedded += 1;
+        }
+
+        self.state.lock().unwrap().built = true;
+        if embedded > 0 || !removed.is_empty() {
+            log::info!(
+                "[SemanticIndex] Embedded {} files, dropped {} under {:?}",
+                embedded,
+                removed.len(),
+                self.root()
+            );
+        }
+        Ok(embedded)
+    }
+
+    /// Files to (re-)embed and files to drop
+    ///
+    /// Blocking: walks the workspace on a full scan, otherwise stats the
+    /// watcher's dirty paths.
+    fn stale_files(&self, full_scan: bool) -> (Vec<(PathBuf, SystemTime)>, Vec<PathBuf>) {
+        let mut state = self.state.lock().unwrap();
+        let dirty = std::mem::take(&mut state.dirty);
+
+        let current: Vec<(PathBuf, SystemTime)> = if full_scan {
+            drop(state);
+            let mut files = Vec::new();
+            collect_text_files(&self.filter, self.root(), &mut files);
+            state = self.state.lock().unwrap();
+            files
+        } else {
+            dirty
+                .iter()
+                .filter(|path| !self.filter.is_excluded(path))
+                .filter_map(|path| Some((path.clone(), text_file_modified(path)?)))
+                .collect()
+        };
+
+        let stale = current
+            .iter()
+            .filter(|(path, modified)| {
+                state
+                    .files
+                    .get(path)
+                    .is_none_or(|file| file.modified != *modified)
+            })
+            .cloned()
+            .collect();
+        let removed = if full_scan {
+            state
+                .files
+                .keys()
+                .filter(|path| !current.iter().any(|(current, _)| current == *path))
+                .cloned()
+                .collect()
+        } else {
+            dirty
+                .into_iter()
+                .filter(|path| !current.iter().any(|(current, _)| current == path))
+                .filter(|path| state.files.contains_key(path))
+                .collect()
+        };
+        (stale, removed)
+    }
+
+    /// The `top_k` chunks most similar to `query`, as context items
+    pub async fn search(self: &Arc<Self>, query: &str) -> ServiceResult<Vec<ContextItem>> {
+        if query.trim().is_empty() {
+            return Err(ServiceError::InvalidInput("Search query is empty".to_string()));
+        }
+        self.sync().await?;
+
+        let query_embedding = self
+            .ai_service
+            .embed(
+                self.config.provider.as_deref(),
+                &self.config.model_name,
+                &[query.to_string()],
+            )
+            .await?
+            .pop()
+            .unwrap_or_default();
+
+        let query_embedding = &query_embedding;
+        let state = self.state.lock().unwrap();
+        let mut scored: Vec<(f32, &Path, &Chunk)> = state
+            .files
+            .iter()
+            .flat_map(|(path, file)| {
+                file.chunks.iter().map(move |chunk| {
+                    let score = cosine_similarity(query_embedding, &chunk.embedding);
+                    (score, path.as_path(), chunk)
+                })
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        Ok(scored
+            .into_iter()
+            .take(self.config.top_k)
+            .map(|(_, path, chunk)| ContextItem::Selection {
+                file_path: path.to_string_lossy().to_string(),
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                content: chunk.text.clone(),
+            })
+            .collect())
+    }
+}
+
+/// Split text into chunks of `lines` lines as `(start_line, end_line, text)`
+///
+/// Line numbers are 1-based and inclusive; whitespace-only chunks are dropped.
+fn chunk_lines(source: &str, lines: usize) -> Vec<(u32, u32, String)> {
+    let all: Vec<&str> = source.lines().collect();
+    all.chunks(lines.max(1))
+        .enumerate()
+        .filter(|(_, chunk)| chunk.iter().any(|line| !line.trim().is_empty()))
+        .map(|(i, chunk)| {
+            let start = i * lines.max(1) + 1;
+            (start as u32, (start + chunk.len() - 1) as u32, chunk.join("\n"))
+        })
+        .collect()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Modification time of a file worth embedding
+fn text_file_modified(path: &Path) -> Option<SystemTime> {
+    let metadata = std::fs::metadata(path).ok()?;
+    (metadata.is_file() && metadata.len() > 0 && metadata.len() <= MAX_FILE_BYTES)
+        .then(|| metadata.modified().ok())
+        .flatten()
+}
+
+fn collect_text_files(filter: &PathFilter, dir: &Path, files: &mut Vec<(PathBuf, SystemTime)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if filter.is_excluded(&path) {
+            continue;
+        }
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            collect_text_files(filter, &path, files);
+        } else if let Some(modified) = text_file_modified(&path) {
+            files.push((path, modified));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_lines() {
+        let source = "a\nb\nc\n\n\n\nd";
+        let chunks = chunk_lines(source, 3);
+        assert_eq!(
+            chunks,
+            vec![(1, 3, "a\nb\nc".to_string()), (7, 7, "d".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+}
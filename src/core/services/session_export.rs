@@ -0,0 +1,437 @@
+//! Session transcripts for export
+//!
+//! A transcript is a session's history with streamed chunks merged back into
+//! whole messages, tool call updates applied to their tool calls and each
+//! run of plan updates reduced to its last plan. It is written as Markdown
+//! in the format of the conversation panel's transcript view, which
+//! [`super::ImportFormat::Markdown`] reads back, or as JSON.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use agent_client_protocol::{
+    ContentBlock, PlanEntryStatus, SessionUpdate, ToolCallContent, ToolCallStatus,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::error::{ServiceError, ServiceResult};
+use super::persistence_service::PersistedMessage;
+use crate::utils::markdown::{fence, quote};
+use crate::utils::tool_call::extract_terminal_output;
+
+/// File format of an exported session
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionExportFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+impl SessionExportFormat {
+    /// Format matching the extension of `path`, Markdown unless it is `.json`
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Markdown,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+
+    /// File name to save a session titled `title` as, e.g. `fix-login-bug.md`
+    pub fn file_name(self, title: &str) -> String {
+        let mut stem = String::new();
+        for c in title.trim().chars() {
+            if c.is_alphanumeric() || c == '_' {
+                stem.extend(c.to_lowercase());
+            } else if !stem.is_empty() && !stem.ends_with('-') {
+                stem.push('-');
+            }
+        }
+        let stem = stem.trim_end_matches('-');
+        let stem = if stem.is_empty() { "session" } else { stem };
+        format!("{}.{}", stem, self.extension())
+    }
+}
+
+impl FromStr for SessionExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "Unknown export format '{}', expected markdown or json",
+                other
+            )),
+        }
+    }
+}
+
+/// One entry of a plan, as it was when the plan was last sent
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TranscriptPlanEntry {
+    pub content: String,
+    pub status: PlanEntryStatus,
+}
+
+/// One item of a transcript; `timestamp` is when it started, in RFC 3339
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TranscriptItem {
+    User {
+        timestamp: String,
+        text: String,
+    },
+    Agent {
+        timestamp: String,
+        text: String,
+    },
+    Thought {
+        timestamp: String,
+        text: String,
+    },
+    ToolCall {
+        timestamp: String,
+        id: String,
+        title: String,
+        status: ToolCallStatus,
+        /// Text, terminal and diff outputs, without empty ones
+        outputs: Vec<String>,
+    },
+    Plan {
+        timestamp: String,
+        entries: Vec<TranscriptPlanEntry>,
+    },
+}
+
+impl TranscriptItem {
+    fn to_markdown(&self) -> String {
+        match self {
+            Self::User { text, .. } => format!("**You**\n\n{}", text),
+            Self::Agent { text, .. } => format!("**Agent**\n\n{}", text),
+            Self::Thought { text, .. } => format!("*Thinking*\n\n{}", quote(text)),
+            Self::ToolCall {
+                title,
+                status,
+                outputs,
+                ..
+            } => {
+                let mut markdown = match status {
+                    ToolCallStatus::Completed => format!("**{}**", title),
+                    ToolCallStatus::Failed => format!("**{}** (failed)", title),
+                    _ => format!("**{}** (unfinished)", title),
+                };
+                for output in outputs {
+                    markdown.push_str("\n\n");
+                    markdown.push_str(&fence(output, ""));
+                }
+                markdown
+            }
+            Self::Plan { entries, .. } => {
+                let lines: Vec<String> = entries
+                    .iter()
+                    .map(|entry| {
+                        let done = matches!(entry.status, PlanEntryStatus::Completed);
+                        format!("- [{}] {}", if done { "x" } else { " " }, entry.content)
+                    })
+                    .collect();
+                format!("**Plan**\n\n{}", lines.join("\n"))
+            }
+        }
+    }
+}
+
+/// A whole session, ready to be written out
+#[derive(Clone, Debug, Serialize)]
+pub struct SessionTranscript {
+    pub session_id: String,
+    pub title: String,
+    pub exported_at: DateTime<Utc>,
+    pub items: Vec<TranscriptItem>,
+}
+
+impl SessionTranscript {
+    /// Transcript of the history `messages` of `session_id`
+    pub fn from_history(session_id: &str, title: &str, messages: &[PersistedMessage]) -> Self {
+        let mut items: Vec<TranscriptItem> = Vec::new();
+        for message in messages {
+            let timestamp = message.timestamp.clone();
+            match &message.update {
+                SessionUpdate::UserMessageChunk(chunk) => {
+                    let text = content_text(&chunk.content);
+                    match items.last_mut() {
+                        Some(TranscriptItem::User { text: last, .. }) => {
+                            if !last.is_empty() && !text.is_empty() {
+                                last.push_str("\n\n");
+                            }
+                            last.push_str(&text);
+                        }
+                        _ => items.push(TranscriptItem::User { timestamp, text }),
+                    }
+                }
+                SessionUpdate::AgentMessageChunk(chunk) => {
+                    let text = content_text(&chunk.content);
+                    match items.last_mut() {
+                        Some(TranscriptItem::Agent { text: last, .. }) => last.push_str(&text),
+                        _ => items.push(TranscriptItem::Agent { timestamp, text }),
+                    }
+                }
+                SessionUpdate::AgentThoughtChunk(chunk) => {
+                    let text = content_text(&chunk.content);
+                    match items.last_mut() {
+                        Some(TranscriptItem::Thought { text: last, .. }) => last.push_str(&text),
+                        _ => items.push(TranscriptItem::Thought { timestamp, text }),
+                    }
+                }
+                SessionUpdate::ToolCall(tool_call) => items.push(TranscriptItem::ToolCall {
+                    timestamp,
+                    id: tool_call.tool_call_id.to_string(),
+                    title: tool_call.title.clone(),
+                    status: tool_call.status,
+                    outputs: outputs(&tool_call.content),
+                }),
+                SessionUpdate::ToolCallUpdate(update) => {
+                    let update_id = update.tool_call_id.to_string();
+                    let tool_call = items.iter_mut().rev().find_map(|item| match item {
+                        TranscriptItem::ToolCall {
+                            id,
+                            title,
+                            status,
+                            outputs,
+                            ..
+                        } if *id == update_id => Some((title, status, outputs)),
+                        _ => None,
+                    });
+                    // Updates of tool calls the history does not have are dropped
+                    if let Some((title, status, tool_outputs)) = tool_call {
+                        if let Some(new_title) = &update.fields.title {
+                            *title = new_title.clone();
+                        }
+                        if let Some(new_status) = update.fields.status {
+                            *status = new_status;
+                        }
+                        if let Some(content) = &update.fields.content {
+                            *tool_outputs = outputs(content);
+                        }
+                    }
+                }
+                SessionUpdate::Plan(plan) => {
+                    let entries = plan
+                        .entries
+                        .iter()
+                        .map(|entry| TranscriptPlanEntry {
+                            content: entry.content.clone(),
+                            status: entry.status.clone(),
+                        })
+                        .collect();
+                    // Plans are sent whole on every change, so only the last
+                    // of consecutive ones is kept
+                    if let Some(TranscriptItem::Plan { entries: last, .. }) = items.last_mut() {
+                        *last = entries;
+                    } else {
+                        items.push(TranscriptItem::Plan { timestamp, entries });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            session_id: session_id.to_string(),
+            title: title.to_string(),
+            exported_at: Utc::now(),
+            items,
+        }
+    }
+
+    /// The transcript as one Markdown document headed by its title
+    pub fn to_markdown(&self) -> String {
+        let mut sections = vec![format!("# {}", self.title)];
+        sections.extend(
+            self.items
+                .iter()
+                .map(TranscriptItem::to_markdown)
+                .filter(|markdown| !markdown.trim().is_empty()),
+        );
+        let mut markdown = sections.join("\n\n");
+        markdown.push('\n');
+        markdown
+    }
+
+    pub fn render(&self, format: SessionExportFormat) -> ServiceResult<String> {
+        match format {
+            SessionExportFormat::Markdown => Ok(self.to_markdown()),
+            SessionExportFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| ServiceError::serialization("Failed to serialize transcript", e)),
+        }
+    }
+}
+
+/// Text of a message chunk; attachments are reduced to a placeholder
+fn content_text(content: &ContentBlock) -> String {
+    match content {
+        ContentBlock::Text(text) => text.text.clone(),
+        ContentBlock::Image(image) => format!("[Image: {}]", image.mime_type),
+        ContentBlock::Audio(audio) => format!("[Audio: {}]", audio.mime_type),
+        ContentBlock::ResourceLink(link) => format!("[Resource: {}]", link.name),
+        ContentBlock::Resource(_) => "[Resource]".to_string(),
+        _ => String::new(),
+    }
+}
+
+fn outputs(content: &[ToolCallContent]) -> Vec<String> {
+    content
+        .iter()
+        .filter_map(|content| match content {
+            ToolCallContent::Content(content) => match &content.content {
+                ContentBlock::Text(text) => Some(text.text.clone()),
+                _ => None,
+            },
+            ToolCallContent::Terminal(terminal) => extract_terminal_output(terminal),
+            ToolCallContent::Diff(diff) => Some(diff.new_text.clone()),
+            _ => None,
+        })
+        .filter(|output| !output.trim().is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::{
+        Content, ContentChunk, Plan, PlanEntry, PlanEntryPriority, ToolCall, ToolCallId,
+        ToolCallUpdate, ToolCallUpdateFields,
+    };
+
+    fn message(update: SessionUpdate) -> PersistedMessage {
+        PersistedMessage::with_timestamp("2026-01-02T03:04:05+00:00".to_string(), update)
+    }
+
+    fn chunk(text: &str) -> ContentChunk {
+        ContentChunk::new(ContentBlock::from(text.to_string()))
+    }
+
+    fn plan(entries: &[(&str, PlanEntryStatus)]) -> SessionUpdate {
+        SessionUpdate::Plan(Plan::new(
+            entries
+                .iter()
+                .map(|(content, status)| {
+                    PlanEntry::new(
+                        content.to_string(),
+                        PlanEntryPriority::Medium,
+                        status.clone(),
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    fn history() -> Vec<PersistedMessage> {
+        let mut tool_call =
+            ToolCall::new(ToolCallId::from("t1".to_string()), "Run tests".to_string());
+        tool_call.status = ToolCallStatus::InProgress;
+        let mut fields = ToolCallUpdateFields::default();
+        fields.status = Some(ToolCallStatus::Completed);
+        fields.content = Some(vec![ToolCallContent::Content(Content::new(
+            ContentBlock::from("ok".to_string()),
+        ))]);
+        vec![
+            message(SessionUpdate::UserMessageChunk(chunk("Fix it"))),
+            message(SessionUpdate::AgentThoughtChunk(chunk("first\n"))),
+            message(SessionUpdate::AgentThoughtChunk(chunk("second"))),
+            message(plan(&[
+                ("Read", PlanEntryStatus::InProgress),
+                ("Write", PlanEntryStatus::Pending),
+            ])),
+            message(plan(&[
+                ("Read", PlanEntryStatus::Completed),
+                ("Write", PlanEntryStatus::Pending),
+            ])),
+            message(SessionUpdate::ToolCall(tool_call)),
+            message(SessionUpdate::ToolCallUpdate(ToolCallUpdate::new(
+                ToolCallId::from("t1".to_string()),
+                fields,
+            ))),
+            message(SessionUpdate::AgentMessageChunk(chunk("Do"))),
+            message(SessionUpdate::AgentMessageChunk(chunk("ne"))),
+        ]
+    }
+
+    #[test]
+    fn test_from_history() {
+        let transcript = SessionTranscript::from_history("s1", "Fix tests", &history());
+        assert_eq!(transcript.items.len(), 5);
+        assert!(matches!(
+            &transcript.items[1],
+            TranscriptItem::Thought { text, .. } if text == "first\nsecond"
+        ));
+        assert!(matches!(
+            &transcript.items[2],
+            TranscriptItem::Plan { entries, .. }
+                if entries[0].status == PlanEntryStatus::Completed
+        ));
+        assert!(matches!(
+            &transcript.items[3],
+            TranscriptItem::ToolCall { status: ToolCallStatus::Completed, outputs, .. }
+                if outputs == &vec!["ok".to_string()]
+        ));
+        assert!(matches!(
+            &transcript.items[4],
+            TranscriptItem::Agent { text, .. } if text == "Done"
+        ));
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let transcript = SessionTranscript::from_history("s1", "Fix tests", &history());
+        assert_eq!(
+            transcript.to_markdown(),
+            "# Fix tests\n\n\
+             **You**\n\nFix it\n\n\
+             *Thinking*\n\n> first\n> second\n\n\
+             **Plan**\n\n- [x] Read\n- [ ] Write\n\n\
+             **Run tests**\n\n```\nok\n```\n\n\
+             **Agent**\n\nDone\n"
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        let transcript = SessionTranscript::from_history("s1", "Fix tests", &history());
+        let json = transcript.render(SessionExportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["session_id"], "s1");
+        assert_eq!(value["items"][0]["kind"], "user");
+        assert_eq!(value["items"][2]["entries"][0]["status"], "completed");
+        assert_eq!(value["items"][3]["kind"], "tool_call");
+        assert_eq!(value["items"][3]["status"], "completed");
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(
+            SessionExportFormat::from_path(Path::new("out.JSON")),
+            SessionExportFormat::Json
+        );
+        assert_eq!(
+            SessionExportFormat::from_path(Path::new("out.txt")),
+            SessionExportFormat::Markdown
+        );
+        assert_eq!(
+            SessionExportFormat::Json.file_name("Fix the *login* bug!"),
+            "fix-the-login-bug.json"
+        );
+        assert_eq!(SessionExportFormat::Markdown.file_name("  "), "session.md");
+        assert_eq!("md".parse(), Ok(SessionExportFormat::Markdown));
+        assert!("html".parse::<SessionExportFormat>().is_err());
+    }
+}
@@ -0,0 +1,283 @@
+//! Session Limiter - Caps concurrent session work per agent
+//!
+//! Heavyweight agents can be limited to a number of concurrent session slots
+//! (`max_concurrent_sessions` in the agent config). An open session holds its
+//! slot until it is closed; session creation and prompts to sessions without a
+//! slot wait in a FIFO queue beyond the limit. Queued requests publish their
+//! position on the workspace bus so the workspace and the conversation panel
+//! can show it.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::oneshot;
+
+use crate::core::event_bus::workspace_bus::{WorkspaceUpdateBusContainer, WorkspaceUpdateEvent};
+
+/// A queued request waiting for a slot
+struct Waiter {
+    /// Session the request belongs to (None for session creation)
+    session_id: Option<String>,
+    notify: oneshot::Sender<()>,
+}
+
+#[derive(Default)]
+struct AgentSlots {
+    active: usize,
+    waiters: VecDeque<Waiter>,
+}
+
+/// Per-agent concurrency limiter with a visible FIFO queue
+#[derive(Clone, Default)]
+pub struct SessionLimiter {
+    slots: Arc<Mutex<HashMap<String, AgentSlots>>>,
+    /// Slots held by open sessions, by session ID
+    held: Arc<Mutex<HashMap<String, SessionSlot>>>,
+    workspace_bus: Option<WorkspaceUpdateBusContainer>,
+}
+
+/// Holds a slot until dropped
+pub struct SessionSlot {
+    limiter: SessionLimiter,
+    agent_name: String,
+}
+
+impl SessionLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the workspace event bus for publishing queue positions
+    pub fn set_workspace_bus(&mut self, bus: WorkspaceUpdateBusContainer) {
+        self.workspace_bus = Some(bus);
+    }
+
+    /// Acquire a slot for `agent_name`, waiting in line if `limit` slots are busy
+    ///
+    /// `limit` of `None` (or 0) means unlimited.
+    pub async fn acquire(
+        &self,
+        agent_name: &str,
+        session_id: Option<&str>,
+        limit: Option<usize>,
+    ) -> SessionSlot {
+        let limit = limit.filter(|limit| *limit > 0);
+
+        let rx = {
+            let mut slots = self.slots.lock().unwrap();
+            let agent_slots = slots.entry(agent_name.to_string()).or_default();
+
+            let has_room = limit.is_none_or(|limit| agent_slots.active < limit);
+            if has_room && agent_slots.waiters.is_empty() {
+                agent_slots.active += 1;
+                None
+            } else {
+                let (tx, rx) = oneshot::channel();
+                agent_slots.waiters.push_back(Waiter {
+                    session_id: session_id.map(str::to_string),
+                    notify: tx,
+                });
+                let position = agent_slots.waiters.len();
+                log::info!(
+                    "Agent {} is at its session limit, queued {} at position {}",
+                    agent_name,
+                    session_id.unwrap_or("new session"),
+                    position
+                );
+                self.publish_position(agent_name, session_id, position);
+                Some(rx)
+            }
+        };
+
+        if let Some(rx) = rx {
+            // The sender is only dropped after handing over a slot
+            let _ = rx.await;
+        }
+
+        SessionSlot {
+            limiter: self.clone(),
+            agent_name: agent_name.to_string(),
+        }
+    }
+
+    /// Keep `slot` until `session_id` is released with `release_session`
+    pub fn hold(&self, session_id: &str, slot: SessionSlot) {
+        // A slot taken meanwhile by another request for the session is given back
+        let previous = self
+            .held
+            .lock()
+            .unwrap()
+            .insert(session_id.to_string(), slot);
+        drop(previous);
+    }
+
+    /// Whether `session_id` holds a slot
+    pub fn holds(&self, session_id: &str) -> bool {
+        self.held.lock().unwrap().contains_key(session_id)
+    }
+
+    /// Give back the slot of a closed session
+    pub fn release_session(&self, session_id: &str) {
+        let slot = self.held.lock().unwrap().remove(session_id);
+        drop(slot);
+    }
+
+    /// Number of queued requests for an agent
+    pub fn queue_len(&self, agent_name: &str) -> usize {
+        self.slots
+            .lock()
+            .unwrap()
+            .get(agent_name)
+            .map(|slots| slots.waiters.len())
+            .unwrap_or(0)
+    }
+
+    /// Number of slots in use for an agent
+    pub fn active_count(&self, agent_name: &str) -> usize {
+        self.slots
+            .lock()
+            .unwrap()
+            .get(agent_name)
+            .map(|slots| slots.active)
+            .unwrap_or(0)
+    }
+
+    fn release(&self, agent_name: &str) {
+        let mut slots = self.slots.lock().unwrap();
+        let Some(agent_slots) = slots.get_mut(agent_name) else {
+            return;
+        };
+
+        // Hand the slot directly to the next live waiter so nobody can jump the queue
+        while let Some(waiter) = agent_slots.waiters.pop_front() {
+            if waiter.notify.send(()).is_ok() {
+                self.publish_position(agent_name, waiter.session_id.as_deref(), 0);
+                for (ix, waiter) in agent_slots.waiters.iter().enumerate() {
+                    self.publish_position(agent_name, waiter.session_id.as_deref(), ix + 1);
+                }
+                return;
+            }
+        }
+
+        agent_slots.active = agent_slots.active.saturating_sub(1);
+    }
+
+    /// Report a waiter's position: a prompt's for its session, a session
+    /// creation's for the agent
+    fn publish_position(&self, agent_name: &str, session_id: Option<&str>, position: usize) {
+        let Some(ref workspace_bus) = self.workspace_bus else {
+            return;
+        };
+        let agent_name = agent_name.to_string();
+        workspace_bus.publish(match session_id {
+            Some(session_id) => WorkspaceUpdateEvent::SessionQueueUpdated {
+                session_id: session_id.to_string(),
+                agent_name,
+                position,
+            },
+            None => WorkspaceUpdateEvent::SessionCreationQueued {
+                agent_name,
+                position,
+            },
+        });
+    }
+}
+
+impl Drop for SessionSlot {
+    fn drop(&mut self) {
+        self.limiter.release(&self.agent_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlimited_never_queues() {
+        let limiter = SessionLimiter::new();
+        smol::block_on(async {
+            let _a = limiter.acquire("agent", Some("s1"), None).await;
+            let _b = limiter.acquire("agent", Some("s2"), None).await;
+            assert_eq!(limiter.active_count("agent"), 2);
+            assert_eq!(limiter.queue_len("agent"), 0);
+        });
+    }
+
+    #[test]
+    fn test_queue_is_fifo_and_hands_over_slot() {
+        let limiter = SessionLimiter::new();
+        smol::block_on(async {
+            let first = limiter.acquire("agent", Some("s1"), Some(1)).await;
+
+            let waiting = {
+                let limiter = limiter.clone();
+                smol::spawn(async move { limiter.acquire("agent", Some("s2"), Some(1)).await })
+            };
+            while limiter.queue_len("agent") == 0 {
+                smol::future::yield_now().await;
+            }
+            assert_eq!(limiter.active_count("agent"), 1);
+
+            drop(first);
+            let second = waiting.await;
+            assert_eq!(limiter.active_count("agent"), 1);
+            assert_eq!(limiter.queue_len("agent"), 0);
+
+            drop(second);
+            assert_eq!(limiter.active_count("agent"), 0);
+        });
+    }
+
+    #[test]
+    fn test_open_sessions_hold_their_slots() {
+        let mut limiter = SessionLimiter::new();
+        let bus = WorkspaceUpdateBusContainer::new();
+        let positions = Arc::new(Mutex::new(Vec::new()));
+        bus.subscribe({
+            let positions = positions.clone();
+            move |event| {
+                if let WorkspaceUpdateEvent::SessionCreationQueued { position, .. } = event {
+                    positions.lock().unwrap().push(*position);
+                }
+            }
+        });
+        limiter.set_workspace_bus(bus);
+        smol::block_on(async {
+            for session_id in ["s1", "s2"] {
+                let slot = limiter.acquire("agent", None, Some(2)).await;
+                limiter.hold(session_id, slot);
+            }
+            assert!(limiter.holds("s1"));
+
+            let third = {
+                let limiter = limiter.clone();
+                smol::spawn(async move { limiter.acquire("agent", None, Some(2)).await })
+            };
+            while limiter.queue_len("agent") == 0 {
+                smol::future::yield_now().await;
+            }
+            assert_eq!(limiter.active_count("agent"), 2);
+            assert_eq!(*positions.lock().unwrap(), vec![1]);
+
+            limiter.release_session("s1");
+            assert!(!limiter.holds("s1"));
+            let _third = third.await;
+            assert_eq!(limiter.active_count("agent"), 2);
+            assert_eq!(*positions.lock().unwrap(), vec![1, 0]);
+        });
+    }
+
+    #[test]
+    fn test_limits_are_per_agent() {
+        let limiter = SessionLimiter::new();
+        smol::block_on(async {
+            let _a = limiter.acquire("a", None, Some(1)).await;
+            let _b = limiter.acquire("b", None, Some(1)).await;
+            assert_eq!(limiter.active_count("a"), 1);
+            assert_eq!(limiter.active_count("b"), 1);
+        });
+    }
+}
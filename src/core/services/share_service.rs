@@ -0,0 +1,673 @@
+//! Share Service - Read-only live view of sessions over HTTP
+//!
+//! "Share Live" on a task starts a small HTTP server on the local network and
+//! gives the session a random token. Anyone with the link can watch the
+//! session in a browser while the agent works: the page polls
+//! `/share/<token>/updates` for new session updates. The only thing viewers
+//! can send back is a comment on a message (`POST /share/<token>/comments`),
+//! saved next to the session and shown in its conversation. Stopping the
+//! share forgets the token, so the link stops working; the server itself
+//! stops with the last share.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use agent_client_protocol::SessionUpdate;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::core::event_bus::{
+    SessionUpdateBusContainer, SubscriptionId, WorkspaceUpdateBusContainer, WorkspaceUpdateEvent,
+};
+
+use super::http_server::{ConnectionSlot, read_head, reason_phrase};
+use super::persistence_service::{PersistedMessage, PersistenceService, SessionComment};
+
+/// Port tried first, so links stay the same across restarts; any free port
+/// is used when it is taken
+pub const DEFAULT_SHARE_PORT: u16 = 7471;
+
+/// The viewer page
+const SHARE_PAGE: &str = include_str!("share_page.html");
+
+/// Longest request body accepted, for comments
+const MAX_BODY_BYTES: usize = 16 * 1024;
+const MAX_COMMENT_CHARS: usize = 2000;
+const MAX_AUTHOR_CHARS: usize = 40;
+/// Most comments a share takes per `COMMENT_WINDOW`, from all viewers
+const MAX_COMMENTS_PER_WINDOW: usize = 10;
+const COMMENT_WINDOW: Duration = Duration::from_secs(60);
+
+/// A link to a shared session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareLink {
+    pub token: String,
+    pub url: String,
+}
+
+/// A session being shared
+struct SharedSession {
+    session_id: String,
+    title: String,
+    /// History at the time sharing started, then every live update
+    messages: Vec<PersistedMessage>,
+    comments: Vec<SessionComment>,
+    /// When the comments of the last `COMMENT_WINDOW` were posted
+    recent_comments: VecDeque<Instant>,
+    subscription: Option<SubscriptionId>,
+}
+
+type Shares = Arc<RwLock<HashMap<String, SharedSession>>>;
+
+/// What the server threads work with
+struct ServerContext {
+    shares: Shares,
+    persistence_service: Arc<PersistenceService>,
+    workspace_bus: WorkspaceUpdateBusContainer,
+}
+
+pub struct ShareService {
+    persistence_service: Arc<PersistenceService>,
+    session_bus: SessionUpdateBusContainer,
+    /// Told when a viewer comments
+    workspace_bus: WorkspaceUpdateBusContainer,
+    /// Shared sessions by token
+    shares: Shares,
+    /// The server, while any session is shared
+    server: Mutex<Option<ShareServer>>,
+}
+
+/// The running HTTP server
+struct ShareServer {
+    addr: SocketAddr,
+    /// Tells the accept loop to exit
+    stopping: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl ShareServer {
+    fn shutdown(self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag
+        let local = SocketAddr::from(([127, 0, 0, 1], self.addr.port()));
+        match TcpStream::connect_timeout(&local, Duration::from_secs(1)) {
+            Ok(_) => {
+                let _ = self.thread.join();
+                log::info!("Share server on {} stopped", self.addr);
+            }
+            Err(e) => log::warn!("Failed to stop the share server on {}: {}", self.addr, e),
+        }
+    }
+}
+
+impl ShareService {
+    pub fn new(
+        persistence_service: Arc<PersistenceService>,
+        session_bus: SessionUpdateBusContainer,
+        workspace_bus: WorkspaceUpdateBusContainer,
+    ) -> Self {
+        Self {
+            persistence_service,
+            session_bus,
+            workspace_bus,
+            shares: Arc::new(RwLock::new(HashMap::new())),
+            server: Mutex::new(None),
+        }
+    }
+
+    /// Start sharing a session, or return its link if it is already shared
+    pub async fn share(&self, session_id: &str, title: &str) -> Result<ShareLink> {
+        if let Some(link) = self.link_for(session_id) {
+            return Ok(link);
+        }
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        // The server lock is held until the share is registered, so stopping
+        // the last share meanwhile cannot shut the server down under it
+        let addr = {
+            let mut server = self.server.lock().unwrap();
+            let addr = self.ensure_server(&mut server)?;
+            self.shares.write().unwrap().insert(
+                token.clone(),
+                SharedSession {
+                    session_id: session_id.to_string(),
+                    title: title.to_string(),
+                    messages: Vec::new(),
+                    comments: Vec::new(),
+                    recent_comments: VecDeque::new(),
+                    subscription: None,
+                },
+            );
+            addr
+        };
+
+        // Subscribe before reading the history so no update falls between
+        // the two; updates received meanwhile are kept after the history
+        let subscription = {
+            let (shares, token) = (self.shares.clone(), token.clone());
+            self.session_bus
+                .subscribe_session(session_id.to_string(), move |event| {
+                    if let Some(share) = shares.write().unwrap().get_mut(&token) {
+                        share
+                            .messages
+                            .push(PersistedMessage::new((*event.update).clone()));
+                    }
+                })
+        };
+        if let Err(e) = self.persistence_service.flush_session(session_id).await {
+            log::warn!(
+                "Failed to flush session {} before sharing: {}",
+                session_id,
+                e
+            );
+        }
+        let history = self
+            .persistence_service
+            .load_messages(session_id)
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!(
+                    "Failed to load history of {} for sharing: {}",
+                    session_id,
+                    e
+                );
+                Vec::new()
+            });
+        let comments = self
+            .persistence_service
+            .load_comments(session_id)
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to load comments of {}: {}", session_id, e);
+                Vec::new()
+            });
+
+        match self.shares.write().unwrap().get_mut(&token) {
+            Some(share) => {
+                share.messages.splice(0..0, history);
+                share.comments = comments;
+                share.subscription = Some(subscription);
+            }
+            // Stopped while the history was loading
+            None => {
+                self.session_bus.unsubscribe(subscription);
+            }
+        }
+
+        log::info!("Sharing session {} on {}", session_id, addr);
+        Ok(ShareLink {
+            url: share_url(addr, &token),
+            token,
+        })
+    }
+
+    /// Stop sharing a session; its link stops working
+    pub fn stop(&self, session_id: &str) -> bool {
+        let removed: Vec<SharedSession> = {
+            let mut shares = self.shares.write().unwrap();
+            let tokens: Vec<String> = shares
+                .iter()
+                .filter(|(_, share)| share.session_id == session_id)
+                .map(|(token, _)| token.clone())
+                .collect();
+            tokens
+                .iter()
+                .filter_map(|token| shares.remove(token))
+                .collect()
+        };
+        for share in &removed {
+            if let Some(subscription) = share.subscription {
+                self.session_bus.unsubscribe(subscription);
+            }
+        }
+        self.stop_server_if_unused();
+        !removed.is_empty()
+    }
+
+    /// Shut the server down once nothing is shared
+    fn stop_server_if_unused(&self) {
+        let mut server = self.server.lock().unwrap();
+        if self.shares.read().unwrap().is_empty()
+            && let Some(server) = server.take()
+        {
+            server.shutdown();
+        }
+    }
+
+    /// The link of a shared session
+    pub fn link_for(&self, session_id: &str) -> Option<ShareLink> {
+        let addr = self.server.lock().unwrap().as_ref()?.addr;
+        self.shares
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(_, share)| share.session_id == session_id)
+            .map(|(token, _)| ShareLink {
+                url: share_url(addr, token),
+                token: token.clone(),
+            })
+    }
+
+    pub fn is_shared(&self, session_id: &str) -> bool {
+        self.shares
+            .read()
+            .unwrap()
+            .values()
+            .any(|share| share.session_id == session_id)
+    }
+
+    /// Start the HTTP server on first use
+    fn ensure_server(&self, server: &mut Option<ShareServer>) -> Result<SocketAddr> {
+        if let Some(server) = server {
+            return Ok(server.addr);
+        }
+        let listener = TcpListener::bind(("0.0.0.0", DEFAULT_SHARE_PORT))
+            .or_else(|_| TcpListener::bind(("0.0.0.0", 0)))
+            .context("Failed to start the share server")?;
+        let addr = listener.local_addr()?;
+        let context = Arc::new(ServerContext {
+            shares: self.shares.clone(),
+            persistence_service: self.persistence_service.clone(),
+            workspace_bus: self.workspace_bus.clone(),
+        });
+        let stopping = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let stopping = stopping.clone();
+            let active = Arc::new(AtomicUsize::new(0));
+            std::thread::Builder::new()
+                .name("share-server".into())
+                .spawn(move || {
+                    for stream in listener.incoming() {
+                        if stopping.load(Ordering::SeqCst) {
+                            break;
+                        }
+                        let Ok(stream) = stream else { continue };
+                        let Some(slot) = ConnectionSlot::acquire(&active) else {
+                            log::debug!("Share server busy, closing a connection");
+                            continue;
+                        };
+                        let context = context.clone();
+                        std::thread::spawn(move || {
+                            let _slot = slot;
+                            if let Err(e) = handle_connection(stream, &context) {
+                                log::debug!("Share server connection failed: {}", e);
+                            }
+                        });
+                    }
+                })?
+        };
+        log::info!("Share server listening on {}", addr);
+        *server = Some(ShareServer {
+            addr,
+            stopping,
+            thread,
+        });
+        Ok(addr)
+    }
+}
+
+/// Link to a share, on this machine's address in the local network
+fn share_url(addr: SocketAddr, token: &str) -> String {
+    let host = local_ip().unwrap_or_else(|| "127.0.0.1".to_string());
+    format!("http://{}:{}/share/{}", host, addr.port(), token)
+}
+
+/// Address of the interface used to reach other machines; connecting a UDP
+/// socket only picks the route, nothing is sent
+fn local_ip() -> Option<String> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(("8.8.8.8", 80)).ok()?;
+    Some(socket.local_addr().ok()?.ip().to_string())
+}
+
+/// What a request asks for
+#[derive(Debug, PartialEq, Eq)]
+enum Route<'a> {
+    /// The viewer page
+    Page(&'a str),
+    /// Updates from index `after` on
+    Updates { token: &'a str, after: usize },
+    /// A new comment, in the body
+    Comment(&'a str),
+}
+
+/// Parse a request line like `GET /share/<token>/updates?after=3 HTTP/1.1`
+fn parse_route(request_line: &str) -> std::result::Result<Route<'_>, u16> {
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(400);
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let rest = path.strip_prefix("/share/").ok_or(404u16)?;
+    match (method, rest.split_once('/')) {
+        ("POST", Some((token, "comments"))) if !token.is_empty() => {
+            return Ok(Route::Comment(token));
+        }
+        ("GET", _) => {}
+        _ => return Err(405),
+    }
+    match rest.split_once('/') {
+        None if !rest.is_empty() => Ok(Route::Page(rest)),
+        Some((token, "updates")) if !token.is_empty() => {
+            let after = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("after="))
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0);
+            Ok(Route::Updates { token, after })
+        }
+        _ => Err(404),
+    }
+}
+
+/// Body of `/share/<token>/updates`
+#[derive(Serialize)]
+struct UpdatesBody<'a> {
+    title: &'a str,
+    messages: &'a [PersistedMessage],
+    /// Index to ask for next
+    next: usize,
+    /// All comments, as viewers may add some at any time
+    comments: &'a [SessionComment],
+}
+
+/// Body of `POST /share/<token>/comments`
+#[derive(Deserialize)]
+struct CommentRequest {
+    message_index: usize,
+    #[serde(default)]
+    author: String,
+    text: String,
+}
+
+/// Read a comment from a request body
+fn parse_comment(body: &[u8]) -> Option<SessionComment> {
+    let request: CommentRequest = serde_json::from_slice(body).ok()?;
+    let text = request.text.trim();
+    if text.is_empty() || text.chars().count() > MAX_COMMENT_CHARS {
+        return None;
+    }
+    let author: String = request
+        .author
+        .trim()
+        .chars()
+        .take(MAX_AUTHOR_CHARS)
+        .collect();
+    let author = if author.is_empty() { "Viewer" } else { &author };
+    Some(SessionComment::new(request.message_index, author, text))
+}
+
+/// Number of user and agent messages in `messages`, counted like
+/// `SessionComment::message_index`: adjacent chunks of one kind are one message
+fn message_count(messages: &[PersistedMessage]) -> usize {
+    let mut count = 0;
+    let mut previous = None;
+    for message in messages {
+        let kind = match message.update {
+            SessionUpdate::UserMessageChunk(_) => Some(true),
+            SessionUpdate::AgentMessageChunk(_) => Some(false),
+            _ => None,
+        };
+        if kind.is_some() && kind != previous {
+            count += 1;
+        }
+        previous = kind;
+    }
+    count
+}
+
+/// Record a comment posted at `now`, unless `MAX_COMMENTS_PER_WINDOW` were
+/// already posted within `COMMENT_WINDOW`
+fn allow_comment(recent: &mut VecDeque<Instant>, now: Instant) -> bool {
+    while recent
+        .front()
+        .is_some_and(|posted| now.duration_since(*posted) >= COMMENT_WINDOW)
+    {
+        recent.pop_front();
+    }
+    if recent.len() >= MAX_COMMENTS_PER_WINDOW {
+        return false;
+    }
+    recent.push_back(now);
+    true
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn json(body: &impl Serialize) -> Self {
+        match serde_json::to_string(body) {
+            Ok(body) => Self {
+                status: 200,
+                content_type: "application/json",
+                body,
+            },
+            Err(_) => Self::error(500),
+        }
+    }
+
+    fn error(status: u16) -> Self {
+        let body = match status {
+            404 => "This share has ended or the link is wrong.",
+            405 => "This view is read-only.",
+            413 => "Request too large.",
+            429 => "Too many comments, try again in a minute.",
+            431 => "Request headers too large.",
+            500 => "Something went wrong.",
+            _ => "Bad request.",
+        };
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.to_string(),
+        }
+    }
+}
+
+/// Answer a request for the page or for updates
+fn respond(route: Route<'_>, shares: &Shares) -> Response {
+    let shares = shares.read().unwrap();
+    match route {
+        Route::Page(token) if shares.contains_key(token) => Response {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: SHARE_PAGE.to_string(),
+        },
+        Route::Updates { token, after } => match shares.get(token) {
+            Some(share) => {
+                let after = after.min(share.messages.len());
+                Response::json(&UpdatesBody {
+                    title: &share.title,
+                    messages: &share.messages[after..],
+                    next: share.messages.len(),
+                    comments: &share.comments,
+                })
+            }
+            None => Response::error(404),
+        },
+        _ => Response::error(404),
+    }
+}
+
+/// Save a viewer's comment and tell the app about it
+fn post_comment(token: &str, body: &[u8], context: &ServerContext) -> Response {
+    let Some(comment) = parse_comment(body) else {
+        return Response::error(400);
+    };
+    let session_id = match context.shares.write().unwrap().get_mut(token) {
+        Some(share) if comment.message_index >= message_count(&share.messages) => {
+            return Response::error(400);
+        }
+        Some(share) if !allow_comment(&mut share.recent_comments, Instant::now()) => {
+            return Response::error(429);
+        }
+        Some(share) => share.session_id.clone(),
+        None => return Response::error(404),
+    };
+    let saved = smol::block_on(
+        context
+            .persistence_service
+            .add_comment(&session_id, comment.clone()),
+    );
+    if let Err(e) = saved {
+        log::error!("Failed to save comment on {}: {}", session_id, e);
+        return Response::error(500);
+    }
+    if let Some(share) = context.shares.write().unwrap().get_mut(token) {
+        share.comments.push(comment.clone());
+    }
+    context
+        .workspace_bus
+        .publish(WorkspaceUpdateEvent::CommentsUpdated { session_id });
+    Response::json(&comment)
+}
+
+fn handle_connection(stream: TcpStream, context: &ServerContext) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(10)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match read_head(&mut reader)? {
+        Ok(head) => match parse_route(&head.request_line) {
+            Ok(Route::Comment(_)) if head.content_length() > MAX_BODY_BYTES => Response::error(413),
+            Ok(Route::Comment(token)) => {
+                let mut body = vec![0; head.content_length()];
+                reader.read_exact(&mut body)?;
+                post_comment(token, &body, context)
+            }
+            Ok(route) => respond(route, &context.shares),
+            Err(status) => Response::error(status),
+        },
+        Err(status) => Response::error(status),
+    };
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nReferrer-Policy: no-referrer\r\n\
+         X-Robots-Tag: noindex\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.content_type,
+        response.body.len()
+    )?;
+    stream.write_all(response.body.as_bytes())?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use agent_client_protocol::{ContentBlock, ContentChunk};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_route() {
+        assert_eq!(
+            parse_route("GET /share/abc HTTP/1.1"),
+            Ok(Route::Page("abc"))
+        );
+        assert_eq!(
+            parse_route("GET /share/abc/updates?after=12 HTTP/1.1"),
+            Ok(Route::Updates {
+                token: "abc",
+                after: 12
+            })
+        );
+        assert_eq!(
+            parse_route("GET /share/abc/updates HTTP/1.1"),
+            Ok(Route::Updates {
+                token: "abc",
+                after: 0
+            })
+        );
+        assert_eq!(
+            parse_route("POST /share/abc/comments HTTP/1.1"),
+            Ok(Route::Comment("abc"))
+        );
+        assert_eq!(parse_route("POST /share/abc HTTP/1.1"), Err(405));
+        assert_eq!(parse_route("GET /share/abc/comments HTTP/1.1"), Err(404));
+        assert_eq!(parse_route("GET /share/ HTTP/1.1"), Err(404));
+        assert_eq!(parse_route("GET /other HTTP/1.1"), Err(404));
+        assert_eq!(parse_route(""), Err(400));
+    }
+
+    #[test]
+    fn test_respond_requires_token() {
+        let shares: Shares = Arc::new(RwLock::new(HashMap::new()));
+        shares.write().unwrap().insert(
+            "token".to_string(),
+            SharedSession {
+                session_id: "session".to_string(),
+                title: "Task".to_string(),
+                messages: Vec::new(),
+                comments: Vec::new(),
+                recent_comments: VecDeque::new(),
+                subscription: None,
+            },
+        );
+
+        assert_eq!(respond(Route::Page("token"), &shares).status, 200);
+        assert_eq!(respond(Route::Page("other"), &shares).status, 404);
+        let updates = respond(
+            Route::Updates {
+                token: "token",
+                after: 5,
+            },
+            &shares,
+        );
+        assert_eq!(updates.status, 200);
+        assert!(updates.body.contains("\"next\":0"));
+    }
+
+    #[test]
+    fn test_message_count() {
+        let text = |text: &str| ContentChunk::new(ContentBlock::from(text.to_string()));
+        let messages: Vec<_> = [
+            SessionUpdate::UserMessageChunk(text("Fix")),
+            SessionUpdate::UserMessageChunk(text(" it")),
+            SessionUpdate::AgentMessageChunk(text("On")),
+            SessionUpdate::AgentThoughtChunk(text("hmm")),
+            SessionUpdate::AgentMessageChunk(text("Done")),
+        ]
+        .into_iter()
+        .map(PersistedMessage::new)
+        .collect();
+        assert_eq!(message_count(&messages), 3);
+        assert_eq!(message_count(&[]), 0);
+    }
+
+    #[test]
+    fn test_allow_comment() {
+        let start = Instant::now();
+        let mut recent = VecDeque::new();
+        for _ in 0..MAX_COMMENTS_PER_WINDOW {
+            assert!(allow_comment(&mut recent, start));
+        }
+        assert!(!allow_comment(&mut recent, start + Duration::from_secs(30)));
+        assert!(allow_comment(&mut recent, start + COMMENT_WINDOW));
+    }
+
+    #[test]
+    fn test_parse_comment() {
+        let comment =
+            parse_comment(br#"{"message_index": 2, "author": " Ana ", "text": " Looks off "}"#)
+                .unwrap();
+        assert_eq!(comment.message_index, 2);
+        assert_eq!(comment.author, "Ana");
+        assert_eq!(comment.text, "Looks off");
+
+        let anonymous = parse_comment(br#"{"message_index": 0, "text": "Hi"}"#).unwrap();
+        assert_eq!(anonymous.author, "Viewer");
+
+        assert!(parse_comment(br#"{"message_index": 0, "text": "  "}"#).is_none());
+        assert!(parse_comment(b"not json").is_none());
+    }
+}
@@ -0,0 +1,456 @@
+//! Symbol Index - Definition-level code context via tree-sitter
+//!
+//! Attaching whole files to a prompt wastes context on large codebases. The
+//! index parses the workspace's source files with tree-sitter and records where
+//! each function, type and module is defined, so an `@symbol` mention in a
+//! prompt can attach just that definition. Files are re-parsed only when their
+//! modification time changes, so refreshing before every lookup stays cheap.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+use tree_sitter::{Language, Parser, Query, QueryCursor, StreamingIterator};
+
+use super::error::{ServiceError, ServiceResult};
+use crate::utils::{context_item::ContextItem, path_filter::PathFilter};
+
+/// Files larger than this are not indexed (generated code, bundles)
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// At most this many definitions are attached for one prompt
+const MAX_ATTACHED_DEFINITIONS: usize = 8;
+/// Ambiguous names attach at most this many definitions each
+const MAX_DEFINITIONS_PER_NAME: usize = 3;
+
+const RUST_QUERY: &str = r#"
+(function_item name: (identifier) @name) @definition.function
+(function_signature_item name: (identifier) @name) @definition.function
+(struct_item name: (type_identifier) @name) @definition.struct
+(enum_item name: (type_identifier) @name) @definition.enum
+(union_item name: (type_identifier) @name) @definition.struct
+(trait_item name: (type_identifier) @name) @definition.trait
+(type_item name: (type_identifier) @name) @definition.type
+(mod_item name: (identifier) @name) @definition.module
+(macro_definition name: (identifier) @name) @definition.macro
+"#;
+
+const PYTHON_QUERY: &str = r#"
+(function_definition name: (identifier) @name) @definition.function
+(class_definition name: (identifier) @name) @definition.class
+"#;
+
+const JAVASCRIPT_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @definition.function
+(generator_function_declaration name: (identifier) @name) @definition.function
+(class_declaration name: (_) @name) @definition.class
+(method_definition name: (property_identifier) @name) @definition.function
+"#;
+
+const TYPESCRIPT_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @definition.function
+(class_declaration name: (_) @name) @definition.class
+(abstract_class_declaration name: (_) @name) @definition.class
+(method_definition name: (property_identifier) @name) @definition.function
+(interface_declaration name: (type_identifier) @name) @definition.interface
+(type_alias_declaration name: (type_identifier) @name) @definition.type
+(enum_declaration name: (identifier) @name) @definition.enum
+"#;
+
+const GO_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @definition.function
+(method_declaration name: (field_identifier) @name) @definition.function
+(type_spec name: (type_identifier) @name) @definition.type
+"#;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Struct,
+    Enum,
+    Trait,
+    Class,
+    Interface,
+    Type,
+    Module,
+    Macro,
+}
+
+impl SymbolKind {
+    fn from_capture(name: &str) -> Option<Self> {
+        Some(match name.strip_prefix("definition.")? {
+            "function" => Self::Function,
+            "struct" => Self::Struct,
+            "enum" => Self::Enum,
+            "trait" => Self::Trait,
+            "class" => Self::Class,
+            "interface" => Self::Interface,
+            "type" => Self::Type,
+            "module" => Self::Module,
+            "macro" => Self::Macro,
+            _ => return None,
+        })
+    }
+
+    /// Keyword-style label used in summaries, e.g. `fn` or `struct`
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Function => "fn",
+            Self::Struct => "struct",
+            Self::Enum => "enum",
+            Self::Trait => "trait",
+            Self::Class => "class",
+            Self::Interface => "interface",
+            Self::Type => "type",
+            Self::Module => "mod",
+            Self::Macro => "macro",
+        }
+    }
+}
+
+/// A definition found in a source file
+#[derive(Clone, Debug, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub path: PathBuf,
+    /// 1-based, inclusive
+    pub start_line: u32,
+    pub end_line: u32,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    /// Visible outside its module (`pub`, exported, capitalized, not `_private`)
+    pub public: bool,
+}
+
+struct IndexedFile {
+    modified: SystemTime,
+    symbols: Vec<Symbol>,
+}
+
+/// Definitions of every supported source file below a workspace root
+pub struct SymbolIndex {
+    filter: PathFilter,
+    files: RwLock<HashMap<PathBuf, IndexedFile>>,
+}
+
+impl SymbolIndex {
+    pub fn new(filter: PathFilter) -> Arc<Self> {
+        Arc::new(Self {
+            filter,
+            files: RwLock::new(HashMap::new()),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        self.filter.root()
+    }
+
+    /// Paths skipped when scanning the workspace
+    pub fn filter(&self) -> &PathFilter {
+        &self.filter
+    }
+
+    /// Re-parse changed files and drop deleted ones; returns how many were parsed
+    ///
+    /// Blocking: call it from a background thread (e.g. `smol::unblock`).
+    pub fn refresh(&self) -> usize {
+        let mut sources = Vec::new();
+        collect_source_files(&self.filter, self.root(), &mut sources);
+
+        let stale: Vec<(PathBuf, SystemTime)> = {
+            let files = self.files.read().unwrap();
+            sources
+                .iter()
+                .filter(|(path, modified)| {
+                    files.get(path).is_none_or(|file| file.modified != *modified)
+                })
+                .cloned()
+                .collect()
+        };
+
+        let parsed: Vec<(PathBuf, IndexedFile)> = stale
+            .into_iter()
+            .filter_map(|(path, modified)| {
+                let source = std::fs::read_to_string(&path).ok()?;
+                let symbols = parse_symbols(&path, &source);
+                Some((path, IndexedFile { modified, symbols }))
+            })
+            .collect();
+        let count = parsed.len();
+
+        let mut files = self.files.write().unwrap();
+        files.retain(|path, _| sources.iter().any(|(source, _)| source == path));
+        files.extend(parsed);
+        if count > 0 {
+            log::debug!(
+                "[SymbolIndex] Parsed {} files under {:?} ({} indexed)",
+                count,
+                self.root(),
+                files.len()
+            );
+        }
+        count
+    }
+
+    /// Definitions named exactly `name`
+    pub fn lookup(&self, name: &str) -> Vec<Symbol> {
+        let files = self.files.read().unwrap();
+        let mut symbols: Vec<Symbol> = files
+            .values()
+            .flat_map(|file| file.symbols.iter())
+            .filter(|symbol| symbol.name == name)
+            .cloned()
+            .collect();
+        symbols.sort_by(|a, b| a.path.cmp(&b.path).then(a.start_line.cmp(&b.start_line)));
+        symbols
+    }
+
+    /// Public definitions of every indexed file, ordered by path and line
+    pub fn public_symbols(&self) -> Vec<Symbol> {
+        let files = self.files.read().unwrap();
+        let mut symbols: Vec<Symbol> = files
+            .values()
+            .flat_map(|file| file.symbols.iter())
+            .filter(|symbol| symbol.public)
+            .cloned()
+            .collect();
+        symbols.sort_by(|a, b| a.path.cmp(&b.path).then(a.start_line.cmp(&b.start_line)));
+        symbols
+    }
+
+    /// Source text of a definition
+    pub fn definition(&self, symbol: &Symbol) -> ServiceResult<String> {
+        let source = std::fs::read_to_string(&symbol.path)
+            .map_err(|e| ServiceError::io("Failed to read symbol source", e))?;
+        source
+            .get(symbol.start_byte..symbol.end_byte)
+            .map(str::to_string)
+            .ok_or_else(|| {
+                ServiceError::InvalidInput(format!(
+                    "{} changed since it was indexed",
+                    symbol.path.display()
+                ))
+            })
+    }
+
+    /// Definitions for the `@symbol` mentions in a prompt, as context items
+    ///
+    /// Blocking: refreshes the index first.
+    pub fn resolve_mentions(&self, text: &str) -> Vec<ContextItem> {
+        let mentions = symbol_mentions(text);
+        if mentions.is_empty() {
+            return Vec::new();
+        }
+        self.refresh();
+
+        mentions
+            .into_iter()
+            .flat_map(|name| {
+                self.lookup(name)
+                    .into_iter()
+                    .take(MAX_DEFINITIONS_PER_NAME)
+            })
+            .filter_map(|symbol| {
+                let content = self.definition(&symbol).ok()?;
+                Some(ContextItem::Selection {
+                    file_path: symbol.path.to_string_lossy().to_string(),
+                    start_line: symbol.start_line,
+                    end_line: symbol.end_line,
+                    content,
+                })
+            })
+            .take(MAX_ATTACHED_DEFINITIONS)
+            .collect()
+    }
+}
+
+/// Grammar and definitions query for a file extension
+fn grammar(path: &Path) -> Option<(Language, &'static str)> {
+    let extension = path.extension()?.to_str()?;
+    Some(match extension {
+        "rs" => (tree_sitter_rust::LANGUAGE.into(), RUST_QUERY),
+        "py" => (tree_sitter_python::LANGUAGE.into(), PYTHON_QUERY),
+        "js" | "jsx" | "mjs" | "cjs" => {
+            (tree_sitter_javascript::LANGUAGE.into(), JAVASCRIPT_QUERY)
+        }
+        "ts" => (
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            TYPESCRIPT_QUERY,
+        ),
+        "tsx" => (tree_sitter_typescript::LANGUAGE_TSX.into(), TYPESCRIPT_QUERY),
+        "go" => (tree_sitter_go::LANGUAGE.into(), GO_QUERY),
+        _ => return None,
+    })
+}
+
+/// Extract the definitions in one source file
+pub fn parse_symbols(path: &Path, source: &str) -> Vec<Symbol> {
+    let Some((language, query_source)) = grammar(path) else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(&language).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+    let query = match Query::new(&language, query_source) {
+        Ok(query) => query,
+        Err(e) => {
+            log::warn!("[SymbolIndex] Invalid query for {:?}: {}", path, e);
+            return Vec::new();
+        }
+    };
+    let capture_names = query.capture_names();
+
+    let mut symbols = Vec::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+    while let Some(m) = matches.next() {
+        let mut name = None;
+        let mut definition = None;
+        for capture in m.captures {
+            let capture_name = capture_names[capture.index as usize];
+            if capture_name == "name" {
+                name = capture.node.utf8_text(source.as_bytes()).ok();
+            } else if let Some(kind) = SymbolKind::from_capture(capture_name) {
+                definition = Some((kind, capture.node));
+            }
+        }
+        if let (Some(name), Some((kind, node))) = (name, definition) {
+            let public = is_public(path, name, &node, source);
+            symbols.push(Symbol {
+                name: name.to_string(),
+                kind,
+                path: path.to_path_buf(),
+                start_line: node.start_position().row as u32 + 1,
+                end_line: node.end_position().row as u32 + 1,
+                start_byte: node.start_byte(),
+                end_byte: node.end_byte(),
+                public,
+            });
+        }
+    }
+    symbols
+}
+
+/// Whether a definition is part of its file's public surface
+///
+/// Follows each language's convention: `pub` items in Rust, exported
+/// declarations in JavaScript/TypeScript, capitalized names in Go and names
+/// without a leading underscore in Python.
+fn is_public(path: &Path, name: &str, node: &tree_sitter::Node, source: &str) -> bool {
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    match extension {
+        "rs" => source[node.start_byte()..].starts_with("pub"),
+        "py" => !name.starts_with('_'),
+        "go" => name.starts_with(|c: char| c.is_uppercase()),
+        _ => node
+            .parent()
+            .is_some_and(|parent| parent.kind() == "export_statement"),
+    }
+}
+
+/// Names mentioned as `@symbol` in a prompt
+///
+/// File mentions (`@src/main.rs`) and e-mail addresses are not symbols.
+pub fn symbol_mentions(text: &str) -> Vec<&str> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut mentions = Vec::new();
+
+    for (index, _) in text.match_indices('@') {
+        let preceded_by_word = text[..index].chars().next_back().is_some_and(is_ident);
+        if preceded_by_word {
+            continue;
+        }
+        let rest = &text[index + 1..];
+        let end = rest.find(|c: char| !is_ident(c)).unwrap_or(rest.len());
+        let name = &rest[..end];
+        let path_like = rest[end..]
+            .strip_prefix(['/', '.', '\\'])
+            .is_some_and(|after| after.starts_with(is_ident));
+        if name.is_empty() || path_like || name.starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+        if !mentions.contains(&name) {
+            mentions.push(name);
+        }
+    }
+    mentions
+}
+
+fn collect_source_files(filter: &PathFilter, dir: &Path, files: &mut Vec<(PathBuf, SystemTime)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if filter.is_excluded(&path) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_source_files(filter, &path, files);
+        } else if metadata.len() <= MAX_FILE_BYTES
+            && grammar(&path).is_some()
+            && let Ok(modified) = metadata.modified()
+        {
+            files.push((path, modified));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rust_definitions() {
+        let source = concat!(
+            "pub struct Config {\n",
+            "    port: u16,\n",
+            "}\n",
+            "\n",
+            "impl Config {\n",
+            "    fn port(&self) -> u16 {\n",
+            "        self.port\n",
+            "    }\n",
+            "}\n",
+        );
+        let symbols = parse_symbols(Path::new("lib.rs"), source);
+
+        let config = symbols.iter().find(|s| s.name == "Config").unwrap();
+        assert_eq!(config.kind, SymbolKind::Struct);
+        assert_eq!((config.start_line, config.end_line), (1, 3));
+        assert!(config.public);
+
+        let port = symbols.iter().find(|s| s.name == "port").unwrap();
+        assert_eq!(port.kind, SymbolKind::Function);
+        assert_eq!(
+            &source[port.start_byte..port.end_byte],
+            "fn port(&self) -> u16 {\n        self.port\n    }"
+        );
+        assert!(!port.public);
+    }
+
+    #[test]
+    fn test_unsupported_files_have_no_symbols() {
+        assert!(parse_symbols(Path::new("notes.txt"), "fn main() {}").is_empty());
+    }
+
+    #[test]
+    fn test_symbol_mentions() {
+        assert_eq!(
+            symbol_mentions("explain @parse_symbols and @Config, mail a@b.com"),
+            vec!["parse_symbols", "Config"]
+        );
+        assert!(symbol_mentions("look at @src/main.rs and @README.md").is_empty());
+        assert_eq!(symbol_mentions("@Config vs @Config"), vec!["Config"]);
+    }
+}
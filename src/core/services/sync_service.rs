@@ -0,0 +1,694 @@
+//! Sync Service - Settings, prompt templates and memories across machines
+//!
+//! Synced items are kept as one JSON document, `agentx-sync.json`, in a
+//! folder synced by another tool or on a WebDAV server:
+//!
+//! - `command/<name>`: prompt templates (`commands` in config.json)
+//! - `system_prompt/<key>`: system prompts of AI features
+//! - `setting/permission_policies`, `setting/command_policy`
+//! - `memory/<workspace name>/<id>`: workspace memories, matched by name as
+//!   paths differ between machines
+//!
+//! Agents, models, MCP servers and the proxy are never synced, as they carry
+//! API keys and machine-specific paths.
+//!
+//! Every item carries the time and machine of its last change; deletions
+//! stay as tombstones. A sync merges the remote document with the local one
+//! against the document of the previous sync: items changed on one side
+//! only take that side, items changed on both sides take the newest change.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::config::{Config, SyncBackendKind, SyncConfig};
+use crate::schemas::workspace_schema::{Workspace, WorkspaceMemory};
+
+use super::ai_service::runtime_handle;
+use super::{AgentConfigService, WorkspaceService};
+
+/// Name of the synced document in the folder or WebDAV collection
+pub const SYNC_FILE_NAME: &str = "agentx-sync.json";
+
+const COMMAND_PREFIX: &str = "command/";
+const SYSTEM_PROMPT_PREFIX: &str = "system_prompt/";
+const MEMORY_PREFIX: &str = "memory/";
+const PERMISSION_POLICIES_KEY: &str = "setting/permission_policies";
+const COMMAND_POLICY_KEY: &str = "setting/command_policy";
+
+/// Last change of a synced item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncEntry {
+    /// `None` once the item was deleted, so the deletion syncs as well
+    #[serde(default)]
+    pub value: Option<Value>,
+    pub modified_at: DateTime<Utc>,
+    /// Machine that made the change
+    pub machine: String,
+}
+
+impl SyncEntry {
+    /// Whether `self` wins over `other`; ties go to the larger machine id so
+    /// every machine picks the same entry
+    fn is_newer_than(&self, other: &SyncEntry) -> bool {
+        (self.modified_at, &self.machine) > (other.modified_at, &other.machine)
+    }
+}
+
+/// All synced items
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncDocument {
+    #[serde(default)]
+    pub entries: BTreeMap<String, SyncEntry>,
+}
+
+impl SyncDocument {
+    /// `self` updated with the local `current` values: changed and new items
+    /// are stamped with `now`, removed ones become tombstones
+    ///
+    /// Keys for which `is_local` is false (e.g. memories of workspaces this
+    /// machine does not have) are not compared and keep their entries.
+    pub fn stamp(
+        &self,
+        current: &BTreeMap<String, Value>,
+        is_local: impl Fn(&str) -> bool,
+        machine: &str,
+        now: DateTime<Utc>,
+    ) -> SyncDocument {
+        let changed = |value: Option<Value>| SyncEntry {
+            value,
+            modified_at: now,
+            machine: machine.to_string(),
+        };
+        let mut entries = BTreeMap::new();
+        for (key, entry) in &self.entries {
+            if !is_local(key.as_str()) {
+                entries.insert(key.clone(), entry.clone());
+                continue;
+            }
+            let entry = match current.get(key) {
+                Some(value) if entry.value.as_ref() == Some(value) => entry.clone(),
+                Some(value) => changed(Some(value.clone())),
+                None if entry.value.is_none() => entry.clone(),
+                None => changed(None),
+            };
+            entries.insert(key.clone(), entry);
+        }
+        for (key, value) in current {
+            if !entries.contains_key(key) {
+                entries.insert(key.clone(), changed(Some(value.clone())));
+            }
+        }
+        SyncDocument { entries }
+    }
+
+    /// Values of the items that were not deleted
+    pub fn values(&self) -> BTreeMap<String, Value> {
+        self.entries
+            .iter()
+            .filter_map(|(key, entry)| Some((key.clone(), entry.value.clone()?)))
+            .collect()
+    }
+}
+
+/// Result of merging two documents
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeOutcome {
+    pub document: SyncDocument,
+    /// Items changed on both sides, resolved to the newest change
+    pub conflicts: Vec<String>,
+}
+
+/// Merge the `local` and `remote` documents that both started from `base`
+pub fn merge(base: &SyncDocument, local: &SyncDocument, remote: &SyncDocument) -> MergeOutcome {
+    let mut entries = BTreeMap::new();
+    let mut conflicts = Vec::new();
+    let keys: std::collections::BTreeSet<&String> =
+        local.entries.keys().chain(remote.entries.keys()).collect();
+
+    for key in keys {
+        let entry = match (local.entries.get(key), remote.entries.get(key)) {
+            (Some(local), None) => local.clone(),
+            (None, Some(remote)) => remote.clone(),
+            (Some(local), Some(remote)) => {
+                let base = base.entries.get(key);
+                let local_changed = base != Some(local);
+                let remote_changed = base != Some(remote);
+                // Times only decide between two changes; clocks of
+                // different machines may disagree
+                if !local_changed {
+                    remote.clone()
+                } else if !remote_changed {
+                    local.clone()
+                } else {
+                    if local.value != remote.value {
+                        conflicts.push(key.clone());
+                    }
+                    if remote.is_newer_than(local) {
+                        remote.clone()
+                    } else {
+                        local.clone()
+                    }
+                }
+            }
+            (None, None) => continue,
+        };
+        entries.insert(key.clone(), entry);
+    }
+
+    MergeOutcome {
+        document: SyncDocument { entries },
+        conflicts,
+    }
+}
+
+/// Synced values of the local configuration and workspaces
+pub fn local_values(config: &Config, workspaces: &[Workspace]) -> BTreeMap<String, Value> {
+    let mut values = BTreeMap::new();
+    let mut insert = |key: String, value: Result<Value, serde_json::Error>| match value {
+        Ok(value) => {
+            values.insert(key, value);
+        }
+        Err(e) => log::warn!("Not syncing {}: {}", key, e),
+    };
+    for (name, command) in &config.commands {
+        insert(
+            format!("{}{}", COMMAND_PREFIX, name),
+            serde_json::to_value(command),
+        );
+    }
+    for (key, prompt) in &config.system_prompts {
+        insert(
+            format!("{}{}", SYSTEM_PROMPT_PREFIX, key),
+            serde_json::to_value(prompt),
+        );
+    }
+    insert(
+        PERMISSION_POLICIES_KEY.to_string(),
+        serde_json::to_value(&config.permission_policies),
+    );
+    insert(
+        COMMAND_POLICY_KEY.to_string(),
+        serde_json::to_value(&config.command_policy),
+    );
+    for workspace in workspaces {
+        for memory in &workspace.memories {
+            insert(
+                memory_key(&workspace.name, &memory.id),
+                serde_json::to_value(memory),
+            );
+        }
+    }
+    values
+}
+
+fn memory_key(workspace_name: &str, memory_id: &str) -> String {
+    format!("{}{}/{}", MEMORY_PREFIX, workspace_name, memory_id)
+}
+
+/// Whether `key` is a memory of the workspace named `workspace_name`
+fn is_memory_of(key: &str, workspace_name: &str) -> bool {
+    key.strip_prefix(MEMORY_PREFIX)
+        .and_then(|rest| rest.strip_prefix(workspace_name))
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Set the synced settings of `config` to `values`
+fn apply_settings(config: &mut Config, values: &BTreeMap<String, Value>) {
+    fn collect<T: serde::de::DeserializeOwned>(
+        values: &BTreeMap<String, Value>,
+        prefix: &str,
+    ) -> HashMap<String, T> {
+        values
+            .iter()
+            .filter_map(|(key, value)| {
+                let name = key.strip_prefix(prefix)?;
+                let value = serde_json::from_value(value.clone()).ok()?;
+                Some((name.to_string(), value))
+            })
+            .collect()
+    }
+
+    config.commands = collect(values, COMMAND_PREFIX);
+    config.system_prompts = collect(values, SYSTEM_PROMPT_PREFIX);
+    if let Some(policies) = values
+        .get(PERMISSION_POLICIES_KEY)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+    {
+        config.permission_policies = policies;
+    }
+    if let Some(policy) = values
+        .get(COMMAND_POLICY_KEY)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+    {
+        config.command_policy = policy;
+    }
+}
+
+/// Where the synced document is stored
+#[derive(Debug, Clone)]
+pub enum SyncBackend {
+    Folder(PathBuf),
+    WebDav {
+        url: String,
+        username: String,
+        password: String,
+    },
+}
+
+impl SyncBackend {
+    /// Backend configured in `config`, `None` while sync is off
+    pub fn from_config(config: &SyncConfig) -> Result<Option<Self>> {
+        let backend = match config.backend {
+            None => return Ok(None),
+            Some(SyncBackendKind::Folder) => match &config.folder {
+                Some(folder) => Self::Folder(folder.clone()),
+                None => bail!("sync backend `folder` needs `folder`"),
+            },
+            Some(SyncBackendKind::Webdav) => {
+                if config.url.trim().is_empty() {
+                    bail!("sync backend `webdav` needs `url`");
+                }
+                Self::WebDav {
+                    url: config.url.trim().trim_end_matches('/').to_string(),
+                    username: config.username.clone(),
+                    password: config.password.clone(),
+                }
+            }
+        };
+        Ok(Some(backend))
+    }
+
+    /// The synced document, `None` if nothing was synced yet
+    async fn read(&self) -> Result<Option<String>> {
+        match self {
+            Self::Folder(folder) => {
+                let path = folder.join(SYNC_FILE_NAME);
+                smol::unblock(move || match std::fs::read_to_string(&path) {
+                    Ok(content) => Ok(Some(content)),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                    Err(e) => Err(e).with_context(|| format!("Failed to read {:?}", path)),
+                })
+                .await
+            }
+            Self::WebDav {
+                url,
+                username,
+                password,
+            } => {
+                let request = webdav_request(reqwest::Method::GET, url, username, password);
+                runtime_handle()
+                    .spawn(async move {
+                        let response = request.send().await?;
+                        if response.status() == reqwest::StatusCode::NOT_FOUND {
+                            return Ok(None);
+                        }
+                        Ok::<_, reqwest::Error>(Some(response.error_for_status()?.text().await?))
+                    })
+                    .await
+                    .context("Failed to spawn WebDAV request task")?
+                    .context("Failed to download from WebDAV")
+            }
+        }
+    }
+
+    /// Replace the synced document with `content`
+    async fn write(&self, content: String) -> Result<()> {
+        match self {
+            Self::Folder(folder) => {
+                let folder = folder.clone();
+                smol::unblock(move || {
+                    std::fs::create_dir_all(&folder)
+                        .with_context(|| format!("Failed to create {:?}", folder))?;
+                    // Write next to the file first, so the sync tool never sees half of it
+                    let temp_path = folder.join(format!("{}.tmp", SYNC_FILE_NAME));
+                    std::fs::write(&temp_path, content)
+                        .with_context(|| format!("Failed to write {:?}", temp_path))?;
+                    std::fs::rename(&temp_path, folder.join(SYNC_FILE_NAME))
+                        .context("Failed to replace the sync file")
+                })
+                .await
+            }
+            Self::WebDav {
+                url,
+                username,
+                password,
+            } => {
+                let request =
+                    webdav_request(reqwest::Method::PUT, url, username, password).body(content);
+                runtime_handle()
+                    .spawn(async move { request.send().await?.error_for_status().map(|_| ()) })
+                    .await
+                    .context("Failed to spawn WebDAV request task")?
+                    .context("Failed to upload to WebDAV")
+            }
+        }
+    }
+
+    /// Where the document is, for messages
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Folder(folder) => folder.join(SYNC_FILE_NAME).display().to_string(),
+            Self::WebDav { url, .. } => format!("{}/{}", url, SYNC_FILE_NAME),
+        }
+    }
+}
+
+fn webdav_request(
+    method: reqwest::Method,
+    url: &str,
+    username: &str,
+    password: &str,
+) -> reqwest::RequestBuilder {
+    let request = reqwest::Client::new()
+        .request(method, format!("{}/{}", url, SYNC_FILE_NAME))
+        .timeout(Duration::from_secs(30));
+    if username.is_empty() {
+        request
+    } else {
+        request.basic_auth(username, Some(password))
+    }
+}
+
+/// Local state kept between syncs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SyncState {
+    /// Identifies this machine in synced entries
+    machine_id: String,
+    /// Document as of the last sync
+    #[serde(default)]
+    base: SyncDocument,
+}
+
+/// What a sync changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncReport {
+    /// Items changed here from the other machines
+    pub pulled: usize,
+    /// Items changed there from this machine
+    pub pushed: usize,
+    /// Items changed on both sides; the newest change was kept
+    pub conflicts: Vec<String>,
+}
+
+/// Sync service - syncs settings, prompt templates and memories
+pub struct SyncService {
+    backend: SyncBackend,
+    state_path: PathBuf,
+    agent_config_service: Arc<AgentConfigService>,
+    workspace_service: Arc<WorkspaceService>,
+    /// Held while a sync runs, so two syncs never interleave
+    running: smol::lock::Mutex<()>,
+}
+
+impl SyncService {
+    pub fn new(
+        backend: SyncBackend,
+        state_path: PathBuf,
+        agent_config_service: Arc<AgentConfigService>,
+        workspace_service: Arc<WorkspaceService>,
+    ) -> Self {
+        Self {
+            backend,
+            state_path,
+            agent_config_service,
+            workspace_service,
+            running: smol::lock::Mutex::new(()),
+        }
+    }
+
+    pub fn backend(&self) -> &SyncBackend {
+        &self.backend
+    }
+
+    /// Sync every `interval`, starting right away
+    pub fn start(self: &Arc<Self>, interval: Duration) {
+        let service = Arc::downgrade(self);
+        smol::spawn(async move {
+            loop {
+                let Some(service) = service.upgrade() else {
+                    break;
+                };
+                if let Err(e) = service.sync_now().await {
+                    log::warn!("Sync with {} failed: {:#}", service.backend.describe(), e);
+                }
+                drop(service);
+                smol::Timer::after(interval).await;
+            }
+        })
+        .detach();
+    }
+
+    /// Exchange changes with the synced document
+    pub async fn sync_now(&self) -> Result<SyncReport> {
+        let _running = self.running.lock().await;
+        let mut state = self.load_state();
+
+        let config = self.agent_config_service.config().await;
+        let workspaces = self.workspace_service.list_workspaces().await;
+        let current = local_values(&config, &workspaces);
+        let local_names: Vec<&str> = workspaces.iter().map(|w| w.name.as_str()).collect();
+        let is_local = |key: &str| {
+            !key.starts_with(MEMORY_PREFIX)
+                || local_names.iter().any(|name| is_memory_of(key, name))
+        };
+        let local = state
+            .base
+            .stamp(&current, is_local, &state.machine_id, Utc::now());
+
+        let remote = match self.backend.read().await? {
+            Some(content) => serde_json::from_str(&content)
+                .with_context(|| format!("Invalid sync file {}", self.backend.describe()))?,
+            None => SyncDocument::default(),
+        };
+        let outcome = merge(&state.base, &local, &remote);
+        let merged = outcome.document.values();
+
+        // Apply what the other machines changed
+        let pulled = count_changes(&current, &merged, &is_local);
+        if changed(&current, &merged, |key| !key.starts_with(MEMORY_PREFIX)) {
+            self.agent_config_service
+                .update_config(|config| apply_settings(config, &merged))
+                .await?;
+        }
+        for workspace in &workspaces {
+            let is_memory = |key: &str| is_memory_of(key, &workspace.name);
+            if !changed(&current, &merged, is_memory) {
+                continue;
+            }
+            let mut memories: Vec<WorkspaceMemory> = merged
+                .iter()
+                .filter(|(key, _)| is_memory(key.as_str()))
+                .filter_map(|(_, value)| serde_json::from_value(value.clone()).ok())
+                .collect();
+            memories.sort_by_key(|memory| memory.created_at);
+            self.workspace_service
+                .set_memories(&workspace.id, memories)
+                .await?;
+        }
+
+        // Publish what this machine changed
+        let pushed = count_entry_changes(&remote, &outcome.document);
+        if outcome.document != remote {
+            let content = serde_json::to_string_pretty(&outcome.document)?;
+            self.backend.write(content).await?;
+        }
+
+        state.base = outcome.document;
+        self.save_state(&state)?;
+
+        for key in &outcome.conflicts {
+            log::info!("Sync conflict on {}: kept the newest change", key);
+        }
+        log::info!(
+            "Synced with {}: {} pulled, {} pushed, {} conflicts",
+            self.backend.describe(),
+            pulled,
+            pushed,
+            outcome.conflicts.len()
+        );
+        Ok(SyncReport {
+            pulled,
+            pushed,
+            conflicts: outcome.conflicts,
+        })
+    }
+
+    fn load_state(&self) -> SyncState {
+        let state = std::fs::read_to_string(&self.state_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<SyncState>(&content).ok());
+        match state {
+            Some(state) if !state.machine_id.is_empty() => state,
+            _ => SyncState {
+                machine_id: uuid::Uuid::new_v4().to_string(),
+                base: SyncDocument::default(),
+            },
+        }
+    }
+
+    fn save_state(&self, state: &SyncState) -> Result<()> {
+        let content = serde_json::to_string_pretty(state)?;
+        std::fs::write(&self.state_path, content)
+            .with_context(|| format!("Failed to write {:?}", self.state_path))
+    }
+}
+
+/// Whether any value with a key matching `filter` differs
+fn changed(
+    before: &BTreeMap<String, Value>,
+    after: &BTreeMap<String, Value>,
+    filter: impl Fn(&str) -> bool,
+) -> bool {
+    count_changes(before, after, filter) > 0
+}
+
+/// Number of keys matching `filter` whose value differs
+fn count_changes(
+    before: &BTreeMap<String, Value>,
+    after: &BTreeMap<String, Value>,
+    filter: impl Fn(&str) -> bool,
+) -> usize {
+    let keys: std::collections::BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter(|key| filter(key.as_str()) && before.get(*key) != after.get(*key))
+        .count()
+}
+
+/// Number of entries that differ between two documents
+fn count_entry_changes(before: &SyncDocument, after: &SyncDocument) -> usize {
+    after
+        .entries
+        .iter()
+        .filter(|(key, entry)| before.entries.get(*key) != Some(*entry))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(seconds, 0).unwrap()
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> BTreeMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), Value::from(*value)))
+            .collect()
+    }
+
+    #[test]
+    fn test_stamp_tracks_changes_and_deletions() {
+        let base = SyncDocument::default().stamp(
+            &values(&[("command/review", "v1"), ("command/old", "x")]),
+            |_| true,
+            "a",
+            at(10),
+        );
+        let stamped = base.stamp(
+            &values(&[("command/review", "v2"), ("command/new", "y")]),
+            |_| true,
+            "a",
+            at(20),
+        );
+        assert_eq!(stamped.entries["command/review"].modified_at, at(20));
+        assert_eq!(stamped.entries["command/old"].value, None);
+        assert_eq!(stamped.entries["command/new"].modified_at, at(20));
+
+        // Unchanged items keep their time; foreign items are left alone
+        let again = stamped.stamp(
+            &values(&[("command/review", "v2")]),
+            |key| key != "command/new",
+            "a",
+            at(30),
+        );
+        assert_eq!(again.entries["command/review"].modified_at, at(20));
+        assert_eq!(again.entries["command/new"].value, Some(Value::from("y")));
+    }
+
+    #[test]
+    fn test_merge_keeps_one_sided_changes_and_newest_conflict() {
+        let base = SyncDocument::default().stamp(
+            &values(&[("command/a", "1"), ("command/b", "1")]),
+            |_| true,
+            "a",
+            at(10),
+        );
+        let local = base.stamp(
+            &values(&[("command/a", "local"), ("command/b", "1")]),
+            |_| true,
+            "a",
+            at(30),
+        );
+        let remote = base.stamp(
+            &values(&[("command/a", "remote"), ("command/c", "new")]),
+            |_| true,
+            "b",
+            at(20),
+        );
+
+        let outcome = merge(&base, &local, &remote);
+        let merged = outcome.document.values();
+        assert_eq!(merged["command/a"], Value::from("local"));
+        assert!(!merged.contains_key("command/b"));
+        assert_eq!(merged["command/c"], Value::from("new"));
+        assert_eq!(outcome.conflicts, vec!["command/a".to_string()]);
+
+        // Both machines end up with the same document
+        assert_eq!(merge(&base, &remote, &local).document, outcome.document);
+    }
+
+    #[test]
+    fn test_merge_keeps_one_sided_change_despite_clock_skew() {
+        let base =
+            SyncDocument::default().stamp(&values(&[("command/a", "1")]), |_| true, "a", at(100));
+        // The remote machine's clock is behind: its change is stamped before the base
+        let remote = base.stamp(&values(&[("command/a", "remote")]), |_| true, "b", at(50));
+
+        let outcome = merge(&base, &base, &remote);
+        assert_eq!(
+            outcome.document.values()["command/a"],
+            Value::from("remote")
+        );
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(merge(&base, &remote, &base).document, outcome.document);
+    }
+
+    #[test]
+    fn test_local_values_skip_secrets() {
+        let mut config: Config = serde_json::from_str(r#"{"agent_servers": {}}"#).unwrap();
+        config.commands.insert(
+            "review".to_string(),
+            crate::core::config::CommandConfig {
+                description: "Review".to_string(),
+                template: "Review {{input}}".to_string(),
+            },
+        );
+        let mut workspace = Workspace::new(PathBuf::from("/repo/web"));
+        let memory = WorkspaceMemory::new("we use pnpm");
+        workspace.memories.push(memory.clone());
+
+        let values = local_values(&config, &[workspace]);
+        assert!(values.contains_key("command/review"));
+        assert!(values.contains_key(&memory_key("web", &memory.id)));
+        assert!(
+            values
+                .keys()
+                .all(|key| !key.contains("model") && !key.contains("proxy"))
+        );
+
+        let mut restored: Config = serde_json::from_str(r#"{"agent_servers": {}}"#).unwrap();
+        apply_settings(&mut restored, &values);
+        assert_eq!(restored.commands["review"].template, "Review {{input}}");
+        assert!(is_memory_of(&memory_key("web", &memory.id), "web"));
+        assert!(!is_memory_of(&memory_key("web-app", &memory.id), "web"));
+    }
+}
@@ -0,0 +1,197 @@
+//! Warm Pool - Pre-initialized sessions for instant first prompts
+//!
+//! Spawning an agent and creating its first session can take several seconds.
+//! When enabled (`warm_pool` in config.json), one idle session per frequently
+//! used agent is created at startup and parked here; the next session request
+//! for that agent, working directory and MCP servers takes it instead of waiting.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use agent_client_protocol as acp;
+
+/// A session created ahead of time and not yet handed out
+#[derive(Clone, Debug)]
+pub struct WarmSession {
+    pub session_id: String,
+    pub cwd: PathBuf,
+    pub mcp_servers: Vec<acp::McpServer>,
+    pub response: acp::NewSessionResponse,
+}
+
+/// At most one parked session per agent
+#[derive(Clone, Default)]
+pub struct WarmPool {
+    sessions: Arc<Mutex<HashMap<String, WarmSession>>>,
+}
+
+impl WarmPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Park a session for an agent, replacing any previous one
+    pub fn insert(&self, agent_name: &str, session: WarmSession) {
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(agent_name.to_string(), session);
+    }
+
+    /// Take the agent's warm session if it was created for `cwd` with the
+    /// same MCP servers
+    pub fn take(
+        &self,
+        agent_name: &str,
+        cwd: &Path,
+        mcp_servers: &[acp::McpServer],
+    ) -> Option<WarmSession> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let matches = sessions.get(agent_name).is_some_and(|s| {
+            s.cwd == cwd && server_keys(&s.mcp_servers) == server_keys(mcp_servers)
+        });
+        if matches {
+            sessions.remove(agent_name)
+        } else {
+            None
+        }
+    }
+
+    /// Drop the agent's warm session (e.g. when its process goes away)
+    pub fn discard(&self, agent_name: &str) -> Option<WarmSession> {
+        self.sessions.lock().unwrap().remove(agent_name)
+    }
+
+    pub fn contains(&self, agent_name: &str) -> bool {
+        self.sessions.lock().unwrap().contains_key(agent_name)
+    }
+}
+
+/// `servers` in a form that compares equal regardless of their order
+fn server_keys(servers: &[acp::McpServer]) -> Vec<String> {
+    let mut keys: Vec<String> = servers
+        .iter()
+        .filter_map(|server| serde_json::to_string(server).ok())
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// Choose which agents to warm up
+///
+/// Explicitly configured agents come first; remaining slots go to the agents
+/// with the most tasks. Only agents in `available` are considered.
+pub fn pick_warm_agents<'a>(
+    configured: &[String],
+    task_agents: impl IntoIterator<Item = &'a str>,
+    available: &[String],
+    size: usize,
+) -> Vec<String> {
+    let mut picked: Vec<String> = configured
+        .iter()
+        .filter(|name| available.contains(name))
+        .take(size)
+        .cloned()
+        .collect();
+
+    let mut usage: HashMap<&str, usize> = HashMap::new();
+    for agent in task_agents {
+        *usage.entry(agent).or_default() += 1;
+    }
+    let mut ranked: Vec<(&str, usize)> = usage.into_iter().collect();
+    // Most used first, ties broken by name for a stable order
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    for (agent, _) in ranked {
+        if picked.len() >= size {
+            break;
+        }
+        if available.iter().any(|name| name == agent) && !picked.iter().any(|p| p == agent) {
+            picked.push(agent.to_string());
+        }
+    }
+    picked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_pick_prefers_configured_then_most_used() {
+        let available = names(&["claude", "codex", "gemini"]);
+        let tasks = ["codex", "gemini", "gemini", "claude", "gemini"];
+
+        let picked = pick_warm_agents(&names(&["claude"]), tasks, &available, 2);
+        assert_eq!(picked, names(&["claude", "gemini"]));
+
+        let picked = pick_warm_agents(&[], tasks, &available, 1);
+        assert_eq!(picked, names(&["gemini"]));
+    }
+
+    #[test]
+    fn test_pick_skips_unavailable_agents() {
+        let available = names(&["claude"]);
+        let picked = pick_warm_agents(&names(&["removed"]), ["gone", "claude"], &available, 3);
+        assert_eq!(picked, names(&["claude"]));
+    }
+
+    fn server(name: &str) -> acp::McpServer {
+        acp::McpServer::Stdio(
+            serde_json::from_value(serde_json::json!({
+                "name": name,
+                "command": format!("{}-mcp", name),
+                "args": [],
+                "env": []
+            }))
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_take_requires_matching_cwd() {
+        let pool = WarmPool::new();
+        pool.insert(
+            "claude",
+            WarmSession {
+                session_id: "s1".into(),
+                cwd: PathBuf::from("/work/a"),
+                mcp_servers: Vec::new(),
+                response: acp::NewSessionResponse::new("s1".to_string()),
+            },
+        );
+
+        assert!(pool.take("claude", Path::new("/work/b"), &[]).is_none());
+        assert!(pool.contains("claude"));
+        let warm = pool.take("claude", Path::new("/work/a"), &[]).unwrap();
+        assert_eq!(warm.session_id, "s1");
+        assert!(!pool.contains("claude"));
+    }
+
+    #[test]
+    fn test_take_requires_matching_mcp_servers() {
+        let pool = WarmPool::new();
+        pool.insert(
+            "claude",
+            WarmSession {
+                session_id: "s1".into(),
+                cwd: PathBuf::from("/work"),
+                mcp_servers: vec![server("fs"), server("git")],
+                response: acp::NewSessionResponse::new("s1".to_string()),
+            },
+        );
+
+        let cwd = Path::new("/work");
+        assert!(pool.take("claude", cwd, &[server("fs")]).is_none());
+        assert!(pool.take("claude", cwd, &[]).is_none());
+        let warm = pool.take("claude", cwd, &[server("git"), server("fs")]);
+        assert_eq!(warm.unwrap().session_id, "s1");
+    }
+}
@@ -0,0 +1,326 @@
+//! Webhook Service - Notify HTTP endpoints of session events
+//!
+//! Each webhook in config.json names a URL and the events it wants: finished
+//! turns, errors (failed prompts, turns cut off at the output limit) and
+//! finished tasks (locked ones). Events are taken from the workspace bus,
+//! completed with their task and POSTed as JSON, or as a plain message for
+//! Slack and Discord webhooks. Delivery is best effort: failures are logged
+//! and not retried.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use super::agent_service::SessionStatus;
+use super::ai_service::runtime_handle;
+use super::workspace_service::WorkspaceService;
+use crate::core::config::{WebhookConfig, WebhookEventKind, WebhookFormat};
+use crate::core::event_bus::{WorkspaceUpdateBusContainer, WorkspaceUpdateEvent};
+use crate::utils::time::format_duration_short;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An event as sent to webhooks
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WebhookEvent {
+    #[serde(rename = "event")]
+    pub kind: WebhookEventKind,
+    pub timestamp: DateTime<Utc>,
+    pub session_id: Option<String>,
+    pub agent_name: Option<String>,
+    pub task_id: Option<String>,
+    pub task_name: Option<String>,
+    /// Fields of the event, e.g. `busy_ms` of a turn
+    pub details: Value,
+}
+
+impl WebhookEvent {
+    fn new(kind: WebhookEventKind, timestamp: DateTime<Utc>, details: Value) -> Self {
+        Self {
+            kind,
+            timestamp,
+            session_id: None,
+            agent_name: None,
+            task_id: None,
+            task_name: None,
+            details,
+        }
+    }
+
+    /// The webhook event for `event`, if it is one webhooks are told about
+    pub fn from_workspace_event(event: &WorkspaceUpdateEvent, now: DateTime<Utc>) -> Option<Self> {
+        let (mut webhook_event, session_id, agent_name) = match event {
+            WorkspaceUpdateEvent::TurnCompleted {
+                session_id,
+                agent_name,
+                busy_ms,
+                prompt_chars,
+                output_chars,
+            } => (
+                Self::new(
+                    WebhookEventKind::TurnCompleted,
+                    now,
+                    json!({
+                        "busy_ms": busy_ms,
+                        "prompt_chars": prompt_chars,
+                        "output_chars": output_chars,
+                    }),
+                ),
+                session_id,
+                agent_name,
+            ),
+            WorkspaceUpdateEvent::SessionStatusUpdated {
+                session_id,
+                agent_name,
+                status: SessionStatus::Failed,
+                ..
+            } => (
+                Self::new(
+                    WebhookEventKind::Error,
+                    now,
+                    json!({ "reason": "prompt_failed" }),
+                ),
+                session_id,
+                agent_name,
+            ),
+            WorkspaceUpdateEvent::SessionOutputLimited {
+                session_id,
+                agent_name,
+                limit,
+            } => (
+                Self::new(
+                    WebhookEventKind::Error,
+                    now,
+                    json!({ "reason": "output_limit", "limit": limit }),
+                ),
+                session_id,
+                agent_name,
+            ),
+            WorkspaceUpdateEvent::TaskLockChanged {
+                task_id,
+                session_ids,
+                locked: true,
+            } => {
+                let mut event = Self::new(WebhookEventKind::TaskDone, now, json!({}));
+                event.task_id = Some(task_id.clone());
+                event.session_id = session_ids.first().cloned();
+                return Some(event);
+            }
+            _ => return None,
+        };
+        webhook_event.session_id = Some(session_id.clone());
+        webhook_event.agent_name = Some(agent_name.clone());
+        Some(webhook_event)
+    }
+
+    /// One line describing the event, e.g. for chat messages
+    pub fn summary(&self) -> String {
+        let agent = self.agent_name.as_deref().unwrap_or("An agent");
+        let in_task = self
+            .task_name
+            .as_ref()
+            .map(|name| format!(" in \"{}\"", name))
+            .unwrap_or_default();
+        match self.kind {
+            WebhookEventKind::TurnCompleted => {
+                let busy_ms = self.details["busy_ms"].as_u64().unwrap_or(0);
+                format!(
+                    "{} finished a turn{} ({})",
+                    agent,
+                    in_task,
+                    format_duration_short(busy_ms)
+                )
+            }
+            WebhookEventKind::Error => match self.details["limit"].as_u64() {
+                Some(limit) => format!(
+                    "{} was stopped{} after exceeding the output limit of {} characters",
+                    agent, in_task, limit
+                ),
+                None => format!("{}'s prompt failed{}", agent, in_task),
+            },
+            WebhookEventKind::TaskDone => match &self.task_name {
+                Some(name) => format!("Task \"{}\" is done", name),
+                None => "A task is done".to_string(),
+            },
+        }
+    }
+
+    /// Request body in `format`
+    pub fn body(&self, format: WebhookFormat) -> Value {
+        match format {
+            WebhookFormat::Json => {
+                let mut body = serde_json::to_value(self).unwrap_or_else(|_| json!({}));
+                body["message"] = Value::String(self.summary());
+                body
+            }
+            WebhookFormat::Slack => json!({ "text": self.summary() }),
+            WebhookFormat::Discord => json!({ "content": self.summary() }),
+        }
+    }
+}
+
+/// Sends session events to the webhooks in config.json
+pub struct WebhookService {
+    hooks: RwLock<Vec<WebhookConfig>>,
+    workspace_service: Arc<WorkspaceService>,
+}
+
+impl WebhookService {
+    pub fn new(hooks: Vec<WebhookConfig>, workspace_service: Arc<WorkspaceService>) -> Self {
+        Self {
+            hooks: RwLock::new(hooks),
+            workspace_service,
+        }
+    }
+
+    /// Replace the webhooks, e.g. after config.json was reloaded
+    pub fn set_hooks(&self, hooks: Vec<WebhookConfig>) {
+        *self.hooks.write().unwrap() = hooks;
+    }
+
+    fn hooks_for(&self, kind: WebhookEventKind) -> Vec<WebhookConfig> {
+        self.hooks
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|hook| hook.accepts(kind))
+            .cloned()
+            .collect()
+    }
+
+    /// Deliver the events reported on the workspace bus
+    pub fn start(self: &Arc<Self>, workspace_bus: &WorkspaceUpdateBusContainer) {
+        let service = Arc::downgrade(self);
+        workspace_bus.subscribe(move |event| {
+            let Some(event) = WebhookEvent::from_workspace_event(event, Utc::now()) else {
+                return;
+            };
+            let Some(service) = service.upgrade() else {
+                return;
+            };
+            if service.hooks_for(event.kind).is_empty() {
+                return;
+            }
+            smol::spawn(async move { service.deliver(event).await }).detach();
+        });
+    }
+
+    /// Send `event`, with its task filled in, to every webhook that wants it
+    pub async fn deliver(&self, mut event: WebhookEvent) {
+        let task = match (&event.task_id, &event.session_id) {
+            (Some(task_id), _) => self.workspace_service.get_task(task_id).await,
+            (None, Some(session_id)) => {
+                self.workspace_service.get_task_by_session(session_id).await
+            }
+            (None, None) => None,
+        };
+        if let Some(task) = task {
+            event.task_id = Some(task.id);
+            event.task_name = Some(task.name);
+            event.agent_name.get_or_insert(task.agent_name);
+        }
+
+        for hook in self.hooks_for(event.kind) {
+            match send(&hook, &event).await {
+                Ok(()) => log::debug!("Sent {:?} to webhook {}", event.kind, hook.url),
+                Err(e) => log::warn!("Webhook {} failed: {:#}", hook.url, e),
+            }
+        }
+    }
+}
+
+async fn send(hook: &WebhookConfig, event: &WebhookEvent) -> Result<()> {
+    let body = serde_json::to_string(&event.body(hook.format))?;
+    let mut request = reqwest::Client::new()
+        .post(&hook.url)
+        .timeout(REQUEST_TIMEOUT)
+        .header("Content-Type", "application/json")
+        .body(body);
+    for (name, value) in &hook.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    runtime_handle()
+        .spawn(async move { request.send().await?.error_for_status().map(|_| ()) })
+        .await
+        .context("Failed to spawn webhook request task")?
+        .context("Webhook request failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn_completed() -> WorkspaceUpdateEvent {
+        WorkspaceUpdateEvent::TurnCompleted {
+            session_id: "s1".to_string(),
+            agent_name: "claude".to_string(),
+            busy_ms: 125_000,
+            prompt_chars: 10,
+            output_chars: 200,
+        }
+    }
+
+    #[test]
+    fn test_from_workspace_event() {
+        let now = Utc::now();
+        let event = WebhookEvent::from_workspace_event(&turn_completed(), now).unwrap();
+        assert_eq!(event.kind, WebhookEventKind::TurnCompleted);
+        assert_eq!(event.session_id.as_deref(), Some("s1"));
+        assert_eq!(event.details["busy_ms"], 125_000);
+
+        let limited = WorkspaceUpdateEvent::SessionOutputLimited {
+            session_id: "s1".to_string(),
+            agent_name: "claude".to_string(),
+            limit: 1000,
+        };
+        let event = WebhookEvent::from_workspace_event(&limited, now).unwrap();
+        assert_eq!(event.kind, WebhookEventKind::Error);
+
+        let locked = |locked| WorkspaceUpdateEvent::TaskLockChanged {
+            task_id: "t1".to_string(),
+            session_ids: vec!["s1".to_string()],
+            locked,
+        };
+        let event = WebhookEvent::from_workspace_event(&locked(true), now).unwrap();
+        assert_eq!(event.kind, WebhookEventKind::TaskDone);
+        assert_eq!(event.task_id.as_deref(), Some("t1"));
+        assert!(WebhookEvent::from_workspace_event(&locked(false), now).is_none());
+        let opened = WorkspaceUpdateEvent::SessionOpened {
+            session_id: "s1".to_string(),
+        };
+        assert!(WebhookEvent::from_workspace_event(&opened, now).is_none());
+    }
+
+    #[test]
+    fn test_body() {
+        let mut event = WebhookEvent::from_workspace_event(&turn_completed(), Utc::now()).unwrap();
+        event.task_name = Some("Fix login".to_string());
+        let summary = "claude finished a turn in \"Fix login\" (2m)";
+        assert_eq!(event.summary(), summary);
+        assert_eq!(event.body(WebhookFormat::Slack), json!({ "text": summary }));
+        assert_eq!(
+            event.body(WebhookFormat::Discord),
+            json!({ "content": summary })
+        );
+        let body = event.body(WebhookFormat::Json);
+        assert_eq!(body["event"], "turn_completed");
+        assert_eq!(body["message"], summary);
+        assert_eq!(body["task_name"], "Fix login");
+    }
+
+    #[test]
+    fn test_accepts() {
+        let hook: WebhookConfig = serde_json::from_value(json!({
+            "url": "https://example.com/hook",
+            "events": ["error", "task_done"],
+        }))
+        .unwrap();
+        assert!(hook.accepts(WebhookEventKind::Error));
+        assert!(!hook.accepts(WebhookEventKind::TurnCompleted));
+        assert_eq!(hook.format, WebhookFormat::Json);
+    }
+}
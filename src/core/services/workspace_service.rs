@@ -1,11 +1,31 @@
-use anyhow::{Context, Result};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::core::event_bus::{WorkspaceUpdateBusContainer, WorkspaceUpdateEvent};
-use crate::core::services::SessionStatus;
-use crate::schemas::workspace_schema::{Workspace, WorkspaceConfig, WorkspaceTask};
+use crate::core::services::{PersistenceService, ServiceError, ServiceResult, SessionStatus};
+use crate::schemas::workspace_schema::{
+    AgentFeedbackSummary, FeedbackRating, LabelSummary, REPO_DATA_DIR, TaskCursor, TaskPage,
+    TurnFeedback, Workspace, WorkspaceConfig, WorkspaceMemory, WorkspaceTask, summarize_by_label,
+    summarize_feedback,
+};
+use crate::utils::context_item::ContextItem;
+use crate::utils::task_name::{TaskNameContext, render_task_name};
+
+/// `.agent-studio/.gitignore` written when a workspace starts keeping its
+/// sessions in the repository
+const REPO_GITIGNORE: &str = "\
+# Written by AgentX when this workspace started keeping session histories here.
+#
+# sessions/*.jsonl are the conversations with agents. Commit them to share them
+# with the repository, or keep them private by adding this line to the
+# repository's own .gitignore:
+#
+#     .agent-studio/
+#
+# Deleted sessions are only kept locally.
+sessions/trash/
+";
 
 /// Service for managing workspaces and tasks
 ///
@@ -20,6 +40,8 @@ pub struct WorkspaceService {
     config: Arc<RwLock<WorkspaceConfig>>,
     config_path: PathBuf,
     workspace_bus: Option<WorkspaceUpdateBusContainer>,
+    /// Told where sessions of workspaces with `repo_sessions` are stored
+    persistence_service: Option<Arc<PersistenceService>>,
 }
 
 impl WorkspaceService {
@@ -31,6 +53,7 @@ impl WorkspaceService {
             config: Arc::new(RwLock::new(config)),
             config_path,
             workspace_bus: None,
+            persistence_service: None,
         }
     }
 
@@ -39,6 +62,36 @@ impl WorkspaceService {
         self.workspace_bus = Some(bus);
     }
 
+    /// Set the persistence service and point it at the sessions of
+    /// workspaces that keep them in their repository
+    pub fn set_persistence_service(&mut self, persistence_service: Arc<PersistenceService>) {
+        if let Ok(config) = self.config.try_read() {
+            for workspace in config.workspaces.iter().filter(|w| w.repo_sessions) {
+                let dir = workspace.repo_sessions_dir();
+                for task in config.tasks_for_workspace(&workspace.id) {
+                    for session_id in task.session_ids() {
+                        persistence_service.set_session_dir(&session_id, Some(dir.clone()));
+                    }
+                }
+            }
+        }
+        self.persistence_service = Some(persistence_service);
+    }
+
+    /// Store a session in `repo_dir`, moving what was already written there
+    async fn route_session(
+        &self,
+        session_id: &str,
+        repo_dir: Option<PathBuf>,
+    ) -> ServiceResult<()> {
+        match (&self.persistence_service, repo_dir) {
+            (Some(persistence_service), Some(dir)) => {
+                persistence_service.relocate_session(session_id, Some(dir)).await
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Publish a workspace update event if bus is available
     fn publish_event(&self, event: WorkspaceUpdateEvent) {
         if let Some(bus) = &self.workspace_bus {
@@ -48,45 +101,72 @@ impl WorkspaceService {
     }
 
     /// Load workspace configuration from disk
-    fn load_config(path: &PathBuf) -> Result<WorkspaceConfig> {
+    fn load_config(path: &PathBuf) -> ServiceResult<WorkspaceConfig> {
         if !path.exists() {
             return Ok(WorkspaceConfig::default());
         }
 
-        let content = std::fs::read_to_string(path).context("Failed to read workspace config")?;
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ServiceError::io("Failed to read workspace config", e))?;
 
-        let config: WorkspaceConfig =
-            serde_json::from_str(&content).context("Failed to parse workspace config")?;
+        let mut config: WorkspaceConfig = serde_json::from_str(&content)
+            .map_err(|e| ServiceError::serialization("Failed to parse workspace config", e))?;
+        if config.migrate() {
+            log::info!("Migrated tasks in workspace config {:?}", path);
+        }
 
         Ok(config)
     }
 
     /// Save workspace configuration to disk
-    async fn save_config(&self) -> Result<()> {
+    async fn save_config(&self) -> ServiceResult<()> {
         let config = self.config.read().await;
         let content = serde_json::to_string_pretty(&*config)
-            .context("Failed to serialize workspace config")?;
+            .map_err(|e| ServiceError::serialization("Failed to serialize workspace config", e))?;
 
-        std::fs::write(&self.config_path, content).context("Failed to write workspace config")?;
+        std::fs::write(&self.config_path, content)
+            .map_err(|e| ServiceError::io("Failed to write workspace config", e))?;
 
         Ok(())
     }
 
+    /// The workspace at `path`, added when it is new, made the active one
+    pub async fn open_workspace(&self, path: PathBuf) -> ServiceResult<Workspace> {
+        let workspace = match self.add_workspace(path.clone()).await {
+            Ok(workspace) => workspace,
+            Err(ServiceError::WorkspaceExists(_)) => self
+                .list_workspaces()
+                .await
+                .into_iter()
+                .find(|workspace| workspace.path == path)
+                .ok_or_else(|| ServiceError::WorkspaceNotFound(path.display().to_string()))?,
+            Err(e) => return Err(e),
+        };
+        self.set_active_workspace(&workspace.id).await?;
+        Ok(workspace)
+    }
+
     /// Add a new workspace from a folder path
-    pub async fn add_workspace(&self, path: PathBuf) -> Result<Workspace> {
+    pub async fn add_workspace(&self, path: PathBuf) -> ServiceResult<Workspace> {
         // Validate that the path exists and is a directory
         if !path.exists() {
-            anyhow::bail!("Path does not exist: {:?}", path);
+            return Err(ServiceError::InvalidInput(format!(
+                "Path does not exist: {:?}",
+                path
+            )));
         }
         if !path.is_dir() {
-            anyhow::bail!("Path is not a directory: {:?}", path);
+            return Err(ServiceError::InvalidInput(format!(
+                "Path is not a directory: {:?}",
+                path
+            )));
         }
 
         // Check if workspace with this path already exists
         {
             let config = self.config.read().await;
             if config.workspaces.iter().any(|w| w.path == path) {
-                anyhow::bail!("Workspace already exists for path: {:?}", path);
+                return Err(ServiceError::WorkspaceExists(path));
             }
         }
 
@@ -118,7 +198,7 @@ impl WorkspaceService {
     }
 
     /// Remove a workspace by ID
-    pub async fn remove_workspace(&self, workspace_id: &str) -> Result<()> {
+    pub async fn remove_workspace(&self, workspace_id: &str) -> ServiceResult<()> {
         {
             let mut config = self.config.write().await;
             config.remove_workspace(workspace_id);
@@ -160,14 +240,185 @@ impl WorkspaceService {
         config.get_workspace(workspace_id).cloned()
     }
 
+    /// The workspace of the task that owns `session_id`
+    pub async fn get_workspace_for_session(&self, session_id: &str) -> Option<Workspace> {
+        let config = self.config.read().await;
+        let task = config
+            .tasks
+            .iter()
+            .find(|task| task.session_id.as_deref() == Some(session_id))?;
+        config.get_workspace(&task.workspace_id).cloned()
+    }
+
+    /// Set the language agents respond in for a workspace (`None` or blank
+    /// to leave it to the agents), and whether replies are checked against it
+    pub async fn set_response_language(
+        &self,
+        workspace_id: &str,
+        language: Option<String>,
+        check: bool,
+    ) -> ServiceResult<()> {
+        let language = language
+            .map(|language| language.trim().to_string())
+            .filter(|language| !language.is_empty());
+        {
+            let mut config = self.config.write().await;
+            let workspace = config
+                .get_workspace_mut(workspace_id)
+                .ok_or_else(|| ServiceError::WorkspaceNotFound(workspace_id.to_string()))?;
+            workspace.check_response_language = check && language.is_some();
+            workspace.response_language = language;
+        }
+        self.save_config().await
+    }
+
+    /// Attach the instruction file `file_name` to the workspace's new sessions,
+    /// or leave it out
+    pub async fn set_instruction_file_included(
+        &self,
+        workspace_id: &str,
+        file_name: &str,
+        included: bool,
+    ) -> ServiceResult<()> {
+        {
+            let mut config = self.config.write().await;
+            let workspace = config
+                .get_workspace_mut(workspace_id)
+                .ok_or_else(|| ServiceError::WorkspaceNotFound(workspace_id.to_string()))?;
+            let excluded = &mut workspace.excluded_instruction_files;
+            excluded.retain(|name| name != file_name);
+            if !included {
+                excluded.push(file_name.to_string());
+            }
+        }
+        self.save_config().await
+    }
+
+    /// Keep the sessions of a workspace in its repository, or move them back
+    ///
+    /// Existing session files are moved along. Turning it on writes
+    /// `.agent-studio/.gitignore` with guidance on sharing them, unless the
+    /// file exists already.
+    pub async fn set_repo_sessions(&self, workspace_id: &str, enabled: bool) -> ServiceResult<()> {
+        let (dir, session_ids) = {
+            let mut config = self.config.write().await;
+            let workspace = config
+                .get_workspace_mut(workspace_id)
+                .ok_or_else(|| ServiceError::WorkspaceNotFound(workspace_id.to_string()))?;
+            workspace.repo_sessions = enabled;
+            let dir = workspace.repo_sessions_dir();
+            let repo_data_dir = workspace.path.join(REPO_DATA_DIR);
+            let session_ids: Vec<String> = config
+                .tasks_for_workspace(workspace_id)
+                .iter()
+                .flat_map(|task| task.session_ids())
+                .collect();
+            if enabled {
+                write_repo_gitignore(&repo_data_dir)?;
+            }
+            (dir, session_ids)
+        };
+        self.save_config().await?;
+
+        if let Some(persistence_service) = &self.persistence_service {
+            for session_id in &session_ids {
+                persistence_service
+                    .relocate_session(session_id, enabled.then(|| dir.clone()))
+                    .await?;
+            }
+        }
+
+        log::info!(
+            "Workspace {} keeps its {} sessions in {}",
+            workspace_id,
+            session_ids.len(),
+            if enabled { "the repository" } else { "the app data" }
+        );
+        Ok(())
+    }
+
+    /// Remember a fact for every future session of the workspace
+    ///
+    /// Remembering the same text twice returns the existing entry.
+    pub async fn add_memory(
+        &self,
+        workspace_id: &str,
+        content: &str,
+    ) -> ServiceResult<WorkspaceMemory> {
+        let content = content.trim();
+        if content.is_empty() {
+            return Err(ServiceError::InvalidInput("Nothing to remember".to_string()));
+        }
+
+        let memory = {
+            let mut config = self.config.write().await;
+            let workspace = config
+                .get_workspace_mut(workspace_id)
+                .ok_or_else(|| ServiceError::WorkspaceNotFound(workspace_id.to_string()))?;
+
+            if let Some(existing) = workspace.memories.iter().find(|m| m.content == content) {
+                return Ok(existing.clone());
+            }
+            let memory = WorkspaceMemory::new(content);
+            workspace.memories.push(memory.clone());
+            memory
+        };
+
+        self.save_config().await?;
+        self.publish_event(WorkspaceUpdateEvent::MemoryUpdated {
+            workspace_id: workspace_id.to_string(),
+        });
+
+        log::info!("Remembered for workspace {}: {}", workspace_id, memory.content);
+        Ok(memory)
+    }
+
+    /// Forget a remembered fact
+    pub async fn remove_memory(&self, workspace_id: &str, memory_id: &str) -> ServiceResult<()> {
+        {
+            let mut config = self.config.write().await;
+            let workspace = config
+                .get_workspace_mut(workspace_id)
+                .ok_or_else(|| ServiceError::WorkspaceNotFound(workspace_id.to_string()))?;
+            workspace.memories.retain(|m| m.id != memory_id);
+        }
+
+        self.save_config().await?;
+        self.publish_event(WorkspaceUpdateEvent::MemoryUpdated {
+            workspace_id: workspace_id.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Replace the memories of a workspace, e.g. with synced ones
+    pub async fn set_memories(
+        &self,
+        workspace_id: &str,
+        memories: Vec<WorkspaceMemory>,
+    ) -> ServiceResult<()> {
+        {
+            let mut config = self.config.write().await;
+            let workspace = config
+                .get_workspace_mut(workspace_id)
+                .ok_or_else(|| ServiceError::WorkspaceNotFound(workspace_id.to_string()))?;
+            workspace.memories = memories;
+        }
+
+        self.save_config().await?;
+        self.publish_event(WorkspaceUpdateEvent::MemoryUpdated {
+            workspace_id: workspace_id.to_string(),
+        });
+        Ok(())
+    }
+
     /// Set the active workspace
-    pub async fn set_active_workspace(&self, workspace_id: &str) -> Result<()> {
+    pub async fn set_active_workspace(&self, workspace_id: &str) -> ServiceResult<()> {
         {
             let mut config = self.config.write().await;
 
             // Verify workspace exists
             if config.get_workspace(workspace_id).is_none() {
-                anyhow::bail!("Workspace not found: {}", workspace_id);
+                return Err(ServiceError::WorkspaceNotFound(workspace_id.to_string()));
             }
 
             config.active_workspace_id = Some(workspace_id.to_string());
@@ -185,14 +436,34 @@ impl WorkspaceService {
     }
 
     /// Create a new task in a workspace
+    ///
+    /// The task is named by filling `name_template` in (see
+    /// `utils::task_name`) from the first prompt. `label` tags the task for
+    /// experiment reports (e.g. "prompt-v2").
     pub async fn create_task(
         &self,
         workspace_id: &str,
-        name: String,
+        prompt: &str,
         agent_name: String,
         mode: String,
-    ) -> Result<WorkspaceTask> {
-        let task = WorkspaceTask::new(workspace_id.to_string(), name, agent_name, mode);
+        label: Option<String>,
+        name_template: &str,
+    ) -> ServiceResult<WorkspaceTask> {
+        let label = label
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty());
+        let name = render_task_name(
+            name_template,
+            &TaskNameContext {
+                prompt,
+                agent_name: &agent_name,
+                mode: &mode,
+                label: label.as_deref(),
+                now: chrono::Local::now(),
+            },
+        );
+        let mut task = WorkspaceTask::new(workspace_id.to_string(), name, agent_name, mode);
+        task.label = label;
         let task_clone = task.clone();
 
         {
@@ -200,7 +471,7 @@ impl WorkspaceService {
 
             // Verify workspace exists
             if config.get_workspace(workspace_id).is_none() {
-                anyhow::bail!("Workspace not found: {}", workspace_id);
+                return Err(ServiceError::WorkspaceNotFound(workspace_id.to_string()));
             }
 
             config.add_task(task);
@@ -222,25 +493,285 @@ impl WorkspaceService {
         Ok(task_clone)
     }
 
+    /// Add a task whose conversation took place elsewhere; its session
+    /// history is written already and moves to the workspace's repository if
+    /// it keeps sessions there
+    pub async fn import_task(&self, task: WorkspaceTask) -> ServiceResult<()> {
+        let workspace_id = task.workspace_id.clone();
+        let task_id = task.id.clone();
+        let session_id = task.session_id.clone();
+        let repo_dir = {
+            let mut config = self.config.write().await;
+            let Some(workspace) = config.get_workspace(&workspace_id) else {
+                return Err(ServiceError::WorkspaceNotFound(workspace_id));
+            };
+            let repo_dir = repo_sessions_dir(Some(workspace));
+            config.add_task(task);
+            repo_dir
+        };
+
+        self.save_config().await?;
+        if let Some(session_id) = session_id {
+            self.route_session(&session_id, repo_dir).await?;
+        }
+        self.publish_event(WorkspaceUpdateEvent::TaskCreated {
+            workspace_id,
+            task_id,
+        });
+        Ok(())
+    }
+
     /// Associate a session with a task
-    pub async fn set_task_session(&self, task_id: &str, session_id: String) -> Result<()> {
+    pub async fn set_task_session(&self, task_id: &str, session_id: String) -> ServiceResult<()> {
+        let repo_dir = {
+            let mut config = self.config.write().await;
+
+            let task = config
+                .tasks
+                .iter_mut()
+                .find(|t| t.id == task_id)
+                .ok_or_else(|| ServiceError::TaskNotFound(task_id.to_string()))?;
+
+            task.set_session(session_id.clone());
+            let workspace_id = task.workspace_id.clone();
+            repo_sessions_dir(config.get_workspace(&workspace_id))
+        };
+
+        self.save_config().await?;
+        self.route_session(&session_id, repo_dir).await?;
+
+        Ok(())
+    }
+
+    /// Record the session another agent uses within a task
+    pub async fn link_agent_session(
+        &self,
+        task_id: &str,
+        agent_name: &str,
+        session_id: String,
+    ) -> ServiceResult<()> {
+        let repo_dir = {
+            let mut config = self.config.write().await;
+
+            let task = config
+                .tasks
+                .iter_mut()
+                .find(|t| t.id == task_id)
+                .ok_or_else(|| ServiceError::TaskNotFound(task_id.to_string()))?;
+
+            task.agent_sessions.insert(agent_name.to_string(), session_id.clone());
+            let workspace_id = task.workspace_id.clone();
+            repo_sessions_dir(config.get_workspace(&workspace_id))
+        };
+
+        self.save_config().await?;
+        self.route_session(&session_id, repo_dir).await?;
+
+        Ok(())
+    }
+
+    /// Replace the context pinned to the task that owns `session_id`
+    pub async fn set_pinned_context(
+        &self,
+        session_id: &str,
+        items: Vec<ContextItem>,
+    ) -> ServiceResult<()> {
         {
             let mut config = self.config.write().await;
 
+            let task = config
+                .find_task_by_session(session_id)
+                .ok_or_else(|| ServiceError::SessionNotFound(session_id.to_string()))?;
+
+            task.pinned_context = items;
+        }
+
+        self.save_config().await?;
+
+        Ok(())
+    }
+
+    /// Translate the agent replies of the task that owns `session_id` as they finish
+    pub async fn set_auto_translate(&self, session_id: &str, enabled: bool) -> ServiceResult<()> {
+        {
+            let mut config = self.config.write().await;
+
+            let task = config
+                .find_task_by_session(session_id)
+                .ok_or_else(|| ServiceError::SessionNotFound(session_id.to_string()))?;
+
+            task.auto_translate = enabled;
+        }
+
+        self.save_config().await?;
+
+        Ok(())
+    }
+
+    /// Lock or unlock a task: a locked task takes no more prompts and its
+    /// agents' writes are refused
+    pub async fn set_task_locked(&self, task_id: &str, locked: bool) -> ServiceResult<()> {
+        let session_ids = {
+            let mut config = self.config.write().await;
+
             let task = config
                 .tasks
                 .iter_mut()
                 .find(|t| t.id == task_id)
-                .context("Task not found")?;
+                .ok_or_else(|| ServiceError::TaskNotFound(task_id.to_string()))?;
+
+            task.locked = locked;
+            task.session_ids()
+        };
+
+        self.save_config().await?;
+        self.publish_event(WorkspaceUpdateEvent::TaskLockChanged {
+            task_id: task_id.to_string(),
+            session_ids,
+            locked,
+        });
+
+        Ok(())
+    }
+
+    /// Sessions of all locked tasks
+    pub async fn locked_session_ids(&self) -> Vec<String> {
+        let config = self.config.read().await;
+        config
+            .tasks
+            .iter()
+            .filter(|t| t.locked)
+            .flat_map(|t| t.session_ids())
+            .collect()
+    }
+
+    /// Rate an agent reply in the conversation of `session_id`'s task
+    ///
+    /// Rating a message again replaces its feedback; `None` clears it.
+    pub async fn rate_message(
+        &self,
+        session_id: &str,
+        message_id: &str,
+        agent_name: &str,
+        rating: Option<FeedbackRating>,
+        comment: Option<String>,
+    ) -> ServiceResult<()> {
+        {
+            let mut config = self.config.write().await;
 
-            task.set_session(session_id);
+            let task = config
+                .find_task_by_session(session_id)
+                .ok_or_else(|| ServiceError::SessionNotFound(session_id.to_string()))?;
+
+            task.feedback.retain(|f| f.message_id != message_id);
+            if let Some(rating) = rating {
+                task.feedback.push(TurnFeedback {
+                    message_id: message_id.to_string(),
+                    agent_name: agent_name.to_string(),
+                    rating,
+                    comment: comment
+                        .map(|c| c.trim().to_string())
+                        .filter(|c| !c.is_empty()),
+                    created_at: chrono::Utc::now(),
+                });
+            }
         }
 
         self.save_config().await?;
+        self.publish_event(WorkspaceUpdateEvent::FeedbackUpdated {
+            session_id: session_id.to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Add a completed turn to the usage of the task that owns `session_id`
+    ///
+    /// Turns of sessions that belong to no task (e.g. warm-pool sessions) are
+    /// not recorded.
+    pub async fn record_turn(
+        &self,
+        session_id: &str,
+        busy_ms: u64,
+        prompt_chars: usize,
+        output_chars: usize,
+    ) -> ServiceResult<()> {
+        let task_id = {
+            let mut config = self.config.write().await;
+            let Some(task) = config.tasks.iter_mut().find(|t| t.has_session(session_id)) else {
+                return Ok(());
+            };
+            task.record_turn(busy_ms, prompt_chars, output_chars);
+            task.id.clone()
+        };
+
+        self.save_config().await?;
+        self.publish_event(WorkspaceUpdateEvent::TaskUpdated { task_id });
+        Ok(())
+    }
 
+    /// Add time the user spent active in the conversation of `session_id`'s task
+    pub async fn add_active_time(&self, session_id: &str, active_ms: u64) -> ServiceResult<()> {
+        let task_id = {
+            let mut config = self.config.write().await;
+            let Some(task) = config.tasks.iter_mut().find(|t| t.has_session(session_id)) else {
+                return Ok(());
+            };
+            task.active_ms += active_ms;
+            task.id.clone()
+        };
+
+        self.save_config().await?;
+        self.publish_event(WorkspaceUpdateEvent::TaskUpdated { task_id });
         Ok(())
     }
 
+    /// Record the turns reported on the workspace bus
+    pub fn start_usage_tracking(self: &Arc<Self>) {
+        let Some(bus) = self.workspace_bus.clone() else {
+            return;
+        };
+        let service = Arc::downgrade(self);
+        bus.subscribe(move |event| {
+            let WorkspaceUpdateEvent::TurnCompleted {
+                session_id,
+                busy_ms,
+                prompt_chars,
+                output_chars,
+                ..
+            } = event
+            else {
+                return;
+            };
+            let Some(service) = service.upgrade() else {
+                return;
+            };
+            let (session_id, busy_ms, prompt_chars, output_chars) =
+                (session_id.clone(), *busy_ms, *prompt_chars, *output_chars);
+            smol::spawn(async move {
+                if let Err(e) = service
+                    .record_turn(&session_id, busy_ms, prompt_chars, output_chars)
+                    .await
+                {
+                    log::warn!("Failed to record turn of session {}: {}", session_id, e);
+                }
+            })
+            .detach();
+        });
+    }
+
+    /// Feedback, usage and duration per experiment label across all tasks
+    pub async fn label_report(&self) -> Vec<LabelSummary> {
+        let config = self.config.read().await;
+        summarize_by_label(&config.tasks)
+    }
+
+    /// Feedback totals per agent across all tasks
+    pub async fn feedback_report(&self) -> Vec<AgentFeedbackSummary> {
+        let config = self.config.read().await;
+        summarize_feedback(config.tasks.iter().flat_map(|t| &t.feedback))
+    }
+
     /// Get all tasks for a workspace
     pub async fn get_workspace_tasks(&self, workspace_id: &str) -> Vec<WorkspaceTask> {
         let config = self.config.read().await;
@@ -252,7 +783,11 @@ impl WorkspaceService {
     }
 
     /// Update task status
-    pub async fn update_task_status(&self, task_id: &str, status: SessionStatus) -> Result<()> {
+    pub async fn update_task_status(
+        &self,
+        task_id: &str,
+        status: SessionStatus,
+    ) -> ServiceResult<()> {
         {
             let mut config = self.config.write().await;
 
@@ -260,9 +795,9 @@ impl WorkspaceService {
                 .tasks
                 .iter_mut()
                 .find(|t| t.id == task_id)
-                .context("Task not found")?;
+                .ok_or_else(|| ServiceError::TaskNotFound(task_id.to_string()))?;
 
-            task.status = status;
+            task.set_status(status);
         }
 
         self.save_config().await?;
@@ -271,7 +806,11 @@ impl WorkspaceService {
     }
 
     /// Update task's last message
-    pub async fn update_task_message(&self, session_id: &str, message: String) -> Result<()> {
+    pub async fn update_task_message(
+        &self,
+        session_id: &str,
+        message: String,
+    ) -> ServiceResult<()> {
         {
             let mut config = self.config.write().await;
 
@@ -302,18 +841,76 @@ impl WorkspaceService {
         config.tasks.clone()
     }
 
+    /// Up to `limit` tasks of all workspaces older than `after`, newest first,
+    /// so lists can load them as they are scrolled to
+    pub async fn get_tasks_page(&self, after: Option<&TaskCursor>, limit: usize) -> TaskPage {
+        let config = self.config.read().await;
+        config.tasks_page(after, limit)
+    }
+
     /// Get a specific task by ID
     pub async fn get_task(&self, task_id: &str) -> Option<WorkspaceTask> {
         let config = self.config.read().await;
         config.tasks.iter().find(|t| t.id == task_id).cloned()
     }
 
+    /// Fold the usage, active time and feedback of `duplicate_ids` into
+    /// `keep_id` and remove those tasks
+    ///
+    /// Returns the removed tasks, whose sessions the caller may delete.
+    pub async fn merge_tasks(
+        &self,
+        keep_id: &str,
+        duplicate_ids: &[String],
+    ) -> ServiceResult<Vec<WorkspaceTask>> {
+        let (workspace_id, removed) = {
+            let mut config = self.config.write().await;
+            if !config.tasks.iter().any(|t| t.id == keep_id) {
+                return Err(ServiceError::TaskNotFound(keep_id.to_string()));
+            }
+
+            let removed: Vec<WorkspaceTask> = duplicate_ids
+                .iter()
+                .filter(|id| id.as_str() != keep_id)
+                .filter_map(|id| config.remove_task(id))
+                .collect();
+            let Some(keep) = config.tasks.iter_mut().find(|t| t.id == keep_id) else {
+                return Err(ServiceError::TaskNotFound(keep_id.to_string()));
+            };
+            for task in &removed {
+                keep.usage.add(&task.usage);
+                keep.active_ms += task.active_ms;
+                keep.feedback.extend(task.feedback.iter().cloned());
+                keep.message_count += task.message_count;
+                keep.last_updated = keep.last_updated.max(task.last_updated);
+            }
+            (keep.workspace_id.clone(), removed)
+        };
+
+        self.save_config().await?;
+
+        for task in &removed {
+            self.publish_event(WorkspaceUpdateEvent::TaskRemoved {
+                workspace_id: workspace_id.clone(),
+                task_id: task.id.clone(),
+            });
+        }
+        self.publish_event(WorkspaceUpdateEvent::TaskUpdated {
+            task_id: keep_id.to_string(),
+        });
+
+        log::info!("Merged {} duplicate tasks into {}", removed.len(), keep_id);
+        Ok(removed)
+    }
+
     /// Remove a task by ID
-    pub async fn remove_task(&self, task_id: &str) -> Result<()> {
+    pub async fn remove_task(&self, task_id: &str) -> ServiceResult<()> {
         let workspace_id = {
             let mut config = self.config.write().await;
 
-            let task = config.remove_task(task_id).context("Task not found")?;
+            let task = config
+                .remove_task(task_id)
+                .ok_or_else(|| ServiceError::TaskNotFound(task_id.to_string()))?;
 
             task.workspace_id.clone()
         };
@@ -330,3 +927,22 @@ impl WorkspaceService {
         Ok(())
     }
 }
+
+/// Where a session of `workspace` is stored, if it keeps sessions in its repository
+fn repo_sessions_dir(workspace: Option<&Workspace>) -> Option<PathBuf> {
+    workspace
+        .filter(|workspace| workspace.repo_sessions)
+        .map(Workspace::repo_sessions_dir)
+}
+
+/// Write the sharing guidance to `<dir>/.gitignore` unless it exists
+fn write_repo_gitignore(dir: &std::path::Path) -> ServiceResult<()> {
+    let path = dir.join(".gitignore");
+    if path.exists() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(dir)
+        .map_err(|e| ServiceError::io("Failed to create .agent-studio directory", e))?;
+    std::fs::write(&path, REPO_GITIGNORE)
+        .map_err(|e| ServiceError::io("Failed to write .agent-studio/.gitignore", e))
+}
@@ -0,0 +1,186 @@
+//! `agent-studio://` links
+//!
+//! Terminals, browsers and other tools open a session or a workspace in
+//! AgentX with a link:
+//!
+//! - `agent-studio://session/<id>` shows the session's conversation
+//! - `agent-studio://workspace?path=<dir>` adds the folder as a workspace
+//!   when it is new, makes it the active one and shows its welcome panel
+//!
+//! The app bundle declares the scheme on macOS; `agentx register-url-scheme`
+//! registers the executable as its handler on Linux and Windows. A link that
+//! starts a second process is handed to the running app through the editor
+//! bridge, so it opens in the existing window.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+/// Scheme of the links AgentX opens
+pub const SCHEME: &str = "agent-studio";
+
+/// Subcommand registering AgentX as the handler of the scheme
+pub const REGISTER_SUBCOMMAND: &str = "register-url-scheme";
+
+/// What a link asks AgentX to open
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenLink {
+    Session(String),
+    Workspace(PathBuf),
+}
+
+impl OpenLink {
+    /// Parse an `agent-studio://` link; `None` for other URLs and unknown targets
+    pub fn parse(url: &str) -> Option<Self> {
+        let rest = url
+            .split_once("://")
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(SCHEME))?
+            .1;
+        let (target, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let target = target.trim_end_matches('/');
+        if let Some(id) = target.strip_prefix("session/") {
+            let id = percent_decode(id)?;
+            return (!id.is_empty() && !id.contains('/')).then_some(Self::Session(id));
+        }
+        if target == "workspace" {
+            let path = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("path="))
+                .and_then(|value| percent_decode(&value.replace('+', " ")))?;
+            return (!path.is_empty()).then(|| Self::Workspace(PathBuf::from(path)));
+        }
+        None
+    }
+}
+
+/// The first `agent-studio://` link among command line arguments
+pub fn link_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    args.into_iter().find(|arg| OpenLink::parse(arg).is_some())
+}
+
+/// Decode `%XX` escapes; `None` when they are malformed or not UTF-8
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut ix = 0;
+    while ix < bytes.len() {
+        if bytes[ix] == b'%' {
+            let hex = value.get(ix + 1..ix + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            ix += 3;
+        } else {
+            decoded.push(bytes[ix]);
+            ix += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Register this executable as the handler of `agent-studio://` links for
+/// the current user
+pub fn register() -> Result<()> {
+    let exe = std::env::current_exe().context("Cannot find the AgentX executable")?;
+    register_handler(&exe.to_string_lossy())
+}
+
+#[cfg(target_os = "linux")]
+fn register_handler(exe: &str) -> Result<()> {
+    const DESKTOP_FILE: &str = "agentx-url-handler.desktop";
+
+    let dir = dirs::data_dir()
+        .context("Cannot find the user data directory")?
+        .join("applications");
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=AgentX\nExec=\"{}\" %u\nNoDisplay=true\n\
+         MimeType=x-scheme-handler/{};\n",
+        exe, SCHEME
+    );
+    let path = dir.join(DESKTOP_FILE);
+    std::fs::write(&path, entry).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let mime_type = format!("x-scheme-handler/{}", SCHEME);
+    let status = std::process::Command::new("xdg-mime")
+        .args(["default", DESKTOP_FILE, mime_type.as_str()])
+        .status()
+        .context("Failed to run xdg-mime")?;
+    anyhow::ensure!(status.success(), "xdg-mime failed ({})", status);
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn register_handler(exe: &str) -> Result<()> {
+    let key = format!("HKCU\\Software\\Classes\\{}", SCHEME);
+    let command = format!("\"{}\" \"%1\"", exe);
+    let entries = [
+        (key.clone(), None, "URL:AgentX".to_string()),
+        (key.clone(), Some("URL Protocol"), String::new()),
+        (format!("{}\\shell\\open\\command", key), None, command),
+    ];
+    for (key, name, data) in entries {
+        let mut reg = std::process::Command::new("reg");
+        reg.args(["add", key.as_str()]);
+        match name {
+            Some(name) => reg.args(["/v", name]),
+            None => reg.arg("/ve"),
+        };
+        let status = reg
+            .args(["/d", data.as_str(), "/f"])
+            .status()
+            .context("Failed to run reg")?;
+        anyhow::ensure!(status.success(), "reg add {} failed ({})", key, status);
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn register_handler(_exe: &str) -> Result<()> {
+    anyhow::bail!(
+        "The AgentX app bundle registers {}:// links on this system",
+        SCHEME
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_session() {
+        assert_eq!(
+            OpenLink::parse("agent-studio://session/3f2a-91"),
+            Some(OpenLink::Session("3f2a-91".to_string()))
+        );
+        assert_eq!(
+            OpenLink::parse("Agent-Studio://session/abc/"),
+            Some(OpenLink::Session("abc".to_string()))
+        );
+        assert_eq!(OpenLink::parse("agent-studio://session/"), None);
+        assert_eq!(OpenLink::parse("agent-studio://task/abc"), None);
+        assert_eq!(OpenLink::parse("https://session/abc"), None);
+    }
+
+    #[test]
+    fn test_parse_workspace() {
+        assert_eq!(
+            OpenLink::parse("agent-studio://workspace?path=%2Fhome%2Fme%2Fmy%20project"),
+            Some(OpenLink::Workspace(PathBuf::from("/home/me/my project")))
+        );
+        assert_eq!(
+            OpenLink::parse("agent-studio://workspace/?focus=1&path=C:%5Ccode"),
+            Some(OpenLink::Workspace(PathBuf::from("C:\\code")))
+        );
+        assert_eq!(OpenLink::parse("agent-studio://workspace"), None);
+        assert_eq!(OpenLink::parse("agent-studio://workspace?path=%zz"), None);
+    }
+
+    #[test]
+    fn test_link_from_args() {
+        let args = ["--profile", "work", "agent-studio://session/abc"].map(String::from);
+        assert_eq!(
+            link_from_args(args).as_deref(),
+            Some("agent-studio://session/abc")
+        );
+        assert_eq!(link_from_args(["--doctor".to_string()]), None);
+    }
+}
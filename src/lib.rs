@@ -21,8 +21,9 @@ mod test_mock_data;
 // Re-export from panels module
 use crate::panels::{DockPanelContainer, DockPanelState};
 pub use panels::{
-    AppSettings, CodeEditorPanel, ConversationPanel, SessionManagerPanel, SettingsPanel, TaskPanel,
-    TerminalPanel, ToolCallDetailPanel, WelcomePanel,
+    AppSettings, AuditPanel, CodeEditorPanel, ConversationPanel, DashboardPanel, FeedbackPanel,
+    GitPanel, McpBrowserPanel, McpConsolePanel, MemoryPanel, SearchPanel, SessionManagerPanel,
+    SettingsPanel, TaskPanel, TerminalPanel, ToolCallDetailPanel, WelcomePanel,
 };
 
 // Re-export from core module
@@ -39,11 +40,14 @@ pub use core::{
 pub use app::app_state::{AppState, WelcomeSession};
 pub use app::{
     actions::{
-        About, AddAgent, AddSessionToList, CancelSession, CloseWindow, CreateTaskFromWelcome, Info,
-        NewSessionConversationPanel, Open, PanelAction, Quit, ReloadAgentConfig, RemoveAgent,
-        RestartAgent, SelectFont, SelectLocale, SelectRadius, SelectScrollbarShow,
-        SelectedAgentTask, SendMessageToSession, SetUploadDir, ShowPanelInfo, Tab, TabPrev,
-        TestAction, ToggleDockToggleButton, TogglePanelVisible, ToggleSearch, UpdateAgent,
+        About, AddAgent, AddSessionToList, CancelSession, CloseWindow, CompareAgents, CreateTask,
+        CreateTaskFromWelcome, ExportSession, ExportStats, FindDuplicateTasks, GoBack, GoForward,
+        ImportChatLogs, Info, MoveSessionPanel, NewProfile, NewSessionConversationPanel, NewTask,
+        Open, PanelAction, QuickSwitch, Quit, ReloadAgentConfig, RemoveAgent, RestartAgent,
+        SelectFont, SelectLocale, SelectRadius, SelectScrollbarShow, SelectedAgentTask,
+        SendMessageToSession, SetUploadDir, ShowPanelInfo, SwitchProfile, SyncNow, Tab, TabPrev,
+        TestAction, ToggleDockToggleButton, ToggleFocusMode, TogglePanelVisible, ToggleSearch,
+        UpdateAgent,
     },
     app_menus, menu, system_tray, themes, title_bar,
 };
@@ -183,8 +187,20 @@ impl Render for DockRoot {
 }
 
 pub fn init(cx: &mut App) {
+    // With a relocated data directory the logs are kept there as well
+    let file_layer = core::config_manager::open_log_file().map(|file| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(std::sync::Mutex::new(file))
+    });
+    // The last log lines go into crash reports
+    let crash_layer = tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .with_writer(|| core::crash_report::LogTail);
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer())
+        .with(file_layer)
+        .with(crash_layer)
         .with(
             tracing_subscriber::EnvFilter::from_default_env()
                 .add_directive("gpui_component=trace".parse().unwrap()),
@@ -1,17 +1,115 @@
 use agentx::Assets;
-use agentx::core::config_manager;
+use agentx::core::config::launch_configs;
+use agentx::core::services::{
+    DEFAULT_PROFILE, ExportFormat, ImportOptions, ImportService, MetricsService,
+    PersistenceService, ProfileService, ServiceError, WorkspaceService, forward_link,
+};
+use agentx::core::{config_manager, crash_report, mcp_proxy, safe_mode, url_scheme};
 use agentx::{AgentManager, Config, PermissionStore, workspace::open_new};
 use anyhow::Context as _;
 use gpui::Application;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 fn main() {
+    // `mcp-proxy` relays an MCP server for an agent, hiding tools disabled for the session
+    if std::env::args().nth(1).as_deref() == Some(mcp_proxy::SUBCOMMAND) {
+        std::process::exit(mcp_proxy::run(std::env::args().skip(2)));
+    }
+
+    // `--data-dir` and portable mode move all config and data, so resolve them first
+    select_data_dir();
+
+    // `export-stats` writes task metrics and feedback for external analysis and exits
+    if std::env::args().nth(1).as_deref() == Some("export-stats") {
+        std::process::exit(run_export_stats(std::env::args().skip(2)));
+    }
+
+    // `import` adds conversations exported from other tools as tasks and exits
+    if std::env::args().nth(1).as_deref() == Some("import") {
+        std::process::exit(run_import(std::env::args().skip(2)));
+    }
+
+    // `register-url-scheme` makes this executable open agent-studio:// links and exits
+    if std::env::args().nth(1).as_deref() == Some(url_scheme::REGISTER_SUBCOMMAND) {
+        std::process::exit(match url_scheme::register() {
+            Ok(()) => {
+                println!("Registered {}:// links", url_scheme::SCHEME);
+                0
+            }
+            Err(e) => {
+                eprintln!("{:#}", e);
+                1
+            }
+        });
+    }
+
+    // The profile decides where config and data live, so pick it before anything reads them
+    let profile_service = Arc::new(select_profile(parse_flag("--profile")));
+
+    // An agent-studio:// link opens in the running app when there is one
+    let link = url_scheme::link_from_args(std::env::args().skip(1));
+    if let Some(url) = &link
+        && forward_link(&config_manager::get_editor_bridge_path(), url)
+    {
+        return;
+    }
+
     // Parse config path from command line arguments
     let config_path = parse_config_path();
+    config_manager::set_config_path(config_path.clone());
+
+    // `--doctor` health-checks the configured agents and exits without opening a window
+    if std::env::args().any(|arg| arg == "--doctor") {
+        std::process::exit(run_doctor(&config_path));
+    }
+
+    // Save pending history and write a report when the app panics
+    crash_report::install();
+
+    // Launches that crashed while starting offer safe mode
+    let unfinished = safe_mode::begin_startup();
+    if std::env::args().any(|arg| arg == safe_mode::FLAG)
+        || (safe_mode::should_offer(unfinished) && safe_mode::ask(unfinished))
+    {
+        safe_mode::activate();
+    }
+
+    // Links from the command line and those the OS sends while running
+    let (link_tx, link_rx) = smol::channel::unbounded::<String>();
+    if let Some(url) = link {
+        _ = link_tx.try_send(url);
+    }
 
     let app = Application::new().with_assets(Assets);
+    app.on_open_urls(move |urls| {
+        for url in urls {
+            _ = link_tx.try_send(url);
+        }
+    });
     app.run(move |cx| {
         agentx::init(cx);
+        agentx::AppState::global_mut(cx).set_profile_service(profile_service.clone());
+
+        // The workspace opens what links point at once its window is up
+        cx.spawn(async move |cx| {
+            while let Ok(url) = link_rx.recv().await {
+                _ = cx.update(|cx| {
+                    if let Some(bridge) = agentx::AppState::global(cx).editor_bridge_service() {
+                        bridge.open_link(&url);
+                    }
+                });
+            }
+        })
+        .detach();
+
+        // A launch that lasts or quits normally did not crash while starting
+        cx.spawn(async move |cx| {
+            cx.background_executor().timer(safe_mode::STABLE_AFTER).await;
+            safe_mode::finish_startup();
+        })
+        .detach();
+        cx.on_app_quit(|_| async { safe_mode::finish_startup() }).detach();
 
         // Initialize system tray
         match agentx::system_tray::SystemTray::new() {
@@ -73,7 +171,8 @@ fn main() {
                 agentx::AppSettings::global(cx).nodejs_path.clone()
             });
 
-            let mut agent_servers = config.agent_servers.clone();
+            // Launch variants run as agents of their own
+            let mut agent_servers = launch_configs(&config.agent_servers);
             if !nodejs_path.is_empty() {
                 log::info!("Using custom Node.js path from settings: {}", nodejs_path);
                 // Inject nodejs_path into all agent configs
@@ -85,16 +184,30 @@ fn main() {
 
             // Initialize agent manager (this happens in background after GUI is shown)
             let permission_store = Arc::new(PermissionStore::default());
+            permission_store.set_policies(config.permission_policies.clone());
+            permission_store.set_command_policy(&config.command_policy);
 
-            match AgentManager::initialize(
-                agent_servers,
-                permission_store.clone(),
-                session_bus.clone(),
-                permission_bus.clone(),
-                config.proxy.clone(),
-            )
-            .await
-            {
+            // Safe mode starts each agent only when a session needs it
+            let manager = if safe_mode::is_active() {
+                AgentManager::initialize_hibernated(
+                    agent_servers,
+                    permission_store.clone(),
+                    session_bus.clone(),
+                    permission_bus.clone(),
+                    config.proxy.clone(),
+                )
+                .await
+            } else {
+                AgentManager::initialize(
+                    agent_servers,
+                    permission_store.clone(),
+                    session_bus.clone(),
+                    permission_bus.clone(),
+                    config.proxy.clone(),
+                )
+                .await
+            };
+            match manager {
                 Ok(manager) => {
                     println!(
                         "Initializing {} agents in background...",
@@ -131,20 +244,63 @@ fn main() {
     });
 }
 
-/// Parse config path from command line arguments or use user data directory
-fn parse_config_path() -> std::path::PathBuf {
+/// Value of `flag` on the command line, e.g. `--config <path>`
+fn parse_flag(flag: &str) -> Option<String> {
     let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next();
+        }
+    }
+    None
+}
 
-    // Check if user specified a custom config path via --config flag
-    while let Some(flag) = args.next() {
-        if flag == "--config" {
-            if let Some(value) = args.next() {
-                return std::path::PathBuf::from(value);
-            }
+/// Relocate the data directory for `--data-dir <path>` or portable mode
+///
+/// Portable mode is on when an `agentx-data` directory sits next to the
+/// executable, e.g. on a USB stick; `--data-dir` takes precedence over it.
+fn select_data_dir() {
+    let dir = parse_flag("--data-dir")
+        .map(PathBuf::from)
+        .or_else(config_manager::portable_data_dir);
+    if let Some(dir) = dir {
+        config_manager::set_data_dir(dir);
+        if let Ok(dir) = config_manager::get_user_data_dir() {
+            eprintln!("Using data directory {}", dir.display());
         }
     }
+}
+
+/// Select the profile to run with and point the config manager at its root
+///
+/// `requested` comes from `--profile <name>` and is created when missing;
+/// without it the profile last chosen from the menu is used.
+fn select_profile(requested: Option<String>) -> ProfileService {
+    let service = ProfileService::load(config_manager::user_data_dir_or_temp());
+    let name = requested.unwrap_or_else(|| service.current());
+    let profile = match service.use_profile(&name) {
+        Ok(profile) => profile,
+        Err(e) => {
+            eprintln!("Cannot use profile '{}': {:#}", name, e);
+            service
+                .use_profile(DEFAULT_PROFILE)
+                .expect("the default profile always exists")
+        }
+    };
+    let root = service.root(&profile);
+    eprintln!("Using profile '{}' at {}", profile.name, root.display());
+    config_manager::set_profile_root(root);
+    service
+}
 
-    // No custom config specified, use user data directory
+/// Parse config path from command line arguments or use the profile's directory
+fn parse_config_path() -> std::path::PathBuf {
+    // Check if user specified a custom config path via --config flag
+    if let Some(value) = parse_flag("--config") {
+        return std::path::PathBuf::from(value);
+    }
+
+    // No custom config specified, use the profile's directory
     match config_manager::initialize_user_config() {
         Ok(path) => {
             println!("Using config from user data directory: {}", path.display());
@@ -166,6 +322,204 @@ fn parse_config_path() -> std::path::PathBuf {
     }
 }
 
+/// Run the agent doctor against the config file and print a report
+///
+/// Returns the process exit code: 0 when every agent is healthy, 1 otherwise.
+fn run_doctor(config_path: &std::path::Path) -> i32 {
+    let config = match std::fs::read_to_string(config_path)
+        .with_context(|| format!("failed to read {}", config_path.display()))
+        .and_then(|raw| {
+            serde_json::from_str::<Config>(&raw)
+                .with_context(|| format!("invalid config at {}", config_path.display()))
+        }) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            return 1;
+        }
+    };
+
+    if config.agent_servers.is_empty() {
+        eprintln!("No agents configured in {}", config_path.display());
+        return 1;
+    }
+
+    println!(
+        "Checking {} agents from {}\n",
+        config.agent_servers.len(),
+        config_path.display()
+    );
+    let results = smol::block_on(agentx::core::agent::diagnose_agents(
+        &config.agent_servers,
+        &config.proxy,
+        agentx::core::agent::DEFAULT_DOCTOR_TIMEOUT,
+    ));
+    for diagnosis in &results {
+        println!("{}\n", diagnosis.to_report());
+    }
+
+    let failed = results.iter().filter(|d| !d.is_healthy()).count();
+    if failed == 0 {
+        println!("All agents healthy");
+        0
+    } else {
+        println!("{} of {} agents failed", failed, results.len());
+        1
+    }
+}
+
+/// Export session metadata, usage metrics and feedback as JSON or CSV
+///
+/// `agentx export-stats [--format json|csv] [--output <file>] [--profile <name>]
+/// [--data-dir <path>]` prints to stdout without `--output`; the format defaults
+/// to the output file's extension.
+/// Returns the process exit code.
+fn run_export_stats(mut args: impl Iterator<Item = String>) -> i32 {
+    let mut format = None;
+    let mut output = None;
+    let mut profile = None;
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--format", Some(value)) => match value.parse::<ExportFormat>() {
+                Ok(value) => format = Some(value),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return 2;
+                }
+            },
+            ("--output" | "-o", Some(path)) => output = Some(PathBuf::from(path)),
+            ("--profile", Some(name)) => profile = Some(name),
+            // Applied in `main` already
+            ("--data-dir", Some(_)) => {}
+            _ => {
+                eprintln!(
+                    "Usage: agentx export-stats [--format json|csv] [--output <file>] \
+                     [--profile <name>] [--data-dir <path>]"
+                );
+                return 2;
+            }
+        }
+    }
+    select_profile(profile);
+    let format = format
+        .or_else(|| output.as_deref().map(ExportFormat::from_path))
+        .unwrap_or_default();
+
+    let persistence_service = Arc::new(PersistenceService::new(config_manager::get_sessions_dir()));
+    let mut workspace_service = WorkspaceService::new(config_manager::get_workspace_config_path());
+    workspace_service.set_persistence_service(persistence_service.clone());
+    let workspace_service = Arc::new(workspace_service);
+    let metrics_service = MetricsService::new(workspace_service, persistence_service);
+    let export = smol::block_on(metrics_service.export_stats());
+    let rendered = match export.render(format) {
+        Ok(rendered) => rendered,
+        Err(e) => {
+            eprintln!("Failed to export stats: {:#}", e);
+            return 1;
+        }
+    };
+
+    match output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, rendered) {
+                eprintln!("Failed to write {}: {}", path.display(), e);
+                return 1;
+            }
+            eprintln!("Exported {} tasks to {}", export.tasks.len(), path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    0
+}
+
+/// Import Claude Code session logs, Aider chat histories and Markdown transcripts
+///
+/// `agentx import [--format claude-code|aider|markdown] [--workspace <folder>]
+/// [--agent <name>] [--profile <name>] [--data-dir <path>] <file or folder>...`
+/// detects each file's format unless `--format` is given and reads folders
+/// recursively. `--workspace` is added as a workspace if it is none yet;
+/// without it, conversations go to the workspace of the folder they took
+/// place in, else to the active one.
+/// Returns the process exit code.
+fn run_import(mut args: impl Iterator<Item = String>) -> i32 {
+    const USAGE: &str = "Usage: agentx import [--format claude-code|aider|markdown] \
+                         [--workspace <folder>] [--agent <name>] [--profile <name>] \
+                         [--data-dir <path>] <file or folder>...";
+    let mut options = ImportOptions::default();
+    let mut workspace = None;
+    let mut profile = None;
+    let mut paths = Vec::new();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            paths.push(PathBuf::from(arg));
+            continue;
+        }
+        match (arg.as_str(), args.next()) {
+            ("--format", Some(value)) => match value.parse() {
+                Ok(value) => options.format = Some(value),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return 2;
+                }
+            },
+            ("--workspace", Some(path)) => workspace = Some(PathBuf::from(path)),
+            ("--agent", Some(name)) => options.agent_name = Some(name),
+            ("--profile", Some(name)) => profile = Some(name),
+            // Applied in `main` already
+            ("--data-dir", Some(_)) => {}
+            _ => {
+                eprintln!("{}", USAGE);
+                return 2;
+            }
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("{}", USAGE);
+        return 2;
+    }
+    select_profile(profile);
+
+    let persistence_service = Arc::new(PersistenceService::new(config_manager::get_sessions_dir()));
+    let mut workspace_service = WorkspaceService::new(config_manager::get_workspace_config_path());
+    workspace_service.set_persistence_service(persistence_service.clone());
+    let workspace_service = Arc::new(workspace_service);
+    let import_service = ImportService::new(workspace_service.clone(), persistence_service);
+
+    let report = smol::block_on(async {
+        if let Some(path) = workspace {
+            let path = path.canonicalize().unwrap_or(path);
+            let workspace = match workspace_service.add_workspace(path.clone()).await {
+                Ok(workspace) => Some(workspace),
+                Err(ServiceError::WorkspaceExists(_)) => workspace_service
+                    .list_workspaces()
+                    .await
+                    .into_iter()
+                    .find(|workspace| workspace.path == path),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return None;
+                }
+            };
+            options.workspace_id = workspace.map(|workspace| workspace.id);
+        }
+        Some(import_service.import_paths(&paths, &options).await)
+    });
+    let Some(report) = report else {
+        return 1;
+    };
+
+    for (path, error) in &report.failed {
+        eprintln!("{}: {}", path.display(), error);
+    }
+    eprintln!(
+        "Imported {} conversations, skipped {} imported before, {} failed",
+        report.imported,
+        report.skipped,
+        report.failed.len()
+    );
+    if report.failed.is_empty() { 0 } else { 1 }
+}
+
 fn load_default_config() -> anyhow::Result<Config> {
     let raw = agentx::get_default_config()
         .ok_or_else(|| anyhow::anyhow!("embedded default config missing"))?;
@@ -0,0 +1,372 @@
+//! Audit Panel - Browse and export the audit log
+//!
+//! Lists the files agents wrote, the commands they ran and the permission
+//! decisions taken, newest first, filtered by kind and by session, agent or
+//! subject. The filtered entries can be exported to JSON or CSV.
+
+use std::time::Duration;
+
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement,
+    ParentElement, Pixels, Render, StatefulInteractiveElement, Styled, Subscription, Task, Window,
+    div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonGroup, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    notification::Notification,
+    v_flex,
+};
+use rust_i18n::t;
+use smol::Timer;
+
+use crate::app::focus_mode::NotificationExt as _;
+use crate::{
+    AppState,
+    core::services::{AuditEntry, AuditFilter, AuditKind, ExportFormat, render_entries},
+    panels::dock_panel::DockPanel,
+};
+
+/// How often the log is checked for new entries
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Entries rendered at most; exports include every matching entry
+const MAX_ROWS: usize = 500;
+
+pub struct AuditPanel {
+    focus_handle: FocusHandle,
+    search_state: Entity<InputState>,
+    /// All entries, newest first
+    entries: Vec<AuditEntry>,
+    filter: AuditFilter,
+    /// Audit log revision the entries were read at
+    revision: Option<u64>,
+    _poll_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DockPanel for AuditPanel {
+    fn title() -> &'static str {
+        "Audit"
+    }
+
+    fn title_key() -> Option<&'static str> {
+        Some("audit_panel.title")
+    }
+
+    fn description() -> &'static str {
+        "Files written, commands run and permission decisions of agents"
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn paddings() -> Pixels {
+        px(12.)
+    }
+}
+
+impl AuditPanel {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(t!("audit_panel.search").to_string())
+        });
+        let search_subscription =
+            cx.subscribe(&search_state, |this, input, event: &InputEvent, cx| {
+                if let InputEvent::Change = event {
+                    this.filter.query = input.read(cx).value().to_string();
+                    cx.notify();
+                }
+            });
+
+        // Reload whenever something was appended to the log
+        let poll_task = cx.spawn(async move |this, cx| {
+            loop {
+                let reload = this.update(cx, |this, cx| this.reload_if_changed(cx));
+                if reload.is_err() {
+                    break;
+                }
+                Timer::after(POLL_INTERVAL).await;
+            }
+        });
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            search_state,
+            entries: Vec::new(),
+            filter: AuditFilter::default(),
+            revision: None,
+            _poll_task: poll_task,
+            _subscriptions: vec![search_subscription],
+        }
+    }
+
+    fn reload_if_changed(&mut self, cx: &mut Context<Self>) {
+        let Some(audit_service) = AppState::global(cx).audit_service().cloned() else {
+            return;
+        };
+        let revision = audit_service.revision();
+        if self.revision == Some(revision) {
+            return;
+        }
+        self.revision = Some(revision);
+
+        cx.spawn(async move |this, cx| {
+            let mut entries = cx
+                .background_spawn(async move { audit_service.entries() })
+                .await;
+            entries.reverse();
+            _ = this.update(cx, |this, cx| {
+                this.entries = entries;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn set_kind(&mut self, kind: Option<AuditKind>, cx: &mut Context<Self>) {
+        self.filter.kind = kind;
+        cx.notify();
+    }
+
+    fn matching_entries(&self) -> Vec<AuditEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| self.filter.matches(entry))
+            .cloned()
+            .collect()
+    }
+
+    /// Save the entries matching the filter to a JSON or CSV file
+    fn export(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        // Oldest first, like the log itself
+        let mut entries = self.matching_entries();
+        entries.reverse();
+        let dialog = rfd::AsyncFileDialog::new()
+            .set_title(t!("audit_panel.export_title").to_string())
+            .set_file_name("agentx-audit.json")
+            .add_filter("JSON", &["json"])
+            .add_filter("CSV", &["csv"]);
+
+        cx.spawn_in(window, async move |_this, window| {
+            let Some(file) = dialog.save_file().await else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+            let result = render_entries(&entries, ExportFormat::from_path(&path))
+                .and_then(|rendered| Ok(std::fs::write(&path, rendered)?));
+
+            _ = window.update(|window, cx| {
+                struct AuditExportResult;
+                let note = match result {
+                    Ok(()) => Notification::success(
+                        t!(
+                            "audit_panel.export_done",
+                            count = entries.len(),
+                            path = path.display()
+                        )
+                        .to_string(),
+                    ),
+                    Err(e) => {
+                        log::error!("Failed to export audit log: {:#}", e);
+                        Notification::error(
+                            t!("audit_panel.export_failed", error = e.to_string()).to_string(),
+                        )
+                    }
+                };
+                window.show_notification(note.id::<AuditExportResult>(), cx);
+            });
+        })
+        .detach();
+    }
+
+    fn render_entry(ix: usize, entry: &AuditEntry, cx: &App) -> impl IntoElement {
+        let theme = cx.theme();
+        let icon = match entry.kind() {
+            AuditKind::FileWrite => IconName::File,
+            AuditKind::Command => IconName::SquareTerminal,
+            AuditKind::PermissionDecision => IconName::TriangleAlert,
+        };
+        let session_id: String = entry.session_id.chars().take(8).collect();
+
+        h_flex()
+            .id(("audit-entry", ix))
+            .w_full()
+            .gap_2()
+            .items_center()
+            .py_1()
+            .px_2()
+            .rounded(px(6.))
+            .when(ix % 2 == 0, |this| this.bg(theme.secondary))
+            .child(
+                div()
+                    .w(px(120.))
+                    .flex_shrink_0()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .child(
+                        entry
+                            .timestamp
+                            .with_timezone(&chrono::Local)
+                            .format("%m-%d %H:%M:%S")
+                            .to_string(),
+                    ),
+            )
+            .child(Icon::new(icon).xsmall().text_color(theme.muted_foreground))
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .text_sm()
+                    .text_color(theme.foreground)
+                    .child(entry.subject().to_string()),
+            )
+            .child(
+                div()
+                    .flex_shrink_0()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .child(entry.outcome()),
+            )
+            .child(
+                div()
+                    .w(px(160.))
+                    .flex_shrink_0()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .child(format!("{} · {}", entry.agent_name, session_id)),
+            )
+    }
+}
+
+impl Focusable for AuditPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for AuditPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let kind = self.filter.kind;
+        let entries = self.matching_entries();
+        let shown = entries.len().min(MAX_ROWS);
+
+        let kind_button = |id: &'static str, label: String, value: Option<AuditKind>| {
+            Button::new(id)
+                .label(label)
+                .ghost()
+                .xsmall()
+                .selected(kind == value)
+                .on_click(cx.listener(move |this, _, _, cx| this.set_kind(value, cx)))
+        };
+
+        v_flex()
+            .size_full()
+            .gap_3()
+            .child(
+                h_flex()
+                    .w_full()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        v_flex()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(theme.foreground)
+                                    .child(t!("audit_panel.title").to_string()),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.muted_foreground)
+                                    .child(t!("audit_panel.description").to_string()),
+                            ),
+                    )
+                    .child(
+                        Button::new("audit-export")
+                            .icon(Icon::new(IconName::ArrowDown))
+                            .label(t!("audit_panel.export").to_string())
+                            .small()
+                            .disabled(entries.is_empty())
+                            .on_click(cx.listener(|this, _, window, cx| this.export(window, cx))),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(
+                        ButtonGroup::new("audit-kind")
+                            .small()
+                            .child(kind_button(
+                                "audit-all",
+                                t!("audit_panel.all").to_string(),
+                                None,
+                            ))
+                            .child(kind_button(
+                                "audit-files",
+                                t!("audit_panel.files").to_string(),
+                                Some(AuditKind::FileWrite),
+                            ))
+                            .child(kind_button(
+                                "audit-commands",
+                                t!("audit_panel.commands").to_string(),
+                                Some(AuditKind::Command),
+                            ))
+                            .child(kind_button(
+                                "audit-permissions",
+                                t!("audit_panel.permissions").to_string(),
+                                Some(AuditKind::PermissionDecision),
+                            )),
+                    )
+                    .child(div().flex_1().child(Input::new(&self.search_state).small())),
+            )
+            .when(entries.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(theme.muted_foreground)
+                        .child(t!("audit_panel.empty").to_string()),
+                )
+            })
+            .child(
+                v_flex()
+                    .id("audit-entries")
+                    .flex_1()
+                    .min_h_0()
+                    .w_full()
+                    .gap_0p5()
+                    .overflow_y_scroll()
+                    .children(
+                        entries
+                            .iter()
+                            .take(MAX_ROWS)
+                            .enumerate()
+                            .map(|(ix, entry)| Self::render_entry(ix, entry, cx)),
+                    ),
+            )
+            .when(entries.len() > shown, |this| {
+                this.child(
+                    div().text_xs().text_color(theme.muted_foreground).child(
+                        t!(
+                            "audit_panel.truncated",
+                            shown = shown,
+                            total = entries.len()
+                        )
+                        .to_string(),
+                    ),
+                )
+            })
+    }
+}
@@ -3,7 +3,6 @@ use std::{ops::Range, str::FromStr, time::Duration};
 use anyhow::anyhow;
 use gpui::{App, AppContext, Context, Entity, Result, SharedString, Task, Window};
 use gpui_component::{
-    WindowExt,
     input::{
         CodeActionProvider, CompletionProvider, DefinitionProvider, DocumentColorProvider,
         HoverProvider, InputState, Rope, RopeExt,
@@ -14,6 +13,7 @@ use lsp_types::{
     CodeAction, CodeActionKind, CompletionContext, CompletionResponse, TextEdit, WorkspaceEdit,
 };
 
+use crate::app::focus_mode::NotificationExt as _;
 use crate::AppState;
 
 use super::lsp_store::CodeEditorPanelLspStore;
@@ -566,7 +566,7 @@ impl TextConvertor {
             let note =
                 Notification::error("AI service not configured. Please check your config.json")
                     .id::<AiServiceError>();
-            window.push_notification(note, cx);
+            window.show_notification(note, cx);
             return Task::ready(Err(anyhow!("AI service not configured")));
         };
 
@@ -606,7 +606,7 @@ impl TextConvertor {
                 struct AiCommentLoading;
                 let loading_note =
                     Notification::info("Generating comment with AI...").id::<AiCommentLoading>();
-                window.push_notification(loading_note, cx);
+                window.show_notification(loading_note, cx);
 
                 window.spawn(cx, async move |cx| {
                     // Call AI service
@@ -632,7 +632,7 @@ impl TextConvertor {
                                 let success_note =
                                     Notification::success("Comment generated successfully!")
                                         .id::<AiCommentSuccess>();
-                                window.push_notification(success_note, cx);
+                                window.show_notification(success_note, cx);
                             })?;
 
                             Ok(())
@@ -655,7 +655,7 @@ impl TextConvertor {
                                             e
                                         ))
                                         .id::<AiCommentError>();
-                                        window.push_notification(error_note, cx);
+                                        window.show_notification(error_note, cx);
                                         log::debug!("Notification pushed successfully");
                                     })
                                 } else {
@@ -679,7 +679,7 @@ impl TextConvertor {
                 struct AiExplainLoading;
                 let loading_note =
                     Notification::info("Analyzing code with AI...").id::<AiExplainLoading>();
-                window.push_notification(loading_note, cx);
+                window.show_notification(loading_note, cx);
 
                 window.spawn(cx, async move |cx| {
                     // Call AI service
@@ -699,7 +699,7 @@ impl TextConvertor {
                                         struct AiExplainResult;
                                         let success_note = Notification::success("Code explanation generated! Check logs for details.")
                                             .id::<AiExplainResult>();
-                                        window.push_notification(success_note, cx);
+                                        window.show_notification(success_note, cx);
                                     }).ok();
                                 }
                             }).ok();
@@ -721,7 +721,7 @@ impl TextConvertor {
                                         log::debug!("Inside window.update, pushing explain error notification");
                                         let error_note = Notification::error(format!("Failed to explain code: {}", e))
                                             .id::<AiExplainError>();
-                                        window.push_notification(error_note, cx);
+                                        window.show_notification(error_note, cx);
                                         log::debug!("Explain error notification pushed successfully");
                                     })
                                 } else {
@@ -745,7 +745,7 @@ impl TextConvertor {
                 struct AiImproveLoading;
                 let loading_note = Notification::info("Analyzing code for improvements with AI...")
                     .id::<AiImproveLoading>();
-                window.push_notification(loading_note, cx);
+                window.show_notification(loading_note, cx);
 
                 window.spawn(cx, async move |cx| {
                     // Call AI service
@@ -765,7 +765,7 @@ impl TextConvertor {
                                         struct AiImproveResult;
                                         let success_note = Notification::success("Code improvement suggestions generated! Check logs for details.")
                                             .id::<AiImproveResult>();
-                                        window.push_notification(success_note, cx);
+                                        window.show_notification(success_note, cx);
                                     }).ok();
                                 }
                             }).ok();
@@ -787,7 +787,7 @@ impl TextConvertor {
                                         log::debug!("Inside window.update, pushing improve error notification");
                                         let error_note = Notification::error(format!("Failed to generate suggestions: {}", e))
                                             .id::<AiImproveError>();
-                                        window.push_notification(error_note, cx);
+                                        window.show_notification(error_note, cx);
                                         log::debug!("Improve error notification pushed successfully");
                                     })
                                 } else {
@@ -1,6 +1,5 @@
 use std::{path::PathBuf, rc::Rc, str::FromStr};
 
-use autocorrect::ignorer::Ignorer;
 use gpui::{prelude::FluentBuilder, *};
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable, StyledExt, WindowExt,
@@ -20,6 +19,7 @@ use super::lsp_providers::TextConvertor;
 use super::lsp_store::CodeEditorPanelLspStore;
 use super::types::build_file_items;
 use crate::AppState;
+use crate::utils::context_item::ContextItem;
 
 pub struct CodeEditorPanel {
     editor: Entity<InputState>,
@@ -32,6 +32,8 @@ pub struct CodeEditorPanel {
     show_file_tree: bool,
     lsp_store: CodeEditorPanelLspStore,
     current_file_path: Option<PathBuf>,
+    /// File and selection last published as the editor's chat context
+    published_context: Option<(PathBuf, Option<(Position, Position)>)>,
     has_opened_file: bool,
     workspace_id: Option<String>,
     workspace_name: Option<String>,
@@ -107,9 +109,19 @@ impl CodeEditorPanel {
             working_dir.unwrap_or_else(|| AppState::global(cx).current_working_dir().clone());
         Self::load_files(tree_state.clone(), working_dir.clone(), cx);
 
-        let _subscriptions = vec![cx.subscribe(&editor, |this, _, _: &InputEvent, cx| {
-            this.lint_document(cx);
-        })];
+        let _subscriptions = vec![
+            cx.subscribe(&editor, |this, _, _: &InputEvent, cx| {
+                this.lint_document(cx);
+            }),
+            // Stop offering the closed editor's file as chat context
+            cx.on_release(|_, cx| {
+                let editor_context = AppState::global(cx).editor_context.clone();
+                editor_context.update(cx, |context, cx| {
+                    *context = None;
+                    cx.notify();
+                });
+            }),
+        ];
 
         Self {
             editor,
@@ -122,6 +134,7 @@ impl CodeEditorPanel {
             show_file_tree: true,
             lsp_store,
             current_file_path: None,
+            published_context: None,
             has_opened_file: false,
             workspace_id: None,
             workspace_name: None,
@@ -132,9 +145,9 @@ impl CodeEditorPanel {
     }
 
     fn load_files(state: Entity<TreeState>, path: PathBuf, cx: &mut App) {
+        let filter = AppState::global(cx).path_filter(&path);
         cx.spawn(async move |cx| {
-            let ignorer = Ignorer::new(&path.to_string_lossy());
-            let items = build_file_items(&ignorer, &path, &path);
+            let items = build_file_items(&filter, &path);
             _ = state.update(cx, |state, cx| {
                 state.set_items(items, cx);
             });
@@ -573,7 +586,10 @@ impl CodeEditorPanel {
             .clone()
             .lock()
             .map(|bus| {
-                bus.publish(crate::core::event_bus::CodeSelectionEvent { selection: action });
+                bus.publish(crate::core::event_bus::CodeSelectionEvent {
+                    selection: action,
+                    session_id: None,
+                });
                 log::info!("[CodeEditorPanel] Event published successfully to CodeSelectionBus");
             })
             .unwrap_or_else(|_| {
@@ -581,6 +597,46 @@ impl CodeEditorPanel {
             });
     }
 
+    /// Share the open file, or the selection within it, as context for new prompts
+    fn sync_editor_context(
+        &mut self,
+        selection: Option<(Position, Position)>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(path) = self.current_file_path.clone() else {
+            return;
+        };
+        let key = (path.clone(), selection);
+        if self.published_context.as_ref() == Some(&key) {
+            return;
+        }
+        self.published_context = Some(key);
+
+        let item = match selection {
+            Some((start_pos, end_pos)) => {
+                let text = self.editor.read(cx).text();
+                let start_offset = text.position_to_offset(&start_pos);
+                let end_offset = text.position_to_offset(&end_pos);
+                ContextItem::Selection {
+                    file_path: path.to_string_lossy().to_string(),
+                    start_line: start_pos.line + 1,
+                    end_line: end_pos.line + 1,
+                    content: text.slice(start_offset..end_offset).to_string(),
+                }
+            }
+            None => ContextItem::File { path },
+        };
+
+        // Defer so the observers don't re-render while this panel is rendering
+        let editor_context = AppState::global(cx).editor_context.clone();
+        cx.defer(move |cx| {
+            editor_context.update(cx, |context, cx| {
+                *context = Some(item);
+                cx.notify();
+            });
+        });
+    }
+
     fn render_empty_state(&self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         v_flex()
             .size_full()
@@ -658,6 +714,8 @@ impl Render for CodeEditorPanel {
             }
         });
 
+        self.sync_editor_context(selection_info.map(|(start, end, _)| (start, end)), cx);
+
         let editor_input = Input::new(&self.editor)
             .bordered(false)
             .p_0()
@@ -1,9 +1,10 @@
 use std::path::PathBuf;
 
-use autocorrect::ignorer::Ignorer;
 use gpui_component::tree::TreeItem;
 use lsp_types::{CompletionItem, CompletionTextEdit, InsertReplaceEdit};
 
+use crate::utils::path_filter::PathFilter;
+
 // ============================================================================
 // Constants
 // ============================================================================
@@ -46,16 +47,13 @@ pub fn completion_item(
     }
 }
 
-pub fn build_file_items(ignorer: &Ignorer, root: &PathBuf, path: &PathBuf) -> Vec<TreeItem> {
+pub fn build_file_items(filter: &PathFilter, path: &PathBuf) -> Vec<TreeItem> {
     let mut items = Vec::new();
 
     if let Ok(entries) = std::fs::read_dir(path) {
         for entry in entries.flatten() {
             let path = entry.path();
-            let relative_path = path.strip_prefix(root).unwrap_or(&path);
-            if ignorer.is_ignored(&relative_path.to_string_lossy())
-                || relative_path.ends_with(".git")
-            {
+            if filter.is_excluded(&path) {
                 continue;
             }
             let file_name = path
@@ -65,7 +63,7 @@ pub fn build_file_items(ignorer: &Ignorer, root: &PathBuf, path: &PathBuf) -> Ve
                 .to_string();
             let id = path.to_string_lossy().to_string();
             if path.is_dir() {
-                let children = build_file_items(ignorer, &root, &path);
+                let children = build_file_items(filter, &path);
                 items.push(TreeItem::new(id, file_name).children(children));
             } else {
                 items.push(TreeItem::new(id, file_name));
@@ -1,19 +1,25 @@
 /// UI Components for ConversationPanel
 use gpui::{
-    Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled, Window, div,
-    prelude::*, px,
+    AnyElement, Context, Entity, IntoElement, ObjectFit, ParentElement, Render, SharedString,
+    Styled, StyledImage, Window, div, img, prelude::*, px,
 };
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
     button::{Button, ButtonVariants},
     collapsible::Collapsible,
-    h_flex, v_flex,
+    h_flex,
+    notification::Notification,
+    v_flex,
 };
+use rust_i18n::t;
 
 use agent_client_protocol::ContentBlock;
 
-use super::types::{ResourceInfo, get_file_icon};
+use super::types::{BlobResource, ResourceInfo, get_file_icon};
 use crate::UserMessageData;
+use crate::app::focus_mode::NotificationExt as _;
+use crate::utils::blob::{self, BlobPreview};
+use crate::utils::external_editor::open_in_file_manager;
 
 // ============================================================================
 // Stateful Resource Item
@@ -36,6 +42,160 @@ impl ResourceItemState {
         self.open = !self.open;
         cx.notify();
     }
+
+    fn file_name(&self, blob: &BlobResource) -> String {
+        blob::file_name_for(&self.resource.name, &blob.preview)
+    }
+
+    /// Ask where to save the blob and write it there
+    fn save_blob(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(blob) = self.resource.blob.clone() else {
+            return;
+        };
+        let dialog = rfd::AsyncFileDialog::new()
+            .set_title(t!("conversation.blob.save_title").to_string())
+            .set_file_name(self.file_name(&blob));
+
+        cx.spawn_in(window, async move |_this, window| {
+            let Some(file) = dialog.save_file().await else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+            let result = std::fs::write(&path, blob.bytes.as_slice());
+
+            _ = window.update(|window, cx| {
+                struct BlobSaveResult;
+                let note = match result {
+                    Ok(()) => Notification::success(
+                        t!("conversation.blob.saved", path = path.display()).to_string(),
+                    ),
+                    Err(e) => {
+                        log::error!("Failed to save {}: {}", path.display(), e);
+                        Notification::error(
+                            t!("conversation.blob.save_failed", error = e.to_string()).to_string(),
+                        )
+                    }
+                };
+                window.show_notification(note.id::<BlobSaveResult>(), cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Open the blob in the app the system uses for its kind, through a
+    /// copy in the temp directory
+    fn open_blob(&mut self, cx: &mut Context<Self>) {
+        let Some(blob) = self.resource.blob.clone() else {
+            return;
+        };
+        let path = std::env::temp_dir()
+            .join("agentx-resources")
+            .join(self.file_name(&blob));
+        cx.background_spawn(async move {
+            let written = path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| std::fs::write(&path, blob.bytes.as_slice()));
+            let opened = written
+                .map_err(|e| e.to_string())
+                .and_then(|()| open_in_file_manager(&path));
+            if let Err(e) = opened {
+                log::warn!("Failed to open {}: {}", path.display(), e);
+            }
+        })
+        .detach();
+    }
+
+    fn render_blob(&self, blob: &BlobResource, cx: &mut Context<Self>) -> AnyElement {
+        let muted_foreground = cx.theme().muted_foreground;
+        match &blob.preview {
+            BlobPreview::Image(_) => match &blob.image {
+                Some(image) => img(image.clone())
+                    .max_w_full()
+                    .max_h(px(360.))
+                    .object_fit(ObjectFit::Contain)
+                    .into_any_element(),
+                None => div().into_any_element(),
+            },
+            BlobPreview::Pdf(info) => v_flex()
+                .gap_1()
+                .text_size(px(12.))
+                .when_some(info.title.clone(), |this, title| {
+                    this.child(
+                        div()
+                            .font_weight(gpui::FontWeight::MEDIUM)
+                            .text_color(cx.theme().foreground)
+                            .child(title),
+                    )
+                })
+                .child(
+                    div().text_color(muted_foreground).child(
+                        match info.pages {
+                            Some(pages) => t!("conversation.blob.pdf_pages", count = pages),
+                            None => t!("conversation.blob.pdf_unknown_pages"),
+                        }
+                        .to_string(),
+                    ),
+                )
+                .child(
+                    h_flex().child(
+                        Button::new(SharedString::from(format!(
+                            "resource-open-{}",
+                            self.resource.name
+                        )))
+                        .label(t!("conversation.blob.open").to_string())
+                        .outline()
+                        .xsmall()
+                        .on_click(cx.listener(|this, _ev, _window, cx| this.open_blob(cx))),
+                    ),
+                )
+                .into_any_element(),
+            BlobPreview::Zip(listing) | BlobPreview::Tar(listing) => v_flex()
+                .gap_0p5()
+                .text_size(px(12.))
+                .font_family("Monaco, 'Courier New', monospace")
+                .children(listing.entries.iter().map(|entry| {
+                    h_flex()
+                        .gap_3()
+                        .child(
+                            div()
+                                .flex_1()
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .text_color(cx.theme().foreground)
+                                .child(entry.name.clone()),
+                        )
+                        .when(!entry.is_dir, |this| {
+                            this.child(
+                                div()
+                                    .text_color(muted_foreground)
+                                    .child(blob::format_size(entry.size)),
+                            )
+                        })
+                }))
+                .when(listing.total > listing.entries.len(), |this| {
+                    this.child(
+                        div().text_color(muted_foreground).child(
+                            t!(
+                                "conversation.blob.more_entries",
+                                count = listing.total - listing.entries.len()
+                            )
+                            .to_string(),
+                        ),
+                    )
+                })
+                .into_any_element(),
+            BlobPreview::Gzip(name) => div()
+                .text_size(px(12.))
+                .text_color(muted_foreground)
+                .child(match name {
+                    Some(name) => t!("conversation.blob.gzip_of", name = name).to_string(),
+                    None => t!("conversation.blob.gzip").to_string(),
+                })
+                .into_any_element(),
+            BlobPreview::None => div().into_any_element(),
+        }
+    }
 }
 
 impl Render for ResourceItemState {
@@ -48,7 +208,11 @@ impl Render for ResourceItemState {
             .unwrap_or(0);
 
         let is_open = self.open;
-        let has_content = self.resource.text.is_some();
+        let blob = self.resource.blob.clone();
+        let has_content = self.resource.text.is_some()
+            || blob
+                .as_ref()
+                .is_some_and(|blob| blob.preview != BlobPreview::None);
         let resource_name = self.resource.name.clone();
         let mime_type = self.resource.mime_type.clone();
 
@@ -86,6 +250,34 @@ impl Render for ResourceItemState {
                                 .child(format!("{} lines", line_count)),
                         )
                     })
+                    .when_some(blob.clone(), |this, blob| {
+                        let kind = blob.preview.extension().map(str::to_uppercase);
+                        let size = blob::format_size(blob.bytes.len() as u64);
+                        this.child(
+                            div()
+                                .text_size(px(11.))
+                                .text_color(cx.theme().muted_foreground)
+                                .child(match kind {
+                                    Some(kind) => format!("{} · {}", kind, size),
+                                    None => size,
+                                }),
+                        )
+                        .child(
+                            Button::new(SharedString::from(format!(
+                                "resource-save-{}",
+                                resource_name
+                            )))
+                            .icon(IconName::ArrowDown)
+                            .ghost()
+                            .xsmall()
+                            .tooltip(t!("conversation.blob.save").to_string())
+                            .on_click(cx.listener(
+                                |this, _ev, window, cx| {
+                                    this.save_blob(window, cx);
+                                },
+                            )),
+                        )
+                    })
                     .when(has_content, |this| {
                         this.child(
                             Button::new(SharedString::from(format!(
@@ -108,6 +300,16 @@ impl Render for ResourceItemState {
                     }),
             )
             .when(has_content, |this| {
+                let content = match (&self.resource.text, &blob) {
+                    (None, Some(blob)) => self.render_blob(blob, cx),
+                    (text, _) => div()
+                        .text_size(px(12.))
+                        .font_family("Monaco, 'Courier New', monospace")
+                        .text_color(cx.theme().foreground)
+                        .line_height(px(18.))
+                        .child(text.clone().unwrap_or_default())
+                        .into_any_element(),
+                };
                 this.content(
                     div()
                         .w_full()
@@ -116,14 +318,7 @@ impl Render for ResourceItemState {
                         .bg(cx.theme().secondary)
                         .border_1()
                         .border_color(cx.theme().border)
-                        .child(
-                            div()
-                                .text_size(px(12.))
-                                .font_family("Monaco, 'Courier New', monospace")
-                                .text_color(cx.theme().foreground)
-                                .line_height(px(18.))
-                                .child(self.resource.text.clone().unwrap_or_default()),
-                        ),
+                        .child(content),
                 )
             })
     }
@@ -146,6 +341,10 @@ impl AgentThoughtItemState {
         }
     }
 
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
     /// Append more text to the thought (for streaming updates)
     pub fn append_text(&mut self, text: impl Into<String>, cx: &mut Context<Self>) {
         self.text.push_str(&text.into());
@@ -267,7 +466,7 @@ impl Render for UserMessageView {
                                     .into_any_element(),
                             ),
                             ContentBlock::ResourceLink(_) | ContentBlock::Resource(_) => {
-                                if ResourceInfo::from_content_block(&content).is_some() {
+                                if ResourceInfo::describes(&content) {
                                     let current_index = resource_index;
                                     resource_index += 1;
 
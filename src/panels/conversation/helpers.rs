@@ -1,4 +1,8 @@
 use agent_client_protocol::{ContentBlock, EmbeddedResourceResource, SessionUpdate};
+
+use rust_i18n::t;
+
+use crate::core::services::ChatOptions;
 // Helper functions for ConversationPanel
 
 /// Get a unique ElementId from a string identifier
@@ -54,3 +58,355 @@ pub fn session_update_type_name(update: &SessionUpdate) -> &'static str {
         _ => "Unknown/Future SessionUpdate Type",
     }
 }
+
+/// Split a leading `@AgentName` mention off a prompt
+///
+/// Only names from `agents` count (case-insensitive, longest match wins), so
+/// `@src/main.rs` file mentions are left alone. Returns the agent's configured
+/// name and the remaining prompt.
+pub fn parse_agent_mention<'a>(text: &'a str, agents: &[String]) -> Option<(String, &'a str)> {
+    let rest = text.trim_start().strip_prefix('@')?;
+
+    agents
+        .iter()
+        .filter(|agent| {
+            rest.get(..agent.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(agent))
+                && rest[agent.len()..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace)
+        })
+        .max_by_key(|agent| agent.len())
+        .map(|agent| (agent.clone(), rest[agent.len()..].trim_start()))
+}
+
+/// Plain-text digest of a session, the input for generated PR descriptions
+///
+/// Sections without content are left out.
+pub fn session_digest(
+    prompts: &[String],
+    plan: &[String],
+    changed_files: &[String],
+    final_reply: Option<&str>,
+) -> String {
+    let list = |items: &[String]| {
+        items
+            .iter()
+            .map(|item| format!("- {}", item.trim()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let mut sections = Vec::new();
+    if !prompts.is_empty() {
+        sections.push(format!("## User requests\n{}", list(prompts)));
+    }
+    if !plan.is_empty() {
+        sections.push(format!("## Plan\n{}", list(plan)));
+    }
+    if !changed_files.is_empty() {
+        sections.push(format!("## Changed files\n{}", list(changed_files)));
+    }
+    if let Some(reply) = final_reply.map(str::trim).filter(|reply| !reply.is_empty()) {
+        sections.push(format!("## Final summary\n{}", reply));
+    }
+    sections.join("\n\n")
+}
+
+/// Follow-up prompt asking the agent to run a tool call again with the same
+/// arguments, after the user fixed what made it fail
+pub fn rerun_prompt(title: &str, raw_input: Option<&serde_json::Value>) -> String {
+    let mut prompt = format!(
+        "I fixed the underlying issue. Run this tool call again with exactly the same \
+         arguments, then report the new result.\n\nTool call: {}",
+        title.trim()
+    );
+    if let Some(input) = raw_input {
+        let arguments = serde_json::to_string_pretty(input).unwrap_or_else(|_| input.to_string());
+        prompt.push_str(&format!("\nArguments:\n```json\n{}\n```", arguments));
+    }
+    prompt
+}
+
+/// Pretty JSON of `value` cut to its first `max_lines` lines, and whether
+/// lines were cut
+pub fn json_preview(value: &serde_json::Value, max_lines: usize) -> (String, bool) {
+    let pretty = serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+    let mut lines = pretty.lines();
+    let preview = lines
+        .by_ref()
+        .take(max_lines)
+        .collect::<Vec<_>>()
+        .join("\n");
+    (preview, lines.next().is_some())
+}
+
+/// Follow-up prompts for a finished turn, guessed from the agent's reply
+///
+/// Used when no utility model is configured to suggest them.
+pub fn heuristic_follow_ups(reply: &str, changed_files: bool) -> Vec<String> {
+    let reply = reply.to_lowercase();
+    let mut suggestions = Vec::new();
+    if ["error", "failed", "failing"].iter().any(|word| reply.contains(word)) {
+        suggestions.push(t!("conversation.follow_up.fix_errors"));
+    }
+    if changed_files {
+        if !reply.contains("test") {
+            suggestions.push(t!("conversation.follow_up.add_tests"));
+        }
+        suggestions.push(t!("conversation.follow_up.explain_change"));
+        suggestions.push(t!("conversation.follow_up.review_edge_cases"));
+    } else {
+        if reply.contains("```") {
+            suggestions.push(t!("conversation.follow_up.apply_to_code"));
+        }
+        suggestions.push(t!("conversation.follow_up.explain_more"));
+        suggestions.push(t!("conversation.follow_up.show_example"));
+    }
+    suggestions.into_iter().take(3).map(String::from).collect()
+}
+
+/// Language agent replies are translated into: the app's display language
+pub fn translation_language() -> &'static str {
+    match rust_i18n::locale().to_string().as_str() {
+        "zh-CN" => "Simplified Chinese",
+        _ => "English",
+    }
+}
+
+/// Most replies one regenerate can ask for
+pub const MAX_REPLY_VARIANTS: u32 = 5;
+
+/// Parameters typed into the regenerate popover; empty fields keep the
+/// model's defaults
+///
+/// Returns `None` when a temperature outside 0-2, a max tokens that is not
+/// a positive number or a variant count outside 1-`MAX_REPLY_VARIANTS` is
+/// entered.
+pub fn parse_chat_options(
+    temperature: &str,
+    max_tokens: &str,
+    variants: &str,
+) -> Option<ChatOptions> {
+    let temperature = match temperature.trim() {
+        "" => None,
+        value => match value.parse::<f32>() {
+            Ok(temperature) if (0.0..=2.0).contains(&temperature) => Some(temperature),
+            _ => return None,
+        },
+    };
+    let max_tokens = match max_tokens.trim() {
+        "" => None,
+        value => match value.parse::<u32>() {
+            Ok(max_tokens) if max_tokens > 0 => Some(max_tokens),
+            _ => return None,
+        },
+    };
+    let variants = match variants.trim() {
+        "" => None,
+        value => match value.parse::<u32>() {
+            Ok(variants) if (1..=MAX_REPLY_VARIANTS).contains(&variants) => Some(variants),
+            _ => return None,
+        },
+    };
+    Some(ChatOptions {
+        temperature,
+        max_tokens,
+        variants,
+    })
+}
+
+/// Kinds of rendered items that comments can be anchored to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageRole {
+    User,
+    Agent,
+}
+
+/// Index of the message each item belongs to, the anchor of comments
+///
+/// Adjacent items of one role count as one message, as in the saved history,
+/// which merges their chunks; shared views count messages the same way.
+pub fn message_indices(roles: &[Option<MessageRole>]) -> Vec<Option<usize>> {
+    let mut count = 0;
+    let mut previous = None;
+    roles
+        .iter()
+        .map(|role| {
+            let index = role.map(|role| {
+                if previous != Some(role) {
+                    count += 1;
+                }
+                count - 1
+            });
+            previous = *role;
+            index
+        })
+        .collect()
+}
+
+/// The turn whose last reply each item is, the anchor of turn latencies
+///
+/// A turn is a user message and the items up to the next one; turns count
+/// user messages the way `MessageService` counts them in the history.
+pub fn turn_reply_ends(roles: &[Option<MessageRole>]) -> Vec<Option<usize>> {
+    let mut ends = vec![None; roles.len()];
+    let mut turn: Option<usize> = None;
+    let mut last_reply = None;
+    let mut previous = None;
+    for (ix, role) in roles.iter().enumerate() {
+        match role {
+            Some(MessageRole::User) if previous != Some(MessageRole::User) => {
+                if let (Some(turn), Some(last_reply)) = (turn, last_reply.take()) {
+                    ends[last_reply] = Some(turn);
+                }
+                turn = Some(turn.map_or(0, |turn| turn + 1));
+            }
+            Some(MessageRole::Agent) => last_reply = Some(ix),
+            _ => {}
+        }
+        previous = *role;
+    }
+    if let (Some(turn), Some(last_reply)) = (turn, last_reply) {
+        ends[last_reply] = Some(turn);
+    }
+    ends
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_agent_mention() {
+        let agents = vec![
+            "claude".to_string(),
+            "Claude Code".to_string(),
+            "codex".to_string(),
+        ];
+
+        assert_eq!(
+            parse_agent_mention("@codex fix the build", &agents),
+            Some(("codex".to_string(), "fix the build"))
+        );
+        assert_eq!(
+            parse_agent_mention("@claude code review this", &agents),
+            Some(("Claude Code".to_string(), "review this"))
+        );
+        assert_eq!(parse_agent_mention("@codexx hi", &agents), None);
+        assert_eq!(parse_agent_mention("@src/main.rs explain", &agents), None);
+        assert_eq!(parse_agent_mention("ask @codex later", &agents), None);
+    }
+
+    #[test]
+    fn test_json_preview() {
+        let value = serde_json::json!({"sessionUpdate": "usage", "tokens": 12});
+        let (preview, cut) = json_preview(&value, 2);
+        assert_eq!(preview, "{\n  \"sessionUpdate\": \"usage\",");
+        assert!(cut);
+
+        let (preview, cut) = json_preview(&serde_json::json!([]), 2);
+        assert_eq!(preview, "[]");
+        assert!(!cut);
+    }
+
+    #[test]
+    fn test_session_digest_skips_empty_sections() {
+        let digest = session_digest(
+            &["Add retries to the client".to_string()],
+            &[],
+            &["src/client.rs (+12 -3)".to_string()],
+            Some("  Retries are in place.  "),
+        );
+        assert_eq!(
+            digest,
+            "## User requests\n- Add retries to the client\n\n\
+             ## Changed files\n- src/client.rs (+12 -3)\n\n\
+             ## Final summary\nRetries are in place."
+        );
+        assert!(session_digest(&[], &[], &[], None).is_empty());
+    }
+
+    #[test]
+    fn test_rerun_prompt() {
+        let input = serde_json::json!({"command": "cargo test"});
+        let prompt = rerun_prompt("Run tests ", Some(&input));
+        assert!(prompt.contains("Tool call: Run tests\nArguments:\n```json\n{\n  \"command\""));
+        assert!(prompt.ends_with("}\n```"));
+
+        assert!(rerun_prompt("Read file", None).ends_with("Tool call: Read file"));
+    }
+
+    #[test]
+    fn test_heuristic_follow_ups() {
+        assert_eq!(
+            heuristic_follow_ups("Updated the parser.", true),
+            vec![
+                "Add tests for these changes",
+                "Explain this change",
+                "Review the changes for edge cases"
+            ]
+        );
+        assert_eq!(
+            heuristic_follow_ups("The build failed, see the test output.", true),
+            vec!["Fix the errors", "Explain this change", "Review the changes for edge cases"]
+        );
+        assert_eq!(
+            heuristic_follow_ups("Try this:\n```rust\nfn a() {}\n```", false)[0],
+            "Apply this to the code"
+        );
+    }
+
+    #[test]
+    fn test_parse_chat_options() {
+        assert_eq!(
+            parse_chat_options("", " ", ""),
+            Some(ChatOptions::default())
+        );
+        assert_eq!(
+            parse_chat_options("0.7", "1024", "3"),
+            Some(ChatOptions {
+                temperature: Some(0.7),
+                max_tokens: Some(1024),
+                variants: Some(3),
+            })
+        );
+        assert_eq!(parse_chat_options("2.5", "", ""), None);
+        assert_eq!(parse_chat_options("", "0", ""), None);
+        assert_eq!(parse_chat_options("warm", "", ""), None);
+        assert_eq!(parse_chat_options("", "", "6"), None);
+    }
+
+    #[test]
+    fn test_message_indices() {
+        use MessageRole::{Agent, User};
+        assert_eq!(
+            message_indices(&[Some(User), Some(User), Some(Agent), None, Some(Agent), None]),
+            vec![Some(0), Some(0), Some(1), None, Some(2), None]
+        );
+        assert!(message_indices(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_turn_reply_ends() {
+        use MessageRole::{Agent, User};
+        let ends = turn_reply_ends(&[
+            Some(Agent),
+            Some(User),
+            Some(Agent),
+            None,
+            Some(Agent),
+            Some(User),
+            Some(User),
+            None,
+            Some(User),
+            Some(Agent),
+        ]);
+        // Replies before the first prompt belong to no turn
+        assert_eq!(ends.iter().flatten().count(), 2);
+        assert_eq!(ends[4], Some(0));
+        assert_eq!(ends[9], Some(2));
+        assert!(turn_reply_ends(&[]).is_empty());
+    }
+}
@@ -4,6 +4,8 @@ mod components;
 mod helpers;
 mod panel;
 mod rendered_item;
+mod table_attach;
+mod transcript;
 pub mod types;
 mod update_state_manager;
 
@@ -1,32 +1,79 @@
 use gpui::{
-    App, ClipboardEntry, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement,
-    Render, ScrollHandle, SharedString, Styled, Window, div, prelude::*, px,
+    App, AsyncApp, ClipboardEntry, ClipboardItem, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, IntoElement, ParentElement, Render, ScrollHandle, SharedString, Styled,
+    Subscription, WeakEntity, Window, div, prelude::*, px,
 };
 
 use gpui_component::{
-    ActiveTheme, Icon, IconName, Sizable, StyledExt, h_flex, input::InputState, skeleton::Skeleton,
-    spinner::Spinner, v_flex,
+    ActiveTheme, Icon, IconName, Selectable, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    dialog::DialogButtonProps,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    menu::ContextMenuExt,
+    notification::Notification,
+    popover::Popover,
+    skeleton::Skeleton,
+    spinner::Spinner,
+    text::TextView,
+    v_flex,
 };
 
 // Use the published ACP schema crate
-use agent_client_protocol::{ContentChunk, ImageContent, PlanEntryStatus, SessionUpdate, ToolCall};
+use agent_client_protocol::{
+    AvailableCommand, ContentBlock, ContentChunk, ImageContent, PlanEntryStatus, SessionUpdate,
+    ToolCall, ToolCallStatus,
+};
 use chrono::{DateTime, Utc};
 use rust_i18n::t;
 use smol::Timer;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedReceiver;
 
+use crate::app::focus_mode::NotificationExt as _;
 use crate::components::ToolCallItem;
 use crate::{
-    AgentMessage, AgentTodoList, AppState, ChatInputBox, DiffSummary, DiffSummaryData,
-    SendMessageToSession, app::actions::AddCodeSelection, core::services::SessionStatus,
-    panels::dock_panel::DockPanel,
+    AgentMessage, AgentMessageData, AgentTodoList, AppState, ChatInputBox, DiffSummary,
+    DiffSummaryData, SendMessageToSession,
+    components::{ContextActions, ContextTray, JsonTree},
+    app::actions::AddCodeSelection,
+    core::{
+        event_bus::session_bus::SessionUpdateEvent,
+        mcp_proxy::{self, DisabledTools},
+        services::{
+            AgentConfigService, AgentService, HistoryEntry, ServiceError, ServiceResult,
+            SessionComment, SessionStatus, TurnLatency, WorkspaceService, find_instruction_files,
+            model_for_agent,
+        },
+    },
+    panels::{
+        AppSettings,
+        dock_panel::{DockPanel, DockPanelContainer, PanelActivity},
+        estimate_tokens,
+    },
+    schemas::workspace_schema::FeedbackRating,
+    utils::{
+        clipboard::PastedText,
+        context_item::ContextItem,
+        language, slash_command,
+        tabular::Table,
+        time::{ActivityTimer, format_latency},
+    },
 };
 
 // Import from submodules
 use super::{
     components::{AgentThoughtItemState, ResourceItemState, UserMessageView},
-    helpers::{extract_text_from_content, get_element_id, session_update_type_name},
+    helpers::{
+        MAX_REPLY_VARIANTS, extract_text_from_content, get_element_id, heuristic_follow_ups,
+        json_preview, message_indices, parse_agent_mention, parse_chat_options, rerun_prompt,
+        session_digest, session_update_type_name, translation_language, turn_reply_ends,
+    },
     rendered_item::{RenderedItem, create_agent_message_data},
+    table_attach::{PickedFile, TableAttachment},
+    transcript::{self, TranscriptEntry},
     types::ResourceInfo,
     update_state_manager::{UpdateProcessor, UpdateStateIndex},
 };
@@ -40,6 +87,26 @@ pub struct SessionStatusInfo {
     pub message_count: usize,
 }
 
+/// Translation of an agent reply, shown under it
+#[derive(Clone, Debug)]
+enum Translation {
+    Pending,
+    Done(SharedString),
+    Failed(SharedString),
+}
+
+/// Replies to the last prompt of a direct chat, one per regenerate
+struct ReplyVariants {
+    /// The message showing the selected attempt
+    message_id: String,
+    /// Finished attempts, oldest first
+    attempts: Vec<AgentMessageData>,
+    /// Extra variants of the attempt still streaming, added after it
+    pending: Vec<AgentMessageData>,
+    /// Attempt shown; `attempts.len()` while a new one is streaming
+    selected: usize,
+}
+
 /// Conversation panel that displays SessionUpdate messages from ACP
 pub struct ConversationPanel {
     focus_handle: FocusHandle,
@@ -51,16 +118,81 @@ pub struct ConversationPanel {
     next_index: usize,
     /// Optional session ID to filter updates (None = all sessions)
     session_id: Option<String>,
+    /// Sessions of other agents @mentioned in this task (agent name -> session ID)
+    linked_sessions: HashMap<String, String>,
     /// Scroll handle for auto-scrolling to bottom
     scroll_handle: ScrollHandle,
     /// Input state for the chat input box
     input_state: Entity<InputState>,
+    /// Whether the input shows the markdown preview instead of the editor
+    input_preview: bool,
+    /// Slash commands matching what is typed after "/"
+    command_suggestions: Vec<AvailableCommand>,
+    /// Argument hint for a completed command with no arguments yet
+    command_hint: Option<String>,
+    _input_subscription: Option<Subscription>,
     /// List of pasted images: (ImageContent, filename)
     pasted_images: Vec<(ImageContent, String)>,
     /// List of code selections from editor
     code_selections: Vec<AddCodeSelection>,
+    /// Large pastes attached as text resources
+    pasted_texts: Vec<PastedText>,
+    /// Large paste waiting for the user to attach or inline it
+    pending_paste: Option<String>,
+    /// Context pinned to this conversation, attached to every prompt
+    pinned_context: Vec<ContextItem>,
+    /// Attach the code editor's open file or selection to prompts
+    include_editor_context: bool,
+    /// MCP servers and disabled tools chosen for the session; `None` shows
+    /// the ones it was created with
+    mcp_scope: Option<(Vec<String>, DisabledTools)>,
+    /// Tool names of the MCP servers whose tools were listed
+    mcp_tools: HashMap<String, Vec<String>>,
+    /// The task is locked (read-only): no prompts can be sent
+    locked: bool,
+    /// Why a restored session could not be reconnected to its agent; the
+    /// conversation is read-only until it is
+    detached: Option<String>,
+    /// Scroll offset to restore once the history is loaded
+    restore_scroll: Option<f32>,
+    /// The conversation as one document while selecting text across messages
+    selectable_text: Option<SharedString>,
+    _editor_context_subscription: Option<Subscription>,
     /// Session status information for display
     session_status: Option<SessionStatusInfo>,
+    /// Position in the agent's concurrency queue while waiting for a slot
+    queue_position: Option<usize>,
+    /// Current retry attempt and max attempts while a prompt is being retried
+    retry_attempt: Option<(u32, u32)>,
+    /// Output limit (in characters) that cancelled the last turn
+    output_limited: Option<usize>,
+    /// Response language of the workspace the last reply was not written in
+    language_drift: Option<String>,
+    /// Follow-up prompts offered under the last completed turn
+    follow_ups: Vec<String>,
+    /// The user's ratings of agent replies (message ID -> rating)
+    feedback: HashMap<String, FeedbackRating>,
+    /// Comments viewers of a live share left on the session's messages
+    comments: Vec<SessionComment>,
+    /// Latency of the session's timed turns
+    turn_latencies: Vec<TurnLatency>,
+    /// Translations of agent replies (message ID -> translation)
+    translations: HashMap<String, Translation>,
+    /// Agent replies are translated as they finish
+    auto_translate: bool,
+    /// Earlier replies to the last prompt of a direct chat session
+    reply_variants: Option<ReplyVariants>,
+    /// Temperature and max tokens overriding the model's defaults on regenerate
+    regenerate_temperature: Entity<InputState>,
+    regenerate_max_tokens: Entity<InputState>,
+    /// Number of replies to ask for on regenerate
+    regenerate_variants: Entity<InputState>,
+    /// Time the user is active in the conversation, added to the task's time
+    activity_timer: ActivityTimer,
+    /// Instruction files in the workspace root (file name, attached to new
+    /// sessions) and the workspace they were found in
+    instruction_files: Vec<(String, bool)>,
+    instruction_workspace_id: Option<String>,
     /// Workspace information
     workspace_id: Option<String>,
     workspace_name: Option<String>,
@@ -69,6 +201,12 @@ pub struct ConversationPanel {
 
 const MESSAGE_SERVICE_RETRY_DELAY_MS: u64 = 500;
 const MESSAGE_SERVICE_MAX_RETRIES: usize = 60;
+/// Gap between interactions after which the user counts as away
+const ACTIVE_IDLE_AFTER: Duration = Duration::from_secs(120);
+/// Active time collected before it is saved to the task
+const ACTIVE_SAVE_AFTER: Duration = Duration::from_secs(60);
+/// JSON lines shown on the card of an update kind this version does not handle
+const UNSUPPORTED_PREVIEW_LINES: usize = 12;
 
 impl ConversationPanel {
     /// Create a new panel with mock data (for demo purposes)
@@ -78,6 +216,8 @@ impl ConversationPanel {
         Self::subscribe_to_updates(&entity, None, cx);
         Self::subscribe_to_permissions(&entity, None, cx);
         Self::subscribe_to_code_selections(&entity, cx);
+        Self::subscribe_to_input(&entity, window, cx);
+        Self::observe_editor_context(&entity, cx);
         log::info!("✅ ConversationPanel view created and subscribed");
         entity
     }
@@ -87,21 +227,44 @@ impl ConversationPanel {
         log::info!("🚀 Creating ConversationPanel for session: {}", session_id);
         let entity = cx.new(|cx| Self::new_for_session(session_id.clone(), window, cx));
 
-        // Load historical messages before subscribing to new updates
-        Self::load_history_for_session(&entity, session_id.clone(), cx);
-
-        Self::subscribe_to_updates(&entity, Some(session_id.clone()), cx);
+        // Historical messages, then the running turn, then new updates
+        Self::restore_session(&entity, session_id.clone(), cx);
         Self::subscribe_to_permissions(&entity, Some(session_id.clone()), cx);
         Self::subscribe_to_code_selections(&entity, cx);
         Self::subscribe_to_status_updates(&entity, Some(session_id.clone()), cx);
+        Self::load_task_state(&entity, session_id.clone(), cx);
+        Self::load_comments(&entity, session_id.clone(), cx);
+        Self::load_turn_latencies(&entity, session_id.clone(), cx);
+        Self::load_instruction_files(&entity, session_id.clone(), cx);
+        Self::subscribe_to_input(&entity, window, cx);
+        Self::observe_editor_context(&entity, cx);
+        // Sessions of an earlier run are not known to the agent until resumed
+        Self::reconnect_session(&entity, session_id.clone(), cx);
         log::info!("✅ ConversationPanel created for session: {}", session_id);
         entity
     }
 
+    /// Reopen a session that was open when AgentX quit, at `scroll_offset`
+    pub fn view_for_restored_session(
+        session_id: String,
+        scroll_offset: Option<f32>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let entity = Self::view_for_session(session_id, window, cx);
+        entity.update(cx, |this, _| this.restore_scroll = scroll_offset);
+        entity
+    }
+
     pub fn session_id(&self) -> Option<String> {
         self.session_id.clone()
     }
 
+    /// Vertical scroll offset of the message list
+    pub fn scroll_offset(&self) -> Option<f32> {
+        Some(self.scroll_handle.offset().y.as_f32())
+    }
+
     /// Get the workspace_id (if available)
     pub fn workspace_id(&self) -> Option<String> {
         self.workspace_id.clone()
@@ -134,6 +297,9 @@ impl ConversationPanel {
         let focus_handle = cx.focus_handle();
         let scroll_handle = ScrollHandle::new();
         let input_state = Self::create_input_state(window, cx);
+        let regenerate_temperature = cx.new(|cx| InputState::new(window, cx).placeholder("0.7"));
+        let regenerate_max_tokens = cx.new(|cx| InputState::new(window, cx).placeholder("4096"));
+        let regenerate_variants = cx.new(|cx| InputState::new(window, cx).placeholder("1"));
         let rendered_items = Vec::new();
         let update_index = UpdateStateIndex::new();
         let next_index = rendered_items.len();
@@ -144,11 +310,44 @@ impl ConversationPanel {
             update_index,
             next_index,
             session_id,
+            linked_sessions: HashMap::new(),
             scroll_handle,
             input_state,
+            input_preview: false,
+            command_suggestions: Vec::new(),
+            command_hint: None,
+            _input_subscription: None,
             pasted_images: Vec::new(),
             code_selections: Vec::new(),
+            pasted_texts: Vec::new(),
+            pending_paste: None,
+            pinned_context: Vec::new(),
+            include_editor_context: AppSettings::global(cx).include_editor_context,
+            mcp_scope: None,
+            mcp_tools: HashMap::new(),
+            locked: false,
+            detached: None,
+            restore_scroll: None,
+            selectable_text: None,
+            _editor_context_subscription: None,
             session_status: None,
+            queue_position: None,
+            retry_attempt: None,
+            output_limited: None,
+            language_drift: None,
+            follow_ups: Vec::new(),
+            feedback: HashMap::new(),
+            translations: HashMap::new(),
+            auto_translate: false,
+            reply_variants: None,
+            regenerate_temperature,
+            regenerate_max_tokens,
+            regenerate_variants,
+            comments: Vec::new(),
+            turn_latencies: Vec::new(),
+            activity_timer: ActivityTimer::new(ACTIVE_IDLE_AFTER),
+            instruction_files: Vec::new(),
+            instruction_workspace_id: None,
             workspace_id: None,
             workspace_name: None,
             working_directory: None,
@@ -157,85 +356,158 @@ impl ConversationPanel {
 
     fn create_input_state(window: &mut Window, cx: &mut App) -> Entity<InputState> {
         cx.new(|cx| {
+            // Plain text mode (no code_editor highlighting) keeps huge pastes responsive
             InputState::new(window, cx)
-                .auto_grow(1, 3)
+                .multi_line(true)
+                .auto_grow(1, 10)
                 .soft_wrap(true)
                 .placeholder("Type a message...")
         })
     }
 
-    /// Load historical messages for a session
-    pub fn load_history_for_session(entity: &Entity<Self>, session_id: String, cx: &mut App) {
-        let persistence_service = match AppState::global(cx).persistence_service() {
-            Some(service) => service.clone(),
-            None => {
-                log::error!("PersistenceService not initialized, cannot load history");
-                return;
+    /// Replay the persisted updates of `session_id`; `task_agent` names the
+    /// agent of its task, for sessions not resumed yet
+    fn show_history(
+        &mut self,
+        session_id: &str,
+        history: Vec<HistoryEntry>,
+        task_agent: Option<String>,
+        cx: &mut Context<Self>,
+    ) {
+        let agent_name = AppState::global(cx)
+            .agent_service()
+            .and_then(|service| service.get_agent_for_session(session_id))
+            .or(task_agent);
+
+        // Use optimized UpdateProcessor for batch loading
+        for entry in history.into_iter() {
+            log::debug!(
+                "Loading historical message {}: timestamp={}",
+                self.next_index,
+                entry.timestamp()
+            );
+
+            let mut processor = UpdateProcessor::<ConversationPanel>::new(
+                &mut self.rendered_items,
+                &mut self.update_index,
+                Some(session_id),
+                agent_name.as_deref(),
+                self.next_index,
+            );
+
+            match entry {
+                HistoryEntry::Message(message) => processor.process_update(message.update, cx),
+                HistoryEntry::Unsupported(_, update) => processor.process_unsupported(update),
             }
+            self.next_index += 1;
+        }
+
+        log::info!(
+            "Loaded history for session {}: {} items, next_index={}",
+            session_id,
+            self.rendered_items.len(),
+            self.next_index
+        );
+
+        self.add_diff_summary_if_needed(cx);
+        // Linked sessions load their history too; only the own one restores
+        let restore_scroll = match self.session_id.as_deref() == Some(session_id) {
+            true => self.restore_scroll.take(),
+            false => None,
         };
+        match restore_scroll {
+            Some(y) => self.scroll_handle.set_offset(gpui::point(px(0.), px(y))),
+            None => self.scroll_handle.scroll_to_bottom(),
+        }
+        cx.notify();
+    }
 
+    /// Rebuild the conversation of a session: its history, then the updates
+    /// of a turn it is still running, then new updates as they arrive
+    ///
+    /// Unlike loading the history and subscribing separately, an in-flight
+    /// turn continues rendering, e.g. after the window was recreated.
+    pub fn restore_session(entity: &Entity<Self>, session_id: String, cx: &mut App) {
+        Self::restore_session_with_retry(
+            entity.clone(),
+            session_id,
+            MESSAGE_SERVICE_MAX_RETRIES,
+            cx,
+        );
+    }
+
+    fn restore_session_with_retry(
+        entity: Entity<Self>,
+        session_id: String,
+        remaining_attempts: usize,
+        cx: &mut App,
+    ) {
         let weak_entity = entity.downgrade();
 
-        log::info!("Loading history for session: {}", session_id);
+        let Some(message_service) = AppState::global(cx).message_service().cloned() else {
+            if remaining_attempts == 0 {
+                log::error!(
+                    "MessageService not initialized, cannot restore {}",
+                    session_id
+                );
+                return;
+            }
+            cx.spawn(async move |cx| {
+                Timer::after(Duration::from_millis(MESSAGE_SERVICE_RETRY_DELAY_MS)).await;
+                let _ = cx.update(|cx| {
+                    if let Some(entity) = weak_entity.upgrade() {
+                        Self::restore_session_with_retry(
+                            entity,
+                            session_id,
+                            remaining_attempts - 1,
+                            cx,
+                        );
+                    }
+                });
+            })
+            .detach();
+            return;
+        };
+
+        let workspace_service = AppState::global(cx).workspace_service().cloned();
 
-        cx.spawn(
-            async move |cx| match persistence_service.load_messages(&session_id).await {
-                Ok(messages) => {
-                    log::info!(
-                        "Loaded {} historical messages for session: {}",
-                        messages.len(),
-                        session_id
-                    );
+        // Subscribe first: the turn and the receiver together miss no update
+        let (live_turn, rx) = message_service.subscribe_with_live_turn(session_id.clone());
+        if let Some(live_turn) = &live_turn {
+            log::info!(
+                "Restoring session {} with {} updates of its running turn",
+                session_id,
+                live_turn.updates.len()
+            );
+        }
 
-                    let weak = weak_entity.clone();
+        cx.spawn(async move |cx| {
+            match message_service
+                .load_history_with_live_turn(&session_id, live_turn.as_ref())
+                .await
+            {
+                Ok(messages) => {
+                    let task_agent = match &workspace_service {
+                        Some(service) => service
+                            .get_task_by_session(&session_id)
+                            .await
+                            .map(|task| task.agent_name),
+                        None => None,
+                    };
                     let _ = cx.update(|cx| {
-                        if let Some(entity) = weak.upgrade() {
+                        if let Some(entity) = weak_entity.upgrade() {
                             entity.update(cx, |this, cx| {
-                                let agent_name = AppState::global(cx)
-                                    .agent_service()
-                                    .and_then(|service| service.get_agent_for_session(&session_id));
-
-                                // Use optimized UpdateProcessor for batch loading
-                                for persisted_msg in messages.into_iter() {
-                                    log::debug!(
-                                        "Loading historical message {}: timestamp={}",
-                                        this.next_index,
-                                        persisted_msg.timestamp
-                                    );
-
-                                    let mut processor = UpdateProcessor::<ConversationPanel>::new(
-                                        &mut this.rendered_items,
-                                        &mut this.update_index,
-                                        Some(session_id.as_str()),
-                                        agent_name.as_deref(),
-                                        this.next_index,
-                                    );
-
-                                    processor.process_update(persisted_msg.update, cx);
-                                    this.next_index += 1;
-                                }
-
-                                log::info!(
-                                    "Loaded history for session {}: {} items, next_index={}",
-                                    session_id,
-                                    this.rendered_items.len(),
-                                    this.next_index
-                                );
-
-                                this.add_diff_summary_if_needed(cx);
-                                this.scroll_handle.scroll_to_bottom();
-                                cx.notify();
+                                this.show_history(&session_id, messages, task_agent, cx);
                             });
-                        } else {
-                            log::warn!("Entity dropped while loading history");
                         }
                     });
                 }
                 Err(e) => {
                     log::error!("Failed to load history for session {}: {}", session_id, e);
                 }
-            },
-        )
+            }
+            Self::receive_updates(weak_entity, rx, Some(session_id), cx).await;
+        })
         .detach();
     }
 
@@ -295,66 +567,77 @@ impl ConversationPanel {
         let session_filter_log_end = session_filter.clone();
 
         // Use MessageService to subscribe with automatic filtering
-        let mut rx = message_service.subscribe_session_updates(session_filter);
+        let rx = message_service.subscribe_session_updates(session_filter);
 
         // Spawn background task to receive updates and update entity
         cx.spawn(async move |cx| {
+            Self::receive_updates(weak_entity, rx, session_filter_log, cx).await;
+        })
+        .detach();
+
+        log::info!(
+            "Subscribed to session updates via MessageService for: {}",
+            session_filter_log_end.as_deref().unwrap_or("all sessions")
+        );
+    }
+
+    /// Render the updates of `rx` until it closes or the panel is dropped
+    async fn receive_updates(
+        weak_entity: WeakEntity<Self>,
+        mut rx: UnboundedReceiver<SessionUpdateEvent>,
+        session_filter_log: Option<String>,
+        cx: &mut AsyncApp,
+    ) {
+        log::info!(
+            "Starting background task for session: {}",
+            session_filter_log.as_deref().unwrap_or("all")
+        );
+
+        while let Some(event) = rx.recv().await {
             log::info!(
-                "Starting background task for session: {}",
+                "Background task received update for session: {}",
                 session_filter_log.as_deref().unwrap_or("all")
             );
 
-            while let Some(event) = rx.recv().await {
-                log::info!(
-                    "Background task received update for session: {}",
-                    session_filter_log.as_deref().unwrap_or("all")
-                );
-
-                let session_id = event.session_id.clone();
-                let agent_name = event.agent_name.clone();
-                let update = (*event.update).clone();
-
-                let weak = weak_entity.clone();
-                let _ = cx.update(|cx| {
-                    if let Some(entity) = weak.upgrade() {
-                        entity.update(cx, |this, cx| {
-                            // Use optimized UpdateProcessor
-                            let mut processor = UpdateProcessor::<ConversationPanel>::new(
-                                &mut this.rendered_items,
-                                &mut this.update_index,
-                                Some(session_id.as_str()),
-                                agent_name.as_deref(),
-                                this.next_index,
-                            );
+            let session_id = event.session_id.clone();
+            let agent_name = event.agent_name.clone();
+            let update = (*event.update).clone();
 
-                            processor.process_update(update, cx);
-                            this.next_index += 1;
+            let weak = weak_entity.clone();
+            let _ = cx.update(|cx| {
+                if let Some(entity) = weak.upgrade() {
+                    entity.update(cx, |this, cx| {
+                        // Use optimized UpdateProcessor
+                        let mut processor = UpdateProcessor::<ConversationPanel>::new(
+                            &mut this.rendered_items,
+                            &mut this.update_index,
+                            Some(session_id.as_str()),
+                            agent_name.as_deref(),
+                            this.next_index,
+                        );
 
-                            cx.notify(); // Trigger re-render immediately
+                        processor.process_update(update, cx);
+                        this.next_index += 1;
+                        cx.emit(PanelActivity::Updated);
 
-                            // Scroll to bottom after render completes
-                            this.scroll_handle.scroll_to_bottom();
-                            log::info!(
-                                "Rendered session update, total items: {}",
-                                this.rendered_items.len()
-                            );
-                        });
-                    } else {
-                        log::warn!("Entity dropped, skipping update");
-                    }
-                });
-            }
+                        cx.notify(); // Trigger re-render immediately
 
-            log::info!(
-                "Background task ended for session: {}",
-                session_filter_log.as_deref().unwrap_or("all")
-            );
-        })
-        .detach();
+                        // Scroll to bottom after render completes
+                        this.scroll_handle.scroll_to_bottom();
+                        log::info!(
+                            "Rendered session update, total items: {}",
+                            this.rendered_items.len()
+                        );
+                    });
+                } else {
+                    log::warn!("Entity dropped, skipping update");
+                }
+            });
+        }
 
         log::info!(
-            "Subscribed to session updates via MessageService for: {}",
-            session_filter_log_end.as_deref().unwrap_or("all sessions")
+            "Background task ended for session: {}",
+            session_filter_log.as_deref().unwrap_or("all")
         );
     }
 
@@ -413,6 +696,24 @@ impl ConversationPanel {
                                 "Processing permission request: permission_id={}",
                                 event.permission_id
                             );
+                            // Ask in the tool call itself when it is shown
+                            let tool_call_item = this
+                                .update_index
+                                .find_tool_call(&event.tool_call.tool_call_id.to_string())
+                                .and_then(|ix| match this.rendered_items.get(ix) {
+                                    Some(RenderedItem::ToolCall(item)) => Some(item.clone()),
+                                    _ => None,
+                                });
+                            if event.refusal.is_none()
+                                && let Some(item) = tool_call_item
+                            {
+                                item.update(cx, |item, cx| {
+                                    item.apply_update(event.tool_call.fields.clone(), cx);
+                                    item.request_permission(event.permission_id.clone(), cx);
+                                });
+                                cx.notify();
+                                return;
+                            }
                             // Create PermissionRequestView entity using cx.new
                             let permission_view = cx.new(|cx| {
                                 let inner = cx.new(|_| {
@@ -422,6 +723,7 @@ impl ConversationPanel {
                                         &event.tool_call,
                                         event.options.clone(),
                                     )
+                                    .refused(event.refusal.clone())
                                 });
                                 crate::PermissionRequestView { item: inner }
                             });
@@ -462,10 +764,12 @@ impl ConversationPanel {
 
     /// Subscribe to CodeSelectionBus to receive code selection events
     pub fn subscribe_to_code_selections(entity: &Entity<Self>, cx: &mut App) {
+        let session_id = entity.read(cx).session_id.clone();
         crate::core::event_bus::subscribe_entity_to_code_selections(
             entity,
             AppState::global(cx).code_selection_bus.clone(),
             "ConversationPanel",
+            session_id,
             |panel, selection, cx| {
                 panel.code_selections.push(selection);
                 cx.notify();
@@ -474,6 +778,451 @@ impl ConversationPanel {
         );
     }
 
+    /// Restore the task's pinned context and feedback, and follow agents @mentioned in it earlier
+    fn load_task_state(entity: &Entity<Self>, session_id: String, cx: &mut App) {
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            return;
+        };
+        let weak_entity = entity.downgrade();
+
+        cx.spawn(async move |cx| {
+            let Some(task) = workspace_service.get_task_by_session(&session_id).await else {
+                return;
+            };
+            let _ = cx.update(|cx| {
+                let Some(entity) = weak_entity.upgrade() else {
+                    return;
+                };
+                if task.locked {
+                    entity.update(cx, |this, cx| {
+                        this.locked = true;
+                        cx.notify();
+                    });
+                }
+                if task.auto_translate {
+                    entity.update(cx, |this, _| this.auto_translate = true);
+                }
+                if !task.pinned_context.is_empty() {
+                    entity.update(cx, |this, cx| {
+                        this.pinned_context = task.pinned_context.clone();
+                        cx.notify();
+                    });
+                }
+                if !task.feedback.is_empty() {
+                    entity.update(cx, |this, cx| {
+                        this.feedback = task
+                            .feedback
+                            .iter()
+                            .map(|f| (f.message_id.clone(), f.rating))
+                            .collect();
+                        cx.notify();
+                    });
+                }
+                for (agent_name, linked_session_id) in task.agent_sessions {
+                    log::info!(
+                        "[ConversationPanel] Following @{} session {} for task {}",
+                        agent_name,
+                        linked_session_id,
+                        task.id
+                    );
+                    entity.update(cx, |this, _| {
+                        this.linked_sessions.insert(agent_name, linked_session_id.clone());
+                    });
+                    Self::restore_session(&entity, linked_session_id, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Watch the input for "/" to offer slash command completions
+    fn subscribe_to_input(entity: &Entity<Self>, window: &mut Window, cx: &mut App) {
+        entity.update(cx, |this, cx| {
+            let input_state = this.input_state.clone();
+            this._input_subscription = Some(cx.subscribe_in(
+                &input_state,
+                window,
+                |this, _input, event: &InputEvent, _window, cx| {
+                    if let InputEvent::Change = event {
+                        this.on_input_change(cx);
+                    }
+                },
+            ));
+        });
+    }
+
+    /// Re-render the context tray when the code editor's file or selection changes
+    fn observe_editor_context(entity: &Entity<Self>, cx: &mut App) {
+        let editor_context = AppState::global(cx).editor_context.clone();
+        entity.update(cx, |this, cx| {
+            this._editor_context_subscription =
+                Some(cx.observe(&editor_context, |_, _, cx| cx.notify()));
+        });
+    }
+
+    /// Pinned items plus the editor's file or selection, if it is attached
+    fn prompt_context(&self, cx: &App) -> Vec<ContextItem> {
+        let mut items = self.pinned_context.clone();
+        if self.include_editor_context
+            && let Some(item) = AppState::global(cx).editor_context.read(cx).clone()
+            && !items.contains(&item)
+        {
+            items.push(item);
+        }
+        items
+    }
+
+    fn on_input_change(&mut self, cx: &mut Context<Self>) {
+        let value = self.input_state.read(cx).value();
+        let (suggestions, hint) = if value.trim_start().starts_with('/') {
+            let commands = self.available_commands(cx);
+            match slash_command::command_query(&value) {
+                Some(query) => (slash_command::filter_commands(&commands, query), None),
+                None => (Vec::new(), slash_command::pending_argument_hint(&value, &commands)),
+            }
+        } else {
+            (Vec::new(), None)
+        };
+
+        if suggestions.is_empty()
+            && self.command_suggestions.is_empty()
+            && hint == self.command_hint
+        {
+            return;
+        }
+        self.command_suggestions = suggestions;
+        self.command_hint = hint;
+        cx.notify();
+    }
+
+    /// Commands the agent advertised for this session, plus the app's own
+    fn available_commands(&self, cx: &App) -> Vec<AvailableCommand> {
+        let Some(session_id) = self.session_id.as_ref() else {
+            return Vec::new();
+        };
+        let mut commands = AppState::global(cx)
+            .message_service()
+            .and_then(|service| service.get_commands_by_session_id(session_id))
+            .unwrap_or_default();
+        for command in slash_command::local_commands() {
+            if !commands.iter().any(|c| c.name == command.name) {
+                commands.push(command);
+            }
+        }
+        commands
+    }
+
+    /// Whether the repo map is auto-included because this is the first prompt
+    fn repo_map_for_first_prompt(&self, cx: &App) -> bool {
+        AppSettings::global(cx).include_repo_map && self.rendered_items.is_empty()
+    }
+
+    /// Whether the workspace memory is prepended because this is the first prompt
+    fn memory_for_first_prompt(&self, cx: &App) -> bool {
+        AppSettings::global(cx).include_workspace_memory && self.rendered_items.is_empty()
+    }
+
+    /// Save `fact` to the memory of the workspace that owns the session
+    fn remember(&self, session_id: String, fact: String, window: &mut Window, cx: &mut App) {
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            log::error!("WorkspaceService not initialized");
+            return;
+        };
+
+        window
+            .spawn(cx, async move |cx| {
+                let result = match workspace_service.get_workspace_for_session(&session_id).await {
+                    Some(workspace) => workspace_service
+                        .add_memory(&workspace.id, &fact)
+                        .await
+                        .map_err(|e| e.to_string()),
+                    None => Err(t!("conversation.memory.no_workspace").to_string()),
+                };
+
+                _ = cx.update(|window, cx| {
+                    struct RememberNotification;
+                    let note = match result {
+                        Ok(_) => Notification::success(t!("conversation.memory.saved").to_string()),
+                        Err(e) => {
+                            log::warn!("[ConversationPanel] Failed to remember: {}", e);
+                            Notification::error(e)
+                        }
+                    };
+                    window.show_notification(note.id::<RememberNotification>(), cx);
+                });
+            })
+            .detach();
+    }
+
+    /// Digest of this session's prompts, plan, changes and final reply
+    fn session_digest(&self, cx: &App) -> String {
+        let mut prompts = Vec::new();
+        let mut plan = Vec::new();
+        let mut final_reply = None;
+        for item in &self.rendered_items {
+            match item {
+                RenderedItem::UserMessage(view) => {
+                    let data = view.read(cx).data.read(cx);
+                    let text = data
+                        .contents
+                        .iter()
+                        .filter_map(|content| match content {
+                            ContentBlock::Text(text) => Some(text.text.as_str()),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    if !text.trim().is_empty() {
+                        prompts.push(text);
+                    }
+                }
+                // Later plans replace earlier ones
+                RenderedItem::Plan(current) => {
+                    plan = current.entries.iter().map(|entry| entry.content.clone()).collect();
+                }
+                RenderedItem::AgentMessage(_, data) => final_reply = Some(data.full_text()),
+                _ => {}
+            }
+        }
+
+        let changes = DiffSummaryData::from_tool_calls(&self.collect_tool_calls(cx));
+        let changed_files: Vec<String> = changes
+            .sorted_files()
+            .into_iter()
+            .map(|file| {
+                format!(
+                    "{} (+{} -{})",
+                    file.path.display(),
+                    file.additions,
+                    file.deletions
+                )
+            })
+            .collect();
+
+        session_digest(&prompts, &plan, &changed_files, final_reply.as_deref())
+    }
+
+    /// Draft a pull request description from this session on the utility model
+    fn draft_pr_description(&self, window: &mut Window, cx: &mut Context<Self>) {
+        struct PrDescriptionNotification;
+        let digest = self.session_digest(cx);
+        if digest.is_empty() {
+            let note = Notification::warning(t!("conversation.pr_description.empty").to_string());
+            window.show_notification(note.id::<PrDescriptionNotification>(), cx);
+            return;
+        }
+        let Some(ai_service) = AppState::global(cx).ai_service().cloned() else {
+            let note =
+                Notification::warning(t!("conversation.pr_description.no_model").to_string());
+            window.show_notification(note.id::<PrDescriptionNotification>(), cx);
+            return;
+        };
+
+        let note = Notification::info(t!("conversation.pr_description.generating").to_string());
+        window.show_notification(note.id::<PrDescriptionNotification>(), cx);
+
+        window
+            .spawn(cx, async move |cx| {
+                let result = ai_service.generate_pr_description(&digest).await;
+                _ = cx.update(|window, cx| match result {
+                    Ok(description) => Self::show_pr_description(description, window, cx),
+                    Err(e) => {
+                        log::warn!("[ConversationPanel] PR description failed: {:#}", e);
+                        let note = Notification::error(format!("{:#}", e));
+                        window.show_notification(note.id::<PrDescriptionNotification>(), cx);
+                    }
+                });
+            })
+            .detach();
+    }
+
+    /// Editable preview of a drafted PR description, copied on confirm
+    fn show_pr_description(description: String, window: &mut Window, cx: &mut App) {
+        let input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).multi_line(true).auto_grow(12, 24);
+            state.set_value(description, window, cx);
+            state
+        });
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let input = input.clone();
+            dialog
+                .title(t!("conversation.pr_description.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("conversation.pr_description.copy").to_string())
+                        .cancel_text(t!("conversation.pr_description.close").to_string()),
+                )
+                .on_ok({
+                    let input = input.clone();
+                    move |_, window, cx| {
+                        let description = input.read(cx).value().to_string();
+                        cx.write_to_clipboard(ClipboardItem::new_string(description));
+                        struct PrDescriptionCopied;
+                        let note = Notification::success(
+                            t!("conversation.pr_description.copied").to_string(),
+                        );
+                        window.show_notification(note.id::<PrDescriptionCopied>(), cx);
+                        true
+                    }
+                })
+                .child(div().p_2().child(Input::new(&input)))
+        });
+    }
+
+    /// Show the updates the item at `ix` was built from as a JSON tree, to
+    /// tell rendering bugs from what the agent actually sent
+    fn view_source(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let updates = self.update_index.sources(ix);
+        let value = match (self.rendered_items.get(ix), updates) {
+            // Read from a history this version cannot parse
+            (Some(RenderedItem::Unsupported(update)), []) => Ok(update.raw.clone()),
+            (_, [update]) => serde_json::to_value(update),
+            (_, updates) => serde_json::to_value(updates),
+        };
+        let value = match value {
+            Ok(value) => value,
+            Err(e) => {
+                log::warn!("Failed to serialize the source of item {}: {}", ix, e);
+                return;
+            }
+        };
+        let json = serde_json::to_string_pretty(&value).unwrap_or_default();
+        let tree = cx.new(|cx| JsonTree::new(format!("message-source-{}", ix), value, window, cx));
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            dialog
+                .title(t!("conversation.source.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("conversation.source.copy").to_string())
+                        .cancel_text(t!("conversation.source.close").to_string()),
+                )
+                .on_ok({
+                    let json = json.clone();
+                    move |_, _, cx| {
+                        cx.write_to_clipboard(ClipboardItem::new_string(json.clone()));
+                        true
+                    }
+                })
+                .child(
+                    div()
+                        .id("message-source")
+                        .max_h(px(480.))
+                        .overflow_y_scroll()
+                        .child(tree.clone()),
+                )
+        });
+    }
+
+    /// `actions` followed by selecting text across the conversation and
+    /// showing the source of the item at `ix`, see [`Self::view_source`]
+    fn with_view_source(actions: ContextActions, ix: usize, cx: &Context<Self>) -> ContextActions {
+        let entity = cx.entity();
+        let select_entity = entity.clone();
+        actions
+            .separator()
+            .action(
+                t!("conversation.select_text.action").to_string(),
+                IconName::Copy,
+                move |_, cx| select_entity.update(cx, |this, cx| this.select_text(cx)),
+            )
+            .action(
+                t!("conversation.source.action").to_string(),
+                Icon::new(crate::assets::Icon::Code),
+                move |window, cx| entity.update(cx, |this, cx| this.view_source(ix, window, cx)),
+            )
+    }
+
+    /// Show the conversation as one document, so a selection can span
+    /// messages, thoughts and tool outputs; it is a snapshot, so streaming
+    /// updates do not move the selection
+    fn select_text(&mut self, cx: &mut Context<Self>) {
+        let entries: Vec<_> = self
+            .rendered_items
+            .iter()
+            .filter_map(|item| TranscriptEntry::from_item(item, cx))
+            .collect();
+        self.selectable_text = Some(transcript::to_markdown(&entries).into());
+        cx.notify();
+    }
+
+    fn render_text_selection(
+        &self,
+        text: SharedString,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .w_full()
+            .min_h_0()
+            .on_key_down(cx.listener(|this, event: &gpui::KeyDownEvent, _, cx| {
+                if event.keystroke.key == "escape" {
+                    this.selectable_text = None;
+                    cx.notify();
+                }
+            }))
+            .child(
+                h_flex()
+                    .flex_none()
+                    .gap_2()
+                    .px_4()
+                    .py_2()
+                    .items_center()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().secondary)
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(t!("conversation.select_text.hint").to_string()),
+                    )
+                    .child(
+                        Button::new("select-text-copy-all")
+                            .label(t!("conversation.select_text.copy_all").to_string())
+                            .icon(IconName::Copy)
+                            .ghost()
+                            .xsmall()
+                            .on_click({
+                                let text = text.clone();
+                                move |_, _, cx| {
+                                    cx.write_to_clipboard(ClipboardItem::new_string(
+                                        text.to_string(),
+                                    ));
+                                }
+                            }),
+                    )
+                    .child(
+                        Button::new("select-text-done")
+                            .label(t!("conversation.select_text.done").to_string())
+                            .primary()
+                            .xsmall()
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.selectable_text = None;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(
+                div()
+                    .id("conversation-selectable-text")
+                    .flex_1()
+                    .w_full()
+                    .p_4()
+                    .overflow_y_scroll()
+                    .child(
+                        TextView::markdown("conversation-selectable-markdown", text)
+                            .text_sm()
+                            .text_color(cx.theme().foreground)
+                            .selectable(true),
+                    ),
+            )
+    }
+
     /// Subscribe to WorkspaceUpdateBus to receive session status updates
     pub fn subscribe_to_status_updates(
         entity: &Entity<Self>,
@@ -494,12 +1243,43 @@ impl ConversationPanel {
 
         // Subscribe to workspace bus, send status updates to channel in callback
         workspace_bus.subscribe(move |event| {
-            // Only handle SessionStatusUpdated events
-            if let crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::SessionStatusUpdated { session_id, .. } = event {
-                // Filter by session_id if specified
-                if let Some(ref filter_id) = session_filter {
-                    if session_id != filter_id {
-                        return; // Skip this status update
+            if let crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::TaskLockChanged {
+                session_ids,
+                ..
+            } = event
+            {
+                if session_filter
+                    .as_ref()
+                    .is_some_and(|filter_id| session_ids.contains(filter_id))
+                {
+                    let _ = tx.send(event.clone());
+                }
+                return;
+            }
+
+            if let crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::CommentsUpdated {
+                session_id,
+            }
+            | crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::TurnTimed {
+                session_id,
+                ..
+            } = event
+            {
+                if session_filter.as_ref() == Some(session_id) {
+                    let _ = tx.send(event.clone());
+                }
+                return;
+            }
+
+            // Only handle SessionStatusUpdated and SessionQueueUpdated events
+            if let crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::SessionStatusUpdated { session_id, .. }
+            | crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::SessionQueueUpdated { session_id, .. }
+            | crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::SessionRetrying { session_id, .. }
+            | crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::SessionOutputLimited { session_id, .. } = event {
+                // Filter by session_id if specified
+                if let Some(ref filter_id) = session_filter {
+                    if session_id != filter_id {
+                        return; // Skip this status update
                     }
                 }
 
@@ -519,6 +1299,119 @@ impl ConversationPanel {
                 filter_log2.as_deref().unwrap_or("all")
             );
             while let Some(event) = rx.recv().await {
+                if let crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::TaskLockChanged {
+                    locked,
+                    ..
+                } = event
+                {
+                    let weak = weak_entity.clone();
+                    let _ = cx.update(|cx| {
+                        if let Some(entity) = weak.upgrade() {
+                            entity.update(cx, |this, cx| {
+                                this.locked = locked;
+                                cx.notify();
+                            });
+                        }
+                    });
+                    continue;
+                }
+
+                if let crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::CommentsUpdated {
+                    session_id,
+                } = event
+                {
+                    let weak = weak_entity.clone();
+                    let _ = cx.update(|cx| {
+                        if let Some(entity) = weak.upgrade() {
+                            Self::load_comments(&entity, session_id, cx);
+                        }
+                    });
+                    continue;
+                }
+
+                if let crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::TurnTimed {
+                    latency,
+                    ..
+                } = event
+                {
+                    let weak = weak_entity.clone();
+                    let _ = cx.update(|cx| {
+                        if let Some(entity) = weak.upgrade() {
+                            entity.update(cx, |this, cx| {
+                                this.turn_latencies.push(latency);
+                                cx.notify();
+                            });
+                        }
+                    });
+                    continue;
+                }
+
+                if let crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::SessionOutputLimited {
+                    limit,
+                    ..
+                } = event
+                {
+                    let weak = weak_entity.clone();
+                    let _ = cx.update(|cx| {
+                        if let Some(entity) = weak.upgrade() {
+                            entity.update(cx, |this, cx| {
+                                this.output_limited = Some(limit);
+                                cx.notify();
+                            });
+                        }
+                    });
+                    continue;
+                }
+
+                if let crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::SessionRetrying {
+                    attempt,
+                    max_attempts,
+                    ..
+                } = event
+                {
+                    let weak = weak_entity.clone();
+                    let _ = cx.update(|cx| {
+                        if let Some(entity) = weak.upgrade() {
+                            entity.update(cx, |this, cx| {
+                                this.retry_attempt = Some((attempt, max_attempts));
+                                cx.notify();
+                            });
+                        }
+                    });
+                    continue;
+                }
+
+                if let crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::SessionQueueUpdated {
+                    agent_name,
+                    position,
+                    ..
+                } = event
+                {
+                    let weak = weak_entity.clone();
+                    let _ = cx.update(|cx| {
+                        if let Some(entity) = weak.upgrade() {
+                            entity.update(cx, |this, cx| {
+                                if position == 0 {
+                                    this.queue_position = None;
+                                } else {
+                                    this.queue_position = Some(position);
+                                    this.session_status = Some(SessionStatusInfo {
+                                        agent_name,
+                                        status: SessionStatus::Pending,
+                                        last_active: chrono::Utc::now(),
+                                        message_count: this
+                                            .session_status
+                                            .as_ref()
+                                            .map_or(0, |info| info.message_count),
+                                    });
+                                }
+                                cx.notify();
+                            });
+                        }
+                    });
+                    continue;
+                }
+
                 if let crate::core::event_bus::workspace_bus::WorkspaceUpdateEvent::SessionStatusUpdated {
                     session_id,
                     agent_name,
@@ -541,6 +1434,11 @@ impl ConversationPanel {
                                     status
                                 );
 
+                                let turn_finished = this
+                                    .session_status
+                                    .as_ref()
+                                    .is_some_and(|info| info.status == SessionStatus::InProgress);
+
                                 // Mark last message as complete when session completes or becomes idle
                                 if matches!(status, SessionStatus::Completed | SessionStatus::Idle) {
                                     if let Some(last_item) = this.rendered_items.last_mut() {
@@ -550,6 +1448,30 @@ impl ConversationPanel {
 
                                     // Add DiffSummary to message stream when session ends
                                     this.add_diff_summary_if_needed(cx);
+
+                                    if turn_finished {
+                                        this.suggest_follow_ups(cx);
+                                        this.check_reply_language(cx);
+                                        this.finish_regenerate();
+                                        if this.auto_translate {
+                                            this.translate_last_turn(cx);
+                                        }
+                                    }
+                                } else if status == SessionStatus::InProgress {
+                                    this.follow_ups.clear();
+                                    this.language_drift = None;
+                                }
+
+                                // A status change means the session left the queue
+                                if status != SessionStatus::Pending {
+                                    this.queue_position = None;
+                                }
+                                if status != SessionStatus::InProgress {
+                                    this.retry_attempt = None;
+                                }
+
+                                if status == SessionStatus::Failed {
+                                    cx.emit(PanelActivity::Error);
                                 }
 
                                 // Update session status
@@ -614,56 +1536,1700 @@ impl ConversationPanel {
         }
     }
 
-    /// Handle paste event and add images to pasted_images list
-    /// Returns true if we handled the paste (had images), false otherwise
-    fn handle_paste(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
-        log::info!("Handling paste in ConversationPanel");
+    /// Offer follow-up prompts for the turn that just completed
+    ///
+    /// Heuristic suggestions show right away; when a utility model is
+    /// configured they are replaced by the model's once it answers.
+    fn suggest_follow_ups(&mut self, cx: &mut Context<Self>) {
+        if !AppSettings::global(cx).suggest_follow_ups {
+            return;
+        }
+        let Some(reply) = self.rendered_items.iter().rev().find_map(|item| match item {
+            RenderedItem::AgentMessage(_, data) => Some(data.full_text().to_string()),
+            _ => None,
+        }) else {
+            return;
+        };
 
-        let mut handled = false;
-        if let Some(clipboard_item) = cx.read_from_clipboard() {
-            for entry in clipboard_item.entries().iter() {
-                if let ClipboardEntry::Image(image) = entry {
-                    log::info!("Processing pasted image: {:?}", image.format);
-                    let image = image.clone();
-                    handled = true;
+        let changed_files = self
+            .rendered_items
+            .last()
+            .is_some_and(|item| matches!(item, RenderedItem::DiffSummary(_)));
+        self.follow_ups = heuristic_follow_ups(&reply, changed_files);
+        cx.notify();
 
-                    cx.spawn_in(window, async move |this, cx| {
-                        match crate::utils::clipboard::image_to_content(image).await {
-                            Ok((image_content, filename)) => {
-                                _ = cx.update(move |_window, cx| {
-                                    let _ = this.update(cx, |this, cx| {
-                                        this.pasted_images.push((image_content, filename));
-                                        cx.notify();
-                                    });
-                                });
-                            }
-                            Err(e) => {
-                                log::error!("Failed to process pasted image: {}", e);
-                            }
+        let Some(ai_service) = AppState::global(cx)
+            .ai_service()
+            .filter(|service| service.has_utility_model())
+            .cloned()
+        else {
+            return;
+        };
+        let prompt = self
+            .rendered_items
+            .iter()
+            .rev()
+            .find_map(|item| match item {
+                RenderedItem::UserMessage(view) => Some(view.read(cx).data.read(cx).clone()),
+                _ => None,
+            })
+            .map(|data| {
+                data.contents
+                    .iter()
+                    .map(extract_text_from_content)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default();
+        let heuristics = self.follow_ups.clone();
+
+        cx.spawn(async move |this, cx| {
+            match ai_service.suggest_follow_ups(&prompt, &reply).await {
+                Ok(suggestions) if !suggestions.is_empty() => {
+                    _ = this.update(cx, |this, cx| {
+                        // Keep whatever replaced the heuristics meanwhile (e.g. a new turn)
+                        if this.follow_ups == heuristics {
+                            this.follow_ups = suggestions;
+                            cx.notify();
                         }
-                    })
-                    .detach();
+                    });
                 }
+                Ok(_) => {}
+                Err(e) => log::debug!("[ConversationPanel] No follow-up suggestions: {:#}", e),
+            }
+        })
+        .detach();
+    }
+
+    /// Point out a reply written in another language than the workspace's
+    /// response language, when the workspace asks for the check
+    fn check_reply_language(&mut self, cx: &mut Context<Self>) {
+        let Some(reply) = self.rendered_items.iter().rev().find_map(|item| match item {
+            RenderedItem::AgentMessage(_, data) => Some(data.full_text().to_string()),
+            _ => None,
+        }) else {
+            return;
+        };
+        let (Some(session_id), Some(workspace_service)) = (
+            self.session_id.clone(),
+            AppState::global(cx).workspace_service().cloned(),
+        ) else {
+            return;
+        };
+
+        cx.spawn(async move |this, cx| {
+            let Some(language) = workspace_service
+                .get_workspace_for_session(&session_id)
+                .await
+                .filter(|workspace| workspace.check_response_language)
+                .and_then(|workspace| workspace.response_language)
+            else {
+                return;
+            };
+            if language::is_other_language(&reply, &language) {
+                log::info!("Reply in session {} is not in {}", session_id, language);
+                _ = this.update(cx, |this, cx| {
+                    this.language_drift = Some(language);
+                    cx.notify();
+                });
             }
+        })
+        .detach();
+    }
+
+    /// Ask the agent to continue in the workspace's response language
+    fn request_response_language(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(language) = self.language_drift.take() else {
+            return;
+        };
+        let prompt = t!("conversation.language_drift.prompt", language = language).to_string();
+        self.send_follow_up(prompt, window, cx);
+    }
+
+    /// Send a suggested follow-up prompt, leaving any draft in the input alone
+    fn send_follow_up(&mut self, prompt: String, window: &mut Window, cx: &mut Context<Self>) {
+        self.follow_ups.clear();
+        self.output_limited = None;
+        self.language_drift = None;
+        self.send_message(prompt, Vec::new(), Vec::new(), window, cx);
+        cx.notify();
+    }
+
+    fn render_follow_ups(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let busy = self.session_status.as_ref().is_some_and(|info| {
+            matches!(info.status, SessionStatus::InProgress | SessionStatus::Pending)
+        });
+        if self.follow_ups.is_empty() || busy {
+            return div().into_any_element();
         }
-        handled
+
+        h_flex()
+            .pl_6()
+            .gap_2()
+            .flex_wrap()
+            .children(self.follow_ups.iter().enumerate().map(|(idx, prompt)| {
+                let prompt = prompt.clone();
+                Button::new(("follow-up", idx))
+                    .label(prompt.clone())
+                    .outline()
+                    .xsmall()
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.send_follow_up(prompt.clone(), window, cx);
+                    }))
+            }))
+            .into_any_element()
+    }
+
+    /// Rate an agent reply and offer to add a comment
+    ///
+    /// Clicking the reply's current rating again clears it.
+    fn rate_message(
+        &mut self,
+        message_id: String,
+        agent_name: String,
+        rating: FeedbackRating,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(session_id) = self.session_id.clone() else {
+            return;
+        };
+        if self.feedback.get(&message_id) == Some(&rating) {
+            self.feedback.remove(&message_id);
+            cx.notify();
+            Self::save_feedback(session_id, message_id, agent_name, None, None, cx);
+            return;
+        }
+
+        self.feedback.insert(message_id.clone(), rating);
+        cx.notify();
+        Self::save_feedback(
+            session_id.clone(),
+            message_id.clone(),
+            agent_name.clone(),
+            Some(rating),
+            None,
+            cx,
+        );
+
+        let comment_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(3, 6)
+                .placeholder(t!("conversation.feedback.comment_placeholder").to_string())
+        });
+        let title = match rating {
+            FeedbackRating::Up => t!("conversation.feedback.title_up"),
+            FeedbackRating::Down => t!("conversation.feedback.title_down"),
+        }
+        .to_string();
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            dialog
+                .title(title.clone())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("conversation.feedback.save").to_string())
+                        .cancel_text(t!("conversation.feedback.skip").to_string()),
+                )
+                .on_ok({
+                    let comment_input = comment_input.clone();
+                    let (session_id, message_id, agent_name) =
+                        (session_id.clone(), message_id.clone(), agent_name.clone());
+                    move |_, _window, cx| {
+                        let comment = comment_input.read(cx).value().to_string();
+                        Self::save_feedback(
+                            session_id.clone(),
+                            message_id.clone(),
+                            agent_name.clone(),
+                            Some(rating),
+                            Some(comment),
+                            cx,
+                        );
+                        true
+                    }
+                })
+                .child(div().p_2().child(Input::new(&comment_input)))
+        });
+    }
+
+    fn save_feedback(
+        session_id: String,
+        message_id: String,
+        agent_name: String,
+        rating: Option<FeedbackRating>,
+        comment: Option<String>,
+        cx: &mut App,
+    ) {
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            return;
+        };
+        cx.spawn(async move |_cx| {
+            if let Err(e) = workspace_service
+                .rate_message(&session_id, &message_id, &agent_name, rating, comment)
+                .await
+            {
+                log::warn!("[ConversationPanel] Failed to save feedback: {}", e);
+            }
+        })
+        .detach();
+    }
+
+    /// Thumbs up/down under a completed agent reply
+    fn render_feedback(
+        &self,
+        message_id: &str,
+        agent_name: Option<&str>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let current = self.feedback.get(message_id).copied();
+        let agent_name = agent_name
+            .or(self.session_status.as_ref().map(|info| info.agent_name.as_str()))
+            .unwrap_or_default()
+            .to_string();
+
+        h_flex()
+            .pl_6()
+            .gap_1()
+            .children([FeedbackRating::Up, FeedbackRating::Down].map(|rating| {
+                let (icon, tooltip) = match rating {
+                    FeedbackRating::Up => (IconName::ThumbsUp, t!("conversation.feedback.up")),
+                    FeedbackRating::Down => {
+                        (IconName::ThumbsDown, t!("conversation.feedback.down"))
+                    }
+                };
+                let message_id = message_id.to_string();
+                let agent_name = agent_name.clone();
+                Button::new(SharedString::from(format!("{}-{:?}", message_id, rating)))
+                    .icon(Icon::new(icon))
+                    .ghost()
+                    .xsmall()
+                    .selected(current == Some(rating))
+                    .tooltip(tooltip.to_string())
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.rate_message(
+                            message_id.clone(),
+                            agent_name.clone(),
+                            rating,
+                            window,
+                            cx,
+                        );
+                    }))
+            }))
+    }
+
+    /// Translate an agent reply with the utility model, or hide its translation
+    fn toggle_translation(
+        &mut self,
+        message_id: String,
+        text: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.translations.remove(&message_id).is_some() {
+            cx.notify();
+            return;
+        }
+        let has_utility_model = AppState::global(cx)
+            .ai_service()
+            .is_some_and(|service| service.has_utility_model());
+        if !has_utility_model {
+            struct TranslateNoModel;
+            let note = Notification::warning(t!("conversation.translate.no_model").to_string());
+            window.show_notification(note.id::<TranslateNoModel>(), cx);
+            return;
+        }
+        self.translate_message(message_id, text, cx);
+    }
+
+    fn translate_message(&mut self, message_id: String, text: String, cx: &mut Context<Self>) {
+        let Some(ai_service) = AppState::global(cx)
+            .ai_service()
+            .filter(|service| service.has_utility_model())
+            .cloned()
+        else {
+            return;
+        };
+        self.translations
+            .insert(message_id.clone(), Translation::Pending);
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let translation = match ai_service.translate(&text, translation_language()).await {
+                Ok(translation) => Translation::Done(translation.trim().to_string().into()),
+                Err(e) => {
+                    log::warn!("[ConversationPanel] Translation failed: {:#}", e);
+                    Translation::Failed(format!("{:#}", e).into())
+                }
+            };
+            _ = this.update(cx, |this, cx| {
+                // Hidden while it was being translated
+                if let Some(entry) = this.translations.get_mut(&message_id) {
+                    *entry = translation;
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Translate the replies of the turn that just finished, unless they
+    /// are in the display language already
+    fn translate_last_turn(&mut self, cx: &mut Context<Self>) {
+        let language = translation_language();
+        let replies: Vec<(String, String)> = self
+            .rendered_items
+            .iter()
+            .rev()
+            .take_while(|item| !matches!(item, RenderedItem::UserMessage(_)))
+            .filter_map(|item| match item {
+                RenderedItem::AgentMessage(id, data) => {
+                    Some((id.clone(), data.full_text().to_string()))
+                }
+                _ => None,
+            })
+            .filter(|(id, text)| {
+                !self.translations.contains_key(id) && language::is_other_language(text, language)
+            })
+            .collect();
+        for (message_id, text) in replies.into_iter().rev() {
+            self.translate_message(message_id, text, cx);
+        }
+    }
+
+    /// Turn translating this session's replies as they finish on or off
+    fn set_auto_translate(&mut self, enabled: bool, window: &mut Window, cx: &mut Context<Self>) {
+        if enabled
+            && !AppState::global(cx)
+                .ai_service()
+                .is_some_and(|service| service.has_utility_model())
+        {
+            struct TranslateNoModel;
+            let note = Notification::warning(t!("conversation.translate.no_model").to_string());
+            window.show_notification(note.id::<TranslateNoModel>(), cx);
+            return;
+        }
+        self.auto_translate = enabled;
+        cx.notify();
+
+        let (Some(session_id), Some(workspace_service)) = (
+            self.session_id.clone(),
+            AppState::global(cx).workspace_service().cloned(),
+        ) else {
+            return;
+        };
+        cx.spawn(async move |_, _| {
+            if let Err(e) = workspace_service
+                .set_auto_translate(&session_id, enabled)
+                .await
+            {
+                log::warn!("[ConversationPanel] Failed to save auto-translate: {}", e);
+            }
+        })
+        .detach();
+    }
+
+    /// Translation of an agent reply, under it
+    fn render_translation(
+        &self,
+        message_id: &str,
+        translation: &Translation,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let language = translation_language();
+        let title = t!("conversation.translate.title", language = language);
+        let (text, color) = match translation {
+            Translation::Pending => (
+                SharedString::from(t!("conversation.translate.pending").to_string()),
+                cx.theme().muted_foreground,
+            ),
+            Translation::Done(text) => (text.clone(), cx.theme().foreground),
+            Translation::Failed(error) => (
+                t!("conversation.translate.failed", error = error)
+                    .to_string()
+                    .into(),
+                cx.theme().danger,
+            ),
+        };
+
+        v_flex()
+            .ml_6()
+            .pl_3()
+            .gap_1()
+            .border_l_2()
+            .border_color(cx.theme().border)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(Icon::new(IconName::Globe).xsmall())
+                    .child(title.to_string()),
+            )
+            .child(
+                TextView::markdown(
+                    SharedString::from(format!("{}-translation", message_id)),
+                    text,
+                )
+                .text_sm()
+                .text_color(color)
+                .selectable(true),
+            )
+    }
+
+    /// The model agent of this session, if it is a direct chat
+    fn direct_chat_agent(&self, cx: &App) -> Option<String> {
+        let session_id = self.session_id.as_deref()?;
+        AppState::global(cx)
+            .agent_service()?
+            .get_agent_for_session(session_id)
+            .filter(|agent_name| model_for_agent(agent_name).is_some())
     }
 
-    /// Send a message to the current session
-    /// Dispatches SendMessageToSession action to workspace for handling
-    fn send_message(
-        &self,
-        text: String,
-        images: Vec<(ImageContent, String)>,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        // Only send if we have a session_id
-        let Some(ref session_id) = self.session_id else {
-            log::warn!("Cannot send message: no session_id");
+    /// Index of the reply to the last prompt, if the turn ended with one
+    fn last_reply_index(&self) -> Option<usize> {
+        self.rendered_items
+            .iter()
+            .enumerate()
+            .rev()
+            .take_while(|(_, item)| !matches!(item, RenderedItem::UserMessage(_)))
+            .find(|(_, item)| matches!(item, RenderedItem::AgentMessage(..)))
+            .map(|(ix, _)| ix)
+    }
+
+    /// Answer the last prompt again with the popover's parameters; the
+    /// current reply is kept as an earlier attempt
+    fn regenerate_reply(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let options = parse_chat_options(
+            &self.regenerate_temperature.read(cx).value(),
+            &self.regenerate_max_tokens.read(cx).value(),
+            &self.regenerate_variants.read(cx).value(),
+        );
+        let Some(options) = options else {
+            struct RegenerateInvalid;
+            let message = t!("conversation.regenerate.invalid", max = MAX_REPLY_VARIANTS);
+            let note = Notification::warning(message.to_string());
+            window.show_notification(note.id::<RegenerateInvalid>(), cx);
+            return;
+        };
+        let (Some(session_id), Some(agent_name), Some(agent_service), Some(ix)) = (
+            self.session_id.clone(),
+            self.direct_chat_agent(cx),
+            AppState::global(cx).agent_service().cloned(),
+            self.last_reply_index(),
+        ) else {
+            return;
+        };
+        let RenderedItem::AgentMessage(message_id, data) = &mut self.rendered_items[ix] else {
+            return;
+        };
+
+        let mut variants = match self.reply_variants.take() {
+            Some(variants) if variants.message_id == *message_id => variants,
+            _ => ReplyVariants {
+                message_id: message_id.clone(),
+                attempts: vec![data.clone()],
+                pending: Vec::new(),
+                selected: 0,
+            },
+        };
+        let previous = variants.selected;
+        variants.selected = variants.attempts.len();
+        self.translations.remove(message_id.as_str());
+        self.reply_variants = Some(variants);
+        // The new reply streams into the emptied message
+        *data = AgentMessageData::new(session_id.clone()).with_agent_name(agent_name.clone());
+        self.update_index.set_last_message(ix);
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let result = agent_service
+                .regenerate_reply(&agent_name, &session_id, options)
+                .await;
+            _ = this.update(cx, |this, cx| match result {
+                Ok((_, variants)) => {
+                    let variants = variants
+                        .into_iter()
+                        .map(|text| {
+                            let chunk = ContentChunk::new(ContentBlock::from(text));
+                            let mut data = AgentMessageData::new(session_id.clone())
+                                .with_agent_name(agent_name.clone())
+                                .add_chunk(chunk);
+                            data.meta.is_complete = true;
+                            data
+                        })
+                        .collect();
+                    this.add_reply_variants(variants, cx);
+                }
+                Err(e) => {
+                    log::warn!("[ConversationPanel] Failed to regenerate reply: {}", e);
+                    this.select_reply_variant(previous, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Add the variants that came with a regenerated reply; they wait for
+    /// the streamed one if its turn has not finished yet
+    fn add_reply_variants(&mut self, mut replies: Vec<AgentMessageData>, cx: &mut Context<Self>) {
+        let Some(variants) = self.reply_variants.as_mut() else {
+            return;
+        };
+        if variants.selected == variants.attempts.len() {
+            variants.pending.append(&mut replies);
+        } else {
+            variants.attempts.append(&mut replies);
+        }
+        cx.notify();
+    }
+
+    /// Keep the attempt shown and drop the others
+    fn keep_reply_variant(&mut self, cx: &mut Context<Self>) {
+        self.reply_variants = None;
+        cx.notify();
+    }
+
+    /// Keep the regenerated reply once its turn finished
+    fn finish_regenerate(&mut self) {
+        let Some(variants) = self.reply_variants.as_mut() else {
+            return;
+        };
+        if variants.selected != variants.attempts.len() {
+            return;
+        }
+        let reply = self.rendered_items.iter().find_map(|item| match item {
+            RenderedItem::AgentMessage(id, data) if *id == variants.message_id => {
+                Some(data.clone())
+            }
+            _ => None,
+        });
+        match reply {
+            Some(reply) => {
+                variants.attempts.push(reply);
+                variants.attempts.append(&mut variants.pending);
+            }
+            None => self.reply_variants = None,
+        }
+    }
+
+    /// Show another attempt at the last reply and continue the chat from it
+    fn select_reply_variant(&mut self, index: usize, cx: &mut Context<Self>) {
+        let agent_name = self.direct_chat_agent(cx);
+        let Some(variants) = self.reply_variants.as_mut() else {
+            return;
+        };
+        let Some(attempt) = variants.attempts.get(index).cloned() else {
+            return;
+        };
+        let Some(data) = self.rendered_items.iter_mut().find_map(|item| match item {
+            RenderedItem::AgentMessage(id, data) if *id == variants.message_id => Some(data),
+            _ => None,
+        }) else {
+            return;
+        };
+        let reply = attempt.full_text();
+        *data = attempt;
+        variants.selected = index;
+        self.translations.remove(&variants.message_id);
+
+        if let (Some(session_id), Some(agent_name), Some(agent_service)) = (
+            self.session_id.as_deref(),
+            agent_name,
+            AppState::global(cx).agent_service(),
+        ) && let Err(e) = agent_service.use_reply(&agent_name, session_id, &reply)
+        {
+            log::warn!("[ConversationPanel] Failed to switch reply: {}", e);
+        }
+        cx.notify();
+    }
+
+    /// A tool call, with a re-run button once it has finished
+    fn render_tool_call(
+        &self,
+        ix: usize,
+        entity: &Entity<ToolCallItem>,
+        can_rerun: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let tool_call = entity.read(cx).tool_call().clone();
+        let finished = matches!(
+            tool_call.status,
+            ToolCallStatus::Completed | ToolCallStatus::Failed
+        );
+
+        h_flex()
+            .pl_6()
+            .gap_1()
+            .items_start()
+            .child(div().flex_1().min_w_0().child(entity.clone()))
+            .when(can_rerun && finished, |this| {
+                this.child(
+                    Button::new(("rerun-tool-call", ix))
+                        .icon(Icon::new(crate::assets::Icon::RefreshCw))
+                        .ghost()
+                        .xsmall()
+                        .tooltip(t!("conversation.rerun_tool.tooltip").to_string())
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.rerun_tool_call(&tool_call, window, cx);
+                        })),
+                )
+            })
+    }
+
+    /// Ask the agent to run a finished tool call again with the same
+    /// arguments, sent as a regular follow-up prompt
+    fn rerun_tool_call(
+        &mut self,
+        tool_call: &ToolCall,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let prompt = rerun_prompt(&tool_call.title, tool_call.raw_input.as_ref());
+        log::info!("Re-running tool call {}", tool_call.tool_call_id);
+        self.send_message(prompt, Vec::new(), Vec::new(), window, cx);
+    }
+
+    /// Regenerate button and attempt tabs under the last reply of a direct
+    /// chat
+    fn render_reply_controls(&self, message_id: &str, cx: &mut Context<Self>) -> impl IntoElement {
+        let variants = self
+            .reply_variants
+            .as_ref()
+            .filter(|variants| variants.message_id == message_id && variants.attempts.len() > 1);
+        let entity = cx.entity();
+        let (temperature, max_tokens, variant_count) = (
+            self.regenerate_temperature.clone(),
+            self.regenerate_max_tokens.clone(),
+            self.regenerate_variants.clone(),
+        );
+
+        h_flex()
+            .pl_6()
+            .gap_1()
+            .items_center()
+            .when_some(variants, |this, variants| {
+                let selected = variants.selected;
+                this.children((0..variants.attempts.len()).map(|index| {
+                    Button::new(("reply-variant", index))
+                        .label((index + 1).to_string())
+                        .ghost()
+                        .xsmall()
+                        .selected(index == selected)
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.select_reply_variant(index, cx);
+                        }))
+                }))
+                .child(
+                    Button::new("reply-variant-keep")
+                        .icon(Icon::new(IconName::Check))
+                        .label(t!("conversation.regenerate.keep").to_string())
+                        .ghost()
+                        .xsmall()
+                        .tooltip(t!("conversation.regenerate.keep_tooltip").to_string())
+                        .on_click(cx.listener(|this, _, _, cx| this.keep_reply_variant(cx))),
+                )
+            })
+            .child(
+                Popover::new("regenerate-popover")
+                    .trigger(
+                        Button::new("regenerate-reply")
+                            .icon(Icon::new(crate::assets::Icon::RefreshCw))
+                            .ghost()
+                            .xsmall()
+                            .tooltip(t!("conversation.regenerate.action").to_string()),
+                    )
+                    .content(move |_state, _window, cx| {
+                        let entity = entity.clone();
+                        v_flex()
+                            .w(px(220.))
+                            .gap_2()
+                            .p_3()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_semibold()
+                                    .child(t!("conversation.regenerate.title").to_string()),
+                            )
+                            .child(
+                                v_flex()
+                                    .gap_1()
+                                    .text_xs()
+                                    .child(t!("conversation.regenerate.temperature").to_string())
+                                    .child(Input::new(&temperature).small()),
+                            )
+                            .child(
+                                v_flex()
+                                    .gap_1()
+                                    .text_xs()
+                                    .child(t!("conversation.regenerate.max_tokens").to_string())
+                                    .child(Input::new(&max_tokens).small()),
+                            )
+                            .child(
+                                v_flex()
+                                    .gap_1()
+                                    .text_xs()
+                                    .child(
+                                        t!(
+                                            "conversation.regenerate.variants",
+                                            max = MAX_REPLY_VARIANTS
+                                        )
+                                        .to_string(),
+                                    )
+                                    .child(Input::new(&variant_count).small()),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(t!("conversation.regenerate.hint").to_string()),
+                            )
+                            .child(
+                                Button::new("regenerate-reply-run")
+                                    .label(t!("conversation.regenerate.run").to_string())
+                                    .icon(Icon::new(crate::assets::Icon::RefreshCw))
+                                    .primary()
+                                    .small()
+                                    .on_click(move |_, window, cx| {
+                                        entity.update(cx, |this, cx| {
+                                            this.regenerate_reply(window, cx);
+                                        });
+                                    }),
+                            )
+                    }),
+            )
+    }
+
+    /// Load the comments left on this session from a live share
+    fn load_comments(entity: &Entity<Self>, session_id: String, cx: &mut App) {
+        let Some(persistence_service) = AppState::global(cx).persistence_service().cloned() else {
+            return;
+        };
+        let weak = entity.downgrade();
+        cx.spawn(async move |cx| {
+            match persistence_service.load_comments(&session_id).await {
+                Ok(comments) => {
+                    _ = cx.update(|cx| {
+                        if let Some(entity) = weak.upgrade() {
+                            entity.update(cx, |this, cx| {
+                                this.comments = comments;
+                                cx.notify();
+                            });
+                        }
+                    });
+                }
+                Err(e) => log::warn!("Failed to load comments of {}: {}", session_id, e),
+            }
+        })
+        .detach();
+    }
+
+    /// Find the instruction files of the workspace the session belongs to
+    fn load_instruction_files(entity: &Entity<Self>, session_id: String, cx: &mut App) {
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            return;
+        };
+        let weak = entity.downgrade();
+        cx.spawn(async move |cx| {
+            let Some(workspace) = workspace_service
+                .get_workspace_for_session(&session_id)
+                .await
+            else {
+                return;
+            };
+            let root = workspace.path.clone();
+            let names = smol::unblock(move || find_instruction_files(&root)).await;
+            let files: Vec<(String, bool)> = names
+                .into_iter()
+                .map(|name| {
+                    let included = !workspace.excluded_instruction_files.contains(&name);
+                    (name, included)
+                })
+                .collect();
+            _ = cx.update(|cx| {
+                if let Some(entity) = weak.upgrade() {
+                    entity.update(cx, |this, cx| {
+                        this.instruction_files = files;
+                        this.instruction_workspace_id = Some(workspace.id);
+                        cx.notify();
+                    });
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Attach the `ix`th instruction file to the workspace's new sessions, or
+    /// leave it out
+    fn toggle_instruction_file(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(workspace_id), Some(workspace_service)) = (
+            self.instruction_workspace_id.clone(),
+            AppState::global(cx).workspace_service().cloned(),
+        ) else {
+            return;
+        };
+        let Some((name, included)) = self.instruction_files.get_mut(ix) else {
+            return;
+        };
+        *included = !*included;
+        let (name, included) = (name.clone(), *included);
+        cx.notify();
+
+        cx.spawn_in(window, async move |_, window| {
+            let result = workspace_service
+                .set_instruction_file_included(&workspace_id, &name, included)
+                .await;
+            if let Err(e) = result {
+                log::warn!("[ConversationPanel] Failed to toggle {}: {}", name, e);
+                _ = window.update(|window, cx| {
+                    struct InstructionFileError;
+                    let note = Notification::error(
+                        t!("conversation.instructions.save_failed", error = e).to_string(),
+                    )
+                    .id::<InstructionFileError>();
+                    window.show_notification(note, cx);
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Load the latency of the session's timed turns
+    fn load_turn_latencies(entity: &Entity<Self>, session_id: String, cx: &mut App) {
+        let Some(persistence_service) = AppState::global(cx).persistence_service().cloned() else {
+            return;
+        };
+        let weak = entity.downgrade();
+        cx.spawn(async move |cx| {
+            match persistence_service.load_turn_latencies(&session_id).await {
+                Ok(latencies) => {
+                    _ = cx.update(|cx| {
+                        if let Some(entity) = weak.upgrade() {
+                            entity.update(cx, |this, cx| {
+                                this.turn_latencies = latencies;
+                                cx.notify();
+                            });
+                        }
+                    });
+                }
+                Err(e) => log::warn!("Failed to load turn latency of {}: {}", session_id, e),
+            }
+        })
+        .detach();
+    }
+
+    /// How long a turn waited, took to start streaming and took in total,
+    /// under its last reply
+    fn render_turn_latency(&self, turn: usize, cx: &Context<Self>) -> Option<impl IntoElement> {
+        let latency = self
+            .turn_latencies
+            .iter()
+            .rev()
+            .find(|latency| latency.turn == turn)?;
+        let first_chunk = latency
+            .first_chunk_ms
+            .map_or_else(|| "-".to_string(), format_latency);
+        Some(
+            div()
+                .pl_6()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground.opacity(0.7))
+                .child(
+                    t!(
+                        "conversation.latency",
+                        queue = format_latency(latency.queue_ms),
+                        first_chunk = first_chunk,
+                        total = format_latency(latency.total_ms)
+                    )
+                    .to_string(),
+                ),
+        )
+    }
+
+    /// Comments on one message, shown beside the conversation under it
+    fn render_comment_thread(
+        &self,
+        message_index: usize,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let comments: Vec<&SessionComment> = self
+            .comments
+            .iter()
+            .filter(|comment| comment.message_index == message_index)
+            .collect();
+        if comments.is_empty() {
+            return None;
+        }
+
+        Some(
+            v_flex()
+                .ml_6()
+                .pl_3()
+                .gap_1p5()
+                .border_l_2()
+                .border_color(cx.theme().accent)
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(Icon::new(IconName::Globe).xsmall())
+                        .child(
+                            t!("conversation.comments.title", count = comments.len()).to_string(),
+                        ),
+                )
+                .children(comments.into_iter().map(|comment| {
+                    v_flex()
+                        .gap_0p5()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .text_xs()
+                                .child(div().font_semibold().child(comment.author.clone()))
+                                .child(
+                                    div().text_color(cx.theme().muted_foreground).child(
+                                        comment
+                                            .created_at
+                                            .with_timezone(&chrono::Local)
+                                            .format("%Y-%m-%d %H:%M")
+                                            .to_string(),
+                                    ),
+                                ),
+                        )
+                        .child(div().text_sm().child(comment.text.clone()))
+                })),
+        )
+    }
+
+    /// Right-click menu of an agent reply
+    fn agent_message_actions(
+        &self,
+        message_id: &str,
+        data: &AgentMessageData,
+        cx: &Context<Self>,
+    ) -> ContextActions {
+        let entity = cx.entity();
+        let text = data.full_text();
+        let agent_name = data
+            .agent_name()
+            .or(self.session_status.as_ref().map(|info| info.agent_name.as_str()))
+            .unwrap_or_default()
+            .to_string();
+        let can_rate = data.is_complete() && self.session_id.is_some();
+        let translated = self.translations.contains_key(message_id);
+        let auto_translate = self.auto_translate;
+
+        ContextActions::new()
+            .action(t!("conversation.message.copy").to_string(), IconName::Copy, {
+                let text = text.clone();
+                move |_, cx| cx.write_to_clipboard(ClipboardItem::new_string(text.to_string()))
+            })
+            .action(t!("conversation.message.quote").to_string(), IconName::Replace, {
+                let (entity, text) = (entity.clone(), text.clone());
+                move |window, cx| {
+                    let quote = text
+                        .lines()
+                        .map(|line| format!("> {}\n", line))
+                        .collect::<String>();
+                    entity.update(cx, |this, cx| this.insert_into_input(&quote, window, cx));
+                }
+            })
+            .when(data.is_complete(), |actions| {
+                let label = if translated {
+                    t!("conversation.translate.hide")
+                } else {
+                    t!("conversation.translate.action")
+                };
+                let (entity, message_id) = (entity.clone(), message_id.to_string());
+                actions.action(label.to_string(), IconName::Globe, move |window, cx| {
+                    entity.update(cx, |this, cx| {
+                        this.toggle_translation(message_id.clone(), text.to_string(), window, cx);
+                    });
+                })
+            })
+            .when(self.session_id.is_some(), |actions| {
+                let label = if auto_translate {
+                    t!("conversation.translate.auto_off")
+                } else {
+                    t!("conversation.translate.auto_on")
+                };
+                let entity = entity.clone();
+                actions.action(label.to_string(), IconName::Globe, move |window, cx| {
+                    entity.update(cx, |this, cx| {
+                        this.set_auto_translate(!auto_translate, window, cx);
+                    });
+                })
+            })
+            .when(can_rate, |actions| {
+                [FeedbackRating::Up, FeedbackRating::Down]
+                    .into_iter()
+                    .fold(actions.separator(), |actions, rating| {
+                        let (icon, label) = match rating {
+                            FeedbackRating::Up => {
+                                (IconName::ThumbsUp, t!("conversation.feedback.up"))
+                            }
+                            FeedbackRating::Down => {
+                                (IconName::ThumbsDown, t!("conversation.feedback.down"))
+                            }
+                        };
+                        let entity = entity.clone();
+                        let message_id = message_id.to_string();
+                        let agent_name = agent_name.clone();
+                        actions.action(label.to_string(), icon, move |window, cx| {
+                            entity.update(cx, |this, cx| {
+                                this.rate_message(
+                                    message_id.clone(),
+                                    agent_name.clone(),
+                                    rating,
+                                    window,
+                                    cx,
+                                );
+                            });
+                        })
+                    })
+            })
+    }
+
+    /// Right-click menu of a user message
+    fn user_message_actions(
+        &self,
+        view: &Entity<UserMessageView>,
+        cx: &Context<Self>,
+    ) -> ContextActions {
+        let entity = cx.entity();
+        let text = view
+            .read(cx)
+            .data
+            .read(cx)
+            .contents
+            .iter()
+            .filter_map(|content| match content {
+                ContentBlock::Text(text) => Some(text.text.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        ContextActions::new()
+            .action(t!("conversation.message.copy").to_string(), IconName::Copy, {
+                let text = text.clone();
+                move |_, cx| cx.write_to_clipboard(ClipboardItem::new_string(text.clone()))
+            })
+            .action(t!("conversation.message.edit").to_string(), IconName::Replace, {
+                move |window, cx| {
+                    entity.update(cx, |this, cx| {
+                        this.input_state.update(cx, |state, cx| {
+                            state.set_value(text.clone(), window, cx);
+                            state.focus(window, cx);
+                        });
+                    });
+                }
+            })
+    }
+
+    /// Append `text` to the input box and focus it
+    fn insert_into_input(&mut self, text: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.input_state.update(cx, |state, cx| {
+            let current = state.value();
+            let value = if current.trim().is_empty() {
+                text.to_string()
+            } else {
+                format!("{}\n{}", current.trim_end(), text)
+            };
+            state.set_value(value, window, cx);
+            state.focus(window, cx);
+        });
+    }
+
+    /// Handle paste event and add images to pasted_images list
+    /// Returns true if we handled the paste (had images), false otherwise
+    fn handle_paste(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        log::info!("Handling paste in ConversationPanel");
+
+        let mut handled = false;
+        if let Some(clipboard_item) = cx.read_from_clipboard() {
+            for entry in clipboard_item.entries().iter() {
+                if let ClipboardEntry::Image(image) = entry {
+                    log::info!("Processing pasted image: {:?}", image.format);
+                    let image = image.clone();
+                    handled = true;
+
+                    cx.spawn_in(window, async move |this, cx| {
+                        match crate::utils::clipboard::image_to_content(image).await {
+                            Ok((image_content, filename)) => {
+                                _ = cx.update(move |_window, cx| {
+                                    let _ = this.update(cx, |this, cx| {
+                                        this.pasted_images.push((image_content, filename));
+                                        cx.notify();
+                                    });
+                                });
+                            }
+                            Err(e) => {
+                                log::error!("Failed to process pasted image: {}", e);
+                            }
+                        }
+                    })
+                    .detach();
+                }
+            }
+        }
+        handled
+    }
+
+    /// Whether pasted images or text would go out with the next prompt
+    fn has_attachments(&self) -> bool {
+        !self.pasted_images.is_empty()
+            || !self.pasted_texts.is_empty()
+            || self.pending_paste.is_some()
+    }
+
+    /// Send the current input, asking first if the prompt is above the size threshold
+    fn submit_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.is_read_only() {
+            return;
+        }
+        let text = self.input_state.read(cx).value();
+        if text.trim().is_empty() && !self.has_attachments() {
+            return;
+        }
+
+        let prompt_chars = text.len()
+            + self.pasted_texts.iter().map(|p| p.text.len()).sum::<usize>()
+            + self.pending_paste.as_ref().map_or(0, String::len);
+        if AppSettings::global(cx).should_confirm_send(prompt_chars) {
+            self.confirm_large_send(estimate_tokens(prompt_chars), window, cx);
+            return;
+        }
+        self.send_input(window, cx);
+    }
+
+    fn confirm_large_send(&self, tokens: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let entity = cx.entity().downgrade();
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let entity = entity.clone();
+            dialog
+                .title(t!("conversation.confirm_send.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("conversation.confirm_send.ok").to_string())
+                        .cancel_text(t!("conversation.confirm_send.cancel").to_string()),
+                )
+                .on_ok(move |_, window, cx| {
+                    if let Some(entity) = entity.upgrade() {
+                        entity.update(cx, |this, cx| this.send_input(window, cx));
+                    }
+                    true
+                })
+                .child(
+                    div().p_4().text_sm().child(
+                        t!("conversation.confirm_send.message", tokens = tokens).to_string(),
+                    ),
+                )
+        });
+    }
+
+    /// Send the current input text together with pasted images
+    fn send_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.input_state.read(cx).value().to_string();
+        if text.trim().is_empty() && !self.has_attachments() {
+            return;
+        }
+        // A paste still waiting for a choice goes out as an attachment
+        if let Some(pending) = self.pending_paste.take() {
+            self.attach_pasted_text(pending);
+        }
+
+        // Clear the input
+        self.input_state.update(cx, |state, cx| {
+            state.set_value(SharedString::from(""), window, cx);
+        });
+        self.input_preview = false;
+        self.command_suggestions.clear();
+        self.command_hint = None;
+        self.output_limited = None;
+        self.language_drift = None;
+
+        // Send the message with images and pasted attachments if any
+        let images = std::mem::take(&mut self.pasted_images);
+        let resources = std::mem::take(&mut self.pasted_texts);
+        self.send_message(text, images, resources, window, cx);
+
+        // Clear pasted images and code selections after sending
+        self.code_selections.clear();
+        cx.notify();
+    }
+
+    /// Continue a turn that was cancelled by the output guard
+    ///
+    /// The session's limit is doubled first so output that was legitimately long
+    /// can finish; a looping agent is stopped again at the higher limit.
+    fn resume_after_output_limit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(session_id) = self.session_id.clone() else {
+            return;
+        };
+        self.output_limited = None;
+        if let Some(agent_service) = AppState::global(cx).agent_service() {
+            let limit = agent_service.raise_output_limit(&session_id);
+            log::info!("Resuming session {} with output limit {}", session_id, limit);
+        }
+        let prompt = t!("conversation.output_limit.resume_prompt").to_string();
+        self.send_message(prompt, Vec::new(), Vec::new(), window, cx);
+        cx.notify();
+    }
+
+    /// Kill and respawn the session's agent and continue the conversation in a
+    /// fresh session; with `summary` the digest of the conversation so far is
+    /// sent as its first prompt
+    fn restart_agent(&self, summary: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(session_id), Some(agent_service)) = (
+            self.session_id.clone(),
+            AppState::global(cx).agent_service().cloned(),
+        ) else {
+            return;
+        };
+        let Some(agent_name) = agent_service.get_agent_for_session(&session_id) else {
+            return;
+        };
+        let persistence_service = AppState::global(cx).persistence_service().cloned();
+        let workspace_service = AppState::global(cx).workspace_service().cloned();
+        let digest = summary
+            .then(|| self.session_digest(cx))
+            .filter(|digest| !digest.is_empty());
+
+        struct RestartNotification;
+        let note = Notification::info(
+            t!("conversation.restart.restarting", agent = agent_name).to_string(),
+        );
+        window.show_notification(note.id::<RestartNotification>(), cx);
+
+        cx.spawn_in(window, async move |this, window| {
+            let new_session_id = match agent_service
+                .restart_session(&agent_name, &session_id)
+                .await
+            {
+                Ok(new_session_id) => new_session_id,
+                Err(e) => {
+                    log::error!("Failed to restart agent {}: {}", agent_name, e);
+                    _ = window.update(|window, cx| {
+                        let note = Notification::error(
+                            t!("conversation.restart.failed", error = e.to_string()).to_string(),
+                        );
+                        window.show_notification(note.id::<RestartNotification>(), cx);
+                    });
+                    return;
+                }
+            };
+
+            // Reopening the task shows the whole conversation
+            if let Some(persistence_service) = persistence_service
+                && let Err(e) = persistence_service
+                    .continue_session(&session_id, &new_session_id)
+                    .await
+            {
+                log::warn!("Failed to carry over history of {}: {}", session_id, e);
+            }
+            if let Some(workspace_service) = workspace_service
+                && let Some(task) = workspace_service.get_task_by_session(&session_id).await
+                && let Err(e) = workspace_service
+                    .set_task_session(&task.id, new_session_id.clone())
+                    .await
+            {
+                log::warn!("Failed to move task {} to the new session: {}", task.id, e);
+            }
+
+            _ = window.update(|window, cx| {
+                let Some(entity) = this.upgrade() else {
+                    return;
+                };
+                entity.update(cx, |this, cx| {
+                    this.continue_in_session(new_session_id.clone(), cx);
+                });
+                Self::subscribe_to_updates(&entity, Some(new_session_id.clone()), cx);
+                Self::subscribe_to_permissions(&entity, Some(new_session_id.clone()), cx);
+                Self::subscribe_to_status_updates(&entity, Some(new_session_id), cx);
+
+                let note = Notification::success(t!("conversation.restart.restarted").to_string());
+                window.show_notification(note.id::<RestartNotification>(), cx);
+                if let Some(digest) = digest {
+                    let prompt = t!("conversation.restart.summary_prompt", summary = digest);
+                    entity.update(cx, |this, cx| {
+                        this.send_message(prompt.to_string(), Vec::new(), Vec::new(), window, cx);
+                    });
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Follow `session_id` from now on, below a divider, keeping what is shown
+    fn continue_in_session(&mut self, session_id: String, cx: &mut Context<Self>) {
+        if let Some(last_item) = self.rendered_items.last_mut() {
+            last_item.mark_complete();
+        }
+        self.update_index.clear_streaming_state();
+        self.rendered_items.push(RenderedItem::Divider(
+            t!("conversation.restart.divider").to_string(),
+        ));
+        self.session_id = Some(session_id);
+        self.session_status = None;
+        self.queue_position = None;
+        self.retry_attempt = None;
+        self.output_limited = None;
+        self.follow_ups.clear();
+        self.mcp_scope = None;
+        self.scroll_handle.scroll_to_bottom();
+        cx.notify();
+    }
+
+    /// Count an interaction with the conversation towards the task's active time
+    fn track_activity(&mut self, window: &Window, cx: &mut Context<Self>) {
+        if self.session_id.is_none() || !window.is_window_active() {
+            return;
+        }
+        self.activity_timer.ping(Instant::now());
+        if self.activity_timer.pending() < ACTIVE_SAVE_AFTER {
+            return;
+        }
+
+        let (Some(session_id), Some(workspace_service)) = (
+            self.session_id.clone(),
+            AppState::global(cx).workspace_service().cloned(),
+        ) else {
+            return;
+        };
+        let active_ms = self.activity_timer.take().as_millis() as u64;
+        cx.spawn(async move |_this, _cx| {
+            if let Err(e) = workspace_service.add_active_time(&session_id, active_ms).await {
+                log::warn!("Failed to save active time for {}: {}", session_id, e);
+            }
+        })
+        .detach();
+    }
+
+    /// Replace the pinned context and persist it with the task
+    fn set_pinned_context(&mut self, items: Vec<ContextItem>, cx: &mut Context<Self>) {
+        self.pinned_context = items;
+        cx.notify();
+
+        let (Some(session_id), Some(workspace_service)) = (
+            self.session_id.clone(),
+            AppState::global(cx).workspace_service().cloned(),
+        ) else {
+            return;
+        };
+        let items = self.pinned_context.clone();
+        cx.spawn(async move |_this, _cx| {
+            if let Err(e) = workspace_service.set_pinned_context(&session_id, items).await {
+                log::warn!("Failed to save pinned context for {}: {}", session_id, e);
+            }
+        })
+        .detach();
+    }
+
+    /// Pin items that are not pinned yet
+    pub fn pin_items(&mut self, new_items: Vec<ContextItem>, cx: &mut Context<Self>) {
+        let mut items = self.pinned_context.clone();
+        for item in new_items {
+            if !items.contains(&item) {
+                items.push(item);
+            }
+        }
+        self.set_pinned_context(items, cx);
+    }
+
+    /// Selected MCP servers and disabled tools of the session
+    fn mcp_scope(&self, cx: &App) -> (Vec<String>, DisabledTools) {
+        if let Some(scope) = self.mcp_scope.clone() {
+            return scope;
+        }
+        self.session_id
+            .as_ref()
+            .and_then(|session_id| {
+                AppState::global(cx)
+                    .agent_service()?
+                    .get_session_by_id(session_id)
+            })
+            .map(|info| mcp_proxy::session_scope(&info.mcp_servers))
+            .unwrap_or_default()
+    }
+
+    /// Give the session's agent other MCP servers or tools by resuming the
+    /// session with them
+    fn set_mcp_scope(
+        &mut self,
+        selected: Vec<String>,
+        disabled_tools: DisabledTools,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(session_id), Some(agent_service), Some(mcp_service)) = (
+            self.session_id.clone(),
+            AppState::global(cx).agent_service().cloned(),
+            AppState::global(cx).mcp_service().cloned(),
+        ) else {
+            return;
+        };
+        let Some(agent_name) = agent_service.get_agent_for_session(&session_id) else {
+            return;
+        };
+        let servers =
+            mcp_proxy::session_servers(&mcp_service.servers(), &selected, &disabled_tools);
+        self.mcp_scope = Some((selected, disabled_tools));
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, window| {
+            let result = agent_service
+                .set_session_mcp_servers(&agent_name, &session_id, servers)
+                .await;
+            let Err(e) = result else {
+                return;
+            };
+            log::warn!("Failed to update MCP servers of {}: {}", session_id, e);
+            _ = this.update_in(window, |this, window, cx| {
+                // Show the servers the session still has
+                this.mcp_scope = None;
+                struct McpScopeError;
+                let message = t!("conversation.mcp.update_failed", error = e.to_string());
+                window.show_notification(
+                    Notification::error(message.to_string()).id::<McpScopeError>(),
+                    cx,
+                );
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// List the tools of an MCP server so single tools can be disabled
+    fn list_mcp_tools(&mut self, server: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(service) = AppState::global(cx).mcp_service().cloned() else {
+            return;
+        };
+        cx.spawn_in(window, async move |this, window| {
+            let catalog = service.catalog(&server).await;
+            _ = this.update_in(window, |this, window, cx| match catalog {
+                Ok(catalog) => {
+                    let tools = catalog.tools.into_iter().map(|tool| tool.id).collect();
+                    this.mcp_tools.insert(server, tools);
+                    cx.notify();
+                }
+                Err(e) => {
+                    struct McpToolsError;
+                    let message = t!(
+                        "conversation.mcp.tools_failed",
+                        server = server,
+                        error = format!("{:#}", e)
+                    );
+                    window.show_notification(
+                        Notification::error(message.to_string()).id::<McpToolsError>(),
+                        cx,
+                    );
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Pick files from the working directory to pin
+    fn pin_files(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let mut dialog = rfd::AsyncFileDialog::new()
+            .set_title(t!("conversation.context.pick_files").to_string());
+        if let Some(dir) = self.working_directory.as_ref() {
+            dialog = dialog.set_directory(dir);
+        }
+
+        cx.spawn_in(window, async move |this, window| {
+            let Some(files) = dialog.pick_files().await else {
+                return;
+            };
+            let paths: Vec<_> = files.iter().map(|file| file.path().to_path_buf()).collect();
+            let picked: Vec<PickedFile> =
+                smol::unblock(move || paths.into_iter().map(PickedFile::read).collect()).await;
+            _ = this.update_in(window, |this, window, cx| {
+                let mut items = Vec::new();
+                for file in picked {
+                    match file {
+                        PickedFile::Item(item) => items.push(item),
+                        PickedFile::LargeTable(path, table) => {
+                            this.offer_table_sample(path, table, window, cx)
+                        }
+                    }
+                }
+                this.pin_items(items, cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Ask which rows and columns of a large table to pin
+    fn offer_table_sample(
+        &mut self,
+        path: PathBuf,
+        table: Table,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let attachment = cx.new(|cx| TableAttachment::new(path, table, window, cx));
+        let entity = cx.entity().downgrade();
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let entity = entity.clone();
+            let item_source = attachment.clone();
+            dialog
+                .title(t!("conversation.table.title", name = name).to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("conversation.table.attach").to_string()),
+                )
+                .on_ok(move |_, _window, cx| {
+                    // Keep the dialog open until a column is selected
+                    let Some(item) = item_source.read(cx).context_item(cx) else {
+                        return false;
+                    };
+                    if let Some(entity) = entity.upgrade() {
+                        entity.update(cx, |this, cx| this.pin_items(vec![item], cx));
+                    }
+                    true
+                })
+                .child(attachment.clone())
+        });
+    }
+
+    /// Ask for a URL to pin
+    fn pin_url(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("https://"));
+        let entity = cx.entity().downgrade();
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let entity = entity.clone();
+            let url_input = input.clone();
+            dialog
+                .title(t!("conversation.context.url.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("conversation.context.url.ok").to_string()),
+                )
+                .on_ok(move |_, _window, cx| {
+                    // Keep the dialog open until the URL is valid
+                    let Some(item) = ContextItem::url(&url_input.read(cx).value()) else {
+                        return false;
+                    };
+                    if let Some(entity) = entity.upgrade() {
+                        entity.update(cx, |this, cx| this.pin_items(vec![item], cx));
+                    }
+                    true
+                })
+                .child(div().p_4().child(Input::new(&input)))
+        });
+    }
+
+    /// Move the code selections attached to the input into the pinned context
+    fn pin_code_selections(&mut self, cx: &mut Context<Self>) {
+        let items = self
+            .code_selections
+            .drain(..)
+            .map(|selection| ContextItem::from_selection(&selection))
+            .collect();
+        self.pin_items(items, cx);
+    }
+
+    fn attach_pasted_text(&mut self, text: String) {
+        let pasted = PastedText::new(self.pasted_texts.len(), text);
+        log::info!(
+            "Attaching {} pasted lines as {}",
+            pasted.line_count(),
+            pasted.name
+        );
+        self.pasted_texts.push(pasted);
+    }
+
+    /// Send a message to the current session
+    /// Dispatches SendMessageToSession action to workspace for handling
+    fn send_message(
+        &self,
+        text: String,
+        images: Vec<(ImageContent, String)>,
+        resources: Vec<PastedText>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Only send if we have a session_id
+        let Some(ref session_id) = self.session_id else {
+            log::warn!("Cannot send message: no session_id");
+            return;
+        };
+        if self.locked {
+            log::warn!("Cannot send message: session {} is locked", session_id);
+            return;
+        }
+        if self.detached.is_some() {
+            log::warn!(
+                "Cannot send message: session {} is not connected",
+                session_id
+            );
+            return;
+        }
+
+        // "/remember <fact>" is saved to the workspace memory, not sent
+        if let Some(fact) = slash_command::command_arguments(&text, slash_command::REMEMBER_COMMAND)
+            .filter(|fact| !fact.is_empty())
+        {
+            self.remember(session_id.clone(), fact.to_string(), window, cx);
+            return;
+        }
+
+        // "/pr" drafts a pull request description locally
+        if slash_command::command_arguments(&text, slash_command::PR_DESCRIPTION_COMMAND)
+            .is_some()
+        {
+            self.draft_pr_description(window, cx);
+            return;
+        }
+
+        // "/restart [summary]" restarts the agent in a fresh session
+        if let Some(argument) =
+            slash_command::command_arguments(&text, slash_command::RESTART_COMMAND)
+        {
+            let summary = argument.eq_ignore_ascii_case(slash_command::RESTART_SUMMARY_ARGUMENT);
+            self.restart_agent(summary, window, cx);
             return;
+        }
+
+        // "/repomap [question]" and "/search <query>" attach context instead of
+        // reaching the agent
+        let mut repo_map = self.repo_map_for_first_prompt(cx);
+        let mut search_query = None;
+        let text = if let Some(question) =
+            slash_command::command_arguments(&text, slash_command::REPO_MAP_COMMAND)
+        {
+            repo_map = true;
+            if question.is_empty() {
+                t!("conversation.repo_map.default_prompt").to_string()
+            } else {
+                question.to_string()
+            }
+        } else if let Some(query) =
+            slash_command::command_arguments(&text, slash_command::SEARCH_COMMAND)
+                .filter(|query| !query.is_empty())
+        {
+            search_query = Some(query.to_string());
+            query.to_string()
+        } else {
+            text
         };
 
+        // "@agent ..." may address another agent within the same task
+        if text.trim_start().starts_with('@') {
+            self.send_with_mention(session_id.clone(), text, images, resources, window, cx);
+            return;
+        }
+
         log::info!(
             "Dispatching SendMessageToSession action for session: {}",
             session_id
@@ -674,11 +3240,165 @@ impl ConversationPanel {
             session_id: session_id.clone(),
             message: text,
             images,
+            resources,
+            context: self.prompt_context(cx),
+            repo_map,
+            search_query,
+            memory: self.memory_for_first_prompt(cx),
+            instructions: self.rendered_items.is_empty(),
         };
 
         window.dispatch_action(Box::new(action), cx);
     }
 
+    /// Route a prompt that starts with `@AgentName` to that agent's session
+    ///
+    /// The agent's session under this task is created on demand (same working
+    /// directory and MCP servers as a new task), linked to the task and followed
+    /// by this panel so both agents' replies appear in one thread. Prompts whose
+    /// mention is not an agent name are sent to the current session unchanged.
+    fn send_with_mention(
+        &self,
+        session_id: String,
+        text: String,
+        images: Vec<(ImageContent, String)>,
+        resources: Vec<PastedText>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let app_state = AppState::global(cx);
+        let (Some(agent_service), Some(workspace_service)) = (
+            app_state.agent_service().cloned(),
+            app_state.workspace_service().cloned(),
+        ) else {
+            log::error!("AgentService or WorkspaceService not initialized");
+            return;
+        };
+        let agent_config_service = app_state.agent_config_service().cloned();
+        let context = self.prompt_context(cx);
+        let repo_map = self.repo_map_for_first_prompt(cx);
+        let memory = self.memory_for_first_prompt(cx);
+        let instructions = self.rendered_items.is_empty();
+
+        cx.spawn_in(window, async move |this, window| {
+            let agents = agent_service.list_agents().await;
+            let (target, message) = match parse_agent_mention(&text, &agents) {
+                Some((agent_name, prompt)) if !prompt.is_empty() => {
+                    (Some(agent_name), prompt.to_string())
+                }
+                _ => (None, text.clone()),
+            };
+
+            let result = match target.as_deref() {
+                None => Ok(session_id.clone()),
+                Some(agent_name) => {
+                    Self::resolve_agent_session(
+                        &session_id,
+                        agent_name,
+                        &agent_service,
+                        &workspace_service,
+                        agent_config_service.as_deref(),
+                    )
+                    .await
+                }
+            };
+
+            _ = window.update(|window, cx| match result {
+                Ok(target_session_id) => {
+                    // Follow the other agent's session the first time it is addressed
+                    if let (Some(agent_name), Some(entity)) = (target, this.upgrade()) {
+                        let newly_linked = target_session_id != session_id
+                            && entity.update(cx, |this, _| {
+                                this.linked_sessions
+                                    .insert(agent_name, target_session_id.clone())
+                                    .as_ref()
+                                    != Some(&target_session_id)
+                            });
+                        if newly_linked {
+                            Self::subscribe_to_updates(
+                                &entity,
+                                Some(target_session_id.clone()),
+                                cx,
+                            );
+                        }
+                    }
+                    let action = SendMessageToSession {
+                        session_id: target_session_id,
+                        message,
+                        images,
+                        resources,
+                        context,
+                        repo_map,
+                        search_query: None,
+                        memory,
+                        instructions,
+                    };
+                    window.dispatch_action(Box::new(action), cx);
+                }
+                Err(e) => {
+                    log::error!("[ConversationPanel] Failed to route mention: {}", e);
+                    struct MentionRoutingError;
+                    let message = t!("conversation.mention.failed", error = e.to_string());
+                    let note = Notification::error(message.to_string()).id::<MentionRoutingError>();
+                    window.show_notification(note, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Find or create the session `agent_name` uses within the task owning `session_id`
+    async fn resolve_agent_session(
+        session_id: &str,
+        agent_name: &str,
+        agent_service: &AgentService,
+        workspace_service: &WorkspaceService,
+        agent_config_service: Option<&AgentConfigService>,
+    ) -> ServiceResult<String> {
+        let task = workspace_service
+            .get_task_by_session(session_id)
+            .await
+            .ok_or_else(|| ServiceError::TaskNotFound(session_id.to_string()))?;
+
+        // Reuse the agent's session while it is still alive
+        if let Some(existing) = task.session_for_agent(agent_name)
+            && agent_service.get_session_info(agent_name, existing).is_some()
+        {
+            return Ok(existing.to_string());
+        }
+
+        let cwd = workspace_service
+            .get_workspace(&task.workspace_id)
+            .await
+            .map(|workspace| workspace.path)
+            .ok_or_else(|| ServiceError::WorkspaceNotFound(task.workspace_id.clone()))?;
+        let mcp_servers = match agent_config_service {
+            Some(service) => service
+                .list_mcp_servers()
+                .await
+                .into_iter()
+                .filter(|(_, config)| config.enabled)
+                .map(|(name, config)| config.to_acp_mcp_server(name))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let linked_session_id = agent_service
+            .create_session_with_mcp_and_cwd(agent_name, mcp_servers, cwd)
+            .await?;
+        workspace_service
+            .link_agent_session(&task.id, agent_name, linked_session_id.clone())
+            .await?;
+        log::info!(
+            "[ConversationPanel] Linked @{} session {} to task {}",
+            agent_name,
+            linked_session_id,
+            task.id
+        );
+
+        Ok(linked_session_id)
+    }
+
     /// Cancel the current session
     /// Dispatches cancel via AgentService to avoid lost actions
     fn send_cancel_message(&self, _window: &mut Window, cx: &mut Context<Self>) {
@@ -722,6 +3442,357 @@ impl ConversationPanel {
         .detach();
     }
 
+    /// Notice shown after the output guard cancelled a runaway turn
+    fn render_output_limit_notice(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(limit) = self.output_limited else {
+            return div().into_any_element();
+        };
+
+        h_flex()
+            .pl_6()
+            .gap_2()
+            .items_center()
+            .child(
+                h_flex()
+                    .flex_1()
+                    .gap_2()
+                    .items_center()
+                    .p_2()
+                    .rounded(cx.theme().radius)
+                    .bg(cx.theme().warning.opacity(0.1))
+                    .border_1()
+                    .border_color(cx.theme().warning.opacity(0.4))
+                    .child(
+                        Icon::new(IconName::TriangleAlert)
+                            .size(px(14.))
+                            .text_color(cx.theme().warning),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_xs()
+                            .text_color(cx.theme().foreground)
+                            .child(
+                                t!("conversation.output_limit.message", limit = limit)
+                                    .to_string(),
+                            ),
+                    )
+                    .child(
+                        Button::new("output-limit-resume")
+                            .label(t!("conversation.output_limit.resume").to_string())
+                            .primary()
+                            .xsmall()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.resume_after_output_limit(window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("output-limit-dismiss")
+                            .label(t!("conversation.output_limit.dismiss").to_string())
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.output_limited = None;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .into_any_element()
+    }
+
+    /// Notice shown when the last reply ignored the workspace's response language
+    fn render_language_drift_notice(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(language) = self.language_drift.clone() else {
+            return div().into_any_element();
+        };
+
+        h_flex()
+            .pl_6()
+            .gap_2()
+            .items_center()
+            .child(
+                h_flex()
+                    .flex_1()
+                    .gap_2()
+                    .items_center()
+                    .p_2()
+                    .rounded(cx.theme().radius)
+                    .bg(cx.theme().warning.opacity(0.1))
+                    .border_1()
+                    .border_color(cx.theme().warning.opacity(0.4))
+                    .child(
+                        Icon::new(IconName::Globe)
+                            .size(px(14.))
+                            .text_color(cx.theme().warning),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_xs()
+                            .text_color(cx.theme().foreground)
+                            .child(
+                                t!("conversation.language_drift.message", language = &language)
+                                    .to_string(),
+                            ),
+                    )
+                    .child(
+                        Button::new("language-drift-ask")
+                            .label(
+                                t!("conversation.language_drift.ask", language = language)
+                                    .to_string(),
+                            )
+                            .primary()
+                            .xsmall()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.request_response_language(window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("language-drift-dismiss")
+                            .label(t!("conversation.language_drift.dismiss").to_string())
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.language_drift = None;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .into_any_element()
+    }
+
+    /// Unlock the task of this conversation so prompts can be sent again
+    fn unlock(&mut self, cx: &mut Context<Self>) {
+        let Some(session_id) = self.session_id.clone() else {
+            return;
+        };
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            return;
+        };
+        cx.spawn(async move |_, _| {
+            let Some(task) = workspace_service.get_task_by_session(&session_id).await else {
+                return;
+            };
+            if let Err(e) = workspace_service.set_task_locked(&task.id, false).await {
+                log::error!("Failed to unlock task {}: {}", task.id, e);
+            }
+        })
+        .detach();
+    }
+
+    /// No prompts can be sent: the task is locked or the session has no agent
+    fn is_read_only(&self) -> bool {
+        self.locked || self.detached.is_some()
+    }
+
+    /// Resume a session of an earlier run with the agent of its task, in the
+    /// background; sessions the agent already knows are left as they are
+    fn reconnect_session(entity: &Entity<Self>, session_id: String, cx: &mut App) {
+        let weak_entity = entity.downgrade();
+        cx.spawn(async move |cx| {
+            let detached = match Self::reconnect(&session_id, cx).await {
+                Ok(()) => None,
+                Err(reason) => {
+                    log::warn!("Session {} opened read-only: {}", session_id, reason);
+                    Some(reason)
+                }
+            };
+            let _ = cx.update(|cx| {
+                if let Some(entity) = weak_entity.upgrade() {
+                    entity.update(cx, |this, cx| {
+                        this.detached = detached;
+                        cx.notify();
+                    });
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Resume `session_id`, waiting for the services and the agent to start;
+    /// the error is the reason shown to the user
+    async fn reconnect(session_id: &str, cx: &mut AsyncApp) -> Result<(), String> {
+        let mut services = None;
+        for _ in 0..MESSAGE_SERVICE_MAX_RETRIES {
+            services = cx.update(|cx| {
+                let state = AppState::global(cx);
+                Some((
+                    state.agent_service()?.clone(),
+                    state.workspace_service()?.clone(),
+                    state.agent_config_service()?.clone(),
+                ))
+            });
+            if services.is_some() {
+                break;
+            }
+            Timer::after(Duration::from_millis(MESSAGE_SERVICE_RETRY_DELAY_MS)).await;
+        }
+        let Some((agent_service, workspace_service, agent_config_service)) = services else {
+            return Err(t!("conversation.detached.not_started").to_string());
+        };
+
+        // Already resumed, e.g. in another panel or when it was created
+        if agent_service.is_session_open(session_id) {
+            return Ok(());
+        }
+        let task = workspace_service.get_task_by_session(session_id).await;
+        let closed = agent_service.get_session_by_id(session_id);
+        let Some(agent_name) = task
+            .as_ref()
+            .map(|task| task.agent_name.clone())
+            .or_else(|| closed.as_ref().map(|info| info.agent_name.clone()))
+        else {
+            return Err(t!("conversation.detached.unknown_agent").to_string());
+        };
+        if model_for_agent(&agent_name).is_none()
+            && agent_config_service.get_agent(&agent_name).await.is_none()
+        {
+            return Err(t!("conversation.detached.missing_agent", agent = agent_name).to_string());
+        }
+
+        let mut started = false;
+        for _ in 0..MESSAGE_SERVICE_MAX_RETRIES {
+            if agent_service.list_agents().await.contains(&agent_name) {
+                started = true;
+                break;
+            }
+            Timer::after(Duration::from_millis(MESSAGE_SERVICE_RETRY_DELAY_MS)).await;
+        }
+        if !started {
+            return Err(t!("conversation.detached.not_started").to_string());
+        }
+
+        let workspace = match &task {
+            Some(task) => workspace_service.get_workspace(&task.workspace_id).await,
+            None => None,
+        };
+        let cwd = workspace
+            .map(|workspace| workspace.path)
+            .or_else(|| closed.and_then(|info| info.cwd))
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+        agent_service
+            .resume_session_with_mcp_and_cwd(&agent_name, session_id, Vec::new(), cwd)
+            .await
+            .map(|_| log::info!("Reconnected session {} to {}", session_id, agent_name))
+            .map_err(|e| {
+                t!(
+                    "conversation.detached.failed",
+                    agent = agent_name,
+                    error = e.to_string()
+                )
+                .to_string()
+            })
+    }
+
+    /// Notice shown instead of the input while the session is not connected
+    fn render_detached_notice(&self, reason: String, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .gap_2()
+            .items_center()
+            .p_2()
+            .rounded(cx.theme().radius)
+            .bg(cx.theme().muted.opacity(0.5))
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(
+                Icon::new(IconName::TriangleAlert)
+                    .size(px(14.))
+                    .text_color(cx.theme().warning),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .text_xs()
+                    .text_color(cx.theme().foreground)
+                    .child(t!("conversation.detached.message", reason = reason).to_string()),
+            )
+            .child(
+                Button::new("conversation-reconnect")
+                    .label(t!("conversation.detached.retry").to_string())
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        let Some(session_id) = this.session_id.clone() else {
+                            return;
+                        };
+                        Self::reconnect_session(&cx.entity(), session_id, cx);
+                    })),
+            )
+    }
+
+    /// Header listing the instruction files found in the workspace root
+    fn render_instruction_files(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .flex_none()
+            .w_full()
+            .gap_1()
+            .items_center()
+            .px_3()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(t!("conversation.instructions.label").to_string()),
+            )
+            .children(
+                self.instruction_files
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, (name, included))| {
+                        let tooltip = match included {
+                            true => t!("conversation.instructions.included"),
+                            false => t!("conversation.instructions.excluded"),
+                        };
+                        Button::new(("instruction-file", ix))
+                            .icon(Icon::new(IconName::File))
+                            .label(name.clone())
+                            .ghost()
+                            .xsmall()
+                            .selected(*included)
+                            .tooltip(tooltip.to_string())
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.toggle_instruction_file(ix, window, cx);
+                            }))
+                    }),
+            )
+    }
+
+    /// Notice shown instead of the input while the task is locked
+    fn render_lock_notice(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .gap_2()
+            .items_center()
+            .p_2()
+            .rounded(cx.theme().radius)
+            .bg(cx.theme().muted.opacity(0.5))
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(
+                Icon::new(crate::assets::Icon::Lock)
+                    .size(px(14.))
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .text_xs()
+                    .text_color(cx.theme().foreground)
+                    .child(t!("conversation.locked.message").to_string()),
+            )
+            .child(
+                Button::new("conversation-unlock")
+                    .label(t!("conversation.locked.unlock").to_string())
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _, _, cx| this.unlock(cx))),
+            )
+    }
+
     /// Render the loading skeleton and status info when session is in progress
     fn render_loading_skeleton(&self, cx: &mut Context<Self>) -> impl IntoElement {
         // Only show loading skeleton when session is actively processing
@@ -787,6 +3858,37 @@ impl ConversationPanel {
                             .items_center()
                             .gap_2p5()
                             .flex_1()
+                            .when_some(self.retry_attempt, |this, (attempt, max_attempts)| {
+                                // Retry indicator while a transient failure is being retried
+                                this.child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().warning)
+                                        .child(
+                                            t!(
+                                                "conversation.status.retrying",
+                                                attempt = attempt,
+                                                max = max_attempts
+                                            )
+                                            .to_string(),
+                                        ),
+                                )
+                            })
+                            .when_some(self.queue_position, |this, position| {
+                                // Queue position while waiting for a concurrency slot
+                                this.child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().warning)
+                                        .child(
+                                            t!(
+                                                "conversation.status.queued",
+                                                position = position
+                                            )
+                                            .to_string(),
+                                        ),
+                                )
+                            })
                             .when_some(current_todo, |this, todo| {
                                 // Current task indicator
                                 this.child(
@@ -905,8 +4007,18 @@ impl DockPanel for ConversationPanel {
     fn paddings() -> gpui::Pixels {
         px(0.)
     }
+
+    fn observe_activity(
+        view: gpui::AnyView,
+        cx: &mut Context<DockPanelContainer>,
+    ) -> Option<Subscription> {
+        let conversation = view.downcast::<Self>().ok()?;
+        Some(DockPanelContainer::subscribe_activity(&conversation, cx))
+    }
 }
 
+impl EventEmitter<PanelActivity> for ConversationPanel {}
+
 impl Focusable for ConversationPanel {
     fn focus_handle(&self, _cx: &App) -> FocusHandle {
         self.focus_handle.clone()
@@ -916,25 +4028,75 @@ impl Focusable for ConversationPanel {
 impl Render for ConversationPanel {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let mut children = v_flex().p_4().gap_3().bg(cx.theme().background);
+        let roles: Vec<_> = self.rendered_items.iter().map(RenderedItem::message_role).collect();
+        let message_indices = message_indices(&roles);
+        let turn_ends = turn_reply_ends(&roles);
+        let last_reply = self.last_reply_index();
+        let direct_chat = !self.is_read_only() && self.direct_chat_agent(cx).is_some();
+        let busy = self.session_status.as_ref().is_some_and(|info| {
+            matches!(
+                info.status,
+                SessionStatus::InProgress | SessionStatus::Pending
+            )
+        });
 
-        for item in &self.rendered_items {
+        for (ix, item) in self.rendered_items.iter().enumerate() {
             match item {
                 RenderedItem::UserMessage(entity) => {
-                    children = children.child(entity.clone());
+                    let actions =
+                        Self::with_view_source(self.user_message_actions(entity, cx), ix, cx);
+                    children = children.child(
+                        div()
+                            .id(entity.entity_id())
+                            .child(entity.clone())
+                            .context_menu(actions.into_builder()),
+                    );
                 }
                 RenderedItem::AgentMessage(id, data) => {
                     let msg = AgentMessage::new(get_element_id(id), data.clone());
-                    children = children.child(msg);
+                    let actions =
+                        Self::with_view_source(self.agent_message_actions(id, data, cx), ix, cx);
+                    children = children.child(
+                        div()
+                            .id(SharedString::from(format!("agent-message-menu-{}", id)))
+                            .child(msg)
+                            .context_menu(actions.into_builder()),
+                    );
+                    if let Some(translation) = self.translations.get(id) {
+                        children = children.child(self.render_translation(id, translation, cx));
+                    }
+                    if let Some(turn) = turn_ends[ix] {
+                        children = children.children(self.render_turn_latency(turn, cx));
+                    }
+                    if data.is_complete() && self.session_id.is_some() {
+                        children = children.child(self.render_feedback(id, data.agent_name(), cx));
+                    }
+                    if data.is_complete() && Some(ix) == last_reply && direct_chat && !busy {
+                        children = children.child(self.render_reply_controls(id, cx));
+                    }
                 }
                 RenderedItem::AgentThought(entity) => {
-                    children = children.child(entity.clone());
+                    let actions = Self::with_view_source(ContextActions::new(), ix, cx);
+                    children = children.child(
+                        div()
+                            .id(entity.entity_id())
+                            .child(entity.clone())
+                            .context_menu(actions.into_builder()),
+                    );
                 }
                 RenderedItem::Plan(plan) => {
                     let todo_list = AgentTodoList::from_plan(plan.clone());
                     children = children.child(v_flex().pl_6().child(todo_list));
                 }
                 RenderedItem::ToolCall(entity) => {
-                    children = children.child(v_flex().pl_6().child(entity.clone()));
+                    let can_rerun = self.session_id.is_some() && !self.is_read_only() && !busy;
+                    let actions = Self::with_view_source(ContextActions::new(), ix, cx);
+                    children = children.child(
+                        div()
+                            .id(entity.entity_id())
+                            .child(self.render_tool_call(ix, entity, can_rerun, cx))
+                            .context_menu(actions.into_builder()),
+                    );
                 }
                 RenderedItem::PermissionRequest(entity) => {
                     children = children.child(v_flex().pl_6().child(entity.clone()));
@@ -961,20 +4123,94 @@ impl Render for ConversationPanel {
                         ),
                     );
                 }
+                RenderedItem::Unsupported(update) => {
+                    let (preview, cut) = json_preview(&update.raw, UNSUPPORTED_PREVIEW_LINES);
+                    let actions = Self::with_view_source(ContextActions::new(), ix, cx);
+                    children = children.child(
+                        div()
+                            .id(SharedString::from(format!("unsupported-update-{}", ix)))
+                            .pl_6()
+                            .child(
+                                v_flex()
+                                    .gap_1()
+                                    .p_2()
+                                    .rounded(cx.theme().radius)
+                                    .bg(cx.theme().muted.opacity(0.5))
+                                    .border_1()
+                                    .border_color(cx.theme().border.opacity(0.3))
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(
+                                        t!("conversation.unsupported.title", kind = update.kind)
+                                            .to_string(),
+                                    )
+                                    .child(
+                                        div()
+                                            .font_family(cx.theme().mono_font_family.clone())
+                                            .child(preview),
+                                    )
+                                    .when(cut, |this| {
+                                        this.child(t!("conversation.unsupported.more").to_string())
+                                    }),
+                            )
+                            .context_menu(actions.into_builder()),
+                    );
+                }
+                RenderedItem::Divider(label) => {
+                    let line = || div().flex_1().h(px(1.)).bg(cx.theme().border);
+                    children = children.child(
+                        h_flex()
+                            .w_full()
+                            .gap_2()
+                            .py_1()
+                            .child(line())
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(label.clone()),
+                            )
+                            .child(line()),
+                    );
+                }
+            }
+
+            // Comments go after the last item of their message
+            if let Some(message_index) = message_indices[ix]
+                && message_indices.get(ix + 1) != Some(&Some(message_index))
+            {
+                children = children.children(self.render_comment_thread(message_index, cx));
             }
         }
 
         // Add loading skeleton when session is in progress (conditional rendering handled in function)
         children = children.child(self.render_loading_skeleton(cx));
+        children = children.child(self.render_output_limit_notice(cx));
+        children = children.child(self.render_language_drift_notice(cx));
+        children = children.child(self.render_follow_ups(cx));
 
         // Main layout: vertical flex with scroll area on top and input box at bottom
         v_flex()
             .id("messages")
             .size_full()
+            .capture_key_down(cx.listener(|this, _, window, cx| this.track_activity(window, cx)))
+            .on_any_mouse_down(cx.listener(|this, _, window, cx| this.track_activity(window, cx)))
+            .on_mouse_move(cx.listener(|this, _, window, cx| this.track_activity(window, cx)))
+            .on_scroll_wheel(cx.listener(|this, _, window, cx| this.track_activity(window, cx)))
+            .when(!self.instruction_files.is_empty(), |this| {
+                this.child(self.render_instruction_files(cx))
+            })
+            .children(
+                self.selectable_text
+                    .clone()
+                    .map(|text| self.render_text_selection(text, cx)),
+            )
             .child(
-                // Scrollable message area - takes remaining space
+                // Scrollable message area - takes remaining space; hidden, not
+                // dropped, while selecting text so it keeps its scroll position
                 div()
                     .id("conversation-scroll-container")
+                    .when(self.selectable_text.is_some(), |this| this.hidden())
                     .flex_1()
                     .w_full()
                     .track_scroll(&self.scroll_handle)
@@ -1011,55 +4247,175 @@ impl Render for ConversationPanel {
                     // .border_t_1()
                     .p_1()
                     // .border_color(cx.theme().border)
-                    .child({
-                        let entity = cx.entity().clone();
-                        ChatInputBox::new("chat-input", self.input_state.clone())
-                            .pasted_images(self.pasted_images.clone())
-                            .code_selections(self.code_selections.clone())
-                            .session_status(
-                                self.session_status.as_ref().map(|info| info.status.clone()),
-                            )
-                            .on_paste(move |window, cx| {
-                                entity.update(cx, |this, cx| {
-                                    this.handle_paste(window, cx);
-                                });
-                            })
-                            .on_remove_image(cx.listener(|this, idx, _, cx| {
-                                // Remove the image at the given index
-                                if *idx < this.pasted_images.len() {
-                                    this.pasted_images.remove(*idx);
+                    .when(self.locked, |this| this.child(self.render_lock_notice(cx)))
+                    .when_some(self.detached.clone(), |this, reason| {
+                        this.child(self.render_detached_notice(reason, cx))
+                    })
+                    .when(!self.is_read_only(), |this| {
+                        this.child({
+                            let entity = cx.entity().clone();
+                            let (add_file, add_url, toggle) =
+                                (entity.clone(), entity.clone(), entity.clone());
+                            ContextTray::new("context-tray", self.pinned_context.clone())
+                                .editor_context(
+                                    AppState::global(cx).editor_context.read(cx).clone(),
+                                    self.include_editor_context,
+                                )
+                                .pinnable_selections(self.code_selections.len())
+                                .on_toggle_editor_context(move |_, cx| {
+                                    toggle.update(cx, |this, cx| {
+                                        this.include_editor_context = !this.include_editor_context;
+                                        cx.notify();
+                                    });
+                                })
+                                .on_remove(cx.listener(|this, idx: &usize, _, cx| {
+                                    if *idx < this.pinned_context.len() {
+                                        let mut items = this.pinned_context.clone();
+                                        items.remove(*idx);
+                                        this.set_pinned_context(items, cx);
+                                    }
+                                }))
+                                .on_toggle_pdf(cx.listener(|this, idx: &usize, _, cx| {
+                                    let mut items = this.pinned_context.clone();
+                                    if let Some(ContextItem::Pdf { send_blob, .. }) =
+                                        items.get_mut(*idx)
+                                    {
+                                        *send_blob = !*send_blob;
+                                        this.set_pinned_context(items, cx);
+                                    }
+                                }))
+                                .on_add_file(move |window, cx| {
+                                    add_file.update(cx, |this, cx| this.pin_files(window, cx));
+                                })
+                                .on_add_url(move |window, cx| {
+                                    add_url.update(cx, |this, cx| this.pin_url(window, cx));
+                                })
+                                .on_pin_selections(move |_, cx| {
+                                    entity.update(cx, |this, cx| this.pin_code_selections(cx));
+                                })
+                        })
+                        .child({
+                            let entity = cx.entity().clone();
+                            let (selected_mcps, disabled_mcp_tools) = self.mcp_scope(cx);
+                            let available_mcps = AppState::global(cx)
+                                .mcp_service()
+                                .map(|service| service.servers())
+                                .unwrap_or_default();
+                            ChatInputBox::new("chat-input", self.input_state.clone())
+                                .pasted_images(self.pasted_images.clone())
+                                .code_selections(self.code_selections.clone())
+                                .pasted_texts(self.pasted_texts.clone())
+                                .pending_paste_lines(
+                                    self.pending_paste.as_ref().map(|text| text.lines().count()),
+                                )
+                                .on_large_paste(cx.listener(|this, text: &String, _, cx| {
+                                    this.pending_paste = Some(text.clone());
                                     cx.notify();
-                                }
-                            }))
-                            .on_remove_code_selection(cx.listener(|this, idx, _, cx| {
-                                // Remove the code selection at the given index
-                                if *idx < this.code_selections.len() {
-                                    this.code_selections.remove(*idx);
+                                }))
+                                .on_resolve_paste(cx.listener(|this, attach: &bool, window, cx| {
+                                    let Some(text) = this.pending_paste.take() else {
+                                        return;
+                                    };
+                                    if *attach {
+                                        this.attach_pasted_text(text);
+                                    } else {
+                                        this.input_state.update(cx, |state, cx| {
+                                            state.insert(text, window, cx);
+                                        });
+                                    }
                                     cx.notify();
-                                }
-                            }))
-                            .on_send(cx.listener(|this, _ev, window, cx| {
-                                let text = this.input_state.read(cx).value().to_string();
-                                if !text.trim().is_empty() || !this.pasted_images.is_empty() {
-                                    // Clear the input
-                                    this.input_state.update(cx, |state, cx| {
-                                        state.set_value(SharedString::from(""), window, cx);
+                                }))
+                                .on_remove_pasted_text(cx.listener(|this, idx, _, cx| {
+                                    if *idx < this.pasted_texts.len() {
+                                        this.pasted_texts.remove(*idx);
+                                        cx.notify();
+                                    }
+                                }))
+                                .session_status(
+                                    self.session_status.as_ref().map(|info| info.status.clone()),
+                                )
+                                .on_paste(move |window, cx| {
+                                    entity.update(cx, |this, cx| {
+                                        this.handle_paste(window, cx);
                                     });
-
-                                    // Send the message with images if any
-                                    let images = std::mem::take(&mut this.pasted_images);
-                                    this.send_message(text, images, window, cx);
-
-                                    // Clear pasted images and code selections after sending
-                                    this.code_selections.clear();
+                                })
+                                .on_remove_image(cx.listener(|this, idx, _, cx| {
+                                    // Remove the image at the given index
+                                    if *idx < this.pasted_images.len() {
+                                        this.pasted_images.remove(*idx);
+                                        cx.notify();
+                                    }
+                                }))
+                                .on_remove_code_selection(cx.listener(|this, idx, _, cx| {
+                                    // Remove the code selection at the given index
+                                    if *idx < this.code_selections.len() {
+                                        this.code_selections.remove(*idx);
+                                        cx.notify();
+                                    }
+                                }))
+                                .command_suggestions(self.command_suggestions.clone())
+                                .show_command_suggestions(!self.command_suggestions.is_empty())
+                                .command_hint(self.command_hint.clone())
+                                .available_mcps(available_mcps)
+                                .selected_mcps(selected_mcps)
+                                .on_mcp_toggle(cx.listener(
+                                    |this, (name, checked): &(String, bool), window, cx| {
+                                        let (mut selected, disabled_tools) = this.mcp_scope(cx);
+                                        selected.retain(|s| s != name);
+                                        if *checked {
+                                            selected.push(name.clone());
+                                        }
+                                        this.set_mcp_scope(selected, disabled_tools, window, cx);
+                                    },
+                                ))
+                                .mcp_tools(self.mcp_tools.clone(), disabled_mcp_tools)
+                                .on_mcp_tool_toggle(cx.listener(
+                                    |this, toggle: &(String, String, bool), window, cx| {
+                                        let (server, tool, enabled) = toggle;
+                                        let (selected, mut disabled_tools) = this.mcp_scope(cx);
+                                        let disabled =
+                                            disabled_tools.entry(server.clone()).or_default();
+                                        if *enabled {
+                                            disabled.remove(tool);
+                                        } else {
+                                            disabled.insert(tool.clone());
+                                        }
+                                        this.set_mcp_scope(selected, disabled_tools, window, cx);
+                                    },
+                                ))
+                                .on_mcp_list_tools(cx.listener(
+                                    |this, server: &String, window, cx| {
+                                        this.list_mcp_tools(server.clone(), window, cx);
+                                    },
+                                ))
+                                .send_with_modifier(AppSettings::global(cx).send_with_modifier())
+                                .preview(self.input_preview)
+                                .on_toggle_preview(cx.listener(|this, preview, window, cx| {
+                                    this.input_preview = *preview;
+                                    if !this.input_preview {
+                                        this.input_state.update(cx, |state, cx| {
+                                            state.focus(window, cx);
+                                        });
+                                    }
                                     cx.notify();
-                                }
-                            }))
-                            .on_cancel(cx.listener(|this, _ev, window, cx| {
-                                log::info!("[ConversationPanel] on_cancel callback triggered");
-                                this.send_cancel_message(window, cx);
-                                cx.notify();
-                            }))
+                                }))
+                                .on_submit({
+                                    let entity = cx.entity().clone();
+                                    move |window, cx| {
+                                        entity.update(cx, |this, cx| {
+                                            this.submit_input(window, cx);
+                                        });
+                                    }
+                                })
+                                .on_send(cx.listener(|this, _ev, window, cx| {
+                                    this.submit_input(window, cx);
+                                }))
+                                .on_cancel(cx.listener(|this, _ev, window, cx| {
+                                    log::info!("[ConversationPanel] on_cancel callback triggered");
+                                    this.send_cancel_message(window, cx);
+                                    cx.notify();
+                                }))
+                        })
                     }),
             )
     }
@@ -3,7 +3,9 @@ use agent_client_protocol::{ContentChunk, Plan};
 use gpui::{App, Entity};
 
 use super::components::{AgentThoughtItemState, UserMessageView};
+use super::helpers::MessageRole;
 use crate::components::ToolCallItem;
+use crate::core::services::UnsupportedUpdate;
 use crate::{AgentMessageData, DiffSummary, PermissionRequestView};
 
 // ============================================================================
@@ -24,6 +26,10 @@ pub enum RenderedItem {
     PermissionRequest(Entity<PermissionRequestView>),
     // Diff summary for file changes
     DiffSummary(Entity<DiffSummary>),
+    /// Labelled line across the conversation, e.g. where the agent restarted
+    Divider(String),
+    /// Update of a kind not rendered (yet), shown as its JSON
+    Unsupported(UnsupportedUpdate),
 }
 
 impl RenderedItem {
@@ -64,6 +70,15 @@ impl RenderedItem {
     pub fn can_accept_agent_thought_chunk(&self) -> bool {
         matches!(self, RenderedItem::AgentThought(..))
     }
+
+    /// Whether this item is a user or agent message, which comments can be left on
+    pub fn message_role(&self) -> Option<MessageRole> {
+        match self {
+            RenderedItem::UserMessage(_) => Some(MessageRole::User),
+            RenderedItem::AgentMessage(..) => Some(MessageRole::Agent),
+            _ => None,
+        }
+    }
 }
 
 // ============================================================================
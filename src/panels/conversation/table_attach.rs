@@ -0,0 +1,306 @@
+//! Dialog for attaching a large CSV file or spreadsheet: a preview of its
+//! first rows, the columns to keep and how many rows to send
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme, Selectable, Sizable, StyledExt as _,
+    button::{Button, ButtonGroup, ButtonVariants as _},
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex,
+};
+use rust_i18n::t;
+
+use crate::utils::blob::format_size;
+use crate::utils::context_item::ContextItem;
+use crate::utils::tabular::{LARGE_TABLE_ROWS, RowSample, Table, is_spreadsheet, is_table_file};
+
+/// Rows shown in the preview
+const PREVIEW_ROWS: usize = 8;
+
+/// Rows in a sample until changed
+const DEFAULT_SAMPLE_ROWS: usize = 100;
+
+/// A file picked to pin to the conversation
+pub(super) enum PickedFile {
+    Item(ContextItem),
+    /// A table too large to attach without asking which part of it
+    LargeTable(PathBuf, Table),
+}
+
+impl PickedFile {
+    /// Reads PDFs and tables, so this is called off the main thread
+    pub(super) fn read(path: PathBuf) -> Self {
+        if !is_table_file(&path) {
+            return Self::Item(ContextItem::file(path));
+        }
+        match Table::read_file(&path) {
+            Ok(table) if table.rows.len() > LARGE_TABLE_ROWS => Self::LargeTable(path, table),
+            // Agents cannot read spreadsheets, so small ones are sent as CSV
+            Ok(table) if is_spreadsheet(&path) => Self::Item(ContextItem::TableSample {
+                rows: table.rows.len(),
+                total_rows: table.rows.len(),
+                text: table.to_csv(),
+                path,
+            }),
+            Ok(_) => Self::Item(ContextItem::File { path }),
+            Err(e) => {
+                log::warn!("{:#}", e);
+                Self::Item(ContextItem::File { path })
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RowMode {
+    All,
+    First,
+    Spread,
+}
+
+pub(super) struct TableAttachment {
+    path: PathBuf,
+    table: Table,
+    columns: BTreeSet<usize>,
+    mode: RowMode,
+    row_count: Entity<InputState>,
+    /// Length of the CSV that would be attached
+    estimate: usize,
+    _row_count_subscription: Subscription,
+}
+
+impl TableAttachment {
+    pub(super) fn new(
+        path: PathBuf,
+        table: Table,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let row_count =
+            cx.new(|cx| InputState::new(window, cx).default_value(DEFAULT_SAMPLE_ROWS.to_string()));
+        let _row_count_subscription =
+            cx.subscribe(&row_count, |this, _, event: &InputEvent, cx| {
+                if matches!(event, InputEvent::Change) {
+                    this.update_estimate(cx);
+                }
+            });
+        let mut this = Self {
+            path,
+            columns: (0..table.columns.len()).collect(),
+            table,
+            mode: RowMode::Spread,
+            row_count,
+            estimate: 0,
+            _row_count_subscription,
+        };
+        this.update_estimate(cx);
+        this
+    }
+
+    fn sample(&self, cx: &App) -> RowSample {
+        let count = self
+            .row_count
+            .read(cx)
+            .value()
+            .trim()
+            .parse()
+            .unwrap_or(DEFAULT_SAMPLE_ROWS);
+        match self.mode {
+            RowMode::All => RowSample::All,
+            RowMode::First => RowSample::First(count),
+            RowMode::Spread => RowSample::Spread(count),
+        }
+    }
+
+    fn selected(&self, cx: &App) -> Table {
+        let columns: Vec<usize> = self.columns.iter().copied().collect();
+        self.table.select(self.sample(cx), &columns)
+    }
+
+    fn update_estimate(&mut self, cx: &mut Context<Self>) {
+        self.estimate = self.selected(cx).to_csv().len();
+        cx.notify();
+    }
+
+    fn set_mode(&mut self, mode: RowMode, cx: &mut Context<Self>) {
+        self.mode = mode;
+        self.update_estimate(cx);
+    }
+
+    /// Item to pin, `None` while no column is selected; a whole CSV file is
+    /// pinned as the file itself
+    pub(super) fn context_item(&self, cx: &App) -> Option<ContextItem> {
+        if self.columns.is_empty() {
+            return None;
+        }
+        let whole = self.mode == RowMode::All && self.columns.len() == self.table.columns.len();
+        if whole && !is_spreadsheet(&self.path) {
+            return Some(ContextItem::File {
+                path: self.path.clone(),
+            });
+        }
+        let selected = self.selected(cx);
+        Some(ContextItem::TableSample {
+            path: self.path.clone(),
+            rows: selected.rows.len(),
+            total_rows: self.table.rows.len(),
+            text: selected.to_csv(),
+        })
+    }
+}
+
+impl Render for TableAttachment {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (secondary, border, foreground, muted_foreground, radius) = {
+            let theme = cx.theme();
+            (
+                theme.secondary,
+                theme.border,
+                theme.foreground,
+                theme.muted_foreground,
+                theme.radius,
+            )
+        };
+        let cell = || {
+            div()
+                .flex_shrink_0()
+                .w(px(140.))
+                .px_2()
+                .overflow_hidden()
+                .text_ellipsis()
+                .whitespace_nowrap()
+        };
+
+        let headers = self.table.columns.iter().enumerate().map(|(ix, column)| {
+            h_flex()
+                .flex_shrink_0()
+                .w(px(140.))
+                .gap_1()
+                .px_2()
+                .py_1()
+                .child(
+                    Checkbox::new(("table-column", ix))
+                        .checked(self.columns.contains(&ix))
+                        .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                            if *checked {
+                                this.columns.insert(ix);
+                            } else {
+                                this.columns.remove(&ix);
+                            }
+                            this.update_estimate(cx);
+                        })),
+                )
+                .child(
+                    div()
+                        .overflow_hidden()
+                        .text_ellipsis()
+                        .whitespace_nowrap()
+                        .font_semibold()
+                        .child(column.clone()),
+                )
+        });
+        let rows = self.table.rows.iter().take(PREVIEW_ROWS).map(|row| {
+            h_flex()
+                .border_t_1()
+                .border_color(border)
+                .children(row.iter().enumerate().map(|(ix, text)| {
+                    cell()
+                        .py_0p5()
+                        .when(!self.columns.contains(&ix), |this| {
+                            this.text_color(muted_foreground.opacity(0.5))
+                        })
+                        .child(text.clone())
+                }))
+        });
+
+        let mode = self.mode;
+        let mode_button = |id: &'static str, label: String, value: RowMode| {
+            Button::new(id)
+                .label(label)
+                .ghost()
+                .xsmall()
+                .selected(mode == value)
+                .on_click(cx.listener(move |this, _, _, cx| this.set_mode(value, cx)))
+        };
+
+        v_flex()
+            .w_full()
+            .gap_3()
+            .child(
+                div().text_xs().text_color(muted_foreground).child(
+                    t!(
+                        "conversation.table.summary",
+                        rows = self.table.rows.len(),
+                        columns = self.table.columns.len()
+                    )
+                    .to_string(),
+                ),
+            )
+            .child(
+                v_flex()
+                    .id("table-preview")
+                    .w_full()
+                    .overflow_x_scroll()
+                    .text_size(px(12.))
+                    .text_color(foreground)
+                    .border_1()
+                    .border_color(border)
+                    .rounded(radius)
+                    .child(h_flex().bg(secondary).children(headers))
+                    .children(rows),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        ButtonGroup::new("table-rows")
+                            .small()
+                            .child(mode_button(
+                                "table-rows-spread",
+                                t!("conversation.table.spread").to_string(),
+                                RowMode::Spread,
+                            ))
+                            .child(mode_button(
+                                "table-rows-first",
+                                t!("conversation.table.first").to_string(),
+                                RowMode::First,
+                            ))
+                            .child(mode_button(
+                                "table-rows-all",
+                                t!("conversation.table.all").to_string(),
+                                RowMode::All,
+                            )),
+                    )
+                    .when(mode != RowMode::All, |this| {
+                        this.child(div().w(px(90.)).child(Input::new(&self.row_count).small()))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(muted_foreground)
+                                    .child(t!("conversation.table.rows").to_string()),
+                            )
+                    }),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(muted_foreground)
+                    .child(if self.columns.is_empty() {
+                        t!("conversation.table.no_columns").to_string()
+                    } else {
+                        t!(
+                            "conversation.table.estimate",
+                            size = format_size(self.estimate as u64)
+                        )
+                        .to_string()
+                    }),
+            )
+    }
+}
@@ -0,0 +1,171 @@
+//! The conversation as one Markdown document, so text can be selected
+//! across messages, thoughts and tool outputs at once
+
+use agent_client_protocol::{ContentBlock, PlanEntryStatus, ToolCallContent};
+use gpui::App;
+
+use super::helpers::extract_text_from_content;
+use super::rendered_item::RenderedItem;
+use crate::AppState;
+use crate::utils::markdown::fence;
+use crate::utils::tool_call::extract_terminal_output;
+
+/// The text of one rendered item
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptEntry {
+    User(String),
+    Agent {
+        name: String,
+        text: String,
+    },
+    Thought(String),
+    ToolCall {
+        title: String,
+        outputs: Vec<String>,
+    },
+    /// Plan entries and whether each is done
+    Plan(Vec<(String, bool)>),
+    Info(String),
+    Divider(String),
+    Json(String),
+}
+
+impl TranscriptEntry {
+    /// Entry for `item`; permission requests and diff summaries have no text
+    /// of their own
+    pub fn from_item(item: &RenderedItem, cx: &App) -> Option<Self> {
+        match item {
+            RenderedItem::UserMessage(view) => {
+                let data = view.read(cx).data.read(cx);
+                let text = data
+                    .contents
+                    .iter()
+                    .map(|content| match content {
+                        ContentBlock::Text(text) => text.text.clone(),
+                        // Only the `[Resource: name]` line of attachments
+                        content => extract_text_from_content(content)
+                            .lines()
+                            .next()
+                            .unwrap_or_default()
+                            .to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+                Some(Self::User(text))
+            }
+            RenderedItem::AgentMessage(_, data) => Some(Self::Agent {
+                name: AppState::global(cx).agent_label(data.agent_name().unwrap_or("Agent")),
+                text: data.full_text().to_string(),
+            }),
+            RenderedItem::AgentThought(entity) => {
+                Some(Self::Thought(entity.read(cx).text().to_string()))
+            }
+            RenderedItem::ToolCall(entity) => {
+                let tool_call = entity.read(cx).tool_call();
+                let outputs = tool_call
+                    .content
+                    .iter()
+                    .filter_map(|content| match content {
+                        ToolCallContent::Content(content) => match &content.content {
+                            ContentBlock::Text(text) => Some(text.text.clone()),
+                            _ => None,
+                        },
+                        ToolCallContent::Terminal(terminal) => extract_terminal_output(terminal),
+                        ToolCallContent::Diff(diff) => Some(diff.new_text.clone()),
+                        _ => None,
+                    })
+                    .filter(|output| !output.trim().is_empty())
+                    .collect();
+                Some(Self::ToolCall {
+                    title: tool_call.title.clone(),
+                    outputs,
+                })
+            }
+            RenderedItem::Plan(plan) => Some(Self::Plan(
+                plan.entries
+                    .iter()
+                    .map(|entry| {
+                        let done = matches!(entry.status, PlanEntryStatus::Completed);
+                        (entry.content.clone(), done)
+                    })
+                    .collect(),
+            )),
+            RenderedItem::InfoUpdate(text) => Some(Self::Info(text.clone())),
+            RenderedItem::Divider(label) => Some(Self::Divider(label.clone())),
+            RenderedItem::Unsupported(update) => Some(Self::Json(
+                serde_json::to_string_pretty(&update.raw).unwrap_or_default(),
+            )),
+            RenderedItem::PermissionRequest(_) | RenderedItem::DiffSummary(_) => None,
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        match self {
+            Self::User(text) => format!("**You**\n\n{}", text),
+            Self::Agent { name, text } => format!("**{}**\n\n{}", name, text),
+            Self::Thought(text) => {
+                let quoted: Vec<String> = text.lines().map(|line| format!("> {}", line)).collect();
+                format!("*Thinking*\n\n{}", quoted.join("\n"))
+            }
+            Self::ToolCall { title, outputs } => {
+                let mut markdown = format!("**{}**", title);
+                for output in outputs {
+                    markdown.push_str("\n\n");
+                    markdown.push_str(&fence(output, ""));
+                }
+                markdown
+            }
+            Self::Plan(entries) => {
+                let lines: Vec<String> = entries
+                    .iter()
+                    .map(|(entry, done)| format!("- [{}] {}", if *done { "x" } else { " " }, entry))
+                    .collect();
+                format!("**Plan**\n\n{}", lines.join("\n"))
+            }
+            Self::Info(text) => text.clone(),
+            Self::Divider(label) => format!("---\n\n{}\n\n---", label),
+            Self::Json(json) => fence(json, "json"),
+        }
+    }
+}
+
+/// `entries` as one Markdown document
+pub fn to_markdown(entries: &[TranscriptEntry]) -> String {
+    entries
+        .iter()
+        .map(TranscriptEntry::to_markdown)
+        .filter(|markdown| !markdown.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_markdown() {
+        let entries = vec![
+            TranscriptEntry::User("Fix it".into()),
+            TranscriptEntry::Thought("first\nsecond".into()),
+            TranscriptEntry::ToolCall {
+                title: "Run tests".into(),
+                outputs: vec!["ok".into()],
+            },
+            TranscriptEntry::Plan(vec![("Read".into(), true), ("Write".into(), false)]),
+            TranscriptEntry::Info(String::new()),
+            TranscriptEntry::Agent {
+                name: "Claude".into(),
+                text: "Done".into(),
+            },
+        ];
+        assert_eq!(
+            to_markdown(&entries),
+            "**You**\n\nFix it\n\n\
+             *Thinking*\n\n> first\n> second\n\n\
+             **Run tests**\n\n```\nok\n```\n\n\
+             **Plan**\n\n- [x] Read\n- [ ] Write\n\n\
+             **Claude**\n\nDone"
+        );
+    }
+}
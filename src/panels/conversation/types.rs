@@ -1,7 +1,11 @@
-use agent_client_protocol::{ContentBlock, ToolCallStatus, ToolKind};
-use gpui::SharedString;
+use std::sync::Arc;
+
+use agent_client_protocol::{ContentBlock, EmbeddedResourceResource, ToolCallStatus, ToolKind};
+use gpui::{Image, SharedString};
 use gpui_component::{Icon, IconName};
 
+use crate::utils::blob::{self, BlobPreview};
+
 // ============================================================================
 // Helper Traits
 // ============================================================================
@@ -76,9 +80,47 @@ pub struct ResourceInfo {
     pub name: SharedString,
     pub mime_type: Option<SharedString>,
     pub text: Option<SharedString>,
+    pub blob: Option<BlobResource>,
+}
+
+/// Decoded contents of an embedded binary resource
+#[derive(Clone)]
+pub struct BlobResource {
+    pub bytes: Arc<Vec<u8>>,
+    pub preview: BlobPreview,
+    /// Set for images, created once so that GPUI caches the decoded image
+    pub image: Option<Arc<Image>>,
+}
+
+impl BlobResource {
+    pub fn new(bytes: Vec<u8>, mime_type: Option<&str>) -> Self {
+        let preview = BlobPreview::inspect(&bytes, mime_type);
+        let image = match preview {
+            BlobPreview::Image(format) => Some(Arc::new(Image::from_bytes(format, bytes.clone()))),
+            _ => None,
+        };
+        Self {
+            bytes: Arc::new(bytes),
+            preview,
+            image,
+        }
+    }
 }
 
 impl ResourceInfo {
+    /// Whether `content` is shown as a resource; cheaper than building it
+    pub fn describes(content: &ContentBlock) -> bool {
+        match content {
+            ContentBlock::ResourceLink(_) => true,
+            ContentBlock::Resource(embedded) => matches!(
+                embedded.resource,
+                EmbeddedResourceResource::TextResourceContents(_)
+                    | EmbeddedResourceResource::BlobResourceContents(_)
+            ),
+            _ => false,
+        }
+    }
+
     pub fn from_content_block(content: &ContentBlock) -> Option<Self> {
         match content {
             ContentBlock::ResourceLink(link) => Some(ResourceInfo {
@@ -86,9 +128,32 @@ impl ResourceInfo {
                 name: link.name.clone().into(),
                 mime_type: link.mime_type.clone().map(Into::into),
                 text: None,
+                blob: None,
             }),
-            // TODO: Handle Resource type when schema is clarified
-            // ContentBlock::Resource(res) => { ... }
+            ContentBlock::Resource(embedded) => match &embedded.resource {
+                EmbeddedResourceResource::TextResourceContents(text_res) => Some(ResourceInfo {
+                    uri: text_res.uri.clone().into(),
+                    name: extract_filename(&text_res.uri).into(),
+                    mime_type: text_res.mime_type.clone().map(Into::into),
+                    text: Some(text_res.text.clone().into()),
+                    blob: None,
+                }),
+                EmbeddedResourceResource::BlobResourceContents(blob_res) => {
+                    let blob = blob::decode(&blob_res.blob)
+                        .map(|bytes| BlobResource::new(bytes, blob_res.mime_type.as_deref()));
+                    if blob.is_none() {
+                        log::warn!("Resource {} is not valid base64", blob_res.uri);
+                    }
+                    Some(ResourceInfo {
+                        uri: blob_res.uri.clone().into(),
+                        name: extract_filename(&blob_res.uri).into(),
+                        mime_type: blob_res.mime_type.clone().map(Into::into),
+                        text: None,
+                        blob,
+                    })
+                }
+                _ => None,
+            },
             _ => None,
         }
     }
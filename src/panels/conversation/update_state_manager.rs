@@ -11,6 +11,7 @@ use super::helpers::{extract_text_from_content, session_update_type_name};
 use super::rendered_item::{RenderedItem, create_agent_message_data};
 use super::types::ResourceInfo;
 use crate::components::ToolCallItem;
+use crate::core::services::UnsupportedUpdate;
 use crate::{AppState, UserMessageData};
 
 /// Fast index for locating items in the rendered list
@@ -22,6 +23,8 @@ pub struct UpdateStateIndex {
     last_message_index: Option<usize>,
     /// Track the index of the last thought item (for fast appending)
     last_thought_index: Option<usize>,
+    /// Maps index in rendered_items -> the updates it was built from
+    sources: HashMap<usize, Vec<SessionUpdate>>,
 }
 
 impl UpdateStateIndex {
@@ -70,6 +73,16 @@ impl UpdateStateIndex {
         self.last_thought_index
     }
 
+    /// Record that `update` went into the item at `index`
+    pub fn record_source(&mut self, index: usize, update: SessionUpdate) {
+        self.sources.entry(index).or_default().push(update);
+    }
+
+    /// The updates the item at `index` was built from, oldest first
+    pub fn sources(&self, index: usize) -> &[SessionUpdate] {
+        self.sources.get(&index).map_or(&[], Vec::as_slice)
+    }
+
     /// Clear the last message/thought tracking when type changes
     pub fn clear_streaming_state(&mut self) {
         self.last_message_index = None;
@@ -137,6 +150,16 @@ impl<'a, T> UpdateProcessor<'a, T> {
             update_type
         );
 
+        let source = update.clone();
+        let tool_call_id = match &update {
+            SessionUpdate::ToolCall(tool_call) => Some(tool_call.tool_call_id.to_string()),
+            SessionUpdate::ToolCallUpdate(tool_call_update) => {
+                Some(tool_call_update.tool_call_id.to_string())
+            }
+            _ => None,
+        };
+        let item_count = self.items.len();
+
         match update {
             SessionUpdate::UserMessageChunk(chunk) => {
                 self.process_user_message_chunk(chunk, cx);
@@ -180,13 +203,27 @@ impl<'a, T> UpdateProcessor<'a, T> {
             _ => {
                 log::warn!(
                     "⚠️  UNHANDLED SessionUpdate type: {}\n\
-                     This update will be ignored. Consider implementing support for this type.\n\
+                     This update is shown as JSON. Consider implementing support for this type.\n\
                      Update details: {:?}",
                     update_type,
                     update
                 );
+                self.process_unsupported(UnsupportedUpdate::from_update(&update));
             }
         }
+
+        // The item the update went into, for viewing its source
+        let target = match &source {
+            SessionUpdate::AgentMessageChunk(_) => self.index.last_message(),
+            SessionUpdate::AgentThoughtChunk(_) => self.index.last_thought(),
+            _ => match &tool_call_id {
+                Some(id) => self.index.find_tool_call(id),
+                None => (self.items.len() > item_count).then(|| self.items.len() - 1),
+            },
+        };
+        if let Some(target) = target {
+            self.index.record_source(target, source);
+        }
     }
 
     /// Process UserMessageChunk
@@ -366,6 +403,15 @@ impl<'a, T> UpdateProcessor<'a, T> {
         }
     }
 
+    /// Process an update of a kind not rendered, or not readable by this
+    /// version when loaded from history
+    pub fn process_unsupported(&mut self, update: UnsupportedUpdate) {
+        self.complete_last_item();
+        self.index.clear_streaming_state();
+        log::debug!("  └─ Creating Unsupported item: {}", update.kind);
+        self.items.push(RenderedItem::Unsupported(update));
+    }
+
     /// Process Plan
     fn process_plan(&mut self, plan: Plan) {
         self.complete_last_item();
@@ -448,4 +494,21 @@ mod tests {
         assert_eq!(index.last_message(), None);
         assert_eq!(index.last_thought(), None);
     }
+
+    #[test]
+    fn test_index_sources() {
+        use agent_client_protocol::ContentBlock;
+
+        let mut index = UpdateStateIndex::new();
+        let chunk = |text: &str| {
+            SessionUpdate::AgentMessageChunk(ContentChunk::new(ContentBlock::from(
+                text.to_string(),
+            )))
+        };
+
+        index.record_source(2, chunk("Hello"));
+        index.record_source(2, chunk(", world"));
+        assert_eq!(index.sources(2).len(), 2);
+        assert!(index.sources(3).is_empty());
+    }
 }
@@ -0,0 +1,419 @@
+//! Dashboard Panel - Agent activity in the active workspace over time
+//!
+//! Charts sessions and tool calls per day, with totals of edited files and
+//! estimated token usage, the average latency of turns and the most used
+//! agents, computed by the MetricsService from the tasks and session
+//! histories on disk.
+
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, Hsla, IntoElement, ParentElement,
+    Pixels, Render, SharedString, Styled, Window, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex, v_flex,
+};
+use rust_i18n::t;
+
+use crate::{
+    AppState, ExportStats,
+    core::{event_bus::WorkspaceUpdateEvent, services::WorkspaceActivity},
+    panels::dock_panel::DockPanel,
+    utils::time::format_latency,
+};
+
+/// Days shown in the charts
+const DASHBOARD_DAYS: usize = 14;
+/// Agents listed under "Top agents"
+const MAX_TOP_AGENTS: usize = 5;
+/// Height of the tallest bar
+const CHART_HEIGHT: f32 = 72.;
+
+pub struct DashboardPanel {
+    focus_handle: FocusHandle,
+    workspace_name: Option<String>,
+    activity: WorkspaceActivity,
+    loading: bool,
+}
+
+impl DockPanel for DashboardPanel {
+    fn title() -> &'static str {
+        "Dashboard"
+    }
+
+    fn title_key() -> Option<&'static str> {
+        Some("dashboard_panel.title")
+    }
+
+    fn description() -> &'static str {
+        "Agent activity in the active workspace"
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        Self::view(window, cx)
+    }
+
+    fn paddings() -> Pixels {
+        px(12.)
+    }
+}
+
+impl DashboardPanel {
+    pub fn view(_window: &mut Window, cx: &mut App) -> Entity<Self> {
+        let entity = cx.new(|cx| Self {
+            focus_handle: cx.focus_handle(),
+            workspace_name: None,
+            activity: WorkspaceActivity::default(),
+            loading: false,
+        });
+        Self::subscribe_to_workspace_updates(&entity, cx);
+        entity.update(cx, |this, cx| this.refresh(cx));
+        entity
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let state = AppState::global(cx);
+        let (Some(workspace_service), Some(metrics_service)) = (
+            state.workspace_service().cloned(),
+            state.metrics_service().cloned(),
+        ) else {
+            log::warn!("[DashboardPanel] Services not initialized");
+            return;
+        };
+
+        self.loading = true;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let workspace = workspace_service.get_active_workspace().await;
+            let activity = match &workspace {
+                Some(workspace) => {
+                    metrics_service
+                        .workspace_activity(&workspace.id, DASHBOARD_DAYS)
+                        .await
+                }
+                None => WorkspaceActivity::default(),
+            };
+            _ = this.update(cx, |this, cx| {
+                this.workspace_name = workspace.map(|workspace| workspace.name);
+                this.activity = activity;
+                this.loading = false;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn subscribe_to_workspace_updates(entity: &Entity<Self>, cx: &mut App) {
+        let workspace_bus = AppState::global(cx).workspace_bus.clone();
+        let weak_entity = entity.downgrade();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        workspace_bus.subscribe(move |event| {
+            if matches!(
+                event,
+                WorkspaceUpdateEvent::WorkspaceAdded { .. }
+                    | WorkspaceUpdateEvent::WorkspaceRemoved { .. }
+                    | WorkspaceUpdateEvent::TaskCreated { .. }
+                    | WorkspaceUpdateEvent::TaskUpdated { .. }
+                    | WorkspaceUpdateEvent::TaskRemoved { .. }
+            ) {
+                let _ = tx.send(());
+            }
+        });
+
+        cx.spawn(async move |cx| {
+            while rx.recv().await.is_some() {
+                let Some(entity) = weak_entity.upgrade() else {
+                    break;
+                };
+                _ = cx.update(|cx| entity.update(cx, |this, cx| this.refresh(cx)));
+            }
+        })
+        .detach();
+    }
+
+    fn render_stat(label: SharedString, value: String, cx: &App) -> impl IntoElement {
+        let theme = cx.theme();
+        v_flex()
+            .flex_1()
+            .gap_0p5()
+            .p_2()
+            .rounded(px(6.))
+            .bg(theme.secondary)
+            .child(
+                div()
+                    .text_lg()
+                    .font_semibold()
+                    .text_color(theme.foreground)
+                    .child(value),
+            )
+            .child(div().text_xs().text_color(theme.muted_foreground).child(label))
+    }
+
+    /// Average queue, first update and turn time of the timed turns
+    fn render_latency(&self, cx: &App) -> impl IntoElement {
+        let latency = &self.activity.latency;
+        h_flex()
+            .w_full()
+            .gap_2()
+            .child(Self::render_stat(
+                t!("dashboard_panel.latency.queue").to_string().into(),
+                format_latency(latency.queue_ms),
+                cx,
+            ))
+            .child(Self::render_stat(
+                t!("dashboard_panel.latency.first_chunk").to_string().into(),
+                latency
+                    .first_chunk_ms
+                    .map_or_else(|| "-".to_string(), format_latency),
+                cx,
+            ))
+            .child(Self::render_stat(
+                t!("dashboard_panel.latency.total").to_string().into(),
+                format_latency(latency.total_ms),
+                cx,
+            ))
+    }
+
+    /// One bar per day, scaled to the largest value
+    fn render_chart(
+        &self,
+        title: SharedString,
+        values: Vec<usize>,
+        color: Hsla,
+        cx: &App,
+    ) -> impl IntoElement {
+        let theme = cx.theme();
+        let max = values.iter().copied().max().unwrap_or(0).max(1);
+        let bars = values.into_iter().map(|value| {
+            let height = 2. + (CHART_HEIGHT - 2.) * value as f32 / max as f32;
+            v_flex()
+                .flex_1()
+                .items_center()
+                .justify_end()
+                .gap_0p5()
+                .when(value > 0, |this| {
+                    this.child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child(value.to_string()),
+                    )
+                })
+                .child(
+                    div()
+                        .w_full()
+                        .h(px(height))
+                        .rounded(px(2.))
+                        .bg(if value > 0 { color } else { theme.border }),
+                )
+        });
+
+        let days = &self.activity.days;
+        let first = days.first().map(|day| day.date.format("%m-%d").to_string());
+        let last = days.last().map(|day| day.date.format("%m-%d").to_string());
+
+        v_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                div()
+                    .text_sm()
+                    .font_semibold()
+                    .text_color(theme.foreground)
+                    .child(title),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .h(px(CHART_HEIGHT + 16.))
+                    .items_end()
+                    .gap_1()
+                    .children(bars),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .children(first)
+                    .children(last),
+            )
+    }
+
+    fn render_top_agents(&self, cx: &App) -> impl IntoElement {
+        let theme = cx.theme();
+        let max = self
+            .activity
+            .top_agents
+            .first()
+            .map(|(_, count)| *count)
+            .unwrap_or(1)
+            .max(1);
+        let rows = self
+            .activity
+            .top_agents
+            .iter()
+            .take(MAX_TOP_AGENTS)
+            .map(|(agent, count)| {
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .items_center()
+                    .text_xs()
+                    .child(
+                        div()
+                            .w(px(120.))
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .text_color(theme.foreground)
+                            .child(agent.clone()),
+                    )
+                    .child(
+                        div().flex_1().child(
+                            div()
+                                .h(px(8.))
+                                .w(gpui::relative(*count as f32 / max as f32))
+                                .rounded(px(2.))
+                                .bg(theme.primary),
+                        ),
+                    )
+                    .child(
+                        div()
+                            .w(px(32.))
+                            .text_right()
+                            .text_color(theme.muted_foreground)
+                            .child(count.to_string()),
+                    )
+            });
+
+        v_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                div()
+                    .text_sm()
+                    .font_semibold()
+                    .text_color(theme.foreground)
+                    .child(t!("dashboard_panel.top_agents").to_string()),
+            )
+            .children(rows)
+    }
+}
+
+impl Focusable for DashboardPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DashboardPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        let Some(workspace_name) = self.workspace_name.clone() else {
+            return v_flex()
+                .size_full()
+                .items_center()
+                .justify_center()
+                .text_sm()
+                .text_color(theme.muted_foreground)
+                .child(t!("dashboard_panel.no_workspace").to_string());
+        };
+
+        let activity = &self.activity;
+        let sessions = activity.days.iter().map(|day| day.sessions).collect();
+        let tool_calls = activity.days.iter().map(|day| day.tool_calls).collect();
+
+        v_flex()
+            .size_full()
+            .gap_4()
+            .child(
+                h_flex()
+                    .w_full()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child(
+                                t!(
+                                    "dashboard_panel.description",
+                                    workspace = workspace_name,
+                                    days = DASHBOARD_DAYS
+                                )
+                                .to_string(),
+                            ),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("dashboard-export")
+                                    .label(t!("dashboard_panel.export").to_string())
+                                    .ghost()
+                                    .small()
+                                    .on_click(|_, window, cx| {
+                                        window.dispatch_action(Box::new(ExportStats), cx);
+                                    }),
+                            )
+                            .child(
+                                Button::new("dashboard-refresh")
+                                    .icon(Icon::new(IconName::LoaderCircle))
+                                    .ghost()
+                                    .small()
+                                    .loading(self.loading)
+                                    .on_click(cx.listener(|this, _, _window, cx| this.refresh(cx))),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(Self::render_stat(
+                        t!("dashboard_panel.sessions").to_string().into(),
+                        activity.sessions.to_string(),
+                        cx,
+                    ))
+                    .child(Self::render_stat(
+                        t!("dashboard_panel.tool_calls").to_string().into(),
+                        activity.tool_calls.to_string(),
+                        cx,
+                    ))
+                    .child(Self::render_stat(
+                        t!("dashboard_panel.files_modified").to_string().into(),
+                        activity.files_modified.to_string(),
+                        cx,
+                    ))
+                    .child(Self::render_stat(
+                        t!("dashboard_panel.tokens").to_string().into(),
+                        format!("~{}", activity.usage.estimated_tokens()),
+                        cx,
+                    )),
+            )
+            .when(activity.latency.turns > 0, |this| {
+                this.child(self.render_latency(cx))
+            })
+            .child(self.render_chart(
+                t!("dashboard_panel.sessions_per_day").to_string().into(),
+                sessions,
+                theme.primary,
+                cx,
+            ))
+            .child(self.render_chart(
+                t!("dashboard_panel.tool_calls_per_day").to_string().into(),
+                tool_calls,
+                theme.primary.opacity(0.6),
+                cx,
+            ))
+            .when(!activity.top_agents.is_empty(), |this| {
+                this.child(self.render_top_agents(cx))
+            })
+    }
+}
@@ -3,22 +3,25 @@ use gpui::{prelude::FluentBuilder, *};
 use gpui_component::{
     ActiveTheme, IconName, WindowExt,
     button::Button,
-    dock::{Panel, PanelControl, PanelEvent, PanelInfo, PanelState, TitleStyle},
+    dock::{DockPlacement, Panel, PanelControl, PanelEvent, PanelInfo, PanelState, TitleStyle},
     group_box::{GroupBox, GroupBoxVariants as _},
     h_flex,
-    menu::PopupMenu,
+    menu::{DropdownMenu as _, PopupMenu},
     notification::Notification,
 };
 
 use rust_i18n::t;
 use serde::{Deserialize, Serialize};
 
+use crate::app::focus_mode::{FocusMode, NotificationExt as _};
 use crate::AppState;
+use crate::core::services::SessionExportFormat;
 use crate::panels::{
-    CodeEditorPanel, ConversationPanel, SessionManagerPanel, SettingsPanel, TaskPanel,
-    TerminalPanel, ToolCallDetailPanel, WelcomePanel,
+    AuditPanel, CodeEditorPanel, ConversationPanel, DashboardPanel, FeedbackPanel, GitPanel,
+    McpBrowserPanel, McpConsolePanel, MemoryPanel, SearchPanel, SessionManagerPanel, SettingsPanel,
+    TaskPanel, TerminalPanel, ToolCallDetailPanel, WelcomePanel,
 };
-use crate::{ShowPanelInfo, ToggleSearch};
+use crate::{ExportSession, MoveSessionPanel, ShowPanelInfo, ToggleSearch};
 
 #[derive(IntoElement)]
 pub struct DockPanelSection {
@@ -123,6 +126,10 @@ pub struct DockPanelContainer {
     pub zoomable: Option<PanelControl>,
     pub paddings: Pixels,
     pub on_active: Option<fn(AnyView, bool, &mut Window, &mut App)>,
+    /// Unseen activity of the hosted panel, shown as a badge on the tab
+    pub activity: Option<PanelActivity>,
+    is_active: bool,
+    _activity_subscription: Option<Subscription>,
 }
 
 #[derive(Debug)]
@@ -130,6 +137,15 @@ pub enum ContainerEvent {
     Close,
 }
 
+/// Activity a panel reports while it may be in a background tab
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PanelActivity {
+    /// New content arrived
+    Updated,
+    /// Something went wrong
+    Error,
+}
+
 pub trait DockPanel: Render + Sized {
     fn klass() -> &'static str {
         std::any::type_name::<Self>().split("::").last().unwrap()
@@ -179,6 +195,18 @@ pub trait DockPanel: Render + Sized {
             });
         }
     }
+
+    /// Forward the `PanelActivity` events of the hosted view to its container
+    ///
+    /// Panels that emit `PanelActivity` override this with
+    /// `DockPanelContainer::subscribe_activity`.
+    fn observe_activity(
+        view: AnyView,
+        cx: &mut Context<DockPanelContainer>,
+    ) -> Option<Subscription> {
+        let _ = (view, cx);
+        None
+    }
 }
 
 impl EventEmitter<ContainerEvent> for DockPanelContainer {}
@@ -201,9 +229,36 @@ impl DockPanelContainer {
             zoomable: Some(PanelControl::default()),
             paddings: px(0.0),
             on_active: None,
+            activity: None,
+            is_active: false,
+            _activity_subscription: None,
         }
     }
 
+    /// Subscription that badges the tab when `view` reports activity
+    pub fn subscribe_activity<V: EventEmitter<PanelActivity>>(
+        view: &Entity<V>,
+        cx: &mut Context<Self>,
+    ) -> Subscription {
+        cx.subscribe(view, |this, _, activity: &PanelActivity, cx| {
+            if this.is_active {
+                return;
+            }
+            // An error stays visible over later updates
+            if this.activity.is_none_or(|current| current < *activity) {
+                this.activity = Some(*activity);
+                cx.notify();
+            }
+        })
+    }
+
+    fn observe_activity<S: DockPanel + 'static>(&mut self, cx: &mut Context<Self>) {
+        self._activity_subscription = self
+            .agent_studio
+            .clone()
+            .and_then(|view| S::observe_activity(view, cx));
+    }
+
     pub fn panel<S: DockPanel>(window: &mut Window, cx: &mut App) -> Entity<Self> {
         let name = S::title();
         let title_key = S::title_key();
@@ -223,6 +278,7 @@ impl DockPanelContainer {
             agent_studio.description = description.into();
             agent_studio.title_bg = S::title_bg();
             agent_studio.paddings = S::paddings();
+            agent_studio.observe_activity::<S>(cx);
             agent_studio
         });
 
@@ -285,12 +341,43 @@ impl DockPanelContainer {
             container.description = description.into();
             container.title_bg = ConversationPanel::title_bg();
             container.paddings = ConversationPanel::paddings();
+            container.observe_activity::<ConversationPanel>(cx);
             container
         });
 
         view
     }
 
+    /// Wrap an existing ConversationPanel entity in a new container
+    ///
+    /// Used when moving a session between dock placements: the conversation entity
+    /// (and its live subscriptions, scroll position and input state) is reused as-is.
+    pub fn panel_for_conversation(
+        conversation: Entity<ConversationPanel>,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let name = ConversationPanel::title();
+        let title_key = ConversationPanel::title_key();
+        let description = ConversationPanel::description();
+        let agent_studio_klass = ConversationPanel::klass();
+
+        cx.new(|cx| {
+            let mut container = Self::new(cx)
+                .agent_studio(conversation.into(), agent_studio_klass)
+                .on_active(ConversationPanel::on_active_any);
+            container.focus_handle = cx.focus_handle();
+            container.closable = ConversationPanel::closable();
+            container.zoomable = ConversationPanel::zoomable();
+            container.name = name.into();
+            container.title_key = title_key.map(SharedString::from);
+            container.description = description.into();
+            container.title_bg = ConversationPanel::title_bg();
+            container.paddings = ConversationPanel::paddings();
+            container.observe_activity::<ConversationPanel>(cx);
+            container
+        })
+    }
+
     pub fn replace_with_conversation_session(
         &mut self,
         session_id: Option<String>,
@@ -316,6 +403,8 @@ impl DockPanelContainer {
         self.description = description.into();
         self.title_bg = ConversationPanel::title_bg();
         self.paddings = ConversationPanel::paddings();
+        self.activity = None;
+        self.observe_activity::<ConversationPanel>(cx);
         cx.notify();
     }
 
@@ -426,6 +515,14 @@ impl DockPanelContainer {
             "TaskPanel" => Self::panel::<TaskPanel>(window, cx),
             "SessionManagerPanel" => Self::panel::<SessionManagerPanel>(window, cx),
             "SettingsPanel" => Self::panel::<SettingsPanel>(window, cx),
+            "MemoryPanel" => Self::panel::<MemoryPanel>(window, cx),
+            "AuditPanel" => Self::panel::<AuditPanel>(window, cx),
+            "SearchPanel" => Self::panel::<SearchPanel>(window, cx),
+            "McpConsolePanel" => Self::panel::<McpConsolePanel>(window, cx),
+            "McpBrowserPanel" => Self::panel::<McpBrowserPanel>(window, cx),
+            "GitPanel" => Self::panel::<GitPanel>(window, cx),
+            "FeedbackPanel" => Self::panel::<FeedbackPanel>(window, cx),
+            "DashboardPanel" => Self::panel::<DashboardPanel>(window, cx),
             "ToolCallDetailPanel" => Self::panel::<ToolCallDetailPanel>(window, cx),
             "ConversationPanel" => {
                 if let Some(session_id) = agent_state
@@ -433,7 +530,13 @@ impl DockPanelContainer {
                     .as_deref()
                     .filter(|id| !id.is_empty())
                 {
-                    Self::panel_for_session(session_id.to_string(), window, cx)
+                    let conversation = ConversationPanel::view_for_restored_session(
+                        session_id.to_string(),
+                        agent_state.scroll_offset,
+                        window,
+                        cx,
+                    );
+                    Self::panel_for_conversation(conversation, cx)
                 } else {
                     Self::panel::<ConversationPanel>(window, cx)
                 }
@@ -555,7 +658,7 @@ impl DockPanelContainer {
         let note = Notification::new()
             .message(format!("You have clicked panel info on: {}", self.name))
             .id::<Info>();
-        window.push_notification(note, cx);
+        window.show_notification(note, cx);
     }
 
     fn on_action_toggle_search(
@@ -573,7 +676,7 @@ impl DockPanelContainer {
         let note = Notification::new()
             .message(format!("You have toggled search on: {}", self.name))
             .id::<Search>();
-        window.push_notification(note, cx);
+        window.show_notification(note, cx);
     }
 }
 
@@ -588,6 +691,9 @@ pub struct DockPanelState {
     pub workspace_name: Option<String>,
     #[serde(default)]
     pub working_directory: Option<String>,
+    /// Vertical scroll offset of a conversation
+    #[serde(default)]
+    pub scroll_offset: Option<f32>,
 }
 
 impl DockPanelState {
@@ -598,6 +704,7 @@ impl DockPanelState {
             "workspace_id": self.workspace_id,
             "workspace_name": self.workspace_name,
             "working_directory": self.working_directory,
+            "scroll_offset": self.scroll_offset,
         })
     }
 
@@ -614,14 +721,24 @@ impl Panel for DockPanelContainer {
     fn title(
         &mut self,
         _window: &mut gpui::Window,
-        _cx: &mut gpui::Context<'_, DockPanelContainer>,
+        cx: &mut gpui::Context<'_, DockPanelContainer>,
     ) -> impl gpui::IntoElement {
         let title = if let Some(key) = &self.title_key {
             SharedString::from(t!(key.as_ref()).to_string())
         } else {
             self.name.clone()
         };
-        title.into_any_element()
+        let activity = self.activity.filter(|_| !FocusMode::is_active(cx));
+        let badge = activity.map(|activity| match activity {
+            PanelActivity::Updated => cx.theme().primary,
+            PanelActivity::Error => cx.theme().danger,
+        });
+        h_flex()
+            .gap_1p5()
+            .child(title)
+            .when_some(badge, |this, color| {
+                this.child(div().size(px(6.)).flex_shrink_0().rounded_full().bg(color))
+            })
     }
 
     fn title_style(&self, cx: &App) -> Option<TitleStyle> {
@@ -666,6 +783,10 @@ impl Panel for DockPanelContainer {
         cx: &mut gpui::Context<'_, DockPanelContainer>,
     ) {
         println!("panel: {} active: {}", self.name, active);
+        self.is_active = active;
+        if active && self.activity.take().is_some() {
+            cx.notify();
+        }
         if let Some(on_active) = self.on_active {
             if let Some(agent_studio) = self.agent_studio.clone() {
                 on_active(agent_studio, active, _window, cx);
@@ -685,8 +806,64 @@ impl Panel for DockPanelContainer {
     fn toolbar_buttons(
         &mut self,
         _window: &mut Window,
-        _cx: &mut gpui::Context<'_, DockPanelContainer>,
+        cx: &mut gpui::Context<'_, DockPanelContainer>,
     ) -> Option<Vec<Button>> {
+        let session_id = self
+            .agent_studio
+            .clone()
+            .and_then(|view| view.downcast::<ConversationPanel>().ok())
+            .and_then(|entity| entity.read(cx).session_id());
+
+        if let Some(session_id) = session_id {
+            let export_session_id = session_id.clone();
+            return Some(vec![
+                Button::new("export-session")
+                    .icon(IconName::ArrowDown)
+                    .tooltip(t!("conversation.export.tooltip").to_string())
+                    .dropdown_menu(move |menu, _, _| {
+                        menu.menu(
+                            t!("conversation.export.markdown").to_string(),
+                            Box::new(ExportSession {
+                                session_id: export_session_id.clone(),
+                                format: SessionExportFormat::Markdown,
+                            }),
+                        )
+                        .menu(
+                            t!("conversation.export.json").to_string(),
+                            Box::new(ExportSession {
+                                session_id: export_session_id.clone(),
+                                format: SessionExportFormat::Json,
+                            }),
+                        )
+                    }),
+                Button::new("move-session")
+                    .icon(IconName::Ellipsis)
+                    .dropdown_menu(move |menu, _, _| {
+                        menu.menu(
+                            t!("conversation.move.center").to_string(),
+                            Box::new(MoveSessionPanel {
+                                session_id: session_id.clone(),
+                                placement: DockPlacement::Center,
+                            }),
+                        )
+                        .menu(
+                            t!("conversation.move.right").to_string(),
+                            Box::new(MoveSessionPanel {
+                                session_id: session_id.clone(),
+                                placement: DockPlacement::Right,
+                            }),
+                        )
+                        .menu(
+                            t!("conversation.move.bottom").to_string(),
+                            Box::new(MoveSessionPanel {
+                                session_id: session_id.clone(),
+                                placement: DockPlacement::Bottom,
+                            }),
+                        )
+                    }),
+            ]);
+        }
+
         Some(vec![
             // Button::new("info")
             //     .icon(IconName::Info)
@@ -709,6 +886,7 @@ impl Panel for DockPanelContainer {
         let mut workspace_id = None;
         let mut workspace_name = None;
         let mut working_directory = None;
+        let mut scroll_offset = None;
 
         // Helper function to normalize Windows paths (remove \\?\ prefix)
         fn normalize_path(path: std::path::PathBuf) -> String {
@@ -730,6 +908,7 @@ impl Panel for DockPanelContainer {
                         workspace_id = panel.workspace_id();
                         workspace_name = panel.workspace_name();
                         working_directory = panel.working_directory();
+                        scroll_offset = panel.scroll_offset();
                     }
                 }
                 "WelcomePanel" => {
@@ -766,6 +945,7 @@ impl Panel for DockPanelContainer {
             workspace_id,
             workspace_name,
             working_directory,
+            scroll_offset,
         };
         state.info = PanelInfo::panel(agent_state.to_value());
         state
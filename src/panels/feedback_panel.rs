@@ -0,0 +1,306 @@
+//! Feedback Panel - How the user rated each agent's replies
+//!
+//! Totals the thumbs up/down given in conversations per agent, with the most
+//! recent comments, to compare agents and configurations over time. Tasks
+//! tagged with an experiment label are also compared per label, including
+//! their turns, average turn time and estimated token usage.
+
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, Pixels,
+    Render, Styled, Window, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex, v_flex,
+};
+use rust_i18n::t;
+
+use crate::{
+    AppState,
+    core::event_bus::WorkspaceUpdateEvent,
+    panels::dock_panel::DockPanel,
+    schemas::workspace_schema::{AgentFeedbackSummary, FeedbackRating, LabelSummary},
+};
+
+/// Comments shown per agent
+const MAX_COMMENTS: usize = 3;
+
+pub struct FeedbackPanel {
+    focus_handle: FocusHandle,
+    summaries: Vec<AgentFeedbackSummary>,
+    labels: Vec<LabelSummary>,
+}
+
+impl DockPanel for FeedbackPanel {
+    fn title() -> &'static str {
+        "Feedback"
+    }
+
+    fn title_key() -> Option<&'static str> {
+        Some("feedback_panel.title")
+    }
+
+    fn description() -> &'static str {
+        "Ratings of agent replies, per agent"
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        Self::view(window, cx)
+    }
+
+    fn paddings() -> Pixels {
+        px(12.)
+    }
+}
+
+impl FeedbackPanel {
+    pub fn view(_window: &mut Window, cx: &mut App) -> Entity<Self> {
+        let entity = cx.new(|cx| Self {
+            focus_handle: cx.focus_handle(),
+            summaries: Vec::new(),
+            labels: Vec::new(),
+        });
+        Self::subscribe_to_feedback_updates(&entity, cx);
+        entity.update(cx, |this, cx| this.refresh(cx));
+        entity
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            log::warn!("[FeedbackPanel] WorkspaceService not initialized");
+            return;
+        };
+        cx.spawn(async move |this, cx| {
+            let summaries = workspace_service.feedback_report().await;
+            let labels = workspace_service.label_report().await;
+            _ = this.update(cx, |this, cx| {
+                this.summaries = summaries;
+                // Only worth showing once some task carries a label
+                this.labels = if labels.iter().any(|summary| summary.label.is_some()) {
+                    labels
+                } else {
+                    Vec::new()
+                };
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn subscribe_to_feedback_updates(entity: &Entity<Self>, cx: &mut App) {
+        let workspace_bus = AppState::global(cx).workspace_bus.clone();
+        let weak_entity = entity.downgrade();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        workspace_bus.subscribe(move |event| {
+            if matches!(
+                event,
+                WorkspaceUpdateEvent::FeedbackUpdated { .. }
+                    | WorkspaceUpdateEvent::TaskCreated { .. }
+                    | WorkspaceUpdateEvent::TaskUpdated { .. }
+                    | WorkspaceUpdateEvent::TaskRemoved { .. }
+                    | WorkspaceUpdateEvent::WorkspaceRemoved { .. }
+            ) {
+                let _ = tx.send(());
+            }
+        });
+
+        cx.spawn(async move |cx| {
+            while rx.recv().await.is_some() {
+                let Some(entity) = weak_entity.upgrade() else {
+                    break;
+                };
+                _ = cx.update(|cx| entity.update(cx, |this, cx| this.refresh(cx)));
+            }
+        })
+        .detach();
+    }
+}
+
+impl Focusable for FeedbackPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for FeedbackPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        let rows = self.summaries.iter().map(|summary| {
+            let comments = summary
+                .comments
+                .iter()
+                .take(MAX_COMMENTS)
+                .map(|(rating, comment)| {
+                    let icon = match rating {
+                        FeedbackRating::Up => IconName::ThumbsUp,
+                        FeedbackRating::Down => IconName::ThumbsDown,
+                    };
+                    h_flex()
+                        .gap_2()
+                        .items_start()
+                        .child(
+                            Icon::new(icon)
+                                .size(px(12.))
+                                .mt_0p5()
+                                .text_color(theme.muted_foreground),
+                        )
+                        .child(
+                            div()
+                                .flex_1()
+                                .text_xs()
+                                .text_color(theme.muted_foreground)
+                                .child(comment.clone()),
+                        )
+                });
+
+            v_flex()
+                .w_full()
+                .gap_1()
+                .p_2()
+                .rounded(px(6.))
+                .bg(theme.secondary)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_3()
+                        .items_center()
+                        .child(
+                            div()
+                                .flex_1()
+                                .text_sm()
+                                .font_semibold()
+                                .text_color(theme.foreground)
+                                .child(summary.agent_name.clone()),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .items_center()
+                                .text_xs()
+                                .text_color(theme.muted_foreground)
+                                .child(Icon::new(IconName::ThumbsUp).size(px(12.)))
+                                .child(summary.up.to_string())
+                                .child(Icon::new(IconName::ThumbsDown).size(px(12.)).ml_1())
+                                .child(summary.down.to_string()),
+                        )
+                        .child(
+                            div().text_xs().font_semibold().text_color(theme.foreground).child(
+                                t!(
+                                    "feedback_panel.approval",
+                                    percent = (summary.approval() * 100.0).round()
+                                )
+                                .to_string(),
+                            ),
+                        ),
+                )
+                .children(comments)
+        });
+
+        let label_rows = self.labels.iter().map(|summary| {
+            let label = summary
+                .label
+                .clone()
+                .unwrap_or_else(|| t!("feedback_panel.labels.unlabeled").to_string());
+            let stats = t!(
+                "feedback_panel.labels.stats",
+                tasks = summary.tasks,
+                turns = summary.usage.turns,
+                seconds = format!("{:.1}", summary.avg_turn_ms() as f64 / 1000.0),
+                tokens = summary.usage.estimated_tokens()
+            )
+            .to_string();
+
+            h_flex()
+                .w_full()
+                .gap_3()
+                .items_center()
+                .p_2()
+                .rounded(px(6.))
+                .bg(theme.secondary)
+                .child(
+                    v_flex()
+                        .flex_1()
+                        .gap_0p5()
+                        .child(
+                            div()
+                                .text_sm()
+                                .font_semibold()
+                                .text_color(theme.foreground)
+                                .child(label),
+                        )
+                        .child(div().text_xs().text_color(theme.muted_foreground).child(stats)),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .font_semibold()
+                        .text_color(theme.foreground)
+                        .when(summary.up + summary.down > 0, |this| {
+                            this.child(
+                                t!(
+                                    "feedback_panel.approval",
+                                    percent = (summary.approval() * 100.0).round()
+                                )
+                                .to_string(),
+                            )
+                        }),
+                )
+        });
+
+        v_flex()
+            .size_full()
+            .gap_3()
+            .child(
+                h_flex()
+                    .w_full()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child(t!("feedback_panel.description").to_string()),
+                    )
+                    .child(
+                        Button::new("feedback-refresh")
+                            .icon(Icon::new(IconName::LoaderCircle))
+                            .ghost()
+                            .small()
+                            .on_click(cx.listener(|this, _, _window, cx| this.refresh(cx))),
+                    ),
+            )
+            .when(self.summaries.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(theme.muted_foreground)
+                        .child(t!("feedback_panel.empty").to_string()),
+                )
+            })
+            .child(v_flex().w_full().gap_2().children(rows))
+            .when(!self.labels.is_empty(), |this| {
+                this.child(
+                    v_flex()
+                        .w_full()
+                        .gap_1()
+                        .child(
+                            div()
+                                .text_sm()
+                                .font_semibold()
+                                .text_color(theme.foreground)
+                                .child(t!("feedback_panel.labels.title").to_string()),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.muted_foreground)
+                                .child(t!("feedback_panel.labels.description").to_string()),
+                        ),
+                )
+                .child(v_flex().w_full().gap_2().children(label_rows))
+            })
+    }
+}
@@ -0,0 +1,385 @@
+//! Git Panel - Staged changes and the commit box of the active workspace
+//!
+//! "Generate" sends the staged diff to the utility model and fills the commit
+//! box with the result, optionally in the Conventional Commits format; the
+//! message can be edited before committing.
+
+use std::path::PathBuf;
+
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, Pixels,
+    Render, Styled, Window, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, Disableable, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputState},
+    notification::Notification,
+    switch::Switch,
+    v_flex,
+};
+use rust_i18n::t;
+
+use crate::app::focus_mode::NotificationExt as _;
+use crate::{
+    AppSettings, AppState,
+    core::{event_bus::WorkspaceUpdateEvent, services::CommitMessageOptions},
+    panels::dock_panel::DockPanel,
+    utils::git::{self, StagedFile},
+};
+
+pub struct GitPanel {
+    focus_handle: FocusHandle,
+    message_state: Entity<InputState>,
+    scope_state: Entity<InputState>,
+    /// Repository of the active workspace
+    repo: Option<PathBuf>,
+    staged: Vec<StagedFile>,
+    /// Why the staged files could not be listed (e.g. not a git repository)
+    error: Option<String>,
+    generating: bool,
+    committing: bool,
+}
+
+impl DockPanel for GitPanel {
+    fn title() -> &'static str {
+        "Git"
+    }
+
+    fn title_key() -> Option<&'static str> {
+        Some("git_panel.title")
+    }
+
+    fn description() -> &'static str {
+        "Staged changes and commit message generation"
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        Self::view(window, cx)
+    }
+
+    fn paddings() -> Pixels {
+        px(12.)
+    }
+}
+
+impl GitPanel {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        let entity = cx.new(|cx| Self::new(window, cx));
+        Self::subscribe_to_workspace_updates(&entity, cx);
+        entity.update(cx, |this, cx| this.refresh(cx));
+        entity
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let message_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(3, 12)
+                .placeholder(t!("git_panel.message.placeholder").to_string())
+        });
+        let scope_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(t!("git_panel.scope.placeholder").to_string())
+        });
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            message_state,
+            scope_state,
+            repo: None,
+            staged: Vec::new(),
+            error: None,
+            generating: false,
+            committing: false,
+        }
+    }
+
+    /// Reload the active workspace and its staged files
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            log::warn!("[GitPanel] WorkspaceService not initialized");
+            return;
+        };
+        cx.spawn(async move |this, cx| {
+            let repo = workspace_service
+                .get_active_workspace()
+                .await
+                .map(|workspace| workspace.path);
+            let staged = match repo.clone() {
+                Some(repo) => smol::unblock(move || git::staged_files(&repo)).await,
+                None => Ok(Vec::new()),
+            };
+            _ = this.update(cx, |this, cx| {
+                this.repo = repo;
+                match staged {
+                    Ok(staged) => {
+                        this.staged = staged;
+                        this.error = None;
+                    }
+                    Err(e) => {
+                        this.staged.clear();
+                        this.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn subscribe_to_workspace_updates(entity: &Entity<Self>, cx: &mut App) {
+        let workspace_bus = AppState::global(cx).workspace_bus.clone();
+        let weak_entity = entity.downgrade();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        // Agents change files during a turn; re-read the index once it ends
+        workspace_bus.subscribe(move |event| {
+            if matches!(
+                event,
+                WorkspaceUpdateEvent::WorkspaceAdded { .. }
+                    | WorkspaceUpdateEvent::WorkspaceRemoved { .. }
+                    | WorkspaceUpdateEvent::TaskCreated { .. }
+                    | WorkspaceUpdateEvent::SessionStatusUpdated { .. }
+            ) {
+                let _ = tx.send(());
+            }
+        });
+
+        cx.spawn(async move |cx| {
+            while rx.recv().await.is_some() {
+                let Some(entity) = weak_entity.upgrade() else {
+                    break;
+                };
+                _ = cx.update(|cx| entity.update(cx, |this, cx| this.refresh(cx)));
+            }
+        })
+        .detach();
+    }
+
+    fn toggle_conventional(&mut self, checked: bool, cx: &mut Context<Self>) {
+        AppSettings::global_mut(cx).conventional_commits = checked;
+        cx.notify();
+    }
+
+    /// Fill the commit box with a message for the staged diff
+    fn generate(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let Some(ai_service) = AppState::global(cx).ai_service().cloned() else {
+            struct NoModel;
+            let note = Notification::warning(t!("git_panel.generate.no_model").to_string())
+                .id::<NoModel>();
+            window.show_notification(note, cx);
+            return;
+        };
+        let scope = self.scope_state.read(cx).value().trim().to_string();
+        let options = CommitMessageOptions {
+            conventional: AppSettings::global(cx).conventional_commits,
+            scope: (!scope.is_empty()).then_some(scope),
+        };
+
+        self.generating = true;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, window| {
+            let result = match smol::unblock(move || git::staged_diff(&repo)).await {
+                Ok(diff) if diff.trim().is_empty() => {
+                    Err(anyhow::anyhow!(t!("git_panel.generate.nothing_staged").to_string()))
+                }
+                Ok(diff) => ai_service.generate_commit_message(&diff, &options).await,
+                Err(e) => Err(e),
+            };
+
+            _ = window.update(|window, cx| {
+                _ = this.update(cx, |this, cx| {
+                    this.generating = false;
+                    match result {
+                        Ok(message) => this.message_state.update(cx, |state, cx| {
+                            state.set_value(message, window, cx);
+                        }),
+                        Err(e) => {
+                            log::warn!("[GitPanel] Commit message generation failed: {:#}", e);
+                            struct GenerateError;
+                            let note = Notification::error(format!("{:#}", e))
+                                .id::<GenerateError>();
+                            window.show_notification(note, cx);
+                        }
+                    }
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn commit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let message = self.message_state.read(cx).value().trim().to_string();
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        if message.is_empty() || self.staged.is_empty() {
+            return;
+        }
+
+        self.committing = true;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, window| {
+            let result = smol::unblock(move || git::commit(&repo, &message)).await;
+
+            _ = window.update(|window, cx| {
+                _ = this.update(cx, |this, cx| {
+                    this.committing = false;
+                    struct CommitResult;
+                    let note = match result {
+                        Ok(summary) => {
+                            this.message_state
+                                .update(cx, |state, cx| state.set_value("", window, cx));
+                            Notification::success(
+                                t!("git_panel.commit.done", summary = summary).to_string(),
+                            )
+                        }
+                        Err(e) => Notification::error(e.to_string()),
+                    };
+                    window.show_notification(note.id::<CommitResult>(), cx);
+                    this.refresh(cx);
+                });
+            });
+        })
+        .detach();
+    }
+}
+
+impl Focusable for GitPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for GitPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        if self.repo.is_none() {
+            return v_flex()
+                .size_full()
+                .items_center()
+                .justify_center()
+                .text_sm()
+                .text_color(theme.muted_foreground)
+                .child(t!("git_panel.no_workspace").to_string());
+        }
+
+        let conventional = AppSettings::global(cx).conventional_commits;
+        let can_commit = !self.staged.is_empty() && !self.committing;
+
+        let files = self.staged.iter().map(|file| {
+            h_flex()
+                .gap_2()
+                .text_xs()
+                .child(
+                    div()
+                        .w(px(14.))
+                        .font_semibold()
+                        .text_color(theme.muted_foreground)
+                        .child(file.status.clone()),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .overflow_hidden()
+                        .text_ellipsis()
+                        .whitespace_nowrap()
+                        .text_color(theme.foreground)
+                        .child(file.path.clone()),
+                )
+        });
+
+        v_flex()
+            .size_full()
+            .gap_3()
+            .child(
+                h_flex()
+                    .w_full()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(theme.foreground)
+                            .child(t!("git_panel.staged", count = self.staged.len()).to_string()),
+                    )
+                    .child(
+                        Button::new("git-refresh")
+                            .icon(Icon::new(IconName::LoaderCircle))
+                            .ghost()
+                            .small()
+                            .on_click(cx.listener(|this, _, _window, cx| this.refresh(cx))),
+                    ),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(theme.muted_foreground)
+                        .child(error),
+                )
+            })
+            .when(self.staged.is_empty() && self.error.is_none(), |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(theme.muted_foreground)
+                        .child(t!("git_panel.nothing_staged").to_string()),
+                )
+            })
+            .child(v_flex().w_full().gap_0p5().children(files))
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        Switch::new("git-conventional")
+                            .checked(conventional)
+                            .label(t!("git_panel.conventional").to_string())
+                            .small()
+                            .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                this.toggle_conventional(*checked, cx);
+                            })),
+                    )
+                    .when(conventional, |this| {
+                        this.child(div().w(px(140.)).child(Input::new(&self.scope_state).small()))
+                    }),
+            )
+            .child(Input::new(&self.message_state))
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .justify_end()
+                    .child(
+                        Button::new("git-generate")
+                            .icon(Icon::new(IconName::Bot))
+                            .label(t!("git_panel.generate.label").to_string())
+                            .outline()
+                            .small()
+                            .loading(self.generating)
+                            .disabled(self.staged.is_empty() || self.generating)
+                            .on_click(cx.listener(|this, _, window, cx| this.generate(window, cx))),
+                    )
+                    .child(
+                        Button::new("git-commit")
+                            .label(t!("git_panel.commit.label").to_string())
+                            .primary()
+                            .small()
+                            .loading(self.committing)
+                            .disabled(!can_commit)
+                            .on_click(cx.listener(|this, _, window, cx| this.commit(window, cx))),
+                    ),
+            )
+    }
+}
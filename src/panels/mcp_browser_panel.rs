@@ -0,0 +1,502 @@
+//! MCP Browser Panel - Browse what MCP servers offer
+//!
+//! Lists the tools, resources, resource templates and prompts of the MCP
+//! servers run by `McpService`, with the schemas each entry is listed with.
+//! Resources can be read and pinned to the conversation shown in the center.
+
+use std::collections::HashMap;
+
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement,
+    ParentElement, Pixels, Render, StatefulInteractiveElement, Styled, Window, div,
+    prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputState},
+    notification::Notification,
+    v_flex,
+};
+use rust_i18n::t;
+
+use crate::app::{actions::PinContextItem, focus_mode::NotificationExt as _};
+use crate::{
+    AppState,
+    components::JsonTree,
+    core::services::{McpCatalog, McpEntry},
+    panels::dock_panel::DockPanel,
+    utils::context_item::ContextItem,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Tools,
+    Resources,
+    ResourceTemplates,
+    Prompts,
+}
+
+impl Section {
+    const ALL: [Section; 4] = [
+        Section::Tools,
+        Section::Resources,
+        Section::ResourceTemplates,
+        Section::Prompts,
+    ];
+
+    fn entries(self, catalog: &McpCatalog) -> &[McpEntry] {
+        match self {
+            Section::Tools => &catalog.tools,
+            Section::Resources => &catalog.resources,
+            Section::ResourceTemplates => &catalog.resource_templates,
+            Section::Prompts => &catalog.prompts,
+        }
+    }
+
+    fn id(self) -> &'static str {
+        match self {
+            Section::Tools => "mcp-tool",
+            Section::Resources => "mcp-resource",
+            Section::ResourceTemplates => "mcp-resource-template",
+            Section::Prompts => "mcp-prompt",
+        }
+    }
+
+    fn title(self) -> String {
+        match self {
+            Section::Tools => t!("mcp_browser_panel.tools"),
+            Section::Resources => t!("mcp_browser_panel.resources"),
+            Section::ResourceTemplates => t!("mcp_browser_panel.resource_templates"),
+            Section::Prompts => t!("mcp_browser_panel.prompts"),
+        }
+        .to_string()
+    }
+}
+
+/// The entry shown in the details pane
+struct Selection {
+    section: Section,
+    entry: McpEntry,
+    tree: Entity<JsonTree>,
+}
+
+pub struct McpBrowserPanel {
+    focus_handle: FocusHandle,
+    server: Option<String>,
+    /// Catalog of each server listed so far, or why listing failed
+    catalogs: HashMap<String, Result<McpCatalog, String>>,
+    /// Servers being listed
+    loading: Vec<String>,
+    selection: Option<Selection>,
+    /// URI of the resource to attach, filled in from a template
+    uri_input: Entity<InputState>,
+    attaching: bool,
+}
+
+impl DockPanel for McpBrowserPanel {
+    fn title() -> &'static str {
+        "MCP Browser"
+    }
+
+    fn title_key() -> Option<&'static str> {
+        Some("mcp_browser_panel.title")
+    }
+
+    fn description() -> &'static str {
+        "Tools, resources and prompts of MCP servers with their schemas"
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn paddings() -> Pixels {
+        px(12.)
+    }
+}
+
+impl McpBrowserPanel {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let uri_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(t!("mcp_browser_panel.uri").to_string())
+        });
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            server: None,
+            catalogs: HashMap::new(),
+            loading: Vec::new(),
+            selection: None,
+            uri_input,
+            attaching: false,
+        }
+    }
+
+    fn select_server(&mut self, server: String, cx: &mut Context<Self>) {
+        if self.server.as_ref() != Some(&server) {
+            self.server = Some(server);
+            self.selection = None;
+            cx.notify();
+        }
+    }
+
+    /// List the catalog of a server, starting it if needed
+    fn load(&mut self, server: String, cx: &mut Context<Self>) {
+        let Some(service) = AppState::global(cx).mcp_service().cloned() else {
+            return;
+        };
+        if self.loading.contains(&server) {
+            return;
+        }
+        self.loading.push(server.clone());
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let catalog = service.catalog(&server).await;
+            _ = this.update(cx, |this, cx| {
+                if let Err(e) = &catalog {
+                    log::warn!("Failed to list MCP server '{}': {:#}", server, e);
+                }
+                this.loading.retain(|name| name != &server);
+                this.catalogs
+                    .insert(server, catalog.map_err(|e| format!("{:#}", e)));
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn select_entry(
+        &mut self,
+        section: Section,
+        entry: McpEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if section == Section::ResourceTemplates {
+            let uri = entry.id.clone();
+            self.uri_input
+                .update(cx, |input, cx| input.set_value(uri, window, cx));
+        }
+        let raw = entry.raw.clone();
+        let tree = cx.new(|cx| JsonTree::new("mcp-browser-entry", raw, window, cx));
+        self.selection = Some(Selection {
+            section,
+            entry,
+            tree,
+        });
+        cx.notify();
+    }
+
+    /// Read a resource and pin it to the conversation shown in the center
+    fn attach(&mut self, uri: String, name: String, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(server), Some(service)) = (
+            self.server.clone(),
+            AppState::global(cx).mcp_service().cloned(),
+        ) else {
+            return;
+        };
+        if uri.trim().is_empty() {
+            return;
+        }
+        self.attaching = true;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, window| {
+            let resource = service.read_resource(&server, uri.trim()).await;
+            _ = this.update_in(window, |this, window, cx| {
+                this.attaching = false;
+                cx.notify();
+                match resource {
+                    Ok(resource) => {
+                        let item = ContextItem::McpResource {
+                            server,
+                            uri: uri.trim().to_string(),
+                            name,
+                            mime_type: resource.mime_type,
+                            text: resource.text,
+                        };
+                        window.dispatch_action(Box::new(PinContextItem { item }), cx);
+                    }
+                    Err(e) => {
+                        struct McpAttachFailed;
+                        let note = Notification::error(
+                            t!(
+                                "mcp_browser_panel.attach_failed",
+                                error = format!("{:#}", e)
+                            )
+                            .to_string(),
+                        )
+                        .id::<McpAttachFailed>();
+                        window.show_notification(note, cx);
+                    }
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn render_catalog(&self, catalog: &McpCatalog, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let mut list = v_flex()
+            .id("mcp-browser-entries")
+            .w(px(280.))
+            .flex_shrink_0()
+            .h_full()
+            .gap_0p5()
+            .overflow_y_scroll();
+
+        for section in Section::ALL {
+            let entries = section.entries(catalog);
+            list = list.child(
+                div()
+                    .pt_2()
+                    .text_xs()
+                    .font_semibold()
+                    .text_color(theme.muted_foreground)
+                    .child(format!("{} ({})", section.title(), entries.len())),
+            );
+            list = list.children(entries.iter().enumerate().map(|(ix, entry)| {
+                let selected = self.selection.as_ref().is_some_and(|selection| {
+                    selection.section == section && selection.entry.id == entry.id
+                });
+                let clicked = entry.clone();
+                div()
+                    .id((section.id(), ix))
+                    .px_2()
+                    .py_0p5()
+                    .rounded(px(4.))
+                    .cursor_pointer()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .text_sm()
+                    .text_color(theme.foreground)
+                    .when(selected, |this| this.bg(theme.accent))
+                    .hover(|this| this.bg(theme.muted))
+                    .child(entry.title.clone())
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.select_entry(section, clicked.clone(), window, cx)
+                    }))
+            }));
+        }
+
+        h_flex()
+            .flex_1()
+            .min_h_0()
+            .w_full()
+            .gap_3()
+            .items_start()
+            .child(list)
+            .child(match &self.selection {
+                Some(selection) => self.render_selection(selection, cx).into_any_element(),
+                None => div()
+                    .text_sm()
+                    .text_color(theme.muted_foreground)
+                    .child(t!("mcp_browser_panel.select_entry").to_string())
+                    .into_any_element(),
+            })
+    }
+
+    fn render_selection(&self, selection: &Selection, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let entry = &selection.entry;
+
+        v_flex()
+            .id("mcp-browser-selection")
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .gap_2()
+            .overflow_y_scroll()
+            .child(
+                div()
+                    .text_sm()
+                    .font_semibold()
+                    .text_color(theme.foreground)
+                    .child(entry.title.clone()),
+            )
+            .when(entry.title != entry.id, |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .font_family(theme.mono_font_family.clone())
+                        .text_color(theme.muted_foreground)
+                        .child(entry.id.clone()),
+                )
+            })
+            .when(!entry.description.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(theme.foreground)
+                        .child(entry.description.clone()),
+                )
+            })
+            .when(selection.section == Section::Resources, |this| {
+                let (uri, name) = (entry.id.clone(), entry.title.clone());
+                this.child(
+                    h_flex().child(
+                        Button::new("mcp-browser-attach")
+                            .icon(Icon::new(IconName::Plus))
+                            .label(t!("mcp_browser_panel.attach").to_string())
+                            .small()
+                            .loading(self.attaching)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.attach(uri.clone(), name.clone(), window, cx)
+                            })),
+                    ),
+                )
+            })
+            .when(selection.section == Section::ResourceTemplates, |this| {
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .child(div().flex_1().child(Input::new(&self.uri_input).small()))
+                        .child(
+                            Button::new("mcp-browser-attach-uri")
+                                .icon(Icon::new(IconName::Plus))
+                                .label(t!("mcp_browser_panel.attach").to_string())
+                                .small()
+                                .loading(self.attaching)
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    // Resources read from a template are named by their URI
+                                    let uri = this.uri_input.read(cx).value().trim().to_string();
+                                    this.attach(uri.clone(), uri, window, cx)
+                                })),
+                        ),
+                )
+            })
+            .child(selection.tree.clone())
+    }
+}
+
+impl Focusable for McpBrowserPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for McpBrowserPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let service = AppState::global(cx).mcp_service().cloned();
+        let servers = service
+            .as_ref()
+            .map(|service| service.server_names())
+            .unwrap_or_default();
+        let running: Vec<bool> = servers
+            .iter()
+            .map(|server| service.as_ref().is_some_and(|s| s.is_running(server)))
+            .collect();
+        if self
+            .server
+            .as_ref()
+            .is_none_or(|server| !servers.contains(server))
+        {
+            self.server = servers.first().cloned();
+            self.selection = None;
+        }
+        // Running servers are listed as soon as they are shown
+        if let Some(server) = self.server.clone()
+            && !self.catalogs.contains_key(&server)
+            && servers
+                .iter()
+                .zip(&running)
+                .any(|(name, running)| *running && name == &server)
+        {
+            self.load(server, cx);
+        }
+
+        let loading = self
+            .server
+            .as_ref()
+            .is_some_and(|server| self.loading.contains(server));
+        let catalog = self
+            .server
+            .as_ref()
+            .and_then(|server| self.catalogs.get(server));
+        let theme = cx.theme();
+        let content = match catalog {
+            Some(Ok(catalog)) => self.render_catalog(catalog, cx).into_any_element(),
+            Some(Err(error)) => div()
+                .text_sm()
+                .text_color(theme.red)
+                .child(error.clone())
+                .into_any_element(),
+            None => div()
+                .text_sm()
+                .text_color(theme.muted_foreground)
+                .when(self.server.is_some() && !loading, |this| {
+                    this.child(t!("mcp_browser_panel.not_running").to_string())
+                })
+                .into_any_element(),
+        };
+
+        v_flex()
+            .size_full()
+            .gap_3()
+            .child(
+                v_flex()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(theme.foreground)
+                            .child(t!("mcp_browser_panel.title").to_string()),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child(t!("mcp_browser_panel.description").to_string()),
+                    ),
+            )
+            .when(servers.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(theme.muted_foreground)
+                        .child(t!("mcp_console_panel.no_servers").to_string()),
+                )
+            })
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_1()
+                    .flex_wrap()
+                    .children(servers.iter().zip(running).enumerate().map(
+                        |(ix, (server, running))| {
+                            let name = server.clone();
+                            Button::new(("mcp-browser-server", ix))
+                                .label(server.clone())
+                                .when(running, |this| this.icon(Icon::new(IconName::CircleCheck)))
+                                .small()
+                                .selected(self.server.as_ref() == Some(server))
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    this.select_server(name.clone(), cx)
+                                }))
+                        },
+                    ))
+                    .when_some(self.server.clone(), |this, server| {
+                        this.child(
+                            Button::new("mcp-browser-refresh")
+                                .icon(Icon::new(IconName::Replace))
+                                .label(t!("mcp_browser_panel.refresh").to_string())
+                                .ghost()
+                                .small()
+                                .loading(loading)
+                                .tooltip(t!("mcp_browser_panel.refresh_tooltip").to_string())
+                                .on_click(
+                                    cx.listener(move |this, _, _, cx| {
+                                        this.load(server.clone(), cx)
+                                    }),
+                                ),
+                        )
+                    }),
+            )
+            .child(content)
+    }
+}
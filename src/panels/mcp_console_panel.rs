@@ -0,0 +1,331 @@
+//! MCP Console Panel - Call MCP server tools by hand
+//!
+//! Sends a tool call, or a `tools/list`, straight to a configured MCP server
+//! through `McpService`, bypassing agents, and shows the raw result. Meant
+//! for debugging MCP server configurations.
+
+use std::time::{Duration, Instant};
+
+use gpui::{
+    App, AppContext, ClipboardItem, Context, Entity, FocusHandle, Focusable, InteractiveElement,
+    IntoElement, ParentElement, Pixels, Render, StatefulInteractiveElement, Styled, Window, div,
+    prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Selectable, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputState},
+    v_flex,
+};
+use rust_i18n::t;
+use serde_json::{Value, json};
+
+use crate::{
+    AppState, components::JsonTree, core::services::parse_tool_arguments,
+    panels::dock_panel::DockPanel,
+};
+
+/// Outcome of the last request
+struct ConsoleResult {
+    /// Method and tool, e.g. `tools/call read_file`
+    request: String,
+    elapsed: Duration,
+    outcome: Result<(Value, Entity<JsonTree>), String>,
+}
+
+pub struct McpConsolePanel {
+    focus_handle: FocusHandle,
+    /// Server requests go to
+    server: Option<String>,
+    tool_input: Entity<InputState>,
+    arguments_input: Entity<InputState>,
+    /// A request is waiting for its response
+    running: bool,
+    result: Option<ConsoleResult>,
+}
+
+impl DockPanel for McpConsolePanel {
+    fn title() -> &'static str {
+        "MCP Console"
+    }
+
+    fn title_key() -> Option<&'static str> {
+        Some("mcp_console_panel.title")
+    }
+
+    fn description() -> &'static str {
+        "Call MCP server tools directly and inspect the raw results"
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn paddings() -> Pixels {
+        px(12.)
+    }
+}
+
+impl McpConsolePanel {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let tool_input = cx
+            .new(|cx| InputState::new(window, cx).placeholder(t!("mcp_console_panel.tool").to_string()));
+        let arguments_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .code_editor("json")
+                .multi_line(true)
+                .placeholder(t!("mcp_console_panel.arguments").to_string())
+        });
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            server: None,
+            tool_input,
+            arguments_input,
+            running: false,
+            result: None,
+        }
+    }
+
+    /// Call the typed tool, or list the server's tools
+    fn send(&mut self, list_tools: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(server) = self.server.clone() else {
+            return;
+        };
+        let Some(service) = AppState::global(cx).mcp_service().cloned() else {
+            return;
+        };
+
+        let (method, params, request) = if list_tools {
+            ("tools/list", json!({}), "tools/list".to_string())
+        } else {
+            let tool = self.tool_input.read(cx).value().trim().to_string();
+            let arguments = self.arguments_input.read(cx).value().to_string();
+            let arguments = match parse_tool_arguments(&arguments) {
+                Ok(arguments) if !tool.is_empty() => arguments,
+                Ok(_) => return self.show_error(t!("mcp_console_panel.no_tool").to_string(), cx),
+                Err(e) => return self.show_error(format!("{:#}", e), cx),
+            };
+            let request = format!("tools/call {}", tool);
+            (
+                "tools/call",
+                json!({ "name": tool, "arguments": arguments }),
+                request,
+            )
+        };
+
+        log::info!("MCP console: {} on '{}'", request, server);
+        self.running = true;
+        cx.notify();
+        let started = Instant::now();
+        cx.spawn_in(window, async move |this, window| {
+            let response = service.request(&server, method, params).await;
+            _ = this.update_in(window, |this, window, cx| {
+                let outcome = response
+                    .map(|value| {
+                        let tree = cx.new(|cx| {
+                            JsonTree::new("mcp-console-result", value.clone(), window, cx)
+                        });
+                        (value, tree)
+                    })
+                    .map_err(|e| format!("{:#}", e));
+                this.running = false;
+                this.result = Some(ConsoleResult {
+                    request,
+                    elapsed: started.elapsed(),
+                    outcome,
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn show_error(&mut self, error: String, cx: &mut Context<Self>) {
+        self.result = Some(ConsoleResult {
+            request: String::new(),
+            elapsed: Duration::ZERO,
+            outcome: Err(error),
+        });
+        cx.notify();
+    }
+
+    fn stop_server(&mut self, cx: &mut Context<Self>) {
+        if let (Some(server), Some(service)) =
+            (self.server.as_ref(), AppState::global(cx).mcp_service())
+        {
+            service.stop(server);
+        }
+        cx.notify();
+    }
+
+    fn render_result(&self, result: &ConsoleResult, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let summary = if result.request.is_empty() {
+            String::new()
+        } else {
+            t!(
+                "mcp_console_panel.summary",
+                request = result.request,
+                ms = result.elapsed.as_millis()
+            )
+            .to_string()
+        };
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child(summary),
+                    )
+                    .when_some(result.outcome.as_ref().ok(), |this, (value, _)| {
+                        let raw = serde_json::to_string_pretty(value).unwrap_or_default();
+                        this.child(
+                            Button::new("mcp-console-copy")
+                                .icon(Icon::new(IconName::Copy))
+                                .label(t!("mcp_console_panel.copy").to_string())
+                                .ghost()
+                                .xsmall()
+                                .on_click(move |_, _, cx| {
+                                    cx.write_to_clipboard(ClipboardItem::new_string(raw.clone()));
+                                }),
+                        )
+                    }),
+            )
+            .child(match &result.outcome {
+                Ok((_, tree)) => tree.clone().into_any_element(),
+                Err(error) => div()
+                    .text_sm()
+                    .text_color(theme.red)
+                    .child(error.clone())
+                    .into_any_element(),
+            })
+    }
+}
+
+impl Focusable for McpConsolePanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for McpConsolePanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let service = AppState::global(cx).mcp_service().cloned();
+        let servers = service
+            .as_ref()
+            .map(|service| service.server_names())
+            .unwrap_or_default();
+        if self
+            .server
+            .as_ref()
+            .is_none_or(|server| !servers.contains(server))
+        {
+            self.server = servers.first().cloned();
+        }
+        let running_server = match (&self.server, &service) {
+            (Some(server), Some(service)) => service.is_running(server),
+            _ => false,
+        };
+        let can_send = self.server.is_some() && !self.running;
+        let theme = cx.theme();
+
+        v_flex()
+            .id("mcp-console")
+            .size_full()
+            .gap_3()
+            .overflow_y_scroll()
+            .child(
+                v_flex()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(theme.foreground)
+                            .child(t!("mcp_console_panel.title").to_string()),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child(t!("mcp_console_panel.description").to_string()),
+                    ),
+            )
+            .when(servers.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(theme.muted_foreground)
+                        .child(t!("mcp_console_panel.no_servers").to_string()),
+                )
+            })
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_1()
+                    .flex_wrap()
+                    .children(servers.iter().enumerate().map(|(ix, server)| {
+                        let name = server.clone();
+                        Button::new(("mcp-console-server", ix))
+                            .label(server.clone())
+                            .small()
+                            .selected(self.server.as_ref() == Some(server))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.server = Some(name.clone());
+                                cx.notify();
+                            }))
+                    }))
+                    .when(running_server, |this| {
+                        this.child(
+                            Button::new("mcp-console-stop")
+                                .icon(Icon::new(IconName::CircleX))
+                                .label(t!("mcp_console_panel.stop").to_string())
+                                .ghost()
+                                .small()
+                                .tooltip(t!("mcp_console_panel.stop_tooltip").to_string())
+                                .on_click(cx.listener(|this, _, _, cx| this.stop_server(cx))),
+                        )
+                    }),
+            )
+            .child(Input::new(&self.tool_input).small())
+            .child(Input::new(&self.arguments_input).h(px(120.)))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("mcp-console-send")
+                            .label(t!("mcp_console_panel.send").to_string())
+                            .primary()
+                            .small()
+                            .loading(self.running)
+                            .disabled(!can_send)
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.send(false, window, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("mcp-console-list")
+                            .label(t!("mcp_console_panel.list_tools").to_string())
+                            .small()
+                            .disabled(!can_send)
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.send(true, window, cx)),
+                            ),
+                    ),
+            )
+            .children(
+                self.result
+                    .as_ref()
+                    .map(|result| self.render_result(result, cx)),
+            )
+    }
+}
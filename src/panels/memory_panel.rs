@@ -0,0 +1,280 @@
+//! Memory Panel - Facts remembered across the sessions of a workspace
+//!
+//! Lists the active workspace's memory, lets the user add entries (the same as
+//! typing `/remember` in a conversation) and forget them. Remembered facts are
+//! prepended to the first prompt of every new session in the workspace.
+
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, Pixels,
+    Render, Styled, Subscription, Window, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    notification::Notification,
+    v_flex,
+};
+use rust_i18n::t;
+
+use crate::app::focus_mode::NotificationExt as _;
+use crate::{
+    AppState, core::event_bus::WorkspaceUpdateEvent, panels::dock_panel::DockPanel,
+    schemas::workspace_schema::Workspace,
+};
+
+pub struct MemoryPanel {
+    focus_handle: FocusHandle,
+    input_state: Entity<InputState>,
+    /// Active workspace and its memory
+    workspace: Option<Workspace>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DockPanel for MemoryPanel {
+    fn title() -> &'static str {
+        "Memory"
+    }
+
+    fn title_key() -> Option<&'static str> {
+        Some("memory_panel.title")
+    }
+
+    fn description() -> &'static str {
+        "Facts remembered across the sessions of a workspace"
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        Self::view(window, cx)
+    }
+
+    fn paddings() -> Pixels {
+        px(12.)
+    }
+}
+
+impl MemoryPanel {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        let entity = cx.new(|cx| Self::new(window, cx));
+        Self::subscribe_to_workspace_updates(&entity, cx);
+        entity.update(cx, |this, cx| this.refresh(cx));
+        entity
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let input_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(t!("memory_panel.placeholder").to_string())
+        });
+        let input_subscription = cx.subscribe_in(
+            &input_state,
+            window,
+            |this, _input, event: &InputEvent, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.remember(window, cx);
+                }
+            },
+        );
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            input_state,
+            workspace: None,
+            _subscriptions: vec![input_subscription],
+        }
+    }
+
+    /// Reload the active workspace's memory
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            log::warn!("[MemoryPanel] WorkspaceService not initialized");
+            return;
+        };
+        cx.spawn(async move |this, cx| {
+            let workspace = workspace_service.get_active_workspace().await;
+            _ = this.update(cx, |this, cx| {
+                this.workspace = workspace;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn subscribe_to_workspace_updates(entity: &Entity<Self>, cx: &mut App) {
+        let workspace_bus = AppState::global(cx).workspace_bus.clone();
+        let weak_entity = entity.downgrade();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        workspace_bus.subscribe(move |event| {
+            if matches!(
+                event,
+                WorkspaceUpdateEvent::MemoryUpdated { .. }
+                    | WorkspaceUpdateEvent::WorkspaceAdded { .. }
+                    | WorkspaceUpdateEvent::WorkspaceRemoved { .. }
+                    | WorkspaceUpdateEvent::TaskCreated { .. }
+            ) {
+                let _ = tx.send(());
+            }
+        });
+
+        cx.spawn(async move |cx| {
+            while rx.recv().await.is_some() {
+                let Some(entity) = weak_entity.upgrade() else {
+                    break;
+                };
+                _ = cx.update(|cx| entity.update(cx, |this, cx| this.refresh(cx)));
+            }
+        })
+        .detach();
+    }
+
+    /// Remember the text in the input for the active workspace
+    fn remember(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let content = self.input_state.read(cx).value().trim().to_string();
+        let Some(workspace_id) = self.workspace.as_ref().map(|w| w.id.clone()) else {
+            return;
+        };
+        if content.is_empty() {
+            return;
+        }
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            return;
+        };
+        self.input_state.update(cx, |state, cx| state.set_value("", window, cx));
+
+        cx.spawn_in(window, async move |_this, window| {
+            if let Err(e) = workspace_service.add_memory(&workspace_id, &content).await {
+                log::error!("[MemoryPanel] Failed to remember: {}", e);
+                _ = window.update(|window, cx| {
+                    struct RememberError;
+                    let note = Notification::error(e.to_string()).id::<RememberError>();
+                    window.show_notification(note, cx);
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn forget(&mut self, memory_id: String, cx: &mut Context<Self>) {
+        let (Some(workspace_id), Some(workspace_service)) = (
+            self.workspace.as_ref().map(|w| w.id.clone()),
+            AppState::global(cx).workspace_service().cloned(),
+        ) else {
+            return;
+        };
+        cx.spawn(async move |_this, _cx| {
+            if let Err(e) = workspace_service
+                .remove_memory(&workspace_id, &memory_id)
+                .await
+            {
+                log::error!("[MemoryPanel] Failed to forget memory: {}", e);
+            }
+        })
+        .detach();
+    }
+}
+
+impl Focusable for MemoryPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for MemoryPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        let Some(workspace) = self.workspace.as_ref() else {
+            return v_flex()
+                .size_full()
+                .items_center()
+                .justify_center()
+                .text_sm()
+                .text_color(theme.muted_foreground)
+                .child(t!("memory_panel.no_workspace").to_string());
+        };
+
+        let rows = workspace.memories.iter().enumerate().map(|(idx, memory)| {
+            let memory_id = memory.id.clone();
+            h_flex()
+                .w_full()
+                .gap_2()
+                .items_center()
+                .py_1()
+                .px_2()
+                .rounded(px(6.))
+                .bg(theme.secondary)
+                .child(
+                    div()
+                        .flex_1()
+                        .text_sm()
+                        .text_color(theme.foreground)
+                        .child(memory.content.clone()),
+                )
+                .child(
+                    Button::new(("memory-forget", idx))
+                        .icon(Icon::new(IconName::Close))
+                        .ghost()
+                        .xsmall()
+                        .tooltip(t!("memory_panel.forget").to_string())
+                        .on_click(cx.listener(move |this, _, _window, cx| {
+                            this.forget(memory_id.clone(), cx);
+                        })),
+                )
+        });
+
+        v_flex()
+            .size_full()
+            .gap_3()
+            .child(
+                h_flex()
+                    .w_full()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        v_flex()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(theme.foreground)
+                                    .child(workspace.name.clone()),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.muted_foreground)
+                                    .child(t!("memory_panel.description").to_string()),
+                            ),
+                    )
+                    .child(
+                        Button::new("memory-refresh")
+                            .icon(Icon::new(IconName::LoaderCircle))
+                            .ghost()
+                            .small()
+                            .on_click(cx.listener(|this, _, _window, cx| this.refresh(cx))),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(div().flex_1().child(Input::new(&self.input_state).small()))
+                    .child(
+                        Button::new("memory-remember")
+                            .label(t!("memory_panel.remember").to_string())
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| this.remember(window, cx))),
+                    ),
+            )
+            .when(workspace.memories.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(theme.muted_foreground)
+                        .child(t!("memory_panel.empty").to_string()),
+                )
+            })
+            .child(v_flex().w_full().gap_1().children(rows))
+    }
+}
@@ -1,8 +1,16 @@
 // Panel-related modules
 
+mod audit_panel;
 pub mod code_editor;
 pub mod conversation;
+mod dashboard_panel;
 pub mod dock_panel;
+mod feedback_panel;
+mod git_panel;
+mod mcp_browser_panel;
+mod mcp_console_panel;
+mod memory_panel;
+mod search_panel;
 mod session_manager;
 mod settings_panel;
 mod task_panel;
@@ -11,11 +19,19 @@ mod tool_call_detail_panel;
 mod welcome_panel;
 
 // Re-export panel types
+pub use audit_panel::AuditPanel;
 pub use code_editor::CodeEditorPanel;
 pub use conversation::ConversationPanel;
+pub use dashboard_panel::DashboardPanel;
 pub use dock_panel::{DockPanel, DockPanelContainer, DockPanelState};
+pub use feedback_panel::FeedbackPanel;
+pub use git_panel::GitPanel;
+pub use mcp_browser_panel::McpBrowserPanel;
+pub use mcp_console_panel::McpConsolePanel;
+pub use memory_panel::MemoryPanel;
+pub use search_panel::SearchPanel;
 pub use session_manager::SessionManagerPanel;
-pub use settings_panel::{AppSettings, SettingsPanel};
+pub use settings_panel::{AppSettings, SettingsPanel, estimate_tokens};
 pub use task_panel::TaskPanel;
 pub use terminal_panel::TerminalPanel;
 pub use tool_call_detail_panel::ToolCallDetailPanel;
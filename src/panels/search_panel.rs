@@ -0,0 +1,423 @@
+//! Search Panel - Find messages across the history of every session
+//!
+//! Searches the messages, thoughts, tool calls and plans of all sessions with
+//! `SearchService` as the user types, filtered by agent and by how recent
+//! they are. Clicking a hit opens its session's conversation.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement,
+    ParentElement, Pixels, Render, StatefulInteractiveElement, Styled, Subscription, Task, Window,
+    div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonGroup, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    menu::{DropdownMenu as _, PopupMenuItem},
+    v_flex,
+};
+use rust_i18n::t;
+use smol::Timer;
+
+use crate::{
+    AppState, PanelAction,
+    core::services::{SearchHit, SearchHitKind, SearchQuery},
+    panels::dock_panel::DockPanel,
+};
+
+/// Time to wait after a keystroke before searching
+const DEBOUNCE: Duration = Duration::from_millis(250);
+/// Hits fetched at most
+const MAX_HITS: usize = 200;
+
+/// How far back hits may be
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DateRange {
+    Any,
+    Today,
+    Week,
+    Month,
+}
+
+impl DateRange {
+    /// Earliest time of a hit, as of `now`
+    fn since(self, now: DateTime<Local>) -> Option<DateTime<Utc>> {
+        let since = match self {
+            Self::Any => return None,
+            Self::Today => now
+                .date_naive()
+                .and_hms_opt(0, 0, 0)?
+                .and_local_timezone(Local)
+                .earliest()?,
+            Self::Week => now - chrono::Duration::days(7),
+            Self::Month => now - chrono::Duration::days(30),
+        };
+        Some(since.with_timezone(&Utc))
+    }
+}
+
+pub struct SearchPanel {
+    focus_handle: FocusHandle,
+    search_state: Entity<InputState>,
+    range: DateRange,
+    /// Only hits in sessions of this agent
+    agent: Option<String>,
+    /// Agents of the searchable tasks
+    agents: Vec<String>,
+    hits: Vec<SearchHit>,
+    searching: bool,
+    error: Option<String>,
+    _search_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DockPanel for SearchPanel {
+    fn title() -> &'static str {
+        "Search"
+    }
+
+    fn title_key() -> Option<&'static str> {
+        Some("search_panel.title")
+    }
+
+    fn description() -> &'static str {
+        "Find messages, thoughts and tool calls across all sessions"
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn paddings() -> Pixels {
+        px(12.)
+    }
+}
+
+impl SearchPanel {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(t!("search_panel.placeholder").to_string())
+        });
+        let search_subscription = cx.subscribe(
+            &search_state,
+            |this, _input, event: &InputEvent, cx| match event {
+                InputEvent::Change => this.search(DEBOUNCE, cx),
+                InputEvent::PressEnter { .. } => this.search(Duration::ZERO, cx),
+                _ => {}
+            },
+        );
+
+        // Agents for the filter, before the first search
+        let search_service = AppState::global(cx).search_service().cloned();
+        let agents_task = cx.spawn(async move |this, cx| {
+            let Some(search_service) = search_service else {
+                return;
+            };
+            let agents = search_service.agent_names().await;
+            _ = this.update(cx, |this, cx| {
+                this.agents = agents;
+                cx.notify();
+            });
+        });
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            search_state,
+            range: DateRange::Any,
+            agent: None,
+            agents: Vec::new(),
+            hits: Vec::new(),
+            searching: false,
+            error: None,
+            _search_task: agents_task,
+            _subscriptions: vec![search_subscription],
+        }
+    }
+
+    /// Search for the input's text after `delay`, replacing a pending search
+    fn search(&mut self, delay: Duration, cx: &mut Context<Self>) {
+        let Some(search_service) = AppState::global(cx).search_service().cloned() else {
+            return;
+        };
+        let mut query = SearchQuery::parse(&self.search_state.read(cx).value());
+        if query.agent_name.is_none() {
+            query.agent_name = self.agent.clone();
+        }
+        query.since = self.range.since(Local::now());
+        if query.terms.is_empty() {
+            self._search_task = Task::ready(());
+            self.hits.clear();
+            self.searching = false;
+            self.error = None;
+            cx.notify();
+            return;
+        }
+
+        self.searching = true;
+        cx.notify();
+        self._search_task = cx.spawn(async move |this, cx| {
+            Timer::after(delay).await;
+            let result = search_service.search(&query, MAX_HITS).await;
+            let agents = search_service.agent_names().await;
+            _ = this.update(cx, |this, cx| {
+                this.searching = false;
+                this.agents = agents;
+                match result {
+                    Ok(hits) => {
+                        this.hits = hits;
+                        this.error = None;
+                    }
+                    Err(e) => {
+                        log::error!("Failed to search sessions: {}", e);
+                        this.hits.clear();
+                        this.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            });
+        });
+    }
+
+    fn set_range(&mut self, range: DateRange, cx: &mut Context<Self>) {
+        self.range = range;
+        self.search(Duration::ZERO, cx);
+    }
+
+    fn set_agent(&mut self, agent: Option<String>, cx: &mut Context<Self>) {
+        self.agent = agent;
+        self.search(Duration::ZERO, cx);
+    }
+
+    fn agent_button(&self, cx: &mut Context<Self>) -> Button {
+        let label = self
+            .agent
+            .as_deref()
+            .map(|agent| AppState::global(cx).agent_label(agent))
+            .unwrap_or_else(|| t!("search_panel.all_agents").to_string());
+        let agents = self.agents.clone();
+        let panel = cx.entity().downgrade();
+
+        Button::new("search-agent")
+            .label(label)
+            .icon(IconName::ChevronDown)
+            .small()
+            .ghost()
+            .dropdown_menu(move |mut menu, _, cx| {
+                let all = panel.clone();
+                menu = menu.item(
+                    PopupMenuItem::new(t!("search_panel.all_agents").to_string()).on_click(
+                        move |_, _, cx| {
+                            _ = all.update(cx, |this, cx| this.set_agent(None, cx));
+                        },
+                    ),
+                );
+                for agent in &agents {
+                    let panel = panel.clone();
+                    let name = agent.clone();
+                    menu = menu.item(
+                        PopupMenuItem::new(AppState::global(cx).agent_label(agent)).on_click(
+                            move |_, _, cx| {
+                                let name = Some(name.clone());
+                                _ = panel.update(cx, |this, cx| this.set_agent(name, cx));
+                            },
+                        ),
+                    );
+                }
+                menu
+            })
+    }
+
+    fn render_hit(ix: usize, hit: &SearchHit, cx: &App) -> impl IntoElement {
+        let theme = cx.theme();
+        let icon = match hit.kind {
+            SearchHitKind::User => IconName::User,
+            SearchHitKind::Agent => IconName::Bot,
+            SearchHitKind::Thought => IconName::Asterisk,
+            SearchHitKind::ToolCall => IconName::SquareTerminal,
+            SearchHitKind::Plan => IconName::CircleCheck,
+        };
+        let time = hit
+            .timestamp
+            .map(|time| {
+                time.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let session: String = hit.session_id.chars().take(8).collect();
+        let source = match (&hit.info.task_name, &hit.info.agent_name) {
+            (Some(task), Some(agent)) => {
+                format!("{} · {}", task, AppState::global(cx).agent_label(agent))
+            }
+            (Some(task), None) => task.clone(),
+            _ => session,
+        };
+        let session_id = hit.session_id.clone();
+
+        h_flex()
+            .id(("search-hit", ix))
+            .w_full()
+            .gap_2()
+            .items_center()
+            .py_1()
+            .px_2()
+            .rounded(px(6.))
+            .cursor_pointer()
+            .when(ix % 2 == 0, |this| this.bg(theme.secondary))
+            .hover(|this| this.bg(theme.accent.opacity(0.3)))
+            .child(
+                div()
+                    .w(px(120.))
+                    .flex_shrink_0()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .child(time),
+            )
+            .child(Icon::new(icon).xsmall().text_color(theme.muted_foreground))
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .text_sm()
+                    .text_color(theme.foreground)
+                    .child(hit.snippet.clone()),
+            )
+            .child(
+                div()
+                    .w(px(200.))
+                    .flex_shrink_0()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .child(source),
+            )
+            .on_click(move |_, window, cx| {
+                window.dispatch_action(
+                    Box::new(PanelAction::show_conversation(Some(session_id.clone()))),
+                    cx,
+                );
+            })
+    }
+}
+
+impl Focusable for SearchPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for SearchPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let range = self.range;
+        let has_terms = !SearchQuery::parse(&self.search_state.read(cx).value())
+            .terms
+            .is_empty();
+        let status = if let Some(error) = &self.error {
+            Some(t!("search_panel.failed", error = error).to_string())
+        } else if self.searching {
+            Some(t!("search_panel.searching").to_string())
+        } else if !has_terms {
+            Some(t!("search_panel.hint").to_string())
+        } else if self.hits.is_empty() {
+            Some(t!("search_panel.empty").to_string())
+        } else if self.hits.len() >= MAX_HITS {
+            Some(t!("search_panel.truncated", count = MAX_HITS).to_string())
+        } else {
+            None
+        };
+
+        let range_button = |id: &'static str, label: String, value: DateRange| {
+            Button::new(id)
+                .label(label)
+                .ghost()
+                .xsmall()
+                .selected(range == value)
+                .on_click(cx.listener(move |this, _, _, cx| this.set_range(value, cx)))
+        };
+        let range_buttons = ButtonGroup::new("search-range")
+            .small()
+            .child(range_button(
+                "search-any-time",
+                t!("search_panel.any_time").to_string(),
+                DateRange::Any,
+            ))
+            .child(range_button(
+                "search-today",
+                t!("search_panel.today").to_string(),
+                DateRange::Today,
+            ))
+            .child(range_button(
+                "search-week",
+                t!("search_panel.week").to_string(),
+                DateRange::Week,
+            ))
+            .child(range_button(
+                "search-month",
+                t!("search_panel.month").to_string(),
+                DateRange::Month,
+            ));
+        let agent_button = self.agent_button(cx);
+        let hits: Vec<_> = self
+            .hits
+            .iter()
+            .enumerate()
+            .map(|(ix, hit)| Self::render_hit(ix, hit, cx).into_any_element())
+            .collect();
+        let theme = cx.theme();
+
+        v_flex()
+            .size_full()
+            .gap_3()
+            .child(
+                v_flex()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(theme.foreground)
+                            .child(t!("search_panel.title").to_string()),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child(t!("search_panel.description").to_string()),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(range_buttons)
+                    .child(agent_button)
+                    .child(div().flex_1().child(Input::new(&self.search_state).small())),
+            )
+            .when_some(status, |this, status| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(theme.muted_foreground)
+                        .child(status),
+                )
+            })
+            .child(
+                v_flex()
+                    .id("search-hits")
+                    .flex_1()
+                    .min_h_0()
+                    .w_full()
+                    .gap_0p5()
+                    .overflow_y_scroll()
+                    .children(hits),
+            )
+    }
+}
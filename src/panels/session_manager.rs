@@ -1,24 +1,39 @@
+use chrono::Duration;
 use gpui::{
-    App, AppContext, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, Pixels,
-    Render, Styled, Window, prelude::FluentBuilder, px,
+    AnyElement, App, AppContext, ClipboardItem, Context, Entity, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Pixels, Render, StatefulInteractiveElement,
+    Styled, Window, prelude::FluentBuilder, px, relative,
 };
 
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
     button::{Button, ButtonVariants},
-    h_flex, v_flex,
+    h_flex,
+    menu::ContextMenuExt,
+    tooltip::Tooltip,
+    v_flex,
 };
+use rust_i18n::t;
 
 use crate::{
     AppState,
+    components::{AgentAvatar, ContextActions},
     core::services::{AgentSessionInfo, SessionStatus},
     panels::dock_panel::DockPanel,
+    utils::{
+        session_timeline::{
+            IDLE_GAP_SECS, TimelineEvent, TimelineKind, axis_position, session_timeline,
+        },
+        time::format_duration_short,
+    },
 };
 
 /// Session Manager Panel - Displays and manages all agent sessions
 pub struct SessionManagerPanel {
     focus_handle: FocusHandle,
     sessions_by_agent: Vec<(String, Vec<AgentSessionInfo>)>,
+    /// The session whose timeline is shown, with its events
+    timeline: Option<(String, Vec<TimelineEvent>)>,
 }
 
 impl DockPanel for SessionManagerPanel {
@@ -52,6 +67,7 @@ impl SessionManagerPanel {
         let mut panel = Self {
             focus_handle: cx.focus_handle(),
             sessions_by_agent: Vec::new(),
+            timeline: None,
         };
 
         // Load initial session data
@@ -193,6 +209,155 @@ impl SessionManagerPanel {
         .detach();
     }
 
+    /// Cancel a session's turn, if one runs, and forget the session
+    fn kill_session(&mut self, session_id: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(agent_service) = AppState::global(cx).agent_service().cloned() else {
+            log::error!("[SessionManagerPanel] AgentService not initialized");
+            return;
+        };
+
+        cx.spawn_in(window, async move |this, window| {
+            if let Err(e) = agent_service.remove_session(&session_id).await {
+                log::error!("[SessionManagerPanel] Failed to kill session: {}", e);
+            }
+            _ = this.update_in(window, |this, _, cx| this.refresh_sessions(cx));
+        })
+        .detach();
+    }
+
+    /// Close every session that is not running a turn
+    fn close_idle_sessions(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(agent_service) = AppState::global(cx).agent_service().cloned() else {
+            log::error!("[SessionManagerPanel] AgentService not initialized");
+            return;
+        };
+
+        cx.spawn_in(window, async move |this, window| {
+            let closed = agent_service.close_idle_sessions().await;
+            log::info!(
+                "[SessionManagerPanel] Closed {} idle sessions",
+                closed.len()
+            );
+            _ = this.update_in(window, |this, _, cx| this.refresh_sessions(cx));
+        })
+        .detach();
+    }
+
+    /// Show the timeline of a session, or hide it when it is shown
+    fn toggle_timeline(&mut self, session: &AgentSessionInfo, cx: &mut Context<Self>) {
+        if self
+            .timeline
+            .as_ref()
+            .is_some_and(|(session_id, _)| *session_id == session.session_id)
+        {
+            self.timeline = None;
+            cx.notify();
+            return;
+        }
+        let Some(persistence_service) = AppState::global(cx).persistence_service().cloned() else {
+            log::error!("[SessionManagerPanel] PersistenceService not initialized");
+            return;
+        };
+
+        let (session_id, created_at) = (session.session_id.clone(), session.created_at);
+        cx.spawn(async move |this, cx| {
+            let messages = persistence_service
+                .load_messages(&session_id)
+                .await
+                .unwrap_or_else(|e| {
+                    log::warn!("[SessionManagerPanel] Failed to load history: {}", e);
+                    Vec::new()
+                });
+            let events = session_timeline(created_at, &messages, Duration::seconds(IDLE_GAP_SECS));
+            _ = this.update(cx, |this, cx| {
+                this.timeline = Some((session_id, events));
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn timeline_button(&self, session: &AgentSessionInfo, id: usize, cx: &Context<Self>) -> Button {
+        let session = session.clone();
+        Button::new(("timeline", id))
+            .label(t!("session_manager.timeline").to_string())
+            .ghost()
+            .small()
+            .tooltip(t!("session_manager.timeline_tooltip").to_string())
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.toggle_timeline(&session, cx);
+            }))
+    }
+
+    /// Events of the session on a time axis, with their details on hover
+    fn render_timeline(&self, session_id: &str, cx: &Context<Self>) -> Option<AnyElement> {
+        let (_, events) = self.timeline.as_ref().filter(|(id, _)| id == session_id)?;
+        let theme = cx.theme();
+        let start = events.first()?.at;
+        let end = events.iter().map(TimelineEvent::end).max()?;
+
+        let markers = events.iter().enumerate().map(|(idx, event)| {
+            let left = axis_position(event.at, start, end);
+            let (color, label) = match event.kind {
+                TimelineKind::Created => (theme.success, t!("session_manager.timeline.created")),
+                TimelineKind::Prompt => (theme.info, t!("session_manager.timeline.prompt")),
+                TimelineKind::ToolCall => (theme.warning, t!("session_manager.timeline.tool_call")),
+                TimelineKind::Error => (theme.danger, t!("session_manager.timeline.error")),
+                TimelineKind::Idle => (
+                    theme.muted_foreground,
+                    t!(
+                        "session_manager.timeline.idle",
+                        duration = format_duration_short(event.duration.num_milliseconds() as u64)
+                    ),
+                ),
+            };
+            let mut details = format!("{} {}", event.at.format("%H:%M:%S"), label);
+            if !event.detail.is_empty() {
+                details.push_str(&format!(": {}", event.detail));
+            }
+
+            let marker = gpui::div()
+                .id(("timeline-event", idx))
+                .absolute()
+                .top_0()
+                .bottom_0()
+                .left(relative(left))
+                .tooltip(move |window, cx| Tooltip::new(details.clone()).build(window, cx));
+            if event.kind == TimelineKind::Idle {
+                let width = axis_position(event.end(), start, end) - left;
+                marker.w(relative(width)).bg(color.opacity(0.3))
+            } else {
+                marker.w(px(3.)).rounded(px(1.)).bg(color)
+            }
+        });
+
+        Some(
+            v_flex()
+                .w_full()
+                .gap_1()
+                .px_2()
+                .child(
+                    gpui::div()
+                        .relative()
+                        .w_full()
+                        .h(px(14.))
+                        .rounded(px(4.))
+                        .bg(theme.secondary)
+                        .children(markers),
+                )
+                .child(
+                    h_flex()
+                        .w_full()
+                        .justify_between()
+                        .text_xs()
+                        .text_color(theme.muted_foreground)
+                        .child(start.format("%H:%M:%S").to_string())
+                        .child(end.format("%H:%M:%S").to_string()),
+                )
+                .into_any_element(),
+        )
+    }
+
     /// Open a conversation panel for the given session
     fn open_session(&self, session_id: String, window: &mut Window, cx: &mut Context<Self>) {
         // Dispatch PanelAction to open the conversation panel
@@ -205,6 +370,50 @@ impl SessionManagerPanel {
         );
     }
 
+    /// Right-click menu of a session card
+    fn session_actions(
+        &self,
+        session: &AgentSessionInfo,
+        agent_name: String,
+        session_id: String,
+        cx: &Context<Self>,
+    ) -> ContextActions {
+        let entity = cx.entity();
+        ContextActions::new()
+            .action(t!("session_manager.open").to_string(), IconName::ArrowRight, {
+                let (entity, session_id) = (entity.clone(), session_id.clone());
+                move |window, cx| {
+                    entity.update(cx, |this, cx| this.open_session(session_id.clone(), window, cx));
+                }
+            })
+            .action(t!("session_manager.copy_id").to_string(), IconName::Copy, {
+                let session_id = session_id.clone();
+                move |_, cx| cx.write_to_clipboard(ClipboardItem::new_string(session_id.clone()))
+            })
+            .separator()
+            .when(session.status != SessionStatus::Closed, |actions| {
+                let (entity, session_id) = (entity.clone(), session_id.clone());
+                actions.action(
+                    t!("session_manager.close").to_string(),
+                    IconName::Close,
+                    move |window, cx| {
+                        entity.update(cx, |this, cx| {
+                            this.close_session(agent_name.clone(), session_id.clone(), window, cx);
+                        });
+                    },
+                )
+            })
+            .action(
+                t!("session_manager.kill").to_string(),
+                IconName::CircleX,
+                move |window, cx| {
+                    entity.update(cx, |this, cx| {
+                        this.kill_session(session_id.clone(), window, cx);
+                    });
+                },
+            )
+    }
+
     /// Get status badge color
     fn status_color(&self, status: &SessionStatus, cx: &App) -> gpui::Hsla {
         let theme = cx.theme();
@@ -261,13 +470,27 @@ impl Render for SessionManagerPanel {
                             .child("Sessions"),
                     )
                     .child(
-                        Button::new("refresh")
-                            .icon(Icon::new(IconName::LoaderCircle))
-                            .ghost()
-                            .small()
-                            .on_click(cx.listener(|this, _, _window, cx| {
-                                this.refresh_sessions(cx);
-                            })),
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("close-idle")
+                                    .label(t!("session_manager.close_idle").to_string())
+                                    .ghost()
+                                    .small()
+                                    .tooltip(t!("session_manager.close_idle_tooltip").to_string())
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.close_idle_sessions(window, cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("refresh")
+                                    .icon(Icon::new(IconName::LoaderCircle))
+                                    .ghost()
+                                    .small()
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.refresh_sessions(cx);
+                                    })),
+                            ),
                     ),
             )
             .child(
@@ -278,6 +501,7 @@ impl Render for SessionManagerPanel {
                             .gap_4()
                             .children(self.sessions_by_agent.iter().enumerate().map(|(agent_idx, (agent_name, sessions))| {
                                 let agent_name_clone = agent_name.clone();
+                                let agent_label = AppState::global(cx).agent_label(agent_name);
 
                                 v_flex()
                                     .w_full()
@@ -294,11 +518,14 @@ impl Render for SessionManagerPanel {
                                             .items_center()
                                             .justify_between()
                                             .child(
-                                                gpui::div()
+                                                h_flex()
+                                                    .gap_2()
+                                                    .items_center()
                                                     .text_sm()
                                                     .font_weight(gpui::FontWeight::SEMIBOLD)
                                                     .text_color(theme.foreground)
-                                                    .child(format!("{} ({} sessions)", agent_name, sessions.len())),
+                                                    .child(AgentAvatar::new(agent_name.clone(), cx))
+                                                    .child(format!("{} ({} sessions)", agent_label, sessions.len())),
                                             )
                                             .child(
                                                 Button::new(("new-session", agent_idx))
@@ -333,7 +560,8 @@ impl Render for SessionManagerPanel {
                                                 // Create unique button ID from indices
                                                 let btn_id = agent_idx * 1000 + session_idx;
 
-                                                h_flex()
+                                                let card = h_flex()
+                                                    .id(("session-card", btn_id))
                                                     .w_full()
                                                     .items_center()
                                                     .justify_between()
@@ -378,6 +606,9 @@ impl Render for SessionManagerPanel {
                                                     .child(
                                                         h_flex()
                                                             .gap_1()
+                                                            .child(
+                                                                self.timeline_button(session, btn_id, cx),
+                                                            )
                                                             .child(
                                                                 Button::new(("open", btn_id))
                                                                     .label("Open")
@@ -388,21 +619,47 @@ impl Render for SessionManagerPanel {
                                                                     })),
                                                             )
                                                             .when(session.status != SessionStatus::Closed, |this| {
+                                                                let agent_name = agent_name_for_close.clone();
+                                                                let session_id = session_id_for_close.clone();
                                                                 this.child(
                                                                     Button::new(("close", btn_id))
-                                                                        .label("Close")
+                                                                        .icon(Icon::new(IconName::Close))
                                                                         .ghost()
                                                                         .small()
-                                                                        .on_click({
-                                                                            let agent_name = agent_name_for_close.clone();
-                                                                            let session_id = session_id_for_close.clone();
-                                                                            cx.listener(move |this, _, window, cx| {
-                                                                                this.close_session(agent_name.clone(), session_id.clone(), window, cx);
-                                                                            })
-                                                                        }),
+                                                                        .tooltip(t!("session_manager.close").to_string())
+                                                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                                                            this.close_session(agent_name.clone(), session_id.clone(), window, cx);
+                                                                        })),
                                                                 )
+                                                            })
+                                                            .child({
+                                                                let session_id = session_id_for_close.clone();
+                                                                Button::new(("kill", btn_id))
+                                                                    .icon(Icon::new(IconName::CircleX))
+                                                                    .ghost()
+                                                                    .small()
+                                                                    .tooltip(t!("session_manager.kill_tooltip").to_string())
+                                                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                                                        this.kill_session(session_id.clone(), window, cx);
+                                                                    }))
                                                             }),
                                                     )
+                                                    // Close and the other actions
+                                                    .context_menu(
+                                                        self.session_actions(
+                                                            session,
+                                                            agent_name_for_close,
+                                                            session_id_for_close,
+                                                            cx,
+                                                        )
+                                                        .into_builder(),
+                                                    );
+
+                                                v_flex()
+                                                    .w_full()
+                                                    .gap_1()
+                                                    .child(card)
+                                                    .children(self.render_timeline(&session_id, cx))
                                             })),
                                     )
                             })),
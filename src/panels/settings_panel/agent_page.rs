@@ -1,11 +1,14 @@
-use gpui::{AppContext as _, Context, Entity, ParentElement as _, Styled, Window, px};
+use gpui::{
+    App, AppContext as _, Context, Entity, IntoElement, ParentElement as _, Styled, Window, px,
+};
 use gpui_component::{
-    ActiveTheme, IconName, Sizable, WindowExt as _,
+    ActiveTheme, Disableable as _, IconName, Sizable, WindowExt as _,
     button::Button,
     dialog::DialogButtonProps,
     h_flex,
     input::{Input, InputState},
     label::Label,
+    notification::Notification,
     setting::{SettingField, SettingGroup, SettingItem, SettingPage},
     v_flex,
 };
@@ -13,11 +16,13 @@ use rust_i18n::t;
 use std::collections::HashMap;
 
 use super::panel::SettingsPanel;
+use crate::app::focus_mode::NotificationExt as _;
 use crate::{
-    AppState,
+    AppSettings, AppState,
     app::actions::{
         AddAgent, ChangeConfigPath, ReloadAgentConfig, RemoveAgent, RestartAgent, UpdateAgent,
     },
+    core::agent::AgentDiagnosis,
 };
 
 impl SettingsPanel {
@@ -134,6 +139,8 @@ impl SettingsPanel {
                         let view = view.clone();
                         move |_options, _window, cx| {
                             let agent_configs = view.read(cx).cached_agents.clone();
+                            let doctor_results = view.read(cx).doctor_results.clone();
+                            let doctor_running = view.read(cx).doctor_running;
 
                             let mut content = v_flex()
                                 .w_full()
@@ -143,6 +150,28 @@ impl SettingsPanel {
                                     h_flex()
                                         .w_full()
                                         .justify_end()
+                                        .gap_2()
+                                        .child(
+                                            Button::new("agent-doctor-btn")
+                                                .label(if doctor_running {
+                                                    t!("settings.agents.doctor.running").to_string()
+                                                } else {
+                                                    t!("settings.agents.doctor.run").to_string()
+                                                })
+                                                .icon(IconName::CircleCheck)
+                                                .outline()
+                                                .small()
+                                                .loading(doctor_running)
+                                                .disabled(doctor_running || agent_configs.is_empty())
+                                                .on_click({
+                                                    let view = view.clone();
+                                                    move |_, window, cx| {
+                                                        view.update(cx, |this, cx| {
+                                                            this.run_agent_doctor(window, cx);
+                                                        });
+                                                    }
+                                                })
+                                        )
                                         .child(
                                             Button::new("add-agent-btn")
                                                 .label(
@@ -227,6 +256,11 @@ impl SettingsPanel {
                                         );
                                     }
 
+                                    if let Some(diagnosis) = doctor_results.get(name) {
+                                        agent_info =
+                                            agent_info.child(render_diagnosis(diagnosis, cx));
+                                    }
+
                                     content = content.child(
                                         h_flex()
                                             .w_full()
@@ -318,6 +352,51 @@ impl SettingsPanel {
             ])
     }
 
+    /// Health-check every configured agent and cache the results for display
+    pub fn run_agent_doctor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+            log::warn!("AgentConfigService not available, cannot run agent doctor");
+            return;
+        };
+        let nodejs_path = AppSettings::global(cx).nodejs_path.to_string();
+
+        self.doctor_running = true;
+        self.doctor_results.clear();
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, window| {
+            let results = service.diagnose_agents(Some(nodejs_path)).await;
+            let failed = results.iter().filter(|d| !d.is_healthy()).count();
+            log::info!(
+                "Agent doctor finished: {} checked, {} failed",
+                results.len(),
+                failed
+            );
+
+            _ = window.update(|window, cx| {
+                _ = this.update(cx, |this, cx| {
+                    this.doctor_running = false;
+                    this.doctor_results = results
+                        .into_iter()
+                        .map(|diagnosis| (diagnosis.name.clone(), diagnosis))
+                        .collect();
+                    cx.notify();
+                });
+
+                let note = if failed == 0 {
+                    Notification::success(t!("settings.agents.doctor.all_ok").to_string())
+                } else {
+                    Notification::warning(
+                        t!("settings.agents.doctor.failed", count = failed).to_string(),
+                    )
+                };
+                struct AgentDoctorResult;
+                window.show_notification(note.id::<AgentDoctorResult>(), cx);
+            });
+        })
+        .detach();
+    }
+
     /// Show dialog to add or edit an agent
     pub fn show_add_edit_agent_dialog(
         &mut self,
@@ -586,3 +665,55 @@ impl SettingsPanel {
         .detach();
     }
 }
+
+/// Render one agent's doctor result below its config summary
+fn render_diagnosis(diagnosis: &AgentDiagnosis, cx: &App) -> impl IntoElement {
+    let mut details = v_flex().gap_0p5().pt_1();
+
+    if diagnosis.is_healthy() {
+        let mut summary = vec![t!("settings.agents.doctor.healthy").to_string()];
+        if let Some(info) = &diagnosis.agent_info {
+            summary.push(info.clone());
+        }
+        if let Some(latency) = diagnosis.latency {
+            summary.push(
+                t!("settings.agents.doctor.latency", ms = latency.as_millis()).to_string(),
+            );
+        }
+        details = details.child(
+            Label::new(summary.join(" · "))
+                .text_xs()
+                .text_color(cx.theme().green),
+        );
+        if !diagnosis.capabilities.is_empty() {
+            details = details.child(
+                Label::new(
+                    t!(
+                        "settings.agents.doctor.capabilities",
+                        capabilities = diagnosis.capabilities.join(", ")
+                    )
+                    .to_string(),
+                )
+                .text_xs()
+                .text_color(cx.theme().muted_foreground),
+            );
+        }
+    } else {
+        if let Some(problem) = &diagnosis.problem {
+            details = details.child(
+                Label::new(t!("settings.agents.doctor.problem", problem = problem).to_string())
+                    .text_xs()
+                    .text_color(cx.theme().red),
+            );
+        }
+        if let Some(hint) = &diagnosis.hint {
+            details = details.child(
+                Label::new(t!("settings.agents.doctor.hint", hint = hint).to_string())
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            );
+        }
+    }
+
+    details
+}
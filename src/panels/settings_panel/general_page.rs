@@ -9,7 +9,9 @@ use gpui_component::{
 use rust_i18n::t;
 
 use super::panel::SettingsPanel;
-use super::types::AppSettings;
+use super::types::{AppSettings, SEND_KEY_CTRL_ENTER, SEND_KEY_ENTER};
+use crate::AppState;
+use crate::utils::chunking::MIN_LIMIT_CHARS;
 
 impl SettingsPanel {
     pub fn general_page(&self, _view: &Entity<Self>, resettable: bool) -> SettingPage {
@@ -193,6 +195,264 @@ impl SettingsPanel {
                             t!("settings.general.font.line_height.description").to_string(),
                         ),
                     ),
+                SettingGroup::new()
+                    .title(t!("settings.general.group.input").to_string())
+                    .items(vec![
+                        SettingItem::new(
+                            t!("settings.general.input.send_key.label").to_string(),
+                            SettingField::dropdown(
+                                vec![
+                                    (
+                                        SEND_KEY_ENTER.into(),
+                                        t!("settings.general.input.send_key.enter")
+                                            .to_string()
+                                            .into(),
+                                    ),
+                                    (
+                                        SEND_KEY_CTRL_ENTER.into(),
+                                        t!("settings.general.input.send_key.ctrl_enter")
+                                            .to_string()
+                                            .into(),
+                                    ),
+                                ],
+                                |cx: &App| AppSettings::global(cx).send_key.clone(),
+                                |val: SharedString, cx: &mut App| {
+                                    AppSettings::global_mut(cx).send_key = val;
+                                },
+                            )
+                            .default_value(default_settings.send_key),
+                        )
+                        .description(t!("settings.general.input.send_key.description").to_string()),
+                        SettingItem::new(
+                            t!("settings.general.input.confirm_large.label").to_string(),
+                            SettingField::switch(
+                                |cx: &App| AppSettings::global(cx).confirm_large_prompts,
+                                |val: bool, cx: &mut App| {
+                                    AppSettings::global_mut(cx).confirm_large_prompts = val;
+                                },
+                            )
+                            .default_value(default_settings.confirm_large_prompts),
+                        )
+                        .description(
+                            t!("settings.general.input.confirm_large.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.input.confirm_tokens.label").to_string(),
+                            SettingField::number_input(
+                                NumberFieldOptions {
+                                    min: 500.0,
+                                    max: 1_000_000.0,
+                                    step: 500.0,
+                                    ..Default::default()
+                                },
+                                |cx: &App| AppSettings::global(cx).confirm_prompt_tokens,
+                                |val: f64, cx: &mut App| {
+                                    AppSettings::global_mut(cx).confirm_prompt_tokens = val;
+                                },
+                            )
+                            .default_value(default_settings.confirm_prompt_tokens),
+                        )
+                        .description(
+                            t!("settings.general.input.confirm_tokens.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.input.max_paste.label").to_string(),
+                            SettingField::number_input(
+                                NumberFieldOptions {
+                                    min: MIN_LIMIT_CHARS as f64,
+                                    max: 10_000_000.0,
+                                    step: 10_000.0,
+                                    ..Default::default()
+                                },
+                                |cx: &App| AppSettings::global(cx).max_paste_chars,
+                                |val: f64, cx: &mut App| {
+                                    AppSettings::global_mut(cx).max_paste_chars = val;
+                                },
+                            )
+                            .default_value(default_settings.max_paste_chars),
+                        )
+                        .description(
+                            t!("settings.general.input.max_paste.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.input.max_attachment.label").to_string(),
+                            SettingField::number_input(
+                                NumberFieldOptions {
+                                    min: MIN_LIMIT_CHARS as f64,
+                                    max: 10_000_000.0,
+                                    step: 10_000.0,
+                                    ..Default::default()
+                                },
+                                |cx: &App| AppSettings::global(cx).max_attachment_chars,
+                                |val: f64, cx: &mut App| {
+                                    AppSettings::global_mut(cx).max_attachment_chars = val;
+                                },
+                            )
+                            .default_value(default_settings.max_attachment_chars),
+                        )
+                        .description(
+                            t!("settings.general.input.max_attachment.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.input.editor_context.label").to_string(),
+                            SettingField::switch(
+                                |cx: &App| AppSettings::global(cx).include_editor_context,
+                                |val: bool, cx: &mut App| {
+                                    AppSettings::global_mut(cx).include_editor_context = val;
+                                },
+                            )
+                            .default_value(default_settings.include_editor_context),
+                        )
+                        .description(
+                            t!("settings.general.input.editor_context.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.input.repo_map.label").to_string(),
+                            SettingField::switch(
+                                |cx: &App| AppSettings::global(cx).include_repo_map,
+                                |val: bool, cx: &mut App| {
+                                    AppSettings::global_mut(cx).include_repo_map = val;
+                                },
+                            )
+                            .default_value(default_settings.include_repo_map),
+                        )
+                        .description(
+                            t!("settings.general.input.repo_map.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.input.memory.label").to_string(),
+                            SettingField::switch(
+                                |cx: &App| AppSettings::global(cx).include_workspace_memory,
+                                |val: bool, cx: &mut App| {
+                                    AppSettings::global_mut(cx).include_workspace_memory = val;
+                                },
+                            )
+                            .default_value(default_settings.include_workspace_memory),
+                        )
+                        .description(
+                            t!("settings.general.input.memory.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.input.follow_ups.label").to_string(),
+                            SettingField::switch(
+                                |cx: &App| AppSettings::global(cx).suggest_follow_ups,
+                                |val: bool, cx: &mut App| {
+                                    AppSettings::global_mut(cx).suggest_follow_ups = val;
+                                },
+                            )
+                            .default_value(default_settings.suggest_follow_ups),
+                        )
+                        .description(
+                            t!("settings.general.input.follow_ups.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.input.task_name.label").to_string(),
+                            SettingField::input(
+                                |cx: &App| AppSettings::global(cx).task_name_template.clone(),
+                                |val: SharedString, cx: &mut App| {
+                                    AppSettings::global_mut(cx).task_name_template = val;
+                                },
+                            )
+                            .default_value(default_settings.task_name_template),
+                        )
+                        .layout(Axis::Vertical)
+                        .description(
+                            t!("settings.general.input.task_name.description").to_string(),
+                        ),
+                    ]),
+                SettingGroup::new()
+                    .title(t!("settings.general.group.focus").to_string())
+                    .items(vec![
+                        SettingItem::new(
+                            t!("settings.general.focus.minutes.label").to_string(),
+                            SettingField::number_input(
+                                NumberFieldOptions {
+                                    min: 5.0,
+                                    max: 480.0,
+                                    step: 5.0,
+                                    ..Default::default()
+                                },
+                                |cx: &App| AppSettings::global(cx).focus_mode_minutes,
+                                |val: f64, cx: &mut App| {
+                                    AppSettings::global_mut(cx).focus_mode_minutes = val;
+                                },
+                            )
+                            .default_value(default_settings.focus_mode_minutes),
+                        )
+                        .description(t!("settings.general.focus.minutes.description").to_string()),
+                    ]),
+                SettingGroup::new()
+                    .title(t!("settings.general.group.developer").to_string())
+                    .items(vec![
+                        SettingItem::new(
+                            t!("settings.general.developer.slow_network.label").to_string(),
+                            SettingField::switch(
+                                |cx: &App| AppSettings::global(cx).simulate_slow_network,
+                                |val: bool, cx: &mut App| {
+                                    AppSettings::global_mut(cx).simulate_slow_network = val;
+                                    apply_network_simulation(cx);
+                                },
+                            )
+                            .default_value(default_settings.simulate_slow_network),
+                        )
+                        .description(
+                            t!("settings.general.developer.slow_network.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.developer.latency.label").to_string(),
+                            SettingField::number_input(
+                                NumberFieldOptions {
+                                    min: 0.0,
+                                    max: 10000.0,
+                                    step: 50.0,
+                                    ..Default::default()
+                                },
+                                |cx: &App| AppSettings::global(cx).simulated_latency_ms,
+                                |val: f64, cx: &mut App| {
+                                    AppSettings::global_mut(cx).simulated_latency_ms = val;
+                                    apply_network_simulation(cx);
+                                },
+                            )
+                            .default_value(default_settings.simulated_latency_ms),
+                        )
+                        .description(
+                            t!("settings.general.developer.latency.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.developer.jitter.label").to_string(),
+                            SettingField::number_input(
+                                NumberFieldOptions {
+                                    min: 0.0,
+                                    max: 10000.0,
+                                    step: 50.0,
+                                    ..Default::default()
+                                },
+                                |cx: &App| AppSettings::global(cx).simulated_jitter_ms,
+                                |val: f64, cx: &mut App| {
+                                    AppSettings::global_mut(cx).simulated_jitter_ms = val;
+                                    apply_network_simulation(cx);
+                                },
+                            )
+                            .default_value(default_settings.simulated_jitter_ms),
+                        )
+                        .description(
+                            t!("settings.general.developer.jitter.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.developer.rebatch.label").to_string(),
+                            SettingField::switch(
+                                |cx: &App| AppSettings::global(cx).simulated_rebatch,
+                                |val: bool, cx: &mut App| {
+                                    AppSettings::global_mut(cx).simulated_rebatch = val;
+                                    apply_network_simulation(cx);
+                                },
+                            )
+                            .default_value(default_settings.simulated_rebatch),
+                        )
+                        .description(
+                            t!("settings.general.developer.rebatch.description").to_string(),
+                        ),
+                    ]),
                 SettingGroup::new()
                     .title(t!("settings.general.group.other").to_string())
                     .items(vec![
@@ -251,3 +511,11 @@ impl SettingsPanel {
             ])
     }
 }
+
+/// Hand the simulated network from the settings to the MessageService
+fn apply_network_simulation(cx: &mut App) {
+    let simulation = AppSettings::global(cx).network_simulation();
+    if let Some(message_service) = AppState::global(cx).message_service() {
+        message_service.set_network_simulation(simulation);
+    }
+}
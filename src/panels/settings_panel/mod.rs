@@ -7,8 +7,9 @@ mod model_page;
 mod network_page;
 mod panel;
 mod prompt_page;
+mod snippet_page;
 mod types;
 mod update_page;
 
 pub use panel::SettingsPanel;
-pub use types::AppSettings;
+pub use types::{AppSettings, estimate_tokens};
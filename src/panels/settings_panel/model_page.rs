@@ -9,13 +9,25 @@ use gpui_component::{
     h_flex,
     input::{Input, InputState},
     label::Label,
+    notification::Notification,
     setting::{SettingGroup, SettingItem, SettingPage},
     v_flex,
 };
 use rust_i18n::t;
 
 use super::panel::SettingsPanel;
-use crate::AppState;
+use crate::app::focus_mode::NotificationExt as _;
+use crate::{
+    AppState,
+    core::config::{ModelConfig, OLLAMA_DEFAULT_URL, OLLAMA_PROVIDER, UtilityModelConfig},
+    core::services::discover_ollama_models,
+};
+
+/// Providers offered in the add dialog: (label, provider, default base URL)
+const PROVIDER_PRESETS: [(&str, &str, &str); 2] = [
+    ("OpenAI", "openai", "https://api.openai.com/v1"),
+    ("Ollama", OLLAMA_PROVIDER, OLLAMA_DEFAULT_URL),
+];
 
 impl SettingsPanel {
     pub fn model_page(&self, view: &Entity<Self>) -> SettingPage {
@@ -76,6 +88,50 @@ impl SettingsPanel {
                                 idx += 1;
                             }
 
+                            v_flex().w_full().gap_2().child(options_flex)
+                        }
+                    })),
+                // Utility model for internal features
+                SettingGroup::new()
+                    .title(t!("settings.models.utility.title").to_string())
+                    .description(t!("settings.models.utility.description").to_string())
+                    .item(SettingItem::render({
+                        let view = view.clone();
+                        move |_options, _window, cx| {
+                            let model_configs = view.read(cx).cached_models.clone();
+                            let utility_model = AppState::global(cx).ai_service().and_then(|s| {
+                                s.config.read().unwrap().router.utility_model().map(String::from)
+                            });
+
+                            let mut names: Vec<&String> = model_configs
+                                .iter()
+                                .filter(|(_, config)| config.enabled)
+                                .map(|(name, _)| name)
+                                .collect();
+                            names.sort();
+
+                            let option = |id: usize, label: String, model: Option<String>| {
+                                let selected = utility_model == model;
+                                let view = view.clone();
+                                Button::new(("utility-model-btn", id))
+                                    .label(label)
+                                    .when(selected, |btn| btn.icon(IconName::Check))
+                                    .when(!selected, |btn| btn.outline())
+                                    .small()
+                                    .on_click(move |_, window, cx| {
+                                        view.update(cx, |this, cx| {
+                                            this.set_utility_model(model.clone(), window, cx);
+                                        });
+                                    })
+                            };
+
+                            let mut options_flex = h_flex().w_full().gap_2().flex_wrap().child(
+                                option(0, t!("settings.models.utility.default").to_string(), None),
+                            );
+                            for (idx, name) in names.into_iter().enumerate() {
+                                let button = option(idx + 1, name.clone(), Some(name.clone()));
+                                options_flex = options_flex.child(button);
+                            }
                             v_flex().w_full().gap_2().child(options_flex)
                         }
                     })),
@@ -88,20 +144,42 @@ impl SettingsPanel {
                             let model_configs = view.read(cx).cached_models.clone();
 
                             let mut content = v_flex().w_full().gap_3().child(
-                                h_flex().w_full().justify_end().child(
-                                    Button::new("add-model-btn")
-                                        .label(t!("settings.models.button.add").to_string())
-                                        .icon(IconName::Plus)
-                                        .small()
-                                        .on_click({
-                                            let view = view.clone();
-                                            move |_, window, cx| {
-                                                view.update(cx, |this, cx| {
-                                                    this.show_add_model_dialog(window, cx);
-                                                });
-                                            }
-                                        }),
-                                ),
+                                h_flex()
+                                    .w_full()
+                                    .gap_2()
+                                    .justify_end()
+                                    .child(
+                                        Button::new("discover-ollama-btn")
+                                            .label(
+                                                t!("settings.models.button.discover_ollama")
+                                                    .to_string(),
+                                            )
+                                            .icon(IconName::Search)
+                                            .outline()
+                                            .small()
+                                            .on_click({
+                                                let view = view.clone();
+                                                move |_, window, cx| {
+                                                    view.update(cx, |this, cx| {
+                                                        this.discover_ollama_models(window, cx);
+                                                    });
+                                                }
+                                            }),
+                                    )
+                                    .child(
+                                        Button::new("add-model-btn")
+                                            .label(t!("settings.models.button.add").to_string())
+                                            .icon(IconName::Plus)
+                                            .small()
+                                            .on_click({
+                                                let view = view.clone();
+                                                move |_, window, cx| {
+                                                    view.update(cx, |this, cx| {
+                                                        this.show_add_model_dialog(window, cx);
+                                                    });
+                                                }
+                                            }),
+                                    ),
                             );
 
                             if model_configs.is_empty() {
@@ -356,6 +434,26 @@ impl SettingsPanel {
                                 .child(Label::new(
                                     t!("settings.models.field.provider_label").to_string(),
                                 ))
+                                .child(
+                                    h_flex().gap_2().children(PROVIDER_PRESETS.iter().map(
+                                        |&(label, provider, url)| {
+                                            let provider_input = provider_input.clone();
+                                            let url_input = url_input.clone();
+                                            Button::new(label)
+                                                .label(label)
+                                                .outline()
+                                                .xsmall()
+                                                .on_click(move |_, window, cx| {
+                                                    provider_input.update(cx, |state, cx| {
+                                                        state.set_value(provider, window, cx);
+                                                    });
+                                                    url_input.update(cx, |state, cx| {
+                                                        state.set_value(url, window, cx);
+                                                    });
+                                                })
+                                        },
+                                    )),
+                                )
                                 .child(Input::new(&provider_input)),
                         )
                         .child(
@@ -558,6 +656,109 @@ impl SettingsPanel {
         });
     }
 
+    /// Add the models pulled on the local Ollama server that are not configured yet
+    pub fn discover_ollama_models(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+            return;
+        };
+        let configured: Vec<String> = self
+            .cached_models
+            .values()
+            .filter(|config| config.is_ollama())
+            .map(|config| config.model_name.clone())
+            .collect();
+        let server_url = self
+            .cached_models
+            .values()
+            .find(|config| config.is_ollama())
+            .map(|config| config.base_url.clone())
+            .unwrap_or_else(|| OLLAMA_DEFAULT_URL.to_string());
+
+        cx.spawn_in(window, async move |this, window| {
+            let discovered = match discover_ollama_models(&server_url).await {
+                Ok(models) => models,
+                Err(e) => {
+                    log::warn!("Ollama model discovery failed: {:#}", e);
+                    _ = window.update(|window, cx| {
+                        struct OllamaDiscovery;
+                        let note = Notification::error(
+                            t!("settings.models.ollama.unreachable", url = server_url).to_string(),
+                        )
+                        .id::<OllamaDiscovery>();
+                        window.show_notification(note, cx);
+                    });
+                    return;
+                }
+            };
+
+            let mut added = Vec::new();
+            for model in discovered {
+                if configured.contains(&model) {
+                    continue;
+                }
+                let config = ModelConfig {
+                    enabled: true,
+                    provider: OLLAMA_PROVIDER.to_string(),
+                    base_url: server_url.clone(),
+                    api_key: String::new(),
+                    model_name: model.clone(),
+                };
+                match service.add_model(model.clone(), config.clone()).await {
+                    Ok(()) => added.push((model, config)),
+                    Err(e) => log::warn!("Failed to add Ollama model {}: {}", model, e),
+                }
+            }
+
+            _ = window.update(|window, cx| {
+                // Usable right away for direct chat, without changing the default model
+                if let Some(ai_service) = AppState::global(cx).ai_service() {
+                    let mut config = ai_service.config.write().unwrap();
+                    config.models.extend(added.iter().cloned());
+                }
+
+                struct OllamaDiscovery;
+                let note = Notification::success(
+                    t!("settings.models.ollama.discovered", count = added.len()).to_string(),
+                )
+                .id::<OllamaDiscovery>();
+                window.show_notification(note, cx);
+
+                _ = this.update(cx, |this, cx| {
+                    this.cached_models.extend(added);
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    /// Route internal features to `model` (None = the default model) and save it
+    pub fn set_utility_model(
+        &mut self,
+        model: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ai_service) = AppState::global(cx).ai_service() {
+            let fallbacks = ai_service.config.read().unwrap().router.utility().fallbacks.clone();
+            ai_service.set_utility_model(UtilityModelConfig {
+                model: model.clone(),
+                fallbacks,
+            });
+        }
+        cx.notify();
+
+        let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+            return;
+        };
+        cx.spawn_in(window, async move |_this, _window| {
+            if let Err(e) = service.set_utility_model(model).await {
+                log::error!("Failed to save utility model: {}", e);
+            }
+        })
+        .detach();
+    }
+
     pub fn set_default_model(&mut self, model_name: String, cx: &mut Context<Self>) {
         log::info!("Setting default AI model to: {}", model_name);
 
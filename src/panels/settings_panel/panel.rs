@@ -11,7 +11,8 @@ use std::{collections::HashMap, path::PathBuf};
 use crate::{
     AppState,
     core::{
-        config::{AgentProcessConfig, CommandConfig, McpServerConfig, ModelConfig},
+        agent::AgentDiagnosis,
+        config::{AgentProcessConfig, CommandConfig, McpServerConfig, ModelConfig, Snippet},
         updater::UpdateManager,
     },
 };
@@ -27,7 +28,11 @@ pub struct SettingsPanel {
     pub(super) cached_models: HashMap<String, ModelConfig>,
     pub(super) cached_mcp_servers: HashMap<String, McpServerConfig>,
     pub(super) cached_commands: HashMap<String, CommandConfig>,
+    pub(super) cached_snippets: Vec<Snippet>,
     pub(super) cached_upload_dir: PathBuf,
+    // Latest agent doctor results, keyed by agent name
+    pub(super) doctor_results: HashMap<String, AgentDiagnosis>,
+    pub(super) doctor_running: bool,
     // JSON editor state for MCP servers
     pub(super) mcp_json_editor: Entity<InputState>,
     pub(super) mcp_json_error: Option<String>,
@@ -96,7 +101,10 @@ impl SettingsPanel {
             cached_models: HashMap::new(),
             cached_mcp_servers: HashMap::new(),
             cached_commands: HashMap::new(),
+            cached_snippets: Vec::new(),
             cached_upload_dir: PathBuf::from("."),
+            doctor_results: HashMap::new(),
+            doctor_running: false,
             mcp_json_editor,
             mcp_json_error: None,
             mcp_active_tab: 0,
@@ -115,6 +123,7 @@ impl SettingsPanel {
                 let models = service.list_models().await;
                 let mcp_servers = service.list_mcp_servers().await;
                 let commands = service.list_commands().await;
+                let snippets = service.config().await.snippets;
                 let upload_dir = service.get_upload_dir().await;
 
                 _ = window.update(|window, cx| {
@@ -124,6 +133,7 @@ impl SettingsPanel {
                             this.cached_models = models.into_iter().collect();
                             this.cached_mcp_servers = mcp_servers.into_iter().collect();
                             this.cached_commands = commands.into_iter().collect();
+                            this.cached_snippets = snippets;
                             this.cached_upload_dir = upload_dir;
                             // Load system prompts into input fields
                             this.load_system_prompts(window, cx);
@@ -224,6 +234,7 @@ impl SettingsPanel {
                 self.cached_models = config.models.clone();
                 self.cached_mcp_servers = config.mcp_servers.clone();
                 self.cached_commands = config.commands.clone();
+                self.cached_snippets = config.snippets.clone();
                 self.cached_upload_dir = config.upload_dir.clone();
             }
         }
@@ -245,6 +256,7 @@ impl SettingsPanel {
             self.prompt_page(&view),
             self.mcp_page(&view),
             self.command_page(&view),
+            self.snippet_page(&view),
             super::about_page::about_page(resettable),
         ]
     }
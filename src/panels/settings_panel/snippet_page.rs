@@ -0,0 +1,298 @@
+use gpui::{AppContext as _, Context, Entity, ParentElement as _, Styled, Window, px};
+use gpui_component::{
+    ActiveTheme, IconName, Sizable, WindowExt as _,
+    button::{Button, ButtonVariant},
+    dialog::DialogButtonProps,
+    h_flex,
+    input::{Input, InputState},
+    label::Label,
+    setting::{SettingGroup, SettingItem, SettingPage},
+    v_flex,
+};
+use rust_i18n::t;
+
+use super::panel::SettingsPanel;
+use crate::AppState;
+use crate::core::config::Snippet;
+
+impl SettingsPanel {
+    pub fn snippet_page(&self, view: &Entity<Self>) -> SettingPage {
+        SettingPage::new(t!("settings.snippets.title").to_string())
+            .resettable(false)
+            .groups(vec![
+                SettingGroup::new()
+                    .title(t!("settings.snippets.group").to_string())
+                    .item(SettingItem::render({
+                        let view = view.clone();
+                        move |_options, _window, cx| {
+                            let snippets = view.read(cx).cached_snippets.clone();
+
+                            let mut content = v_flex().w_full().gap_3().child(
+                                h_flex()
+                                    .w_full()
+                                    .gap_3()
+                                    .justify_between()
+                                    .child(
+                                        Label::new(t!("settings.snippets.description").to_string())
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground),
+                                    )
+                                    .child(
+                                        Button::new("add-snippet-btn")
+                                            .label(t!("settings.snippets.button.add").to_string())
+                                            .icon(IconName::Plus)
+                                            .small()
+                                            .on_click({
+                                                let view = view.clone();
+                                                move |_, window, cx| {
+                                                    view.update(cx, |this, cx| {
+                                                        this.show_snippet_dialog(None, window, cx);
+                                                    });
+                                                }
+                                            }),
+                                    ),
+                            );
+
+                            if snippets.is_empty() {
+                                content = content.child(
+                                    h_flex().w_full().p_4().justify_center().child(
+                                        Label::new(t!("settings.snippets.empty").to_string())
+                                            .text_sm()
+                                            .text_color(cx.theme().muted_foreground),
+                                    ),
+                                );
+                            }
+
+                            for (idx, snippet) in snippets.iter().enumerate() {
+                                let preview = snippet.text.lines().next().unwrap_or_default();
+                                content = content.child(
+                                    h_flex()
+                                        .w_full()
+                                        .items_start()
+                                        .justify_between()
+                                        .p_3()
+                                        .gap_3()
+                                        .rounded(px(6.))
+                                        .bg(cx.theme().secondary)
+                                        .border_1()
+                                        .border_color(cx.theme().border)
+                                        .child(
+                                            v_flex()
+                                                .flex_1()
+                                                .min_w_0()
+                                                .gap_1()
+                                                .child(
+                                                    Label::new(snippet.name.clone())
+                                                        .text_sm()
+                                                        .font_weight(gpui::FontWeight::SEMIBOLD),
+                                                )
+                                                .child(
+                                                    Label::new(preview.to_string())
+                                                        .text_xs()
+                                                        .text_color(cx.theme().muted_foreground),
+                                                ),
+                                        )
+                                        .child(
+                                            h_flex()
+                                                .gap_2()
+                                                .items_center()
+                                                .child(
+                                                    Button::new(("edit-snippet-btn", idx))
+                                                        .label(
+                                                            t!("settings.snippets.button.edit")
+                                                                .to_string(),
+                                                        )
+                                                        .icon(IconName::Settings)
+                                                        .outline()
+                                                        .small()
+                                                        .on_click({
+                                                            let view = view.clone();
+                                                            move |_, window, cx| {
+                                                                view.update(cx, |this, cx| {
+                                                                    this.show_snippet_dialog(
+                                                                        Some(idx),
+                                                                        window,
+                                                                        cx,
+                                                                    );
+                                                                });
+                                                            }
+                                                        }),
+                                                )
+                                                .child(
+                                                    Button::new(("delete-snippet-btn", idx))
+                                                        .label(
+                                                            t!("settings.snippets.button.delete")
+                                                                .to_string(),
+                                                        )
+                                                        .icon(IconName::Delete)
+                                                        .outline()
+                                                        .small()
+                                                        .on_click({
+                                                            let view = view.clone();
+                                                            move |_, window, cx| {
+                                                                view.update(cx, |this, cx| {
+                                                                    this.show_delete_snippet_dialog(
+                                                                        idx, window, cx,
+                                                                    );
+                                                                });
+                                                            }
+                                                        }),
+                                                ),
+                                        ),
+                                );
+                            }
+
+                            content
+                        }
+                    })),
+            ])
+    }
+
+    /// Add a snippet, or edit the one at `index`
+    pub fn show_snippet_dialog(
+        &mut self,
+        index: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let existing = index.and_then(|index| self.cached_snippets.get(index).cloned());
+        let name_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx)
+                .placeholder(t!("settings.snippets.input.name.placeholder").to_string());
+            if let Some(snippet) = &existing {
+                state.set_value(snippet.name.clone(), window, cx);
+            }
+            state
+        });
+        let text_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(4, 12)
+                .placeholder(t!("settings.snippets.input.text.placeholder").to_string());
+            if let Some(snippet) = &existing {
+                state.set_value(snippet.text.clone(), window, cx);
+            }
+            state
+        });
+        let title = if existing.is_some() {
+            t!("settings.snippets.dialog.edit.title")
+        } else {
+            t!("settings.snippets.dialog.add.title")
+        }
+        .to_string();
+        let snippets = self.cached_snippets.clone();
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            dialog
+                .title(title.clone())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("settings.snippets.dialog.save").to_string())
+                        .cancel_text(t!("settings.snippets.dialog.cancel").to_string()),
+                )
+                .on_ok({
+                    let name_input = name_input.clone();
+                    let text_input = text_input.clone();
+                    let snippets = snippets.clone();
+                    move |_, _window, cx| {
+                        let name = name_input.read(cx).text().to_string().trim().to_string();
+                        let text = text_input.read(cx).text().to_string();
+                        if name.is_empty() || text.trim().is_empty() {
+                            log::warn!("Snippet name and text cannot be empty");
+                            return false;
+                        }
+
+                        let mut snippets = snippets.clone();
+                        let snippet = Snippet { name, text };
+                        match index.filter(|&index| index < snippets.len()) {
+                            Some(index) => snippets[index] = snippet,
+                            None => snippets.push(snippet),
+                        }
+                        Self::save_snippets(snippets, cx);
+                        true
+                    }
+                })
+                .child(
+                    v_flex()
+                        .w_full()
+                        .gap_3()
+                        .p_4()
+                        .child(
+                            v_flex()
+                                .gap_2()
+                                .child(Label::new(t!("settings.snippets.field.name").to_string()))
+                                .child(Input::new(&name_input)),
+                        )
+                        .child(
+                            v_flex()
+                                .gap_2()
+                                .child(Label::new(t!("settings.snippets.field.text").to_string()))
+                                .child(Input::new(&text_input)),
+                        ),
+                )
+        });
+    }
+
+    pub fn show_delete_snippet_dialog(
+        &mut self,
+        index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(snippet) = self.cached_snippets.get(index).cloned() else {
+            return;
+        };
+        let snippets = self.cached_snippets.clone();
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let snippets = snippets.clone();
+            dialog
+                .title(t!("settings.snippets.dialog.delete.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("settings.snippets.dialog.delete.ok").to_string())
+                        .ok_variant(ButtonVariant::Danger)
+                        .cancel_text(t!("settings.snippets.dialog.cancel").to_string()),
+                )
+                .on_ok(move |_, _window, cx| {
+                    let mut snippets = snippets.clone();
+                    if index < snippets.len() {
+                        snippets.remove(index);
+                    }
+                    Self::save_snippets(snippets, cx);
+                    true
+                })
+                .child(
+                    v_flex().w_full().gap_2().p_4().child(
+                        Label::new(
+                            t!(
+                                "settings.snippets.dialog.delete.message",
+                                name = snippet.name
+                            )
+                            .to_string(),
+                        )
+                        .text_sm(),
+                    ),
+                )
+        });
+    }
+
+    /// Save the snippets to config.json; the reload event refreshes the page
+    /// and the input toolbars
+    fn save_snippets(snippets: Vec<Snippet>, cx: &mut gpui::App) {
+        let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+            return;
+        };
+        cx.spawn(async move |_cx| {
+            if let Err(e) = service
+                .update_config(move |config| config.snippets = snippets)
+                .await
+            {
+                log::error!("Failed to save snippets: {}", e);
+            }
+        })
+        .detach();
+    }
+}
@@ -5,6 +5,10 @@ use gpui_component::{
     setting::{RenderOptions, SettingFieldElement},
 };
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::core::services::NetworkSimulation;
+use crate::utils::chunking::{ChunkLimits, MIN_LIMIT_CHARS};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
@@ -24,6 +28,52 @@ pub struct AppSettings {
     pub resettable: bool,
     pub group_variant: SharedString,
     pub size: SharedString,
+    /// Key that sends a prompt: "enter" or "ctrl-enter" (cmd-enter on macOS)
+    #[serde(default = "default_send_key")]
+    pub send_key: SharedString,
+    /// Ask before sending prompts larger than `confirm_prompt_tokens`
+    #[serde(default = "default_true")]
+    pub confirm_large_prompts: bool,
+    #[serde(default = "default_confirm_prompt_tokens")]
+    pub confirm_prompt_tokens: f64,
+    /// Pasted text longer than this many characters is sent in parts
+    #[serde(default = "default_max_paste_chars")]
+    pub max_paste_chars: f64,
+    /// Embedded attachments longer than this many characters are sent in parts
+    #[serde(default = "default_max_attachment_chars")]
+    pub max_attachment_chars: f64,
+    /// Attach the file (or selection) open in the code editor to new prompts
+    #[serde(default = "default_true")]
+    pub include_editor_context: bool,
+    /// Attach the workspace's repo map to the first prompt of a session
+    #[serde(default)]
+    pub include_repo_map: bool,
+    /// Prepend the workspace memory to the first prompt of a session
+    #[serde(default = "default_true")]
+    pub include_workspace_memory: bool,
+    /// Suggest follow-up prompts under the agent's reply when a turn completes
+    #[serde(default = "default_true")]
+    pub suggest_follow_ups: bool,
+    /// Generate commit messages in the Conventional Commits format
+    #[serde(default)]
+    pub conventional_commits: bool,
+    /// Template new tasks are named from (see `utils::task_name`)
+    #[serde(default = "default_task_name_template")]
+    pub task_name_template: SharedString,
+    /// How long focus mode holds back notifications, in minutes
+    #[serde(default = "default_focus_mode_minutes")]
+    pub focus_mode_minutes: f64,
+    /// Hold back session updates as if the agent were on a slow network;
+    /// a developer setting, never saved, so it is off after a restart
+    #[serde(skip)]
+    pub simulate_slow_network: bool,
+    #[serde(skip, default = "default_simulated_latency_ms")]
+    pub simulated_latency_ms: f64,
+    #[serde(skip, default = "default_simulated_jitter_ms")]
+    pub simulated_jitter_ms: f64,
+    /// Merge the message chunks that queued up while held back
+    #[serde(skip)]
+    pub simulated_rebatch: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -52,6 +102,22 @@ impl Default for AppSettings {
             resettable: true,
             group_variant: "Fill".into(),
             size: "Small".into(),
+            send_key: default_send_key(),
+            confirm_large_prompts: true,
+            confirm_prompt_tokens: default_confirm_prompt_tokens(),
+            max_paste_chars: default_max_paste_chars(),
+            max_attachment_chars: default_max_attachment_chars(),
+            include_editor_context: true,
+            include_repo_map: false,
+            include_workspace_memory: true,
+            suggest_follow_ups: true,
+            conventional_commits: false,
+            task_name_template: default_task_name_template(),
+            focus_mode_minutes: default_focus_mode_minutes(),
+            simulate_slow_network: false,
+            simulated_latency_ms: default_simulated_latency_ms(),
+            simulated_jitter_ms: default_simulated_jitter_ms(),
+            simulated_rebatch: false,
         }
     }
 }
@@ -62,6 +128,50 @@ fn default_locale() -> SharedString {
     "en".into()
 }
 
+pub const SEND_KEY_ENTER: &str = "enter";
+pub const SEND_KEY_CTRL_ENTER: &str = "ctrl-enter";
+
+fn default_send_key() -> SharedString {
+    SEND_KEY_ENTER.into()
+}
+
+fn default_task_name_template() -> SharedString {
+    crate::utils::task_name::DEFAULT_TASK_NAME_TEMPLATE.into()
+}
+
+fn default_focus_mode_minutes() -> f64 {
+    crate::app::focus_mode::DEFAULT_FOCUS_MINUTES
+}
+
+fn default_simulated_latency_ms() -> f64 {
+    300.0
+}
+
+fn default_simulated_jitter_ms() -> f64 {
+    200.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_confirm_prompt_tokens() -> f64 {
+    8000.0
+}
+
+fn default_max_paste_chars() -> f64 {
+    50_000.0
+}
+
+fn default_max_attachment_chars() -> f64 {
+    100_000.0
+}
+
+/// Rough token count for a prompt (about 4 characters per token)
+pub fn estimate_tokens(chars: usize) -> usize {
+    chars.div_ceil(4)
+}
+
 impl AppSettings {
     pub fn global(cx: &App) -> &AppSettings {
         cx.global::<AppSettings>()
@@ -70,6 +180,35 @@ impl AppSettings {
     pub fn global_mut(cx: &mut App) -> &mut AppSettings {
         cx.global_mut::<AppSettings>()
     }
+
+    /// Whether Enter inserts a newline and Ctrl/Cmd+Enter sends
+    pub fn send_with_modifier(&self) -> bool {
+        self.send_key.as_ref() == SEND_KEY_CTRL_ENTER
+    }
+
+    /// Whether a prompt of `chars` characters needs a confirmation before sending
+    pub fn should_confirm_send(&self, chars: usize) -> bool {
+        self.confirm_large_prompts && estimate_tokens(chars) as f64 > self.confirm_prompt_tokens
+    }
+
+    /// Sizes above which pasted text and attachments are split over turns
+    pub fn chunk_limits(&self) -> ChunkLimits {
+        let limit = |chars: f64| (chars.max(0.) as usize).max(MIN_LIMIT_CHARS);
+        ChunkLimits {
+            paste_chars: limit(self.max_paste_chars),
+            attachment_chars: limit(self.max_attachment_chars),
+        }
+    }
+
+    /// The simulated network the MessageService should apply
+    pub fn network_simulation(&self) -> NetworkSimulation {
+        NetworkSimulation {
+            enabled: self.simulate_slow_network,
+            latency: Duration::from_millis(self.simulated_latency_ms.max(0.) as u64),
+            jitter: Duration::from_millis(self.simulated_jitter_ms.max(0.) as u64),
+            rebatch: self.simulated_rebatch,
+        }
+    }
 }
 
 pub struct OpenURLSettingField {
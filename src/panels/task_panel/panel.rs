@@ -5,32 +5,38 @@
 //! - Task items with status indicators
 //! - Tree view (by workspace) and timeline view (by date)
 
+use agent_client_protocol::{ContentBlock, ContentChunk, SessionUpdate};
 use gpui::{
-    App, AppContext, ClickEvent, Context, Entity, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, ParentElement, Pixels, Render, SharedString, StatefulInteractiveElement, Styled,
-    Subscription, Window, div, prelude::FluentBuilder, px,
+    App, AppContext, ClickEvent, ClipboardItem, Context, Entity, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Pixels, Render, ScrollHandle, SharedString,
+    StatefulInteractiveElement, Styled, Subscription, Window, div, prelude::FluentBuilder, px,
 };
 use gpui_component::{
-    ActiveTheme, Icon, IconName, Selectable, Sizable, StyledExt,
-    button::{Button, ButtonGroup, ButtonVariants},
+    ActiveTheme, Icon, IconName, Selectable, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonGroup, ButtonVariant, ButtonVariants},
+    checkbox::Checkbox,
+    dialog::DialogButtonProps,
     dock::DockPlacement,
     h_flex,
     input::{Input, InputState},
     menu::{ContextMenuExt, DropdownMenu, PopupMenuItem},
-    scroll::ScrollableElement as _,
+    notification::Notification,
     v_flex,
 };
 use rust_i18n::t;
 use smol::Timer;
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::time::Duration;
 
-use crate::core::services::WorkspaceService;
+use crate::app::focus_mode::NotificationExt as _;
+use crate::core::services::{ServiceError, WorkspaceService};
 use crate::core::{event_bus::WorkspaceUpdateEvent, services::SessionStatus};
 use crate::panels::dock_panel::DockPanel;
-use crate::schemas::workspace_schema::WorkspaceTask;
-use crate::{AppState, PanelAction, StatusIndicator, utils};
+use crate::schemas::workspace_schema::{TaskCursor, WorkspaceTask, message_preview};
+use crate::components::{AgentAvatar, ContextActions};
+use crate::{AppState, NewTask, PanelAction, StatusIndicator, utils};
 
 // ============================================================================
 // Constants - Layout spacing
@@ -39,6 +45,12 @@ use crate::{AppState, PanelAction, StatusIndicator, utils};
 /// Left indent for child items under workspace header (matches chevron width + gap)
 const CHILD_INDENT: f32 = 22.0; // ChevronIcon(16px) + gap(6px)
 
+/// Tasks loaded at once; older ones load as the list is scrolled to its end
+const TASK_PAGE_SIZE: usize = 100;
+
+/// Distance from the end of the list at which the next page loads
+const LOAD_MORE_THRESHOLD: f32 = 400.0;
+
 // ============================================================================
 // Data Models
 // ============================================================================
@@ -50,6 +62,12 @@ pub struct WorkspaceGroup {
     pub path: std::path::PathBuf,
     pub tasks: Vec<Rc<WorkspaceTask>>,
     pub is_expanded: bool,
+    /// Sessions are kept in the workspace's repository
+    pub repo_sessions: bool,
+    /// Language agents are asked to respond in
+    pub response_language: Option<String>,
+    /// Replies in another language are pointed out
+    pub check_response_language: bool,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -75,8 +93,22 @@ pub struct TaskPanel {
     last_click_task_id: Option<String>,
     /// Loading state indicator
     is_loading: bool,
+    /// Scroll position of the task list, to load older tasks near its end
+    scroll_handle: ScrollHandle,
+    /// Oldest task loaded so far
+    cursor: Option<TaskCursor>,
+    /// Older tasks are being loaded
+    loading: bool,
+    /// All tasks are loaded
+    eof: bool,
     /// Optional callback for custom item focus handling
     on_item_focus: Option<Box<dyn Fn(&str, &mut Window, &mut Context<Self>)>>,
+    /// Agent replies not yet seen, by session ID
+    unread: HashMap<String, usize>,
+    /// Sessions of the task last opened, whose replies are seen as they arrive
+    open_sessions: Vec<String>,
+    /// Text of the replies streaming in, by session ID
+    replies: HashMap<String, String>,
 }
 
 impl DockPanel for TaskPanel {
@@ -108,6 +140,7 @@ impl TaskPanel {
         if let Some(workspace_service) = AppState::global(cx).workspace_service() {
             Self::load_workspace_data(&entity, workspace_service.clone(), cx);
             Self::subscribe_to_workspace_updates(&entity, cx);
+            Self::subscribe_to_session_updates(&entity, cx);
         } else {
             log::warn!("WorkspaceService not available, TaskPanel will remain empty");
         }
@@ -121,7 +154,11 @@ impl TaskPanel {
         // Subscribe to search input changes to trigger re-render
         let search_subscription = cx.subscribe(
             &search_input,
-            |_this, _input, _event: &gpui_component::input::InputEvent, cx| {
+            |this, input, _event: &gpui_component::input::InputEvent, cx| {
+                // Search covers all tasks, not only the pages loaded so far
+                if !input.read(cx).value().trim().is_empty() {
+                    this.load_more(true, cx);
+                }
                 cx.notify(); // Trigger re-render when search input changes
             },
         );
@@ -137,7 +174,14 @@ impl TaskPanel {
             pending_click_generation: 0,
             last_click_task_id: None,
             is_loading: false,
+            scroll_handle: ScrollHandle::new(),
+            cursor: None,
+            loading: false,
+            eof: false,
             on_item_focus: None,
+            unread: HashMap::new(),
+            open_sessions: Vec::new(),
+            replies: HashMap::new(),
         }
     }
 
@@ -170,12 +214,14 @@ impl TaskPanel {
 
         let entity_clone = entity.clone();
         cx.spawn(async move |cx| {
-            let config = workspace_service.get_config().await;
-            let workspaces_list = config.workspaces;
-            let tasks = config.tasks;
+            let workspaces_list = workspace_service.list_workspaces().await;
+            let page = workspace_service.get_tasks_page(None, TASK_PAGE_SIZE).await;
+            let cursor = page.tasks.last().map(TaskCursor::of);
+            let eof = page.eof;
 
+            // Oldest first within each workspace, as tasks are added
             let mut tasks_by_workspace: HashMap<String, Vec<Rc<WorkspaceTask>>> = HashMap::new();
-            for task in tasks {
+            for task in page.tasks.into_iter().rev() {
                 tasks_by_workspace
                     .entry(task.workspace_id.clone())
                     .or_default()
@@ -208,10 +254,16 @@ impl TaskPanel {
                                     .get(&ws.id)
                                     .copied()
                                     .unwrap_or(true),
+                                repo_sessions: ws.repo_sessions,
+                                response_language: ws.response_language.clone(),
+                                check_response_language: ws.check_response_language,
                             }
                         })
                         .collect();
 
+                    this.cursor = cursor;
+                    this.eof = eof;
+                    this.loading = false;
                     this.ensure_selected_task_valid();
                     this.is_loading = false;
                     cx.notify();
@@ -221,6 +273,63 @@ impl TaskPanel {
         .detach();
     }
 
+    /// Load the next page of older tasks, or all remaining ones if `all`
+    fn load_more(&mut self, all: bool, cx: &mut Context<Self>) {
+        if self.is_loading || self.loading || self.eof {
+            return;
+        }
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            return;
+        };
+        self.loading = true;
+        cx.notify();
+
+        let generation = self.load_generation;
+        let cursor = self.cursor.clone();
+        let limit = if all { usize::MAX } else { TASK_PAGE_SIZE };
+        cx.spawn(async move |this, cx| {
+            let page = workspace_service
+                .get_tasks_page(cursor.as_ref(), limit)
+                .await;
+            _ = this.update(cx, |this, cx| {
+                // A full reload started meanwhile and loads its own pages
+                if this.load_generation != generation {
+                    return;
+                }
+                log::debug!("Loaded {} older tasks", page.tasks.len());
+                if let Some(oldest) = page.tasks.last() {
+                    this.cursor = Some(TaskCursor::of(oldest));
+                }
+                this.eof = page.eof;
+                this.loading = false;
+                // Older tasks go before those already loaded
+                for task in page.tasks {
+                    let Some(workspace) = this
+                        .workspaces
+                        .iter_mut()
+                        .find(|w| w.id == task.workspace_id)
+                    else {
+                        continue;
+                    };
+                    if workspace.tasks.iter().all(|t| t.id != task.id) {
+                        workspace.tasks.insert(0, Rc::new(task));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Load older tasks once the list is scrolled close to its end
+    fn load_more_if_near_end(&mut self, cx: &mut Context<Self>) {
+        let scrolled = -self.scroll_handle.offset().y;
+        let max = self.scroll_handle.max_offset().height;
+        if max - scrolled < px(LOAD_MORE_THRESHOLD) {
+            self.load_more(false, cx);
+        }
+    }
+
     /// Incremental update: Add a single task to a workspace
     fn add_task_incremental(
         entity: &Entity<Self>,
@@ -314,14 +423,18 @@ impl TaskPanel {
                         {
                             if let Some(pos) = workspace.tasks.iter().position(|t| t.id == task_id)
                             {
+                                let mut updated_task = updated_task;
+                                // A reply may still be streaming in
+                                if updated_task.last_message.is_none() {
+                                    updated_task.last_message =
+                                        workspace.tasks[pos].last_message.clone();
+                                }
                                 workspace.tasks[pos] = Rc::new(updated_task);
                                 log::debug!("Incrementally updated task {}", task_id);
                             } else {
-                                log::warn!(
-                                    "Task {} not found in workspace {} for update",
-                                    task_id,
-                                    workspace_id
-                                );
+                                // Updated since the page holding it was loaded
+                                workspace.tasks.push(Rc::new(updated_task));
+                                log::debug!("Added updated task {} to the loaded page", task_id);
                             }
                         } else {
                             log::warn!("Workspace {} not found for task update", workspace_id);
@@ -368,6 +481,9 @@ impl TaskPanel {
                             path: workspace.path.clone(),
                             tasks: tasks.into_iter().map(Rc::new).collect(),
                             is_expanded: true,
+                            repo_sessions: workspace.repo_sessions,
+                            response_language: workspace.response_language.clone(),
+                            check_response_language: workspace.check_response_language,
                         });
 
                         log::debug!("Incrementally added workspace {}", workspace_id);
@@ -487,7 +603,8 @@ impl TaskPanel {
                             });
                         }
                     }
-                    WorkspaceUpdateEvent::TaskUpdated { task_id } => {
+                    WorkspaceUpdateEvent::TaskUpdated { task_id }
+                    | WorkspaceUpdateEvent::TaskLockChanged { task_id, .. } => {
                         log::debug!("TaskPanel received TaskUpdated: {}", task_id);
                         if let Some(entity) = entity_weak.upgrade() {
                             cx.update(|cx| {
@@ -516,12 +633,74 @@ impl TaskPanel {
                             });
                         }
                     }
+                    WorkspaceUpdateEvent::TurnCompleted { session_id, .. } => {
+                        if let Some(entity) = entity_weak.upgrade() {
+                            cx.update(|cx| {
+                                entity.update(cx, |this, cx| {
+                                    this.finish_reply(&session_id, cx);
+                                    this.mark_unread(session_id, cx);
+                                });
+                            });
+                        }
+                    }
+                    WorkspaceUpdateEvent::SessionOpened { session_id } => {
+                        if let Some(entity) = entity_weak.upgrade() {
+                            cx.update(|cx| {
+                                entity.update(cx, |this, cx| this.mark_read(&session_id, cx));
+                            });
+                        }
+                    }
+                    WorkspaceUpdateEvent::SessionQueueUpdated { .. }
+                    | WorkspaceUpdateEvent::SessionCreationQueued { .. }
+                    | WorkspaceUpdateEvent::SessionRetrying { .. }
+                    | WorkspaceUpdateEvent::SessionOutputLimited { .. }
+                    | WorkspaceUpdateEvent::MemoryUpdated { .. }
+                    | WorkspaceUpdateEvent::FeedbackUpdated { .. }
+                    | WorkspaceUpdateEvent::CommentsUpdated { .. }
+                    | WorkspaceUpdateEvent::TurnTimed { .. } => {}
                 }
             }
         })
         .detach();
     }
 
+    /// Follow the replies agents stream, for the tasks' last messages
+    fn subscribe_to_session_updates(entity: &Entity<Self>, cx: &mut App) {
+        let session_bus = AppState::global(cx).session_bus.clone();
+        let entity_weak = entity.downgrade();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        // `None` when a new prompt starts the next reply
+        session_bus.subscribe(move |event| {
+            let text = match event.update.as_ref() {
+                SessionUpdate::AgentMessageChunk(ContentChunk {
+                    content: ContentBlock::Text(text),
+                    ..
+                }) => Some(text.text.clone()),
+                SessionUpdate::UserMessageChunk(_) => None,
+                _ => return,
+            };
+            let _ = tx.send((event.session_id.clone(), text));
+        });
+
+        cx.spawn(async move |cx| {
+            while let Some((session_id, text)) = rx.recv().await {
+                let Some(entity) = entity_weak.upgrade() else {
+                    break;
+                };
+                cx.update(|cx| {
+                    entity.update(cx, |this, cx| match text {
+                        Some(text) => this.append_reply(&session_id, &text, cx),
+                        None => {
+                            this.replies.remove(&session_id);
+                        }
+                    });
+                });
+            }
+        })
+        .detach();
+    }
+
     fn ensure_selected_task_valid(&mut self) {
         let selected_is_valid = self.selected_task_id.as_ref().is_some_and(|id| {
             self.workspaces
@@ -548,33 +727,20 @@ impl TaskPanel {
         status: SessionStatus,
         cx: &mut Context<Self>,
     ) {
-        let mut updated = false;
         let mut task_id_to_update: Option<String> = None;
 
         // Update local state for immediate UI feedback
-        for workspace in &mut self.workspaces {
-            for task in &mut workspace.tasks {
-                if task.session_id.as_deref() != Some(session_id) {
-                    continue;
-                }
-
-                if task.status == status {
-                    continue;
-                }
-
+        let updated = self.update_tasks(
+            |task| task.session_id.as_deref() == Some(session_id) && task.status != status,
+            |task| {
                 // Store task_id for persistence
                 task_id_to_update = Some(task.id.clone());
-
-                let mut updated_task = (**task).clone();
-                updated_task.status = status.clone();
-                *task = Rc::new(updated_task);
-                updated = true;
-            }
-        }
+                task.set_status(status.clone());
+            },
+            cx,
+        );
 
         if updated {
-            cx.notify();
-
             // Persist status to JSON file
             if let Some(task_id) = task_id_to_update {
                 if let Some(workspace_service) = AppState::global(cx).workspace_service() {
@@ -599,6 +765,100 @@ impl TaskPanel {
         }
     }
 
+    /// Change the loaded tasks matching `matches` and re-render
+    ///
+    /// Tasks still shared with an element being rendered are copied first
+    /// (`Rc::make_mut`), so changes always apply.
+    fn update_tasks(
+        &mut self,
+        matches: impl Fn(&WorkspaceTask) -> bool,
+        mut update: impl FnMut(&mut WorkspaceTask),
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let mut updated = false;
+        for task in self.workspaces.iter_mut().flat_map(|w| w.tasks.iter_mut()) {
+            if matches(task) {
+                update(Rc::make_mut(task));
+                updated = true;
+            }
+        }
+        if updated {
+            cx.notify();
+        }
+        updated
+    }
+
+    /// Show the reply streaming in `session_id` as its task's last message
+    fn append_reply(&mut self, session_id: &str, text: &str, cx: &mut Context<Self>) {
+        let reply = self.replies.entry(session_id.to_string()).or_default();
+        reply.push_str(text);
+        let Some(preview) = message_preview(reply) else {
+            return;
+        };
+        self.update_tasks(
+            |task| {
+                task.has_session(session_id)
+                    && task.last_message.as_deref() != Some(preview.as_str())
+            },
+            |task| task.update_last_message(preview.clone()),
+            cx,
+        );
+    }
+
+    /// Keep the preview of a finished reply, so reloading the list shows it
+    fn finish_reply(&mut self, session_id: &str, cx: &mut Context<Self>) {
+        let Some(reply) = self.replies.remove(session_id) else {
+            return;
+        };
+        let (Some(preview), Some(workspace_service)) = (
+            message_preview(&reply),
+            AppState::global(cx).workspace_service().cloned(),
+        ) else {
+            return;
+        };
+        let session_id = session_id.to_string();
+        cx.spawn(async move |_, _| {
+            if let Err(e) = workspace_service
+                .update_task_message(&session_id, preview)
+                .await
+            {
+                log::warn!("Failed to keep last message of {}: {}", session_id, e);
+            }
+        })
+        .detach();
+    }
+
+    /// Count a reply in `session_id` as unread unless its task is open
+    fn mark_unread(&mut self, session_id: String, cx: &mut Context<Self>) {
+        if self.open_sessions.contains(&session_id) {
+            return;
+        }
+        *self.unread.entry(session_id).or_default() += 1;
+        cx.notify();
+    }
+
+    /// Clear the unread replies of the task `session_id` belongs to
+    fn mark_read(&mut self, session_id: &str, cx: &mut Context<Self>) {
+        self.open_sessions = self
+            .workspaces
+            .iter()
+            .flat_map(|w| &w.tasks)
+            .map(|t| t.session_ids())
+            .find(|ids| ids.iter().any(|id| id == session_id))
+            .unwrap_or_else(|| vec![session_id.to_string()]);
+        for id in &self.open_sessions {
+            self.unread.remove(id);
+        }
+        cx.notify();
+    }
+
+    fn unread_count(&self, task: &WorkspaceTask) -> usize {
+        task.session_ids()
+            .iter()
+            .filter_map(|id| self.unread.get(id))
+            .sum()
+    }
+
     // ========================================================================
     // Event Handlers
     // ========================================================================
@@ -696,29 +956,427 @@ impl TaskPanel {
         .detach();
     }
 
-    fn remove_task(&mut self, task_id: String, cx: &mut Context<Self>) {
-        let workspace_service = match AppState::global(cx).workspace_service() {
-            Some(service) => service.clone(),
-            None => {
-                log::warn!("WorkspaceService not available");
-                return;
-            }
+    /// Delete tasks together with their sessions
+    ///
+    /// Sessions are dropped from the AgentService and their history files are
+    /// moved to the sessions trash directory before the tasks are removed.
+    fn delete_tasks(&mut self, task_ids: Vec<String>, cx: &mut Context<Self>) {
+        let state = AppState::global(cx);
+        let Some(workspace_service) = state.workspace_service().cloned() else {
+            log::warn!("WorkspaceService not available");
+            return;
         };
+        let agent_service = state.agent_service().cloned();
+        let persistence_service = state.persistence_service().cloned();
 
-        cx.spawn(async move |_entity, _cx| {
-            match workspace_service.remove_task(&task_id).await {
-                Ok(_) => {
-                    log::info!("Successfully removed task: {}", task_id);
-                    // The UI will be updated via the TaskRemoved event
+        cx.spawn(async move |entity, cx| {
+            let mut stale = false;
+            for task_id in task_ids {
+                if let Some(task) = workspace_service.get_task(&task_id).await {
+                    let sessions = task.session_id.iter().chain(task.agent_sessions.values());
+                    for session_id in sessions {
+                        if let Some(agent_service) = &agent_service
+                            && let Err(e) = agent_service.remove_session(session_id).await
+                        {
+                            log::warn!("Failed to remove session {}: {}", session_id, e);
+                        }
+                        if let Some(persistence_service) = &persistence_service
+                            && let Err(e) = persistence_service.trash_session(session_id).await
+                        {
+                            log::error!("Failed to move session {} to trash: {}", session_id, e);
+                        }
+                    }
                 }
-                Err(e) => {
-                    log::error!("Failed to remove task: {}", e);
+
+                match workspace_service.remove_task(&task_id).await {
+                    Ok(_) => {
+                        log::info!("Successfully removed task: {}", task_id);
+                        // The UI will be updated via the TaskRemoved event
+                    }
+                    Err(ServiceError::TaskNotFound(_)) => {
+                        log::warn!("Task {} no longer exists", task_id);
+                        stale = true;
+                    }
+                    Err(e) => {
+                        log::error!("Failed to remove task: {}", e);
+                    }
+                }
+            }
+
+            // Already gone on disk; drop the stale entries from the list
+            if stale {
+                cx.update(|cx| {
+                    if let Some(entity_strong) = entity.upgrade() {
+                        Self::load_workspace_data(&entity_strong, workspace_service.clone(), cx);
+                    }
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Ask before deleting all tasks of a section (a workspace or a day)
+    fn confirm_delete_tasks(
+        entity: Entity<Self>,
+        section: String,
+        task_ids: Vec<String>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        if task_ids.is_empty() {
+            return;
+        }
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let entity = entity.clone();
+            let task_ids = task_ids.clone();
+            dialog
+                .title(t!("task_panel.delete_all.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("task_panel.delete_all.ok").to_string())
+                        .ok_variant(ButtonVariant::Danger)
+                        .cancel_text(t!("task_panel.delete_all.cancel").to_string()),
+                )
+                .on_ok(move |_, _, cx| {
+                    entity.update(cx, |this, cx| this.delete_tasks(task_ids.clone(), cx));
+                    true
+                })
+                .child(
+                    div().text_sm().child(
+                        t!(
+                            "task_panel.delete_all.message",
+                            count = task_ids.len(),
+                            section = section
+                        )
+                        .to_string(),
+                    ),
+                )
+        });
+    }
+
+    /// Right-click menu of a task row
+    fn task_actions(task: &WorkspaceTask, entity: Entity<Self>, cx: &App) -> ContextActions {
+        let task_id = task.id.clone();
+        let session_id = task.session_id.clone();
+        let shared = session_id.as_deref().is_some_and(|session_id| {
+            AppState::global(cx)
+                .share_service()
+                .is_some_and(|service| service.is_shared(session_id))
+        });
+        ContextActions::new()
+            .action(t!("task_panel.task.open").to_string(), IconName::ArrowRight, {
+                let (entity, task_id) = (entity.clone(), task_id.clone());
+                move |window, cx| {
+                    entity.update(cx, |this, cx| {
+                        this.select_task(task_id.clone(), cx);
+                        this.open_task_in_current_panel(&task_id, window, cx);
+                    });
                 }
+            })
+            .action(t!("task_panel.task.open_new").to_string(), IconName::Plus, {
+                let (entity, task_id) = (entity.clone(), task_id.clone());
+                move |window, cx| {
+                    entity.update(cx, |this, cx| {
+                        this.open_task_in_new_panel(&task_id, window, cx);
+                    });
+                }
+            })
+            .when_some(session_id, |actions, session_id| {
+                actions.action(
+                    t!("task_panel.task.copy_session_id").to_string(),
+                    IconName::Copy,
+                    move |_, cx| {
+                        cx.write_to_clipboard(ClipboardItem::new_string(session_id.clone()));
+                    },
+                )
+            })
+            .when_some(task.session_id.clone(), |actions, session_id| {
+                if shared {
+                    actions
+                        .action(
+                            t!("task_panel.task.copy_share_link").to_string(),
+                            IconName::Copy,
+                            {
+                                let session_id = session_id.clone();
+                                move |window, cx| Self::copy_share_link(&session_id, window, cx)
+                            },
+                        )
+                        .action(
+                            t!("task_panel.task.stop_sharing").to_string(),
+                            IconName::Close,
+                            {
+                                let entity = entity.clone();
+                                move |window, cx| {
+                                    entity.update(cx, |this, cx| {
+                                        this.stop_sharing(&session_id, window, cx)
+                                    });
+                                }
+                            },
+                        )
+                } else {
+                    let (entity, title) = (entity.clone(), task.name.clone());
+                    actions.action(
+                        t!("task_panel.task.share_live").to_string(),
+                        IconName::Globe,
+                        move |window, cx| {
+                            entity.update(cx, |this, cx| {
+                                this.share_live(session_id.clone(), title.clone(), window, cx)
+                            });
+                        },
+                    )
+                }
+            })
+            .action(
+                if task.locked {
+                    t!("task_panel.task.unlock").to_string()
+                } else {
+                    t!("task_panel.task.lock").to_string()
+                },
+                Icon::new(if task.locked {
+                    crate::assets::Icon::LockOpen
+                } else {
+                    crate::assets::Icon::Lock
+                }),
+                {
+                    let (entity, task_id, locked) = (entity.clone(), task_id.clone(), !task.locked);
+                    move |_, cx| {
+                        entity.update(cx, |this, cx| this.set_task_locked(&task_id, locked, cx));
+                    }
+                },
+            )
+            .separator()
+            .action(
+                t!("task_panel.task.delete").to_string(),
+                Icon::new(crate::assets::Icon::Trash2),
+                move |_, cx| {
+                    entity.update(cx, |this, cx| this.delete_tasks(vec![task_id.clone()], cx));
+                },
+            )
+    }
+
+    /// Lock a task (read-only) or unlock it; the row updates on `TaskLockChanged`
+    fn set_task_locked(&mut self, task_id: &str, locked: bool, cx: &mut Context<Self>) {
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            return;
+        };
+        let task_id = task_id.to_string();
+        cx.spawn(async move |_, _| {
+            if let Err(e) = workspace_service.set_task_locked(&task_id, locked).await {
+                log::error!("Failed to lock task {}: {}", task_id, e);
             }
         })
         .detach();
     }
 
+    /// Keep the workspace's sessions in its repository, or move them back
+    fn set_repo_sessions(
+        &mut self,
+        workspace_id: String,
+        enabled: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            return;
+        };
+        cx.spawn_in(window, async move |entity, window| {
+            let result = workspace_service
+                .set_repo_sessions(&workspace_id, enabled)
+                .await;
+            _ = window.update(|window, cx| {
+                struct RepoSessionsResult;
+                let note = match result {
+                    Ok(()) if enabled => Notification::success(
+                        t!("task_panel.workspace.sessions_in_repo").to_string(),
+                    ),
+                    Ok(()) => Notification::success(
+                        t!("task_panel.workspace.sessions_in_app_data").to_string(),
+                    ),
+                    Err(e) => {
+                        log::error!("Failed to move sessions of {}: {}", workspace_id, e);
+                        Notification::error(
+                            t!("task_panel.workspace.sessions_move_failed", error = e.to_string())
+                                .to_string(),
+                        )
+                    }
+                };
+                window.show_notification(note.id::<RepoSessionsResult>(), cx);
+                if let Some(entity) = entity.upgrade() {
+                    Self::load_workspace_data(&entity, workspace_service.clone(), cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Ask for the language agents of a workspace respond in
+    fn edit_response_language(
+        entity: Entity<Self>,
+        workspace: &WorkspaceGroup,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let workspace_id = workspace.id.clone();
+        let language = workspace.response_language.clone().unwrap_or_default();
+        let language_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx)
+                .placeholder(t!("task_panel.response_language.placeholder").to_string());
+            state.set_value(language, window, cx);
+            state
+        });
+        let check = Rc::new(Cell::new(workspace.check_response_language));
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let entity = entity.clone();
+            let workspace_id = workspace_id.clone();
+            let language_input = language_input.clone();
+            dialog
+                .title(t!("task_panel.response_language.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("task_panel.response_language.ok").to_string())
+                        .cancel_text(t!("task_panel.response_language.cancel").to_string()),
+                )
+                .on_ok({
+                    let (language_input, check) = (language_input.clone(), check.clone());
+                    move |_, window, cx| {
+                        let language = language_input.read(cx).text().to_string();
+                        entity.update(cx, |this, cx| {
+                            this.set_response_language(
+                                workspace_id.clone(),
+                                language,
+                                check.get(),
+                                window,
+                                cx,
+                            );
+                        });
+                        true
+                    }
+                })
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .p_4()
+                        .child(
+                            div()
+                                .text_sm()
+                                .child(t!("task_panel.response_language.message").to_string()),
+                        )
+                        .child(Input::new(&language_input))
+                        .child(
+                            Checkbox::new("response-language-check")
+                                .label(t!("task_panel.response_language.check").to_string())
+                                .checked(check.get())
+                                .on_click({
+                                    let check = check.clone();
+                                    move |checked: &bool, window, _| {
+                                        check.set(*checked);
+                                        window.refresh();
+                                    }
+                                }),
+                        ),
+                )
+        });
+    }
+
+    /// Save the response language of a workspace; an empty one clears it
+    fn set_response_language(
+        &mut self,
+        workspace_id: String,
+        language: String,
+        check: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            return;
+        };
+        cx.spawn_in(window, async move |entity, window| {
+            let result = workspace_service
+                .set_response_language(&workspace_id, Some(language), check)
+                .await;
+            _ = window.update(|window, cx| {
+                if let Err(e) = result {
+                    log::error!("Failed to set response language of {}: {}", workspace_id, e);
+                    struct ResponseLanguageError;
+                    let note = Notification::error(
+                        t!("task_panel.response_language.failed", error = e.to_string())
+                            .to_string(),
+                    );
+                    window.show_notification(note.id::<ResponseLanguageError>(), cx);
+                }
+                if let Some(entity) = entity.upgrade() {
+                    Self::load_workspace_data(&entity, workspace_service.clone(), cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Serve a read-only live view of the session and copy its link
+    fn share_live(
+        &mut self,
+        session_id: String,
+        title: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(share_service) = AppState::global(cx).share_service().cloned() else {
+            return;
+        };
+        cx.spawn_in(window, async move |entity, window| {
+            let result = share_service.share(&session_id, &title).await;
+            _ = window.update(|window, cx| {
+                struct ShareResult;
+                let note = match result {
+                    Ok(link) => {
+                        cx.write_to_clipboard(ClipboardItem::new_string(link.url.clone()));
+                        Notification::success(
+                            t!("task_panel.task.share_started", url = link.url).to_string(),
+                        )
+                    }
+                    Err(e) => {
+                        log::error!("Failed to share session {}: {:#}", session_id, e);
+                        Notification::error(
+                            t!("task_panel.task.share_failed", error = format!("{:#}", e))
+                                .to_string(),
+                        )
+                    }
+                };
+                window.show_notification(note.id::<ShareResult>(), cx);
+                _ = entity.update(cx, |_, cx| cx.notify());
+            });
+        })
+        .detach();
+    }
+
+    fn copy_share_link(session_id: &str, window: &mut Window, cx: &mut App) {
+        let Some(link) = AppState::global(cx)
+            .share_service()
+            .and_then(|service| service.link_for(session_id))
+        else {
+            return;
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(link.url));
+        struct ShareLinkCopied;
+        let note = Notification::success(t!("task_panel.task.share_link_copied").to_string());
+        window.show_notification(note.id::<ShareLinkCopied>(), cx);
+    }
+
+    fn stop_sharing(&mut self, session_id: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(share_service) = AppState::global(cx).share_service().cloned() else {
+            return;
+        };
+        if share_service.stop(session_id) {
+            struct ShareStopped;
+            let note = Notification::info(t!("task_panel.task.share_stopped").to_string());
+            window.show_notification(note.id::<ShareStopped>(), cx);
+        }
+        cx.notify();
+    }
+
     fn select_task(&mut self, task_id: String, cx: &mut Context<Self>) {
         self.selected_task_id = Some(task_id);
         cx.notify();
@@ -879,6 +1537,9 @@ impl TaskPanel {
                         path: workspace.path.clone(),
                         tasks: filtered_tasks,
                         is_expanded: workspace.is_expanded,
+                        repo_sessions: workspace.repo_sessions,
+                        response_language: workspace.response_language.clone(),
+                        check_response_language: workspace.check_response_language,
                     })
                 } else {
                     None
@@ -1005,6 +1666,8 @@ impl TaskPanel {
             .id("task-tree-scroll")
             .flex_1()
             .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
+            .on_scroll_wheel(cx.listener(|this, _, _, cx| this.load_more_if_near_end(cx)))
             // Show loading indicator when loading
             .when(self.is_loading, |this| {
                 this.child(
@@ -1034,9 +1697,45 @@ impl TaskPanel {
                         self.render_workspace_group(workspace, entity.clone(), cx)
                     }),
                 )
+                .children(self.render_load_more(cx))
             })
     }
 
+    /// Row at the end of the list while older tasks are left to load
+    fn render_load_more(&self, cx: &Context<Self>) -> Option<impl IntoElement> {
+        if self.eof {
+            return None;
+        }
+        let theme = cx.theme();
+        let row = h_flex()
+            .w_full()
+            .justify_center()
+            .items_center()
+            .py_2()
+            .gap_2();
+        Some(if self.loading {
+            row.child(
+                Icon::new(IconName::Loader)
+                    .size_4()
+                    .text_color(theme.muted_foreground),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .child(t!("task_panel.loading_more").to_string()),
+            )
+        } else {
+            row.child(
+                Button::new("load-older-tasks")
+                    .label(t!("task_panel.load_more").to_string())
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _, _, cx| this.load_more(false, cx))),
+            )
+        })
+    }
+
     fn render_workspace_group(
         &self,
         workspace: &WorkspaceGroup,
@@ -1048,9 +1747,9 @@ impl TaskPanel {
         let is_expanded = workspace.is_expanded;
         let workspace_name = workspace.name.clone();
 
-        // Sort tasks by created_at descending (newest first)
+        // Most recently updated first
         let mut sorted_tasks = workspace.tasks.clone();
-        sorted_tasks.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        sorted_tasks.sort_by(|a, b| b.last_updated.cmp(&a.last_updated));
 
         v_flex()
             .w_full()
@@ -1095,6 +1794,11 @@ impl TaskPanel {
                     .child(h_flex().gap_2().items_center().child({
                         let workspace_id = workspace_id.clone();
                         let workspace_path = workspace.path.clone();
+                        let workspace_name = workspace.name.clone();
+                        let repo_sessions = workspace.repo_sessions;
+                        let workspace_group = workspace.clone();
+                        let task_ids: Vec<String> =
+                            workspace.tasks.iter().map(|task| task.id.clone()).collect();
                         let entity = entity.clone();
                         Button::new(SharedString::from(format!(
                             "workspace-menu-{}",
@@ -1195,15 +1899,78 @@ impl TaskPanel {
                                 }),
                             );
 
-                            menu.separator().item(
-                                PopupMenuItem::new(t!("task_panel.workspace.remove").to_string())
+                            let repo_sessions_label = if repo_sessions {
+                                t!("task_panel.workspace.sessions_to_app_data")
+                            } else {
+                                t!("task_panel.workspace.sessions_to_repo")
+                            };
+                            menu = menu.item(
+                                PopupMenuItem::new(repo_sessions_label.to_string())
+                                    .icon(IconName::File)
+                                    .on_click({
+                                        let entity = entity.clone();
+                                        let workspace_id = workspace_id.clone();
+                                        move |_, window, cx| {
+                                            entity.update(cx, |this, cx| {
+                                                this.set_repo_sessions(
+                                                    workspace_id.clone(),
+                                                    !repo_sessions,
+                                                    window,
+                                                    cx,
+                                                );
+                                            });
+                                        }
+                                    }),
+                            );
+                            menu = menu.item(
+                                PopupMenuItem::new(
+                                    t!("task_panel.workspace.response_language").to_string(),
+                                )
+                                .icon(IconName::Globe)
+                                .on_click({
+                                    let entity = entity.clone();
+                                    let workspace_group = workspace_group.clone();
+                                    move |_, window, cx| {
+                                        Self::edit_response_language(
+                                            entity.clone(),
+                                            &workspace_group,
+                                            window,
+                                            cx,
+                                        );
+                                    }
+                                }),
+                            );
+
+                            let delete_entity = entity.clone();
+                            let workspace_name = workspace_name.clone();
+                            let task_ids = task_ids.clone();
+                            menu.separator()
+                                .item(
+                                    PopupMenuItem::new(
+                                        t!("task_panel.workspace.delete_tasks").to_string(),
+                                    )
+                                    .icon(Icon::new(crate::assets::Icon::Trash2))
+                                    .on_click(move |_, window, cx| {
+                                        Self::confirm_delete_tasks(
+                                            delete_entity.clone(),
+                                            workspace_name.clone(),
+                                            task_ids.clone(),
+                                            window,
+                                            cx,
+                                        );
+                                    }),
+                                )
+                                .item(
+                                    PopupMenuItem::new(
+                                        t!("task_panel.workspace.remove").to_string(),
+                                    )
                                     .icon(Icon::new(crate::assets::Icon::Trash2))
                                     .on_click(move |_, _, cx| {
                                         entity.update(cx, |this, cx| {
                                             this.remove_workspace(workspace_id.clone(), cx);
                                         });
                                     }),
-                            )
+                                )
                         })
                     })),
             )
@@ -1237,10 +2004,9 @@ impl TaskPanel {
             .hover(|s| s.bg(theme.accent.opacity(0.3)))
             .on_click(cx.listener(move |_this, _, window, cx| {
                 window.dispatch_action(
-                    Box::new(PanelAction::add_welcome(
-                        Some(workspace_id.clone()),
-                        gpui_component::dock::DockPlacement::Center,
-                    )),
+                    Box::new(NewTask {
+                        workspace_id: Some(workspace_id.clone()),
+                    }),
                     cx,
                 );
             }))
@@ -1266,6 +2032,10 @@ impl TaskPanel {
         let theme = cx.theme();
         let task_id = task.id.clone();
         let is_selected = self.selected_task_id.as_ref() == Some(&task_id);
+        let active_time = (task.active_ms >= 1000).then(|| {
+            let time = utils::time::format_duration_short(task.active_ms);
+            t!("task_panel.task.active_time", time = time).to_string()
+        });
 
         v_flex()
             .id(SharedString::from(format!("task-{}", task_id)))
@@ -1293,7 +2063,7 @@ impl TaskPanel {
                     this.handle_task_click(task_id.clone(), event.click_count(), window, cx);
                 }
             }))
-            // First row: status icon + task name + relative time
+            // First row: agent avatar + task name + unread badge + relative time
             .child(
                 h_flex()
                     .w_full()
@@ -1305,7 +2075,7 @@ impl TaskPanel {
                             .items_center()
                             .min_w_0()
                             .flex_1()
-                            .child(StatusIndicator::new(task.status.clone()).size(8.0))
+                            .child(self.render_agent_avatar(task, 16., cx))
                             .child(
                                 div()
                                     .text_sm()
@@ -1313,15 +2083,29 @@ impl TaskPanel {
                                     .overflow_x_hidden()
                                     .text_ellipsis()
                                     .child(task.name.clone()),
-                            ),
+                            )
+                            .when(task.locked, |this| {
+                                this.child(
+                                    Icon::new(crate::assets::Icon::Lock)
+                                        .size(px(12.))
+                                        .flex_none()
+                                        .text_color(theme.muted_foreground),
+                                )
+                            }),
                     )
                     .child(
-                        div()
-                            .text_xs()
-                            .text_color(theme.muted_foreground)
-                            .min_w(px(60.0)) // Fixed width to prevent layout shift
-                            .text_right()
-                            .child(self.format_relative_time(&task.created_at)),
+                        h_flex()
+                            .gap_1()
+                            .flex_none()
+                            .children(self.render_unread_badge(task, cx))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.muted_foreground)
+                                    .min_w(px(60.0)) // Fixed width to prevent layout shift
+                                    .text_right()
+                                    .child(self.format_relative_time(&task.last_updated)),
+                            ),
                     ),
             )
             // Second row: agent name + last message + status badge (aligned with task name)
@@ -1343,11 +2127,13 @@ impl TaskPanel {
                                     .text_xs()
                                     .text_color(theme.muted_foreground)
                                     .child(
-                                        div()
-                                            .overflow_x_hidden()
-                                            .text_ellipsis()
-                                            .child(task.agent_name.clone()),
+                                        div().overflow_x_hidden().text_ellipsis().child(
+                                            AppState::global(cx).agent_label(&task.agent_name),
+                                        ),
                                     )
+                                    .when_some(active_time, |this, time| {
+                                        this.child("·").child(div().flex_none().child(time))
+                                    })
                                     .child("·")
                                     .when_some(task.last_message.clone(), |this, msg| {
                                         this.child(
@@ -1359,19 +2145,7 @@ impl TaskPanel {
                     .child(self.render_status_badge(&task.status, cx)),
             )
             // Right-click context menu
-            .context_menu(move |menu, _, _| {
-                let task_id = task_id.clone();
-                let entity = entity.clone();
-                menu.item(
-                    PopupMenuItem::new(t!("task_panel.task.delete").to_string())
-                        .icon(Icon::new(crate::assets::Icon::Trash2))
-                        .on_click(move |_, _, cx| {
-                            entity.update(cx, |this, cx| {
-                                this.remove_task(task_id.clone(), cx);
-                            });
-                        }),
-                )
-            })
+            .context_menu(Self::task_actions(task, entity, cx).into_builder())
     }
 
     // ========================================================================
@@ -1389,29 +2163,34 @@ impl TaskPanel {
             .flat_map(|w| w.tasks.clone())
             .collect();
 
-        all_tasks.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        all_tasks.sort_by(|a, b| b.last_updated.cmp(&a.last_updated));
 
         let now = Local::now().date_naive();
 
         let today: Vec<_> = all_tasks
             .iter()
-            .filter(|t| t.created_at.with_timezone(&Local).date_naive() == now)
+            .filter(|t| t.last_updated.with_timezone(&Local).date_naive() == now)
             .collect();
 
         let yesterday: Vec<_> = all_tasks
             .iter()
-            .filter(|t| t.created_at.with_timezone(&Local).date_naive() == now - Duration::days(1))
+            .filter(|t| {
+                t.last_updated.with_timezone(&Local).date_naive() == now - Duration::days(1)
+            })
             .collect();
 
         let older: Vec<_> = all_tasks
             .iter()
-            .filter(|t| t.created_at.with_timezone(&Local).date_naive() < now - Duration::days(1))
+            .filter(|t| t.last_updated.with_timezone(&Local).date_naive() < now - Duration::days(1))
             .collect();
 
         v_flex()
+            .id("task-timeline-scroll")
             .flex_1()
             .min_h_0()
-            .overflow_y_scrollbar()
+            .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
+            .on_scroll_wheel(cx.listener(|this, _, _, cx| this.load_more_if_near_end(cx)))
             .when(!today.is_empty(), |this| {
                 this.child(self.render_time_group(
                     t!("task_panel.group.today").to_string(),
@@ -1436,6 +2215,7 @@ impl TaskPanel {
                     cx,
                 ))
             })
+            .children(self.render_load_more(cx))
     }
 
     fn render_time_group(
@@ -1446,11 +2226,15 @@ impl TaskPanel {
         cx: &Context<Self>,
     ) -> impl IntoElement {
         let theme = cx.theme();
+        let task_ids: Vec<String> = tasks.iter().map(|task| task.id.clone()).collect();
+        let menu_entity = entity.clone();
+        let menu_label = label.clone();
 
         v_flex()
             .w_full()
             .child(
                 div()
+                    .id(SharedString::from(format!("time-group-{}", label)))
                     .px_3()
                     .py_2()
                     .bg(theme.sidebar.opacity(0.95))
@@ -1462,6 +2246,23 @@ impl TaskPanel {
                             .font_medium()
                             .text_color(theme.muted_foreground)
                             .child(label.to_uppercase()),
+                    )
+                    .context_menu(
+                        ContextActions::new()
+                            .action(
+                                t!("task_panel.time.delete_tasks").to_string(),
+                                Icon::new(crate::assets::Icon::Trash2),
+                                move |window, cx| {
+                                    Self::confirm_delete_tasks(
+                                        menu_entity.clone(),
+                                        menu_label.clone(),
+                                        task_ids.clone(),
+                                        window,
+                                        cx,
+                                    );
+                                },
+                            )
+                            .into_builder(),
                     ),
             )
             .children(
@@ -1523,12 +2324,18 @@ impl TaskPanel {
                             .child(task.name.clone()),
                     )
                     .child(
-                        div()
-                            .text_xs()
-                            .text_color(theme.muted_foreground)
-                            .min_w(px(60.0)) // Fixed width to prevent layout shift
-                            .text_right()
-                            .child(self.format_relative_time(&task.created_at)),
+                        h_flex()
+                            .gap_1()
+                            .flex_none()
+                            .children(self.render_unread_badge(task, cx))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.muted_foreground)
+                                    .min_w(px(60.0)) // Fixed width to prevent layout shift
+                                    .text_right()
+                                    .child(self.format_relative_time(&task.last_updated)),
+                            ),
                     ),
             )
             .child(
@@ -1544,13 +2351,20 @@ impl TaskPanel {
                             .flex_1()
                             .text_xs()
                             .text_color(theme.muted_foreground)
-                            .child(StatusIndicator::new(task.status.clone()).size(6.0))
+                            .child(self.render_agent_avatar(task, 14., cx))
                             .child(
                                 div()
                                     .overflow_x_hidden()
                                     .text_ellipsis()
-                                    .child(task.agent_name.clone()),
+                                    .child(AppState::global(cx).agent_label(&task.agent_name)),
                             )
+                            .when(task.locked, |this| {
+                                this.child(
+                                    Icon::new(crate::assets::Icon::Lock)
+                                        .size(px(10.))
+                                        .flex_none(),
+                                )
+                            })
                             .when_some(task.last_message.clone(), |this, msg| {
                                 this.child("·")
                                     .child(div().overflow_x_hidden().text_ellipsis().child(msg))
@@ -1559,31 +2373,19 @@ impl TaskPanel {
                     .child(self.render_status_badge(&task.status, cx)),
             )
             // Right-click context menu
-            .context_menu(move |menu, _, _| {
-                let task_id = task_id.clone();
-                let entity = entity.clone();
-                menu.item(
-                    PopupMenuItem::new(t!("task_panel.task.delete").to_string())
-                        .icon(Icon::new(crate::assets::Icon::Trash2))
-                        .on_click(move |_, _, cx| {
-                            entity.update(cx, |this, cx| {
-                                this.remove_task(task_id.clone(), cx);
-                            });
-                        }),
-                )
-            })
+            .context_menu(Self::task_actions(task, entity, cx).into_builder())
     }
 
     // ========================================================================
     // Time formatting helpers
     // ========================================================================
 
-    fn format_relative_time(&self, created_at: &chrono::DateTime<chrono::Utc>) -> String {
+    fn format_relative_time(&self, time: &chrono::DateTime<chrono::Utc>) -> String {
         use chrono::Local;
 
         let now = Local::now();
-        let created_local = created_at.with_timezone(&Local);
-        let duration = now.signed_duration_since(created_local);
+        let local = time.with_timezone(&Local);
+        let duration = now.signed_duration_since(local);
 
         let minutes = duration.num_minutes();
         let hours = duration.num_hours();
@@ -1621,6 +2423,50 @@ impl TaskPanel {
     // Render - Status helpers
     // ========================================================================
 
+    /// The agent's avatar with the session's status dot on its corner
+    fn render_agent_avatar(&self, task: &WorkspaceTask, size: f32, cx: &App) -> impl IntoElement {
+        div()
+            .relative()
+            .flex_none()
+            .size(px(size))
+            .child(AgentAvatar::new(task.agent_name.clone(), cx).size(size))
+            .child(
+                div()
+                    .absolute()
+                    .right(px(-2.))
+                    .bottom(px(-2.))
+                    .child(StatusIndicator::new(task.status.clone()).size(size * 0.45)),
+            )
+    }
+
+    fn render_unread_badge(&self, task: &WorkspaceTask, cx: &App) -> Option<impl IntoElement> {
+        let count = self.unread_count(task);
+        if count == 0 {
+            return None;
+        }
+        let theme = cx.theme();
+        Some(
+            div()
+                .flex_none()
+                .min_w(px(16.))
+                .h(px(16.))
+                .px_1()
+                .flex()
+                .items_center()
+                .justify_center()
+                .rounded_full()
+                .bg(theme.primary)
+                .text_color(theme.primary_foreground)
+                .text_size(px(10.))
+                .font_semibold()
+                .child(if count > 99 {
+                    "99+".to_string()
+                } else {
+                    count.to_string()
+                }),
+        )
+    }
+
     fn render_status_badge(&self, status: &SessionStatus, cx: &Context<Self>) -> impl IntoElement {
         let theme = cx.theme();
         let (label, color) = match status {
@@ -1,14 +1,27 @@
+use std::collections::HashMap;
+
 use gpui::{
     AnyElement, App, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement,
     ParentElement, Render, ScrollHandle, SharedString, StatefulInteractiveElement, Styled, Window,
     div, prelude::*, px,
 };
 use gpui_component::{ActiveTheme, Icon, IconName, h_flex, text::TextView, v_flex};
+use rust_i18n::t;
 
 use agent_client_protocol::{ContentBlock, ToolCall, ToolCallContent};
 
-use crate::components::DiffView;
+use crate::components::{DiffView, JsonTree};
 use crate::panels::dock_panel::DockPanel;
+use crate::utils::json_tree::parse_structured;
+
+/// Structured values of a tool call shown as a JSON tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum JsonSource {
+    Input,
+    Output,
+    /// Text content at this index that is a JSON object or array
+    Content(usize),
+}
 
 /// Panel that displays detailed tool call content
 pub struct ToolCallDetailPanel {
@@ -16,6 +29,11 @@ pub struct ToolCallDetailPanel {
     scroll_handle: ScrollHandle,
     /// The tool call to display
     tool_call: Option<ToolCall>,
+    /// Trees of the structured values, made on render since their search
+    /// inputs need a window
+    json_trees: HashMap<JsonSource, Entity<JsonTree>>,
+    /// The tool call changed since the trees were updated
+    json_stale: bool,
 }
 
 impl ToolCallDetailPanel {
@@ -27,6 +45,8 @@ impl ToolCallDetailPanel {
             focus_handle,
             scroll_handle,
             tool_call: None,
+            json_trees: HashMap::new(),
+            json_stale: false,
         }
     }
 
@@ -48,20 +68,98 @@ impl ToolCallDetailPanel {
     // }
     /// Update the tool call to display
     pub fn update_tool_call(&mut self, tool_call: ToolCall, cx: &mut Context<Self>) {
-        self.tool_call = Some(tool_call);
+        self.set_tool_call(tool_call);
         cx.notify();
     }
     /// Setup the tool call to display
     pub fn set_tool_call(&mut self, tool_call: ToolCall) {
+        // Expanded nodes and searches belong to one tool call
+        let same_call = self
+            .tool_call
+            .as_ref()
+            .is_some_and(|current| current.tool_call_id == tool_call.tool_call_id);
+        if !same_call {
+            self.json_trees.clear();
+        }
         self.tool_call = Some(tool_call);
+        self.json_stale = true;
     }
 
     /// Clear the displayed tool call
     pub fn clear(&mut self, cx: &mut Context<Self>) {
         self.tool_call = None;
+        self.json_trees.clear();
         cx.notify();
     }
 
+    /// Structured values of the tool call: its raw input and output, and
+    /// text content that is JSON
+    fn json_values(tool_call: &ToolCall) -> Vec<(JsonSource, serde_json::Value)> {
+        let mut values = Vec::new();
+        if let Some(input) = &tool_call.raw_input {
+            values.push((JsonSource::Input, input.clone()));
+        }
+        if let Some(output) = &tool_call.raw_output {
+            values.push((JsonSource::Output, output.clone()));
+        }
+        for (ix, content) in tool_call.content.iter().enumerate() {
+            if let ToolCallContent::Content(c) = content
+                && let ContentBlock::Text(text) = &c.content
+                && let Some(value) = parse_structured(&text.text)
+            {
+                values.push((JsonSource::Content(ix), value));
+            }
+        }
+        values
+    }
+
+    /// Create or update the trees of the structured values
+    fn sync_json_trees(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.json_stale {
+            return;
+        }
+        self.json_stale = false;
+        let values = match &self.tool_call {
+            Some(tool_call) => Self::json_values(tool_call),
+            None => Vec::new(),
+        };
+
+        self.json_trees
+            .retain(|source, _| values.iter().any(|(shown, _)| shown == source));
+        for (source, value) in values {
+            match self.json_trees.get(&source) {
+                Some(tree) => tree.update(cx, |tree, cx| tree.set_value(value, cx)),
+                None => {
+                    let id = SharedString::from(format!("detail-json-{:?}", source));
+                    let tree = cx.new(|cx| JsonTree::new(id, value, window, cx));
+                    self.json_trees.insert(source, tree);
+                }
+            }
+        }
+    }
+
+    /// A titled JSON tree
+    fn render_json_section(
+        &self,
+        source: JsonSource,
+        title: String,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let tree = self.json_trees.get(&source)?;
+        let section = v_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                div()
+                    .text_size(px(13.))
+                    .font_weight(gpui::FontWeight::MEDIUM)
+                    .text_color(cx.theme().muted_foreground)
+                    .child(title),
+            )
+            .child(tree.clone());
+        Some(section.into_any_element())
+    }
+
     /// Render complete diff view using the DiffView component
     fn render_diff_view(
         &self,
@@ -93,10 +191,14 @@ impl ToolCallDetailPanel {
     /// Render content based on ToolCallContent type
     fn render_content(
         &self,
+        ix: usize,
         content: &ToolCallContent,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> AnyElement {
+        if let Some(tree) = self.json_trees.get(&JsonSource::Content(ix)) {
+            return tree.clone().into_any_element();
+        }
         match content {
             ToolCallContent::Content(c) => match &c.content {
                 ContentBlock::Text(text) => {
@@ -220,7 +322,12 @@ impl Focusable for ToolCallDetailPanel {
 
 impl Render for ToolCallDetailPanel {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.sync_json_trees(window, cx);
         let scroll_handle = self.scroll_handle.clone();
+        let input = t!("tool_call_detail_panel.input").to_string();
+        let output = t!("tool_call_detail_panel.output").to_string();
+        let input = self.render_json_section(JsonSource::Input, input, cx);
+        let output = self.render_json_section(JsonSource::Output, output, cx);
 
         div()
             .size_full()
@@ -259,9 +366,13 @@ impl Render for ToolCallDetailPanel {
                                                 ),
                                         )
                                         .child(div().w_full().h(px(1.)).bg(cx.theme().border))
-                                        .children(tool_call.content.iter().map(|content| {
-                                            self.render_content(content, window, cx)
-                                        })),
+                                        .children(input)
+                                        .children(tool_call.content.iter().enumerate().map(
+                                            |(ix, content)| {
+                                                self.render_content(ix, content, window, cx)
+                                            },
+                                        ))
+                                        .children(output),
                                 )
                             })
                             .when(self.tool_call.is_none(), |this| {
@@ -1,23 +1,38 @@
 use gpui::{
     App, AppContext, ClipboardEntry, Context, Entity, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, ParentElement, Render, SharedString, Styled, Subscription, Window, px,
+    IntoElement, ParentElement, Render, SharedString, Styled, Subscription, Window,
+    prelude::FluentBuilder as _, px,
 };
 use rust_i18n::t;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use gpui_component::{
-    ActiveTheme, IndexPath, StyledExt, WindowExt, input::InputState, list::ListState, notification::Notification, select::{SelectEvent, SelectState}, v_flex
+    ActiveTheme, IconName, IndexPath, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputState},
+    list::ListState,
+    menu::{DropdownMenu as _, PopupMenuItem},
+    notification::Notification,
+    select::{SelectEvent, SelectState},
+    v_flex,
 };
 
 use agent_client_protocol::{self as acp, AvailableCommand, ImageContent};
 
+use crate::app::focus_mode::NotificationExt as _;
 use crate::{
-    AppState, CreateTaskFromWelcome, WelcomeSession,
+    AppState, CompareAgents, CreateTaskFromWelcome, WelcomeSession,
     app::actions::AddCodeSelection,
     components::{
         AgentItem, ChatInputBox, FileItem, FilePickerDelegate, ModeSelectItem, ModelSelectItem,
     },
-    core::{config::McpServerConfig, services::AgentSessionInfo},
+    core::{
+        config::McpServerConfig,
+        mcp_proxy::{self, DisabledTools},
+        services::AgentSessionInfo,
+    },
+    utils::slash_command,
 };
 
 // File picker delegate is now imported from components module
@@ -29,10 +44,16 @@ const MAX_FILE_SUGGESTIONS: usize = 8;
 pub struct WelcomePanel {
     focus_handle: FocusHandle,
     input_state: Entity<InputState>,
+    /// Optional experiment label for the created task
+    label_state: Entity<InputState>,
     context_list: Entity<ListState<FilePickerDelegate>>,
     mode_select: Entity<SelectState<Vec<ModeSelectItem>>>,
     model_select: Entity<SelectState<Vec<ModelSelectItem>>>,
     agent_select: Entity<SelectState<Vec<AgentItem>>>,
+    /// Configured agents, offered for comparison
+    agent_names: Vec<String>,
+    /// Agents that get the task too, side by side with the selected one
+    compare_agents: Vec<String>,
     session_select: Entity<SelectState<Vec<String>>>,
     current_agent_name: Option<String>,
     current_session_id: Option<String>,
@@ -54,7 +75,8 @@ pub struct WelcomePanel {
     command_suggestions: Vec<AvailableCommand>,
     /// Whether to show command suggestions (input starts with /)
     show_command_suggestions: bool,
-    /// Selected command index for keyboard navigation
+    /// Argument hint for a completed command with no arguments yet
+    command_hint: Option<String>,
     _subscriptions: Vec<Subscription>,
     /// Available MCP servers (name, config)
     available_mcps: Vec<(String, McpServerConfig)>,
@@ -66,6 +88,10 @@ pub struct WelcomePanel {
     mcp_selection_overridden: bool,
     /// Whether we should recreate the session after MCP config changes
     pending_mcp_session_recreate: bool,
+    /// Tool names of the MCP servers whose tools were listed
+    mcp_tools: HashMap<String, Vec<String>>,
+    /// Tools left out of the session, by MCP server
+    disabled_mcp_tools: DisabledTools,
 }
 
 impl crate::panels::dock_panel::DockPanel for WelcomePanel {
@@ -170,6 +196,7 @@ impl WelcomePanel {
             &entity,
             AppState::global(cx).code_selection_bus.clone(),
             "WelcomePanel",
+            None,
             |panel, selection, cx| {
                 panel.code_selections.push(selection);
                 cx.notify();
@@ -351,6 +378,9 @@ impl WelcomePanel {
                 .soft_wrap(true) // Enable word wrapping
                 .placeholder(t!("welcome.input.placeholder").to_string())
         });
+        let label_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(t!("welcome.label.placeholder").to_string())
+        });
 
         // Get the working directory - use provided or get from AppState
         // If workspace_id is provided, we'll update it asynchronously in load_workspace_info
@@ -358,7 +388,8 @@ impl WelcomePanel {
             working_directory.unwrap_or_else(|| AppState::global(cx).current_working_dir().clone());
 
         let context_list = cx.new(|cx| {
-            let delegate = FilePickerDelegate::new(&working_dir);
+            let filter = AppState::global(cx).path_filter(&working_dir);
+            let delegate = FilePickerDelegate::new(&filter);
             ListState::new(delegate, window, cx).searchable(true)
         });
 
@@ -382,10 +413,13 @@ impl WelcomePanel {
         let mut panel = Self {
             focus_handle: cx.focus_handle(),
             input_state,
+            label_state,
             context_list,
             mode_select,
             model_select,
             agent_select,
+            agent_names: Vec::new(),
+            compare_agents: Vec::new(),
             session_select,
             current_agent_name: None,
             current_session_id: None,
@@ -403,12 +437,15 @@ impl WelcomePanel {
             file_suggestions: Vec::new(),
             command_suggestions: Vec::new(),
             show_command_suggestions: false,
+            command_hint: None,
             _subscriptions: Vec::new(),
             available_mcps: Vec::new(),
             selected_mcps: Vec::new(),
             mcp_selection_initialized: false,
             mcp_selection_overridden: false,
             pending_mcp_session_recreate: false,
+            mcp_tools: HashMap::new(),
+            disabled_mcp_tools: DisabledTools::new(),
         };
 
         // Load sessions for the initially selected agent if any
@@ -484,6 +521,8 @@ impl WelcomePanel {
         } else {
             self.available_mcps.push((name.to_string(), config.clone()));
         }
+        // The new configuration may offer other tools
+        self.mcp_tools.remove(name);
 
         self.on_mcp_servers_changed();
     }
@@ -491,19 +530,39 @@ impl WelcomePanel {
     fn remove_mcp_server(&mut self, name: &str) {
         self.available_mcps
             .retain(|(server_name, _)| server_name != name);
+        self.mcp_tools.remove(name);
+        self.disabled_mcp_tools.remove(name);
         self.on_mcp_servers_changed();
     }
 
-    fn collect_mcp_servers_from_selection(
-        available_mcps: &[(String, McpServerConfig)],
-        selected_mcps: &[String],
-    ) -> Vec<acp::McpServer> {
-        let selected_set: HashSet<&String> = selected_mcps.iter().collect();
-        available_mcps
-            .iter()
-            .filter(|(name, config)| config.enabled && selected_set.contains(name))
-            .map(|(name, config)| config.to_acp_mcp_server(name.clone()))
-            .collect()
+    /// List the tools of an MCP server so single tools can be disabled
+    fn list_mcp_tools(&mut self, server: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(service) = AppState::global(cx).mcp_service().cloned() else {
+            return;
+        };
+        cx.spawn_in(window, async move |this, window| {
+            let catalog = service.catalog(&server).await;
+            _ = this.update_in(window, |this, window, cx| match catalog {
+                Ok(catalog) => {
+                    let tools = catalog.tools.into_iter().map(|tool| tool.id).collect();
+                    this.mcp_tools.insert(server, tools);
+                    cx.notify();
+                }
+                Err(e) => {
+                    struct McpToolsError;
+                    let message = t!(
+                        "welcome.mcp_tools_failed",
+                        server = server,
+                        error = format!("{:#}", e)
+                    );
+                    window.show_notification(
+                        Notification::error(message.to_string()).id::<McpToolsError>(),
+                        cx,
+                    );
+                }
+            });
+        })
+        .detach();
     }
 
     /// Try to refresh agents list from AppState
@@ -523,6 +582,8 @@ impl WelcomePanel {
             _ = window.update(|window, cx| {
                 if let Some(this) = weak_self.upgrade() {
                     this.update(cx, |this, cx| {
+                        this.compare_agents.retain(|agent| agents.contains(agent));
+                        this.agent_names = agents.clone();
                         if agents.is_empty() {
                             this.has_agents = false;
                             agent_select.update(cx, |state, cx| {
@@ -896,6 +957,7 @@ impl WelcomePanel {
                 self.show_command_suggestions = false;
                 self.command_suggestions.clear();
             }
+            self.command_hint = None;
             self.update_file_suggestions(query, cx);
             return;
         }
@@ -903,48 +965,32 @@ impl WelcomePanel {
         self.clear_file_suggestions(cx);
 
         // Check if input starts with / for command suggestions
-        if value.trim_start().starts_with('/') {
-            // Get the command prefix (everything after the /)
-            let trimmed = value.trim_start();
-            let command_text = trimmed.trim_start_matches('/');
-            if command_text.chars().any(char::is_whitespace) {
-                if self.show_command_suggestions {
-                    self.show_command_suggestions = false;
-                    self.command_suggestions.clear();
-                    cx.notify();
-                }
-                return;
-            }
-            let command_prefix = command_text;
-
+        if let Some(command_query) = slash_command::command_query(&value) {
             // Get available commands for the current session
             let all_commands = self.get_available_commands(cx);
 
-            // Filter commands by prefix
-            if command_prefix.is_empty() {
-                // Show all commands when just "/" is entered
-                self.command_suggestions = all_commands;
-                self.show_command_suggestions = !self.command_suggestions.is_empty();
-            } else {
-                // Filter commands that start with the prefix
-                self.command_suggestions = all_commands
-                    .into_iter()
-                    .filter(|cmd| cmd.name.starts_with(command_prefix))
-                    .collect();
-                self.show_command_suggestions = !self.command_suggestions.is_empty();
-            }
+            // Fuzzy-match the typed name; "/" alone lists every command
+            self.command_suggestions = slash_command::filter_commands(&all_commands, command_query);
+            self.show_command_suggestions = !self.command_suggestions.is_empty();
+            self.command_hint = None;
 
             log::debug!(
-                "[WelcomePanel] Command suggestions: {} matches for prefix '{}'",
+                "[WelcomePanel] Command suggestions: {} matches for query '{}'",
                 self.command_suggestions.len(),
-                command_prefix
+                command_query
             );
             cx.notify();
         } else {
-            // Not a command input, hide suggestions
-            if self.show_command_suggestions {
+            // Not typing a command name: hide suggestions, show argument hints if any
+            let command_hint = if value.trim_start().starts_with('/') {
+                slash_command::pending_argument_hint(&value, &self.get_available_commands(cx))
+            } else {
+                None
+            };
+            if self.show_command_suggestions || self.command_hint != command_hint {
                 self.show_command_suggestions = false;
                 self.command_suggestions.clear();
+                self.command_hint = command_hint;
                 cx.notify();
             }
         }
@@ -990,6 +1036,8 @@ impl WelcomePanel {
         });
         self.show_command_suggestions = false;
         self.command_suggestions.clear();
+        self.command_hint = slash_command::input_hint(command)
+            .map(|hint| format!("/{} {}", command.name, hint));
         cx.notify();
     }
 
@@ -1209,6 +1257,7 @@ impl WelcomePanel {
         let agent_config_service = AppState::global(cx).agent_config_service().cloned();
         let available_mcps = self.available_mcps.clone();
         let selected_mcps = self.selected_mcps.clone();
+        let disabled_mcp_tools = self.disabled_mcp_tools.clone();
         let mcp_selection_initialized = self.mcp_selection_initialized;
         let cwd = self.working_directory.clone(); // 使用面板的工作目录
 
@@ -1216,7 +1265,7 @@ impl WelcomePanel {
         let agent_name_for_session = agent_name.clone();
         cx.spawn_in(window, async move |_this, window| {
             let mut mcp_servers =
-                Self::collect_mcp_servers_from_selection(&available_mcps, &selected_mcps);
+                mcp_proxy::session_servers(&available_mcps, &selected_mcps, &disabled_mcp_tools);
 
             if !mcp_selection_initialized {
                 if let Some(service) = agent_config_service {
@@ -1261,7 +1310,7 @@ impl WelcomePanel {
                     log::error!("[WelcomePanel] Failed to create session: {}", e);
 
                     // Provide detailed error context
-                    let (error_message, error_details) = if e.to_string().contains("server shut down unexpectedly") {
+                    let (error_message, error_details) = if e.is_agent_failure() {
                         let details = format!(
                             "Agent '{}' process crashed during session creation. \
                             Possible reasons:\n\
@@ -1298,7 +1347,7 @@ impl WelcomePanel {
                             struct SessionCreationError;
                             let note = Notification::error(error_message)
                                 .id::<SessionCreationError>();
-                            window.push_notification(note, cx);
+                            window.show_notification(note, cx);
                         }
                     });
                 }
@@ -1363,16 +1412,45 @@ impl WelcomePanel {
             self.input_state.update(cx, |state, cx| {
                 state.set_value("", window, cx);
             });
+            self.command_hint = None;
 
             // Dispatch CreateTaskFromWelcome action with images and workspace_id
             let images = std::mem::take(&mut self.pasted_images);
             let workspace_id = self.workspace_id.clone();
+            // Kept after sending, so a series of runs shares the label
+            let label = self.label_state.read(cx).value().trim().to_string();
+
+            let others: Vec<String> = self
+                .compare_agents
+                .iter()
+                .filter(|agent| **agent != agent_name)
+                .cloned()
+                .collect();
+            if !others.is_empty() {
+                let action = CompareAgents {
+                    task_input: task_name,
+                    agent_names: std::iter::once(agent_name).chain(others).collect(),
+                    mode,
+                    images,
+                    workspace_id,
+                    label: (!label.is_empty()).then_some(label),
+                };
+                log::info!(
+                    "[WelcomePanel] Dispatching CompareAgents for {:?}",
+                    action.agent_names
+                );
+                window.dispatch_action(Box::new(action), cx);
+                self.code_selections.clear();
+                return;
+            }
+
             let action = CreateTaskFromWelcome {
                 task_input: task_name,
                 agent_name,
                 mode,
                 images,
                 workspace_id,
+                label: (!label.is_empty()).then_some(label),
             };
 
             log::info!(
@@ -1388,6 +1466,63 @@ impl WelcomePanel {
     }
 }
 
+impl WelcomePanel {
+    fn toggle_compare_agent(&mut self, agent: String, cx: &mut Context<Self>) {
+        if let Some(ix) = self.compare_agents.iter().position(|name| *name == agent) {
+            self.compare_agents.remove(ix);
+        } else {
+            self.compare_agents.push(agent);
+        }
+        cx.notify();
+    }
+
+    /// Picks the agents that get the task too, side by side with the selected one
+    fn compare_button(&self, cx: &mut Context<Self>) -> Button {
+        let selected = self.agent_select.read(cx).selected_value().cloned();
+        let others: Vec<String> = self
+            .agent_names
+            .iter()
+            .filter(|agent| Some(*agent) != selected.as_ref())
+            .cloned()
+            .collect();
+        let compared = self
+            .compare_agents
+            .iter()
+            .filter(|agent| others.contains(agent))
+            .count();
+        let label = if compared == 0 {
+            t!("welcome.compare.button").to_string()
+        } else {
+            t!("welcome.compare.agents", count = compared + 1).to_string()
+        };
+        let checked = self.compare_agents.clone();
+        let panel = cx.entity().downgrade();
+
+        Button::new("welcome-compare")
+            .label(label)
+            .icon(IconName::ChevronDown)
+            .small()
+            .ghost()
+            .tooltip(t!("welcome.compare.tooltip").to_string())
+            .dropdown_menu(move |mut menu, _, cx| {
+                for agent in &others {
+                    let panel = panel.clone();
+                    let name = agent.clone();
+                    menu = menu.item(
+                        PopupMenuItem::new(AppState::global(cx).agent_label(agent))
+                            .checked(checked.contains(agent))
+                            .on_click(move |_, _, cx| {
+                                let name = name.clone();
+                                _ = panel
+                                    .update(cx, |this, cx| this.toggle_compare_agent(name, cx));
+                            }),
+                    );
+                }
+                menu
+            })
+    }
+}
+
 impl Focusable for WelcomePanel {
     fn focus_handle(&self, _cx: &gpui::App) -> FocusHandle {
         self.focus_handle.clone()
@@ -1557,6 +1692,7 @@ impl Render for WelcomePanel {
                                 // Pass command suggestions to ChatInputBox
                                 .command_suggestions(self.command_suggestions.clone())
                                 .show_command_suggestions(self.show_command_suggestions)
+                                .command_hint(self.command_hint.clone())
                                 .on_command_select(cx.listener(|this, command, window, cx| {
                                     this.apply_command_selection(command, window, cx);
                                 }))
@@ -1583,6 +1719,29 @@ impl Render for WelcomePanel {
                                         cx.notify();
                                     },
                                 ))
+                                .mcp_tools(self.mcp_tools.clone(), self.disabled_mcp_tools.clone())
+                                .on_mcp_tool_toggle(cx.listener(
+                                    |this, toggle: &(String, String, bool), window, cx| {
+                                        let (server, tool, enabled) = toggle;
+                                        let disabled = this
+                                            .disabled_mcp_tools
+                                            .entry(server.clone())
+                                            .or_default();
+                                        if *enabled {
+                                            disabled.remove(tool);
+                                        } else {
+                                            disabled.insert(tool.clone());
+                                        }
+                                        this.mcp_selection_overridden = true;
+                                        this.on_mcp_selection_changed(window, cx);
+                                        cx.notify();
+                                    },
+                                ))
+                                .on_mcp_list_tools(cx.listener(
+                                    |this, server: &String, window, cx| {
+                                        this.list_mcp_tools(server.clone(), window, cx);
+                                    },
+                                ))
                                 .on_paste(move |window, cx| {
                                     entity.update(cx, |this, cx| {
                                         this.handle_paste(window, cx);
@@ -1614,6 +1773,21 @@ impl Render for WelcomePanel {
                                     this.handle_send_task(window, cx);
                                 }))
                         },
+                    )
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .px(px(32.))
+                            .gap_2()
+                            .justify_end()
+                            .when(self.agent_names.len() > 1, |this| {
+                                this.child(self.compare_button(cx))
+                            })
+                            .child(
+                                gpui::div()
+                                    .w(px(240.))
+                                    .child(Input::new(&self.label_state).small()),
+                            ),
                     ),
             )
     }
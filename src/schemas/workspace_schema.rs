@@ -1,8 +1,16 @@
 use gpui::SharedString;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::core::services::SessionStatus;
+use crate::utils::context_item::ContextItem;
+
+/// Directory inside a workspace holding AgentX data shared with the repository
+pub const REPO_DATA_DIR: &str = ".agent-studio";
+
+/// Longest last message preview, in characters
+const MESSAGE_PREVIEW_CHARS: usize = 120;
 
 /// Workspace represents a local project folder
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -22,6 +30,22 @@ pub struct Workspace {
     /// Tasks associated with this workspace
     #[serde(skip)]
     pub tasks: Vec<WorkspaceTask>,
+    /// Facts and preferences the user asked agents to remember
+    #[serde(default)]
+    pub memories: Vec<WorkspaceMemory>,
+    /// Keep session histories in `.agent-studio/sessions/` inside the
+    /// workspace, so they can be versioned with the repository
+    #[serde(default)]
+    pub repo_sessions: bool,
+    /// Language agents are asked to respond in, e.g. "Chinese" or "English"
+    #[serde(default)]
+    pub response_language: Option<String>,
+    /// Point out replies written in another language than `response_language`
+    #[serde(default)]
+    pub check_response_language: bool,
+    /// Instruction files (e.g. "AGENTS.md") not attached to new sessions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_instruction_files: Vec<String>,
 }
 
 impl Workspace {
@@ -42,13 +66,57 @@ impl Workspace {
             created_at: now,
             last_accessed: now,
             tasks: Vec::new(),
+            memories: Vec::new(),
+            repo_sessions: false,
+            response_language: None,
+            check_response_language: false,
+            excluded_instruction_files: Vec::new(),
         }
     }
 
+    /// Where session histories are kept when `repo_sessions` is on
+    pub fn repo_sessions_dir(&self) -> PathBuf {
+        self.path.join(REPO_DATA_DIR).join("sessions")
+    }
+
     /// Update last accessed time
     pub fn touch(&mut self) {
         self.last_accessed = chrono::Utc::now();
     }
+
+    /// The workspace memory as a preamble for a new session's first prompt
+    pub fn memory_prompt(&self) -> Option<String> {
+        if self.memories.is_empty() {
+            return None;
+        }
+        let mut prompt = format!(
+            "Project memory for \"{}\" (facts and preferences the user asked you to remember):\n",
+            self.name
+        );
+        for memory in &self.memories {
+            prompt.push_str(&format!("- {}\n", memory.content));
+        }
+        Some(prompt)
+    }
+}
+
+/// A fact or preference remembered across the sessions of a workspace
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceMemory {
+    pub id: String,
+    pub content: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl WorkspaceMemory {
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            content: content.into(),
+            created_at: chrono::Utc::now(),
+        }
+    }
 }
 
 /// Task within a workspace
@@ -64,13 +132,44 @@ pub struct WorkspaceTask {
     pub agent_name: String,
     /// Task mode (Auto, Ask, Plan, Code, Explain)
     pub mode: String,
+    /// Experiment label given at creation (e.g. "prompt-v2"), to compare configurations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
     /// Session ID if a session has been created
     pub session_id: Option<String>,
+    /// Sessions of other agents @mentioned in this task (agent name -> session ID)
+    #[serde(default)]
+    pub agent_sessions: HashMap<String, String>,
+    /// Context pinned to the task's conversation, attached to every prompt
+    #[serde(default)]
+    pub pinned_context: Vec<ContextItem>,
+    /// The user's ratings of agent replies in the task's conversation
+    #[serde(default)]
+    pub feedback: Vec<TurnFeedback>,
+    /// Totals over the task's completed turns
+    #[serde(default)]
+    pub usage: TaskUsage,
+    /// Time the user spent active in the task's conversation, in milliseconds
+    #[serde(default)]
+    pub active_ms: u64,
+    /// Read-only: no more prompts are sent and the agents' writes are refused
+    #[serde(default)]
+    pub locked: bool,
+    /// Agent replies in the task's conversation are translated as they finish
+    #[serde(default)]
+    pub auto_translate: bool,
     /// Task status
     pub status: SessionStatus,
     /// When the task was created
     #[serde(with = "chrono::serde::ts_seconds")]
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// When a message was last sent or received in the task, or its status
+    /// changed; set to `created_at` for tasks saved before it was tracked
+    #[serde(default, with = "chrono::serde::ts_seconds")]
+    pub last_updated: chrono::DateTime<chrono::Utc>,
+    /// Messages in the task's conversation: prompts and agent replies
+    #[serde(default)]
+    pub message_count: u32,
     /// Last message preview
     #[serde(skip)]
     pub last_message: Option<SharedString>,
@@ -79,15 +178,26 @@ pub struct WorkspaceTask {
 impl WorkspaceTask {
     /// Create a new task for a workspace
     pub fn new(workspace_id: String, name: String, agent_name: String, mode: String) -> Self {
+        let now = chrono::Utc::now();
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             workspace_id,
             name,
             agent_name,
             mode,
+            label: None,
             session_id: None,
+            agent_sessions: HashMap::new(),
+            pinned_context: Vec::new(),
+            feedback: Vec::new(),
+            usage: TaskUsage::default(),
+            active_ms: 0,
+            locked: false,
+            auto_translate: false,
             status: SessionStatus::Pending,
-            created_at: chrono::Utc::now(),
+            created_at: now,
+            last_updated: now,
+            message_count: 0,
             last_message: None,
         }
     }
@@ -95,7 +205,51 @@ impl WorkspaceTask {
     /// Associate a session with this task
     pub fn set_session(&mut self, session_id: String) {
         self.session_id = Some(session_id);
-        self.status = SessionStatus::InProgress;
+        self.set_status(SessionStatus::InProgress);
+    }
+
+    /// Change the status, counting as an update if it differs
+    pub fn set_status(&mut self, status: SessionStatus) {
+        if self.status != status {
+            self.status = status;
+            self.touch();
+        }
+    }
+
+    /// Record a completed turn: the prompt and the agent's reply
+    pub fn record_turn(&mut self, busy_ms: u64, prompt_chars: usize, output_chars: usize) {
+        self.usage.record_turn(busy_ms, prompt_chars, output_chars);
+        self.message_count += 2;
+        self.touch();
+    }
+
+    /// Mark the task as updated now
+    pub fn touch(&mut self) {
+        self.last_updated = chrono::Utc::now();
+    }
+
+    /// Whether `session_id` is the task's session or that of an agent @mentioned in it
+    pub fn has_session(&self, session_id: &str) -> bool {
+        self.session_id.as_deref() == Some(session_id)
+            || self.agent_sessions.values().any(|id| id == session_id)
+    }
+
+    /// The task's session and those of the agents @mentioned in it
+    pub fn session_ids(&self) -> Vec<String> {
+        self.session_id
+            .iter()
+            .chain(self.agent_sessions.values())
+            .cloned()
+            .collect()
+    }
+
+    /// Session that handles prompts for `agent_name` within this task
+    pub fn session_for_agent(&self, agent_name: &str) -> Option<&str> {
+        if agent_name == self.agent_name {
+            self.session_id.as_deref()
+        } else {
+            self.agent_sessions.get(agent_name).map(String::as_str)
+        }
     }
 
     /// Update the last message preview
@@ -104,6 +258,208 @@ impl WorkspaceTask {
     }
 }
 
+/// Preview of a message: its last non-empty line, shortened
+pub fn message_preview(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).rfind(|line| !line.is_empty())?;
+    if line.chars().count() <= MESSAGE_PREVIEW_CHARS {
+        return Some(line.to_string());
+    }
+    let mut preview: String = line.chars().take(MESSAGE_PREVIEW_CHARS - 1).collect();
+    preview.push('…');
+    Some(preview)
+}
+
+/// Work done in a task's turns
+///
+/// Agents don't report token usage or cost, so prompt and output sizes are
+/// counted in characters and estimated as tokens.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskUsage {
+    pub turns: u32,
+    /// Time from sending a prompt to the end of the agent's turn, summed
+    pub busy_ms: u64,
+    pub prompt_chars: u64,
+    pub output_chars: u64,
+}
+
+impl TaskUsage {
+    pub fn record_turn(&mut self, busy_ms: u64, prompt_chars: usize, output_chars: usize) {
+        self.turns += 1;
+        self.busy_ms += busy_ms;
+        self.prompt_chars += prompt_chars as u64;
+        self.output_chars += output_chars as u64;
+    }
+
+    pub fn add(&mut self, other: &TaskUsage) {
+        self.turns += other.turns;
+        self.busy_ms += other.busy_ms;
+        self.prompt_chars += other.prompt_chars;
+        self.output_chars += other.output_chars;
+    }
+
+    /// Rough token count of prompts and output (4 characters per token)
+    pub fn estimated_tokens(&self) -> u64 {
+        (self.prompt_chars + self.output_chars).div_ceil(4)
+    }
+}
+
+/// Thumbs up or down on an agent reply
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackRating {
+    Up,
+    Down,
+}
+
+/// The user's rating of one agent reply
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TurnFeedback {
+    /// ID of the rated agent message within the conversation
+    pub message_id: String,
+    /// Agent that wrote the reply
+    pub agent_name: String,
+    pub rating: FeedbackRating,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Feedback totals for one agent
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AgentFeedbackSummary {
+    pub agent_name: String,
+    pub up: usize,
+    pub down: usize,
+    /// Comments left with the ratings, newest first
+    pub comments: Vec<(FeedbackRating, String)>,
+}
+
+impl AgentFeedbackSummary {
+    pub fn total(&self) -> usize {
+        self.up + self.down
+    }
+
+    /// Share of thumbs-up ratings, 0.0 to 1.0
+    pub fn approval(&self) -> f32 {
+        if self.total() == 0 {
+            0.0
+        } else {
+            self.up as f32 / self.total() as f32
+        }
+    }
+}
+
+/// Totals `feedback` per agent, sorted by agent name
+pub fn summarize_feedback<'a>(
+    feedback: impl IntoIterator<Item = &'a TurnFeedback>,
+) -> Vec<AgentFeedbackSummary> {
+    let mut feedback: Vec<&TurnFeedback> = feedback.into_iter().collect();
+    feedback.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    let mut by_agent: HashMap<&str, AgentFeedbackSummary> = HashMap::new();
+    for entry in feedback {
+        let summary = by_agent
+            .entry(entry.agent_name.as_str())
+            .or_insert_with(|| AgentFeedbackSummary {
+                agent_name: entry.agent_name.clone(),
+                ..Default::default()
+            });
+        match entry.rating {
+            FeedbackRating::Up => summary.up += 1,
+            FeedbackRating::Down => summary.down += 1,
+        }
+        if let Some(comment) = &entry.comment {
+            summary.comments.push((entry.rating, comment.clone()));
+        }
+    }
+
+    let mut summaries: Vec<AgentFeedbackSummary> = by_agent.into_values().collect();
+    summaries.sort_by(|a, b| a.agent_name.cmp(&b.agent_name));
+    summaries
+}
+
+/// Feedback, usage and duration of the tasks sharing an experiment label
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LabelSummary {
+    /// `None` for tasks created without a label
+    pub label: Option<String>,
+    pub tasks: usize,
+    pub usage: TaskUsage,
+    pub up: usize,
+    pub down: usize,
+}
+
+impl LabelSummary {
+    /// Share of thumbs-up ratings, 0.0 to 1.0
+    pub fn approval(&self) -> f32 {
+        let total = self.up + self.down;
+        if total == 0 {
+            0.0
+        } else {
+            self.up as f32 / total as f32
+        }
+    }
+
+    /// Average time per turn, in milliseconds
+    pub fn avg_turn_ms(&self) -> u64 {
+        self.usage.busy_ms / u64::from(self.usage.turns.max(1))
+    }
+}
+
+/// Totals `tasks` per experiment label, sorted by label with unlabeled last
+pub fn summarize_by_label<'a>(
+    tasks: impl IntoIterator<Item = &'a WorkspaceTask>,
+) -> Vec<LabelSummary> {
+    let mut by_label: HashMap<Option<&str>, LabelSummary> = HashMap::new();
+    for task in tasks {
+        let summary = by_label
+            .entry(task.label.as_deref())
+            .or_insert_with(|| LabelSummary {
+                label: task.label.clone(),
+                ..Default::default()
+            });
+        summary.tasks += 1;
+        summary.usage.add(&task.usage);
+        for feedback in &task.feedback {
+            match feedback.rating {
+                FeedbackRating::Up => summary.up += 1,
+                FeedbackRating::Down => summary.down += 1,
+            }
+        }
+    }
+
+    let mut summaries: Vec<LabelSummary> = by_label.into_values().collect();
+    // `None` sorts first, so compare "is unlabeled" before the label
+    summaries.sort_by(|a, b| (a.label.is_none(), &a.label).cmp(&(b.label.is_none(), &b.label)));
+    summaries
+}
+
+/// Where a page of tasks ended: its least recently updated task
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskCursor {
+    pub last_updated: chrono::DateTime<chrono::Utc>,
+    /// Orders tasks updated in the same second
+    pub id: String,
+}
+
+impl TaskCursor {
+    pub fn of(task: &WorkspaceTask) -> Self {
+        Self {
+            last_updated: task.last_updated,
+            id: task.id.clone(),
+        }
+    }
+}
+
+/// Tasks of all workspaces, most recently updated first
+#[derive(Clone, Debug, Default)]
+pub struct TaskPage {
+    pub tasks: Vec<WorkspaceTask>,
+    /// No less recently updated tasks are left
+    pub eof: bool,
+}
+
 /// Persistent workspace configuration
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub struct WorkspaceConfig {
@@ -150,6 +506,45 @@ impl WorkspaceConfig {
             .collect()
     }
 
+    /// Up to `limit` tasks updated before `after` (or the most recently
+    /// updated if `None`)
+    ///
+    /// A task updated while paging moves above the cursor, so it is never
+    /// listed twice; the caller receives it through `TaskUpdated` instead.
+    pub fn tasks_page(&self, after: Option<&TaskCursor>, limit: usize) -> TaskPage {
+        let key = |task: &WorkspaceTask| (task.last_updated, task.id.clone());
+        let mut tasks: Vec<&WorkspaceTask> = self
+            .tasks
+            .iter()
+            .filter(|task| {
+                after.is_none_or(|cursor| key(task) < (cursor.last_updated, cursor.id.clone()))
+            })
+            .collect();
+        tasks.sort_by_key(|task| std::cmp::Reverse(key(task)));
+        let eof = tasks.len() <= limit;
+        TaskPage {
+            tasks: tasks.into_iter().take(limit).cloned().collect(),
+            eof,
+        }
+    }
+
+    /// Fill in the fields of tasks saved before they were tracked; returns
+    /// whether any task changed
+    pub fn migrate(&mut self) -> bool {
+        let mut changed = false;
+        for task in &mut self.tasks {
+            if task.last_updated < task.created_at {
+                task.last_updated = task.created_at;
+                changed = true;
+            }
+            if task.message_count == 0 && task.usage.turns > 0 {
+                task.message_count = task.usage.turns * 2;
+                changed = true;
+            }
+        }
+        changed
+    }
+
     /// Get mutable tasks for a specific workspace
     pub fn tasks_for_workspace_mut(&mut self, workspace_id: &str) -> Vec<&mut WorkspaceTask> {
         self.tasks
@@ -175,3 +570,200 @@ impl WorkspaceConfig {
         self.workspaces.iter_mut().find(|w| w.id == workspace_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_prompt() {
+        let mut workspace = Workspace::new(PathBuf::from("/repo/web"));
+        assert_eq!(workspace.memory_prompt(), None);
+
+        workspace.memories.push(WorkspaceMemory::new("we use pnpm"));
+        workspace.memories.push(WorkspaceMemory::new("tests live in /spec"));
+        let prompt = workspace.memory_prompt().unwrap();
+        assert!(prompt.starts_with("Project memory for \"web\""));
+        assert!(prompt.ends_with("- we use pnpm\n- tests live in /spec\n"));
+    }
+
+    #[test]
+    fn test_repo_sessions() {
+        let workspace = Workspace::new(PathBuf::from("/repo/web"));
+        assert_eq!(
+            workspace.repo_sessions_dir(),
+            PathBuf::from("/repo/web/.agent-studio/sessions")
+        );
+
+        // Workspaces saved before the option existed keep sessions in the app data
+        let mut value = serde_json::to_value(&workspace).unwrap();
+        value.as_object_mut().unwrap().remove("repo_sessions");
+        let workspace: Workspace = serde_json::from_value(value).unwrap();
+        assert!(!workspace.repo_sessions);
+    }
+
+    #[test]
+    fn test_task_lock() {
+        let mut task = WorkspaceTask::new(
+            "ws".to_string(),
+            "task".to_string(),
+            "claude".to_string(),
+            "Auto".to_string(),
+        );
+        task.set_session("s1".to_string());
+        task.agent_sessions.insert("codex".to_string(), "s2".to_string());
+        assert_eq!(task.session_ids(), vec!["s1".to_string(), "s2".to_string()]);
+
+        // Tasks saved before locking existed are unlocked
+        let mut value = serde_json::to_value(&task).unwrap();
+        value.as_object_mut().unwrap().remove("locked");
+        let task: WorkspaceTask = serde_json::from_value(value).unwrap();
+        assert!(!task.locked);
+    }
+
+    #[test]
+    fn test_message_preview() {
+        assert_eq!(message_preview(""), None);
+        assert_eq!(message_preview(" \n\n"), None);
+        assert_eq!(
+            message_preview("Done.\n\n  All tests pass  \n"),
+            Some("All tests pass".to_string())
+        );
+        let preview = message_preview(&"é".repeat(200)).unwrap();
+        assert_eq!(preview.chars().count(), MESSAGE_PREVIEW_CHARS);
+        assert!(preview.ends_with('…'));
+    }
+
+    #[test]
+    fn test_tasks_page() {
+        let mut config = WorkspaceConfig::default();
+        let start = chrono::Utc::now();
+        for (ix, seconds) in [0, 10, 10, 20, 30].into_iter().enumerate() {
+            let mut task = WorkspaceTask::new(
+                "ws".to_string(),
+                format!("task {}", ix),
+                "claude".to_string(),
+                "Auto".to_string(),
+            );
+            task.id = format!("t{}", ix);
+            task.last_updated = start + chrono::Duration::seconds(seconds);
+            config.add_task(task);
+        }
+        let ids = |page: &TaskPage| page.tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+
+        let first = config.tasks_page(None, 2);
+        assert_eq!(ids(&first), vec!["t4", "t3"]);
+        assert!(!first.eof);
+        // Tasks updated in the same second are split across pages by ID
+        let cursor = TaskCursor::of(first.tasks.last().unwrap());
+        let second = config.tasks_page(Some(&cursor), 1);
+        assert_eq!(ids(&second), vec!["t2"]);
+        let cursor = TaskCursor::of(second.tasks.last().unwrap());
+        let last = config.tasks_page(Some(&cursor), 2);
+        assert_eq!(ids(&last), vec!["t1", "t0"]);
+        assert!(last.eof);
+    }
+
+    #[test]
+    fn test_migrate() {
+        let mut task = WorkspaceTask::new(
+            "ws".to_string(),
+            "task".to_string(),
+            "claude".to_string(),
+            "Auto".to_string(),
+        );
+        task.usage.record_turn(1_000, 10, 20);
+        task.usage.record_turn(1_000, 10, 20);
+
+        // Tasks saved before updates and messages were tracked
+        let mut value = serde_json::to_value(&task).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("last_updated");
+        object.remove("message_count");
+        let mut config = WorkspaceConfig {
+            tasks: vec![serde_json::from_value(value).unwrap()],
+            ..Default::default()
+        };
+        assert!(config.migrate());
+        assert_eq!(config.tasks[0].last_updated, config.tasks[0].created_at);
+        assert_eq!(config.tasks[0].message_count, 4);
+        assert!(!config.migrate());
+    }
+
+    #[test]
+    fn test_record_turn() {
+        let mut task = WorkspaceTask::new(
+            "ws".to_string(),
+            "task".to_string(),
+            "claude".to_string(),
+            "Auto".to_string(),
+        );
+        task.last_updated = chrono::DateTime::from_timestamp(0, 0).unwrap();
+        task.record_turn(1_000, 10, 20);
+        assert_eq!(task.usage.turns, 1);
+        assert_eq!(task.message_count, 2);
+        assert!(task.last_updated >= task.created_at);
+    }
+
+    #[test]
+    fn test_summarize_by_label() {
+        let task = |label: Option<&str>, busy_ms| {
+            let mut task = WorkspaceTask::new(
+                "ws".to_string(),
+                "task".to_string(),
+                "codex".to_string(),
+                "Auto".to_string(),
+            );
+            task.label = label.map(str::to_string);
+            task.usage.record_turn(busy_ms, 400, 800);
+            task
+        };
+        let mut tasks = vec![task(None, 1_000), task(Some("prompt-v2"), 3_000)];
+        tasks.push(task(Some("prompt-v2"), 5_000));
+        tasks[1].feedback.push(TurnFeedback {
+            message_id: "agent-msg-1".to_string(),
+            agent_name: "codex".to_string(),
+            rating: FeedbackRating::Up,
+            comment: None,
+            created_at: chrono::Utc::now(),
+        });
+
+        let summaries = summarize_by_label(&tasks);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].label.as_deref(), Some("prompt-v2"));
+        assert_eq!(summaries[0].tasks, 2);
+        assert_eq!(summaries[0].avg_turn_ms(), 4_000);
+        assert_eq!(summaries[0].usage.estimated_tokens(), 600);
+        assert_eq!((summaries[0].up, summaries[0].down), (1, 0));
+        assert_eq!(summaries[1].label, None);
+    }
+
+    #[test]
+    fn test_summarize_feedback() {
+        let feedback = |agent: &str, rating, comment: Option<&str>, secs| TurnFeedback {
+            message_id: format!("agent-msg-{}", secs),
+            agent_name: agent.to_string(),
+            rating,
+            comment: comment.map(str::to_string),
+            created_at: chrono::DateTime::from_timestamp(secs, 0).unwrap(),
+        };
+        let entries = vec![
+            feedback("codex", FeedbackRating::Down, Some("ignored the tests"), 1),
+            feedback("claude", FeedbackRating::Up, None, 2),
+            feedback("codex", FeedbackRating::Up, Some("nice refactor"), 3),
+        ];
+
+        let summaries = summarize_feedback(&entries);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].agent_name, "claude");
+        assert_eq!((summaries[1].up, summaries[1].down), (1, 1));
+        assert_eq!(summaries[1].approval(), 0.5);
+        assert_eq!(
+            summaries[1].comments,
+            vec![
+                (FeedbackRating::Up, "nice refactor".to_string()),
+                (FeedbackRating::Down, "ignored the tests".to_string())
+            ]
+        );
+    }
+}
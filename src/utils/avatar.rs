@@ -0,0 +1,81 @@
+//! Colors and initials of agent avatars
+
+/// Colors of agents without a configured one, picked by name
+const PALETTE: [u32; 8] = [
+    0xd97757, 0x3b82f6, 0x22c55e, 0xa855f7, 0xf59e0b, 0x06b6d4, 0xec4899, 0x64748b,
+];
+
+/// `#rrggbb` or `#rgb` (the `#` is optional) as `0xrrggbb`
+pub fn parse_hex_color(text: &str) -> Option<u32> {
+    let hex = text.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        6 => u32::from_str_radix(hex, 16).ok(),
+        3 => {
+            let doubled: String = hex.chars().flat_map(|c| [c, c]).collect();
+            u32::from_str_radix(&doubled, 16).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Color of `agent_name`, the same on every start
+pub fn fallback_color(agent_name: &str) -> u32 {
+    // FNV-1a, which unlike `DefaultHasher` is stable across Rust releases
+    let hash = agent_name.bytes().fold(0x811c9dc5u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    });
+    PALETTE[hash as usize % PALETTE.len()]
+}
+
+/// Configured color of an agent, or its fallback if unset or invalid
+pub fn avatar_color(agent_name: &str, configured: Option<&str>) -> u32 {
+    configured
+        .and_then(parse_hex_color)
+        .unwrap_or_else(|| fallback_color(agent_name))
+}
+
+/// Letter shown in an avatar: the first alphanumeric character, uppercased
+pub fn initial(label: &str) -> String {
+    label
+        .chars()
+        .find(|c| c.is_alphanumeric())
+        .map(|c| c.to_uppercase().collect())
+        .unwrap_or_else(|| "?".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#d97757"), Some(0xd97757));
+        assert_eq!(parse_hex_color(" 3B82F6 "), Some(0x3b82f6));
+        assert_eq!(parse_hex_color("#f0a"), Some(0xff00aa));
+        assert_eq!(parse_hex_color("#12345"), None);
+        assert_eq!(parse_hex_color("red"), None);
+        assert_eq!(parse_hex_color("#+12345"), None);
+    }
+
+    #[test]
+    fn test_avatar_color() {
+        assert_eq!(avatar_color("claude", Some("#000000")), 0);
+        assert_eq!(
+            avatar_color("claude", Some("blue")),
+            fallback_color("claude")
+        );
+        assert_eq!(avatar_color("claude", None), fallback_color("claude"));
+        assert!(PALETTE.contains(&fallback_color("gemini")));
+    }
+
+    #[test]
+    fn test_initial() {
+        assert_eq!(initial("claude"), "C");
+        assert_eq!(initial("@codex"), "C");
+        assert_eq!(initial("ärger"), "Ä");
+        assert_eq!(initial("--"), "?");
+    }
+}
@@ -0,0 +1,436 @@
+//! Embedded binary resources: what a blob holds, judged from its first bytes
+//! before its MIME type, and what can be shown of it without another app
+//!
+//! Images are shown as they are, PDFs by version, page count and title, ZIP
+//! and tar archives by their file list, and gzip files by the name of the
+//! file inside. Nothing here decompresses, so archives inside gzip are not
+//! listed.
+
+use std::sync::LazyLock;
+
+use base64::Engine as _;
+use gpui::ImageFormat;
+use regex::bytes::Regex;
+
+/// Archive entries kept for a listing; the rest are only counted
+pub const MAX_LISTED_ENTRIES: usize = 200;
+
+static PDF_PAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/Type\s*/Page\b").unwrap());
+static PDF_COUNT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/Count\s+(\d+)").unwrap());
+static PDF_TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"/Title\s*\(([^)\\]*)\)").unwrap());
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlobPreview {
+    Image(ImageFormat),
+    Pdf(PdfInfo),
+    Zip(Listing),
+    Tar(Listing),
+    /// Name of the compressed file, if the header holds it
+    Gzip(Option<String>),
+    /// Nothing to show but the size
+    None,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PdfInfo {
+    pub version: Option<String>,
+    pub pages: Option<usize>,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Listing {
+    /// The first `MAX_LISTED_ENTRIES` entries
+    pub entries: Vec<ArchiveEntry>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveEntry {
+    pub name: String,
+    /// Uncompressed size in bytes
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+impl BlobPreview {
+    /// Inspect `bytes`; the MIME type only decides when the bytes do not
+    pub fn inspect(bytes: &[u8], mime_type: Option<&str>) -> Self {
+        if let Some(format) = sniff_image(bytes) {
+            return Self::Image(format);
+        }
+        if bytes.starts_with(b"%PDF-") {
+            return Self::Pdf(pdf_info(bytes));
+        }
+        if bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06") {
+            return zip_listing(bytes).map_or(Self::None, Self::Zip);
+        }
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            return Self::Gzip(gzip_file_name(bytes));
+        }
+        if bytes.get(257..262) == Some(b"ustar") {
+            return tar_listing(bytes).map_or(Self::None, Self::Tar);
+        }
+
+        let mime_type = mime_type.unwrap_or_default().to_ascii_lowercase();
+        let text = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]);
+        if mime_type == "image/svg+xml" || text.trim_start().starts_with("<svg") {
+            return Self::Image(ImageFormat::Svg);
+        }
+        match mime_type.as_str() {
+            "application/x-tar" => tar_listing(bytes).map_or(Self::None, Self::Tar),
+            _ => Self::None,
+        }
+    }
+
+    /// File name extension of the kind of blob
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::Image(format) => Some(match format {
+                ImageFormat::Png => "png",
+                ImageFormat::Jpeg => "jpg",
+                ImageFormat::Webp => "webp",
+                ImageFormat::Gif => "gif",
+                ImageFormat::Svg => "svg",
+                ImageFormat::Bmp => "bmp",
+                ImageFormat::Tiff => "tiff",
+                ImageFormat::Ico => "ico",
+            }),
+            Self::Pdf(_) => Some("pdf"),
+            Self::Zip(_) => Some("zip"),
+            Self::Tar(_) => Some("tar"),
+            Self::Gzip(_) => Some("gz"),
+            Self::None => None,
+        }
+    }
+}
+
+/// Bytes of a base64 blob; line breaks in it are skipped
+pub fn decode(blob: &str) -> Option<Vec<u8>> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    engine.decode(blob.trim()).ok().or_else(|| {
+        let joined: String = blob.split_whitespace().collect();
+        engine.decode(joined).ok()
+    })
+}
+
+/// File name to save a blob named `name` as, with the extension of its kind
+/// added if the name has none
+pub fn file_name_for(name: &str, preview: &BlobPreview) -> String {
+    let name = name.trim();
+    let name = if name.is_empty() || name == "unknown" {
+        "resource"
+    } else {
+        name
+    };
+    match preview.extension() {
+        Some(extension) if !name.contains('.') => format!("{}.{}", name, extension),
+        _ => name.to_string(),
+    }
+}
+
+/// Size for display, such as `12.3 KB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.;
+    let mut unit = 0;
+    while size >= 1024. && unit + 1 < UNITS.len() {
+        size /= 1024.;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+fn sniff_image(bytes: &[u8]) -> Option<ImageFormat> {
+    let format = if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        ImageFormat::Png
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        ImageFormat::Jpeg
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        ImageFormat::Gif
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        ImageFormat::Webp
+    } else if bytes.starts_with(b"BM") && bytes.len() > 14 {
+        ImageFormat::Bmp
+    } else if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+        ImageFormat::Tiff
+    } else if bytes.starts_with(&[0, 0, 1, 0]) {
+        ImageFormat::Ico
+    } else {
+        return None;
+    };
+    Some(format)
+}
+
+/// Counts pages by their page objects, or by the page tree when those are
+/// compressed into object streams
+fn pdf_info(bytes: &[u8]) -> PdfInfo {
+    let version = bytes[5..]
+        .split(|byte| byte.is_ascii_whitespace())
+        .next()
+        .map(|version| String::from_utf8_lossy(version).into_owned())
+        .filter(|version| !version.is_empty());
+    let pages = match PDF_PAGE.find_iter(bytes).count() {
+        0 => PDF_COUNT
+            .captures_iter(bytes)
+            .filter_map(|captures| std::str::from_utf8(&captures[1]).ok()?.parse().ok())
+            .max(),
+        pages => Some(pages),
+    };
+    // Titles in UTF-16 or with escapes are left out
+    let title = PDF_TITLE
+        .captures(bytes)
+        .and_then(|captures| String::from_utf8(captures[1].to_vec()).ok())
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty() && !title.starts_with('\u{feff}'));
+    PdfInfo {
+        version,
+        pages,
+        title,
+    }
+}
+
+fn le16(bytes: &[u8], at: usize) -> Option<usize> {
+    let field = bytes.get(at..at + 2)?;
+    Some(u16::from_le_bytes([field[0], field[1]]) as usize)
+}
+
+fn le32(bytes: &[u8], at: usize) -> Option<u64> {
+    let field = bytes.get(at..at + 4)?;
+    Some(u32::from_le_bytes([field[0], field[1], field[2], field[3]]) as u64)
+}
+
+/// Entries of the central directory at the end of a ZIP file
+fn zip_listing(bytes: &[u8]) -> Option<Listing> {
+    // The end record is 22 bytes, followed by a comment of up to 64 KiB
+    let last = bytes.len().checked_sub(22)?;
+    let first = last.saturating_sub(u16::MAX as usize);
+    let end = (first..=last)
+        .rev()
+        .find(|&at| bytes[at..].starts_with(b"PK\x05\x06"))?;
+    let total = le16(bytes, end + 10)?;
+    let mut at = le32(bytes, end + 16)? as usize;
+
+    let mut entries = Vec::new();
+    for _ in 0..total.min(MAX_LISTED_ENTRIES) {
+        if !bytes.get(at..)?.starts_with(b"PK\x01\x02") {
+            break;
+        }
+        let size = le32(bytes, at + 24)?;
+        let name_len = le16(bytes, at + 28)?;
+        let extra_len = le16(bytes, at + 30)?;
+        let comment_len = le16(bytes, at + 32)?;
+        let name = String::from_utf8_lossy(bytes.get(at + 46..at + 46 + name_len)?).into_owned();
+        entries.push(ArchiveEntry {
+            is_dir: name.ends_with('/'),
+            name,
+            size,
+        });
+        at += 46 + name_len + extra_len + comment_len;
+    }
+    Some(Listing { entries, total })
+}
+
+/// Text of a NUL padded tar header field
+fn tar_field(field: &[u8]) -> String {
+    let end = field
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+fn tar_listing(bytes: &[u8]) -> Option<Listing> {
+    let mut listing = Listing::default();
+    let mut at = 0;
+    while let Some(header) = bytes.get(at..at + 512) {
+        if header.iter().all(|&byte| byte == 0) {
+            break;
+        }
+        // Sizes past 8 GiB are stored in binary, which ends the listing
+        let size = u64::from_str_radix(tar_field(&header[124..136]).trim(), 8).ok()?;
+        let kind = header[156];
+        let name = tar_field(&header[..100]);
+        let prefix = match &header[257..262] {
+            b"ustar" => tar_field(&header[345..500]),
+            _ => String::new(),
+        };
+        // Extended headers describe the entry after them
+        if !matches!(kind, b'x' | b'g' | b'L' | b'K') {
+            let name = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            if listing.entries.len() < MAX_LISTED_ENTRIES {
+                listing.entries.push(ArchiveEntry {
+                    is_dir: kind == b'5' || name.ends_with('/'),
+                    name,
+                    size,
+                });
+            }
+            listing.total += 1;
+        }
+        at += 512 + (size as usize).div_ceil(512) * 512;
+    }
+    (listing.total > 0).then_some(listing)
+}
+
+/// Name stored in a gzip header
+fn gzip_file_name(bytes: &[u8]) -> Option<String> {
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    let flags = *bytes.get(3)?;
+    let mut at = 10;
+    if flags & FEXTRA != 0 {
+        at += 2 + le16(bytes, at)?;
+    }
+    if flags & FNAME == 0 {
+        return None;
+    }
+    let name = bytes.get(at..)?;
+    let end = name.iter().position(|&byte| byte == 0)?;
+    Some(String::from_utf8_lossy(&name[..end]).into_owned()).filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stored (uncompressed) ZIP file with the given entries
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut directory = Vec::new();
+        for (name, data) in files {
+            let offset = out.len() as u32;
+            out.extend(b"PK\x03\x04");
+            out.extend([0; 14]);
+            out.extend((data.len() as u32).to_le_bytes());
+            out.extend((data.len() as u32).to_le_bytes());
+            out.extend((name.len() as u16).to_le_bytes());
+            out.extend([0; 2]);
+            out.extend(name.as_bytes());
+            out.extend(*data);
+
+            directory.extend(b"PK\x01\x02");
+            directory.extend([0; 16]);
+            directory.extend((data.len() as u32).to_le_bytes());
+            directory.extend((data.len() as u32).to_le_bytes());
+            directory.extend((name.len() as u16).to_le_bytes());
+            directory.extend([0; 12]);
+            directory.extend(offset.to_le_bytes());
+            directory.extend(name.as_bytes());
+        }
+        let directory_offset = out.len() as u32;
+        out.extend(&directory);
+        out.extend(b"PK\x05\x06");
+        out.extend([0; 4]);
+        out.extend((files.len() as u16).to_le_bytes());
+        out.extend((files.len() as u16).to_le_bytes());
+        out.extend((directory.len() as u32).to_le_bytes());
+        out.extend(directory_offset.to_le_bytes());
+        out.extend([0; 2]);
+        out
+    }
+
+    fn tar_header(name: &str, size: usize, kind: u8) -> Vec<u8> {
+        let mut header = vec![0; 512];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        let size = format!("{:011o}", size);
+        header[124..135].copy_from_slice(size.as_bytes());
+        header[156] = kind;
+        header[257..262].copy_from_slice(b"ustar");
+        header
+    }
+
+    #[test]
+    fn test_inspect_images_and_pdf() {
+        assert_eq!(
+            BlobPreview::inspect(b"\x89PNG\r\n\x1a\n....", None),
+            BlobPreview::Image(ImageFormat::Png)
+        );
+        assert_eq!(
+            BlobPreview::inspect(b"RIFF\0\0\0\0WEBPVP8 ", Some("application/octet-stream")),
+            BlobPreview::Image(ImageFormat::Webp)
+        );
+        assert_eq!(
+            BlobPreview::inspect(b"<?xml?><svg/>", Some("image/svg+xml")),
+            BlobPreview::Image(ImageFormat::Svg)
+        );
+        assert_eq!(
+            BlobPreview::inspect(b"plain", Some("image/png")),
+            BlobPreview::None
+        );
+
+        let pdf = b"%PDF-1.7\n1 0 obj << /Type /Pages /Count 2 >>\n\
+                    2 0 obj << /Type /Page >>\n3 0 obj << /Type/Page >>\n\
+                    4 0 obj << /Title (Quarterly report) >>";
+        assert_eq!(
+            BlobPreview::inspect(pdf, None),
+            BlobPreview::Pdf(PdfInfo {
+                version: Some("1.7".to_string()),
+                pages: Some(2),
+                title: Some("Quarterly report".to_string()),
+            })
+        );
+        let compressed = b"%PDF-2.0\n<< /Type /Pages /Kids [3 0 R] /Count 12 >>";
+        let BlobPreview::Pdf(info) = BlobPreview::inspect(compressed, None) else {
+            panic!("not a PDF");
+        };
+        assert_eq!(info.pages, Some(12));
+        assert_eq!(info.title, None);
+    }
+
+    #[test]
+    fn test_inspect_archives() {
+        let bytes = zip(&[("src/", b""), ("src/main.rs", b"fn main() {}")]);
+        let BlobPreview::Zip(listing) = BlobPreview::inspect(&bytes, None) else {
+            panic!("not a ZIP file");
+        };
+        assert_eq!(listing.total, 2);
+        assert!(listing.entries[0].is_dir);
+        assert_eq!(listing.entries[1].name, "src/main.rs");
+        assert_eq!(listing.entries[1].size, 12);
+
+        let mut tar = tar_header("notes.txt", 600, b'0');
+        tar.extend(vec![b'x'; 1024]);
+        tar.extend(tar_header("docs/", 0, b'5'));
+        tar.extend(vec![0; 1024]);
+        let BlobPreview::Tar(listing) = BlobPreview::inspect(&tar, None) else {
+            panic!("not a tar file");
+        };
+        assert_eq!(listing.total, 2);
+        assert_eq!(listing.entries[0].size, 600);
+        assert_eq!(listing.entries[1].name, "docs/");
+        assert!(listing.entries[1].is_dir);
+
+        let gzip = b"\x1f\x8b\x08\x08\0\0\0\0\0\x03data.csv\0\x01\x02";
+        assert_eq!(
+            BlobPreview::inspect(gzip, None),
+            BlobPreview::Gzip(Some("data.csv".to_string()))
+        );
+        assert_eq!(
+            BlobPreview::inspect(b"\x1f\x8b\x08\0\0\0\0\0\0\x03", None),
+            BlobPreview::Gzip(None)
+        );
+    }
+
+    #[test]
+    fn test_decode_and_names() {
+        assert_eq!(decode("aGVs\nbG8=\n"), Some(b"hello".to_vec()));
+        assert_eq!(decode("not base64!"), None);
+
+        let pdf = BlobPreview::Pdf(PdfInfo::default());
+        assert_eq!(file_name_for("report", &pdf), "report.pdf");
+        assert_eq!(file_name_for("report.PDF", &pdf), "report.PDF");
+        assert_eq!(file_name_for("unknown", &BlobPreview::None), "resource");
+
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(12_595), "12.3 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+}
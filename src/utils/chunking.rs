@@ -0,0 +1,263 @@
+//! Pasted text and attachments above the size limits, split into parts sent
+//! over several turns
+//!
+//! The first turn carries the prompt with the first part of each oversize
+//! resource and a note that more follow; each following turn carries the next
+//! part of every resource that has one. Parts end at line ends where possible
+//! and are marked in their URI (`#part-2-of-3`), so nothing is cut off.
+
+use agent_client_protocol::{
+    ContentBlock, EmbeddedResource, EmbeddedResourceResource, TextResourceContents,
+};
+
+/// Smallest limit the settings allow
+pub const MIN_LIMIT_CHARS: usize = 1_000;
+
+/// Sizes above which resources are split, in characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkLimits {
+    /// Text pasted as an attachment
+    pub paste_chars: usize,
+    /// Embedded context items: selections, PDFs, tables and MCP resources
+    pub attachment_chars: usize,
+}
+
+/// A split resource's parts after the first
+struct RemainingParts {
+    name: String,
+    total: usize,
+    parts: Vec<ContentBlock>,
+}
+
+/// Blocks of a prompt, some of whose resources may be split over turns
+#[derive(Default)]
+pub struct ChunkedPrompt {
+    blocks: Vec<ContentBlock>,
+    remaining: Vec<RemainingParts>,
+}
+
+impl ChunkedPrompt {
+    pub fn push(&mut self, block: ContentBlock) {
+        self.blocks.push(block);
+    }
+
+    /// Add a resource, split into parts if its text is over `max_chars`
+    pub fn push_limited(&mut self, block: ContentBlock, max_chars: usize) {
+        let Some(resource) = text_resource(&block) else {
+            self.blocks.push(block);
+            return;
+        };
+        let texts = split_text(&resource.text, max_chars);
+        if texts.len() < 2 {
+            self.blocks.push(block);
+            return;
+        }
+
+        let total = texts.len();
+        log::info!("Splitting {} into {} parts", resource.uri, total);
+        let mut parts = texts
+            .into_iter()
+            .enumerate()
+            .map(|(ix, text)| part_block(resource, text, ix + 1, total));
+        self.blocks.extend(parts.next());
+        self.remaining.push(RemainingParts {
+            name: resource_name(&resource.uri),
+            total,
+            parts: parts.collect(),
+        });
+    }
+
+    /// Number of turns the prompt takes
+    pub fn turn_count(&self) -> usize {
+        1 + self
+            .remaining
+            .iter()
+            .map(|remaining| remaining.parts.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The prompt of each turn, in the order to send them
+    pub fn into_turns(self) -> Vec<Vec<ContentBlock>> {
+        let follow_ups = self.turn_count() - 1;
+        let mut first = self.blocks;
+        if follow_ups == 0 {
+            return vec![first];
+        }
+        let names: Vec<String> = self
+            .remaining
+            .iter()
+            .map(|remaining| format!("{} ({} parts)", remaining.name, remaining.total))
+            .collect();
+        first.push(
+            format!(
+                "Attachments too long for one message are split into parts: {}. \
+                 This message has the first part of each, the next {} messages have the \
+                 rest. Until the last part has arrived, only acknowledge each part; then \
+                 answer this message.",
+                names.join(", "),
+                follow_ups
+            )
+            .into(),
+        );
+
+        let mut turns = vec![first];
+        let mut remaining: Vec<_> = self
+            .remaining
+            .into_iter()
+            .map(|remaining| (remaining.name, remaining.total, remaining.parts.into_iter()))
+            .collect();
+        for turn in 0..follow_ups {
+            let mut headers = Vec::new();
+            let mut blocks = Vec::new();
+            for (name, total, parts) in remaining.iter_mut() {
+                if let Some(part) = parts.next() {
+                    headers.push(format!("Part {} of {} of {}", turn + 2, total, name));
+                    blocks.push(part);
+                }
+            }
+            if turn + 1 == follow_ups {
+                headers.push("All parts have arrived; answer the message they belong to.".into());
+            }
+            blocks.insert(0, headers.join("\n").into());
+            turns.push(blocks);
+        }
+        turns
+    }
+}
+
+/// `text` in parts of at most `max_chars` characters, ending at line ends
+/// unless a single line is longer than that
+pub fn split_text(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut part_chars = 0;
+    for mut line in text.split_inclusive('\n') {
+        let mut line_chars = line.chars().count();
+        if part_chars + line_chars > max_chars && !part.is_empty() {
+            parts.push(std::mem::take(&mut part));
+            part_chars = 0;
+        }
+        while line_chars > max_chars {
+            let cut = line
+                .char_indices()
+                .nth(max_chars)
+                .map_or(line.len(), |(ix, _)| ix);
+            parts.push(line[..cut].to_string());
+            line = &line[cut..];
+            line_chars -= max_chars;
+        }
+        part.push_str(line);
+        part_chars += line_chars;
+    }
+    if !part.is_empty() || parts.is_empty() {
+        parts.push(part);
+    }
+    parts
+}
+
+fn text_resource(block: &ContentBlock) -> Option<&TextResourceContents> {
+    match block {
+        ContentBlock::Resource(embedded) => match &embedded.resource {
+            EmbeddedResourceResource::TextResourceContents(resource) => Some(resource),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn part_block(
+    resource: &TextResourceContents,
+    text: String,
+    part: usize,
+    total: usize,
+) -> ContentBlock {
+    let mut resource = resource.clone();
+    resource.text = text;
+    resource.uri = part_uri(&resource.uri, part, total);
+    ContentBlock::Resource(EmbeddedResource::new(
+        EmbeddedResourceResource::TextResourceContents(resource),
+    ))
+}
+
+/// `uri` marked as part `part` of `total`, after a fragment it already has
+fn part_uri(uri: &str, part: usize, total: usize) -> String {
+    let separator = if uri.contains('#') { '-' } else { '#' };
+    format!("{}{}part-{}-of-{}", uri, separator, part, total)
+}
+
+fn resource_name(uri: &str) -> String {
+    let path = uri.split('#').next().unwrap_or(uri);
+    path.rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or(uri)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(uri: &str, text: &str) -> ContentBlock {
+        ContentBlock::Resource(EmbeddedResource::new(
+            EmbeddedResourceResource::TextResourceContents(TextResourceContents::new(
+                text.to_string(),
+                uri.to_string(),
+            )),
+        ))
+    }
+
+    fn uri_and_text(block: &ContentBlock) -> (String, String) {
+        let resource = text_resource(block).expect("a text resource");
+        (resource.uri.clone(), resource.text.clone())
+    }
+
+    #[test]
+    fn test_split_text() {
+        assert_eq!(split_text("", 4), vec![""]);
+        assert_eq!(split_text("ab\ncd\n", 10), vec!["ab\ncd\n"]);
+        assert_eq!(split_text("ab\ncd\nef", 6), vec!["ab\ncd\n", "ef"]);
+        assert_eq!(split_text("abcdefg\nh", 3), vec!["abc", "def", "g\nh"]);
+        assert_eq!(split_text("äöü\nß", 2), vec!["äö", "ü\n", "ß"]);
+        let text = "line\n".repeat(50);
+        assert_eq!(split_text(&text, 12).concat(), text);
+    }
+
+    #[test]
+    fn test_turns() {
+        let mut prompt = ChunkedPrompt::default();
+        prompt.push("question".to_string().into());
+        prompt.push_limited(resource("paste:///pasted-1.txt", "aaa\nbbb\nccc\n"), 4);
+        prompt.push_limited(resource("file:///a.rs#L1-L2", "x\ny\n"), 3);
+        prompt.push_limited(resource("paste:///pasted-2.txt", "short"), 100);
+        assert_eq!(prompt.turn_count(), 3);
+
+        let turns = prompt.into_turns();
+        assert_eq!(turns.len(), 3);
+        let first: Vec<_> = turns[0][1..4].iter().map(uri_and_text).collect();
+        assert_eq!(first[0].0, "paste:///pasted-1.txt#part-1-of-3");
+        assert_eq!(first[1].0, "file:///a.rs#L1-L2-part-1-of-2");
+        assert_eq!(first[2].0, "paste:///pasted-2.txt");
+        let ContentBlock::Text(note) = &turns[0][4] else {
+            panic!("the first turn ends with a note");
+        };
+        assert!(note.text.contains("pasted-1.txt (3 parts), a.rs (2 parts)"));
+
+        assert_eq!(turns[1].len(), 3);
+        assert_eq!(uri_and_text(&turns[1][2]).1, "y\n");
+        assert_eq!(turns[2].len(), 2);
+        assert_eq!(
+            uri_and_text(&turns[2][1]),
+            ("paste:///pasted-1.txt#part-3-of-3".into(), "ccc\n".into())
+        );
+        let ContentBlock::Text(header) = &turns[2][0] else {
+            panic!("follow-ups start with a header");
+        };
+        assert!(
+            header
+                .text
+                .starts_with("Part 3 of 3 of pasted-1.txt\nAll parts")
+        );
+    }
+}
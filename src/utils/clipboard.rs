@@ -1,5 +1,62 @@
-use agent_client_protocol::ImageContent;
+use agent_client_protocol::{
+    ContentBlock, EmbeddedResource, EmbeddedResourceResource, ImageContent, TextResourceContents,
+};
 use gpui::{Image, ImageFormat};
+use serde::Deserialize;
+
+/// Pastes with more lines than this are offered as an attachment
+pub const LARGE_PASTE_LINES: usize = 40;
+
+/// Whether pasted text is big enough to offer attaching it instead of inlining
+pub fn is_large_paste(text: &str) -> bool {
+    text.lines().count() > LARGE_PASTE_LINES
+}
+
+/// A pasted text blob sent as an embedded resource instead of inline prompt text
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct PastedText {
+    pub name: String,
+    pub text: String,
+}
+
+impl PastedText {
+    /// Name pastes by their position in the prompt (`pasted-1.txt`, ...)
+    pub fn new(index: usize, text: impl Into<String>) -> Self {
+        Self {
+            name: format!("pasted-{}.txt", index + 1),
+            text: text.into(),
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.text.lines().count()
+    }
+
+    /// First non-empty line, shortened for the attachment chip
+    pub fn preview(&self, max_chars: usize) -> String {
+        let line = self
+            .text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default();
+        if line.chars().count() > max_chars {
+            let truncated: String = line.chars().take(max_chars).collect();
+            format!("{}…", truncated)
+        } else {
+            line.to_string()
+        }
+    }
+
+    pub fn to_content_block(&self) -> ContentBlock {
+        let uri = format!("paste:///{}", self.name);
+        let resource =
+            TextResourceContents::new(self.text.clone(), uri).mime_type("text/plain".to_string());
+        ContentBlock::Resource(EmbeddedResource::new(
+            EmbeddedResourceResource::TextResourceContents(resource),
+        ))
+    }
+}
 
 pub async fn image_to_content(image: Image) -> anyhow::Result<(ImageContent, String)> {
     let temp_path = crate::utils::file::write_image_to_temp_file(&image).await?;
@@ -35,3 +92,20 @@ fn mime_type_for_format(format: ImageFormat) -> &'static str {
         ImageFormat::Ico => "image/icon",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_large_paste_threshold_and_preview() {
+        let small = "line\n".repeat(LARGE_PASTE_LINES);
+        assert!(!is_large_paste(&small));
+        assert!(is_large_paste(&format!("{small}one more")));
+
+        let pasted = PastedText::new(0, "\n   fn main() { println!(\"hello\"); }\n}");
+        assert_eq!(pasted.name, "pasted-1.txt");
+        assert_eq!(pasted.line_count(), 3);
+        assert_eq!(pasted.preview(10), "fn main() …");
+    }
+}
@@ -0,0 +1,381 @@
+//! Context items pinned to a conversation
+//!
+//! Pinned items are attached to every prompt of a session until the user
+//! removes them. Files and URLs are sent as resource links for the agent to
+//! read; code selections are embedded so the agent sees the exact lines. MCP
+//! resources are embedded as read when attached, since the agent may not run
+//! the server they came from.
+//!
+//! PDFs have their text extracted when attached, so that agents without
+//! vision can use them; each one can be switched to sending the PDF itself.
+//! Of large CSV files and spreadsheets, a sample of rows and columns can be
+//! attached as CSV instead.
+
+use std::path::{Path, PathBuf};
+
+use agent_client_protocol::{
+    BlobResourceContents, ContentBlock, EmbeddedResource, EmbeddedResourceResource, ResourceLink,
+    TextResourceContents,
+};
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+
+use crate::app::actions::AddCodeSelection;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ContextItem {
+    File {
+        path: PathBuf,
+    },
+    Selection {
+        file_path: String,
+        start_line: u32,
+        end_line: u32,
+        content: String,
+    },
+    Url {
+        url: String,
+    },
+    McpResource {
+        server: String,
+        uri: String,
+        name: String,
+        mime_type: Option<String>,
+        text: String,
+    },
+    Pdf {
+        path: PathBuf,
+        /// Text extracted when the PDF was attached
+        text: String,
+        /// Send the PDF itself instead of its text
+        #[serde(default)]
+        send_blob: bool,
+    },
+    TableSample {
+        path: PathBuf,
+        /// Rows in the sample and in the whole table
+        rows: usize,
+        total_rows: usize,
+        /// The sample as CSV
+        text: String,
+    },
+}
+
+impl ContextItem {
+    /// Item for an attached file; PDFs are read to extract their text, so
+    /// this is called off the main thread
+    pub fn file(path: PathBuf) -> Self {
+        if !is_pdf(&path) {
+            return Self::File { path };
+        }
+        match pdf_extract::extract_text(&path) {
+            Ok(text) if !text.trim().is_empty() => Self::Pdf {
+                text: tidy_pdf_text(&text),
+                path,
+                send_blob: false,
+            },
+            Ok(_) => {
+                log::info!("{} has no text, attaching it as a link", path.display());
+                Self::File { path }
+            }
+            Err(e) => {
+                log::warn!("Failed to extract the text of {}: {}", path.display(), e);
+                Self::File { path }
+            }
+        }
+    }
+
+    pub fn from_selection(selection: &AddCodeSelection) -> Self {
+        Self::Selection {
+            file_path: selection.file_path.clone(),
+            start_line: selection.start_line,
+            end_line: selection.end_line,
+            content: selection.content.clone(),
+        }
+    }
+
+    /// A URL item, if `input` is an http(s) URL
+    pub fn url(input: &str) -> Option<Self> {
+        let url = input.trim();
+        let rest = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))?;
+        if rest.is_empty() || url.chars().any(char::is_whitespace) {
+            return None;
+        }
+        Some(Self::Url {
+            url: url.to_string(),
+        })
+    }
+
+    /// Short name for the tray chip
+    pub fn label(&self) -> String {
+        match self {
+            Self::File { path } | Self::Pdf { path, .. } => file_name(&path.to_string_lossy()),
+            Self::TableSample {
+                path,
+                rows,
+                total_rows,
+                ..
+            } => format!(
+                "{} ({}/{} rows)",
+                file_name(&path.to_string_lossy()),
+                rows,
+                total_rows
+            ),
+            Self::Selection {
+                file_path,
+                start_line,
+                end_line,
+                ..
+            } => {
+                if start_line == end_line {
+                    format!("{}:{}", file_name(file_path), start_line)
+                } else {
+                    format!("{}:{}~{}", file_name(file_path), start_line, end_line)
+                }
+            }
+            Self::Url { url } => url
+                .trim_start_matches("https://")
+                .trim_start_matches("http://")
+                .trim_end_matches('/')
+                .to_string(),
+            Self::McpResource { name, .. } => name.clone(),
+        }
+    }
+
+    pub fn uri(&self) -> String {
+        match self {
+            Self::File { path } | Self::Pdf { path, .. } => format!("file://{}", path.display()),
+            // The fragment tells the agent that it is not the whole file
+            Self::TableSample {
+                path,
+                rows,
+                total_rows,
+                ..
+            } => format!(
+                "file://{}#sample-{}-of-{}-rows",
+                path.display(),
+                rows,
+                total_rows
+            ),
+            Self::Selection {
+                file_path,
+                start_line,
+                end_line,
+                ..
+            } => format!("file://{}#L{}-L{}", file_path, start_line, end_line),
+            Self::Url { url } | Self::McpResource { uri: url, .. } => url.clone(),
+        }
+    }
+
+    pub fn to_content_block(&self) -> ContentBlock {
+        match self {
+            Self::File { .. } | Self::Url { .. } => {
+                ContentBlock::ResourceLink(ResourceLink::new(self.label(), self.uri()))
+            }
+            Self::Selection { content, .. } => {
+                let resource = TextResourceContents::new(content.clone(), self.uri())
+                    .mime_type("text/plain".to_string());
+                ContentBlock::Resource(EmbeddedResource::new(
+                    EmbeddedResourceResource::TextResourceContents(resource),
+                ))
+            }
+            Self::McpResource {
+                text, mime_type, ..
+            } => {
+                let mime_type = mime_type
+                    .clone()
+                    .unwrap_or_else(|| "text/plain".to_string());
+                let resource =
+                    TextResourceContents::new(text.clone(), self.uri()).mime_type(mime_type);
+                ContentBlock::Resource(EmbeddedResource::new(
+                    EmbeddedResourceResource::TextResourceContents(resource),
+                ))
+            }
+            Self::Pdf {
+                path,
+                text,
+                send_blob,
+            } => {
+                let resource = match send_blob.then(|| std::fs::read(path)) {
+                    Some(Ok(bytes)) => {
+                        let blob = base64::engine::general_purpose::STANDARD.encode(bytes);
+                        EmbeddedResourceResource::BlobResourceContents(
+                            BlobResourceContents::new(blob, self.uri())
+                                .mime_type("application/pdf".to_string()),
+                        )
+                    }
+                    read => {
+                        if let Some(Err(e)) = read {
+                            log::warn!(
+                                "Failed to read {}, sending its text: {}",
+                                path.display(),
+                                e
+                            );
+                        }
+                        EmbeddedResourceResource::TextResourceContents(
+                            TextResourceContents::new(text.clone(), self.uri())
+                                .mime_type("text/plain".to_string()),
+                        )
+                    }
+                };
+                ContentBlock::Resource(EmbeddedResource::new(resource))
+            }
+            Self::TableSample { text, .. } => {
+                let resource = TextResourceContents::new(text.clone(), self.uri())
+                    .mime_type("text/csv".to_string());
+                ContentBlock::Resource(EmbeddedResource::new(
+                    EmbeddedResourceResource::TextResourceContents(resource),
+                ))
+            }
+        }
+    }
+}
+
+fn file_name(path: &str) -> String {
+    path.rsplit(['/', '\\'])
+        .find(|part| !part.is_empty())
+        .unwrap_or(path)
+        .to_string()
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"))
+}
+
+/// Extracted PDF text without trailing spaces and runs of blank lines
+fn tidy_pdf_text(text: &str) -> String {
+    let mut tidy = String::with_capacity(text.len());
+    let mut blank_lines = 0;
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        if !tidy.is_empty() {
+            tidy.push_str(if blank_lines > 0 { "\n\n" } else { "\n" });
+        }
+        tidy.push_str(line);
+        blank_lines = 0;
+    }
+    tidy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_items() {
+        let item = ContextItem::url(" https://docs.rs/gpui/ ").unwrap();
+        assert_eq!(item.label(), "docs.rs/gpui");
+        assert_eq!(item.uri(), "https://docs.rs/gpui/");
+
+        assert!(ContextItem::url("docs.rs").is_none());
+        assert!(ContextItem::url("https://").is_none());
+        assert!(ContextItem::url("https://a b").is_none());
+    }
+
+    #[test]
+    fn test_selection_label_and_uri() {
+        let item = ContextItem::Selection {
+            file_path: "/repo/src/main.rs".into(),
+            start_line: 10,
+            end_line: 20,
+            content: "fn main() {}".into(),
+        };
+        assert_eq!(item.label(), "main.rs:10~20");
+        assert_eq!(item.uri(), "file:///repo/src/main.rs#L10-L20");
+        assert!(matches!(item.to_content_block(), ContentBlock::Resource(_)));
+
+        let file = ContextItem::File {
+            path: PathBuf::from("/repo/Cargo.toml"),
+        };
+        assert_eq!(file.label(), "Cargo.toml");
+        assert!(matches!(file.to_content_block(), ContentBlock::ResourceLink(_)));
+    }
+
+    #[test]
+    fn test_mcp_resource() {
+        let item = ContextItem::McpResource {
+            server: "docs".into(),
+            uri: "docs://guide/intro".into(),
+            name: "Introduction".into(),
+            mime_type: Some("text/markdown".into()),
+            text: "# Intro".into(),
+        };
+        assert_eq!(item.label(), "Introduction");
+        assert_eq!(item.uri(), "docs://guide/intro");
+        let ContentBlock::Resource(resource) = item.to_content_block() else {
+            panic!("MCP resources are embedded");
+        };
+        let EmbeddedResourceResource::TextResourceContents(contents) = resource.resource else {
+            panic!("MCP resources are text");
+        };
+        assert_eq!(contents.text, "# Intro");
+        assert_eq!(contents.mime_type.as_deref(), Some("text/markdown"));
+    }
+
+    #[test]
+    fn test_pdf() {
+        assert_eq!(
+            tidy_pdf_text("\n\nTitle  \n\n\n\nFirst line\nsecond line\n\n"),
+            "Title\n\nFirst line\nsecond line"
+        );
+
+        let path = std::env::temp_dir().join(format!("agentx-{}.pdf", std::process::id()));
+        std::fs::write(&path, b"%PDF-1.7").unwrap();
+        let mut item = ContextItem::Pdf {
+            path: path.clone(),
+            text: "Quarterly report".into(),
+            send_blob: false,
+        };
+        assert_eq!(item.label(), path.file_name().unwrap().to_string_lossy());
+        let ContentBlock::Resource(resource) = item.to_content_block() else {
+            panic!("PDFs are embedded");
+        };
+        assert!(matches!(
+            resource.resource,
+            EmbeddedResourceResource::TextResourceContents(contents)
+                if contents.text == "Quarterly report"
+        ));
+
+        if let ContextItem::Pdf { send_blob, .. } = &mut item {
+            *send_blob = true;
+        }
+        let ContentBlock::Resource(resource) = item.to_content_block() else {
+            panic!("PDFs are embedded");
+        };
+        let EmbeddedResourceResource::BlobResourceContents(contents) = resource.resource else {
+            panic!("the PDF itself is a blob");
+        };
+        assert_eq!(contents.blob, "JVBERi0xLjc=");
+        assert_eq!(contents.mime_type.as_deref(), Some("application/pdf"));
+        _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_table_sample() {
+        let item = ContextItem::TableSample {
+            path: PathBuf::from("/data/sales.xlsx"),
+            rows: 50,
+            total_rows: 12000,
+            text: "region,total\neu,10".into(),
+        };
+        assert_eq!(item.label(), "sales.xlsx (50/12000 rows)");
+        assert_eq!(
+            item.uri(),
+            "file:///data/sales.xlsx#sample-50-of-12000-rows"
+        );
+        let ContentBlock::Resource(resource) = item.to_content_block() else {
+            panic!("samples are embedded");
+        };
+        let EmbeddedResourceResource::TextResourceContents(contents) = resource.resource else {
+            panic!("samples are text");
+        };
+        assert_eq!(contents.mime_type.as_deref(), Some("text/csv"));
+    }
+}
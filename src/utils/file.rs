@@ -1,6 +1,8 @@
 use gpui::Image;
 use std::fs::File;
 
+use crate::core::config_manager;
+
 pub async fn write_image_to_temp_file(image: &Image) -> anyhow::Result<String> {
     let image_bytes = image.bytes();
 
@@ -29,14 +31,14 @@ pub async fn write_image_to_temp_file(image: &Image) -> anyhow::Result<String> {
 
     if has_alpha {
         // For images with transparency, save as PNG to preserve alpha channel
-        let temp_file =
-            std::env::temp_dir().join(format!("{}.png", crate::utils::time::now_millis()));
+        let temp_file = config_manager::get_uploads_dir()
+            .join(format!("{}.png", crate::utils::time::now_millis()));
         img.save_with_format(&temp_file, image::ImageFormat::Png)?;
         Ok(temp_file.to_string_lossy().to_string())
     } else {
         // For images without transparency, convert to JPEG for better compression
-        let temp_file =
-            std::env::temp_dir().join(format!("{}.jpg", crate::utils::time::now_millis()));
+        let temp_file = config_manager::get_uploads_dir()
+            .join(format!("{}.jpg", crate::utils::time::now_millis()));
 
         // Convert to RGB if needed
         let rgb_img = img.to_rgb8();
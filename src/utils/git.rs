@@ -0,0 +1,80 @@
+//! Git helpers for a workspace's repository
+//!
+//! Thin wrappers around the `git` CLI. They block, so call them through
+//! `smol::unblock` from UI code.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context as _, Result, anyhow};
+
+/// A staged change, as listed by `git diff --cached --name-status`
+#[derive(Clone, Debug, PartialEq)]
+pub struct StagedFile {
+    /// Status letter: A(dded), M(odified), D(eleted), R(enamed), ...
+    pub status: String,
+    pub path: String,
+}
+
+/// Files staged for the next commit
+pub fn staged_files(repo: &Path) -> Result<Vec<StagedFile>> {
+    let output = run_git(repo, &["diff", "--cached", "--name-status"])?;
+    Ok(parse_name_status(&output))
+}
+
+/// The staged diff
+pub fn staged_diff(repo: &Path) -> Result<String> {
+    run_git(repo, &["diff", "--cached", "--no-color"])
+}
+
+/// Commit the staged changes with `message`; returns git's summary line
+pub fn commit(repo: &Path, message: &str) -> Result<String> {
+    let output = run_git(repo, &["commit", "--quiet", "-m", message])?;
+    let summary = run_git(repo, &["log", "-1", "--format=%h %s"]).unwrap_or(output);
+    Ok(summary.trim().to_string())
+}
+
+fn run_git(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo)
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git {} failed: {}", args[0], stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn parse_name_status(output: &str) -> Vec<StagedFile> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split('\t');
+            let status = parts.next()?.trim();
+            // Renames and copies list the old path first
+            let path = parts.last()?;
+            (!status.is_empty()).then(|| StagedFile {
+                status: status.chars().take(1).collect(),
+                path: path.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_name_status() {
+        let output = "M\tsrc/lib.rs\nA\tREADME.md\nR100\told.rs\tnew.rs\n\n";
+        let files = parse_name_status(output);
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].status, "M");
+        assert_eq!(files[2].status, "R");
+        assert_eq!(files[2].path, "new.rs");
+    }
+}
@@ -0,0 +1,259 @@
+//! Rows of the JSON tree viewer: which nodes of a value are shown, expanded
+//! or matched by a key search, and the path that addresses each of them
+//!
+//! Paths are written as `$.name[0]["key with spaces"]`.
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+/// Path of the root value
+pub const ROOT_PATH: &str = "$";
+
+/// Containers this deep or deeper start collapsed
+const OPEN_DEPTH: usize = 2;
+
+/// Rows returned at most; the rest are dropped
+const MAX_ROWS: usize = 2000;
+
+/// String previews are cut to this many characters
+const MAX_PREVIEW_CHARS: usize = 200;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonKey {
+    Name(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JsonKind {
+    /// An object with this many keys
+    Object(usize),
+    /// An array with this many items
+    Array(usize),
+    String,
+    Number,
+    Bool,
+    Null,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonRow {
+    pub path: String,
+    pub depth: usize,
+    /// `None` for the root
+    pub key: Option<JsonKey>,
+    pub kind: JsonKind,
+    /// The value of a scalar as JSON; empty for objects and arrays
+    pub preview: String,
+    pub expanded: bool,
+    /// The key matches the search
+    pub matched: bool,
+}
+
+/// Path of the `key` member of the object at `parent`
+pub fn key_path(parent: &str, key: &str) -> String {
+    let mut chars = key.chars();
+    let is_identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_identifier {
+        format!("{parent}.{key}")
+    } else {
+        format!("{parent}[{}]", Value::String(key.to_string()))
+    }
+}
+
+/// Path of item `index` of the array at `parent`
+pub fn index_path(parent: &str, index: usize) -> String {
+    format!("{parent}[{index}]")
+}
+
+/// `text` as JSON when it is an object or an array
+pub fn parse_structured(text: &str) -> Option<Value> {
+    let text = text.trim();
+    if !(text.starts_with('{') || text.starts_with('[')) {
+        return None;
+    }
+    serde_json::from_str(text).ok()
+}
+
+/// Rows to show for `value`
+///
+/// Containers up to [`OPEN_DEPTH`] start expanded; `toggled` holds the paths
+/// the user flipped from that. With a `query`, only keys containing it
+/// (ignoring case), their ancestors and their contents are shown, and
+/// ancestors start expanded instead.
+pub fn visible_rows(value: &Value, toggled: &HashSet<String>, query: &str) -> Vec<JsonRow> {
+    let mut walk = Walk {
+        toggled,
+        query: query.trim().to_lowercase(),
+        rows: Vec::new(),
+    };
+    walk.node(value, ROOT_PATH.to_string(), None, 0, false);
+    walk.rows.truncate(MAX_ROWS);
+    walk.rows
+}
+
+struct Walk<'a> {
+    toggled: &'a HashSet<String>,
+    query: String,
+    rows: Vec<JsonRow>,
+}
+
+impl Walk<'_> {
+    /// Add the rows of one node; whether a key in it matches the query
+    fn node(
+        &mut self,
+        value: &Value,
+        path: String,
+        key: Option<JsonKey>,
+        depth: usize,
+        in_match: bool,
+    ) -> bool {
+        let searching = !self.query.is_empty();
+        let matched = match &key {
+            Some(JsonKey::Name(name)) if searching => name.to_lowercase().contains(&self.query),
+            _ => false,
+        };
+        let at = self.rows.len();
+        let (kind, preview) = describe(value);
+        let toggled = self.toggled.contains(&path);
+        self.rows.push(JsonRow {
+            path: path.clone(),
+            depth,
+            key,
+            kind,
+            preview,
+            expanded: false,
+            matched,
+        });
+
+        let children: Vec<(String, JsonKey, &Value)> = match value {
+            Value::Object(object) => object
+                .iter()
+                .map(|(name, child)| (key_path(&path, name), JsonKey::Name(name.clone()), child))
+                .collect(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(ix, child)| (index_path(&path, ix), JsonKey::Index(ix), child))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let mut inner_match = false;
+        let expanded = if searching {
+            // Every child is visited to find the matches below this node
+            let in_match = in_match || matched;
+            for (path, key, child) in children {
+                inner_match |= self.node(child, path, Some(key), depth + 1, in_match);
+            }
+            inner_match != toggled
+        } else {
+            let expanded = (depth < OPEN_DEPTH) != toggled;
+            if expanded && self.rows.len() < MAX_ROWS {
+                for (path, key, child) in children {
+                    self.node(child, path, Some(key), depth + 1, false);
+                }
+            }
+            expanded
+        };
+
+        let expandable = matches!(kind, JsonKind::Object(n) | JsonKind::Array(n) if n > 0);
+        self.rows[at].expanded = expanded && expandable;
+        if !self.rows[at].expanded {
+            self.rows.truncate(at + 1);
+        }
+        if searching && !(matched || inner_match || in_match) {
+            self.rows.truncate(at);
+        }
+        matched || inner_match
+    }
+}
+
+fn describe(value: &Value) -> (JsonKind, String) {
+    match value {
+        Value::Object(object) => (JsonKind::Object(object.len()), String::new()),
+        Value::Array(items) => (JsonKind::Array(items.len()), String::new()),
+        Value::String(_) => {
+            let text = value.to_string();
+            let preview = match text.char_indices().nth(MAX_PREVIEW_CHARS) {
+                Some((end, _)) => format!("{}…", &text[..end]),
+                None => text,
+            };
+            (JsonKind::String, preview)
+        }
+        Value::Number(number) => (JsonKind::Number, number.to_string()),
+        Value::Bool(flag) => (JsonKind::Bool, flag.to_string()),
+        Value::Null => (JsonKind::Null, "null".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(rows: &[JsonRow]) -> Vec<&str> {
+        rows.iter().map(|row| row.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(key_path(ROOT_PATH, "name"), "$.name");
+        assert_eq!(key_path("$.a", "two words"), r#"$.a["two words"]"#);
+        assert_eq!(key_path(ROOT_PATH, "1st"), r#"$["1st"]"#);
+        assert_eq!(index_path("$.items", 3), "$.items[3]");
+    }
+
+    #[test]
+    fn test_parse_structured() {
+        assert_eq!(parse_structured(" {\"a\": 1}\n"), Some(json!({"a": 1})));
+        assert_eq!(parse_structured("[1, 2]"), Some(json!([1, 2])));
+        assert_eq!(parse_structured("42"), None);
+        assert_eq!(parse_structured("[not json"), None);
+    }
+
+    #[test]
+    fn test_expand_and_collapse() {
+        let value = json!({"a": {"b": {"c": 1}}, "list": [true, null]});
+        let rows = visible_rows(&value, &HashSet::new(), "");
+        assert_eq!(
+            paths(&rows),
+            vec!["$", "$.a", "$.a.b", "$.list", "$.list[0]", "$.list[1]"]
+        );
+        assert!(!rows[2].expanded);
+        assert_eq!(rows[3].kind, JsonKind::Array(2));
+        assert_eq!(rows[5].preview, "null");
+
+        let toggled = HashSet::from(["$.a".to_string(), "$.a.b".to_string()]);
+        let rows = visible_rows(&value, &toggled, "");
+        assert_eq!(
+            paths(&rows),
+            vec!["$", "$.a", "$.list", "$.list[0]", "$.list[1]"]
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let value = json!({"user": {"address": {"city": "y"}, "name": "x"}, "other": 1});
+        let rows = visible_rows(&value, &HashSet::new(), "CITY");
+        assert_eq!(
+            paths(&rows),
+            vec!["$", "$.user", "$.user.address", "$.user.address.city"]
+        );
+        assert!(rows[3].matched);
+        assert!(rows[2].expanded);
+
+        // The contents of a matching key are kept
+        let rows = visible_rows(&value, &HashSet::from(["$.user".to_string()]), "user");
+        assert_eq!(
+            paths(&rows),
+            vec!["$", "$.user", "$.user.address", "$.user.name"]
+        );
+
+        assert_eq!(visible_rows(&value, &HashSet::new(), "missing"), vec![]);
+    }
+}
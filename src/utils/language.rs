@@ -0,0 +1,177 @@
+//! Language agents answer in: the instruction added to every prompt of a
+//! workspace with a response language, and a check of the writing system a
+//! reply uses
+//!
+//! The check tells scripts apart (Latin, Chinese, Japanese, Korean,
+//! Cyrillic), not languages sharing one, so English and French look alike.
+
+use agent_client_protocol::{
+    ContentBlock, EmbeddedResource, EmbeddedResourceResource, TextResourceContents,
+};
+
+/// Replies with less text than this (in comparable units) are not checked
+const MIN_CHECKED_UNITS: usize = 12;
+
+/// Latin letters per unit; a Chinese character carries about as much as a
+/// short English word
+const LATIN_LETTERS_PER_UNIT: usize = 5;
+
+/// Writing systems the check tells apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    Latin,
+    Han,
+    /// Japanese: kana, with Han characters
+    Kana,
+    Hangul,
+    Cyrillic,
+}
+
+impl Script {
+    /// The script a language is written in, from its English or native name
+    /// or its code
+    pub fn of_language(language: &str) -> Self {
+        let language = language.trim().to_lowercase();
+        let code = language.split(['-', '_']).next().unwrap_or_default();
+        let is = |names: &[&str], codes: &[&str]| {
+            names.iter().any(|name| language.starts_with(name)) || codes.contains(&code)
+        };
+        if is(
+            &["chinese", "中文", "汉语", "漢語", "简体", "繁體"],
+            &["zh"],
+        ) {
+            Script::Han
+        } else if is(&["japanese", "日本語"], &["ja"]) {
+            Script::Kana
+        } else if is(&["korean", "한국어"], &["ko"]) {
+            Script::Hangul
+        } else if is(
+            &["russian", "ukrainian", "bulgarian", "русский"],
+            &["ru", "uk", "bg"],
+        ) {
+            Script::Cyrillic
+        } else {
+            Script::Latin
+        }
+    }
+
+    fn of_char(c: char) -> Option<Self> {
+        match c {
+            'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' => Some(Script::Latin),
+            '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' => Some(Script::Han),
+            '\u{3040}'..='\u{30FF}' => Some(Script::Kana),
+            '\u{1100}'..='\u{11FF}' | '\u{AC00}'..='\u{D7AF}' => Some(Script::Hangul),
+            '\u{0400}'..='\u{04FF}' => Some(Script::Cyrillic),
+            _ => None,
+        }
+    }
+}
+
+/// Instruction asking the agent to answer in `language`
+pub fn language_prompt(language: &str) -> String {
+    format!(
+        "Always respond in {language}, including explanations, plans and summaries, even when \
+         files, tool output or earlier messages use another language. Keep code, identifiers, \
+         paths and commands as they are."
+    )
+}
+
+/// The instruction as an embedded resource, so it reaches the agent without
+/// showing up as part of the user's message
+pub fn language_block(language: &str) -> ContentBlock {
+    let resource = TextResourceContents::new(
+        language_prompt(language),
+        "agentx://workspace/response-language".to_string(),
+    )
+    .mime_type("text/plain".to_string());
+    ContentBlock::Resource(EmbeddedResource::new(
+        EmbeddedResourceResource::TextResourceContents(resource),
+    ))
+}
+
+/// Text of a reply without code blocks and inline code
+fn prose(text: &str) -> String {
+    let mut prose = String::new();
+    let mut in_block = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_block = !in_block;
+            continue;
+        }
+        if in_block {
+            continue;
+        }
+        // Odd-numbered pieces between backticks are inline code
+        for (i, piece) in line.split('`').enumerate() {
+            if i % 2 == 0 {
+                prose.push_str(piece);
+            }
+        }
+        prose.push('\n');
+    }
+    prose
+}
+
+/// Whether a reply is mostly written in another script than `language`
+///
+/// Code is ignored, and short replies are never flagged.
+pub fn is_other_language(reply: &str, language: &str) -> bool {
+    let expected = Script::of_language(language);
+    let (mut latin, mut expected_units, mut other_units) = (0, 0, 0);
+    for script in prose(reply).chars().filter_map(Script::of_char) {
+        match script {
+            Script::Latin => latin += 1,
+            // Japanese is written with Han characters too
+            Script::Han if expected == Script::Kana => expected_units += 1,
+            script if script == expected => expected_units += 1,
+            _ => other_units += 1,
+        }
+    }
+    let latin_units = latin / LATIN_LETTERS_PER_UNIT;
+    if expected == Script::Latin {
+        expected_units += latin_units;
+    } else {
+        other_units += latin_units;
+    }
+
+    let total = expected_units + other_units;
+    total >= MIN_CHECKED_UNITS && expected_units * 2 < total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_of_language() {
+        assert_eq!(Script::of_language("Chinese"), Script::Han);
+        assert_eq!(Script::of_language("简体中文"), Script::Han);
+        assert_eq!(Script::of_language("zh-CN"), Script::Han);
+        assert_eq!(Script::of_language("Japanese"), Script::Kana);
+        assert_eq!(Script::of_language("English"), Script::Latin);
+        assert_eq!(Script::of_language("French"), Script::Latin);
+    }
+
+    #[test]
+    fn test_is_other_language() {
+        let chinese = "我已经修改了 `src/main.rs` 中的解析逻辑，现在空输入会返回错误而不是崩溃。\
+                       请运行测试确认一下。";
+        let english = "I changed the parser in src/main.rs so that empty input returns an \
+                       error instead of panicking. Please run the tests to confirm.";
+
+        assert!(!is_other_language(chinese, "Chinese"));
+        assert!(is_other_language(english, "Chinese"));
+        assert!(!is_other_language(english, "English"));
+        assert!(is_other_language(chinese, "English"));
+
+        // Code is not prose
+        let english_with_code = format!(
+            "{}\n```rust\n// 中文注释中文注释中文注释中文注释\n```",
+            english
+        );
+        assert!(!is_other_language(&english_with_code, "English"));
+
+        // Too short to tell
+        assert!(!is_other_language("Done.", "Chinese"));
+    }
+}
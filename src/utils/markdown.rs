@@ -0,0 +1,38 @@
+//! Helpers for writing Markdown documents
+
+/// `text` in a code block, with a fence longer than any backtick run in it
+pub fn fence(text: &str, language: &str) -> String {
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat((longest_run + 1).max(3));
+    format!(
+        "{}{}\n{}\n{}",
+        fence,
+        language,
+        text.trim_end_matches('\n'),
+        fence
+    )
+}
+
+/// `text` as a blockquote, one `>` per line
+pub fn quote(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("> {}", line).trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fence() {
+        assert_eq!(fence("ls\n", ""), "```\nls\n```");
+        assert_eq!(fence("a ```rust b", "md"), "````md\na ```rust b\n````");
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("first\n\nsecond"), "> first\n>\n> second");
+    }
+}
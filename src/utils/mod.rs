@@ -1,6 +1,19 @@
+pub mod avatar;
 pub mod clipboard;
+pub mod blob;
+pub mod chunking;
+pub mod context_item;
 pub mod external_editor;
 pub mod file;
+pub mod git;
+pub mod json_tree;
+pub mod language;
+pub mod markdown;
+pub mod path_filter;
+pub mod session_timeline;
+pub mod slash_command;
+pub mod tabular;
+pub mod task_name;
 pub mod time;
 pub mod tool_call;
 /// Open a folder picker dialog and return the selected path
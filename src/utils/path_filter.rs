@@ -0,0 +1,101 @@
+//! Paths skipped when scanning a workspace
+//!
+//! Every feature that walks a workspace (file tree, file picker, symbol index,
+//! repo map) skips the same paths: `.git`, whatever the workspace's
+//! `.gitignore` ignores, and the exclude globs configured in config.json.
+
+use std::path::{Component, Path, PathBuf};
+
+use autocorrect::ignorer::Ignorer;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+pub struct PathFilter {
+    root: PathBuf,
+    gitignore: Ignorer,
+    globs: GlobSet,
+}
+
+impl PathFilter {
+    pub fn new(root: &Path, exclude_globs: &[String]) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            gitignore: Ignorer::new(&root.to_string_lossy()),
+            globs: build_glob_set(exclude_globs),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether `path` (absolute, or relative to the root) should be skipped
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+        relative
+            .components()
+            .any(|component| component == Component::Normal(".git".as_ref()))
+            || self.globs.is_match(relative)
+            || self.gitignore.is_ignored(&relative.to_string_lossy())
+    }
+}
+
+/// Compile exclude globs; like .gitignore, a pattern without `/` matches at any depth
+fn build_glob_set(patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.trim().trim_end_matches('/');
+        if pattern.is_empty() {
+            continue;
+        }
+        let pattern = match pattern.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if pattern.contains('/') => pattern.to_string(),
+            None => format!("**/{}", pattern),
+        };
+        // Also match everything below an excluded directory
+        for pattern in [pattern.clone(), format!("{}/**", pattern)] {
+            match GlobBuilder::new(&pattern).literal_separator(true).build() {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(e) => log::warn!("Ignoring invalid exclude glob '{}': {}", pattern, e),
+            }
+        }
+    }
+    builder.build().unwrap_or_else(|e| {
+        log::warn!("Failed to build exclude globs: {}", e);
+        GlobSet::empty()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(globs: &[&str]) -> PathFilter {
+        let globs: Vec<String> = globs.iter().map(|glob| glob.to_string()).collect();
+        PathFilter::new(Path::new("/nonexistent/repo"), &globs)
+    }
+
+    #[test]
+    fn test_unanchored_globs_match_at_any_depth() {
+        let filter = filter(&["node_modules", "*.min.js"]);
+        assert!(filter.is_excluded(Path::new("/nonexistent/repo/node_modules")));
+        assert!(filter.is_excluded(Path::new("web/node_modules/react/index.js")));
+        assert!(filter.is_excluded(Path::new("web/dist/app.min.js")));
+        assert!(!filter.is_excluded(Path::new("web/src/app.js")));
+        assert!(!filter.is_excluded(Path::new("/nonexistent/repo")));
+    }
+
+    #[test]
+    fn test_anchored_globs_and_git_dir() {
+        let filter = filter(&["/build", "docs/generated/"]);
+        assert!(filter.is_excluded(Path::new("build/out.o")));
+        assert!(!filter.is_excluded(Path::new("tools/build/main.rs")));
+        assert!(filter.is_excluded(Path::new("docs/generated/api.md")));
+        assert!(filter.is_excluded(Path::new(".git/HEAD")));
+    }
+}
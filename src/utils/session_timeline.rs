@@ -0,0 +1,240 @@
+//! Events of a session on a time axis, for spotting where it stalled
+//!
+//! Built from the persisted history, where every update carries the time it
+//! was written. Consecutive prompt chunks count as one prompt, and pauses of
+//! at least the idle gap between two updates become idle events.
+
+use agent_client_protocol::{ContentBlock, SessionUpdate, ToolCallStatus};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::services::PersistedMessage;
+
+/// Pauses this long or longer show up as idle gaps
+pub const IDLE_GAP_SECS: i64 = 60;
+
+/// Prompt details are cut to this many characters
+const MAX_DETAIL_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineKind {
+    Created,
+    Prompt,
+    ToolCall,
+    /// A tool call that failed
+    Error,
+    Idle,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineEvent {
+    pub kind: TimelineKind,
+    pub at: DateTime<Utc>,
+    /// Length of an idle gap; zero for the other kinds
+    pub duration: Duration,
+    /// Prompt text or tool call title
+    pub detail: String,
+}
+
+impl TimelineEvent {
+    fn new(kind: TimelineKind, at: DateTime<Utc>, detail: String) -> Self {
+        Self {
+            kind,
+            at,
+            duration: Duration::zero(),
+            detail,
+        }
+    }
+
+    /// When the event ends; the start for point events
+    pub fn end(&self) -> DateTime<Utc> {
+        self.at + self.duration
+    }
+}
+
+/// Events of a session created at `created_at` with the history `messages`
+pub fn session_timeline(
+    created_at: DateTime<Utc>,
+    messages: &[PersistedMessage],
+    idle_gap: Duration,
+) -> Vec<TimelineEvent> {
+    let timestamps: Vec<Option<DateTime<Utc>>> = messages
+        .iter()
+        .map(|message| {
+            DateTime::parse_from_rfc3339(&message.timestamp)
+                .ok()
+                .map(|at| at.with_timezone(&Utc))
+        })
+        .collect();
+    // A resumed session may have been registered after its first updates
+    let created_at = timestamps
+        .iter()
+        .flatten()
+        .min()
+        .map_or(created_at, |first| created_at.min(*first));
+
+    let mut events = vec![TimelineEvent::new(
+        TimelineKind::Created,
+        created_at,
+        String::new(),
+    )];
+    let mut last = created_at;
+    let mut in_prompt = false;
+    for (message, at) in messages.iter().zip(timestamps) {
+        let Some(at) = at else {
+            continue;
+        };
+        if at - last >= idle_gap {
+            let mut idle = TimelineEvent::new(TimelineKind::Idle, last, String::new());
+            idle.duration = at - last;
+            events.push(idle);
+        }
+        last = last.max(at);
+
+        let was_in_prompt = std::mem::replace(
+            &mut in_prompt,
+            matches!(message.update, SessionUpdate::UserMessageChunk(_)),
+        );
+        match &message.update {
+            SessionUpdate::UserMessageChunk(chunk) => {
+                let text = match &chunk.content {
+                    ContentBlock::Text(text) => text.text.as_str(),
+                    _ => "",
+                };
+                match events.last_mut() {
+                    Some(event) if was_in_prompt && event.kind == TimelineKind::Prompt => {
+                        event.detail.push_str(text);
+                    }
+                    _ => events.push(TimelineEvent::new(
+                        TimelineKind::Prompt,
+                        at,
+                        text.to_string(),
+                    )),
+                }
+            }
+            SessionUpdate::ToolCall(tool_call) => {
+                let kind = if tool_call.status == ToolCallStatus::Failed {
+                    TimelineKind::Error
+                } else {
+                    TimelineKind::ToolCall
+                };
+                events.push(TimelineEvent::new(kind, at, tool_call.title.clone()));
+            }
+            SessionUpdate::ToolCallUpdate(update)
+                if update.fields.status == Some(ToolCallStatus::Failed) =>
+            {
+                let title = update
+                    .fields
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| update.tool_call_id.to_string());
+                events.push(TimelineEvent::new(TimelineKind::Error, at, title));
+            }
+            _ => {}
+        }
+    }
+
+    for event in &mut events {
+        event.detail = shorten(&event.detail);
+    }
+    events
+}
+
+/// First line of `text`, cut to `MAX_DETAIL_CHARS`
+fn shorten(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or_default();
+    if line.chars().count() <= MAX_DETAIL_CHARS {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(MAX_DETAIL_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Position of `at` between `start` and `end`, from 0 to 1
+pub fn axis_position(at: DateTime<Utc>, start: DateTime<Utc>, end: DateTime<Utc>) -> f32 {
+    let span = (end - start).num_milliseconds();
+    if span <= 0 {
+        return 0.;
+    }
+    ((at - start).num_milliseconds() as f32 / span as f32).clamp(0., 1.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::{
+        ContentChunk, ToolCall, ToolCallId, ToolCallUpdate, ToolCallUpdateFields,
+    };
+
+    fn message(secs: i64, update: SessionUpdate) -> PersistedMessage {
+        let at = start() + Duration::seconds(secs);
+        PersistedMessage::with_timestamp(at.to_rfc3339(), update)
+    }
+
+    fn start() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-02T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn user(text: &str) -> SessionUpdate {
+        SessionUpdate::UserMessageChunk(ContentChunk::new(ContentBlock::from(text.to_string())))
+    }
+
+    #[test]
+    fn test_session_timeline() {
+        let mut fields = ToolCallUpdateFields::default();
+        fields.status = Some(ToolCallStatus::Failed);
+        let failed = ToolCallUpdate::new(ToolCallId::from("call-2".to_string()), fields);
+        let tests = ToolCall::new(
+            ToolCallId::from("call-1".to_string()),
+            "Run tests".to_string(),
+        );
+        let messages = vec![
+            message(1, user("Fix the ")),
+            message(1, user("tests")),
+            message(2, SessionUpdate::ToolCall(tests)),
+            message(3, SessionUpdate::ToolCallUpdate(failed)),
+            message(300, user("Still there?")),
+        ];
+
+        let events = session_timeline(start(), &messages, Duration::seconds(IDLE_GAP_SECS));
+        let kinds: Vec<TimelineKind> = events.iter().map(|event| event.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TimelineKind::Created,
+                TimelineKind::Prompt,
+                TimelineKind::ToolCall,
+                TimelineKind::Error,
+                TimelineKind::Idle,
+                TimelineKind::Prompt,
+            ]
+        );
+        assert_eq!(events[1].detail, "Fix the tests");
+        assert_eq!(events[2].detail, "Run tests");
+        assert_eq!(events[3].detail, "call-2");
+        assert_eq!(events[4].at, start() + Duration::seconds(3));
+        assert_eq!(events[4].duration, Duration::seconds(297));
+        assert_eq!(events[4].end(), start() + Duration::seconds(300));
+    }
+
+    #[test]
+    fn test_timeline_starts_at_first_update() {
+        let messages = vec![message(5, user("Hi"))];
+        let registered = start() + Duration::seconds(60);
+        let events = session_timeline(registered, &messages, Duration::seconds(IDLE_GAP_SECS));
+        assert_eq!(events[0].at, start() + Duration::seconds(5));
+        assert_eq!(events.len(), 2);
+    }
+
+    #[test]
+    fn test_axis_position() {
+        let end = start() + Duration::seconds(100);
+        assert_eq!(
+            axis_position(start() + Duration::seconds(25), start(), end),
+            0.25
+        );
+        assert_eq!(axis_position(end + Duration::seconds(5), start(), end), 1.);
+        assert_eq!(axis_position(start(), start(), start()), 0.);
+    }
+}
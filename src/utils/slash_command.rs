@@ -0,0 +1,222 @@
+//! Slash command helpers for the chat inputs
+//!
+//! Parses the `/command` being typed, fuzzy-ranks the session's available
+//! commands against it and extracts the argument hint advertised in the
+//! command's ACP input spec.
+
+use agent_client_protocol::{AvailableCommand, AvailableCommandInput, UnstructuredCommandInput};
+
+/// Attaches the workspace's repo map; local commands never reach the agent
+pub const REPO_MAP_COMMAND: &str = "repomap";
+
+/// Attaches the workspace snippets most relevant to a query (semantic search)
+pub const SEARCH_COMMAND: &str = "search";
+
+/// Saves a fact to the workspace memory
+pub const REMEMBER_COMMAND: &str = "remember";
+
+/// Drafts a pull request description from the session
+pub const PR_DESCRIPTION_COMMAND: &str = "pr";
+
+/// Restarts the session's agent process in a fresh session
+pub const RESTART_COMMAND: &str = "restart";
+
+/// Argument of `/restart` that tells the fresh session what happened so far
+pub const RESTART_SUMMARY_ARGUMENT: &str = "summary";
+
+/// Commands the app handles itself, offered next to the agent's own
+pub fn local_commands() -> Vec<AvailableCommand> {
+    vec![
+        AvailableCommand::new(
+            REPO_MAP_COMMAND,
+            "Attach a map of the workspace layout and public symbols",
+        )
+        .input(AvailableCommandInput::Unstructured(
+            UnstructuredCommandInput::new("[question]"),
+        )),
+        AvailableCommand::new(
+            SEARCH_COMMAND,
+            "Attach the workspace snippets most relevant to a query",
+        )
+        .input(AvailableCommandInput::Unstructured(
+            UnstructuredCommandInput::new("<query>"),
+        )),
+        AvailableCommand::new(
+            REMEMBER_COMMAND,
+            "Remember a fact for every new session in this workspace",
+        )
+        .input(AvailableCommandInput::Unstructured(
+            UnstructuredCommandInput::new("<fact>"),
+        )),
+        AvailableCommand::new(
+            PR_DESCRIPTION_COMMAND,
+            "Draft a pull request description from this session",
+        ),
+        AvailableCommand::new(
+            RESTART_COMMAND,
+            "Restart the agent in a fresh session, keeping this conversation",
+        )
+        .input(AvailableCommandInput::Unstructured(
+            UnstructuredCommandInput::new("[summary]"),
+        )),
+    ]
+}
+
+/// The arguments of `/name args`, if the input invokes `name`
+pub fn command_arguments<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    let rest = value.trim_start().strip_prefix('/')?.strip_prefix(name)?;
+    if rest.is_empty() {
+        return Some("");
+    }
+    rest.starts_with(char::is_whitespace).then(|| rest.trim())
+}
+
+/// The command name being typed, if the input is still in `/name` position
+///
+/// Returns `None` once the user has typed a space (the command is complete and
+/// arguments follow) or when the input is not a slash command at all.
+pub fn command_query(value: &str) -> Option<&str> {
+    let query = value.trim_start().strip_prefix('/')?;
+    if query.chars().any(char::is_whitespace) {
+        None
+    } else {
+        Some(query)
+    }
+}
+
+/// Commands matching `query`, best match first
+///
+/// An empty query keeps every command in its original order.
+pub fn filter_commands(commands: &[AvailableCommand], query: &str) -> Vec<AvailableCommand> {
+    if query.is_empty() {
+        return commands.to_vec();
+    }
+    let mut scored: Vec<(i32, &AvailableCommand)> = commands
+        .iter()
+        .filter_map(|command| fuzzy_score(query, &command.name).map(|score| (score, command)))
+        .collect();
+    // Stable sort keeps the agent's ordering for equal scores
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    scored.into_iter().map(|(_, command)| command.clone()).collect()
+}
+
+/// Argument placeholder advertised by the command, e.g. `<file> [lines]`
+pub fn input_hint(command: &AvailableCommand) -> Option<String> {
+    match command.input.as_ref()? {
+        AvailableCommandInput::Unstructured(input) => {
+            let hint = input.hint.trim();
+            (!hint.is_empty()).then(|| hint.to_string())
+        }
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// Hint to show while the arguments of a completed `/name ` are still empty
+pub fn pending_argument_hint(value: &str, commands: &[AvailableCommand]) -> Option<String> {
+    let rest = value.trim_start().strip_prefix('/')?;
+    let (name, args) = rest.split_once(char::is_whitespace)?;
+    if !args.trim().is_empty() {
+        return None;
+    }
+    let command = commands.iter().find(|command| command.name == name)?;
+    input_hint(command).map(|hint| format!("/{} {}", command.name, hint))
+}
+
+/// Case-insensitive subsequence match; higher is better, `None` = no match
+///
+/// Rewards matches at the start, right after a separator (`-`, `_`, `:`, `.`)
+/// and runs of consecutive characters, so `rv` ranks `review` above `prev`.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for query_char in query.chars().flat_map(char::to_lowercase) {
+        let offset = candidate[position..]
+            .iter()
+            .position(|&c| c == query_char)?;
+        let index = position + offset;
+
+        score += 1;
+        if index == 0 {
+            score += 8;
+        } else if matches!(candidate[index - 1], '-' | '_' | ':' | '.' | ' ') {
+            score += 5;
+        }
+        if previous.is_some_and(|prev| prev + 1 == index) {
+            score += 4;
+        }
+
+        previous = Some(index);
+        position = index + 1;
+    }
+
+    // Prefer shorter names when everything else is equal
+    Some(score * 16 - candidate.len() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(name: &str) -> AvailableCommand {
+        AvailableCommand::new(name, format!("{name} description"))
+    }
+
+    #[test]
+    fn test_command_query() {
+        assert_eq!(command_query("/rev"), Some("rev"));
+        assert_eq!(command_query("  /"), Some(""));
+        assert_eq!(command_query("/review src"), None);
+        assert_eq!(command_query("review"), None);
+    }
+
+    #[test]
+    fn test_filter_commands_ranks_fuzzy_matches() {
+        let commands = vec![command("prev"), command("review"), command("init")];
+
+        let names: Vec<_> = filter_commands(&commands, "rv")
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(names, vec!["review", "prev"]);
+
+        assert_eq!(filter_commands(&commands, "").len(), 3);
+        assert!(filter_commands(&commands, "xyz").is_empty());
+    }
+
+    #[test]
+    fn test_pending_argument_hint() {
+        let commands = vec![
+            command("review").input(AvailableCommandInput::Unstructured(
+                UnstructuredCommandInput::new("<path>"),
+            )),
+            command("init"),
+        ];
+
+        assert_eq!(
+            pending_argument_hint("/review ", &commands).as_deref(),
+            Some("/review <path>")
+        );
+        assert_eq!(pending_argument_hint("/review src", &commands), None);
+        assert_eq!(pending_argument_hint("/init ", &commands), None);
+        assert_eq!(pending_argument_hint("/review", &commands), None);
+    }
+
+    #[test]
+    fn test_command_arguments() {
+        assert_eq!(command_arguments("/repomap", "repomap"), Some(""));
+        assert_eq!(
+            command_arguments(" /repomap  where is auth? ", "repomap"),
+            Some("where is auth?")
+        );
+        assert_eq!(command_arguments("/repomaps", "repomap"), None);
+        assert_eq!(command_arguments("repomap", "repomap"), None);
+        assert_eq!(
+            command_arguments("/restart summary", RESTART_COMMAND),
+            Some(RESTART_SUMMARY_ARGUMENT)
+        );
+    }
+}
@@ -0,0 +1,458 @@
+//! Tabular tool output: a JSON array or CSV read into columns and rows, for
+//! the table and chart views of a tool call
+//!
+//! CSV fields may be quoted, but a quoted field cannot span lines.
+//!
+//! Attached CSV files and spreadsheets are read into the same table, so that
+//! a sample of their rows and columns can be attached instead of all of it.
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+use anyhow::{Context as _, Result, anyhow};
+use calamine::Reader as _;
+use serde_json::Value;
+
+/// Rows read from one output; the rest are dropped
+const MAX_ROWS: usize = 1000;
+
+/// Outputs longer than this are not parsed
+const MAX_TEXT_LEN: usize = 512 * 1024;
+
+/// Rows read from an attached file; the rest are dropped
+const MAX_FILE_ROWS: usize = 100_000;
+
+/// Attached tables with more rows than this are offered as a sample
+pub const LARGE_TABLE_ROWS: usize = 200;
+
+/// Rows of a table to attach
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowSample {
+    All,
+    First(usize),
+    /// This many rows spread evenly over the table, first and last included
+    Spread(usize),
+}
+
+impl RowSample {
+    /// Indices of the rows picked out of `total`
+    pub fn indices(self, total: usize) -> Vec<usize> {
+        match self {
+            Self::All => (0..total).collect(),
+            Self::First(count) => (0..count.min(total)).collect(),
+            Self::Spread(count) if count >= total => (0..total).collect(),
+            Self::Spread(0) => Vec::new(),
+            Self::Spread(1) => vec![0],
+            Self::Spread(count) => (0..count)
+                .map(|ix| ix * (total - 1) / (count - 1))
+                .collect(),
+        }
+    }
+}
+
+/// Whether `path` is a file [`Table::read_file`] reads
+pub fn is_table_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            ["csv", "tsv", "xlsx", "xlsm", "xls", "ods"]
+                .iter()
+                .any(|known| extension.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Whether `path` is a spreadsheet, which agents cannot read as text
+pub fn is_spreadsheet(path: &Path) -> bool {
+    is_table_file(path)
+        && !path.extension().is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("csv") || extension.eq_ignore_ascii_case("tsv")
+        })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Read a CSV or TSV file, or the first sheet of a spreadsheet; the first
+    /// row holds the column names
+    pub fn read_file(path: &Path) -> Result<Self> {
+        if is_spreadsheet(path) {
+            let mut workbook = calamine::open_workbook_auto(path)
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            let sheet = workbook
+                .worksheet_range_at(0)
+                .ok_or_else(|| anyhow!("{} has no sheets", path.display()))??;
+            let rows = sheet
+                .rows()
+                .map(|row| row.iter().map(|cell| cell.to_string()).collect());
+            return Self::from_rows(rows).ok_or_else(|| anyhow!("{} is empty", path.display()));
+        }
+
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let tsv = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("tsv"));
+        let delimiter = if tsv { '\t' } else { ',' };
+        let rows = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| split_csv_line(line, delimiter));
+        Self::from_rows(rows).ok_or_else(|| anyhow!("{} is empty", path.display()))
+    }
+
+    /// Table with the first row as column names; shorter rows are padded
+    fn from_rows(mut rows: impl Iterator<Item = Vec<String>>) -> Option<Self> {
+        let columns = rows.next()?;
+        let rows = rows
+            .take(MAX_FILE_ROWS)
+            .map(|mut row| {
+                row.resize(columns.len(), String::new());
+                row
+            })
+            .collect();
+        Some(Self { columns, rows })
+    }
+
+    /// The `rows` of the table, with only `columns` in them
+    pub fn select(&self, rows: RowSample, columns: &[usize]) -> Self {
+        let pick = |row: &[String]| -> Vec<String> {
+            columns
+                .iter()
+                .map(|&column| row.get(column).cloned().unwrap_or_default())
+                .collect()
+        };
+        Self {
+            columns: pick(&self.columns),
+            rows: rows
+                .indices(self.rows.len())
+                .into_iter()
+                .map(|row| pick(&self.rows[row]))
+                .collect(),
+        }
+    }
+
+    /// The table as CSV, quoting fields where needed
+    pub fn to_csv(&self) -> String {
+        let line = |fields: &[String]| {
+            fields
+                .iter()
+                .map(|field| {
+                    if field.contains([',', '"', '\n', '\r']) {
+                        format!("\"{}\"", field.replace('"', "\"\""))
+                    } else {
+                        field.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let mut csv = line(&self.columns);
+        for row in &self.rows {
+            csv.push('\n');
+            csv.push_str(&line(row));
+        }
+        csv
+    }
+
+    /// Read `text` as a JSON array or as CSV (or TSV) with a header row;
+    /// `None` if it is neither
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() || text.len() > MAX_TEXT_LEN {
+            return None;
+        }
+        if text.starts_with('[') {
+            Self::from_json(text)
+        } else {
+            Self::from_csv(text)
+        }
+    }
+
+    fn from_json(text: &str) -> Option<Self> {
+        let Ok(Value::Array(items)) = serde_json::from_str::<Value>(text) else {
+            return None;
+        };
+        let items = &items[..items.len().min(MAX_ROWS)];
+        if items.is_empty() {
+            return None;
+        }
+
+        if items.iter().all(Value::is_object) {
+            // Keys in the order they first appear
+            let mut columns: Vec<String> = Vec::new();
+            let keys = items
+                .iter()
+                .filter_map(Value::as_object)
+                .flat_map(|object| object.keys());
+            for key in keys {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+            let rows = items
+                .iter()
+                .map(|item| {
+                    columns
+                        .iter()
+                        .map(|column| item.get(column).map(cell_text).unwrap_or_default())
+                        .collect()
+                })
+                .collect();
+            Some(Self { columns, rows })
+        } else if items.iter().all(Value::is_array) {
+            let width = items
+                .iter()
+                .filter_map(Value::as_array)
+                .map(Vec::len)
+                .max()
+                .unwrap_or_default();
+            if width == 0 {
+                return None;
+            }
+            let columns = (1..=width).map(|column| column.to_string()).collect();
+            let rows = items
+                .iter()
+                .filter_map(Value::as_array)
+                .map(|row| {
+                    (0..width)
+                        .map(|column| row.get(column).map(cell_text).unwrap_or_default())
+                        .collect()
+                })
+                .collect();
+            Some(Self { columns, rows })
+        } else {
+            let rows = items.iter().map(|item| vec![cell_text(item)]).collect();
+            Some(Self {
+                columns: vec!["value".to_string()],
+                rows,
+            })
+        }
+    }
+
+    /// CSV needs at least two columns and every row as wide as the header,
+    /// so prose with commas is not mistaken for a table
+    fn from_csv(text: &str) -> Option<Self> {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let header = lines.next()?;
+        let delimiter = if header.contains('\t') { '\t' } else { ',' };
+        let columns = split_csv_line(header, delimiter);
+        if columns.len() < 2 {
+            return None;
+        }
+
+        let mut rows = Vec::new();
+        for line in lines.take(MAX_ROWS) {
+            let row = split_csv_line(line, delimiter);
+            if row.len() != columns.len() {
+                return None;
+            }
+            rows.push(row);
+        }
+        if rows.is_empty() {
+            return None;
+        }
+        Some(Self { columns, rows })
+    }
+
+    /// Row indices ordered by `column`: numerically when both cells are
+    /// numbers, as text otherwise
+    pub fn sorted_rows(&self, column: usize, ascending: bool) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (self.cell(a, column), self.cell(b, column));
+            let ordering = match (number(a), number(b)) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                _ => a.cmp(b),
+            };
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+        order
+    }
+
+    /// Columns whose non-empty cells are all numbers
+    pub fn numeric_columns(&self) -> Vec<usize> {
+        (0..self.columns.len())
+            .filter(|&column| {
+                let mut cells = self
+                    .rows
+                    .iter()
+                    .map(|row| row.get(column).map_or("", String::as_str))
+                    .filter(|cell| !cell.trim().is_empty())
+                    .peekable();
+                cells.peek().is_some() && cells.all(|cell| number(cell).is_some())
+            })
+            .collect()
+    }
+
+    /// Label and value of every row for a bar chart of `column`
+    ///
+    /// Rows are labelled by the first column that is not numeric, or
+    /// numbered when there is none.
+    pub fn bars(&self, column: usize) -> Vec<(String, f64)> {
+        let numeric = self.numeric_columns();
+        let label_column = (0..self.columns.len()).find(|column| !numeric.contains(column));
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(ix, row)| {
+                let label = match label_column {
+                    Some(label_column) => row.get(label_column).cloned().unwrap_or_default(),
+                    None => (ix + 1).to_string(),
+                };
+                let value = row
+                    .get(column)
+                    .and_then(|cell| number(cell))
+                    .unwrap_or_default();
+                (label, value)
+            })
+            .collect()
+    }
+
+    fn cell(&self, row: usize, column: usize) -> &str {
+        self.rows[row].get(column).map_or("", String::as_str)
+    }
+}
+
+fn number(cell: &str) -> Option<f64> {
+    cell.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+/// A JSON value as table cell text; strings lose their quotes
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Fields of one CSV line; `""` inside a quoted field is a quote
+fn split_csv_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            c if c == delimiter && !quoted => {
+                fields.push(field.trim().to_string());
+                field.clear();
+            }
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json() {
+        let table = Table::parse(r#"[{"name": "a", "size": 3}, {"name": "b", "ok": true}]"#);
+        assert_eq!(
+            table,
+            Some(Table {
+                columns: vec!["name".into(), "size".into(), "ok".into()],
+                rows: vec![
+                    vec!["a".into(), "3".into(), "".into()],
+                    vec!["b".into(), "".into(), "true".into()],
+                ],
+            })
+        );
+
+        let table = Table::parse("[[1, 2], [3]]").unwrap();
+        assert_eq!(table.columns, vec!["1", "2"]);
+        assert_eq!(table.rows[1], vec!["3", ""]);
+
+        assert!(Table::parse("[]").is_none());
+        assert!(Table::parse("[not json").is_none());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let table = Table::parse("city,population\n\"Paris, FR\",2100000\nLyon,500000\n").unwrap();
+        assert_eq!(table.columns, vec!["city", "population"]);
+        assert_eq!(table.rows[0], vec!["Paris, FR", "2100000"]);
+
+        let tsv = Table::parse("a\tb\n1\t2").unwrap();
+        assert_eq!(tsv.rows, vec![vec!["1", "2"]]);
+
+        // Prose is not a table
+        assert!(Table::parse("Done, all tests pass.\nNothing else, really, to do.").is_none());
+        assert!(Table::parse("one column\nvalue").is_none());
+    }
+
+    #[test]
+    fn test_row_sample() {
+        assert_eq!(RowSample::All.indices(3), vec![0, 1, 2]);
+        assert_eq!(RowSample::First(2).indices(5), vec![0, 1]);
+        assert_eq!(RowSample::First(9).indices(2), vec![0, 1]);
+        assert_eq!(RowSample::Spread(3).indices(11), vec![0, 5, 10]);
+        assert_eq!(RowSample::Spread(4).indices(10), vec![0, 3, 6, 9]);
+        assert_eq!(RowSample::Spread(1).indices(10), vec![0]);
+        assert_eq!(RowSample::Spread(0).indices(10), Vec::<usize>::new());
+        assert_eq!(RowSample::Spread(5).indices(2), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_read_select_and_write_csv() {
+        let path = std::env::temp_dir().join(format!("agentx-table-{}.csv", std::process::id()));
+        std::fs::write(
+            &path,
+            "id,city,note\n1,Paris,\"big, old\"\n2,Lyon\n\n3,Nice,\"say \"\"hi\"\"\"\n",
+        )
+        .unwrap();
+        let table = Table::read_file(&path).unwrap();
+        _ = std::fs::remove_file(&path);
+        assert_eq!(table.columns, vec!["id", "city", "note"]);
+        assert_eq!(table.rows.len(), 3);
+        assert_eq!(table.rows[1], vec!["2", "Lyon", ""]);
+
+        let sample = table.select(RowSample::Spread(2), &[2, 0]);
+        assert_eq!(sample.columns, vec!["note", "id"]);
+        assert_eq!(
+            sample.to_csv(),
+            "note,id\n\"big, old\",1\n\"say \"\"hi\"\"\",3"
+        );
+
+        assert!(is_table_file(Path::new("data/Report.XLSX")));
+        assert!(is_spreadsheet(Path::new("report.ods")));
+        assert!(!is_spreadsheet(Path::new("data.tsv")));
+        assert!(!is_table_file(Path::new("notes.txt")));
+    }
+
+    #[test]
+    fn test_sort_and_chart() {
+        let table = Table::parse("name,count\nb,10\na,9\nc,100").unwrap();
+        assert_eq!(table.sorted_rows(1, true), vec![1, 0, 2]);
+        assert_eq!(table.sorted_rows(0, false), vec![2, 0, 1]);
+        assert_eq!(table.numeric_columns(), vec![1]);
+        assert_eq!(
+            table.bars(1),
+            vec![("b".into(), 10.0), ("a".into(), 9.0), ("c".into(), 100.0)]
+        );
+    }
+}
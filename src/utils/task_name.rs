@@ -0,0 +1,103 @@
+//! Task names generated from a template, e.g. "{agent} – {date} – {words}"
+//!
+//! Placeholders: `{prompt}` (the prompt's first line), `{words}` (its first six
+//! words) or `{words:N}`, `{agent}`, `{mode}`, `{label}`, `{date}` and
+//! `{time}`. Unknown placeholders are kept as typed.
+
+use chrono::{DateTime, Local};
+
+/// Names tasks after the prompt's first line
+pub const DEFAULT_TASK_NAME_TEMPLATE: &str = "{prompt}";
+
+/// Words taken by `{words}`
+const DEFAULT_WORDS: usize = 6;
+
+/// What a task name template can refer to
+pub struct TaskNameContext<'a> {
+    pub prompt: &'a str,
+    pub agent_name: &'a str,
+    pub mode: &'a str,
+    pub label: Option<&'a str>,
+    pub now: DateTime<Local>,
+}
+
+/// Fill `template` in; falls back to the prompt's first line when it renders empty
+pub fn render_task_name(template: &str, context: &TaskNameContext) -> String {
+    let first_line = context.prompt.trim().lines().next().unwrap_or_default().trim();
+    let mut name = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        name.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let placeholder = &rest[start + 1..start + len];
+        match placeholder_value(placeholder, first_line, context) {
+            Some(value) => name.push_str(&value),
+            None => name.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    name.push_str(rest);
+
+    // Drop separators left over by empty placeholders, e.g. "{label} – "
+    let name = name.trim_matches(|c: char| c.is_whitespace() || "-–—·|/:".contains(c));
+    if name.is_empty() {
+        first_line.to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn placeholder_value(
+    placeholder: &str,
+    first_line: &str,
+    context: &TaskNameContext,
+) -> Option<String> {
+    let value = match placeholder {
+        "prompt" => first_line.to_string(),
+        "agent" => context.agent_name.to_string(),
+        "mode" => context.mode.to_string(),
+        "label" => context.label.unwrap_or_default().to_string(),
+        "date" => context.now.format("%Y-%m-%d").to_string(),
+        "time" => context.now.format("%H:%M").to_string(),
+        "words" => first_words(context.prompt, DEFAULT_WORDS),
+        _ => {
+            let count = placeholder.strip_prefix("words:")?.trim().parse().ok()?;
+            first_words(context.prompt, count)
+        }
+    };
+    Some(value)
+}
+
+fn first_words(text: &str, count: usize) -> String {
+    text.split_whitespace().take(count).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render_task_name() {
+        let context = TaskNameContext {
+            prompt: "  Fix the login redirect loop after\nsigning out of the admin panel",
+            agent_name: "claude",
+            mode: "code",
+            label: None,
+            now: Local.with_ymd_and_hms(2026, 3, 2, 9, 30, 0).unwrap(),
+        };
+        assert_eq!(
+            render_task_name("{agent} – {date} – {words}", &context),
+            "claude – 2026-03-02 – Fix the login redirect loop after"
+        );
+        assert_eq!(render_task_name("{label} – {words:3}", &context), "Fix the login");
+        assert_eq!(
+            render_task_name("{prompt} {unknown}", &context),
+            "Fix the login redirect loop after {unknown}"
+        );
+        assert_eq!(render_task_name("{label}", &context), "Fix the login redirect loop after");
+    }
+}
@@ -1,5 +1,5 @@
 use chrono::{DateTime, Local, TimeZone};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub fn format_time_friendly<T: TimeZone>(time: &DateTime<T>) -> String {
     let now = Local::now();
@@ -25,3 +25,101 @@ pub fn now_millis() -> u128 {
         .expect("Time went backwards")
         .as_millis()
 }
+
+/// Compact duration for labels: "45s", "12m", "1h 05m"
+pub fn format_duration_short(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// Latency for labels: "850ms", "1.4s", then as `format_duration_short`
+pub fn format_latency(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1000.)
+    } else {
+        format_duration_short(ms)
+    }
+}
+
+/// Adds up active time from activity pings (keystrokes, clicks, scrolling)
+///
+/// The time between two pings counts when they are at most `idle_after`
+/// apart; a longer gap means the user was away and is not counted.
+#[derive(Clone, Debug)]
+pub struct ActivityTimer {
+    idle_after: Duration,
+    last: Option<Instant>,
+    pending: Duration,
+}
+
+impl ActivityTimer {
+    pub fn new(idle_after: Duration) -> Self {
+        Self {
+            idle_after,
+            last: None,
+            pending: Duration::ZERO,
+        }
+    }
+
+    /// Record activity at `now`
+    pub fn ping(&mut self, now: Instant) {
+        if let Some(last) = self.last {
+            let gap = now.saturating_duration_since(last);
+            if gap <= self.idle_after {
+                self.pending += gap;
+            }
+        }
+        self.last = Some(now);
+    }
+
+    /// Active time counted and not yet taken
+    pub fn pending(&self) -> Duration {
+        self.pending
+    }
+
+    /// Take the active time counted so far
+    pub fn take(&mut self) -> Duration {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activity_timer_skips_idle_gaps() {
+        let start = Instant::now();
+        let mut timer = ActivityTimer::new(Duration::from_secs(60));
+        timer.ping(start);
+        timer.ping(start + Duration::from_secs(20));
+        timer.ping(start + Duration::from_secs(50));
+        // Away for ten minutes
+        timer.ping(start + Duration::from_secs(650));
+        timer.ping(start + Duration::from_secs(655));
+        assert_eq!(timer.take(), Duration::from_secs(55));
+        assert_eq!(timer.pending(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_format_duration_short() {
+        assert_eq!(format_duration_short(45_000), "45s");
+        assert_eq!(format_duration_short(12 * 60_000 + 5_000), "12m");
+        assert_eq!(format_duration_short(65 * 60_000), "1h 05m");
+    }
+
+    #[test]
+    fn test_format_latency() {
+        assert_eq!(format_latency(850), "850ms");
+        assert_eq!(format_latency(1_440), "1.4s");
+        assert_eq!(format_latency(90_000), "1m");
+    }
+}
@@ -1,33 +1,44 @@
 use agent_client_protocol as acp;
 use gpui::*;
 use gpui_component::{
-    WindowExt, dock::{
-        DockItem, DockPlacement, Panel, PanelInfo, PanelState, PanelView, TabPanel,
-    }, notification::Notification
+    dock::{DockArea, DockItem, DockPlacement, Panel, PanelInfo, PanelState, PanelView, TabPanel},
+    notification::Notification,
 };
+use rust_i18n::t;
+use std::path::Path;
 use std::sync::Arc;
 
+use crate::app::focus_mode::{FocusMode, NotificationExt as _};
 use crate::{
-    AppState, ConversationPanel, CreateTaskFromWelcome, NewSessionConversationPanel, PanelAction,
-    SendMessageToSession, SettingsPanel, ToggleDockToggleButton, TogglePanelVisible, WelcomePanel,
+    AppSettings, AppState, CompareAgents, ConversationPanel, CreateTaskFromWelcome, ExportSession,
+    ExportStats, NewSessionConversationPanel, PanelAction, SendMessageToSession, SettingsPanel,
+    SyncNow, ToggleDockToggleButton, ToggleFocusMode, TogglePanelVisible, WelcomePanel,
     app::actions::{
-        AddAgent, CancelSession, ChangeConfigPath, PanelCommand, PanelKind, ReloadAgentConfig,
-        RemoveAgent, RestartAgent, SetUploadDir, Submit, UpdateAgent,
+        AddAgent, CancelSession, ChangeConfigPath, MoveSessionPanel, PanelCommand, PanelKind,
+        PinContextItem, ReloadAgentConfig, RemoveAgent, RestartAgent, SetUploadDir, Submit,
+        UpdateAgent,
+    },
+    core::event_bus::{CodeSelectionEvent, WorkspaceUpdateEvent},
+    core::services::{
+        DEFAULT_REPO_MAP_CHARS, ExportFormat, ServiceError, SessionExportFormat, WorkspaceService,
+        generate_repo_map, instruction_blocks, repo_map_block,
     },
     panels::{
         DockPanel,
         dock_panel::{DockPanelContainer, DockPanelState},
     },
     title_bar::OpenSettings,
-    utils,
+    utils::{self, chunking::ChunkedPrompt},
 };
 
 use super::DockWorkspace;
+use super::new_task::TaskStart;
 //   - on_action_panel_action - 添加/展示面板
 //   - on_action_toggle_panel_visible - 切换面板可见性
 //   - on_action_toggle_dock_toggle_button - 切换 dock 按钮显示
 //   - on_action_open - 打开文件夹选择器
 //   - on_action_create_task_from_welcome - 从欢迎面板创建任务
+//   - on_action_compare_agents - 将同一任务发送给多个 Agent 并排对比
 
 impl DockWorkspace {
     pub(super) fn submit(&mut self, _: &Submit, _: &mut Window, _cx: &mut Context<Self>) {
@@ -54,14 +65,13 @@ impl DockWorkspace {
         cx: &mut Context<Self>,
     ) {
         if let Some(session_id) = session_id.as_deref().filter(|id| !id.is_empty()) {
+            Self::publish_session_opened(session_id, cx);
             if self.activate_existing_session_panel(session_id, window, cx) {
                 return;
             }
 
             let session_id = session_id.to_string();
 
-            Self::resume_session_if_needed(&session_id, cx);
-
             self.dock_area.update(cx, |dock_area, cx| {
                 let conversation_panel =
                     DockPanelContainer::panel_for_session(session_id.clone(), window, cx);
@@ -104,37 +114,111 @@ impl DockWorkspace {
         });
     }
 
-    fn resume_session_if_needed(session_id: &str, cx: &mut Context<Self>) {
-        let agent_service = AppState::global(cx).agent_service().cloned();
-        if let Some(agent_service) = agent_service {
-            let session_id_clone = session_id.to_string();
-            cx.spawn(async move |_this, _cx| {
-                if let Some(agent_name) = agent_service.get_agent_for_session(&session_id_clone) {
-                    log::info!(
-                        "Resuming session {} for agent {}",
-                        session_id_clone,
-                        agent_name
-                    );
-                    match agent_service
-                        .resume_session(&agent_name, &session_id_clone)
-                        .await
-                    {
-                        Ok(_) => {
-                            log::info!("Successfully resumed session {}", session_id_clone);
+    /// Carry out what editor plugins and `agent-studio://` links send over
+    /// the editor bridge: open a linked workspace, open and focus the target
+    /// session, then hand the selection to its input
+    pub(super) fn handle_editor_requests(window: &mut Window, cx: &mut Context<Self>) {
+        let Some(mut requests) = AppState::global(cx)
+            .editor_bridge_service()
+            .and_then(|service| service.take_requests())
+        else {
+            return;
+        };
+        let workspace_service = AppState::global(cx).workspace_service().cloned();
+        cx.spawn_in(window, async move |this, window| {
+            while let Some(request) = requests.recv().await {
+                // Links to a folder open it as a workspace, adding it when new
+                let workspace = match (request.workspace.clone(), &workspace_service) {
+                    (Some(path), Some(service)) => {
+                        let path = path.canonicalize().unwrap_or(path);
+                        Some(service.open_workspace(path).await)
+                    }
+                    _ => None,
+                };
+                let handled = window.update(|window, cx| {
+                    window.activate_window();
+                    match workspace {
+                        Some(Ok(workspace)) => {
+                            AppState::global_mut(cx).set_current_working_dir(workspace.path);
+                            let action = PanelAction::show_welcome(Some(workspace.id));
+                            window.dispatch_action(Box::new(action), cx);
                         }
-                        Err(e) => {
-                            log::warn!("Failed to resume session {}: {}", session_id_clone, e);
+                        Some(Err(e)) => {
+                            struct OpenWorkspaceError;
+                            let note = Notification::error(
+                                t!("url_scheme.workspace_failed", error = e.to_string())
+                                    .to_string(),
+                            )
+                            .id::<OpenWorkspaceError>();
+                            window.show_notification(note, cx);
                         }
+                        None => {}
                     }
-                } else {
-                    log::warn!(
-                        "No agent found for session {}, skipping resume",
-                        session_id_clone
-                    );
+                    if let Some(session_id) = request.session_id.clone() {
+                        _ = this.update(cx, |this, cx| {
+                            this.show_conversation_panel(Some(session_id), window, cx);
+                        });
+                    }
+                    // The session's panel exists now, so it takes the selection
+                    let Some(selection) = request.selection else {
+                        return;
+                    };
+                    let bus = AppState::global(cx).code_selection_bus.clone();
+                    if let Ok(bus) = bus.lock() {
+                        bus.publish(CodeSelectionEvent {
+                            selection,
+                            session_id: request.session_id,
+                        });
+                    }
+                });
+                if handled.is_err() {
+                    break;
                 }
-            })
-            .detach();
-        }
+            }
+        })
+        .detach();
+    }
+
+    /// Tell while a new session waits for a free slot on its agent
+    /// (`max_concurrent_sessions`)
+    pub(super) fn show_session_creation_queue(window: &mut Window, cx: &mut Context<Self>) {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        AppState::global(cx).workspace_bus.subscribe(move |event| {
+            if let WorkspaceUpdateEvent::SessionCreationQueued {
+                agent_name,
+                position,
+            } = event
+            {
+                let _ = tx.send((agent_name.clone(), *position));
+            }
+        });
+        cx.spawn_in(window, async move |_this, window| {
+            while let Some((agent_name, position)) = rx.recv().await {
+                if position == 0 {
+                    continue;
+                }
+                let shown = window.update(|window, cx| {
+                    struct SessionCreationQueued;
+                    let message = t!("new_task.queued", agent = agent_name, position = position);
+                    let note =
+                        Notification::info(message.to_string()).id::<SessionCreationQueued>();
+                    window.show_notification(note, cx);
+                });
+                if shown.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Tell panels the session is in view, e.g. so it no longer counts as unread
+    fn publish_session_opened(session_id: &str, cx: &mut Context<Self>) {
+        AppState::global(cx)
+            .workspace_bus
+            .publish(WorkspaceUpdateEvent::SessionOpened {
+                session_id: session_id.to_string(),
+            });
     }
 
     fn find_focused_tab_panel(
@@ -331,6 +415,153 @@ impl DockWorkspace {
         );
         false
     }
+
+    /// Root items of the center and of every side dock, with their placement
+    fn dock_items(&self, cx: &App) -> Vec<(DockPlacement, DockItem)> {
+        let dock_area = self.dock_area.read(cx);
+        let mut items = vec![(DockPlacement::Center, dock_area.center().clone())];
+        let docks = [
+            (DockPlacement::Left, dock_area.left_dock()),
+            (DockPlacement::Right, dock_area.right_dock()),
+            (DockPlacement::Bottom, dock_area.bottom_dock()),
+        ];
+        for (placement, dock) in docks {
+            if let Some(dock) = dock {
+                items.push((placement, dock.read(cx).panel().clone()));
+            }
+        }
+        items
+    }
+
+    /// Locate the container hosting `session_id` within the given dock item
+    ///
+    /// Returns the container together with its ConversationPanel so the conversation
+    /// entity can be re-hosted elsewhere without re-creating it.
+    fn find_session_panel(
+        item: &DockItem,
+        session_id: &str,
+        cx: &mut App,
+    ) -> Option<(Arc<dyn PanelView>, Entity<ConversationPanel>)> {
+        let panel = match item {
+            DockItem::Tabs { view, .. } => {
+                let tab_state = view.read(cx).dump(cx);
+                let ix = tab_state
+                    .children
+                    .iter()
+                    .position(|child| Self::panel_state_contains_session(child, session_id))?;
+                let _ = item.clone().active_index(ix, cx);
+                view.read(cx).active_panel(cx)?
+            }
+            DockItem::Split { items, .. } => {
+                return items
+                    .iter()
+                    .find_map(|item| Self::find_session_panel(item, session_id, cx));
+            }
+            DockItem::Panel { view, .. } => {
+                if !Self::panel_matches_session(view, session_id, cx) {
+                    return None;
+                }
+                view.clone()
+            }
+            DockItem::Tiles { .. } => return None,
+        };
+
+        let container = panel.view().downcast::<DockPanelContainer>().ok()?;
+        let conversation = container
+            .read(cx)
+            .agent_studio
+            .clone()?
+            .downcast::<ConversationPanel>()
+            .ok()?;
+        Some((panel, conversation))
+    }
+
+    /// Handle MoveSessionPanel action - move a live conversation to another dock placement
+    ///
+    /// The ConversationPanel entity is kept alive and re-hosted in a new container, so its
+    /// MessageService subscription keeps streaming and scroll/input state is untouched.
+    pub(super) fn on_action_move_session_panel(
+        &mut self,
+        action: &MoveSessionPanel,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let session_id = action.session_id.clone();
+        let placement = action.placement;
+
+        let found = self.dock_items(cx).into_iter().find_map(|(from, item)| {
+            Self::find_session_panel(&item, &session_id, cx)
+                .map(|(panel, conversation)| (from, panel, conversation))
+        });
+        let Some((from, old_panel, conversation)) = found else {
+            log::warn!(
+                "No open panel for session {}, opening a new one at {:?}",
+                session_id,
+                placement
+            );
+            self.add_conversation_panel_to(Some(session_id), placement, window, cx);
+            return;
+        };
+
+        if from == placement {
+            old_panel.focus_handle(cx).focus(window, cx);
+            return;
+        }
+
+        log::info!(
+            "Moving session {} panel from {:?} to {:?}",
+            session_id,
+            from,
+            placement
+        );
+
+        let panel = Arc::new(DockPanelContainer::panel_for_conversation(conversation, cx));
+        self.dock_area.update(cx, |dock_area, cx| {
+            // Add the new host first so the conversation entity is never orphaned
+            let was_dock_open = dock_area.is_dock_open(placement, cx);
+            dock_area.add_panel(panel.clone(), placement, None, window, cx);
+            dock_area.remove_panel(old_panel, from, window, cx);
+
+            if placement != DockPlacement::Center && !was_dock_open {
+                dock_area.toggle_dock(placement, window, cx);
+            }
+        });
+        panel.focus_handle(cx).focus(window, cx);
+    }
+
+    /// Handle PinContextItem action - pin context to the conversation shown in the center
+    pub(super) fn on_action_pin_context_item(
+        &mut self,
+        action: &PinContextItem,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let center = self.dock_area.read(cx).center().clone();
+        let conversation = Self::find_first_tab_panel(&center, cx)
+            .and_then(|(_, panel)| panel.view().downcast::<DockPanelContainer>().ok())
+            .and_then(|container| container.read(cx).agent_studio.clone())
+            .and_then(|view| view.downcast::<ConversationPanel>().ok())
+            .filter(|conversation| conversation.read(cx).session_id().is_some());
+        let Some(conversation) = conversation else {
+            struct NoConversationToPin;
+            let note = Notification::warning(t!("conversation.context.no_session").to_string())
+                .id::<NoConversationToPin>();
+            window.show_notification(note, cx);
+            return;
+        };
+
+        let label = action.item.label();
+        log::info!("Pinning {} to the active conversation", action.item.uri());
+        conversation.update(cx, |conversation, cx| {
+            conversation.pin_items(vec![action.item.clone()], cx)
+        });
+        struct ContextPinned;
+        let note =
+            Notification::success(t!("conversation.context.pinned", label = label).to_string())
+                .id::<ContextPinned>();
+        window.show_notification(note, cx);
+    }
+
     /// Handle PanelAction - add/show panels with unified parameters
     pub(super) fn on_action_panel_action(
         &mut self,
@@ -338,7 +569,18 @@ impl DockWorkspace {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        match &action.0 {
+        self.record_navigation(&action.0, cx);
+        self.open_panel(&action.0, window, cx);
+    }
+
+    /// Add/show a panel without recording it in the navigation history
+    pub(super) fn open_panel(
+        &mut self,
+        command: &PanelCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match command {
             PanelCommand::Add { panel, placement } => match panel {
                 PanelKind::Conversation { session_id } => {
                     self.add_conversation_panel_to(session_id.clone(), *placement, window, cx);
@@ -406,12 +648,11 @@ impl DockWorkspace {
     ) {
         let session_id = session_id.filter(|id| !id.is_empty());
         if let Some(session_id) = session_id {
+            Self::publish_session_opened(&session_id, cx);
             if self.activate_existing_session_panel(&session_id, window, cx) {
                 return;
             }
 
-            Self::resume_session_if_needed(&session_id, cx);
-
             let panel = Arc::new(Self::panel_for_session(session_id, window, cx));
             self.dock_area.update(cx, |dock_area, cx| {
                 // Check if dock is open BEFORE adding panel
@@ -602,6 +843,175 @@ impl DockWorkspace {
         })
         .detach();
     }
+
+    /// Save task metadata, usage metrics and feedback to a JSON or CSV file
+    pub(super) fn on_action_export_stats(
+        &mut self,
+        _: &ExportStats,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(metrics_service) = AppState::global(cx).metrics_service().cloned() else {
+            log::error!("MetricsService not initialized");
+            return;
+        };
+        let dialog = rfd::AsyncFileDialog::new()
+            .set_title(t!("export_stats.dialog_title").to_string())
+            .set_file_name("agentx-stats.json")
+            .add_filter("JSON", &["json"])
+            .add_filter("CSV", &["csv"]);
+
+        cx.spawn_in(window, async move |_this, window| {
+            let Some(file) = dialog.save_file().await else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+            let export = metrics_service.export_stats().await;
+            let result = export
+                .render(ExportFormat::from_path(&path))
+                .and_then(|rendered| Ok(std::fs::write(&path, rendered)?));
+
+            _ = window.update(|window, cx| {
+                struct ExportStatsResult;
+                let note = match result {
+                    Ok(()) => Notification::success(
+                        t!(
+                            "export_stats.done",
+                            count = export.tasks.len(),
+                            path = path.display()
+                        )
+                        .to_string(),
+                    ),
+                    Err(e) => {
+                        log::error!("Failed to export stats: {:#}", e);
+                        Notification::error(
+                            t!("export_stats.failed", error = e.to_string()).to_string(),
+                        )
+                    }
+                };
+                window.show_notification(note.id::<ExportStatsResult>(), cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Save the transcript of a session as Markdown or JSON
+    pub(super) fn on_action_export_session(
+        &mut self,
+        action: &ExportSession,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let state = AppState::global(cx);
+        let (Some(persistence_service), workspace_service) = (
+            state.persistence_service().cloned(),
+            state.workspace_service().cloned(),
+        ) else {
+            log::error!("PersistenceService not initialized");
+            return;
+        };
+        let session_id = action.session_id.clone();
+        let format = action.format;
+
+        cx.spawn_in(window, async move |_this, window| {
+            let task = match &workspace_service {
+                Some(service) => service.get_task_by_session(&session_id).await,
+                None => None,
+            };
+            let title = task.map_or_else(|| session_id.clone(), |task| task.name);
+            let dialog = rfd::AsyncFileDialog::new()
+                .set_title(t!("conversation.export.dialog_title").to_string())
+                .set_file_name(format.file_name(&title));
+            let dialog = match format {
+                SessionExportFormat::Markdown => dialog.add_filter("Markdown", &["md"]),
+                SessionExportFormat::Json => dialog.add_filter("JSON", &["json"]),
+            };
+            let Some(file) = dialog.save_file().await else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+            let result = match persistence_service
+                .export_session(&session_id, &title, format)
+                .await
+            {
+                Ok(rendered) => std::fs::write(&path, rendered)
+                    .map_err(|e| ServiceError::io("Failed to write transcript", e)),
+                Err(e) => Err(e),
+            };
+
+            _ = window.update(|window, cx| {
+                struct ExportSessionResult;
+                let note = match result {
+                    Ok(()) => Notification::success(
+                        t!("conversation.export.done", path = path.display()).to_string(),
+                    ),
+                    Err(e) => {
+                        log::error!("Failed to export session {}: {}", session_id, e);
+                        Notification::error(
+                            t!("conversation.export.failed", error = e.to_string()).to_string(),
+                        )
+                    }
+                };
+                window.show_notification(note.id::<ExportSessionResult>(), cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Sync settings, prompt templates and memories with the sync backend
+    pub(super) fn on_action_sync_now(
+        &mut self,
+        _: &SyncNow,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        struct SyncResult;
+        let Some(sync_service) = AppState::global(cx).sync_service().cloned() else {
+            let note = Notification::info(t!("sync.not_configured").to_string());
+            window.show_notification(note.id::<SyncResult>(), cx);
+            return;
+        };
+
+        cx.spawn_in(window, async move |_this, window| {
+            let result = sync_service.sync_now().await;
+            _ = window.update(|window, cx| {
+                let note = match result {
+                    Ok(report) if report.conflicts.is_empty() => Notification::success(
+                        t!("sync.done", pulled = report.pulled, pushed = report.pushed).to_string(),
+                    ),
+                    Ok(report) => Notification::warning(
+                        t!(
+                            "sync.done_with_conflicts",
+                            pulled = report.pulled,
+                            pushed = report.pushed,
+                            conflicts = report.conflicts.join(", ")
+                        )
+                        .to_string(),
+                    ),
+                    Err(e) => {
+                        log::error!("Sync failed: {:#}", e);
+                        Notification::error(
+                            t!("sync.failed", error = format!("{:#}", e)).to_string(),
+                        )
+                    }
+                };
+                window.show_notification(note.id::<SyncResult>(), cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Turn focus mode on or off
+    pub(super) fn on_action_toggle_focus_mode(
+        &mut self,
+        _: &ToggleFocusMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        FocusMode::toggle(window, cx);
+        cx.notify();
+    }
+
     pub(super) fn on_action_open_setting_panel(
         &mut self,
         _action: &OpenSettings,
@@ -706,29 +1116,20 @@ impl DockWorkspace {
     ) {
         let agent_name = action.agent_name.clone();
         let task_input = action.task_input.clone();
-        let mode = action.mode.clone();
         let images = action.images.clone();
 
         log::info!(
             "Creating task from welcome: agent={}, mode={}, input={}, images={}",
             agent_name,
-            mode,
+            action.mode,
             task_input,
             images.len()
         );
 
         // Check for existing welcome session (created by WelcomePanel)
+        // IMPORTANT: Reuse it so the task runs in the agent process that's already running
         let welcome_session = AppState::global(cx).welcome_session().cloned();
 
-        // Get services
-        let agent_service = match AppState::global(cx).agent_service() {
-            Some(service) => service.clone(),
-            None => {
-                log::error!("AgentService not initialized");
-                return;
-            }
-        };
-
         let message_service = match AppState::global(cx).message_service() {
             Some(service) => service.clone(),
             None => {
@@ -737,8 +1138,6 @@ impl DockWorkspace {
             }
         };
 
-        let agent_config_service = AppState::global(cx).agent_config_service().cloned();
-
         let workspace_service = match AppState::global(cx).workspace_service() {
             Some(service) => service.clone(),
             None => {
@@ -748,217 +1147,369 @@ impl DockWorkspace {
         };
 
         let dock_area = self.dock_area.clone();
-
-        // Get workspace_id from action or use active workspace
-        let target_workspace_id = action.workspace_id.clone();
+        let start = TaskStart {
+            agent_name: agent_name.clone(),
+            workspace_id: action.workspace_id.clone(),
+            cwd: None,
+            session_id: welcome_session.map(|session| session.session_id),
+            task_input: task_input.clone(),
+            mode: action.mode.clone(),
+            mode_id: None,
+            model_id: None,
+            label: action.label.clone(),
+        };
 
         cx.spawn_in(window, async move |_this, window| {
-            // Step 1: Get target workspace (from action) or active workspace
-            let workspace = if let Some(ws_id) = target_workspace_id {
-                log::info!("Using specified workspace: {}", ws_id);
-                match workspace_service.get_workspace(&ws_id).await {
-                    Some(ws) => ws,
-                    None => {
-                        log::error!("Specified workspace not found: {}", ws_id);
-
-                        // Show error notification
-                        _ = window.update(|window, cx| {
-                            struct WorkspaceNotFoundError;
-                            let note = Notification::error(
-                                format!("Workspace not found: {}", ws_id)
-                            ).id::<WorkspaceNotFoundError>();
-                            window.push_notification(note, cx);
-                        });
+            // Step 1-3: Create the task and its session and open the
+            // conversation, which subscribes before we send the message
+            let Some(started) = Self::start_task(&dock_area, start, window).await else {
+                return;
+            };
+            _ = window.update(|_, cx| AppState::global_mut(cx).clear_welcome_session());
+            let session_id = started.session_id;
 
-                        return;
-                    }
+            // Step 4: Build content blocks from text and images
+            let prompt_blocks = Self::task_prompt_blocks(
+                &workspace_service,
+                &started.workspace.id,
+                &started.workspace.path,
+                task_input,
+                &images,
+                window,
+            )
+            .await;
+
+            // Step 5: Now send the message - panel is subscribed and will receive it
+            match message_service
+                .send_message_to_session(&agent_name, &session_id, prompt_blocks)
+                .await
+            {
+                Ok(response) => {
+                    log::info!(
+                        "Message sent successfully to session {}, Response: {:?}",
+                        session_id,
+                        response
+                    );
                 }
-            } else {
-                log::info!("Using active workspace");
-                match workspace_service.get_active_workspace().await {
-                    Some(ws) => ws,
-                    None => {
-                        log::error!("No active workspace available");
+                Err(e) => {
+                    log::error!("Failed to send message: {}", e);
 
-                        // Show error notification
-                        _ = window.update(|window, cx| {
-                            struct NoActiveWorkspaceError;
-                            let note = Notification::error(
-                                "No workspace available. Please create or open a workspace first."
-                            ).id::<NoActiveWorkspaceError>();
-                            window.push_notification(note, cx);
-                        });
+                    let error_message = Self::send_error_message(&e);
 
-                        return;
-                    }
+                    // Show error notification
+                    _ = window.update(|window, cx| {
+                        struct MessageSendError;
+                        let note = Notification::error(error_message).id::<MessageSendError>();
+                        window.show_notification(note, cx);
+                    });
                 }
-            };
+            }
+        })
+        .detach();
+    }
 
-            let workspace_id = workspace.id.clone();
-            let workspace_cwd = workspace.path.clone();
+    /// Show a new task's conversation as the center, collapsing the right
+    /// and bottom docks
+    ///
+    /// The panel subscribes to the session, so this is called before the
+    /// first prompt is sent.
+    pub(super) fn show_task_conversation(
+        dock_area: &Entity<DockArea>,
+        session_id: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let conversation_panel = Self::panel_for_session(session_id, window, cx);
 
-            log::info!(
-                "Creating task in workspace: {} ({}), cwd: {:?}",
-                workspace.name,
-                workspace_id,
-                workspace_cwd
-            );
+        let conversation_item =
+            DockItem::tab(conversation_panel, &dock_area.downgrade(), window, cx);
 
-            // Step 2: Get or reuse session
-            // IMPORTANT: Reuse welcome_session if it exists (created by WelcomePanel)
-            // This ensures we use the same agent process that's already running
-            let session_id = if let Some(ws) = welcome_session {
-                log::info!(
-                    "Reusing welcome session {} for agent {}",
-                    ws.session_id,
-                    ws.agent_name
-                );
-                ws.session_id
-            } else {
-                // No welcome session, create new one with workspace cwd
-                let mcp_servers = if let Some(service) = agent_config_service {
-                    service
-                        .list_mcp_servers()
-                        .await
-                        .into_iter()
-                        .filter(|(_, config)| config.enabled)
-                        .map(|(name, config)| config.to_acp_mcp_server(name))
-                        .collect()
-                } else {
-                    Vec::new()
-                };
+        // Wrap in split_with_sizes to ensure proper StackPanel hierarchy
+        // This is required for zoom functionality and proper layout persistence
+        let conversation_dock = DockItem::split_with_sizes(
+            Axis::Horizontal,
+            vec![conversation_item],
+            vec![None],
+            &dock_area.downgrade(),
+            window,
+            cx,
+        );
 
-                log::info!(
-                    "Creating new session for agent '{}' with cwd: {:?}",
-                    agent_name,
-                    workspace_cwd
-                );
+        dock_area.update(cx, |dock_area, cx| {
+            dock_area.set_center(conversation_dock, window, cx);
 
-                match agent_service
-                    .create_session_with_mcp_and_cwd(&agent_name, mcp_servers, workspace_cwd.clone())
-                    .await
-                {
-                    Ok(session_id) => {
-                        log::info!(
-                            "Created new session {} for agent {}",
-                            session_id,
-                            agent_name
-                        );
-                        session_id
-                    }
-                    Err(e) => {
-                        let (error_message, error_details) = if e.to_string().contains("server shut down unexpectedly") {
-                            let details = format!(
-                                "Agent '{}' process crashed during session creation. \
-                                Possible reasons:\n\
-                                1. npx/@zed-industries/claude-code-acp is not installed (run: npm install -g @zed-industries/claude-code-acp)\n\
-                                2. Working directory '{}' does not exist or is not accessible\n\
-                                3. Node.js is not properly installed or configured\n\
-                                4. The agent binary has bugs or incompatibilities\n\n\
-                                Original error: {}",
-                                agent_name,
-                                workspace_cwd.display(),
-                                e
-                            );
-                            (
-                                format!("Failed to create task: Agent '{}' crashed", agent_name),
-                                details
-                            )
-                        } else {
-                            (
-                                format!("Failed to create task: {}", e),
-                                e.to_string()
-                            )
-                        };
+            // Collapse right and bottom docks
+            if dock_area.is_dock_open(DockPlacement::Right, cx) {
+                dock_area.toggle_dock(DockPlacement::Right, window, cx);
+            }
+            if dock_area.is_dock_open(DockPlacement::Bottom, cx) {
+                dock_area.toggle_dock(DockPlacement::Bottom, window, cx);
+            }
+        });
+    }
 
-                        log::error!("{}", error_details);
+    /// Content blocks of a new task's first prompt: the task with the
+    /// workspace memory, response language, repo map and images added as
+    /// enabled in settings
+    async fn task_prompt_blocks(
+        workspace_service: &WorkspaceService,
+        workspace_id: &str,
+        workspace_cwd: &Path,
+        task_input: String,
+        images: &[(acp::ImageContent, String)],
+        window: &mut AsyncWindowContext,
+    ) -> Vec<acp::ContentBlock> {
+        let mut prompt_blocks: Vec<acp::ContentBlock> = Vec::new();
+
+        // Prepend what the user asked to remember for this workspace
+        let workspace = workspace_service.get_workspace(workspace_id).await;
+        let include_memory = window
+            .update(|_, cx| AppSettings::global(cx).include_workspace_memory)
+            .unwrap_or(false);
+        if include_memory
+            && let Some(memory) = workspace
+                .as_ref()
+                .and_then(|workspace| workspace.memory_prompt())
+        {
+            prompt_blocks.push(memory.into());
+        }
 
-                        // Show error notification to user
-                        _ = window.update(|window, cx| {
-                            struct TaskCreationError;
-                            let note = Notification::error(error_message)
-                                .id::<TaskCreationError>();
-                            window.push_notification(note, cx);
-                        });
+        // Attach the instruction files of the workspace root (AGENTS.md etc.)
+        let excluded = workspace
+            .as_ref()
+            .map(|workspace| workspace.excluded_instruction_files.clone())
+            .unwrap_or_default();
+        let root = workspace_cwd.to_path_buf();
+        prompt_blocks.extend(smol::unblock(move || instruction_blocks(&root, &excluded)).await);
+
+        // Add text content
+        prompt_blocks.push(task_input.into());
+
+        // Ask for the workspace's response language
+        if let Some(language) = workspace
+            .as_ref()
+            .and_then(|workspace| workspace.response_language.as_deref())
+        {
+            prompt_blocks.push(utils::language::language_block(language));
+        }
 
-                        return;
-                    }
-                }
+        // Orient the agent with the repo map when enabled in settings
+        let include_repo_map = window
+            .update(|_, cx| AppSettings::global(cx).include_repo_map)
+            .unwrap_or(false);
+        if include_repo_map {
+            if let Ok(index) =
+                window.update(|_, cx| AppState::global_mut(cx).symbol_index(workspace_cwd))
+            {
+                let map =
+                    smol::unblock(move || generate_repo_map(&index, DEFAULT_REPO_MAP_CHARS)).await;
+                prompt_blocks.push(repo_map_block(workspace_cwd, map));
+            }
+        }
+
+        // Add image contents - convert schema::ImageContent to agent_client_protocol::ImageContent
+        for (image_content, _filename) in images.iter() {
+            prompt_blocks.push(acp::ContentBlock::Image(image_content.clone()));
+        }
+        log::debug!("Built {} content blocks for prompt", prompt_blocks.len());
+        prompt_blocks
+    }
+
+    /// What to tell the user when sending a prompt failed
+    fn send_error_message(e: &ServiceError) -> String {
+        match e {
+            ServiceError::AgentUnavailable { agent, .. } => format!(
+                "Agent '{}' stopped responding. Restart it from Settings > Agents and send again.",
+                agent
+            ),
+            ServiceError::SessionNotFound(_) => {
+                "The session has ended. Start a new task to continue.".to_string()
+            }
+            ServiceError::SessionLocked(_) => {
+                "The session is locked. Unlock it to send more prompts.".to_string()
+            }
+            _ => format!("Failed to send message: {}", e),
+        }
+    }
+
+    /// Handle CompareAgents action - send one task to several agents at once
+    ///
+    /// Every agent gets its own session and task, and their conversation
+    /// panels are laid out side by side in the center. The tasks share an
+    /// experiment label, so the Feedback panel can compare them later.
+    pub(super) fn on_action_compare_agents(
+        &mut self,
+        action: &CompareAgents,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut agent_names: Vec<String> = Vec::new();
+        for name in &action.agent_names {
+            if !agent_names.contains(name) {
+                agent_names.push(name.clone());
+            }
+        }
+        if agent_names.is_empty() {
+            return;
+        }
+        let task_input = action.task_input.clone();
+        let mode = action.mode.clone();
+        let images = action.images.clone();
+        let label = action
+            .label
+            .clone()
+            .filter(|label| !label.trim().is_empty())
+            .unwrap_or_else(|| {
+                let id = uuid::Uuid::new_v4().simple().to_string();
+                format!("compare-{}", &id[..8])
+            });
+
+        log::info!(
+            "Comparing agents {:?} with label {}: {}",
+            agent_names,
+            label,
+            task_input
+        );
+
+        let app_state = AppState::global(cx);
+        let (Some(agent_service), Some(message_service), Some(workspace_service)) = (
+            app_state.agent_service().cloned(),
+            app_state.message_service().cloned(),
+            app_state.workspace_service().cloned(),
+        ) else {
+            log::error!("Services not initialized");
+            return;
+        };
+        let agent_config_service = app_state.agent_config_service().cloned();
+        let welcome_session = app_state.welcome_session().cloned();
+
+        let dock_area = self.dock_area.clone();
+        let name_template = AppSettings::global(cx).task_name_template.to_string();
+        let target_workspace_id = action.workspace_id.clone();
+
+        cx.spawn_in(window, async move |_this, window| {
+            let workspace = match &target_workspace_id {
+                Some(workspace_id) => workspace_service.get_workspace(workspace_id).await,
+                None => workspace_service.get_active_workspace().await,
+            };
+            let Some(workspace) = workspace else {
+                _ = window.update(|window, cx| {
+                    struct CompareWorkspaceError;
+                    let note = Notification::error(t!("compare.no_workspace").to_string())
+                        .id::<CompareWorkspaceError>();
+                    window.show_notification(note, cx);
+                });
+                return;
             };
 
-            // Step 3: Create WorkspaceTask
+            let mcp_servers: Vec<_> = match agent_config_service {
+                Some(service) => service
+                    .list_mcp_servers()
+                    .await
+                    .into_iter()
+                    .filter(|(_, config)| config.enabled)
+                    .map(|(name, config)| config.to_acp_mcp_server(name))
+                    .collect(),
+                None => Vec::new(),
+            };
 
-            // Create task in workspace
-            let task = match workspace_service
-                .create_task(
-                    &workspace_id,
-                    task_input.clone(),
-                    agent_name.clone(),
-                    mode.clone(),
-                )
-                .await
-            {
-                Ok(task) => {
-                    log::info!(
-                        "Created workspace task: {} in workspace: {}",
-                        task.name,
-                        workspace_id
-                    );
-                    task
+            // Start every agent's session at once, reusing the welcome panel's
+            let sessions = futures::future::join_all(agent_names.iter().map(|agent_name| {
+                let reused = welcome_session
+                    .as_ref()
+                    .filter(|session| &session.agent_name == agent_name)
+                    .map(|session| session.session_id.clone());
+                let agent_service = agent_service.clone();
+                let mcp_servers = mcp_servers.clone();
+                let cwd = workspace.path.clone();
+                async move {
+                    match reused {
+                        Some(session_id) => Ok(session_id),
+                        None => {
+                            agent_service
+                                .create_session_with_mcp_and_cwd(agent_name, mcp_servers, cwd)
+                                .await
+                        }
+                    }
                 }
-                Err(e) => {
-                    log::error!("Failed to create workspace task: {}", e);
+            }))
+            .await;
 
-                    // Show error notification
-                    _ = window.update(|window, cx| {
-                        struct WorkspaceTaskCreationError;
-                        let note = Notification::error(
-                            format!("Failed to create task: {}", e)
-                        ).id::<WorkspaceTaskCreationError>();
-                        window.push_notification(note, cx);
-                    });
+            let mut compared: Vec<(String, String)> = Vec::new();
+            for (agent_name, session) in agent_names.into_iter().zip(sessions) {
+                let session_id = match session {
+                    Ok(session_id) => session_id,
+                    Err(e) => {
+                        log::error!("Failed to create session for agent {}: {}", agent_name, e);
+                        let message = t!(
+                            "compare.session_failed",
+                            agent = agent_name,
+                            error = e.to_string()
+                        )
+                        .to_string();
+                        _ = window.update(|window, cx| {
+                            struct CompareSessionError;
+                            let note = Notification::error(message).id::<CompareSessionError>();
+                            window.show_notification(note, cx);
+                        });
+                        continue;
+                    }
+                };
 
-                    return;
+                match workspace_service
+                    .create_task(
+                        &workspace.id,
+                        &task_input,
+                        agent_name.clone(),
+                        mode.clone(),
+                        Some(label.clone()),
+                        &name_template,
+                    )
+                    .await
+                {
+                    Ok(task) => {
+                        if let Err(e) = workspace_service
+                            .set_task_session(&task.id, session_id.clone())
+                            .await
+                        {
+                            log::error!("Failed to associate session with task: {}", e);
+                        }
+                    }
+                    Err(e) => log::error!("Failed to create task for agent {}: {}", agent_name, e),
                 }
-            };
-
-            // Associate session with task
-            if let Err(e) = workspace_service
-                .set_task_session(&task.id, session_id.clone())
-                .await
-            {
-                log::error!("Failed to associate session with task: {}", e);
+                compared.push((agent_name, session_id));
+            }
+            if compared.is_empty() {
+                return;
             }
 
-            // Step 3: Clear welcome session and create ConversationPanel
-            // Panel will subscribe to session updates BEFORE we send the message
-            let session_id_for_send = session_id.clone();
-            let task_id = task.id.clone();
+            // Panels subscribe to their sessions before the prompt is sent
+            let session_ids: Vec<String> = compared
+                .iter()
+                .map(|(_, session_id)| session_id.clone())
+                .collect();
             _ = window.update(move |window, cx| {
-                // Clear welcome session
                 AppState::global_mut(cx).clear_welcome_session();
 
-                // Create panel - this subscribes to the session
-                let conversation_panel = Self::panel_for_session(session_id, window, cx);
-
-                let conversation_item =
-                    DockItem::tab(conversation_panel, &dock_area.downgrade(), window, cx);
-
-                // Wrap in split_with_sizes to ensure proper StackPanel hierarchy
-                // This is required for zoom functionality and proper layout persistence
-                let conversation_dock = DockItem::split_with_sizes(
+                let mut items = Vec::new();
+                for session_id in session_ids {
+                    let panel = Self::panel_for_session(session_id, window, cx);
+                    items.push(DockItem::tab(panel, &dock_area.downgrade(), window, cx));
+                }
+                let sizes = vec![None; items.len()];
+                let compare_dock = DockItem::split_with_sizes(
                     Axis::Horizontal,
-                    vec![conversation_item],
-                    vec![None],
+                    items,
+                    sizes,
                     &dock_area.downgrade(),
                     window,
                     cx,
                 );
 
                 dock_area.update(cx, |dock_area, cx| {
-                    dock_area.set_center(conversation_dock, window, cx);
+                    dock_area.set_center(compare_dock, window, cx);
 
-                    // Collapse right and bottom docks
+                    // Leave the width to the compared conversations
                     if dock_area.is_dock_open(DockPlacement::Right, cx) {
                         dock_area.toggle_dock(DockPlacement::Right, window, cx);
                     }
@@ -966,51 +1517,46 @@ impl DockWorkspace {
                         dock_area.toggle_dock(DockPlacement::Bottom, window, cx);
                     }
                 });
-
-                // Task created - TaskPanel will pick it up via periodic refresh
-                log::info!("[DockWorkspace] Task created ({})", task_id);
             });
 
-            // Step 4: Build content blocks from text and images
-            let mut prompt_blocks: Vec<acp::ContentBlock> = Vec::new();
-
-            // Add text content
-            prompt_blocks.push(task_input.into());
-
-            // Add image contents - convert schema::ImageContent to agent_client_protocol::ImageContent
-            for (image_content, _filename) in images.iter() {
-                prompt_blocks.push(acp::ContentBlock::Image(image_content.clone()));
-            }
-            log::debug!("Built {} content blocks for prompt", prompt_blocks.len());
-
-            // Step 5: Now send the message - panel is subscribed and will receive it
-            match message_service
-                .send_message_to_session(&agent_name, &session_id_for_send, prompt_blocks)
-                .await
-            {
-                Ok(response) => {
-                    log::info!(
-                        "Message sent successfully to session {}, Response: {:?}",
-                        session_id_for_send,
-                        response
-                    );
-                }
-                Err(e) => {
-                    log::error!("Failed to send message: {}", e);
-
-                    // Show error notification
-                    _ = window.update(|window, cx| {
-                        struct MessageSendError;
-                        let note = Notification::error(
-                            format!("Failed to send message: {}", e)
-                        ).id::<MessageSendError>();
-                        window.push_notification(note, cx);
-                    });
+            let prompt_blocks = Self::task_prompt_blocks(
+                &workspace_service,
+                &workspace.id,
+                &workspace.path,
+                task_input,
+                &images,
+                window,
+            )
+            .await;
+
+            // Send the same prompt to every agent at once
+            let results =
+                futures::future::join_all(compared.iter().map(|(agent_name, session_id)| {
+                    message_service.send_message_to_session(
+                        agent_name,
+                        session_id,
+                        prompt_blocks.clone(),
+                    )
+                }))
+                .await;
+            for ((agent_name, session_id), result) in compared.iter().zip(results) {
+                match result {
+                    Ok(_) => log::info!("Comparison prompt sent to session {}", session_id),
+                    Err(e) => {
+                        log::error!("Failed to send comparison prompt to {}: {}", agent_name, e);
+                        let message = format!("{}: {}", agent_name, Self::send_error_message(&e));
+                        _ = window.update(|window, cx| {
+                            struct CompareSendError;
+                            let note = Notification::error(message).id::<CompareSendError>();
+                            window.show_notification(note, cx);
+                        });
+                    }
                 }
             }
         })
         .detach();
     }
+
     /// Create a panel specifically for a session (ConversationPanel only)
     pub fn panel_for_session(
         session_id: String,
@@ -1046,12 +1592,28 @@ impl DockWorkspace {
     pub(super) fn on_action_send_message_to_session(
         &mut self,
         action: &SendMessageToSession,
-        _window: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
         let session_id = action.session_id.clone();
         let message = action.message.clone();
         let images = action.images.clone();
+        let resources = action.resources.clone();
+        let context = action.context.clone();
+        let repo_map = action.repo_map;
+        let search_query = action.search_query.clone();
+        let memory = action.memory;
+        let instructions = action.instructions;
+
+        if search_query.is_some() && !AppState::global(cx).semantic_search_enabled() {
+            struct SemanticSearchUnavailable;
+            let note = Notification::warning(
+                "/search needs \"embeddings\" enabled and an AI model configured in config.json. \
+                Sending the query without search results.",
+            )
+            .id::<SemanticSearchUnavailable>();
+            window.show_notification(note, cx);
+        }
 
         log::info!("Sending message to session: {}", session_id);
 
@@ -1080,28 +1642,121 @@ impl DockWorkspace {
                 }
             };
 
-            // Build prompt with text and images
-            let mut prompt_blocks: Vec<acp::ContentBlock> = Vec::new();
-            prompt_blocks.push(message.clone().into());
+            // Attach just the definitions of @symbol mentions, not whole files
+            let mut context = context;
+            let cwd = agent_service
+                .get_session_info(&agent_name, &session_id)
+                .and_then(|info| info.cwd);
+            if let Some(cwd) = cwd.as_ref()
+                && !crate::core::services::symbol_mentions(&message).is_empty()
+            {
+                let index = cx.update(|cx| AppState::global_mut(cx).symbol_index(cwd));
+                let text = message.clone();
+                let definitions = smol::unblock(move || index.resolve_mentions(&text)).await;
+                log::debug!("Attaching {} symbol definitions", definitions.len());
+                for item in definitions {
+                    if !context.contains(&item) {
+                        context.push(item);
+                    }
+                }
+            }
+
+            // Attach the snippets most similar to a /search query
+            if let (Some(query), Some(cwd)) = (search_query, cwd.as_ref()) {
+                match cx.update(|cx| AppState::global_mut(cx).semantic_index(cwd)) {
+                    Some(index) => match index.search(&query).await {
+                        Ok(snippets) => {
+                            log::debug!("Attaching {} search results", snippets.len());
+                            for item in snippets {
+                                if !context.contains(&item) {
+                                    context.push(item);
+                                }
+                            }
+                        }
+                        Err(e) => log::warn!("Semantic search failed for {:?}: {}", cwd, e),
+                    },
+                    None => log::debug!("Semantic search is disabled"),
+                }
+            }
+
+            // Build prompt with memory, instruction files, text, response
+            // language, pasted attachments, pinned context and images
+            let workspace_service =
+                cx.update(|cx| AppState::global(cx).workspace_service().cloned());
+            let workspace = match workspace_service {
+                Some(service) => service.get_workspace_for_session(&session_id).await,
+                None => None,
+            };
+            // Pasted text and attachments above the limits are split over turns
+            let limits = cx.update(|cx| AppSettings::global(cx).chunk_limits());
+            let mut prompt = ChunkedPrompt::default();
+            if memory
+                && let Some(workspace) = workspace.as_ref()
+                && let Some(memory) = workspace.memory_prompt()
+            {
+                log::debug!("Prepending {} memories", workspace.memories.len());
+                prompt.push(memory.into());
+            }
+            if instructions && let Some(cwd) = cwd.clone() {
+                let excluded = workspace
+                    .as_ref()
+                    .map(|workspace| workspace.excluded_instruction_files.clone())
+                    .unwrap_or_default();
+                let blocks = smol::unblock(move || instruction_blocks(&cwd, &excluded)).await;
+                log::debug!("Attaching {} instruction files", blocks.len());
+                for block in blocks {
+                    prompt.push(block);
+                }
+            }
+            prompt.push(message.clone().into());
+            if let Some(language) = workspace
+                .as_ref()
+                .and_then(|workspace| workspace.response_language.as_deref())
+            {
+                prompt.push(utils::language::language_block(language));
+            }
+            if repo_map && let Some(cwd) = cwd {
+                let index = cx.update(|cx| AppState::global_mut(cx).symbol_index(&cwd));
+                let map =
+                    smol::unblock(move || generate_repo_map(&index, DEFAULT_REPO_MAP_CHARS)).await;
+                log::debug!("Attaching repo map ({} chars) for {:?}", map.len(), cwd);
+                prompt.push(repo_map_block(&cwd, map));
+            }
+            for resource in resources.iter() {
+                prompt.push_limited(resource.to_content_block(), limits.paste_chars);
+            }
+            for item in context.iter() {
+                prompt.push_limited(item.to_content_block(), limits.attachment_chars);
+            }
             for (image_content, _filename) in images.iter() {
-                prompt_blocks.push(acp::ContentBlock::Image(image_content.clone()));
+                prompt.push(acp::ContentBlock::Image(image_content.clone()));
             }
 
             log::debug!(
-                "Sending prompt to agent {} for session {}",
+                "Sending prompt to agent {} for session {} in {} turns",
                 agent_name,
-                session_id
+                session_id,
+                prompt.turn_count()
             );
 
-            match message_service
-                .send_message_to_session(&agent_name, &session_id, prompt_blocks)
-                .await
-            {
-                Ok(_response) => {
-                    log::info!("Prompt sent successfully to session: {}", session_id);
-                }
-                Err(e) => {
-                    log::error!("Failed to send prompt to session {}: {}", session_id, e);
+            // Each turn waits for the one before; a failed or cancelled turn
+            // drops the parts after it
+            for prompt_blocks in prompt.into_turns() {
+                match message_service
+                    .send_message_to_session(&agent_name, &session_id, prompt_blocks)
+                    .await
+                {
+                    Ok(response) if response.stop_reason == acp::StopReason::Cancelled => {
+                        log::info!("Session {} cancelled, not sending more parts", session_id);
+                        break;
+                    }
+                    Ok(_response) => {
+                        log::info!("Prompt sent successfully to session: {}", session_id);
+                    }
+                    Err(e) => {
+                        log::error!("Failed to send prompt to session {}: {}", session_id, e);
+                        break;
+                    }
                 }
             }
         })
@@ -1176,6 +1831,10 @@ pub fn add_agent(action: &AddAgent, cx: &mut App) {
         command: action.command.clone(),
         args: action.args.clone(),
         env: action.env.clone(),
+        max_concurrent_sessions: None,
+        network: Default::default(),
+        display: Default::default(),
+        variants: Default::default(),
         nodejs_path: None,
     };
 
@@ -1207,6 +1866,10 @@ pub fn update_agent(action: &UpdateAgent, cx: &mut App) {
         command: action.command.clone(),
         args: action.args.clone(),
         env: action.env.clone(),
+        max_concurrent_sessions: None,
+        network: Default::default(),
+        display: Default::default(),
+        variants: Default::default(),
         nodejs_path: None,
     };
 
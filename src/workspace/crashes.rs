@@ -0,0 +1,104 @@
+//! Crash recovery when AgentX starts again: the report of the last crash,
+//! and a notice while running in safe mode
+
+use std::path::Path;
+
+use gpui::*;
+use gpui_component::{
+    ActiveTheme, Sizable, WindowExt,
+    button::{Button, ButtonVariants as _},
+    dialog::DialogButtonProps,
+    h_flex,
+    notification::Notification,
+    v_flex,
+};
+use rust_i18n::t;
+
+use crate::app::focus_mode::NotificationExt as _;
+use crate::core::{crash_report, safe_mode};
+use crate::utils::external_editor::open_in_file_manager;
+
+use super::DockWorkspace;
+
+impl DockWorkspace {
+    /// Show the report a crash left behind; each report is offered once
+    pub(super) fn offer_crash_report(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(path) = crash_report::take_pending_report() else {
+            return;
+        };
+        log::info!("Offering crash report {}", path.display());
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let muted_foreground = cx.theme().muted_foreground;
+            dialog
+                .title(t!("crash_report.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("crash_report.submit").to_string())
+                        .cancel_text(t!("crash_report.dismiss").to_string()),
+                )
+                .on_ok({
+                    let path = path.clone();
+                    move |_, _window, cx| {
+                        submit_report(&path, cx);
+                        true
+                    }
+                })
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(
+                            div()
+                                .text_sm()
+                                .child(t!("crash_report.message").to_string()),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .justify_between()
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(muted_foreground)
+                                        .overflow_hidden()
+                                        .text_ellipsis()
+                                        .child(path.display().to_string()),
+                                )
+                                .child(
+                                    Button::new("open-crash-report")
+                                        .label(t!("crash_report.open").to_string())
+                                        .small()
+                                        .outline()
+                                        .on_click({
+                                            let path = path.clone();
+                                            move |_, _, _| {
+                                                if let Err(e) = open_in_file_manager(&path) {
+                                                    log::warn!("{}", e);
+                                                }
+                                            }
+                                        }),
+                                ),
+                        ),
+                )
+        });
+    }
+
+    /// Tell what safe mode leaves out and how to leave it
+    pub(super) fn notify_safe_mode(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !safe_mode::is_active() {
+            return;
+        }
+        struct SafeModeNotice;
+        let note = Notification::warning(t!("safe_mode.notice").to_string()).id::<SafeModeNotice>();
+        window.show_notification(note, cx);
+    }
+}
+
+/// Open a new issue prefilled with the report at `path`
+fn submit_report(path: &Path, cx: &mut App) {
+    match std::fs::read_to_string(path) {
+        Ok(report) => cx.open_url(&crash_report::issue_url(&report)),
+        Err(e) => log::warn!("Failed to read crash report {}: {}", path.display(), e),
+    }
+}
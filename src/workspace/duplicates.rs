@@ -0,0 +1,208 @@
+//! Review dialog for merging duplicate tasks of the active workspace
+
+use std::collections::BTreeSet;
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme, StyledExt as _, WindowExt, checkbox::Checkbox, dialog::DialogButtonProps, h_flex,
+    notification::Notification, v_flex,
+};
+use rust_i18n::t;
+
+use crate::app::focus_mode::NotificationExt as _;
+use crate::{AppState, FindDuplicateTasks, core::services::DuplicateGroup};
+
+use super::DockWorkspace;
+
+/// Groups of duplicates, each merged only if checked
+struct DuplicateReview {
+    groups: Vec<DuplicateGroup>,
+    selected: BTreeSet<usize>,
+}
+
+impl DuplicateReview {
+    fn new(groups: Vec<DuplicateGroup>) -> Self {
+        let selected = (0..groups.len()).collect();
+        Self { groups, selected }
+    }
+
+    fn selected_groups(&self) -> Vec<DuplicateGroup> {
+        self.selected
+            .iter()
+            .filter_map(|ix| self.groups.get(*ix).cloned())
+            .collect()
+    }
+}
+
+impl Render for DuplicateReview {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (secondary, foreground, muted_foreground) = {
+            let theme = cx.theme();
+            (theme.secondary, theme.foreground, theme.muted_foreground)
+        };
+        let rows = self.groups.iter().enumerate().map(|(ix, group)| {
+            let details = t!(
+                "duplicates.group.details",
+                agent = group.agent_name,
+                count = group.duplicates.len(),
+                name = group.keep.name
+            )
+            .to_string();
+
+            h_flex()
+                .w_full()
+                .gap_2()
+                .items_start()
+                .p_2()
+                .rounded(px(6.))
+                .bg(secondary)
+                .child(
+                    Checkbox::new(("duplicate-group", ix))
+                        .checked(self.selected.contains(&ix))
+                        .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                            if *checked {
+                                this.selected.insert(ix);
+                            } else {
+                                this.selected.remove(&ix);
+                            }
+                            cx.notify();
+                        })),
+                )
+                .child(
+                    v_flex()
+                        .flex_1()
+                        .min_w_0()
+                        .gap_0p5()
+                        .child(
+                            div()
+                                .text_sm()
+                                .font_semibold()
+                                .text_color(foreground)
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .whitespace_nowrap()
+                                .child(group.prompt.clone()),
+                        )
+                        .child(div().text_xs().text_color(muted_foreground).child(details)),
+                )
+        });
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(muted_foreground)
+                    .child(t!("duplicates.description").to_string()),
+            )
+            .child(
+                v_flex()
+                    .id("duplicate-groups")
+                    .w_full()
+                    .max_h(px(360.))
+                    .overflow_y_scroll()
+                    .gap_2()
+                    .children(rows),
+            )
+            .when(self.selected.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(muted_foreground)
+                        .child(t!("duplicates.none_selected").to_string()),
+                )
+            })
+    }
+}
+
+impl DockWorkspace {
+    /// Find duplicate tasks in the active workspace and offer to merge them
+    pub(super) fn on_action_find_duplicate_tasks(
+        &mut self,
+        _: &FindDuplicateTasks,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let state = AppState::global(cx);
+        let (Some(workspace_service), Some(maintenance_service)) = (
+            state.workspace_service().cloned(),
+            state.maintenance_service().cloned(),
+        ) else {
+            log::error!("MaintenanceService not initialized");
+            return;
+        };
+
+        cx.spawn_in(window, async move |_this, window| {
+            let groups = match workspace_service.get_active_workspace().await {
+                Some(workspace) => maintenance_service.find_duplicates(&workspace.id).await,
+                None => Vec::new(),
+            };
+
+            _ = window.update(|window, cx| {
+                if groups.is_empty() {
+                    struct NoDuplicates;
+                    let note = Notification::info(t!("duplicates.none_found").to_string())
+                        .id::<NoDuplicates>();
+                    window.show_notification(note, cx);
+                    return;
+                }
+
+                let review = cx.new(|_| DuplicateReview::new(groups));
+                window.open_dialog(cx, move |dialog, _window, _cx| {
+                    let review = review.clone();
+                    let maintenance_service = maintenance_service.clone();
+                    dialog
+                        .title(t!("duplicates.title").to_string())
+                        .confirm()
+                        .button_props(
+                            DialogButtonProps::default()
+                                .ok_text(t!("duplicates.merge").to_string())
+                                .cancel_text(t!("duplicates.cancel").to_string()),
+                        )
+                        .on_ok({
+                            let review = review.clone();
+                            move |_, window, cx| {
+                                let groups = review.read(cx).selected_groups();
+                                if groups.is_empty() {
+                                    return false;
+                                }
+                                let maintenance_service = maintenance_service.clone();
+                                window
+                                    .spawn(cx, async move |cx| {
+                                        let mut merged = 0;
+                                        let mut failed = None;
+                                        for group in &groups {
+                                            match maintenance_service.merge_duplicates(group).await
+                                            {
+                                                Ok(()) => merged += group.duplicates.len(),
+                                                Err(e) => failed = Some(e.to_string()),
+                                            }
+                                        }
+                                        _ = cx.update(|window, cx| {
+                                            struct MergeResult;
+                                            let note = match failed {
+                                                Some(error) => Notification::error(
+                                                    t!("duplicates.failed", error = error)
+                                                        .to_string(),
+                                                ),
+                                                None => Notification::success(
+                                                    t!("duplicates.merged", count = merged)
+                                                        .to_string(),
+                                                ),
+                                            };
+                                            window.show_notification(note.id::<MergeResult>(), cx);
+                                        });
+                                    })
+                                    .detach();
+                                true
+                            }
+                        })
+                        .child(review)
+                });
+            });
+        })
+        .detach();
+    }
+}
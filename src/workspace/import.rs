@@ -0,0 +1,290 @@
+//! Review dialog for importing conversations exported from other tools into
+//! the active workspace
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme, StyledExt as _, WindowExt, checkbox::Checkbox, dialog::DialogButtonProps, h_flex,
+    notification::Notification, v_flex,
+};
+use rust_i18n::t;
+
+use crate::app::focus_mode::NotificationExt as _;
+use crate::core::services::{ImportFile, ImportFormat, ImportOptions, ImportReport, collect_files};
+use crate::{AppState, ImportChatLogs};
+
+use super::DockWorkspace;
+
+fn format_label(format: ImportFormat) -> String {
+    match format {
+        ImportFormat::ClaudeCode => t!("import.format.claude_code"),
+        ImportFormat::Aider => t!("import.format.aider"),
+        ImportFormat::Markdown => t!("import.format.markdown"),
+    }
+    .to_string()
+}
+
+/// Files picked for import, each imported only if checked
+struct ImportReview {
+    workspace_name: String,
+    files: Vec<ImportFile>,
+    /// Files that cannot be imported, with the reason
+    unreadable: Vec<(PathBuf, String)>,
+    selected: BTreeSet<usize>,
+}
+
+impl ImportReview {
+    fn new(
+        workspace_name: String,
+        files: Vec<ImportFile>,
+        unreadable: Vec<(PathBuf, String)>,
+    ) -> Self {
+        let selected = (0..files.len()).collect();
+        Self {
+            workspace_name,
+            files,
+            unreadable,
+            selected,
+        }
+    }
+
+    fn selected_files(&self) -> Vec<ImportFile> {
+        self.selected
+            .iter()
+            .filter_map(|ix| self.files.get(*ix).cloned())
+            .collect()
+    }
+}
+
+impl Render for ImportReview {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (secondary, foreground, muted_foreground, danger) = {
+            let theme = cx.theme();
+            (
+                theme.secondary,
+                theme.foreground,
+                theme.muted_foreground,
+                theme.danger,
+            )
+        };
+        let file_name = |path: &PathBuf| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string())
+        };
+
+        let rows = self.files.iter().enumerate().map(|(ix, file)| {
+            let details = t!(
+                "import.file.details",
+                format = format_label(file.format),
+                count = file.conversations.len()
+            )
+            .to_string();
+
+            h_flex()
+                .w_full()
+                .gap_2()
+                .items_start()
+                .p_2()
+                .rounded(px(6.))
+                .bg(secondary)
+                .child(
+                    Checkbox::new(("import-file", ix))
+                        .checked(self.selected.contains(&ix))
+                        .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                            if *checked {
+                                this.selected.insert(ix);
+                            } else {
+                                this.selected.remove(&ix);
+                            }
+                            cx.notify();
+                        })),
+                )
+                .child(
+                    v_flex()
+                        .flex_1()
+                        .min_w_0()
+                        .gap_0p5()
+                        .child(
+                            div()
+                                .text_sm()
+                                .font_semibold()
+                                .text_color(foreground)
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .whitespace_nowrap()
+                                .child(file_name(&file.path)),
+                        )
+                        .child(div().text_xs().text_color(muted_foreground).child(details)),
+                )
+        });
+        let unreadable = self.unreadable.iter().map(|(path, error)| {
+            div().text_xs().text_color(danger).child(
+                t!(
+                    "import.file.unreadable",
+                    name = file_name(path),
+                    error = error
+                )
+                .to_string(),
+            )
+        });
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                div().text_xs().text_color(muted_foreground).child(
+                    t!(
+                        "import.description",
+                        workspace = self.workspace_name.as_str()
+                    )
+                    .to_string(),
+                ),
+            )
+            .child(
+                v_flex()
+                    .id("import-files")
+                    .w_full()
+                    .max_h(px(360.))
+                    .overflow_y_scroll()
+                    .gap_2()
+                    .children(rows)
+                    .children(unreadable),
+            )
+            .when(self.selected.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(muted_foreground)
+                        .child(t!("import.none_selected").to_string()),
+                )
+            })
+    }
+}
+
+impl DockWorkspace {
+    /// Pick exported conversations and offer to import them into the active workspace
+    pub(super) fn on_action_import_chat_logs(
+        &mut self,
+        _: &ImportChatLogs,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let state = AppState::global(cx);
+        let (Some(workspace_service), Some(import_service)) = (
+            state.workspace_service().cloned(),
+            state.import_service().cloned(),
+        ) else {
+            log::error!("ImportService not initialized");
+            return;
+        };
+        let dialog = rfd::AsyncFileDialog::new()
+            .set_title(t!("import.dialog_title").to_string())
+            .add_filter(
+                t!("import.filter").to_string(),
+                &["jsonl", "md", "markdown", "txt"],
+            );
+
+        cx.spawn_in(window, async move |_this, window| {
+            let Some(picked) = dialog.pick_files().await else {
+                return;
+            };
+            let Some(workspace) = workspace_service.get_active_workspace().await else {
+                _ = window.update(|window, cx| {
+                    struct NoWorkspace;
+                    let note = Notification::error(t!("import.no_workspace").to_string())
+                        .id::<NoWorkspace>();
+                    window.show_notification(note, cx);
+                });
+                return;
+            };
+            let paths: Vec<PathBuf> = picked
+                .iter()
+                .map(|file| file.path().to_path_buf())
+                .collect();
+            let (files, unreadable) = smol::unblock(move || {
+                let mut files = Vec::new();
+                let mut unreadable = Vec::new();
+                for path in collect_files(&paths) {
+                    match ImportFile::read(&path, None) {
+                        Ok(file) => files.push(file),
+                        Err(e) => unreadable.push((path, e)),
+                    }
+                }
+                (files, unreadable)
+            })
+            .await;
+
+            _ = window.update(|window, cx| {
+                let review =
+                    cx.new(|_| ImportReview::new(workspace.name.clone(), files, unreadable));
+                let options = ImportOptions {
+                    workspace_id: Some(workspace.id.clone()),
+                    ..Default::default()
+                };
+                window.open_dialog(cx, move |dialog, _window, _cx| {
+                    let review = review.clone();
+                    let import_service = import_service.clone();
+                    let options = options.clone();
+                    dialog
+                        .title(t!("import.title").to_string())
+                        .confirm()
+                        .button_props(
+                            DialogButtonProps::default()
+                                .ok_text(t!("import.import").to_string())
+                                .cancel_text(t!("import.cancel").to_string()),
+                        )
+                        .on_ok({
+                            let review = review.clone();
+                            move |_, window, cx| {
+                                let files = review.read(cx).selected_files();
+                                if files.is_empty() {
+                                    return false;
+                                }
+                                let import_service = import_service.clone();
+                                let options = options.clone();
+                                window
+                                    .spawn(cx, async move |cx| {
+                                        let mut report = ImportReport::default();
+                                        for file in &files {
+                                            import_service
+                                                .import_file(file, &options, &mut report)
+                                                .await;
+                                        }
+                                        _ = cx.update(|window, cx| {
+                                            struct ImportResult;
+                                            let note = match report.failed.first() {
+                                                Some((_, error)) => Notification::error(
+                                                    t!(
+                                                        "import.failed",
+                                                        count = report.failed.len(),
+                                                        error = error
+                                                    )
+                                                    .to_string(),
+                                                ),
+                                                None => Notification::success(
+                                                    t!(
+                                                        "import.done",
+                                                        imported = report.imported,
+                                                        skipped = report.skipped
+                                                    )
+                                                    .to_string(),
+                                                ),
+                                            };
+                                            window.show_notification(note.id::<ImportResult>(), cx);
+                                        });
+                                    })
+                                    .detach();
+                                true
+                            }
+                        })
+                        .child(review)
+                });
+            });
+        })
+        .detach();
+    }
+}
@@ -6,23 +6,36 @@ use smol::Timer;
 use std::{sync::Arc, time::Duration};
 
 use crate::{
-    AppTitleBar, CodeEditorPanel, ConversationPanel, SessionManagerPanel, TaskPanel,
-    TerminalPanel, panels::dock_panel::DockPanelContainer,
+    AppTitleBar, AuditPanel, CodeEditorPanel, ConversationPanel, DashboardPanel, FeedbackPanel,
+    GitPanel, McpBrowserPanel, McpConsolePanel, MemoryPanel, SearchPanel, SessionManagerPanel,
+    TaskPanel, TerminalPanel,
+    app::status_bar::StatusBar,
+    panels::dock_panel::DockPanelContainer,
 };
 
+use self::navigation::NavigationBar;
 use self::startup::StartupState;
 
 // Action handlers module
 pub mod actions;
+mod crashes;
+mod duplicates;
+mod import;
+mod navigation;
+mod new_task;
+mod profiles;
+mod quick_switcher;
 mod startup;
 
 const MAIN_DOCK_AREA: DockAreaTab = DockAreaTab {
     id: "main-dock",
-    version: 5,
+    version: 13,
 };
 
 pub struct DockWorkspace {
     title_bar: Entity<AppTitleBar>,
+    status_bar: Entity<StatusBar>,
+    navigation: Entity<NavigationBar>,
     dock_area: Entity<DockArea>,
     last_layout_state: Option<DockAreaState>,
     toggle_button_visible: bool,
@@ -82,8 +95,11 @@ impl DockWorkspace {
         })
         .detach();
 
+        let navigation = cx.new(|_| NavigationBar::new());
         let title_bar = cx.new(|cx| {
+            let navigation = navigation.clone();
             AppTitleBar::new("Agent Studio", window, cx)
+                .child(move |_, _| navigation.clone())
             // .child({
             //     move |_, cx| {
             //         Button::new("add-panel")
@@ -152,9 +168,22 @@ impl DockWorkspace {
             // })
         });
 
+        let status_bar = cx.new(StatusBar::new);
+
+        Self::handle_editor_requests(window, cx);
+        Self::show_session_creation_queue(window, cx);
+
+        // Dialogs need the window's root, so wait until the workspace is in it
+        cx.defer_in(window, |this, window, cx| {
+            this.offer_crash_report(window, cx);
+            this.notify_safe_mode(window, cx);
+        });
+
         Self {
             dock_area,
             title_bar,
+            status_bar,
+            navigation,
             last_layout_state: None,
             toggle_button_visible: true,
             _save_layout_task: None,
@@ -218,6 +247,9 @@ impl DockWorkspace {
         cx: &mut Context<Self>,
     ) -> Result<()> {
         println!("Load Docks layout...");
+        if crate::core::safe_mode::is_active() {
+            anyhow::bail!("safe mode opens the default layout");
+        }
         let state_file = crate::core::config_manager::get_docks_layout_path();
         let json = std::fs::read_to_string(state_file)?;
         let state = serde_json::from_str::<DockAreaState>(&json)?;
@@ -284,6 +316,14 @@ impl DockWorkspace {
                 vec![
                     Arc::new(DockPanelContainer::panel::<TerminalPanel>(window, cx)),
                     Arc::new(DockPanelContainer::panel::<SessionManagerPanel>(window, cx)),
+                    Arc::new(DockPanelContainer::panel::<SearchPanel>(window, cx)),
+                    Arc::new(DockPanelContainer::panel::<MemoryPanel>(window, cx)),
+                    Arc::new(DockPanelContainer::panel::<GitPanel>(window, cx)),
+                    Arc::new(DockPanelContainer::panel::<FeedbackPanel>(window, cx)),
+                    Arc::new(DockPanelContainer::panel::<DashboardPanel>(window, cx)),
+                    Arc::new(DockPanelContainer::panel::<AuditPanel>(window, cx)),
+                    Arc::new(DockPanelContainer::panel::<McpConsolePanel>(window, cx)),
+                    Arc::new(DockPanelContainer::panel::<McpBrowserPanel>(window, cx)),
                 ],
                 &dock_area,
                 window,
@@ -415,7 +455,8 @@ impl Render for DockWorkspace {
         let dialog_layer = Root::render_dialog_layer(window, cx);
         let notification_layer = Root::render_notification_layer(window, cx);
 
-        let content = if self.startup_completed || self.startup_state.is_complete() {
+        let started = self.startup_completed || self.startup_state.is_complete();
+        let content = if started {
             self.dock_area.clone().into_any_element()
         } else {
             self.render_startup(cx)
@@ -429,15 +470,32 @@ impl Render for DockWorkspace {
             .on_action(cx.listener(Self::on_action_open_setting_panel))
             .on_action(cx.listener(Self::on_action_new_session_conversation_panel))
             .on_action(cx.listener(Self::on_action_create_task_from_welcome))
+            .on_action(cx.listener(Self::on_action_compare_agents))
+            .on_action(cx.listener(Self::on_action_new_task))
+            .on_action(cx.listener(Self::on_action_create_task))
             .on_action(cx.listener(Self::on_action_send_message_to_session))
             .on_action(cx.listener(Self::on_action_cancel_session))
+            .on_action(cx.listener(Self::on_action_move_session_panel))
+            .on_action(cx.listener(Self::on_action_pin_context_item))
             .on_action(cx.listener(Self::on_action_open))
+            .on_action(cx.listener(Self::on_action_export_stats))
+            .on_action(cx.listener(Self::on_action_export_session))
+            .on_action(cx.listener(Self::on_action_find_duplicate_tasks))
+            .on_action(cx.listener(Self::on_action_import_chat_logs))
+            .on_action(cx.listener(Self::on_action_go_back))
+            .on_action(cx.listener(Self::on_action_go_forward))
+            .on_action(cx.listener(Self::on_action_quick_switch))
+            .on_action(cx.listener(Self::on_action_toggle_focus_mode))
+            .on_action(cx.listener(Self::on_action_switch_profile))
+            .on_action(cx.listener(Self::on_action_new_profile))
+            .on_action(cx.listener(Self::on_action_sync_now))
             .relative()
             .size_full()
             .flex()
             .flex_col()
             .child(self.title_bar.clone())
-            .child(content)
+            .child(div().flex_1().min_h_0().w_full().child(content))
+            .children(started.then(|| self.status_bar.clone()))
             .children(sheet_layer)
             .children(dialog_layer)
             .children(notification_layer)
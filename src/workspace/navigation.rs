@@ -0,0 +1,349 @@
+//! Back/forward navigation across opened panels
+//!
+//! Every panel opened through `PanelAction` that can be opened again (a
+//! session, a workspace, an editor, a tool call) is recorded, so the user can
+//! step back and forth between them from the title bar or with `GoBack` /
+//! `GoForward`. The title bar also shows where the current panel belongs,
+//! e.g. `workspace › task` for a session.
+
+use std::collections::VecDeque;
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+};
+use rust_i18n::t;
+
+use crate::{
+    AppState, GoBack, GoForward,
+    app::actions::{PanelCommand, PanelKind},
+};
+
+use super::DockWorkspace;
+
+/// Entries kept behind the current one
+const MAX_HISTORY: usize = 50;
+
+/// Linear back/forward history, like a browser's
+pub struct NavigationHistory<T> {
+    back: VecDeque<T>,
+    current: Option<T>,
+    forward: Vec<T>,
+    limit: usize,
+}
+
+impl<T: Clone + PartialEq> NavigationHistory<T> {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            back: VecDeque::new(),
+            current: None,
+            forward: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Make `entry` the current one; visiting the current entry again is a no-op
+    pub fn visit(&mut self, entry: T) {
+        if self.current.as_ref() == Some(&entry) {
+            return;
+        }
+        if let Some(previous) = self.current.replace(entry) {
+            self.back.push_back(previous);
+            if self.back.len() > self.limit {
+                self.back.pop_front();
+            }
+        }
+        self.forward.clear();
+    }
+
+    /// Step back, returning the entry to show
+    pub fn go_back(&mut self) -> Option<T> {
+        let entry = self.back.pop_back()?;
+        if let Some(current) = self.current.replace(entry.clone()) {
+            self.forward.push(current);
+        }
+        Some(entry)
+    }
+
+    /// Step forward, returning the entry to show
+    pub fn go_forward(&mut self) -> Option<T> {
+        let entry = self.forward.pop()?;
+        if let Some(current) = self.current.replace(entry.clone()) {
+            self.back.push_back(current);
+        }
+        Some(entry)
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+
+    pub fn current(&self) -> Option<&T> {
+        self.current.as_ref()
+    }
+
+    /// All entries, for updating them in place
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.back
+            .iter_mut()
+            .chain(self.current.iter_mut())
+            .chain(self.forward.iter_mut())
+    }
+}
+
+/// A panel opening, with the breadcrumb shown while it is current
+#[derive(Clone)]
+pub struct NavEntry {
+    command: PanelCommand,
+    crumbs: Vec<SharedString>,
+}
+
+/// Entries are the same place when they open the same panel, wherever it is docked
+impl PartialEq for NavEntry {
+    fn eq(&self, other: &Self) -> bool {
+        panel_kind(&self.command) == panel_kind(&other.command)
+    }
+}
+
+fn panel_kind(command: &PanelCommand) -> &PanelKind {
+    match command {
+        PanelCommand::Add { panel, .. } => panel,
+        PanelCommand::Show(panel) => panel,
+    }
+}
+
+/// Whether opening `kind` again brings back the same place
+///
+/// New conversations and terminals start fresh every time, so they are not
+/// recorded.
+fn is_navigable(kind: &PanelKind) -> bool {
+    match kind {
+        PanelKind::Conversation { session_id } => {
+            session_id.as_ref().is_some_and(|id| !id.is_empty())
+        }
+        PanelKind::Terminal { .. } => false,
+        PanelKind::CodeEditor { .. }
+        | PanelKind::Welcome { .. }
+        | PanelKind::ToolCallDetail { .. } => true,
+    }
+}
+
+/// Breadcrumb known without looking anything up
+fn initial_crumbs(kind: &PanelKind) -> Vec<SharedString> {
+    let crumb = match kind {
+        PanelKind::Conversation { .. } => t!("navigation.session").to_string(),
+        PanelKind::Terminal { .. } => t!("navigation.terminal").to_string(),
+        PanelKind::CodeEditor { working_directory } => working_directory
+            .as_ref()
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| t!("navigation.code_editor").to_string()),
+        PanelKind::Welcome { .. } => t!("navigation.welcome").to_string(),
+        PanelKind::ToolCallDetail { tool_call, .. } => tool_call.title.clone(),
+    };
+    vec![crumb.into()]
+}
+
+/// Back/forward buttons and the breadcrumb of the current panel
+pub struct NavigationBar {
+    history: NavigationHistory<NavEntry>,
+}
+
+impl NavigationBar {
+    pub fn new() -> Self {
+        Self {
+            history: NavigationHistory::new(MAX_HISTORY),
+        }
+    }
+
+    fn set_crumbs(&mut self, kind: &PanelKind, crumbs: Vec<SharedString>) {
+        for entry in self.history.entries_mut() {
+            if panel_kind(&entry.command) == kind {
+                entry.crumbs = crumbs.clone();
+            }
+        }
+    }
+}
+
+impl Render for NavigationBar {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let muted_foreground = cx.theme().muted_foreground;
+        let crumbs = self
+            .history
+            .current()
+            .map(|entry| entry.crumbs.clone())
+            .unwrap_or_default();
+        let last = crumbs.len().saturating_sub(1);
+
+        h_flex()
+            .gap_1()
+            .child(
+                Button::new("nav-back")
+                    .icon(IconName::ArrowLeft)
+                    .small()
+                    .ghost()
+                    .disabled(!self.history.can_go_back())
+                    .tooltip(t!("navigation.back").to_string())
+                    .on_click(|_, window, cx| window.dispatch_action(Box::new(GoBack), cx)),
+            )
+            .child(
+                Button::new("nav-forward")
+                    .icon(IconName::ArrowRight)
+                    .small()
+                    .ghost()
+                    .disabled(!self.history.can_go_forward())
+                    .tooltip(t!("navigation.forward").to_string())
+                    .on_click(|_, window, cx| window.dispatch_action(Box::new(GoForward), cx)),
+            )
+            .children(crumbs.into_iter().enumerate().map(move |(ix, crumb)| {
+                h_flex()
+                    .gap_1()
+                    .max_w(px(200.))
+                    .text_xs()
+                    .text_color(muted_foreground)
+                    .when(ix > 0, |this| {
+                        this.child(Icon::new(IconName::ChevronRight).xsmall())
+                    })
+                    .child(
+                        div()
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .when(ix == last, |this| this.font_weight(FontWeight::MEDIUM))
+                            .child(crumb),
+                    )
+            }))
+    }
+}
+
+impl DockWorkspace {
+    /// Record `command` in the navigation history and look up its full breadcrumb
+    pub(super) fn record_navigation(&mut self, command: &PanelCommand, cx: &mut Context<Self>) {
+        let kind = panel_kind(command).clone();
+        if !is_navigable(&kind) {
+            return;
+        }
+        let entry = NavEntry {
+            command: command.clone(),
+            crumbs: initial_crumbs(&kind),
+        };
+        self.navigation.update(cx, |bar, cx| {
+            bar.history.visit(entry);
+            cx.notify();
+        });
+
+        let Some(workspace_service) = AppState::global(cx).workspace_service().cloned() else {
+            return;
+        };
+        let navigation = self.navigation.downgrade();
+        cx.spawn(async move |_this, cx| {
+            let crumbs = match &kind {
+                PanelKind::Conversation {
+                    session_id: Some(session_id),
+                } => {
+                    let workspace = workspace_service.get_workspace_for_session(session_id).await;
+                    let task = workspace_service.get_task_by_session(session_id).await;
+                    workspace
+                        .map(|workspace| workspace.name)
+                        .into_iter()
+                        .chain(task.map(|task| task.name))
+                        .map(SharedString::from)
+                        .collect::<Vec<_>>()
+                }
+                PanelKind::Welcome {
+                    workspace_id: Some(workspace_id),
+                } => workspace_service
+                    .get_workspace(workspace_id)
+                    .await
+                    .map(|workspace| vec![workspace.name.into()])
+                    .unwrap_or_default(),
+                _ => return,
+            };
+            if crumbs.is_empty() {
+                return;
+            }
+            _ = navigation.update(cx, |bar, cx| {
+                bar.set_crumbs(&kind, crumbs);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Go back to the previously opened panel
+    pub(super) fn on_action_go_back(
+        &mut self,
+        _: &GoBack,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let entry = self.navigation.update(cx, |bar, cx| {
+            cx.notify();
+            bar.history.go_back()
+        });
+        if let Some(entry) = entry {
+            self.open_panel(&entry.command, window, cx);
+        }
+    }
+
+    /// Go forward again after going back
+    pub(super) fn on_action_go_forward(
+        &mut self,
+        _: &GoForward,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let entry = self.navigation.update(cx, |bar, cx| {
+            cx.notify();
+            bar.history.go_forward()
+        });
+        if let Some(entry) = entry {
+            self.open_panel(&entry.command, window, cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_back_and_forward() {
+        let mut history = NavigationHistory::new(10);
+        assert!(!history.can_go_back());
+        history.visit(1);
+        history.visit(2);
+        history.visit(2);
+        history.visit(3);
+
+        assert_eq!(history.go_back(), Some(2));
+        assert_eq!(history.go_back(), Some(1));
+        assert_eq!(history.go_back(), None);
+        assert_eq!(history.current(), Some(&1));
+        assert_eq!(history.go_forward(), Some(2));
+        assert!(history.can_go_forward());
+
+        // Visiting somewhere new drops the forward entries
+        history.visit(4);
+        assert!(!history.can_go_forward());
+        assert_eq!(history.go_back(), Some(2));
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut history = NavigationHistory::new(2);
+        for entry in 1..=5 {
+            history.visit(entry);
+        }
+        assert_eq!(history.go_back(), Some(4));
+        assert_eq!(history.go_back(), Some(3));
+        assert_eq!(history.go_back(), None);
+    }
+}
@@ -0,0 +1,780 @@
+//! New task dialog: pick the agent, workspace and working directory, mode,
+//! model, attachments and a prompt template, then create the session and the
+//! task and open the conversation in one step
+//!
+//! Agents only tell their modes and models when a session is created, so the
+//! choices come from the agent's latest session; without one, the new session
+//! starts in the agent's defaults.
+
+use std::path::{Path, PathBuf};
+
+use agent_client_protocol as acp;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme, IconName, IndexPath, Sizable, WindowExt,
+    button::{Button, ButtonVariants as _},
+    dialog::DialogButtonProps,
+    dock::DockArea,
+    h_flex,
+    input::{Input, InputState},
+    label::Label,
+    notification::Notification,
+    select::{Select, SelectEvent, SelectItem, SelectState},
+    v_flex,
+};
+use rust_i18n::t;
+
+use crate::{
+    AppSettings, AppState, CreateTask, NewTask, SendMessageToSession,
+    components::{AgentItem, ModeSelectItem, ModelSelectItem},
+    schemas::workspace_schema::Workspace,
+    utils::context_item::ContextItem,
+};
+
+use super::DockWorkspace;
+
+/// Task mode recorded when the agent's default mode is kept
+const DEFAULT_MODE: &str = "default";
+
+/// Working directory of a new task's session: the workspace root when
+/// `input` is empty, a folder in it for relative paths
+pub fn task_cwd(root: &Path, input: &str) -> PathBuf {
+    match input.trim() {
+        "" | "." => root.to_path_buf(),
+        input => root.join(input),
+    }
+}
+
+/// A task to create along with its session, see `DockWorkspace::start_task`
+pub(super) struct TaskStart {
+    pub agent_name: String,
+    /// Workspace of the task; the active workspace when `None`
+    pub workspace_id: Option<String>,
+    /// Working directory of a new session; the workspace root when `None`
+    pub cwd: Option<PathBuf>,
+    /// Session to use instead of creating one
+    pub session_id: Option<String>,
+    pub task_input: String,
+    /// Mode recorded on the task
+    pub mode: String,
+    /// Mode and model to switch a new session to
+    pub mode_id: Option<String>,
+    pub model_id: Option<String>,
+    pub label: Option<String>,
+}
+
+/// A created task's workspace and session
+pub(super) struct StartedTask {
+    pub workspace: Workspace,
+    pub session_id: String,
+}
+
+/// A workspace in the workspace select
+#[derive(Clone, Debug, PartialEq)]
+struct WorkspaceItem {
+    id: String,
+    name: String,
+    path: PathBuf,
+}
+
+impl From<Workspace> for WorkspaceItem {
+    fn from(workspace: Workspace) -> Self {
+        Self {
+            id: workspace.id,
+            name: workspace.name,
+            path: workspace.path,
+        }
+    }
+}
+
+impl SelectItem for WorkspaceItem {
+    type Value = String;
+
+    fn title(&self) -> SharedString {
+        self.name.clone().into()
+    }
+
+    fn value(&self) -> &Self::Value {
+        &self.id
+    }
+}
+
+struct NewTaskForm {
+    agent_select: Entity<SelectState<Vec<AgentItem>>>,
+    workspace_select: Entity<SelectState<Vec<WorkspaceItem>>>,
+    cwd_input: Entity<InputState>,
+    mode_select: Entity<SelectState<Vec<ModeSelectItem>>>,
+    model_select: Entity<SelectState<Vec<ModelSelectItem>>>,
+    template_select: Entity<SelectState<Vec<String>>>,
+    prompt_input: Entity<InputState>,
+    label_input: Entity<InputState>,
+    workspaces: Vec<WorkspaceItem>,
+    attachments: Vec<ContextItem>,
+    has_modes: bool,
+    has_models: bool,
+    has_templates: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl NewTaskForm {
+    fn new(
+        agents: Vec<String>,
+        workspaces: Vec<WorkspaceItem>,
+        workspace_id: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let agent_items: Vec<AgentItem> = agents.into_iter().map(AgentItem::new).collect();
+        let first_agent = (!agent_items.is_empty()).then(IndexPath::default);
+        let agent_select = cx.new(|cx| SelectState::new(agent_items, first_agent, window, cx));
+
+        let workspace_ix = workspace_id
+            .and_then(|id| workspaces.iter().position(|workspace| workspace.id == id))
+            .unwrap_or(0);
+        let workspace_select = cx.new(|cx| {
+            SelectState::new(
+                workspaces.clone(),
+                Some(IndexPath::new(workspace_ix)),
+                window,
+                cx,
+            )
+        });
+        let cwd_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(t!("new_task.cwd.placeholder").to_string())
+        });
+
+        let mode_select =
+            cx.new(|cx| SelectState::new(Vec::<ModeSelectItem>::new(), None, window, cx));
+        let model_select =
+            cx.new(|cx| SelectState::new(Vec::<ModelSelectItem>::new(), None, window, cx));
+
+        let templates: Vec<String> = AppState::global(cx)
+            .snippets()
+            .into_iter()
+            .map(|snippet| snippet.name)
+            .collect();
+        let has_templates = !templates.is_empty();
+        let template_select = cx.new(|cx| SelectState::new(templates, None, window, cx));
+
+        let prompt_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .auto_grow(4, 12)
+                .soft_wrap(true)
+                .placeholder(t!("new_task.prompt.placeholder").to_string())
+        });
+        let label_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(t!("new_task.label.placeholder").to_string())
+        });
+
+        let subscriptions = vec![
+            cx.subscribe_in(
+                &agent_select,
+                window,
+                |this, _, _: &SelectEvent<Vec<AgentItem>>, window, cx| {
+                    this.refresh_choices(window, cx);
+                },
+            ),
+            cx.subscribe_in(
+                &template_select,
+                window,
+                |this, _, _: &SelectEvent<Vec<String>>, window, cx| {
+                    this.apply_template(window, cx);
+                },
+            ),
+        ];
+
+        let mut form = Self {
+            agent_select,
+            workspace_select,
+            cwd_input,
+            mode_select,
+            model_select,
+            template_select,
+            prompt_input,
+            label_input,
+            workspaces,
+            attachments: Vec::new(),
+            has_modes: false,
+            has_models: false,
+            has_templates,
+            _subscriptions: subscriptions,
+        };
+        form.refresh_choices(window, cx);
+        form
+    }
+
+    fn selected_agent(&self, cx: &App) -> Option<String> {
+        self.agent_select.read(cx).selected_value().cloned()
+    }
+
+    fn selected_workspace(&self, cx: &App) -> Option<&WorkspaceItem> {
+        let id = self.workspace_select.read(cx).selected_value()?;
+        self.workspaces.iter().find(|workspace| &workspace.id == id)
+    }
+
+    /// Offer the modes and models of the selected agent, starting from its
+    /// defaults
+    fn refresh_choices(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let choices = match (
+            self.selected_agent(cx),
+            AppState::global(cx).agent_service(),
+        ) {
+            (Some(agent), Some(service)) => service.session_choices(&agent),
+            _ => Default::default(),
+        };
+        self.has_modes = !choices.modes.is_empty();
+        self.has_models = !choices.models.is_empty();
+        self.mode_select.update(cx, |state, cx| {
+            let items = choices
+                .modes
+                .into_iter()
+                .map(|(id, name)| ModeSelectItem::new(id, name))
+                .collect();
+            state.set_items(items, window, cx);
+            state.set_selected_index(None, window, cx);
+        });
+        self.model_select.update(cx, |state, cx| {
+            let items = choices
+                .models
+                .into_iter()
+                .map(|(id, name)| ModelSelectItem::new(id, name))
+                .collect();
+            state.set_items(items, window, cx);
+            state.set_selected_index(None, window, cx);
+        });
+        cx.notify();
+    }
+
+    /// Fill the prompt with the picked template
+    fn apply_template(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(name) = self.template_select.read(cx).selected_value().cloned() else {
+            return;
+        };
+        let Some(snippet) = AppState::global(cx)
+            .snippets()
+            .into_iter()
+            .find(|snippet| snippet.name == name)
+        else {
+            return;
+        };
+        self.prompt_input.update(cx, |state, cx| {
+            state.set_value(snippet.text, window, cx);
+            state.focus(window, cx);
+        });
+    }
+
+    fn pick_attachments(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let mut dialog =
+            rfd::AsyncFileDialog::new().set_title(t!("new_task.attachments.pick").to_string());
+        if let Some(workspace) = self.selected_workspace(cx) {
+            dialog = dialog.set_directory(&workspace.path);
+        }
+
+        cx.spawn_in(window, async move |this, window| {
+            let Some(files) = dialog.pick_files().await else {
+                return;
+            };
+            let paths: Vec<_> = files.iter().map(|file| file.path().to_path_buf()).collect();
+            let items: Vec<ContextItem> =
+                smol::unblock(move || paths.into_iter().map(ContextItem::file).collect()).await;
+            _ = this.update_in(window, |this, _window, cx| {
+                for item in items {
+                    if !this.attachments.contains(&item) {
+                        this.attachments.push(item);
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// The task to create, or what is missing for it
+    fn to_action(&self, cx: &App) -> Result<CreateTask, String> {
+        let agent_name = self
+            .selected_agent(cx)
+            .ok_or_else(|| t!("new_task.no_agent").to_string())?;
+        let workspace = self
+            .selected_workspace(cx)
+            .ok_or_else(|| t!("new_task.no_workspace").to_string())?;
+        let task_input = self.prompt_input.read(cx).text().to_string();
+        if task_input.trim().is_empty() {
+            return Err(t!("new_task.empty_prompt").to_string());
+        }
+        let cwd = task_cwd(&workspace.path, &self.cwd_input.read(cx).text().to_string());
+        if !cwd.is_dir() {
+            return Err(t!("new_task.cwd.missing", path = cwd.display().to_string()).to_string());
+        }
+        let label = self.label_input.read(cx).text().to_string();
+        let label = label.trim();
+
+        Ok(CreateTask {
+            task_input,
+            agent_name,
+            workspace_id: workspace.id.clone(),
+            cwd: Some(cwd),
+            mode_id: self.mode_select.read(cx).selected_value().cloned(),
+            model_id: self.model_select.read(cx).selected_value().cloned(),
+            attachments: self.attachments.clone(),
+            label: (!label.is_empty()).then(|| label.to_string()),
+        })
+    }
+
+    fn render_attachments(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        h_flex()
+            .flex_wrap()
+            .gap_1()
+            .children(self.attachments.iter().enumerate().map(|(ix, item)| {
+                h_flex()
+                    .gap_1()
+                    .pl_2()
+                    .rounded_md()
+                    .bg(theme.muted)
+                    .text_xs()
+                    .child(item.label())
+                    .child(
+                        Button::new(("new-task-attachment-remove", ix))
+                            .icon(IconName::Close)
+                            .ghost()
+                            .xsmall()
+                            .tooltip(t!("new_task.attachments.remove").to_string())
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                if ix < this.attachments.len() {
+                                    this.attachments.remove(ix);
+                                }
+                                cx.notify();
+                            })),
+                    )
+            }))
+            .child(
+                Button::new("new-task-attach")
+                    .icon(IconName::Plus)
+                    .label(t!("new_task.attachments.add").to_string())
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _, window, cx| this.pick_attachments(window, cx))),
+            )
+    }
+}
+
+/// A labelled field of the form
+fn field(label: String, input: impl IntoElement) -> Div {
+    v_flex()
+        .flex_1()
+        .gap_1()
+        .child(Label::new(label).text_sm())
+        .child(input)
+}
+
+/// What is used for a mode or model the agent has not told about
+fn agent_default(cx: &App) -> Div {
+    div()
+        .py_1()
+        .text_sm()
+        .text_color(cx.theme().muted_foreground)
+        .child(t!("new_task.agent_default").to_string())
+}
+
+impl Render for NewTaskForm {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mode = if self.has_modes {
+            Select::new(&self.mode_select)
+                .placeholder(t!("new_task.agent_default").to_string())
+                .into_any_element()
+        } else {
+            agent_default(cx).into_any_element()
+        };
+        let model = if self.has_models {
+            Select::new(&self.model_select)
+                .placeholder(t!("new_task.agent_default").to_string())
+                .into_any_element()
+        } else {
+            agent_default(cx).into_any_element()
+        };
+
+        v_flex()
+            .w_full()
+            .gap_3()
+            .child(
+                h_flex()
+                    .gap_3()
+                    .child(field(
+                        t!("new_task.agent").to_string(),
+                        Select::new(&self.agent_select),
+                    ))
+                    .child(field(
+                        t!("new_task.workspace").to_string(),
+                        Select::new(&self.workspace_select),
+                    )),
+            )
+            .child(field(
+                t!("new_task.cwd").to_string(),
+                Input::new(&self.cwd_input),
+            ))
+            .child(
+                h_flex()
+                    .gap_3()
+                    .child(field(t!("new_task.mode").to_string(), mode))
+                    .child(field(t!("new_task.model").to_string(), model)),
+            )
+            .when(self.has_templates, |this| {
+                this.child(field(
+                    t!("new_task.template").to_string(),
+                    Select::new(&self.template_select)
+                        .placeholder(t!("new_task.template.placeholder").to_string()),
+                ))
+            })
+            .child(field(
+                t!("new_task.prompt").to_string(),
+                Input::new(&self.prompt_input),
+            ))
+            .child(field(
+                t!("new_task.attachments").to_string(),
+                self.render_attachments(cx),
+            ))
+            .child(field(
+                t!("new_task.label").to_string(),
+                Input::new(&self.label_input),
+            ))
+    }
+}
+
+impl DockWorkspace {
+    /// Open the new task dialog
+    pub(super) fn on_action_new_task(
+        &mut self,
+        action: &NewTask,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let state = AppState::global(cx);
+        let (Some(agent_service), Some(workspace_service)) = (
+            state.agent_service().cloned(),
+            state.workspace_service().cloned(),
+        ) else {
+            log::error!("AgentService or WorkspaceService not initialized");
+            return;
+        };
+        let workspace_id = action.workspace_id.clone();
+
+        cx.spawn_in(window, async move |_this, window| {
+            let agents = agent_service.list_agents().await;
+            let active_id = workspace_service
+                .get_active_workspace()
+                .await
+                .map(|workspace| workspace.id);
+            let workspaces: Vec<WorkspaceItem> = workspace_service
+                .list_workspaces()
+                .await
+                .into_iter()
+                .map(WorkspaceItem::from)
+                .collect();
+
+            _ = window.update(|window, cx| {
+                if workspaces.is_empty() {
+                    struct NoWorkspaceError;
+                    let note = Notification::error(t!("new_task.no_workspace").to_string());
+                    window.show_notification(note.id::<NoWorkspaceError>(), cx);
+                    return;
+                }
+
+                let form = cx.new(|cx| {
+                    NewTaskForm::new(agents, workspaces, workspace_id.or(active_id), window, cx)
+                });
+                form.read(cx)
+                    .prompt_input
+                    .focus_handle(cx)
+                    .focus(window, cx);
+
+                window.open_dialog(cx, move |dialog, _window, _cx| {
+                    dialog
+                        .title(t!("new_task.title").to_string())
+                        .confirm()
+                        .button_props(
+                            DialogButtonProps::default()
+                                .ok_text(t!("new_task.ok").to_string())
+                                .cancel_text(t!("new_task.cancel").to_string()),
+                        )
+                        .on_ok({
+                            let form = form.clone();
+                            move |_, window, cx| match form.read(cx).to_action(cx) {
+                                Ok(action) => {
+                                    window.dispatch_action(Box::new(action), cx);
+                                    true
+                                }
+                                Err(message) => {
+                                    struct NewTaskInvalid;
+                                    let note = Notification::warning(message);
+                                    window.show_notification(note.id::<NewTaskInvalid>(), cx);
+                                    false
+                                }
+                            }
+                        })
+                        .child(form.clone())
+                });
+            });
+        })
+        .detach();
+    }
+
+    /// Create the session with the chosen mode and model, then the task,
+    /// open its conversation and send the first prompt
+    pub(super) fn on_action_create_task(
+        &mut self,
+        action: &CreateTask,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let dock_area = self.dock_area.clone();
+        let action = action.clone();
+
+        log::info!(
+            "Creating task: agent={}, workspace={}, cwd={:?}, mode={:?}, model={:?}, files={}",
+            action.agent_name,
+            action.workspace_id,
+            action.cwd,
+            action.mode_id,
+            action.model_id,
+            action.attachments.len()
+        );
+
+        cx.spawn_in(window, async move |_this, window| {
+            let start = TaskStart {
+                agent_name: action.agent_name.clone(),
+                workspace_id: Some(action.workspace_id.clone()),
+                cwd: action.cwd.clone(),
+                session_id: None,
+                task_input: action.task_input.clone(),
+                mode: action
+                    .mode_id
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_MODE.to_string()),
+                mode_id: action.mode_id.clone(),
+                model_id: action.model_id.clone(),
+                label: action.label.clone(),
+            };
+            let Some(started) = Self::start_task(&dock_area, start, window).await else {
+                return;
+            };
+
+            // Send the prompt with the context new tasks get and the attachments
+            _ = window.update(move |window, cx| {
+                let settings = AppSettings::global(cx);
+                let action = SendMessageToSession {
+                    session_id: started.session_id,
+                    message: action.task_input,
+                    images: Vec::new(),
+                    resources: Vec::new(),
+                    context: action.attachments,
+                    repo_map: settings.include_repo_map,
+                    search_query: None,
+                    memory: settings.include_workspace_memory,
+                    instructions: true,
+                };
+                window.dispatch_action(Box::new(action), cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Create a task and its session and open its conversation, the steps
+    /// the new task dialog and the welcome panel share; the caller sends the
+    /// first prompt once the conversation is subscribed
+    ///
+    /// Failures are shown as notifications and return `None`.
+    pub(super) async fn start_task(
+        dock_area: &Entity<DockArea>,
+        start: TaskStart,
+        window: &mut AsyncWindowContext,
+    ) -> Option<StartedTask> {
+        let show_error = |window: &mut AsyncWindowContext, message: String| {
+            _ = window.update(|window, cx| {
+                struct CreateTaskError;
+                let note = Notification::error(message).id::<CreateTaskError>();
+                window.show_notification(note, cx);
+            });
+        };
+
+        let services = window.update(|_, cx| {
+            let state = AppState::global(cx);
+            (
+                state.agent_service().cloned(),
+                state.workspace_service().cloned(),
+                state.agent_manager().cloned(),
+                state.agent_config_service().cloned(),
+                AppSettings::global(cx).task_name_template.to_string(),
+            )
+        });
+        let Ok((
+            Some(agent_service),
+            Some(workspace_service),
+            agent_manager,
+            agent_config_service,
+            name_template,
+        )) = services
+        else {
+            log::error!("AgentService or WorkspaceService not initialized");
+            return None;
+        };
+
+        // Step 1: Find the workspace, the active one unless one was chosen
+        let workspace = match &start.workspace_id {
+            Some(id) => workspace_service.get_workspace(id).await,
+            None => workspace_service.get_active_workspace().await,
+        };
+        let Some(workspace) = workspace else {
+            let message = match &start.workspace_id {
+                Some(id) => t!("new_task.workspace_missing", id = id),
+                None => t!("new_task.no_workspace"),
+            };
+            log::error!("{}", message);
+            show_error(window, message.to_string());
+            return None;
+        };
+        let agent_name = start.agent_name;
+
+        // Step 2: Reuse the given session or create one in the working
+        // directory, switched to the chosen mode and model
+        let session_id = match start.session_id {
+            Some(session_id) => {
+                log::info!("Reusing session {} for agent {}", session_id, agent_name);
+                session_id
+            }
+            None => {
+                let cwd = start.cwd.unwrap_or_else(|| workspace.path.clone());
+                let mcp_servers = match agent_config_service {
+                    Some(service) => service
+                        .list_mcp_servers()
+                        .await
+                        .into_iter()
+                        .filter(|(_, config)| config.enabled)
+                        .map(|(name, config)| config.to_acp_mcp_server(name))
+                        .collect(),
+                    None => Vec::new(),
+                };
+                let session_id = match agent_service
+                    .create_session_with_mcp_and_cwd(&agent_name, mcp_servers, cwd.clone())
+                    .await
+                {
+                    Ok(session_id) => session_id,
+                    Err(e) => {
+                        if e.is_agent_failure() {
+                            log::error!(
+                                "Agent {} crashed creating a session in {}: {}",
+                                agent_name,
+                                cwd.display(),
+                                e
+                            );
+                        } else {
+                            log::error!("Failed to create session for agent {}: {}", agent_name, e);
+                        }
+                        let message = t!(
+                            "new_task.session_failed",
+                            agent = agent_name,
+                            error = e.to_string()
+                        );
+                        show_error(window, message.to_string());
+                        return None;
+                    }
+                };
+
+                let handle = match &agent_manager {
+                    Some(manager) => manager.get(&agent_name).await,
+                    None => None,
+                };
+                if let (Some(mode_id), Some(handle)) = (start.mode_id, handle.as_ref()) {
+                    let request = acp::SetSessionModeRequest::new(
+                        acp::SessionId::from(session_id.clone()),
+                        mode_id.clone(),
+                    );
+                    match handle.set_session_mode(request).await {
+                        Ok(_) => agent_service.update_session_mode(
+                            &agent_name,
+                            &session_id,
+                            acp::SessionModeId::from(mode_id),
+                        ),
+                        Err(e) => {
+                            log::warn!("Failed to set mode of session {}: {}", session_id, e)
+                        }
+                    }
+                }
+                if let (Some(model_id), Some(handle)) = (start.model_id, handle.as_ref()) {
+                    let request = acp::SetSessionModelRequest::new(
+                        acp::SessionId::from(session_id.clone()),
+                        model_id,
+                    );
+                    if let Err(e) = handle.set_session_model(request).await {
+                        log::warn!("Failed to set model of session {}: {}", session_id, e);
+                    }
+                }
+                session_id
+            }
+        };
+
+        // Step 3: Create the task and associate the session with it
+        let task = match workspace_service
+            .create_task(
+                &workspace.id,
+                &start.task_input,
+                agent_name.clone(),
+                start.mode,
+                start.label,
+                &name_template,
+            )
+            .await
+        {
+            Ok(task) => task,
+            Err(e) => {
+                log::error!("Failed to create workspace task: {}", e);
+                show_error(
+                    window,
+                    t!("new_task.failed", error = e.to_string()).to_string(),
+                );
+                return None;
+            }
+        };
+        if let Err(e) = workspace_service
+            .set_task_session(&task.id, session_id.clone())
+            .await
+        {
+            log::error!("Failed to associate session with task: {}", e);
+        }
+
+        // Step 4: Open the conversation, which subscribes to the session
+        let dock_area = dock_area.clone();
+        let conversation_session = session_id.clone();
+        _ = window.update(move |window, cx| {
+            Self::show_task_conversation(&dock_area, conversation_session, window, cx);
+        });
+        log::info!(
+            "[DockWorkspace] Task created ({}) in workspace {}",
+            task.id,
+            workspace.id
+        );
+
+        Some(StartedTask {
+            workspace,
+            session_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_cwd() {
+        let root = Path::new("/work/app");
+        assert_eq!(task_cwd(root, ""), PathBuf::from("/work/app"));
+        assert_eq!(task_cwd(root, " . "), PathBuf::from("/work/app"));
+        assert_eq!(
+            task_cwd(root, "crates/core"),
+            PathBuf::from("/work/app/crates/core")
+        );
+        assert_eq!(task_cwd(root, "/tmp/other"), PathBuf::from("/tmp/other"));
+    }
+}
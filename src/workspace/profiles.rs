@@ -0,0 +1,135 @@
+//! Switching between and creating config profiles
+//!
+//! Services read their paths when the app starts, so changing the profile
+//! saves it as the active one and restarts AgentX.
+
+use gpui::*;
+use gpui_component::{
+    WindowExt,
+    dialog::DialogButtonProps,
+    input::{Input, InputState},
+    notification::Notification,
+    v_flex,
+};
+use rust_i18n::t;
+
+use crate::app::focus_mode::NotificationExt as _;
+use crate::{AppState, NewProfile, SwitchProfile};
+
+use super::DockWorkspace;
+
+/// Save `name` as the active profile and restart into it
+fn activate_and_restart(name: &str, window: &mut Window, cx: &mut App) -> bool {
+    let Some(profile_service) = AppState::global(cx).profile_service().cloned() else {
+        log::error!("ProfileService not initialized");
+        return false;
+    };
+    match profile_service.set_active(name) {
+        Ok(()) => {
+            log::info!("Restarting into profile '{}'", name);
+            cx.restart();
+            true
+        }
+        Err(e) => {
+            struct SwitchProfileError;
+            let note = Notification::error(
+                t!("profiles.switch_failed", error = format!("{:#}", e)).to_string(),
+            );
+            window.show_notification(note.id::<SwitchProfileError>(), cx);
+            false
+        }
+    }
+}
+
+impl DockWorkspace {
+    /// Confirm and restart into another profile
+    pub(super) fn on_action_switch_profile(
+        &mut self,
+        action: &SwitchProfile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let name = action.0.to_string();
+        let current = AppState::global(cx)
+            .profile_service()
+            .map(|service| service.current());
+        if current.as_deref() == Some(name.as_str()) {
+            return;
+        }
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let name = name.clone();
+            dialog
+                .title(t!("profiles.switch.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("profiles.switch.ok").to_string())
+                        .cancel_text(t!("profiles.cancel").to_string()),
+                )
+                .on_ok({
+                    let name = name.clone();
+                    move |_, window, cx| activate_and_restart(&name, window, cx)
+                })
+                .child(
+                    div()
+                        .text_sm()
+                        .child(t!("profiles.switch.message", name = name).to_string()),
+                )
+        });
+    }
+
+    /// Ask for a name, create the profile and restart into it
+    pub(super) fn on_action_new_profile(
+        &mut self,
+        _: &NewProfile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let name_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(t!("profiles.new.placeholder").to_string())
+        });
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            dialog
+                .title(t!("profiles.new.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("profiles.new.ok").to_string())
+                        .cancel_text(t!("profiles.cancel").to_string()),
+                )
+                .on_ok({
+                    let name_input = name_input.clone();
+                    move |_, window, cx| {
+                        let name = name_input.read(cx).text().to_string().trim().to_string();
+                        let Some(profile_service) = AppState::global(cx).profile_service().cloned()
+                        else {
+                            log::error!("ProfileService not initialized");
+                            return false;
+                        };
+                        if let Err(e) = profile_service.create(&name, None) {
+                            struct NewProfileError;
+                            let note = Notification::error(
+                                t!("profiles.new.failed", error = format!("{:#}", e)).to_string(),
+                            );
+                            window.show_notification(note.id::<NewProfileError>(), cx);
+                            return false;
+                        }
+                        activate_and_restart(&name, window, cx)
+                    }
+                })
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .p_4()
+                        .child(
+                            div()
+                                .text_sm()
+                                .child(t!("profiles.new.message").to_string()),
+                        )
+                        .child(Input::new(&name_input)),
+                )
+        });
+    }
+}
@@ -0,0 +1,318 @@
+//! Quick switcher: jump to any open or recent session by typing part of its name
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::{
+    ActiveTheme, Icon, IconName, IndexPath, WindowExt, h_flex,
+    list::{List, ListDelegate, ListItem, ListState},
+    v_flex,
+};
+use rust_i18n::t;
+
+use crate::{AppState, PanelAction, QuickSwitch, utils::slash_command::fuzzy_score};
+
+use super::DockWorkspace;
+
+/// Sessions listed before typing anything
+const MAX_RECENT: usize = 200;
+
+/// A session that can be switched to
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwitcherEntry {
+    pub session_id: String,
+    /// Task name, or the session id when no task owns the session
+    pub title: String,
+    /// Agent and workspace
+    pub detail: String,
+    /// The session is running in an agent right now
+    pub is_open: bool,
+    pub last_active: DateTime<Utc>,
+}
+
+/// Entries matching `query`, best first
+///
+/// Without a query, open sessions come first, then the most recently active.
+/// Matches in the title count more than matches in the detail line.
+pub fn rank_entries(query: &str, entries: &[SwitcherEntry]) -> Vec<SwitcherEntry> {
+    let query = query.trim();
+    let mut ranked: Vec<(i32, &SwitcherEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            if query.is_empty() {
+                return Some((0, entry));
+            }
+            let title = fuzzy_score(query, &entry.title).map(|score| score * 2);
+            let detail = fuzzy_score(query, &entry.detail);
+            title.max(detail).map(|score| (score, entry))
+        })
+        .collect();
+    ranked.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then(b.is_open.cmp(&a.is_open))
+            .then(b.last_active.cmp(&a.last_active))
+    });
+    ranked
+        .into_iter()
+        .take(MAX_RECENT)
+        .map(|(_, entry)| entry.clone())
+        .collect()
+}
+
+struct QuickSwitcherDelegate {
+    workspace: WeakEntity<DockWorkspace>,
+    entries: Vec<SwitcherEntry>,
+    matches: Vec<SwitcherEntry>,
+    selected_index: Option<usize>,
+}
+
+impl QuickSwitcherDelegate {
+    fn new(workspace: WeakEntity<DockWorkspace>, entries: Vec<SwitcherEntry>) -> Self {
+        let matches = rank_entries("", &entries);
+        Self {
+            workspace,
+            entries,
+            matches,
+            selected_index: None,
+        }
+    }
+}
+
+impl ListDelegate for QuickSwitcherDelegate {
+    type Item = ListItem;
+
+    fn perform_search(
+        &mut self,
+        query: &str,
+        window: &mut Window,
+        cx: &mut Context<ListState<Self>>,
+    ) -> Task<()> {
+        self.matches = rank_entries(query, &self.entries);
+        let selected = (!self.matches.is_empty()).then(IndexPath::default);
+        cx.defer_in(window, move |state, window, cx| {
+            state.set_selected_index(selected, window, cx);
+        });
+        Task::ready(())
+    }
+
+    fn items_count(&self, _: usize, _: &App) -> usize {
+        self.matches.len()
+    }
+
+    fn render_item(
+        &mut self,
+        ix: IndexPath,
+        _window: &mut Window,
+        cx: &mut Context<ListState<Self>>,
+    ) -> Option<Self::Item> {
+        let entry = self.matches.get(ix.row)?;
+        let theme = cx.theme();
+
+        Some(
+            ListItem::new(ix).w_full().py_1().px_2().child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(
+                        Icon::new(IconName::Bot)
+                            .size(px(16.))
+                            .text_color(if entry.is_open {
+                                theme.success
+                            } else {
+                                theme.muted_foreground
+                            }),
+                    )
+                    .child(
+                        v_flex()
+                            .flex_1()
+                            .min_w_0()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .overflow_hidden()
+                                    .text_ellipsis()
+                                    .whitespace_nowrap()
+                                    .child(entry.title.clone()),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.muted_foreground)
+                                    .child(entry.detail.clone()),
+                            ),
+                    )
+                    .when(entry.is_open, |this| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(theme.success)
+                                .child(t!("quick_switcher.open").to_string()),
+                        )
+                    }),
+            ),
+        )
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: Option<IndexPath>,
+        _: &mut Window,
+        _: &mut Context<ListState<Self>>,
+    ) {
+        self.selected_index = ix.map(|ix| ix.row);
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<ListState<Self>>) {
+        let Some(entry) = self.selected_index.and_then(|ix| self.matches.get(ix)) else {
+            return;
+        };
+        let action = PanelAction::show_conversation(Some(entry.session_id.clone()));
+        window.close_dialog(cx);
+        _ = self.workspace.update(cx, |workspace, cx| {
+            workspace.on_action_panel_action(&action, window, cx);
+        });
+    }
+
+    fn cancel(&mut self, window: &mut Window, cx: &mut Context<ListState<Self>>) {
+        window.close_dialog(cx);
+    }
+}
+
+impl DockWorkspace {
+    /// Open the quick switcher over open and recent sessions
+    pub(super) fn on_action_quick_switch(
+        &mut self,
+        _: &QuickSwitch,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let state = AppState::global(cx);
+        let Some(workspace_service) = state.workspace_service().cloned() else {
+            log::error!("WorkspaceService not initialized");
+            return;
+        };
+        let open_sessions = state
+            .agent_service()
+            .map(|service| service.list_sessions())
+            .unwrap_or_default();
+
+        cx.spawn_in(window, async move |this, window| {
+            let workspaces: HashMap<String, String> = workspace_service
+                .list_workspaces()
+                .await
+                .into_iter()
+                .map(|workspace| (workspace.id, workspace.name))
+                .collect();
+
+            let mut entries: Vec<SwitcherEntry> = Vec::new();
+            for task in workspace_service.get_all_tasks().await {
+                let Some(session_id) = task.session_id.clone() else {
+                    continue;
+                };
+                let open = open_sessions.iter().find(|s| s.session_id == session_id);
+                let workspace = workspaces
+                    .get(&task.workspace_id)
+                    .cloned()
+                    .unwrap_or_default();
+                entries.push(SwitcherEntry {
+                    session_id,
+                    title: task.name,
+                    detail: format!("{} · {}", task.agent_name, workspace),
+                    is_open: open.is_some(),
+                    last_active: open.map_or(task.last_updated, |s| s.last_active),
+                });
+            }
+            for session in &open_sessions {
+                if entries.iter().any(|e| e.session_id == session.session_id) {
+                    continue;
+                }
+                entries.push(SwitcherEntry {
+                    session_id: session.session_id.clone(),
+                    title: session.session_id.clone(),
+                    detail: session.agent_name.clone(),
+                    is_open: true,
+                    last_active: session.last_active,
+                });
+            }
+
+            _ = window.update(|window, cx| {
+                let list = cx.new(|cx| {
+                    let delegate = QuickSwitcherDelegate::new(this, entries);
+                    let mut list = ListState::new(delegate, window, cx).searchable(true);
+                    list.set_selected_index(Some(IndexPath::default()), window, cx);
+                    list
+                });
+                list.focus_handle(cx).focus(window, cx);
+
+                window.open_dialog(cx, move |dialog, _window, cx| {
+                    let muted_foreground = cx.theme().muted_foreground;
+                    let is_empty = list.read(cx).delegate().entries.is_empty();
+                    dialog
+                        .title(t!("quick_switcher.title").to_string())
+                        .child(
+                            v_flex()
+                                .w_full()
+                                .h(px(360.))
+                                .child(List::new(&list).size_full())
+                                .when(is_empty, |this| {
+                                    this.child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(muted_foreground)
+                                            .child(t!("quick_switcher.empty").to_string()),
+                                    )
+                                }),
+                        )
+                });
+            });
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, detail: &str, is_open: bool, day: u32) -> SwitcherEntry {
+        SwitcherEntry {
+            session_id: title.to_string(),
+            title: title.to_string(),
+            detail: detail.to_string(),
+            is_open,
+            last_active: chrono::NaiveDate::from_ymd_opt(2026, 3, day)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap()
+                .and_utc(),
+        }
+    }
+
+    fn titles(entries: Vec<SwitcherEntry>) -> Vec<String> {
+        entries.into_iter().map(|entry| entry.title).collect()
+    }
+
+    #[test]
+    fn test_rank_without_query() {
+        let entries = vec![
+            entry("old", "claude · app", false, 1),
+            entry("recent", "claude · app", false, 5),
+            entry("running", "codex · app", true, 2),
+        ];
+        assert_eq!(titles(rank_entries("", &entries)), vec!["running", "recent", "old"]);
+    }
+
+    #[test]
+    fn test_rank_prefers_title_matches() {
+        let entries = vec![
+            entry("Write docs", "claude · fixtures", false, 5),
+            entry("Fix login", "codex · app", false, 1),
+            entry("Refactor", "claude · app", false, 3),
+        ];
+        assert_eq!(titles(rank_entries("fix", &entries)), vec!["Fix login", "Write docs"]);
+        assert_eq!(titles(rank_entries("codex", &entries)), vec!["Fix login"]);
+    }
+}
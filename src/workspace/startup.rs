@@ -20,6 +20,7 @@ use crate::{
     core::{
         config::{AgentProcessConfig, Config},
         nodejs::NodeJsChecker,
+        services::ServiceError,
     },
     title_bar::OpenSettings,
     utils,
@@ -432,13 +433,11 @@ impl DockWorkspace {
                 Ok(workspace) => {
                     selected_path = Some(workspace.path);
                 }
+                Err(ServiceError::WorkspaceExists(path)) => {
+                    selected_path = Some(path);
+                }
                 Err(err) => {
-                    let message = err.to_string();
-                    if message.contains("Workspace already exists") {
-                        selected_path = Some(folder_path.clone());
-                    } else {
-                        error_message = Some(message);
-                    }
+                    error_message = Some(err.to_string());
                 }
             }
 